@@ -0,0 +1,559 @@
+//! JSON API for local tools
+//!
+//! `rott serve api` serves a small REST API over the store, so browser
+//! extensions, phone shortcuts and scripts can read and change the
+//! collection without reimplementing Automerge:
+//!
+//! ```text
+//! GET    /api/links?q=&tag=               links, newest first (q is a search; operators work)
+//! POST   /api/links                       save a URL: {"url", "tags", "title", "via", "source"}
+//! GET    /api/links/<id>                  one link with its notes (ID or prefix)
+//! PATCH  /api/links/<id>                  change {"title", "url", "description", "tags"}
+//! DELETE /api/links/<id>                  delete a link
+//! POST   /api/links/<id>/notes            add a note: {"title", "body"}
+//! PATCH  /api/links/<id>/notes/<note-id>  change a note's {"title", "body"}
+//! DELETE /api/links/<id>/notes/<note-id>  remove a note
+//! GET    /api/tags                        tags with their link counts
+//! ```
+//!
+//! Every request must send `Authorization: Bearer <api_token>`; the server
+//! doesn't start until `api_token` is set. Bodies and answers are JSON, and
+//! errors are `{"error": "..."}` with a 4xx or 5xx status. Changes go
+//! through the store like the CLI's (validation, checks, the undo history)
+//! and are synced afterwards if sync is enabled.
+//!
+//! Like the viewer (whose HTTP handling this shares), the API answers one
+//! request at a time and picks up changes saved by other rott processes
+//! before each.
+
+use std::collections::{BTreeMap, HashSet};
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use rott_core::{Link, LinkSort, Note, Store};
+
+use crate::commands::link::{new_link, parse_link_id};
+use crate::commands::sync::sync_quiet;
+use crate::output::Output;
+use crate::remote::AddedNote;
+use crate::viewer::{
+    parse_query, percent_decode, read_request, tokens_match, write_response, Request, Response,
+    REQUEST_TIMEOUT,
+};
+
+/// Largest request body read (a long note)
+const MAX_BODY: usize = 1024 * 1024;
+
+/// A link to save, from `POST /api/links`
+#[derive(Debug, Deserialize)]
+struct NewLink {
+    url: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    title: Option<String>,
+    via: Option<String>,
+    source: Option<String>,
+}
+
+/// Changes to a link, from `PATCH /api/links/<id>`
+#[derive(Debug, Default, Deserialize)]
+struct LinkChanges {
+    title: Option<String>,
+    url: Option<String>,
+    /// An empty description removes it
+    description: Option<String>,
+    tags: Option<Vec<String>>,
+}
+
+/// A note's fields, from `POST` or `PATCH` on notes
+#[derive(Debug, Default, Deserialize)]
+struct NoteFields {
+    /// An empty title removes it
+    title: Option<String>,
+    body: Option<String>,
+}
+
+/// Serve the API until Ctrl-C
+pub async fn serve(store: &mut Store, bind: &str, port: u16, output: &Output) -> Result<()> {
+    let Some(token) = store.config().api_token.clone() else {
+        anyhow::bail!(
+            "The API needs a token, which every request must send. \
+             Set one with `rott config set api_token generate`."
+        );
+    };
+    let listener = TcpListener::bind((bind, port))
+        .await
+        .with_context(|| format!("Failed to listen on {}:{}", bind, port))?;
+    let address = listener.local_addr()?;
+    output.message(&format!(
+        "Serving the API on http://{}/api (Ctrl-C to stop)",
+        address
+    ));
+
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    let mut version = store.stored_version();
+
+    loop {
+        let stream = tokio::select! {
+            _ = &mut ctrl_c => return Ok(()),
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    debug!("Failed to accept API connection: {}", e);
+                    continue;
+                }
+            },
+        };
+
+        // Pick up changes saved by other processes
+        if store.stored_version() != version {
+            store.reload_and_merge()?;
+        }
+
+        if let Err(e) = answer(store, stream, &token).await {
+            debug!("API request failed: {:#}", e);
+        }
+        // Including this request's own changes
+        version = store.stored_version();
+    }
+}
+
+/// Read one request from `stream` and answer it
+async fn answer(store: &mut Store, mut stream: TcpStream, token: &str) -> Result<()> {
+    let request = tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream, MAX_BODY))
+        .await
+        .context("Timed out reading request")??;
+    let response = respond(store, request, token).await;
+    write_response(&mut stream, &response).await
+}
+
+/// Answer a request (None if it couldn't be parsed), if it has the token
+async fn respond(store: &mut Store, request: Option<Request>, token: &str) -> Response {
+    match request {
+        Some(request)
+            if !request
+                .bearer_token()
+                .is_some_and(|t| tokens_match(&t, token)) =>
+        {
+            Response::error("401 Unauthorized", "Wrong or missing token")
+        }
+        Some(request) => {
+            let response = route(store, &request).await.unwrap_or_else(|e| {
+                Response::error("500 Internal Server Error", &format!("{:#}", e))
+            });
+            let config = store.config().clone();
+            if request.method != "GET" && response.status.starts_with('2') && config.sync_enabled {
+                if let Err(e) = sync_quiet(store, &config, false).await {
+                    warn!("Sync after API change failed: {:#}", e);
+                }
+            }
+            response
+        }
+        None => Response::error("400 Bad Request", "Malformed request"),
+    }
+}
+
+/// Answer a request
+async fn route(store: &mut Store, request: &Request) -> Result<Response> {
+    let target = request.target.as_str();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let segments: Vec<String> = path
+        .trim_matches('/')
+        .split('/')
+        .map(percent_decode)
+        .collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["api", "links"]) => list_links(store, &parse_query(query)),
+        ("POST", ["api", "links"]) => create_link(store, request).await,
+        ("GET", ["api", "links", id]) => match find_link(store, id)? {
+            Some(link) => Response::json(&link),
+            None => Ok(link_not_found()),
+        },
+        ("PATCH", ["api", "links", id]) => update_link(store, id, request),
+        ("DELETE", ["api", "links", id]) => {
+            let Some(link) = find_link(store, id)? else {
+                return Ok(link_not_found());
+            };
+            store.delete_link(link.id)?;
+            Response::json(&serde_json::json!({ "status": "deleted", "id": link.id }))
+        }
+        ("POST", ["api", "links", id, "notes"]) => add_note(store, id, request),
+        ("PATCH", ["api", "links", id, "notes", note_id]) => {
+            update_note(store, id, note_id, request)
+        }
+        ("DELETE", ["api", "links", id, "notes", note_id]) => {
+            let Some((link, note)) = find_note(store, id, note_id)? else {
+                return Ok(Response::error("404 Not Found", "Note not found"));
+            };
+            store.remove_note_from_link(link.id, note.id)?;
+            Response::json(&serde_json::json!({ "status": "deleted", "id": note.id }))
+        }
+        ("GET", ["api", "tags"]) => Response::json(&store.get_tags_with_counts()?),
+        (_, ["api", ..]) => Ok(Response::error(
+            "405 Method Not Allowed",
+            "Method not allowed here",
+        )),
+        _ => Ok(Response::error("404 Not Found", "Not found")),
+    }
+}
+
+/// `GET /api/links`: all links, or those matching `q` and `tag`
+fn list_links(store: &Store, query: &BTreeMap<String, String>) -> Result<Response> {
+    let search = query.get("q").map(|q| q.trim()).filter(|q| !q.is_empty());
+    let tag = query.get("tag").filter(|t| !t.is_empty());
+    let mut links = match search {
+        Some(search) => store.search_links(search)?,
+        None => store.get_all_links(LinkSort::default())?,
+    };
+    if let Some(tag) = tag {
+        let tagged: HashSet<Uuid> = store
+            .get_links_by_tag(tag, LinkSort::default())?
+            .iter()
+            .map(|link| link.id)
+            .collect();
+        links.retain(|link| tagged.contains(&link.id));
+    }
+    links.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Response::json(&links)
+}
+
+/// `POST /api/links`: save a URL, fetching its metadata
+async fn create_link(store: &mut Store, request: &Request) -> Result<Response> {
+    let new: NewLink = match parse_body(request) {
+        Ok(new) => new,
+        Err(response) => return Ok(response),
+    };
+    if let Some(existing) = store.get_link_by_url(&new.url)? {
+        return Response::json_with_status(
+            "409 Conflict",
+            &serde_json::json!({
+                "error": "A link with this URL already exists",
+                "link": existing,
+            }),
+        );
+    }
+
+    let mut link = match new_link(store, &new.url, new.tags).await {
+        Ok(link) => link,
+        Err(e) => return Ok(bad_request(e)),
+    };
+    if let Some(title) = new.title.filter(|t| !t.trim().is_empty()) {
+        link.set_title(title.trim());
+    }
+    if new.via.is_some() || new.source.is_some() {
+        link.set_source(new.via, new.source);
+    }
+    if let Err(e) = store.add_link(&link) {
+        return Ok(bad_request(e));
+    }
+    let saved = store.get_link(link.id)?.unwrap_or(link);
+    Response::json_with_status("201 Created", &saved)
+}
+
+/// `PATCH /api/links/<id>`
+fn update_link(store: &mut Store, id: &str, request: &Request) -> Result<Response> {
+    let Some(mut link) = find_link(store, id)? else {
+        return Ok(link_not_found());
+    };
+    let changes: LinkChanges = match parse_body(request) {
+        Ok(changes) => changes,
+        Err(response) => return Ok(response),
+    };
+    if let Some(title) = changes.title {
+        link.set_title(title.trim());
+    }
+    if let Some(url) = changes.url {
+        link.url = url.trim().to_string();
+    }
+    if let Some(description) = changes.description {
+        let description = description.trim();
+        link.set_description((!description.is_empty()).then(|| description.to_string()));
+    }
+    if let Some(tags) = changes.tags {
+        link.set_tags(tags);
+    }
+    link.updated_at = chrono::Utc::now();
+    if let Err(e) = store.update_link(&link) {
+        return Ok(bad_request(e));
+    }
+    match store.get_link(link.id)? {
+        Some(link) => Response::json(&link),
+        None => Ok(link_not_found()),
+    }
+}
+
+/// `POST /api/links/<id>/notes`
+fn add_note(store: &mut Store, id: &str, request: &Request) -> Result<Response> {
+    let Some(link) = find_link(store, id)? else {
+        return Ok(link_not_found());
+    };
+    let fields: NoteFields = match parse_body(request) {
+        Ok(fields) => fields,
+        Err(response) => return Ok(response),
+    };
+    let Some(body) = fields.body.filter(|body| !body.trim().is_empty()) else {
+        return Ok(Response::error("400 Bad Request", "Note body is empty"));
+    };
+    let note = match fields.title.filter(|title| !title.trim().is_empty()) {
+        Some(title) => Note::with_title(title, body),
+        None => Note::new(body),
+    };
+    store.add_note_to_link(link.id, &note)?;
+    Response::json_with_status(
+        "201 Created",
+        &AddedNote {
+            link_id: link.id,
+            note_id: note.id,
+        },
+    )
+}
+
+/// `PATCH /api/links/<id>/notes/<note-id>`
+fn update_note(store: &mut Store, id: &str, note_id: &str, request: &Request) -> Result<Response> {
+    let Some((link, mut note)) = find_note(store, id, note_id)? else {
+        return Ok(Response::error("404 Not Found", "Note not found"));
+    };
+    let fields: NoteFields = match parse_body(request) {
+        Ok(fields) => fields,
+        Err(response) => return Ok(response),
+    };
+    if let Some(title) = fields.title {
+        note.set_title(Some(title).filter(|title| !title.trim().is_empty()));
+    }
+    if let Some(body) = fields.body {
+        if body.trim().is_empty() {
+            return Ok(Response::error("400 Bad Request", "Note body is empty"));
+        }
+        note.set_body(body);
+    }
+    store.update_note(link.id, &note)?;
+    Response::json(&note)
+}
+
+/// A link by full ID or prefix (None if there's no such link, or the
+/// prefix matches several)
+fn find_link(store: &Store, id: &str) -> Result<Option<Link>> {
+    match parse_link_id(id, store) {
+        Ok(uuid) => store.get_link(uuid),
+        Err(_) => Ok(None),
+    }
+}
+
+/// A link and one of its notes, by ID
+fn find_note(store: &Store, id: &str, note_id: &str) -> Result<Option<(Link, Note)>> {
+    let Ok(note_id) = Uuid::parse_str(note_id) else {
+        return Ok(None);
+    };
+    Ok(find_link(store, id)?.and_then(|link| {
+        let note = link.get_note(note_id).cloned()?;
+        Some((link, note))
+    }))
+}
+
+/// Parse a JSON request body, or the response saying why it can't be
+fn parse_body<T: DeserializeOwned>(request: &Request) -> Result<T, Response> {
+    serde_json::from_slice(&request.body)
+        .map_err(|e| Response::error("400 Bad Request", &format!("Invalid JSON body: {}", e)))
+}
+
+fn bad_request(e: anyhow::Error) -> Response {
+    Response::error("400 Bad Request", &format!("{:#}", e))
+}
+
+fn link_not_found() -> Response {
+    Response::error("404 Not Found", "Link not found")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, target: &str, body: &str) -> Request {
+        Request {
+            method: method.to_string(),
+            target: target.to_string(),
+            headers: BTreeMap::new(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    const TOKEN: &str = "secret";
+
+    fn authorized(method: &str, target: &str, body: &str) -> Request {
+        let mut request = request(method, target, body);
+        request
+            .headers
+            .insert("authorization".to_string(), format!("Bearer {}", TOKEN));
+        request
+    }
+
+    async fn call(store: &mut Store, request: Request) -> (&'static str, serde_json::Value) {
+        let response = respond(store, Some(request), TOKEN).await;
+        let body = serde_json::from_slice(&response.body).unwrap_or_default();
+        (response.status, body)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_requests_need_the_token() {
+        let mut store = rott_testing::StoreBuilder::new().build().unwrap();
+
+        let (status, body) = call(&mut store, request("GET", "/api/links", "")).await;
+        assert_eq!(status, "401 Unauthorized");
+        assert_eq!(body["error"], "Wrong or missing token");
+
+        let mut wrong = request("GET", "/api/links", "");
+        wrong
+            .headers
+            .insert("authorization".to_string(), "Bearer guess".to_string());
+        let (status, _) = call(&mut store, wrong).await;
+        assert_eq!(status, "401 Unauthorized");
+
+        let (status, _) = call(&mut store, authorized("GET", "/api/links", "")).await;
+        assert_eq!(status, "200 OK");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_link_routes() {
+        let mut fixtures = rott_testing::Fixtures::default();
+        let essay = fixtures.link("https://example.com/essay", "Essay", &["essays"]);
+        let mut store = rott_testing::StoreBuilder::new()
+            .link(essay.clone())
+            .build()
+            .unwrap();
+
+        // Nothing listens on the discard port, so no metadata is fetched
+        let (status, created) = call(
+            &mut store,
+            authorized(
+                "POST",
+                "/api/links",
+                r#"{"url": "http://127.0.0.1:9/saved", "title": "Saved", "tags": ["later"]}"#,
+            ),
+        )
+        .await;
+        assert_eq!(status, "201 Created");
+        assert_eq!(created["title"], "Saved");
+        assert_eq!(created["tags"], serde_json::json!(["later"]));
+        let id = created["id"].as_str().unwrap().to_string();
+
+        let (status, _) = call(
+            &mut store,
+            authorized(
+                "POST",
+                "/api/links",
+                r#"{"url": "http://127.0.0.1:9/saved"}"#,
+            ),
+        )
+        .await;
+        assert_eq!(status, "409 Conflict");
+
+        let (_, links) = call(&mut store, authorized("GET", "/api/links?tag=later", "")).await;
+        assert_eq!(links.as_array().unwrap().len(), 1);
+        let (_, links) = call(&mut store, authorized("GET", "/api/links", "")).await;
+        assert_eq!(links.as_array().unwrap().len(), 2);
+
+        let path = format!("/api/links/{}", &essay.id.to_string()[..8]);
+        let (status, link) = call(&mut store, authorized("GET", &path, "")).await;
+        assert_eq!(status, "200 OK");
+        assert_eq!(link["title"], "Essay");
+
+        let (status, link) = call(
+            &mut store,
+            authorized(
+                "PATCH",
+                &path,
+                r#"{"title": "Long essay", "tags": ["read"]}"#,
+            ),
+        )
+        .await;
+        assert_eq!(status, "200 OK");
+        assert_eq!(link["title"], "Long essay");
+        let stored = store.get_link(essay.id).unwrap().unwrap();
+        assert_eq!(stored.tags, vec!["read"]);
+
+        let (status, _) = call(
+            &mut store,
+            authorized("DELETE", &format!("/api/links/{}", id), ""),
+        )
+        .await;
+        assert_eq!(status, "200 OK");
+        assert!(store.get_link(id.parse().unwrap()).unwrap().is_none());
+
+        let unknown = format!("/api/links/{}", Uuid::new_v4());
+        for method in ["GET", "PATCH", "DELETE"] {
+            let (status, body) = call(&mut store, authorized(method, &unknown, "{}")).await;
+            assert_eq!(status, "404 Not Found", "{}", method);
+            assert_eq!(body["error"], "Link not found");
+        }
+        let (status, _) = call(&mut store, authorized("PUT", &path, "{}")).await;
+        assert_eq!(status, "405 Method Not Allowed");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_note_routes() {
+        let mut fixtures = rott_testing::Fixtures::default();
+        let essay = fixtures.link("https://example.com/essay", "Essay", &[]);
+        let mut store = rott_testing::StoreBuilder::new()
+            .link(essay.clone())
+            .build()
+            .unwrap();
+        let notes = format!("/api/links/{}/notes", essay.id);
+
+        let (status, _) = call(&mut store, authorized("POST", &notes, r#"{"body": " "}"#)).await;
+        assert_eq!(status, "400 Bad Request");
+
+        let (status, added) = call(
+            &mut store,
+            authorized(
+                "POST",
+                &notes,
+                r#"{"title": "Takeaways", "body": "Read twice"}"#,
+            ),
+        )
+        .await;
+        assert_eq!(status, "201 Created");
+        let note_id: Uuid = added["note_id"].as_str().unwrap().parse().unwrap();
+        let link = store.get_link(essay.id).unwrap().unwrap();
+        assert_eq!(link.notes.len(), 1);
+        assert_eq!(link.notes[0].title.as_deref(), Some("Takeaways"));
+
+        let note = format!("{}/{}", notes, note_id);
+        let (status, changed) = call(
+            &mut store,
+            authorized("PATCH", &note, r#"{"body": "Read again"}"#),
+        )
+        .await;
+        assert_eq!(status, "200 OK");
+        assert_eq!(changed["body"], "Read again");
+
+        let (status, _) = call(&mut store, authorized("DELETE", &note, "")).await;
+        assert_eq!(status, "200 OK");
+        assert!(store.get_link(essay.id).unwrap().unwrap().notes.is_empty());
+
+        let (status, body) = call(&mut store, authorized("DELETE", &note, "")).await;
+        assert_eq!(status, "404 Not Found");
+        assert_eq!(body["error"], "Note not found");
+    }
+
+    #[test]
+    fn test_parse_body() {
+        let changes: LinkChanges =
+            parse_body(&request("PATCH", "/api/links/a1", r#"{"tags": ["rust"]}"#)).unwrap();
+        assert_eq!(changes.tags, Some(vec!["rust".to_string()]));
+        assert!(changes.title.is_none());
+
+        let invalid = parse_body::<NoteFields>(&request("POST", "/api/links/a1/notes", "{"));
+        assert_eq!(invalid.unwrap_err().status, "400 Bad Request");
+
+        let missing_url = parse_body::<NewLink>(&request("POST", "/api/links", "{}"));
+        assert!(missing_url.is_err());
+    }
+}
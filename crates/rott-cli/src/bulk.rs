@@ -0,0 +1,369 @@
+//! Bulk editing links as a table
+//!
+//! `rott link edit --bulk` writes the selected links to a tab-separated
+//! table, one row per link, and opens it in $EDITOR:
+//!
+//! ```text
+//! 3f2a9c1e	The Rust Book	https://doc.rust-lang.org/book/	rust, books	unread
+//! ```
+//!
+//! The columns are the link ID (which identifies the row and can't be
+//! changed), title, URL, tags (comma-separated), and status: `read`,
+//! `unread`, or `delete`. Rows removed from the table are left alone.
+//!
+//! Every row is checked before anything is saved; if any row has a problem,
+//! the errors are reported by line number and nothing is changed.
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use rott_core::{Link, Validator};
+use uuid::Uuid;
+
+/// Length of the short IDs used in the table
+const SHORT_ID_LEN: usize = 8;
+
+/// What a row's status column can say
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// Opened at least once
+    Read,
+    /// Never opened
+    Unread,
+    /// Delete the link
+    Delete,
+}
+
+impl Status {
+    /// A link's current status
+    pub fn of(link: &Link) -> Self {
+        if link.opened_at.is_some() {
+            Status::Read
+        } else {
+            Status::Unread
+        }
+    }
+
+    /// Parse a status column
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "read" => Some(Status::Read),
+            "unread" => Some(Status::Unread),
+            "delete" => Some(Status::Delete),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Status::Read => "read",
+            Status::Unread => "unread",
+            Status::Delete => "delete",
+        }
+    }
+}
+
+/// A problem with one row of the edited table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowError {
+    /// 1-based line number in the edited file
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Changes made in the table
+#[derive(Debug, Clone, Default)]
+pub struct BulkChanges {
+    /// Links with an edited title, URL, tags, or read status
+    pub updates: Vec<Link>,
+    /// Links marked `delete`
+    pub deletes: Vec<Uuid>,
+}
+
+impl BulkChanges {
+    /// Whether nothing was changed
+    pub fn is_empty(&self) -> bool {
+        self.updates.is_empty() && self.deletes.is_empty()
+    }
+}
+
+/// Render links as an editable table, with instructions as `#` comments
+pub fn render(links: &[Link]) -> String {
+    let mut table = String::from(
+        "# Edit the title, url, tags (comma-separated), and status (read, unread,\n\
+         # or delete) columns. Columns are separated by tabs. Don't change the\n\
+         # id; rows you remove are left as they are.\n\
+         #\n\
+         # id\ttitle\turl\ttags\tstatus\n",
+    );
+    for link in links {
+        table.push_str(&format!(
+            "{}\t{}\t{}\t{}\t{}\n",
+            row_id(link, links),
+            cell(&link.title),
+            cell(&link.url),
+            link.tags.join(", "),
+            Status::of(link).as_str()
+        ));
+    }
+    table
+}
+
+/// Short ID for a row, or the full ID if the short one isn't unique
+fn row_id(link: &Link, links: &[Link]) -> String {
+    let id = link.id.to_string();
+    let short = &id[..SHORT_ID_LEN];
+    if links
+        .iter()
+        .filter(|l| l.id.to_string().starts_with(short))
+        .count()
+        > 1
+    {
+        id
+    } else {
+        short.to_string()
+    }
+}
+
+/// Keep a value on one line and out of the other columns
+fn cell(value: &str) -> String {
+    value
+        .split(['\t', '\n', '\r'])
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Work out what changed in an edited table
+///
+/// `links` are the links that were rendered; `all_links` is the whole
+/// collection, used to reject URLs that another link already has.
+pub fn parse(
+    edited: &str,
+    links: &[Link],
+    all_links: &[Link],
+    validator: &Validator,
+    now: DateTime<Utc>,
+) -> Result<BulkChanges, Vec<RowError>> {
+    let mut changes = BulkChanges::default();
+    let mut errors = Vec::new();
+    let mut seen: Vec<Uuid> = Vec::new();
+    // URLs each link will have once the changes are applied
+    let mut urls: Vec<(Uuid, String)> = all_links
+        .iter()
+        .map(|l| (l.id, l.canonical_url.clone().unwrap_or_default()))
+        .chain(all_links.iter().map(|l| (l.id, l.url.clone())))
+        .filter(|(_, url)| !url.is_empty())
+        .collect();
+
+    for (index, line) in edited.lines().enumerate() {
+        let line_number = index + 1;
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let mut error = |message: String| {
+            errors.push(RowError {
+                line: line_number,
+                message,
+            })
+        };
+
+        let columns: Vec<&str> = line.split('\t').collect();
+        let [id, title, url, tags, status] = columns[..] else {
+            error(format!(
+                "expected 5 tab-separated columns, found {}",
+                columns.len()
+            ));
+            continue;
+        };
+
+        let id = id.trim().to_lowercase();
+        let matching: Vec<&Link> = links
+            .iter()
+            .filter(|l| !id.is_empty() && l.id.to_string().starts_with(&id))
+            .collect();
+        let original = match matching[..] {
+            [link] => link,
+            [] => {
+                error(format!("no link being edited has ID '{}'", id));
+                continue;
+            }
+            _ => {
+                error(format!("ID '{}' matches more than one link", id));
+                continue;
+            }
+        };
+        if seen.contains(&original.id) {
+            error(format!("link {} appears more than once", id));
+            continue;
+        }
+        seen.push(original.id);
+
+        let Some(status) = Status::parse(status) else {
+            error(format!(
+                "status must be read, unread, or delete, not '{}'",
+                status.trim()
+            ));
+            continue;
+        };
+        if status == Status::Delete {
+            changes.deletes.push(original.id);
+            continue;
+        }
+
+        let mut link = original.clone();
+        // Compare against the values as rendered, so a title that had a tab
+        // in it isn't counted as edited
+        let url = url.trim();
+        if url != cell(&link.url).trim() {
+            link.url = url.to_string();
+            // The canonical URL belonged to the old address
+            link.canonical_url = None;
+            link.updated_at = now;
+        }
+        let title = title.trim();
+        if title != cell(&link.title).trim() {
+            link.set_title(if title.is_empty() { url } else { title });
+        }
+        let tags: Vec<String> = tags
+            .split(',')
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+        if tags != link.tags {
+            link.set_tags(tags);
+        }
+        match status {
+            Status::Read if link.opened_at.is_none() => link.opened_at = Some(now),
+            Status::Unread => link.opened_at = None,
+            _ => {}
+        }
+
+        if let Err(e) = validator.validate(&mut link, Some(original)) {
+            error(e.to_string());
+            continue;
+        }
+        if link.url != original.url {
+            if let Some((other, _)) = urls
+                .iter()
+                .find(|(other, existing)| *other != link.id && *existing == link.url)
+            {
+                error(format!(
+                    "another link ({}) already has this URL",
+                    &other.to_string()[..SHORT_ID_LEN]
+                ));
+                continue;
+            }
+            urls.retain(|(other, _)| *other != link.id);
+            urls.push((link.id, link.url.clone()));
+        }
+
+        if link != *original {
+            changes.updates.push(link);
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(changes)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Add the errors to the table as comments, so it can be fixed and saved
+/// again
+pub fn annotate(edited: &str, errors: &[RowError]) -> String {
+    let mut annotated = String::new();
+    for (index, line) in edited.lines().enumerate() {
+        if line.starts_with("# ERROR") {
+            continue;
+        }
+        for error in errors.iter().filter(|e| e.line == index + 1) {
+            annotated.push_str(&format!("# ERROR: {}\n", error.message));
+        }
+        annotated.push_str(line);
+        annotated.push('\n');
+    }
+    annotated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rott_core::Config;
+
+    fn links() -> Vec<Link> {
+        let mut a = Link::new("https://a.example.com");
+        a.set_title("First\tone");
+        a.add_tag("rust");
+        let b = Link::new("https://b.example.com");
+        vec![a, b]
+    }
+
+    fn validator() -> Validator {
+        Validator::from_config(&Config::default())
+    }
+
+    #[test]
+    fn test_unchanged_table_has_no_changes() {
+        let links = links();
+        let table = render(&links);
+        assert!(table.contains("First one\thttps://a.example.com\trust\tunread"));
+
+        let changes = parse(&table, &links, &links, &validator(), Utc::now()).unwrap();
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn test_edits_are_applied_per_row() {
+        let links = links();
+        let now = Utc::now();
+        let edited = render(&links)
+            .replace("First one\t", "Renamed\t")
+            .replace("\trust\tunread", "\tRust Lang, web\tread")
+            .replace(
+                "https://b.example.com\t\tunread",
+                "https://b.example.com\t\tdelete",
+            );
+
+        let changes = parse(&edited, &links, &links, &validator(), now).unwrap();
+        assert_eq!(changes.deletes, vec![links[1].id]);
+        assert_eq!(changes.updates.len(), 1);
+        let updated = &changes.updates[0];
+        assert_eq!(updated.title, "Renamed");
+        assert_eq!(updated.tags, vec!["rust-lang", "web"]);
+        assert_eq!(updated.opened_at, Some(now));
+    }
+
+    #[test]
+    fn test_row_errors_are_reported_by_line() {
+        let links = links();
+        let table = render(&links);
+        let edited = table
+            .replace("https://a.example.com\trust", "https://b.example.com\trust")
+            .replace("\t\tunread", "\t\tsomeday")
+            + "deadbeef\tMissing\thttps://c.example.com\t\tread\nnot a row\n";
+
+        let errors = parse(&edited, &links, &links, &validator(), Utc::now()).unwrap_err();
+        let first_row = table.lines().count() - 1;
+        assert_eq!(
+            errors.iter().map(|e| e.line).collect::<Vec<_>>(),
+            vec![first_row, first_row + 1, first_row + 2, first_row + 3]
+        );
+        assert!(errors[0].message.contains("already has this URL"));
+        assert!(errors[1].message.contains("someday"));
+
+        let annotated = annotate(&edited, &errors);
+        assert_eq!(annotated.matches("# ERROR:").count(), 4);
+        // Annotating again replaces the old errors
+        let errors = parse(&annotated, &links, &links, &validator(), Utc::now()).unwrap_err();
+        assert_eq!(errors.len(), 4);
+        assert_eq!(annotate(&annotated, &errors).matches("# ERROR:").count(), 4);
+    }
+}
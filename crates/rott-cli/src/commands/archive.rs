@@ -0,0 +1,164 @@
+//! Archive command handlers
+//!
+//! Old links can be moved out of the root document into one archive
+//! document per year, so the document every device loads and syncs stays
+//! small. Archived links keep their notes and are still found with
+//! `rott link list --archived` and `rott link search --archived`.
+
+use anyhow::{bail, Result};
+use chrono::{Datelike, Months, Utc};
+use uuid::Uuid;
+
+use rott_core::{Link, LinkSort, Store};
+
+use crate::editor::confirm;
+use crate::i18n::{t, tf};
+use crate::output::{Output, OutputFormat};
+use crate::preview::{Change, ChangeKind, Preview};
+
+/// Move links saved more than `older_than` years ago (and/or with a tag)
+/// into the archives
+pub fn archive(
+    store: &mut Store,
+    older_than: Option<u32>,
+    tag: Option<String>,
+    yes: bool,
+    dry_run: bool,
+    output: &Output,
+) -> Result<()> {
+    if !dry_run && !yes && !output.should_prompt() {
+        bail!(t(
+            "Archiving without prompting needs --yes (or use --dry-run to list the links)"
+        ));
+    }
+
+    let cutoff = match older_than {
+        Some(years) => Some(
+            Utc::now()
+                .checked_sub_months(Months::new(years.saturating_mul(12)))
+                .unwrap_or_default(),
+        ),
+        None => None,
+    };
+    let links: Vec<Link> = store
+        .get_all_links(LinkSort::default())?
+        .into_iter()
+        .filter(|l| cutoff.is_none_or(|cutoff| l.created_at < cutoff))
+        .filter(|l| tag.as_deref().is_none_or(|t| l.has_tag(t)))
+        .collect();
+
+    if links.is_empty() {
+        output.success(t("No links to archive"));
+        return Ok(());
+    }
+
+    if dry_run {
+        let mut preview = Preview::new("Archive links").count("links", links.len());
+        for link in &links {
+            preview.push(
+                Change::new(
+                    ChangeKind::Delete,
+                    tf(
+                        "{} (to {} archive)",
+                        &[&link.title, &link.created_at.year()],
+                    ),
+                )
+                .link(link.id),
+            );
+        }
+        return preview.print(output);
+    }
+
+    if !yes && !confirm(&tf("Archive {} link(s)?", &[&links.len()]))? {
+        output.message(t("Cancelled"));
+        return Ok(());
+    }
+
+    let ids: Vec<Uuid> = links.iter().map(|l| l.id).collect();
+    let moved = store.archive_links(&ids)?;
+    output.success(&tf("Archived {} link(s)", &[&moved]));
+    Ok(())
+}
+
+/// List the archives with their link counts
+pub fn list(store: &Store, output: &Output) -> Result<()> {
+    let mut archives = Vec::new();
+    for (year, id) in store.archives()? {
+        let links = store
+            .load_archive(&id)?
+            .map(|archive| archive.link_count())
+            .transpose()?;
+        archives.push((year, id, links));
+    }
+
+    match output.format {
+        OutputFormat::Json => {
+            let json: Vec<_> = archives
+                .iter()
+                .map(|(year, id, links)| {
+                    serde_json::json!({
+                        "year": year,
+                        "document_id": id.to_bs58check(),
+                        "links": links,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        OutputFormat::Quiet => {
+            for (year, _, _) in &archives {
+                println!("{}", year);
+            }
+        }
+        OutputFormat::Human => {
+            if archives.is_empty() {
+                println!("{}", t("No archives"));
+                return Ok(());
+            }
+            for (year, _, links) in &archives {
+                match links {
+                    Some(count) => println!("{}  {}", year, tf("{} link(s)", &[count])),
+                    None => println!(
+                        "{}  {}",
+                        year,
+                        t("not on this device yet (run `rott sync`)")
+                    ),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Move an archived link back into the root document
+pub fn restore(store: &mut Store, id: String, output: &Output) -> Result<()> {
+    let uuid = parse_archived_id(&id, store)?;
+    let link = store.unarchive_link(uuid)?;
+    output.success(&tf("Restored '{}' from the archive", &[&link.title]));
+    Ok(())
+}
+
+/// Resolve a full ID or prefix among archived links
+fn parse_archived_id(id: &str, store: &Store) -> Result<Uuid> {
+    if let Ok(uuid) = Uuid::parse_str(id) {
+        return Ok(uuid);
+    }
+
+    let links = store.archived_links()?;
+    let matches: Vec<_> = links
+        .iter()
+        .filter(|l| l.id.to_string().starts_with(id))
+        .collect();
+
+    match matches.len() {
+        0 => bail!(tf("No archived link found matching: {}", &[&id])),
+        1 => Ok(matches[0].id),
+        _ => {
+            eprintln!("{}", tf("Multiple archived links match '{}':", &[&id]));
+            for link in &matches {
+                eprintln!("  {} - {}", link.id, link.title);
+            }
+            bail!(t("Ambiguous ID. Please provide more characters."));
+        }
+    }
+}
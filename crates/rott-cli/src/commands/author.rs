@@ -0,0 +1,14 @@
+//! Author command handlers
+
+use anyhow::Result;
+
+use rott_core::Store;
+
+use crate::output::Output;
+
+/// List all authors with usage counts
+pub fn list(store: &Store, output: &Output) -> Result<()> {
+    let authors = store.get_authors_with_counts()?;
+    output.print_authors(&authors);
+    Ok(())
+}
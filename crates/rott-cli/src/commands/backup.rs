@@ -0,0 +1,269 @@
+//! Backup command handlers
+//!
+//! `rott backup export` writes a single `.rottpack` archive (see
+//! `rott_core::backup`); `rott backup import` restores it, typically on a
+//! new machine. `rott restore` merges selected links back from a backup
+//! into the live data instead of replacing it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+use rott_core::backup::load_document;
+use rott_core::{AutomergePersistence, Backup, Config, Link, Store};
+
+use crate::editor::confirm;
+use crate::i18n::{t, tf};
+use crate::output::{Output, OutputFormat};
+
+/// Write a full backup archive
+pub fn export(store: &Store, file: PathBuf, output: &Output) -> Result<()> {
+    let (backup, bytes) = write_backup(store, &file)?;
+
+    let links = backup.link_count()?;
+    let archived = backup.archived_link_count()?;
+    let missing = store.missing_archives()?;
+    match output.format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::json!({
+                    "file": file,
+                    "root_id": backup.root_id.to_bs58check(),
+                    "links": links,
+                    "archived_links": archived,
+                    "attachments": backup.attachments.len(),
+                    "missing_archives": missing,
+                    "bytes": bytes,
+                })
+            );
+        }
+        OutputFormat::Quiet => {}
+        OutputFormat::Human => {
+            output.success(&tf(
+                "Backed up {} link(s), {} archived link(s) and {} attachment(s) to {} ({} bytes)",
+                &[
+                    &links,
+                    &archived,
+                    &backup.attachments.len(),
+                    &file.display(),
+                    &bytes,
+                ],
+            ));
+            for year in missing {
+                output.message(&tf(
+                    "Warning: the {} archive isn't on this device yet, so it isn't in the \
+                     backup; run `rott sync` and back up again",
+                    &[&year],
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write a backup archive to `file` and verify it
+///
+/// Returns the backup and the archive's size in bytes.
+pub fn write_backup(store: &Store, file: &Path) -> Result<(Backup, usize)> {
+    let backup = store.backup()?;
+    let bytes = backup.to_bytes()?;
+
+    fs::write(file, &bytes).with_context(|| tf("Failed to write {}", &[&file.display()]))?;
+
+    // Read it back so a bad write is caught now rather than at restore time
+    let written = fs::read(file).with_context(|| tf("Failed to read {}", &[&file.display()]))?;
+    Backup::from_bytes(&written).context(t("Backup verification failed"))?;
+
+    Ok((backup, bytes.len()))
+}
+
+/// Restore a backup archive
+///
+/// Refuses to replace existing data unless `force` is set (and confirmed
+/// when interactive).
+pub fn import(
+    file: PathBuf,
+    force: bool,
+    no_config: bool,
+    config_path: Option<&PathBuf>,
+    output: &Output,
+) -> Result<()> {
+    let bytes = fs::read(&file).with_context(|| tf("Failed to read {}", &[&file.display()]))?;
+    let backup = Backup::from_bytes(&bytes)?;
+    let links = backup.link_count()?;
+
+    let config =
+        Config::load_with_cli_override(config_path).context(t("Failed to load configuration"))?;
+    let persistence = AutomergePersistence::new(config.clone());
+
+    if let Some(existing) = persistence.load_root_doc_id()? {
+        if !force {
+            bail!(tf(
+                "This machine already has data (root document ID {}).\n\
+                 Use --force to replace it with the backup.",
+                &[&existing],
+            ));
+        }
+        if output.should_prompt() {
+            println!(
+                "{}",
+                tf(
+                    "Replace the data in {} with the backup from {} ({} link(s))?",
+                    &[
+                        &config.data_dir.display(),
+                        &backup.created_at.format("%Y-%m-%d %H:%M"),
+                        &links,
+                    ],
+                )
+            );
+            if !confirm(t("Are you sure?"))? {
+                println!("{}", t("Cancelled."));
+                return Ok(());
+            }
+        }
+    }
+
+    let restore_config_path = if no_config {
+        None
+    } else {
+        Some(
+            config_path
+                .cloned()
+                .unwrap_or_else(Config::config_file_path),
+        )
+    };
+    backup.restore(&config, restore_config_path.as_ref())?;
+
+    match output.format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::json!({
+                    "root_id": backup.root_id.to_bs58check(),
+                    "links": links,
+                    "created_at": backup.created_at,
+                    "config_restored": !no_config,
+                })
+            );
+        }
+        OutputFormat::Quiet => {}
+        OutputFormat::Human => {
+            output.success(&tf(
+                "Restored {} link(s) and {} archived link(s) from backup made {}",
+                &[
+                    &links,
+                    &backup.archived_link_count()?,
+                    &backup.created_at.format("%Y-%m-%d %H:%M"),
+                ],
+            ));
+            output.message(&tf("Root document ID: {}", &[&backup.root_id]));
+        }
+    }
+    Ok(())
+}
+
+/// Restore selected links (and their notes) from a backup
+///
+/// Links are picked by ID (or prefix) and/or tag from the backup's copy of
+/// the document, then merged into the live store as new changes.
+pub fn restore(
+    store: &mut Store,
+    from: PathBuf,
+    ids: Vec<String>,
+    tags: Vec<String>,
+    output: &Output,
+) -> Result<()> {
+    if ids.is_empty() && tags.is_empty() {
+        bail!(t(
+            "Choose what to restore with --link <id> and/or --tag <tag>"
+        ));
+    }
+
+    let bytes = fs::read(&from).with_context(|| tf("Failed to read {}", &[&from.display()]))?;
+    let source = load_document(&bytes)?.get_all_links()?;
+
+    let mut selected: Vec<Link> = Vec::new();
+    for id in &ids {
+        let link = find_link(&source, id)?;
+        if !selected.iter().any(|l| l.id == link.id) {
+            selected.push(link.clone());
+        }
+    }
+    for tag in &tags {
+        let tagged: Vec<&Link> = source.iter().filter(|l| l.has_tag(tag)).collect();
+        if tagged.is_empty() {
+            bail!(tf("No links tagged '{}' in {}", &[tag, &from.display()]));
+        }
+        for link in tagged {
+            if !selected.iter().any(|l| l.id == link.id) {
+                selected.push(link.clone());
+            }
+        }
+    }
+
+    let summary = store.restore_links(&selected)?;
+
+    match output.format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::json!({
+                    "selected": selected.len(),
+                    "links_restored": summary.links_restored,
+                    "notes_restored": summary.notes_restored,
+                    "unchanged": summary.unchanged,
+                    "skipped_duplicates": summary.skipped_duplicates,
+                    "rejected": summary.rejected.iter().map(|(link, reason)| {
+                        serde_json::json!({
+                            "id": link.id,
+                            "url": link.url,
+                            "reason": reason,
+                        })
+                    }).collect::<Vec<_>>(),
+                })
+            );
+        }
+        OutputFormat::Quiet => {}
+        OutputFormat::Human => {
+            output.success(&tf(
+                "Restored {} link(s) and {} note(s)",
+                &[&summary.links_restored, &summary.notes_restored],
+            ));
+            if summary.unchanged > 0 {
+                output.message(&tf("{} link(s) already up to date", &[&summary.unchanged]));
+            }
+            if summary.skipped_duplicates > 0 {
+                output.message(&tf(
+                    "{} link(s) skipped: URL saved again since the backup",
+                    &[&summary.skipped_duplicates],
+                ));
+            }
+            for (link, reason) in &summary.rejected {
+                output.message(&tf("Not restored: {} ({})", &[&link.url, reason]));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Find a link in the backup by full ID or prefix
+fn find_link<'a>(links: &'a [Link], id: &str) -> Result<&'a Link> {
+    let matches: Vec<&Link> = links
+        .iter()
+        .filter(|l| l.id.to_string().starts_with(id))
+        .collect();
+
+    match matches.len() {
+        0 => bail!(tf("No link in the backup matches: {}", &[&id])),
+        1 => Ok(matches[0]),
+        _ => {
+            eprintln!("{}", tf("Multiple links match '{}':", &[&id]));
+            for link in &matches {
+                eprintln!("  {} - {}", link.id, link.title);
+            }
+            bail!(t("Ambiguous ID. Please provide more characters."));
+        }
+    }
+}
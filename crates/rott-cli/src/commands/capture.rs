@@ -0,0 +1,282 @@
+//! Capture command handler
+//!
+//! A capture alias (`[captures.<name>]` in the config) saves a link with
+//! preset tags and a note rendered from a template, so `rott capture hn
+//! <url>` (or just `rott hn <url>`) files a link the same way every time.
+//!
+//! `rott capture --from-selection` harvests links from text selected in the
+//! terminal instead: the tmux paste buffer inside tmux, or else the primary
+//! selection. The URLs found are listed and the chosen ones saved with the
+//! same tags.
+
+use std::io::{self, Write};
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use chrono::Local;
+
+use rott_core::desktop::SystemClipboard;
+use rott_core::template::{link_vars, render, required_names};
+use rott_core::urls::url_strs;
+use rott_core::{Note, Store};
+
+use crate::commands::link::new_link;
+use crate::i18n::{t, tf};
+use crate::output::{Output, OutputFormat};
+
+/// Values that every capture note can use without being given them
+const BUILTIN_VARS: &[&str] = &["url", "title", "domain", "date", "time", "alias"];
+
+/// Save a link using a capture alias
+///
+/// `assignments` are `key=value` pairs that fill the note template's
+/// placeholders, overriding the alias's default `vars`.
+pub async fn capture(
+    store: &mut Store,
+    name: String,
+    url: String,
+    assignments: Vec<String>,
+    output: &Output,
+) -> Result<()> {
+    let Some(alias) = store.config().captures.get(&name).cloned() else {
+        bail!(tf(
+            "No capture alias named '{}'. Define one under [captures.{}] in the config file.",
+            &[&name, &name],
+        ));
+    };
+
+    let mut vars = alias.vars.clone();
+    for assignment in assignments {
+        let Some((key, value)) = assignment.split_once('=') else {
+            bail!(tf(
+                "Invalid template value '{}'. Use key=value (e.g. source=\"a friend\").",
+                &[&assignment],
+            ));
+        };
+        vars.insert(key.trim().to_string(), value.to_string());
+    }
+
+    // Check the template before fetching anything
+    if let Some(ref template) = alias.note {
+        let names = required_names(template)
+            .with_context(|| tf("Invalid note in capture '{}'", &[&name]))?;
+        if let Some(missing) = names
+            .iter()
+            .find(|n| !BUILTIN_VARS.contains(&n.as_str()) && !vars.contains_key(*n))
+        {
+            bail!(tf(
+                "Capture '{}' needs a value for '{}' (pass it as {}=<value>)",
+                &[&name, missing, missing],
+            ));
+        }
+    }
+
+    let mut link = new_link(store, &url, alias.tags.clone()).await?;
+
+    if let Some(ref template) = alias.note {
+        let mut values = vars;
+        values.extend(link_vars(&link.url, &link.title, Local::now()));
+        values.insert("alias".to_string(), name.clone());
+        let body = render(template, &values)
+            .with_context(|| tf("Invalid note in capture '{}'", &[&name]))?;
+        if !body.trim().is_empty() {
+            link.add_note(Note::new(body.trim()));
+        }
+    }
+
+    store.add_link(&link).context(t("Failed to create link"))?;
+
+    output.success(&tf("Captured link with '{}': {}", &[&name, &link.id]));
+    if !output.is_quiet() {
+        output.print_link(&link);
+    }
+
+    Ok(())
+}
+
+/// Save the links found in the terminal selection
+///
+/// URLs already saved are left out. When prompting, the user picks which
+/// of the rest to save; otherwise all of them are saved.
+pub async fn from_selection(store: &mut Store, tags: Vec<String>, output: &Output) -> Result<()> {
+    let (source, text) = read_selection()?;
+    let mut urls = Vec::new();
+    let mut saved = 0;
+    for url in unique_urls(&text) {
+        if store.get_link_by_url(&url)?.is_some() {
+            saved += 1;
+        } else {
+            urls.push(url);
+        }
+    }
+    if urls.is_empty() {
+        if saved > 0 {
+            output.message(&tf(
+                "Every URL in the {} is saved already ({})",
+                &[&source, &saved],
+            ));
+            return Ok(());
+        }
+        bail!(tf("No URLs in the {}", &[&source]));
+    }
+
+    let chosen = if output.should_prompt() && atty::is(atty::Stream::Stdin) {
+        println!("{}", tf("URLs in the {}:", &[&source]));
+        for (i, url) in urls.iter().enumerate() {
+            println!("  {:>3}. {}", i + 1, url);
+        }
+        if saved > 0 {
+            println!("  {}", tf("({} already saved)", &[&saved]));
+        }
+        print!(
+            "{} ",
+            t("Save which? (e.g. 1,3-5; Enter for all, 'none' to cancel)")
+        );
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        parse_selection(&input, urls.len())?
+    } else {
+        (0..urls.len()).collect()
+    };
+    if chosen.is_empty() {
+        output.message(t("Nothing saved."));
+        return Ok(());
+    }
+
+    let mut added = Vec::new();
+    let mut failed = 0;
+    for index in chosen {
+        let url = &urls[index];
+        let result = match new_link(store, url, tags.clone()).await {
+            Ok(link) => store.add_link(&link).map(|()| link),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(link) => added.push(link),
+            Err(e) => {
+                failed += 1;
+                output.message(&tf("Failed to save {}: {}", &[url, &format!("{:#}", e)]));
+            }
+        }
+    }
+
+    match output.format {
+        OutputFormat::Json => {
+            let ids: Vec<_> = added.iter().map(|link| link.id.to_string()).collect();
+            println!("{}", serde_json::json!({ "added": ids, "failed": failed }));
+        }
+        OutputFormat::Quiet => {
+            for link in &added {
+                println!("{}", link.id);
+            }
+        }
+        OutputFormat::Human => {
+            for link in &added {
+                println!("{} | {}", &link.id.to_string()[..8], link.title);
+            }
+            output.success(&tf(
+                "Saved {} link(s) from the {}",
+                &[&added.len(), &source],
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Read the selected text: the tmux paste buffer inside tmux, or else the
+/// primary selection
+///
+/// Returns where the text came from, for messages.
+fn read_selection() -> Result<(&'static str, String)> {
+    if std::env::var_os("TMUX").is_some() {
+        let result = Command::new("tmux")
+            .arg("show-buffer")
+            .output()
+            .context(t("Failed to run tmux"))?;
+        if result.status.success() {
+            return Ok((
+                t("tmux buffer"),
+                String::from_utf8_lossy(&result.stdout).into_owned(),
+            ));
+        }
+        // No buffer yet: fall back to the selection
+    }
+    let text = SystemClipboard::new()?.paste_selection()?;
+    Ok((t("selection"), text))
+}
+
+/// The URLs in `text`, each once, in the order they first appear
+fn unique_urls(text: &str) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    for url in url_strs(text) {
+        if !urls.iter().any(|u| u == url) {
+            urls.push(url.to_string());
+        }
+    }
+    urls
+}
+
+/// Parse a choice of items like `1,3-5`, returning 0-based indices
+///
+/// Empty input (or `all`) picks every item; `none` picks nothing.
+fn parse_selection(input: &str, count: usize) -> Result<Vec<usize>> {
+    let input = input.trim().to_lowercase();
+    match input.as_str() {
+        "" | "all" | "a" => return Ok((0..count).collect()),
+        "none" | "n" | "q" => return Ok(Vec::new()),
+        _ => {}
+    }
+
+    let mut chosen = Vec::new();
+    for part in input.split([',', ' ']).filter(|p| !p.is_empty()) {
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (start.trim(), end.trim()),
+            None => (part, part),
+        };
+        let parse = |n: &str| -> Result<usize> {
+            match n.parse::<usize>() {
+                Ok(n) if (1..=count).contains(&n) => Ok(n - 1),
+                _ => bail!(tf("'{}' isn't a number from 1 to {}", &[&n, &count])),
+            }
+        };
+        let (start, end) = (parse(start)?, parse(end)?);
+        if start > end {
+            bail!(tf("Invalid range '{}'", &[&part]));
+        }
+        for index in start..=end {
+            if !chosen.contains(&index) {
+                chosen.push(index);
+            }
+        }
+    }
+    Ok(chosen)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique_urls() {
+        let log = "<alice> see https://example.com/a, and https://example.com/b\n\
+                   <bob> https://example.com/a again";
+        assert_eq!(
+            unique_urls(log),
+            ["https://example.com/a", "https://example.com/b"]
+        );
+        assert!(unique_urls("no links here").is_empty());
+    }
+
+    #[test]
+    fn test_parse_selection() {
+        assert_eq!(parse_selection("\n", 3).unwrap(), [0, 1, 2]);
+        assert_eq!(parse_selection("none", 3).unwrap(), Vec::<usize>::new());
+        assert_eq!(parse_selection("1,3", 3).unwrap(), [0, 2]);
+        assert_eq!(parse_selection("2-4 1", 5).unwrap(), [1, 2, 3, 0]);
+        assert_eq!(parse_selection("1,1-2", 3).unwrap(), [0, 1]);
+        assert!(parse_selection("4", 3).is_err());
+        assert!(parse_selection("3-1", 3).is_err());
+        assert!(parse_selection("x", 3).is_err());
+    }
+}
@@ -0,0 +1,127 @@
+//! Collection command handlers
+//!
+//! Each collection is its own document with its own ID and sync, so work
+//! and personal links can be kept apart. Commands act on the active
+//! collection, which `rott collection switch` changes (or `ROTT_COLLECTION`
+//! overrides for one command).
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+
+use rott_core::collections::{self, DEFAULT_COLLECTION};
+use rott_core::{Config, DocumentId};
+
+use crate::i18n::{t, tf};
+use crate::output::{Output, OutputFormat};
+
+/// List the collections on this device
+pub fn list(config_path: Option<&PathBuf>, output: &Output) -> Result<()> {
+    let config = load_config(config_path)?;
+    let collections = collections::list(&config)?;
+
+    match output.format {
+        OutputFormat::Json => {
+            let json: Vec<_> = collections
+                .iter()
+                .map(|c| {
+                    serde_json::json!({
+                        "name": c.name,
+                        "root_id": c.root_id.map(|id| id.to_bs58check()),
+                        "pending_sync": c.pending_sync,
+                        "active": c.active,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        OutputFormat::Quiet => {
+            for collection in &collections {
+                println!("{}", collection.name);
+            }
+        }
+        OutputFormat::Human => {
+            let width = collections.iter().map(|c| c.name.len()).max().unwrap_or(0);
+            for c in &collections {
+                let marker = if c.active { "*" } else { " " };
+                let state = match c.root_id {
+                    Some(id) if c.pending_sync => tf("{}  (not synced yet)", &[&id]),
+                    Some(id) => id.to_string(),
+                    None => t("not set up (run `rott init`)").to_string(),
+                };
+                println!("{} {:<width$}  {}", marker, c.name, state, width = width);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Create a collection, or join one from another device by its ID
+pub fn create(
+    name: String,
+    join: Option<String>,
+    config_path: Option<&PathBuf>,
+    output: &Output,
+) -> Result<()> {
+    let config = load_config(config_path)?;
+    let join = join
+        .map(|id| {
+            DocumentId::from_bs58check(&id)
+                .map_err(|e| anyhow::anyhow!(tf("Invalid document ID: {}", &[&e])))
+        })
+        .transpose()?;
+
+    let result = collections::create(&config, &name, join)?;
+
+    if output.is_json() {
+        println!(
+            "{}",
+            serde_json::json!({
+                "name": name,
+                "root_id": result.root_id.to_bs58check(),
+                "is_new": result.is_new,
+            })
+        );
+    } else if result.is_new {
+        output.success(&tf(
+            "Created collection '{}' ({})",
+            &[&name, &result.root_id],
+        ));
+        output.message(&tf(
+            "Switch to it with: rott collection switch {}",
+            &[&name],
+        ));
+    } else {
+        output.success(&tf(
+            "Joined collection '{}'; switch to it and run `rott sync` to pull it",
+            &[&name],
+        ));
+    }
+    Ok(())
+}
+
+/// Make a collection the active one
+pub fn switch(name: String, config_path: Option<&PathBuf>, output: &Output) -> Result<()> {
+    let config = load_config(config_path)?;
+    if name != DEFAULT_COLLECTION && !collections::exists(&config, &name) {
+        bail!(tf(
+            "No collection '{}'. Create it with: rott collection create {}",
+            &[&name, &name],
+        ));
+    }
+
+    let config = config.with_collection(&name);
+    let save_path = config_path
+        .cloned()
+        .unwrap_or_else(Config::config_file_path);
+    config
+        .save_to_path(&save_path)
+        .context(t("Failed to save configuration"))?;
+
+    output.success(&tf("Switched to collection '{}'", &[&name]));
+    Ok(())
+}
+
+fn load_config(config_path: Option<&PathBuf>) -> Result<Config> {
+    Config::load_with_cli_override(config_path).context(t("Failed to load configuration"))
+}
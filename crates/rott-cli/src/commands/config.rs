@@ -1,17 +1,25 @@
 //! Config command handlers
 
+use std::collections::BTreeMap;
+use std::fs;
 use std::path::PathBuf;
 
 use anyhow::{bail, Context, Result};
 
-use rott_core::Config;
+use rott_core::canonical::TrailingSlash;
+use rott_core::config::FavoriteMatch;
+use rott_core::maintenance::Interval;
+use rott_core::search::SearchBackend;
+use rott_core::{Config, LinkSort};
+use uuid::Uuid;
 
+use crate::i18n::{t, tf, Locale};
 use crate::output::{Output, OutputFormat};
 
 /// Show current configuration
 pub fn show(config_path: Option<&PathBuf>, output: &Output) -> Result<()> {
     let config =
-        Config::load_with_cli_override(config_path).context("Failed to load configuration")?;
+        Config::load_with_cli_override(config_path).context(t("Failed to load configuration"))?;
 
     match output.format {
         OutputFormat::Json => {
@@ -19,10 +27,51 @@ pub fn show(config_path: Option<&PathBuf>, output: &Output) -> Result<()> {
                 "{}",
                 serde_json::json!({
                     "data_dir": config.data_dir,
+                    "collection": config.collection_name(),
                     "sync_url": config.sync_url,
                     "sync_enabled": config.sync_enabled,
-                    "favorite_tag": config.favorite_tag,
-                    "log_file": config.log_file
+                    "trace_sync": config.trace_sync,
+                    "sync_compression": config.sync_compression,
+                    "sync_batch_ms": config.sync_batch_ms,
+                    "device_name": config.device_name,
+                    "favorite_tags": config.favorite_tags,
+                    "favorite_match": config.favorite_match,
+                    "log_file": config.log_file,
+                    "accessible_mode": config.accessible_mode,
+                    "announce_path": config.announce_path,
+                    "blocked_domains": config.blocked_domains,
+                    "domain_tags": config.domain_tags,
+                    "openers": config.openers,
+                    "allowed_schemes": config.allowed_schemes,
+                    "url_trailing_slash": config.url_trailing_slash.name(),
+                    "lock_enabled": config.is_lock_enabled(),
+                    "lock_timeout_minutes": config.lock_timeout_minutes,
+                    "max_title_length": config.max_title_length,
+                    "max_description_length": config.max_description_length,
+                    "metadata_cache_hours": config.metadata_cache_hours,
+                    "backup_interval": config.backup_interval,
+                    "backup_dir": config.backup_dir(),
+                    "backup_keep": config.backup_keep,
+                    "link_check_interval": config.link_check_interval,
+                    "archive_expired_interval": config.archive_expired_interval,
+                    "report_interval": config.report_interval,
+                    "report_dir": config.report_dir(),
+                    "record_opens": config.record_opens,
+                    "fuzzy_filter": config.fuzzy_filter,
+                    "default_sort": config.default_sort.name(),
+                    "search_provider": config.search_provider.name(),
+                    "triage_tags": config.triage_tags,
+                    "locale": config.locale,
+                    "hypothesis_enabled": config.hypothesis_token.is_some(),
+                    "quick_add_enabled": config.add_token.is_some(),
+                    "api_enabled": config.api_token.is_some(),
+                    "note_template": config.note_template,
+                    "note_section_threshold": config.note_section_threshold,
+                    "screenshot_command": config.screenshot_command,
+                    "screenshot_on_save": config.screenshot_on_save,
+                    "archive_content_on_save": config.archive_content_on_save,
+                    "captures": config.captures,
+                    "checks": config.checks
                 })
             );
         }
@@ -33,16 +82,31 @@ pub fn show(config_path: Option<&PathBuf>, output: &Output) -> Result<()> {
             let effective_path = config_path
                 .cloned()
                 .unwrap_or_else(Config::config_file_path);
-            println!("Configuration:");
+            println!("{}", t("Configuration:"));
             println!("  data_dir:     {}", config.data_dir.display());
+            println!("  collection:   {}", config.collection_name());
             println!(
                 "  sync_url:     {}",
-                config.sync_url.as_deref().unwrap_or("(not set)")
+                config.sync_url.as_deref().unwrap_or(t("(not set)"))
             );
             println!("  sync_enabled: {}", config.sync_enabled);
+            println!("  trace_sync:   {}", config.trace_sync);
+            println!("  sync_compression: {}", config.sync_compression);
+            println!("  sync_batch_ms: {}", config.sync_batch_ms);
             println!(
-                "  favorite_tag: {}",
-                config.favorite_tag.as_deref().unwrap_or("(not set)")
+                "  device_name:  {}",
+                config.device_name.as_deref().unwrap_or(t("(host name)"))
+            );
+            println!(
+                "  favorite_tags: {}",
+                if config.favorite_tags.is_empty() {
+                    t("(not set)").to_string()
+                } else {
+                    tf(
+                        "{} (match {})",
+                        &[&config.favorite_tags.join(", "), &config.favorite_match],
+                    )
+                }
             );
             println!(
                 "  log_file:     {}",
@@ -50,10 +114,206 @@ pub fn show(config_path: Option<&PathBuf>, output: &Output) -> Result<()> {
                     .log_file
                     .as_ref()
                     .map(|p| p.display().to_string())
-                    .unwrap_or_else(|| "(not set)".to_string())
+                    .unwrap_or_else(|| t("(not set)").to_string())
+            );
+            println!("  accessible_mode: {}", config.accessible_mode);
+            println!(
+                "  announce_path: {}",
+                config
+                    .announce_path
+                    .as_ref()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_else(|| t("(not set)").to_string())
+            );
+            println!(
+                "  blocked_domains: {}",
+                if config.blocked_domains.is_empty() {
+                    t("(not set)").to_string()
+                } else {
+                    config.blocked_domains.join(", ")
+                }
+            );
+            if config.domain_tags.is_empty() {
+                println!("  domain_tags:  {}", t("(not set)"));
+            } else {
+                println!("  domain_tags:");
+                for (domain, tags) in &config.domain_tags {
+                    println!("    {} = {}", domain, tags.join(", "));
+                }
+            }
+            if config.openers.is_empty() {
+                println!(
+                    "  openers:      {}",
+                    t("(not set, links open in the browser)")
+                );
+            } else {
+                println!("  openers:");
+                for (key, command) in &config.openers {
+                    println!("    {} = {}", key, command);
+                }
+            }
+            println!(
+                "  allowed_schemes: {}",
+                if config.allowed_schemes.is_empty() {
+                    t("(not set)").to_string()
+                } else {
+                    config.allowed_schemes.join(", ")
+                }
+            );
+            println!("  url_trailing_slash: {}", config.url_trailing_slash.name());
+            println!(
+                "  lock:         {}",
+                if config.is_lock_enabled() {
+                    tf(
+                        "enabled ({} minute(s) idle timeout)",
+                        &[&config.lock_timeout_minutes],
+                    )
+                } else {
+                    t("disabled").to_string()
+                }
+            );
+            println!("  max_title_length:       {}", config.max_title_length);
+            println!(
+                "  max_description_length: {}",
+                config.max_description_length
+            );
+            println!(
+                "  metadata_cache_hours:   {}",
+                if config.metadata_cache_hours == 0 {
+                    t("0 (cache off)").to_string()
+                } else {
+                    config.metadata_cache_hours.to_string()
+                }
+            );
+            println!(
+                "  backup_interval:        {}",
+                config
+                    .backup_interval
+                    .map(|i| i.to_string())
+                    .unwrap_or_else(|| t("(not set)").to_string())
+            );
+            println!(
+                "  backup_dir:             {}",
+                config.backup_dir().display()
             );
+            println!("  backup_keep:            {}", config.backup_keep);
+            println!(
+                "  link_check_interval:    {}",
+                config
+                    .link_check_interval
+                    .map(|i| i.to_string())
+                    .unwrap_or_else(|| t("(not set)").to_string())
+            );
+            println!(
+                "  archive_expired_interval: {}",
+                config
+                    .archive_expired_interval
+                    .map(|i| i.to_string())
+                    .unwrap_or_else(|| t("(not set)").to_string())
+            );
+            println!(
+                "  report_interval:        {}",
+                config
+                    .report_interval
+                    .map(|i| i.to_string())
+                    .unwrap_or_else(|| t("(not set)").to_string())
+            );
+            println!(
+                "  report_dir:             {}",
+                config.report_dir().display()
+            );
+            println!("  record_opens:           {}", config.record_opens);
+            println!("  fuzzy_filter:           {}", config.fuzzy_filter);
+            println!("  default_sort:           {}", config.default_sort.name());
+            println!(
+                "  search_provider:        {}",
+                config.search_provider.name()
+            );
+            println!(
+                "  triage_tags:            {}",
+                if config.triage_tags.is_empty() {
+                    t("(most used)").to_string()
+                } else {
+                    config.triage_tags.join(", ")
+                }
+            );
+            println!(
+                "  locale:                 {}",
+                config
+                    .locale
+                    .as_deref()
+                    .unwrap_or(t("(not set, from environment)"))
+            );
+            println!(
+                "  hypothesis:             {}",
+                if config.hypothesis_token.is_some() {
+                    t("enabled (token set)")
+                } else {
+                    t("disabled")
+                }
+            );
+            println!(
+                "  quick add:              {}",
+                if config.add_token.is_some() {
+                    t("enabled (add_token set)")
+                } else {
+                    t("disabled")
+                }
+            );
+            println!(
+                "  api:                    {}",
+                if config.api_token.is_some() {
+                    t("enabled (api_token set)")
+                } else {
+                    t("disabled")
+                }
+            );
+            println!(
+                "  note_template:          {}",
+                config.note_template.as_deref().unwrap_or(t("(not set)"))
+            );
+            println!(
+                "  note_section_threshold: {}",
+                config.note_section_threshold
+            );
+            println!(
+                "  screenshot_command:     {}",
+                config
+                    .screenshot_command
+                    .as_deref()
+                    .unwrap_or(t("(not set)"))
+            );
+            println!("  screenshot_on_save:     {}", config.screenshot_on_save);
+            println!(
+                "  archive_content_on_save: {}",
+                config.archive_content_on_save
+            );
+            if config.captures.is_empty() {
+                println!("  captures:     {}", t("(not set)"));
+            } else {
+                println!("  captures:");
+                for (name, capture) in &config.captures {
+                    println!(
+                        "    {} = {}",
+                        name,
+                        if capture.tags.is_empty() {
+                            t("(no tags)").to_string()
+                        } else {
+                            capture.tags.join(", ")
+                        }
+                    );
+                }
+            }
+            if config.checks.is_empty() {
+                println!("  checks:       {}", t("(not set)"));
+            } else {
+                println!(
+                    "  checks:       {}",
+                    config.checks.keys().cloned().collect::<Vec<_>>().join(", ")
+                );
+            }
             println!();
-            println!("Config file: {}", effective_path.display());
+            println!("{}", tf("Config file: {}", &[&effective_path.display()]));
         }
     }
 
@@ -68,7 +328,7 @@ pub fn set(
     output: &Output,
 ) -> Result<()> {
     let mut config =
-        Config::load_with_cli_override(config_path).context("Failed to load configuration")?;
+        Config::load_with_cli_override(config_path).context(t("Failed to load configuration"))?;
 
     match key.as_str() {
         "data_dir" => {
@@ -82,17 +342,34 @@ pub fn set(
             };
         }
         "sync_enabled" => {
-            config.sync_enabled = value
-                .parse()
-                .context("Invalid value for sync_enabled. Use 'true' or 'false'.")?;
+            config.sync_enabled = parse_bool(&key, &value)?;
+        }
+        "trace_sync" => {
+            config.trace_sync = parse_bool(&key, &value)?;
+        }
+        "sync_compression" => {
+            config.sync_compression = parse_bool(&key, &value)?;
         }
-        "favorite_tag" => {
-            config.favorite_tag = if value.is_empty() || value == "none" {
+        "sync_batch_ms" => {
+            config.sync_batch_ms = value.parse().context(t(
+                "Invalid value for sync_batch_ms. Use a number of milliseconds.",
+            ))?;
+        }
+        "device_name" => {
+            config.device_name = if value.is_empty() || value == "none" {
                 None
             } else {
                 Some(value.clone())
             };
         }
+        // favorite_tag is the old single-tag key
+        "favorite_tags" | "favorite_tag" => {
+            config.favorite_tags = parse_list(&value);
+        }
+        "favorite_match" => {
+            config.favorite_match = FavoriteMatch::parse(&value)
+                .context(t("Invalid value for favorite_match. Use 'any' or 'all'."))?;
+        }
         "log_file" => {
             config.log_file = if value.is_empty() || value == "none" {
                 None
@@ -100,12 +377,200 @@ pub fn set(
                 Some(value.clone().into())
             };
         }
+        "accessible_mode" => {
+            config.accessible_mode = parse_bool(&key, &value)?;
+        }
+        "announce_path" => {
+            config.announce_path = if value.is_empty() || value == "none" {
+                None
+            } else {
+                Some(value.clone().into())
+            };
+        }
+        "blocked_domains" => {
+            config.blocked_domains = parse_list(&value);
+        }
+        "domain_tags" => {
+            config.domain_tags = parse_domain_tags(&value)?;
+        }
+        "openers" => {
+            config.openers = parse_openers(&value)?;
+        }
+        "allowed_schemes" => {
+            config.allowed_schemes = parse_list(&value);
+        }
+        "url_trailing_slash" => {
+            config.url_trailing_slash = TrailingSlash::parse(&value).with_context(|| {
+                tf(
+                    "Invalid value for url_trailing_slash: '{}'. Use 'strip' or 'keep'.",
+                    &[&value],
+                )
+            })?;
+        }
+        "lock_timeout_minutes" => {
+            config.lock_timeout_minutes = value.parse().context(t(
+                "Invalid value for lock_timeout_minutes. Use a number of minutes.",
+            ))?;
+        }
+        "max_title_length" => {
+            config.max_title_length = parse_length(&key, &value)?;
+        }
+        "max_description_length" => {
+            config.max_description_length = parse_length(&key, &value)?;
+        }
+        "metadata_cache_hours" => {
+            config.metadata_cache_hours = value.parse().context(
+                t("Invalid value for metadata_cache_hours. Use a number of hours (0 turns the cache off)."),
+            )?;
+        }
+        "backup_interval" => {
+            config.backup_interval = parse_interval(&key, &value)?;
+        }
+        "backup_dir" => {
+            config.backup_dir = if value.is_empty() || value == "none" {
+                None
+            } else {
+                Some(value.clone().into())
+            };
+        }
+        "backup_keep" => {
+            config.backup_keep = value
+                .parse()
+                .context(t("Invalid value for backup_keep. Use a number of backups."))?;
+        }
+        "link_check_interval" => {
+            config.link_check_interval = parse_interval(&key, &value)?;
+        }
+        "archive_expired_interval" => {
+            config.archive_expired_interval = parse_interval(&key, &value)?;
+        }
+        "report_interval" => {
+            config.report_interval = parse_interval(&key, &value)?;
+        }
+        "report_dir" => {
+            config.report_dir = if value.is_empty() || value == "none" {
+                None
+            } else {
+                Some(value.clone().into())
+            };
+        }
+        "record_opens" => {
+            config.record_opens = parse_bool(&key, &value)?;
+        }
+        "fuzzy_filter" => {
+            config.fuzzy_filter = parse_bool(&key, &value)?;
+        }
+        "default_sort" => {
+            config.default_sort = LinkSort::parse(&value).with_context(|| {
+                tf(
+                    "Invalid value for default_sort: '{}'. Use updated, created, oldest, title, or domain.",
+                    &[&value],
+                )
+            })?;
+        }
+        "search_provider" => {
+            let provider = SearchBackend::parse(&value).with_context(|| {
+                tf(
+                    "Invalid value for search_provider: '{}'. Use scan or sqlite.",
+                    &[&value],
+                )
+            })?;
+            if !provider.is_available() {
+                eprintln!(
+                    "{}",
+                    tf(
+                        "Warning: this build of rott doesn't include the {} search provider \
+                         (build with --features sqlite-search); searches will scan the document.",
+                        &[&provider.name()],
+                    )
+                );
+            }
+            config.search_provider = provider;
+        }
+        "triage_tags" => {
+            let tags = parse_list(&value);
+            if tags.len() > 9 {
+                bail!(t("Too many triage_tags: only the keys 1-9 can be bound."));
+            }
+            config.triage_tags = tags;
+        }
+        "locale" => {
+            config.locale = if value.is_empty() || value == "none" {
+                None
+            } else if let Some(locale) = Locale::parse(&value) {
+                Some(locale.code().to_string())
+            } else {
+                let codes: Vec<_> = Locale::ALL.iter().map(|l| l.code()).collect();
+                bail!(tf(
+                    "Unsupported locale '{}'. Use one of: {} (or none for the environment's)",
+                    &[&value, &codes.join(", ")],
+                ));
+            };
+        }
+        "hypothesis_token" => {
+            config.hypothesis_token = if value.is_empty() || value == "none" {
+                None
+            } else {
+                Some(value.clone())
+            };
+        }
+        "add_token" => {
+            config.add_token = match value.as_str() {
+                "" | "none" => None,
+                "generate" => Some(Uuid::new_v4().simple().to_string()),
+                _ => Some(value.clone()),
+            };
+        }
+        "api_token" => {
+            config.api_token = match value.as_str() {
+                "" | "none" => None,
+                "generate" => Some(Uuid::new_v4().simple().to_string()),
+                _ => Some(value.clone()),
+            };
+        }
+        "note_template" => {
+            config.note_template = if value.is_empty() || value == "none" {
+                None
+            } else {
+                rott_core::template::required_names(&value).context(t("Invalid note_template"))?;
+                Some(value.clone())
+            };
+        }
+        "note_section_threshold" => {
+            config.note_section_threshold = value.parse().context(
+                t("Invalid value for note_section_threshold. Use a number of bytes (0 to turn sections off)."),
+            )?;
+        }
+        "screenshot_command" => {
+            config.screenshot_command = if value.is_empty() || value == "none" {
+                None
+            } else {
+                Some(value.clone())
+            };
+        }
+        "screenshot_on_save" => {
+            config.screenshot_on_save = parse_bool(&key, &value)?;
+        }
+        "archive_content_on_save" => {
+            config.archive_content_on_save = parse_bool(&key, &value)?;
+        }
         _ => {
-            bail!(
-                "Unknown configuration key: '{}'\n\
-                 Valid keys: data_dir, sync_url, sync_enabled, favorite_tag, log_file",
-                key
-            );
+            bail!(tf(
+                "Unknown configuration key: '{}'\nValid keys: {}",
+                &[
+                    &key,
+                    &"data_dir, sync_url, sync_enabled, trace_sync, sync_compression, \
+                      sync_batch_ms, device_name, favorite_tags, \
+                      favorite_match, log_file, \
+                      accessible_mode, announce_path, blocked_domains, domain_tags, openers, allowed_schemes, url_trailing_slash, \
+                      lock_timeout_minutes, max_title_length, max_description_length, \
+                      metadata_cache_hours, \
+                      backup_interval, backup_dir, backup_keep, link_check_interval, \
+                      archive_expired_interval, report_interval, report_dir, record_opens, fuzzy_filter, default_sort, search_provider, triage_tags, locale, hypothesis_token, add_token, api_token, note_template, \
+                      note_section_threshold, \
+                      screenshot_command, screenshot_on_save, archive_content_on_save",
+                ],
+            ));
         }
     }
 
@@ -115,9 +580,194 @@ pub fn set(
         .unwrap_or_else(Config::config_file_path);
     config
         .save_to_path(&save_path)
-        .context("Failed to save configuration")?;
+        .context(t("Failed to save configuration"))?;
+
+    if key == "hypothesis_token" {
+        // Don't echo the secret
+        output.success(&tf("Set {}", &[&key]));
+    } else if matches!(key.as_str(), "add_token" | "api_token") && value == "generate" {
+        // Shown once, to copy into the phone's shortcut or the extension
+        let token = if key == "add_token" {
+            config.add_token.as_deref()
+        } else {
+            config.api_token.as_deref()
+        }
+        .unwrap_or_default();
+        if output.is_quiet() {
+            println!("{}", token);
+        } else {
+            output.success(&tf("Set {} = {}", &[&key, &token]));
+        }
+    } else if matches!(key.as_str(), "add_token" | "api_token") {
+        output.success(&tf("Set {}", &[&key]));
+    } else {
+        output.success(&tf("Set {} = {}", &[&key, &value]));
+    }
+
+    Ok(())
+}
+
+/// Export settings as a bundle
+pub fn export(
+    file: Option<PathBuf>,
+    include_secrets: bool,
+    config_path: Option<&PathBuf>,
+    output: &Output,
+) -> Result<()> {
+    let config =
+        Config::load_with_cli_override(config_path).context(t("Failed to load configuration"))?;
+    let bundle = config.export_bundle(include_secrets)?;
+
+    match file {
+        None => print!("{}", bundle),
+        Some(file) => {
+            fs::write(&file, &bundle)
+                .with_context(|| tf("Failed to write {}", &[&file.display()]))?;
+            output.success(&tf("Exported configuration to {}", &[&file.display()]));
+        }
+    }
+    Ok(())
+}
 
-    output.success(&format!("Set {} = {}", key, value));
+/// Replace settings with an exported bundle
+pub fn import(file: PathBuf, config_path: Option<&PathBuf>, output: &Output) -> Result<()> {
+    let bundle =
+        fs::read_to_string(&file).with_context(|| tf("Failed to read {}", &[&file.display()]))?;
+    let current =
+        Config::load_with_cli_override(config_path).context(t("Failed to load configuration"))?;
+    let config = current.import_bundle(&bundle)?;
+
+    let save_path = config_path
+        .cloned()
+        .unwrap_or_else(Config::config_file_path);
+    config
+        .save_to_path(&save_path)
+        .context(t("Failed to save configuration"))?;
 
+    output.success(&tf(
+        "Imported configuration from {} into {}",
+        &[&file.display(), &save_path.display()],
+    ));
     Ok(())
 }
+
+/// Parse a comma-separated list ("none" or empty clears it)
+fn parse_list(value: &str) -> Vec<String> {
+    if value == "none" {
+        return Vec::new();
+    }
+    value
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Parse a `true` or `false` setting
+fn parse_bool(key: &str, value: &str) -> Result<bool> {
+    value
+        .parse()
+        .with_context(|| tf("Invalid value for {}. Use 'true' or 'false'.", &[&key]))
+}
+
+/// Parse a maximum length (must be at least 1)
+fn parse_length(key: &str, value: &str) -> Result<usize> {
+    match value.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => bail!(tf(
+            "Invalid value for {}. Use a number of characters (at least 1).",
+            &[&key],
+        )),
+    }
+}
+
+/// Parse a maintenance job interval ("none" or empty disables the job)
+fn parse_interval(key: &str, value: &str) -> Result<Option<Interval>> {
+    if value.is_empty() || value == "none" {
+        return Ok(None);
+    }
+    match Interval::parse(value) {
+        Some(interval) => Ok(Some(interval)),
+        None => bail!(tf(
+            "Invalid value for {}. Use daily, weekly, monthly, or a number of days (e.g. 10d).",
+            &[&key],
+        )),
+    }
+}
+
+/// Parse domain auto-tag rules
+///
+/// Format: `domain=tag1,tag2;other.com=tag3` ("none" or empty clears them)
+fn parse_domain_tags(value: &str) -> Result<BTreeMap<String, Vec<String>>> {
+    let mut rules = BTreeMap::new();
+    if value == "none" {
+        return Ok(rules);
+    }
+
+    for entry in value.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+        let Some((domain, tags)) = entry.split_once('=') else {
+            bail!(tf(
+                "Invalid domain_tags entry '{}'. Use: domain=tag1,tag2;other.com=tag3",
+                &[&entry],
+            ));
+        };
+        rules.insert(domain.trim().to_lowercase(), parse_list(tags));
+    }
+
+    Ok(rules)
+}
+
+fn parse_openers(value: &str) -> Result<BTreeMap<String, String>> {
+    let mut openers = BTreeMap::new();
+    if value == "none" {
+        return Ok(openers);
+    }
+
+    for entry in value.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+        let Some((key, command)) = entry
+            .split_once('=')
+            .filter(|(_, command)| !command.trim().is_empty())
+        else {
+            bail!(tf(
+                "Invalid openers entry '{}'. Use: video=mpv {url};arxiv.org=zathura {url};default=firefox",
+                &[&entry],
+            ));
+        };
+        openers.insert(key.trim().to_lowercase(), command.trim().to_string());
+    }
+
+    Ok(openers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_list() {
+        assert_eq!(parse_list("a.com, b.com,,"), vec!["a.com", "b.com"]);
+        assert!(parse_list("none").is_empty());
+        assert!(parse_list("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_domain_tags() {
+        let rules = parse_domain_tags("GitHub.com=code,dev; arxiv.org=papers").unwrap();
+        assert_eq!(rules["github.com"], vec!["code", "dev"]);
+        assert_eq!(rules["arxiv.org"], vec!["papers"]);
+
+        assert!(parse_domain_tags("none").unwrap().is_empty());
+        assert!(parse_domain_tags("github.com").is_err());
+    }
+
+    #[test]
+    fn test_parse_openers() {
+        let openers = parse_openers("Video=mpv --fs {url}; arxiv.org=zathura {url}").unwrap();
+        assert_eq!(openers["video"], "mpv --fs {url}");
+        assert_eq!(openers["arxiv.org"], "zathura {url}");
+
+        assert!(parse_openers("none").unwrap().is_empty());
+        assert!(parse_openers("video").is_err());
+        assert!(parse_openers("video=").is_err());
+    }
+}
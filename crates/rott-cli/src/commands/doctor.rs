@@ -0,0 +1,64 @@
+//! Doctor command handler
+//!
+//! Reports records that could only be read in part. Everything else keeps
+//! working with those records (missing fields are filled with defaults),
+//! so this is the place to find out that something is wrong with them.
+
+use anyhow::Result;
+
+use rott_core::{RecordWarning, Store};
+
+use crate::i18n::{t, tf};
+use crate::output::{Output, OutputFormat};
+
+/// Check the document for malformed records
+pub fn check(store: &Store, output: &Output) -> Result<()> {
+    let warnings = store.check_records()?;
+
+    match output.format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::json!({
+                    "ok": warnings.is_empty(),
+                    "warnings": warnings,
+                })
+            );
+        }
+        OutputFormat::Quiet => {
+            for warning in &warnings {
+                println!("{}", describe(warning));
+            }
+        }
+        OutputFormat::Human => {
+            if warnings.is_empty() {
+                println!("{}", t("No problems found"));
+                return Ok(());
+            }
+            if warnings.len() == 1 {
+                println!("{}", t("1 problem found:"));
+            } else {
+                println!("{}", tf("{} problems found:", &[&warnings.len()]));
+            }
+            for warning in &warnings {
+                println!("  {}", describe(warning));
+            }
+            println!();
+            println!(
+                "{}",
+                t("These records are shown with defaults for the parts that couldn't be read.")
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn describe(warning: &RecordWarning) -> String {
+    let place = match (warning.link_id, warning.note_id) {
+        (Some(link_id), Some(note_id)) => tf("link {} note {}", &[&link_id, &note_id]),
+        (Some(link_id), None) => tf("link {}", &[&link_id]),
+        (None, _) => t("links").to_string(),
+    };
+    format!("{}: {}", place, warning.problem)
+}
@@ -0,0 +1,863 @@
+//! Export command handlers
+//!
+//! The text export flattens the whole collection into one line per field,
+//! each prefixed with its link ID, so it can be searched and processed with
+//! standard unix tools:
+//!
+//! ```text
+//! <link-id>\ttitle\tRust Programming Language
+//! <link-id>\turl\thttps://rust-lang.org
+//! <link-id>\ttag\trust
+//! <link-id>\tnote\tFirst line of a note
+//! <link-id>\treply\tFirst line of a reply to that note
+//! <link-id>\tnote-url\thttps://a-page-mentioned-in-a-note.example
+//! ```
+//!
+//! The SQLite export writes a standalone database with the schema in
+//! [`SQLITE_SCHEMA`], filled with parameterized inserts in one
+//! transaction.
+//!
+//! The JSON, CSV, and Markdown exports are for leaving ROTT or feeding other
+//! tools: JSON has every field of every link and note, CSV one row per link,
+//! and Markdown one file per link with YAML frontmatter (see
+//! [`markdown_file`]), ready for a static site generator.
+//!
+//! Every export can be pinned to the document's state: `--heads-file`
+//! records the heads it was generated from, and `--at-heads` exports the
+//! collection as it was at those heads. The text, JSON, CSV, and Markdown
+//! exports come out byte-identical every time, so a published snapshot can
+//! be checked later. Markdown exports keep their heads in the directory
+//! ([`HEADS_FILE`]), so `--incremental` only rewrites what changed since.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
+use uuid::Uuid;
+
+use rott_core::models::thread_notes;
+use rott_core::urls::url_strs;
+use rott_core::{Link, LinkSort, Store};
+
+use crate::i18n::{t, tf};
+use crate::output::{Output, OutputFormat};
+
+/// File in a Markdown export's directory holding the heads it was
+/// generated from
+pub const HEADS_FILE: &str = ".rott-heads";
+
+/// Split heads as written by [`write_heads`] (separated by commas or
+/// whitespace)
+pub fn parse_heads(text: &str) -> Vec<String> {
+    text.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|head| !head.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Write document heads to a file, comma-separated on one line
+pub fn write_heads(path: &Path, heads: &[String]) -> Result<()> {
+    fs::write(path, format!("{}\n", heads.join(",")))
+        .with_context(|| tf("Failed to write {}", &[&path.display()]))
+}
+
+/// A single line of the flat text representation
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextRecord {
+    /// The link this line belongs to
+    pub link_id: Uuid,
+    /// Which field the text came from (title, url, tag, note, reply, ...)
+    pub field: &'static str,
+    /// The text itself (never contains newlines)
+    pub text: String,
+}
+
+/// Export the whole collection as greppable text
+///
+/// Writes to `out` if given, otherwise to stdout.
+pub fn text(
+    store: &Store,
+    out: Option<PathBuf>,
+    include_private: bool,
+    output: &Output,
+) -> Result<()> {
+    let links = exported_links(store, None, include_private, output)?;
+    let records = text_records(&links);
+
+    match out {
+        Some(path) => {
+            let mut file = fs::File::create(&path)
+                .with_context(|| tf("Failed to create {}", &[&path.display()]))?;
+            write_records(&mut file, &records)
+                .with_context(|| tf("Failed to write {}", &[&path.display()]))?;
+            output.success(&tf(
+                "Exported {} link(s) to {}",
+                &[&links.len(), &path.display()],
+            ));
+        }
+        None => {
+            let stdout = io::stdout();
+            write_records(&mut stdout.lock(), &records)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The links to export: all of them, or all but those with a private tag,
+/// narrowed to those with `tag` if given
+///
+/// Says how many were left out, on stderr so a text export to stdout
+/// stays clean.
+fn exported_links(
+    store: &Store,
+    tag: Option<&str>,
+    include_private: bool,
+    output: &Output,
+) -> Result<Vec<Link>> {
+    let mut links = if include_private {
+        store.get_all_links(LinkSort::default())?
+    } else {
+        let links = store.shareable_links()?;
+        let private = (store.link_count()? as usize).saturating_sub(links.len());
+        if private > 0 && !output.is_quiet() {
+            eprintln!(
+                "{}",
+                tf(
+                    "Left out {} link(s) with a private tag (use --include-private to export them)",
+                    &[&private]
+                )
+            );
+        }
+        links
+    };
+    if let Some(tag) = tag {
+        links.retain(|link| link.has_tag(tag));
+    }
+    Ok(links)
+}
+
+/// Links ordered by creation date, so exports diff cleanly over time
+fn by_creation(links: &[Link]) -> Vec<&Link> {
+    let mut links: Vec<&Link> = links.iter().collect();
+    links.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+    links
+}
+
+/// Write an export to `out`, or to stdout if not given
+fn write_export(out: Option<PathBuf>, content: &str, count: usize, output: &Output) -> Result<()> {
+    match out {
+        Some(path) => {
+            fs::write(&path, content)
+                .with_context(|| tf("Failed to write {}", &[&path.display()]))?;
+            output.success(&tf("Exported {} link(s) to {}", &[&count, &path.display()]));
+        }
+        None => io::stdout().lock().write_all(content.as_bytes())?,
+    }
+    Ok(())
+}
+
+/// Export links and their notes as a JSON array
+pub fn json(
+    store: &Store,
+    out: Option<PathBuf>,
+    tag: Option<String>,
+    include_private: bool,
+    output: &Output,
+) -> Result<()> {
+    let links = exported_links(store, tag.as_deref(), include_private, output)?;
+    let mut content = serde_json::to_string_pretty(&by_creation(&links))?;
+    content.push('\n');
+    write_export(out, &content, links.len(), output)
+}
+
+/// Export links as CSV, one row per link
+pub fn csv(
+    store: &Store,
+    out: Option<PathBuf>,
+    tag: Option<String>,
+    include_private: bool,
+    output: &Output,
+) -> Result<()> {
+    let links = exported_links(store, tag.as_deref(), include_private, output)?;
+    write_export(out, &csv_table(&links), links.len(), output)
+}
+
+/// Columns of the CSV export
+pub const CSV_COLUMNS: &[&str] = &[
+    "id",
+    "url",
+    "title",
+    "description",
+    "tags",
+    "author",
+    "created_at",
+    "updated_at",
+    "published_at",
+    "opened_at",
+    "notes",
+];
+
+/// The CSV export: a header row, then one row per link
+///
+/// Tags and authors are joined with `;`. The notes column holds every note
+/// (title, then body) in the order shown, separated by blank lines.
+pub fn csv_table(links: &[Link]) -> String {
+    let mut csv = CSV_COLUMNS.join(",");
+    csv.push_str("\r\n");
+    for link in by_creation(links) {
+        let notes: Vec<String> = thread_notes(&link.notes)
+            .into_iter()
+            .map(|(note, _)| match note.title {
+                Some(ref title) => format!("{}\n{}", title, note.body),
+                None => note.body.clone(),
+            })
+            .collect();
+        let row = [
+            link.id.to_string(),
+            link.url.clone(),
+            link.title.clone(),
+            link.description.clone().unwrap_or_default(),
+            link.tags.join(";"),
+            link.author.join(";"),
+            link.created_at.to_rfc3339(),
+            link.updated_at.to_rfc3339(),
+            link.published_at
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_default(),
+            link.opened_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            notes.join("\n\n"),
+        ];
+        let row: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+/// A CSV field, quoted if it holds a comma, quote, or line break
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Export one Markdown file per link into the directory `out`
+///
+/// The directory is created if needed, and files from an earlier export
+/// with the same name (see [`markdown_file_name`]) are replaced. With
+/// `incremental`, files that are the same as at the last export's heads
+/// are left alone, and those of links no longer exported are removed.
+pub fn markdown(
+    store: &Store,
+    out: PathBuf,
+    tag: Option<String>,
+    include_private: bool,
+    incremental: bool,
+    output: &Output,
+) -> Result<()> {
+    let links = exported_links(store, tag.as_deref(), include_private, output)?;
+    fs::create_dir_all(&out).with_context(|| tf("Failed to create {}", &[&out.display()]))?;
+    let files = markdown_files(&links);
+    let previous = if incremental {
+        previous_markdown_files(store, &out, tag.as_deref(), include_private)?
+    } else {
+        None
+    };
+
+    let mut written = 0;
+    for (name, content) in &files {
+        let path = out.join(name);
+        let unchanged = previous
+            .as_ref()
+            .is_some_and(|previous| previous.get(name) == Some(content));
+        if unchanged && path.exists() {
+            continue;
+        }
+        fs::write(&path, content).with_context(|| tf("Failed to write {}", &[&path.display()]))?;
+        written += 1;
+    }
+    let mut removed = 0;
+    for name in previous.iter().flat_map(|previous| previous.keys()) {
+        let path = out.join(name);
+        if !files.contains_key(name) && path.exists() {
+            fs::remove_file(&path)
+                .with_context(|| tf("Failed to remove {}", &[&path.display()]))?;
+            removed += 1;
+        }
+    }
+    write_heads(&out.join(HEADS_FILE), &store.heads())?;
+
+    if previous.is_some() {
+        output.success(&tf(
+            "Exported {} changed link(s) to {} ({} unchanged, {} removed)",
+            &[&written, &out.display(), &(files.len() - written), &removed],
+        ));
+    } else {
+        output.success(&tf(
+            "Exported {} link(s) to {}",
+            &[&links.len(), &out.display()],
+        ));
+    }
+    Ok(())
+}
+
+/// Each link's Markdown file, by file name
+fn markdown_files(links: &[Link]) -> BTreeMap<String, String> {
+    links
+        .iter()
+        .map(|link| (markdown_file_name(link), markdown_file(link)))
+        .collect()
+}
+
+/// The files the last export to `out` wrote, rendered again at the heads
+/// it recorded; None if it didn't record any
+fn previous_markdown_files(
+    store: &Store,
+    out: &Path,
+    tag: Option<&str>,
+    include_private: bool,
+) -> Result<Option<BTreeMap<String, String>>> {
+    let path = out.join(HEADS_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let text =
+        fs::read_to_string(&path).with_context(|| tf("Failed to read {}", &[&path.display()]))?;
+    let then = store
+        .at_heads(&parse_heads(&text))
+        .with_context(|| tf("Can't export incrementally from {}", &[&path.display()]))?;
+    let quiet = Output::new(OutputFormat::Quiet);
+    let links = exported_links(&then, tag, include_private, &quiet)?;
+    Ok(Some(markdown_files(&links)))
+}
+
+/// File name of a link's Markdown export: a slug of its title and the
+/// first 8 characters of its ID, such as `rust-programming-1a2b3c4d.md`
+pub fn markdown_file_name(link: &Link) -> String {
+    let mut slug = String::new();
+    for c in link.title.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+        if slug.chars().count() >= 60 {
+            break;
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    let id = &link.id.to_string()[..8];
+    if slug.is_empty() {
+        format!("{}.md", id)
+    } else {
+        format!("{}-{}.md", slug, id)
+    }
+}
+
+/// A link as Markdown: YAML frontmatter with its fields, then its
+/// description and notes
+///
+/// Notes are `##` sections (replies `###`) headed by their title or date,
+/// in the order shown.
+pub fn markdown_file(link: &Link) -> String {
+    let mut md = String::from("---\n");
+    let mut field = |key: &str, value: &str| {
+        md.push_str(&format!("{}: {}\n", key, yaml_string(value)));
+    };
+    field("id", &link.id.to_string());
+    field("title", &link.title);
+    field("url", &link.url);
+    if let Some(ref canonical) = link.canonical_url {
+        field("canonical_url", canonical);
+    }
+    if let Some(ref description) = link.description {
+        field("description", description);
+    }
+    field("created", &link.created_at.to_rfc3339());
+    field("updated", &link.updated_at.to_rfc3339());
+    if let Some(published) = link.published_at {
+        field("published", &published.to_rfc3339());
+    }
+    if let Some(opened) = link.opened_at {
+        field("opened", &opened.to_rfc3339());
+    }
+    for (key, values) in [("author", &link.author), ("tags", &link.tags)] {
+        if values.is_empty() {
+            continue;
+        }
+        md.push_str(&format!("{}:\n", key));
+        for value in values {
+            md.push_str(&format!("  - {}\n", yaml_string(value)));
+        }
+    }
+    md.push_str("---\n\n");
+
+    md.push_str(&format!("# [{}]({})\n", link.title, link.url));
+    if let Some(ref description) = link.description {
+        md.push_str(&format!("\n{}\n", description));
+    }
+    for (note, depth) in thread_notes(&link.notes) {
+        let date = note.created_at.format("%Y-%m-%d");
+        let heading = match note.title {
+            Some(ref title) => format!("{} ({})", title, date),
+            None => date.to_string(),
+        };
+        let level = if depth > 0 { "###" } else { "##" };
+        md.push_str(&format!(
+            "\n{} {}\n\n{}\n",
+            level,
+            heading,
+            note.body.trim_end()
+        ));
+    }
+    md
+}
+
+/// A YAML double-quoted scalar (JSON string syntax is valid YAML)
+fn yaml_string(text: &str) -> String {
+    serde_json::Value::from(text).to_string()
+}
+
+/// Flatten links into one record per line of each searchable field
+///
+/// Links are ordered by creation date so exports diff cleanly over time.
+pub fn text_records(links: &[Link]) -> Vec<TextRecord> {
+    let mut links: Vec<&Link> = links.iter().collect();
+    links.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+
+    let mut records = Vec::new();
+    for link in links {
+        let mut push = |field: &'static str, text: &str| {
+            for line in text.lines() {
+                let line = line.trim();
+                if !line.is_empty() {
+                    records.push(TextRecord {
+                        link_id: link.id,
+                        field,
+                        text: line.replace('\t', " "),
+                    });
+                }
+            }
+        };
+
+        push("title", &link.title);
+        push("url", &link.url);
+        if let Some(ref desc) = link.description {
+            push("description", desc);
+        }
+        for author in &link.author {
+            push("author", author);
+        }
+        for tag in &link.tags {
+            push("tag", tag);
+        }
+        // Replies follow the note they reply to
+        for (note, depth) in thread_notes(&link.notes) {
+            let (title_field, body_field) = if depth > 0 {
+                ("reply-title", "reply")
+            } else {
+                ("note-title", "note")
+            };
+            if let Some(ref title) = note.title {
+                push(title_field, title);
+            }
+            push(body_field, &note.body);
+            // URLs mentioned in notes get their own lines, ready to open or save
+            for url in url_strs(&note.body) {
+                push("note-url", url);
+            }
+        }
+    }
+    records
+}
+
+/// Version of [`SQLITE_SCHEMA`], stored in the `meta` table
+///
+/// Bumped only when a table or column is changed or removed; adding one
+/// doesn't break existing queries.
+pub const SQLITE_SCHEMA_VERSION: u32 = 1;
+
+/// Tables written by `rott export sqlite`
+///
+/// Timestamps are RFC 3339 text in UTC; flags are 0 or 1.
+pub const SQLITE_SCHEMA: &str = "\
+CREATE TABLE meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+CREATE TABLE links (
+    id TEXT PRIMARY KEY,
+    url TEXT NOT NULL,
+    canonical_url TEXT,
+    title TEXT NOT NULL,
+    description TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    published_at TEXT,
+    opened_at TEXT,
+    paywalled INTEGER NOT NULL,
+    unreachable INTEGER NOT NULL,
+    content_changed INTEGER NOT NULL,
+    content_checked_at TEXT,
+    language TEXT,
+    open_count INTEGER NOT NULL,
+    expires_at TEXT
+);
+CREATE TABLE link_authors (
+    link_id TEXT NOT NULL REFERENCES links(id),
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    PRIMARY KEY (link_id, position)
+);
+CREATE TABLE link_tags (
+    link_id TEXT NOT NULL REFERENCES links(id),
+    tag TEXT NOT NULL,
+    PRIMARY KEY (link_id, tag)
+);
+CREATE TABLE link_fields (
+    link_id TEXT NOT NULL REFERENCES links(id),
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (link_id, key)
+);
+CREATE TABLE notes (
+    id TEXT PRIMARY KEY,
+    link_id TEXT NOT NULL REFERENCES links(id),
+    parent_id TEXT REFERENCES notes(id),
+    position INTEGER NOT NULL,
+    title TEXT,
+    body TEXT NOT NULL,
+    pinned INTEGER NOT NULL,
+    created_at TEXT NOT NULL
+);
+CREATE INDEX link_tags_tag ON link_tags(tag);
+CREATE INDEX notes_link_id ON notes(link_id);
+";
+
+/// Export the whole collection as a standalone SQLite database
+///
+/// The database is built next to `out` and moved into place once complete,
+/// so an interrupted export never leaves a half-written snapshot behind.
+pub fn sqlite(store: &Store, out: PathBuf, include_private: bool, output: &Output) -> Result<()> {
+    let links = exported_links(store, None, include_private, output)?;
+
+    let temp_path = out.with_extension("partial");
+    if temp_path.exists() {
+        fs::remove_file(&temp_path)
+            .with_context(|| tf("Failed to remove {}", &[&temp_path.display()]))?;
+    }
+    if let Err(e) = write_sqlite(&temp_path, &links, Utc::now()) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
+    fs::rename(&temp_path, &out).with_context(|| tf("Failed to write {}", &[&out.display()]))?;
+
+    output.success(&tf(
+        "Exported {} link(s) to {}",
+        &[&links.len(), &out.display()],
+    ));
+    Ok(())
+}
+
+/// Create a database at `path` with [`SQLITE_SCHEMA`], filled with the links
+///
+/// Everything runs in one transaction. Links are ordered by creation date,
+/// like the text export.
+pub fn write_sqlite(path: &Path, links: &[Link], exported_at: DateTime<Utc>) -> Result<()> {
+    let mut links: Vec<&Link> = links.iter().collect();
+    links.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+
+    let mut conn =
+        Connection::open(path).with_context(|| tf("Failed to create {}", &[&path.display()]))?;
+    let tx = conn.transaction()?;
+    tx.execute_batch(SQLITE_SCHEMA)
+        .context(t("Failed to create tables"))?;
+    {
+        let mut meta = tx.prepare("INSERT INTO meta VALUES (?1, ?2)")?;
+        for (key, value) in [
+            ("schema_version", SQLITE_SCHEMA_VERSION.to_string()),
+            ("exported_at", exported_at.to_rfc3339()),
+            ("rott_version", env!("CARGO_PKG_VERSION").to_string()),
+        ] {
+            meta.execute(params![key, value])?;
+        }
+
+        let mut insert_link = tx.prepare(
+            "INSERT INTO links VALUES \
+             (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+        )?;
+        let mut insert_author = tx.prepare("INSERT INTO link_authors VALUES (?1, ?2, ?3)")?;
+        let mut insert_tag = tx.prepare("INSERT OR IGNORE INTO link_tags VALUES (?1, ?2)")?;
+        let mut insert_field = tx.prepare("INSERT INTO link_fields VALUES (?1, ?2, ?3)")?;
+        let mut insert_note =
+            tx.prepare("INSERT INTO notes VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)")?;
+        for link in links {
+            let id = link.id.to_string();
+            insert_link
+                .execute(params![
+                    id,
+                    link.url,
+                    link.canonical_url,
+                    link.title,
+                    link.description,
+                    sql_time(link.created_at),
+                    sql_time(link.updated_at),
+                    link.published_at.map(sql_time),
+                    link.opened_at.map(sql_time),
+                    link.paywalled,
+                    link.unreachable,
+                    link.content_changed,
+                    link.content_checked_at.map(sql_time),
+                    link.language,
+                    link.open_count,
+                    link.expires_at.map(sql_time),
+                ])
+                .with_context(|| tf("Failed to export link {}", &[&id]))?;
+            for (position, name) in link.author.iter().enumerate() {
+                insert_author.execute(params![id, position, name])?;
+            }
+            for tag in &link.tags {
+                insert_tag.execute(params![id, tag])?;
+            }
+            for (key, value) in &link.derived {
+                insert_field.execute(params![id, key, value])?;
+            }
+            for (position, (note, depth)) in thread_notes(&link.notes).into_iter().enumerate() {
+                // A reply whose parent is gone is exported as a top-level note
+                let parent_id = note.parent_id.filter(|_| depth > 0).map(|p| p.to_string());
+                insert_note.execute(params![
+                    note.id.to_string(),
+                    id,
+                    parent_id,
+                    position,
+                    note.title,
+                    note.body,
+                    note.pinned,
+                    sql_time(note.created_at),
+                ])?;
+            }
+        }
+    }
+    tx.commit().context(t("Failed to write the database"))?;
+    Ok(())
+}
+
+/// Timestamps are stored as RFC 3339 text
+fn sql_time(time: DateTime<Utc>) -> String {
+    time.to_rfc3339()
+}
+
+fn write_records(writer: &mut impl Write, records: &[TextRecord]) -> io::Result<()> {
+    for record in records {
+        writeln!(
+            writer,
+            "{}\t{}\t{}",
+            record.link_id, record.field, record.text
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rott_core::Note;
+
+    #[test]
+    fn test_text_records_one_line_per_field() {
+        let mut link = Link::new("https://example.com");
+        link.set_title("Example\tSite");
+        link.add_tag("rust");
+        link.add_tag("web");
+        link.add_note(Note::with_title("Summary", "line one\n\n  line two  "));
+
+        let link_id = link.id;
+        let records = text_records(&[link]);
+        let lines: Vec<_> = records.iter().map(|r| (r.field, r.text.as_str())).collect();
+
+        assert!(records.iter().all(|r| r.link_id == link_id));
+        assert_eq!(
+            lines,
+            vec![
+                ("title", "Example Site"),
+                ("url", "https://example.com"),
+                ("tag", "rust"),
+                ("tag", "web"),
+                ("note-title", "Summary"),
+                ("note", "line one"),
+                ("note", "line two"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_text_records_note_urls() {
+        let mut link = Link::new("https://example.com");
+        link.add_note(Note::new("Compare https://other.example.com/post."));
+
+        let records = text_records(&[link]);
+        let urls: Vec<_> = records
+            .iter()
+            .filter(|r| r.field == "note-url")
+            .map(|r| r.text.as_str())
+            .collect();
+        assert_eq!(urls, vec!["https://other.example.com/post"]);
+    }
+
+    #[test]
+    fn test_text_records_thread_replies() {
+        let mut link = Link::new("https://example.com");
+        let note = Note::new("observation");
+        let mut reply = Note::new("follow-up");
+        reply.parent_id = Some(note.id);
+        link.add_note(note);
+        link.add_note(Note::new("unrelated"));
+        link.add_note(reply);
+
+        let records = text_records(&[link]);
+        let notes: Vec<_> = records
+            .iter()
+            .filter(|r| r.field == "note" || r.field == "reply")
+            .map(|r| (r.field, r.text.as_str()))
+            .collect();
+        assert_eq!(
+            notes,
+            vec![
+                ("note", "observation"),
+                ("reply", "follow-up"),
+                ("note", "unrelated")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_text_records_keep_note_order() {
+        let mut link = Link::new("https://example.com");
+        link.add_note(Note::new("older"));
+        let mut summary = Note::new("summary");
+        summary.pinned = true;
+        link.add_note(summary);
+        rott_core::models::sort_notes(&mut link.notes);
+
+        let records = text_records(&[link]);
+        let notes: Vec<_> = records
+            .iter()
+            .filter(|r| r.field == "note")
+            .map(|r| r.text.as_str())
+            .collect();
+        assert_eq!(notes, vec!["summary", "older"]);
+    }
+
+    #[test]
+    fn test_write_sqlite_fills_tables() {
+        let mut link = Link::new("https://example.com/it's");
+        link.set_title("Bob's page");
+        link.set_author(vec!["Bob".to_string(), "Alice".to_string()]);
+        link.add_tag("rust");
+        link.add_note(Note::with_title("Summary", "It's 'quoted'"));
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("export.db");
+        write_sqlite(&path, std::slice::from_ref(&link), Utc::now()).unwrap();
+
+        let conn = Connection::open(&path).unwrap();
+        let (url, title): (String, String) = conn
+            .query_row("SELECT url, title FROM links", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(url, "https://example.com/it's");
+        assert_eq!(title, "Bob's page");
+        let second: String = conn
+            .query_row(
+                "SELECT name FROM link_authors WHERE link_id = ?1 AND position = 1",
+                [link.id.to_string()],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(second, "Alice");
+        let body: String = conn
+            .query_row("SELECT body FROM notes", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(body, "It's 'quoted'");
+        let version: String = conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = 'schema_version'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(version, "1");
+    }
+
+    #[test]
+    fn test_csv_table_quotes_fields() {
+        let mut link = Link::new("https://example.com/?a=1,b=2");
+        link.set_title("Say \"hi\"");
+        link.add_tag("rust");
+        link.add_tag("web");
+        link.add_note(Note::with_title("Summary", "line one\nline two"));
+
+        let csv = csv_table(std::slice::from_ref(&link));
+        let mut lines = csv.split("\r\n");
+        assert_eq!(lines.next(), Some(CSV_COLUMNS.join(",").as_str()));
+        let row = lines.next().unwrap();
+        assert!(row.starts_with(&format!(
+            "{},\"https://example.com/?a=1,b=2\",\"Say \"\"hi\"\"\",,rust;web,",
+            link.id
+        )));
+        assert!(row.ends_with(",\"Summary\nline one\nline two\""));
+        assert_eq!(lines.next(), Some(""));
+    }
+
+    #[test]
+    fn test_markdown_file() {
+        let mut link = Link::new("https://example.com/post");
+        link.set_title("Rust: \"Ownership\" explained!");
+        link.add_tag("rust");
+        link.set_author(vec!["Jane Doe".to_string()]);
+        let note = Note::with_title("Summary", "Borrowing rules.");
+        let mut reply = Note::new("Also lifetimes.");
+        reply.parent_id = Some(note.id);
+        link.add_note(note);
+        link.add_note(reply);
+
+        let name = markdown_file_name(&link);
+        assert_eq!(
+            name,
+            format!("rust-ownership-explained-{}.md", &link.id.to_string()[..8])
+        );
+
+        let md = markdown_file(&link);
+        assert!(md.starts_with(&format!("---\nid: \"{}\"\n", link.id)));
+        assert!(md.contains("title: \"Rust: \\\"Ownership\\\" explained!\"\n"));
+        assert!(md.contains("author:\n  - \"Jane Doe\"\ntags:\n  - \"rust\"\n---\n"));
+        assert!(md.contains("\n## Summary ("));
+        assert!(md.contains("Borrowing rules.\n\n### "));
+        assert!(md.ends_with("Also lifetimes.\n"));
+    }
+
+    #[test]
+    fn test_parse_heads() {
+        assert_eq!(parse_heads("ab12,cd34\n"), vec!["ab12", "cd34"]);
+        assert_eq!(parse_heads(" ab12  cd34 "), vec!["ab12", "cd34"]);
+        assert!(parse_heads("\n").is_empty());
+    }
+
+    #[test]
+    fn test_markdown_file_name_without_title() {
+        let mut link = Link::new("https://example.com");
+        link.set_title("???");
+        assert_eq!(
+            markdown_file_name(&link),
+            format!("{}.md", &link.id.to_string()[..8])
+        );
+    }
+}
@@ -0,0 +1,80 @@
+//! Grep command handler
+//!
+//! Searches the flat text export (see `commands::export`) with a regular
+//! expression and prints matches ripgrep-style, prefixed by link ID.
+
+use anyhow::{Context, Result};
+use regex::RegexBuilder;
+
+use rott_core::{LinkSort, Store};
+
+use super::export::text_records;
+use crate::i18n::{t, tf};
+use crate::output::{Output, OutputFormat};
+
+/// Search titles, URLs, tags, and notes of all links
+///
+/// With `links_only`, only the IDs of matching links are printed (like `rg -l`).
+pub fn grep(
+    store: &Store,
+    pattern: String,
+    ignore_case: bool,
+    links_only: bool,
+    output: &Output,
+) -> Result<()> {
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(ignore_case)
+        .build()
+        .with_context(|| tf("Invalid pattern: {}", &[&pattern]))?;
+
+    let links = store.get_all_links(LinkSort::default())?;
+    let matches: Vec<_> = text_records(&links)
+        .into_iter()
+        .filter(|r| regex.is_match(&r.text))
+        .collect();
+
+    // Records are grouped by link, so consecutive dedup yields unique IDs
+    let mut link_ids: Vec<_> = matches.iter().map(|r| r.link_id).collect();
+    link_ids.dedup();
+
+    match output.format {
+        OutputFormat::Json => {
+            let json: Vec<_> = matches
+                .iter()
+                .map(|r| {
+                    serde_json::json!({
+                        "link_id": r.link_id,
+                        "field": r.field,
+                        "text": r.text,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&json).unwrap());
+        }
+        OutputFormat::Quiet => {
+            for id in &link_ids {
+                println!("{}", id);
+            }
+        }
+        OutputFormat::Human if links_only => {
+            for id in &link_ids {
+                println!("{}", id);
+            }
+        }
+        OutputFormat::Human => {
+            if matches.is_empty() {
+                println!("{}", t("No matches."));
+            }
+            for record in &matches {
+                println!(
+                    "{}:{}:{}",
+                    &record.link_id.to_string()[..8],
+                    record.field,
+                    record.text
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
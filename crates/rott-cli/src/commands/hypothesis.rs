@@ -0,0 +1,91 @@
+//! Hypothes.is command handlers
+
+use anyhow::{Context, Result};
+
+use rott_core::Store;
+
+use super::link::parse_link_id;
+use crate::hypothesis::{annotation_text, new_notes, unpushed_notes, Hypothesis};
+use crate::i18n::{t, tf};
+use crate::output::{Output, OutputFormat};
+
+/// Push a link's notes to Hypothes.is as private page notes
+pub async fn push(store: &Store, id: String, output: &Output) -> Result<()> {
+    let hypothesis = client(store)?;
+    let link_id = parse_link_id(&id, store)?;
+    let link = store
+        .get_link(link_id)?
+        .ok_or_else(|| anyhow::anyhow!(tf("Link not found: {}", &[&id])))?;
+
+    let user = hypothesis.user_id().await?;
+    let existing = hypothesis.annotations(&link.url, &user).await?;
+    let notes = unpushed_notes(&link, &existing);
+
+    let mut pushed = Vec::with_capacity(notes.len());
+    for note in &notes {
+        let annotation = hypothesis
+            .create(&link.url, &user, &annotation_text(note), &link.tags)
+            .await
+            .with_context(|| tf("Failed to push note {}", &[&&note.id.to_string()[..8]]))?;
+        pushed.push(annotation.id);
+    }
+
+    match output.format {
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::json!({
+                "link_id": link.id.to_string(),
+                "pushed": pushed,
+                "skipped": link.notes.len() - notes.len(),
+            })
+        ),
+        _ => output.success(&tf(
+            "Pushed {} note(s) to Hypothes.is ({} already there)",
+            &[&pushed.len(), &(link.notes.len() - notes.len())],
+        )),
+    }
+    Ok(())
+}
+
+/// Add a link's Hypothes.is annotations as notes
+pub async fn pull(store: &mut Store, id: String, output: &Output) -> Result<()> {
+    let hypothesis = client(store)?;
+    let link_id = parse_link_id(&id, store)?;
+    let link = store
+        .get_link(link_id)?
+        .ok_or_else(|| anyhow::anyhow!(tf("Link not found: {}", &[&id])))?;
+
+    let user = hypothesis.user_id().await?;
+    let annotations = hypothesis.annotations(&link.url, &user).await?;
+    let notes = new_notes(&link, &annotations);
+    for note in &notes {
+        store
+            .add_note_to_link(link.id, note)
+            .context(t("Failed to add note to link"))?;
+    }
+
+    match output.format {
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::json!({
+                "link_id": link.id.to_string(),
+                "added": notes.iter().map(|n| n.id.to_string()).collect::<Vec<_>>(),
+                "annotations": annotations.len(),
+            })
+        ),
+        _ => output.success(&tf(
+            "Added {} note(s) from {} Hypothes.is annotation(s)",
+            &[&notes.len(), &annotations.len()],
+        )),
+    }
+    Ok(())
+}
+
+fn client(store: &Store) -> Result<Hypothesis> {
+    Hypothesis::from_config(store.config())?.ok_or_else(|| {
+        anyhow::anyhow!(t(
+            "No Hypothes.is token configured. Create one at https://hypothes.is/account/developer \
+             and set it with `rott config set hypothesis_token <token>`."
+        ))
+    })
+}
@@ -0,0 +1,540 @@
+//! Import command handlers
+//!
+//! Brings links in from external sources such as browser history, browser
+//! bookmark exports, or another person's collection.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use uuid::Uuid;
+
+use rott_core::backup::load_document;
+use rott_core::bookmarks::parse_bookmarks;
+use rott_core::incoming::{incoming_changes, IncomingChange};
+use rott_core::{Link, LinkSort, Store};
+
+use crate::history::{parse_since, read_history, Browser, HistoryEntry};
+use crate::i18n::{t, tf};
+use crate::output::{Output, OutputFormat};
+use crate::preview::{Change, ChangeKind, Preview};
+
+/// Suggest frequently-visited pages from browser history that aren't saved yet
+///
+/// In interactive mode each suggestion is offered for saving in turn.
+/// With `accept_all`, every suggestion is saved without prompting; with
+/// `dry_run`, the links that would be saved are previewed. Pages are checked
+/// like any import (see `Store::plan_import`), and the accepted ones are
+/// saved together as one edit.
+#[allow(clippy::too_many_arguments)]
+pub fn history(
+    store: &mut Store,
+    browser: Browser,
+    profile: Option<PathBuf>,
+    min_visits: u32,
+    since: String,
+    tags: Vec<String>,
+    accept_all: bool,
+    dry_run: bool,
+    output: &Output,
+) -> Result<()> {
+    let window = parse_since(&since)?;
+    let entries = read_history(browser, profile.as_deref(), min_visits, Utc::now() - window)
+        .context(t("Failed to read browser history"))?;
+
+    // Only suggest pages that aren't already saved, blocked, or invalid
+    let candidates: Vec<(HistoryEntry, Link)> = entries
+        .into_iter()
+        .map(|entry| {
+            let link = history_link(&entry, &tags);
+            (entry, link)
+        })
+        .collect();
+    let plan = store.plan_import(candidates.iter().map(|(_, link)| link.clone()).collect())?;
+    let importable: HashSet<Uuid> = plan.added.iter().map(|link| link.id).collect();
+    let suggestions: Vec<(HistoryEntry, Link)> = candidates
+        .into_iter()
+        .filter(|(_, link)| importable.contains(&link.id))
+        .collect();
+
+    if suggestions.is_empty() {
+        output.message(t("No new suggestions from browser history."));
+        return Ok(());
+    }
+
+    if dry_run {
+        let mut preview =
+            Preview::new("Import from browser history").count("link(s)", suggestions.len());
+        for (entry, _) in &suggestions {
+            preview.push(Change::new(
+                ChangeKind::Add,
+                format!(
+                    "{} <{}> ({} visits)",
+                    entry.title.as_deref().unwrap_or(t("(untitled)")),
+                    entry.url,
+                    entry.visits
+                ),
+            ));
+        }
+        return preview.print(output);
+    }
+
+    let interactive = output.should_prompt() && atty::is(atty::Stream::Stdin);
+    if !accept_all && !interactive {
+        let entries: Vec<HistoryEntry> = suggestions.into_iter().map(|(entry, _)| entry).collect();
+        print_suggestions(&entries, output);
+        return Ok(());
+    }
+
+    if !accept_all {
+        println!(
+            "{}",
+            tf(
+                "Found {} frequently-visited page(s) not yet saved.",
+                &[&suggestions.len()]
+            )
+        );
+        println!("{}\n", t("Save each one? [y]es / [N]o / [q]uit"));
+    }
+
+    let mut accepted = Vec::new();
+    for (entry, link) in suggestions {
+        if !accept_all {
+            match prompt_suggestion(&entry)? {
+                Answer::Yes => {}
+                Answer::No => continue,
+                Answer::Quit => break,
+            }
+        }
+        accepted.push(link);
+    }
+
+    // Saved together, so the import is one edit and a bad entry is skipped
+    // rather than stopping the rest
+    let summary = store
+        .import_links(accepted)
+        .context(t("Failed to save links"))?;
+    output.success(&tf(
+        "Imported {} link(s) from history",
+        &[&summary.added.len()],
+    ));
+    for (link, reason) in &summary.rejected {
+        output.message(&tf("Skipped {}: {}", &[&link.url, &reason]));
+    }
+    Ok(())
+}
+
+/// The link to save for a history entry
+fn history_link(entry: &HistoryEntry, tags: &[String]) -> Link {
+    let mut link = Link::new(&entry.url);
+    if let Some(ref title) = entry.title {
+        link.set_title(title.clone());
+    }
+    for tag in tags {
+        link.add_tag(tag.clone());
+    }
+    link
+}
+
+/// Save the bookmarks in a browser's bookmark HTML export as links
+///
+/// Folders become tags, along with any `tags` given. Bookmarks whose URL
+/// is already saved, and ones that are blocked or invalid, are skipped and
+/// reported. With `dry_run`, the links that would be saved are previewed.
+pub fn bookmarks(
+    store: &mut Store,
+    file: PathBuf,
+    tags: Vec<String>,
+    dry_run: bool,
+    output: &Output,
+) -> Result<()> {
+    let html =
+        fs::read_to_string(&file).with_context(|| tf("Failed to read {}", &[&file.display()]))?;
+    let links: Vec<Link> = parse_bookmarks(&html)
+        .iter()
+        .map(|bookmark| {
+            let mut link = bookmark.to_link();
+            for tag in &tags {
+                link.add_tag(tag.clone());
+            }
+            link
+        })
+        .collect();
+
+    if links.is_empty() {
+        output.message(&tf("No bookmarks found in {}", &[&file.display()]));
+        return Ok(());
+    }
+
+    if dry_run {
+        let plan = store.plan_import(links)?;
+        let mut preview = Preview::new("Import bookmarks from {}")
+            .subject(file.display())
+            .count("link(s)", plan.added.len())
+            .count("already saved", plan.duplicates.len())
+            .count("invalid", plan.rejected.len());
+        for link in &plan.added {
+            preview.push(Change::new(ChangeKind::Add, describe_link(link)));
+        }
+        return preview.print(output);
+    }
+
+    let summary = store
+        .import_links(links)
+        .context(t("Failed to save bookmarks"))?;
+    match output.format {
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "added": summary.added.iter().map(|l| serde_json::json!({
+                    "id": l.id,
+                    "title": l.title,
+                    "url": l.url,
+                    "tags": l.tags,
+                })).collect::<Vec<_>>(),
+                "duplicates": summary.duplicates.iter().map(|(l, existing)| serde_json::json!({
+                    "url": l.url,
+                    "existing_id": existing,
+                })).collect::<Vec<_>>(),
+                "rejected": summary.rejected.iter().map(|(l, reason)| serde_json::json!({
+                    "url": l.url,
+                    "reason": reason,
+                })).collect::<Vec<_>>(),
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        OutputFormat::Quiet => {}
+        OutputFormat::Human => {
+            output.success(&tf(
+                "Imported {} link(s) from {}",
+                &[&summary.added.len(), &file.display()],
+            ));
+            if !summary.duplicates.is_empty() {
+                println!(
+                    "{}",
+                    tf(
+                        "Skipped {} already saved link(s)",
+                        &[&summary.duplicates.len()]
+                    )
+                );
+            }
+            if !summary.rejected.is_empty() {
+                println!(
+                    "{}",
+                    tf("Skipped {} invalid link(s):", &[&summary.rejected.len()])
+                );
+                for (link, reason) in &summary.rejected {
+                    println!("  {} ({})", link.url, reason);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// "Title <url> (tags: a, b)"
+fn describe_link(link: &Link) -> String {
+    if link.tags.is_empty() {
+        format!("{} <{}>", link.title, link.url)
+    } else {
+        format!(
+            "{} <{}> (tags: {})",
+            link.title,
+            link.url,
+            link.tags.join(", ")
+        )
+    }
+}
+
+/// Merge links and notes from someone else's document or backup
+///
+/// Incoming items are listed (new links, then tags and notes for links
+/// already here) and, interactively, accepted or rejected one at a time.
+/// Nothing is saved until the review is over; then every accepted item is
+/// saved together. Without a terminal, `accept_all` is needed to save.
+pub fn document(
+    store: &mut Store,
+    file: PathBuf,
+    tags: Vec<String>,
+    accept_all: bool,
+    dry_run: bool,
+    output: &Output,
+) -> Result<()> {
+    let bytes = fs::read(&file).with_context(|| tf("Failed to read {}", &[&file.display()]))?;
+    let incoming: Vec<Link> = load_document(&bytes)?
+        .get_all_links()?
+        .into_iter()
+        .filter(|l| tags.is_empty() || tags.iter().any(|t| l.has_tag(t)))
+        .collect();
+
+    let mut changes = incoming_changes(&store.get_all_links(LinkSort::default())?, &incoming);
+    // Grouped: new links first, then additions to existing ones
+    changes.sort_by_key(|c| matches!(c, IncomingChange::Changed { .. }));
+
+    if changes.is_empty() {
+        output.message(&tf("Nothing new in {}", &[&file.display()]));
+        return Ok(());
+    }
+
+    if dry_run {
+        let added = changes
+            .iter()
+            .filter(|c| matches!(c, IncomingChange::Added { .. }))
+            .count();
+        let mut preview = Preview::new("Merge {}")
+            .subject(file.display())
+            .count("added", added)
+            .count("changed", changes.len() - added);
+        for change in &changes {
+            let (kind, link_id) = match change {
+                IncomingChange::Added { link } => (ChangeKind::Add, link.id),
+                IncomingChange::Changed { link_id, .. } => (ChangeKind::Change, *link_id),
+            };
+            preview.push(Change::new(kind, describe_incoming(change)).link(link_id));
+        }
+        return preview.print(output);
+    }
+
+    let interactive = output.should_prompt() && atty::is(atty::Stream::Stdin);
+    if !accept_all && !interactive {
+        print_incoming(&changes, output);
+        if output.format == OutputFormat::Human {
+            output.message(&format!(
+                "\n{}",
+                t("Run in a terminal to review each item, or pass --yes to accept all.")
+            ));
+        }
+        return Ok(());
+    }
+
+    let accepted: Vec<IncomingChange> = if accept_all {
+        changes
+    } else {
+        print_incoming(&changes, output);
+        println!(
+            "\n{}\n",
+            t("Review each item: [y]es / [N]o / [a]ccept the rest / [q]uit (reject the rest)")
+        );
+        let mut accepted = Vec::new();
+        let mut remaining = changes.into_iter();
+        while let Some(change) = remaining.next() {
+            match prompt_incoming(&change)? {
+                Review::Accept => accepted.push(change),
+                Review::Reject => {}
+                Review::AcceptRest => {
+                    accepted.push(change);
+                    accepted.extend(remaining.by_ref());
+                }
+                Review::Stop => break,
+            }
+        }
+        accepted
+    };
+
+    if accepted.is_empty() {
+        output.message(t("Nothing accepted; no changes made."));
+        return Ok(());
+    }
+    let summary = store
+        .accept_incoming(&accepted)
+        .context(t("Failed to save incoming links"))?;
+    output.success(&tf(
+        "Merged {} item(s) from {}",
+        &[&summary.applied, &file.display()],
+    ));
+    for (link, reason) in &summary.rejected {
+        output.message(&tf("Skipped {}: {}", &[&link.url, &reason]));
+    }
+    Ok(())
+}
+
+/// List incoming items, grouped into added and changed links
+fn print_incoming(changes: &[IncomingChange], output: &Output) {
+    match output.format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(changes).unwrap());
+        }
+        OutputFormat::Quiet => {}
+        OutputFormat::Human => {
+            let added = changes
+                .iter()
+                .filter(|c| matches!(c, IncomingChange::Added { .. }))
+                .count();
+            if added > 0 {
+                println!("{}", tf("Added ({}):", &[&added]));
+            }
+            for (index, change) in changes.iter().enumerate() {
+                if index == added {
+                    println!("{}", tf("Changed ({}):", &[&(changes.len() - added)]));
+                }
+                println!("  {} {}", marker(change), describe_incoming(change));
+            }
+        }
+    }
+}
+
+/// `+` for a new link, `~` for additions to an existing one
+fn marker(change: &IncomingChange) -> char {
+    match change {
+        IncomingChange::Added { .. } => '+',
+        IncomingChange::Changed { .. } => '~',
+    }
+}
+
+/// One-line summary of an incoming item
+fn describe_incoming(change: &IncomingChange) -> String {
+    match change {
+        IncomingChange::Added { link } => {
+            let mut details = Vec::new();
+            if !link.tags.is_empty() {
+                details.push(tf("tags: {}", &[&link.tags.join(", ")]));
+            }
+            if !link.notes.is_empty() {
+                details.push(tf("{} note(s)", &[&link.notes.len()]));
+            }
+            format!(
+                "{} <{}>{}",
+                link.title,
+                link.url,
+                if details.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({})", details.join(", "))
+                }
+            )
+        }
+        IncomingChange::Changed {
+            link_id,
+            title,
+            tags,
+            notes,
+        } => {
+            let mut details = Vec::new();
+            if !tags.is_empty() {
+                details.push(tf("+tags: {}", &[&tags.join(", ")]));
+            }
+            if !notes.is_empty() {
+                details.push(tf("+{} note(s)", &[&notes.len()]));
+            }
+            format!(
+                "{} ({}): {}",
+                title,
+                &link_id.to_string()[..8],
+                details.join(", ")
+            )
+        }
+    }
+}
+
+/// Answer when reviewing an incoming item
+enum Review {
+    Accept,
+    Reject,
+    AcceptRest,
+    Stop,
+}
+
+/// Ask whether to accept a single incoming item, showing its notes
+fn prompt_incoming(change: &IncomingChange) -> Result<Review> {
+    use std::io::{self, Write};
+
+    println!("{} {}", marker(change), describe_incoming(change));
+    let notes = match change {
+        IncomingChange::Added { link } => &link.notes,
+        IncomingChange::Changed { notes, .. } => notes,
+    };
+    for note in notes {
+        for line in note.body.lines() {
+            println!("    | {}", line);
+        }
+    }
+    print!("{} ", t("Accept? [y/N/a/q]"));
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    Ok(match input.trim().to_lowercase().as_str() {
+        "y" | "yes" => Review::Accept,
+        "a" | "all" => Review::AcceptRest,
+        "q" | "quit" => Review::Stop,
+        _ => Review::Reject,
+    })
+}
+
+/// Print suggestions without prompting (JSON, quiet, or non-interactive)
+fn print_suggestions(suggestions: &[HistoryEntry], output: &Output) {
+    match output.format {
+        OutputFormat::Json => {
+            let json: Vec<_> = suggestions
+                .iter()
+                .map(|e| {
+                    serde_json::json!({
+                        "url": e.url,
+                        "title": e.title,
+                        "visits": e.visits,
+                        "last_visit": e.last_visit,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&json).unwrap());
+        }
+        OutputFormat::Quiet => {
+            for entry in suggestions {
+                println!("{}", entry.url);
+            }
+        }
+        OutputFormat::Human => {
+            for entry in suggestions {
+                println!(
+                    "{}",
+                    tf(
+                        "{} visits | {} | {}",
+                        &[
+                            &format!("{:>4}", entry.visits),
+                            &entry.title.as_deref().unwrap_or(t("(untitled)")),
+                            &entry.url
+                        ]
+                    )
+                );
+            }
+        }
+    }
+}
+
+/// Answer to a suggestion prompt
+enum Answer {
+    Yes,
+    No,
+    Quit,
+}
+
+/// Ask whether to save a single suggestion
+fn prompt_suggestion(entry: &HistoryEntry) -> Result<Answer> {
+    use std::io::{self, Write};
+
+    println!(
+        "{}",
+        tf(
+            "{} ({} visits, last {})",
+            &[
+                &entry.title.as_deref().unwrap_or(t("(untitled)")),
+                &entry.visits,
+                &entry.last_visit.format("%Y-%m-%d")
+            ]
+        )
+    );
+    println!("  {}", entry.url);
+    print!("{} ", t("Save? [y/N/q]"));
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    Ok(match input.trim().to_lowercase().as_str() {
+        "y" | "yes" => Answer::Yes,
+        "q" | "quit" => Answer::Quit,
+        _ => Answer::No,
+    })
+}
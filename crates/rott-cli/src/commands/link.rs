@@ -1,25 +1,78 @@
 //! Link command handlers
 
 use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, Utc};
 use uuid::Uuid;
 
-use rott_core::{Link, Store};
+use rott_core::attachments::AttachmentStore;
+use rott_core::fetch_failures::FetchFailures;
+use rott_core::health::{needs_attention, Health};
+use rott_core::language::normalize_language;
+use rott_core::openers::Openers;
+use rott_core::urls::title_from_url;
+use rott_core::{Fingerprint, Link, LinkSort, Store};
 
-use crate::editor::confirm;
-use crate::metadata::fetch_metadata;
-use crate::output::Output;
+use crate::bulk;
+use crate::content;
+use crate::editor::{confirm, edit_text};
+use crate::hypothesis;
+use crate::i18n::{t, tf};
+use crate::metadata::{
+    fetch_metadata, fetch_metadata_cached, record_failures, MetadataLimits, UrlMetadata,
+    FAILED_DESCRIPTION, FAILED_PAGE, FAILED_TITLE,
+};
+use crate::output::{Output, OutputFormat};
+use crate::preview::{Change, ChangeKind, Preview};
+use crate::screenshot;
 
 /// Create a new link
 pub async fn create(
     store: &mut Store,
     url: String,
     tags: Vec<String>,
+    via: Option<String>,
+    source: Option<String>,
+    expires: Option<NaiveDate>,
     output: &Output,
 ) -> Result<()> {
-    // Fetch metadata from URL
-    let metadata = fetch_metadata(&url).await;
+    let mut link = new_link(store, &url, tags).await?;
+    if via.is_some() || source.is_some() {
+        link.set_source(via, source);
+    }
+    if let Some(date) = expires {
+        link.set_expires_at(Some(end_of_day(date)));
+    }
+
+    store.add_link(&link).context(t("Failed to create link"))?;
+
+    output.success(&tf("Created link: {}", &[&link.id]));
+    if !output.is_quiet() {
+        output.print_link(&link);
+    }
+    if let Some(Err(e)) = screenshot::snapshot_on_save(store, link.id).await {
+        output.message(&tf("Screenshot failed: {}", &[&format!("{:#}", e)]));
+    }
+    if let Some(Err(e)) = content::archive_on_save(store, link.id).await {
+        output.message(&tf(
+            "Archiving the page text failed: {}",
+            &[&format!("{:#}", e)],
+        ));
+    }
+
+    Ok(())
+}
+
+/// Build a new link with fetched metadata, without saving it
+pub(crate) async fn new_link(store: &Store, url: &str, tags: Vec<String>) -> Result<Link> {
+    let mut link = Link::new(url);
 
-    let mut link = Link::new(&url);
+    // Enforce domain rules and URL policy before making any network requests
+    store.apply_domain_rules(&mut link)?;
+    store.validator().check_url(url)?;
+
+    // Fetch metadata from URL
+    let metadata = fetch_metadata_cached(url, store.config()).await;
+    record_failures(store.config(), &link, &metadata);
 
     // Apply fetched metadata
     if let Some(title) = metadata.title {
@@ -31,42 +84,204 @@ pub async fn create(
     if !metadata.author.is_empty() {
         link.set_author(metadata.author);
     }
+    if metadata.published_at.is_some() {
+        link.set_published_at(metadata.published_at);
+    }
+    if metadata.canonical_url.is_some() {
+        link.set_canonical_url(metadata.canonical_url);
+    }
+    link.paywalled = metadata.paywalled;
+    link.language = metadata.language;
+    if let Some(fingerprint) = metadata.content_hash {
+        link.set_content_hash(Some(fingerprint.to_string()));
+    }
 
     // Add tags
     for tag in tags {
         link.add_tag(tag);
     }
 
-    store.add_link(&link).context("Failed to create link")?;
-
-    output.success(&format!("Created link: {}", link.id));
-    if !output.is_quiet() {
-        output.print_link(&link);
+    // Existing Hypothes.is annotations on the page become notes
+    for note in hypothesis::fetch_notes(store.config(), &link).await {
+        link.add_note(note);
     }
 
-    Ok(())
+    Ok(link)
+}
+
+/// Which links `list` shows (mirrors the TUI's filters)
+#[derive(Debug, Default, Clone)]
+pub struct ListFilter {
+    /// Only links with this tag
+    pub tag: Option<String>,
+    /// Only favorite links (by the configured favorite tags)
+    pub favorites: bool,
+    /// Only links without tags
+    pub untagged: bool,
+    /// Most recently updated first
+    pub recent: bool,
+    /// Most recently published first
+    pub by_published: bool,
+    /// Only links never opened (on any synced device)
+    pub unread: bool,
+    /// Only links by this author
+    pub author: Option<String>,
+    /// Only links in this language (code or name)
+    pub lang: Option<String>,
+    /// Only links published on or after this date
+    pub published_after: Option<NaiveDate>,
+    /// Only links published before this date
+    pub published_before: Option<NaiveDate>,
+    /// Only links whose expiry date has passed
+    pub expired: bool,
+    /// Only links that need attention, worst health first
+    pub stale: bool,
+    /// Include archived links
+    pub archived: bool,
 }
 
-/// List all links, optionally filtered by tag
-pub fn list(store: &Store, tag: Option<String>, output: &Output) -> Result<()> {
-    let links = match tag {
-        Some(ref t) => store.get_links_by_tag(t)?,
-        None => store.get_all_links()?,
+/// List all links, optionally filtered like the TUI
+pub fn list(store: &Store, filter: ListFilter, output: &Output) -> Result<()> {
+    let config = store.config();
+    if filter.favorites && config.favorite_tags.is_empty() {
+        bail!(t(
+            "No favorite tags configured. Set them with `rott config set favorite_tags <tag,...>`."
+        ));
+    }
+    let tag = filter.tag;
+    let sort = config.default_sort;
+    let mut links = match tag {
+        Some(ref t) => store.get_links_by_tag(t, sort)?,
+        None if filter.favorites => {
+            let mut links = store.favorite_links()?;
+            sort.sort(&mut links);
+            links
+        }
+        None => store.get_all_links(sort)?,
     };
+    if filter.archived {
+        let archived = archived_links(store, None, output)?;
+        links.extend(archived.into_iter().filter(|l| {
+            tag.as_deref().is_none_or(|t| l.has_tag(t))
+                && (!filter.favorites || config.is_favorite(l))
+        }));
+        sort.sort(&mut links);
+    }
+
+    if filter.untagged {
+        links.retain(|l| l.tags.is_empty());
+    }
+    if filter.unread {
+        links.retain(|l| l.opened_at.is_none());
+    }
+    if filter.expired {
+        let now = Utc::now();
+        links.retain(|l| l.is_expired(now));
+    }
+    if let Some(ref author) = filter.author {
+        let author = author.trim().to_lowercase();
+        links.retain(|l| l.author.iter().any(|a| a.trim().to_lowercase() == author));
+    }
+    if let Some(ref lang) = filter.lang {
+        let Some(language) = normalize_language(lang) else {
+            bail!(tf(
+                "Unknown language '{}'. Use a code like 'de' or a name like 'german'.",
+                &[&lang]
+            ));
+        };
+        links.retain(|l| l.language.as_deref() == Some(language.as_str()));
+    }
+    if let Some(after) = filter.published_after {
+        links.retain(|l| l.published_at.is_some_and(|p| p.date_naive() >= after));
+    }
+    if let Some(before) = filter.published_before {
+        links.retain(|l| l.published_at.is_some_and(|p| p.date_naive() < before));
+    }
+    if filter.recent {
+        links.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    }
+    if filter.by_published {
+        // Newest first; None sorts before Some, so reversing puts undated links last
+        links.sort_by(|a, b| b.published_at.cmp(&a.published_at));
+    }
+    if filter.stale {
+        let opens_tracked = store.config().record_opens;
+        output.print_stale_links(&needs_attention(links, chrono::Utc::now(), opens_tracked));
+        return Ok(());
+    }
 
     output.print_links(&links);
     Ok(())
 }
 
+/// Open a link with its configured opener, or in the default browser
+pub fn open(store: &mut Store, id: String, output: &Output) -> Result<()> {
+    let uuid = parse_link_id(&id, store)?;
+
+    let link = store
+        .get_link(uuid)?
+        .ok_or_else(|| anyhow::anyhow!(tf("Link not found: {}", &[&id])))?;
+
+    Openers::from_config(store.config()).open(&link)?;
+    store.record_open(link.id)?;
+    output.success(&tf("Opened '{}'", &[&link.title]));
+    Ok(())
+}
+
+/// Screenshot a link's page and attach the image
+pub async fn snapshot(store: &mut Store, id: String, output: &Output) -> Result<()> {
+    let uuid = parse_link_id(&id, store)?;
+    output.message(t("Capturing screenshot..."));
+    let path = screenshot::snapshot(store, uuid).await?;
+
+    if output.is_json() {
+        println!(
+            "{}",
+            serde_json::json!({
+                "id": uuid.to_string(),
+                "attachment": screenshot::SCREENSHOT_ATTACHMENT,
+                "path": path,
+            })
+        );
+    } else {
+        output.success(&tf("Saved screenshot: {}", &[&path.display()]));
+    }
+    Ok(())
+}
+
+/// Fetch a link's page and keep a copy of its text
+pub async fn archive_content(store: &mut Store, id: String, output: &Output) -> Result<()> {
+    let uuid = parse_link_id(&id, store)?;
+    output.message(t("Fetching page..."));
+    let path = content::archive(store, uuid).await?;
+
+    if output.is_json() {
+        println!(
+            "{}",
+            serde_json::json!({
+                "id": uuid.to_string(),
+                "attachment": content::CONTENT_ATTACHMENT,
+                "path": path,
+            })
+        );
+    } else {
+        output.success(&tf("Archived page text: {}", &[&path.display()]));
+    }
+    Ok(())
+}
+
 /// Show a single link
 pub fn show(store: &Store, id: String, output: &Output) -> Result<()> {
     let uuid = parse_link_id(&id, store)?;
 
     let link = store
         .get_link(uuid)?
-        .ok_or_else(|| anyhow::anyhow!("Link not found: {}", id))?;
+        .ok_or_else(|| anyhow::anyhow!(tf("Link not found: {}", &[&id])))?;
 
-    output.print_link(&link);
+    let health = Health::of(&link, chrono::Utc::now(), store.config().record_opens);
+    let attachments = AttachmentStore::from_config(store.config());
+    let screenshot = screenshot::screenshot_path(&attachments, &link);
+    output.print_link_with_health(&link, Some(&health), screenshot.as_deref());
     Ok(())
 }
 
@@ -76,26 +291,35 @@ pub fn edit(
     id: String,
     add_tags: Vec<String>,
     remove_tags: Vec<String>,
+    expires: Option<NaiveDate>,
+    no_expires: bool,
     output: &Output,
 ) -> Result<()> {
     let uuid = parse_link_id(&id, store)?;
 
     let mut link = store
         .get_link(uuid)?
-        .ok_or_else(|| anyhow::anyhow!("Link not found: {}", id))?;
+        .ok_or_else(|| anyhow::anyhow!(tf("Link not found: {}", &[&id])))?;
 
-    // If tag flags provided, do non-interactive editing
-    if !add_tags.is_empty() || !remove_tags.is_empty() {
+    // If tag or expiry flags provided, do non-interactive editing
+    if !add_tags.is_empty() || !remove_tags.is_empty() || expires.is_some() || no_expires {
         for tag in add_tags {
             link.add_tag(tag);
         }
         for tag in remove_tags {
             link.remove_tag(&tag);
         }
+        if let Some(date) = expires {
+            link.set_expires_at(Some(end_of_day(date)));
+        } else if no_expires {
+            link.set_expires_at(None);
+        }
 
-        store.update_link(&link).context("Failed to update link")?;
+        store
+            .update_link(&link)
+            .context(t("Failed to update link"))?;
 
-        output.success("Link updated");
+        output.success(t("Link updated"));
         if !output.is_quiet() {
             output.print_link(&link);
         }
@@ -104,18 +328,21 @@ pub fn edit(
     }
 
     // Interactive editing
-    println!("Editing link: {}", link.id);
-    println!("Press Enter to keep current value, or type new value.\n");
+    println!("{}", tf("Editing link: {}", &[&link.id]));
+    println!(
+        "{}\n",
+        t("Press Enter to keep current value, or type new value.")
+    );
 
     // Title
     let current_title = &link.title;
-    if let Some(new_title) = prompt_with_default("Title", current_title)? {
+    if let Some(new_title) = prompt_with_default(t("Title"), current_title)? {
         link.set_title(new_title);
     }
 
     // Description
     let current_desc = link.description.as_deref().unwrap_or("");
-    if let Some(new_desc) = prompt_with_default("Description", current_desc)? {
+    if let Some(new_desc) = prompt_with_default(t("Description"), current_desc)? {
         link.set_description(if new_desc.is_empty() {
             None
         } else {
@@ -123,17 +350,32 @@ pub fn edit(
         });
     }
 
+    // Who recommended it and where it was found
+    let current_via = link.via.clone().unwrap_or_default();
+    let current_source = link.source_context.clone().unwrap_or_default();
+    let new_via = prompt_with_default(t("Via"), &current_via)?;
+    let new_source = prompt_with_default(t("Source"), &current_source)?;
+    if new_via.is_some() || new_source.is_some() {
+        link.set_source(
+            new_via.or(link.via.clone()),
+            new_source.or(link.source_context.clone()),
+        );
+    }
+
     // Tags
     let current_tags = link.tags.join(", ");
     println!(
-        "Current tags: {}",
-        if current_tags.is_empty() {
-            "(none)"
-        } else {
-            &current_tags
-        }
+        "{}",
+        tf(
+            "Current tags: {}",
+            &[&(if current_tags.is_empty() {
+                t("(none)")
+            } else {
+                &current_tags
+            })]
+        )
     );
-    if let Some(new_tags) = prompt_optional("New tags (comma-separated)")? {
+    if let Some(new_tags) = prompt_optional(t("New tags (comma-separated)"))? {
         let tags: Vec<String> = new_tags
             .split(',')
             .map(|s| s.trim().to_string())
@@ -142,72 +384,726 @@ pub fn edit(
         link.set_tags(tags);
     }
 
-    store.update_link(&link).context("Failed to update link")?;
+    store
+        .update_link(&link)
+        .context(t("Failed to update link"))?;
 
-    output.success("Link updated");
+    output.success(t("Link updated"));
     output.print_link(&link);
 
     Ok(())
 }
 
+/// When a link given `--expires DATE` expires: the end of that day, local time
+fn end_of_day(date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.succ_opt().unwrap_or(date).and_time(NaiveTime::MIN);
+    midnight
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(|| midnight.and_utc())
+}
+
+/// Edit several links at once as a table in $EDITOR
+///
+/// Edits every link, or only those tagged `tag`. If any row has a problem,
+/// the errors are listed and the table can be reopened (with the errors
+/// marked) to fix them; nothing is saved until every row is valid.
+pub fn bulk_edit(store: &mut Store, tag: Option<String>, output: &Output) -> Result<()> {
+    let all_links = store.get_all_links(LinkSort::default())?;
+    let mut links = match tag {
+        Some(ref tag) => store.get_links_by_tag(tag, LinkSort::default())?,
+        None => all_links.clone(),
+    };
+    if links.is_empty() {
+        output.message(t("No links to edit"));
+        return Ok(());
+    }
+    links.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+
+    let validator = store.validator();
+    let mut table = bulk::render(&links);
+    let changes = loop {
+        let edited = edit_text(&table)?;
+        match bulk::parse(&edited, &links, &all_links, &validator, chrono::Utc::now()) {
+            Ok(changes) => break changes,
+            Err(errors) => {
+                eprintln!("{}", tf("{} row(s) have problems:", &[&errors.len()]));
+                for error in &errors {
+                    eprintln!("  {}", error);
+                }
+                if !confirm(t("Edit again?"))? {
+                    bail!(t("No changes saved"));
+                }
+                table = bulk::annotate(&edited, &errors);
+            }
+        }
+    };
+
+    if changes.is_empty() {
+        output.message(t("No changes"));
+        return Ok(());
+    }
+    store
+        .update_links(&changes.updates, &changes.deletes)
+        .context(t("Failed to save changes"))?;
+
+    output.success(&tf(
+        "Updated {} link(s), deleted {}",
+        &[&changes.updates.len(), &changes.deletes.len()],
+    ));
+    Ok(())
+}
+
 /// Delete a link
 pub fn delete(store: &mut Store, id: String, output: &Output) -> Result<()> {
     let uuid = parse_link_id(&id, store)?;
 
     let link = store
         .get_link(uuid)?
-        .ok_or_else(|| anyhow::anyhow!("Link not found: {}", id))?;
+        .ok_or_else(|| anyhow::anyhow!(tf("Link not found: {}", &[&id])))?;
 
     // Confirm deletion
     if output.should_prompt() {
         println!(
-            "Delete link: {} - {}",
-            &link.id.to_string()[..8],
-            link.title
+            "{}",
+            tf(
+                "Delete link: {} - {}",
+                &[&&link.id.to_string()[..8], &link.title]
+            )
         );
-        if !confirm("Are you sure?")? {
-            println!("Cancelled.");
+        if !confirm(t("Are you sure?"))? {
+            println!("{}", t("Cancelled."));
             return Ok(());
         }
     }
 
-    store.delete_link(uuid).context("Failed to delete link")?;
+    store
+        .delete_link(uuid)
+        .context(t("Failed to delete link"))?;
+
+    output.success(&tf("Deleted link: {}", &[&uuid]));
+
+    Ok(())
+}
+
+/// Merge a duplicate link into another
+pub fn merge(store: &mut Store, keep: String, duplicate: String, output: &Output) -> Result<()> {
+    let keep = parse_link_id(&keep, store)?;
+    let duplicate = parse_link_id(&duplicate, store)?;
+    let merge = store.merge_links(keep, duplicate)?;
+
+    if output.is_json() {
+        println!(
+            "{}",
+            serde_json::json!({
+                "kept": merge.merged.id.to_string(),
+                "deleted": merge.duplicate.id.to_string(),
+                "notes_moved": merge.duplicate.notes.len(),
+            })
+        );
+    } else {
+        output.success(&tf(
+            "Merged '{}' into '{}' ({} note(s) moved)",
+            &[
+                &merge.duplicate.title,
+                &merge.merged.title,
+                &merge.duplicate.notes.len(),
+            ],
+        ));
+        output.message(t("Undo with: rott link unmerge"));
+    }
+    Ok(())
+}
+
+/// Undo the last link merge
+pub fn unmerge(store: &mut Store, output: &Output) -> Result<()> {
+    match store.undo_merge()? {
+        Some(edit) => output.success(&tf("Undid {}", &[&edit.label])),
+        None => output.message(t("No merge to undo (the last edit isn't a merge)")),
+    }
+    Ok(())
+}
+
+/// Find links saved more than once and merge each group into its oldest link
+///
+/// Asks before each group unless `yes`. With `dry_run`, previews the merges
+/// without asking or saving.
+pub fn dedupe(store: &mut Store, yes: bool, dry_run: bool, output: &Output) -> Result<()> {
+    let groups = store.find_duplicates()?;
+    if !dry_run && !yes && !output.should_prompt() {
+        bail!(t(
+            "Merging without prompting needs --yes (or use --dry-run to list duplicates)"
+        ));
+    }
+
+    if groups.is_empty() {
+        output.success(t("No duplicate links found"));
+        return Ok(());
+    }
+
+    if dry_run {
+        let duplicates = groups.iter().map(|g| g.duplicates.len()).sum();
+        let mut preview = Preview::new("Merge duplicate links")
+            .count("group(s)", groups.len())
+            .count("duplicates", duplicates);
+        for group in &groups {
+            for duplicate in &group.duplicates {
+                let summary = tf(
+                    "{} ({}) into {} ({})",
+                    &[
+                        &duplicate.url,
+                        &&duplicate.id.to_string()[..8],
+                        &group.keep.url,
+                        &&group.keep.id.to_string()[..8],
+                    ],
+                );
+                preview.push(Change::new(ChangeKind::Delete, summary).link(duplicate.id));
+            }
+        }
+        return preview.print(output);
+    }
+
+    let mut merged_groups = 0;
+    let mut merged_links = 0;
+    for group in &groups {
+        if output.format == OutputFormat::Human {
+            println!("\n{}", group.url);
+            println!(
+                "  {}",
+                tf(
+                    "keep: {} ({}) {}",
+                    &[
+                        &group.keep.title,
+                        &&group.keep.id.to_string()[..8],
+                        &group.keep.url
+                    ]
+                )
+            );
+            for duplicate in &group.duplicates {
+                println!(
+                    "  {}",
+                    tf(
+                        "copy: {} ({}) {}, {} tag(s), {} note(s)",
+                        &[
+                            &duplicate.title,
+                            &&duplicate.id.to_string()[..8],
+                            &duplicate.url,
+                            &duplicate.tags.len(),
+                            &duplicate.notes.len()
+                        ]
+                    )
+                );
+            }
+        }
+        if !yes && !confirm(t("Merge these links?"))? {
+            continue;
+        }
+        for duplicate in &group.duplicates {
+            store.merge_links(group.keep.id, duplicate.id)?;
+            merged_links += 1;
+        }
+        merged_groups += 1;
+    }
+
+    output.success(&tf(
+        "Merged {} duplicate link(s) in {} group(s)",
+        &[&merged_links, &merged_groups],
+    ));
+    if merged_links > 0 {
+        output.message(t("Undo the last merge with: rott link unmerge"));
+    }
+    Ok(())
+}
 
-    output.success(&format!("Deleted link: {}", uuid));
+/// Undo the last edit
+pub fn undo(store: &mut Store, output: &Output) -> Result<()> {
+    match store.undo()? {
+        Some(edit) => {
+            output.success(&tf("Undid {}", &[&edit.label]));
+            output.message(t("Redo with: rott redo"));
+        }
+        None => output.message(t("Nothing to undo")),
+    }
+    Ok(())
+}
 
+/// Redo the last undone edit
+pub fn redo(store: &mut Store, output: &Output) -> Result<()> {
+    match store.redo()? {
+        Some(edit) => output.success(&tf("Redid {}", &[&edit.label])),
+        None => output.message(t("Nothing to redo")),
+    }
     Ok(())
 }
 
 /// Search links
-pub fn search(store: &Store, query: String, output: &Output) -> Result<()> {
-    let links = store.search_links(&query)?;
+pub fn search(store: &Store, query: String, archived: bool, output: &Output) -> Result<()> {
+    let mut links = store.search_links(&query)?;
+    if archived {
+        links.extend(archived_links(store, Some(&query), output)?);
+    }
     output.print_links(&links);
     Ok(())
 }
 
+/// Archived links on this device, all or matching a search
+///
+/// Archives recorded but not synced here yet are named in a warning, since
+/// their links are missing from the results.
+fn archived_links(store: &Store, query: Option<&str>, output: &Output) -> Result<Vec<Link>> {
+    let missing = store.missing_archives()?;
+    if !missing.is_empty() && !output.is_quiet() {
+        let years: Vec<String> = missing.iter().map(i32::to_string).collect();
+        eprintln!(
+            "{}",
+            tf(
+                "⚠ Archives for {} aren't on this device yet; run `rott sync` to include them",
+                &[&years.join(", ")]
+            )
+        );
+    }
+    match query {
+        Some(query) => store.search_archived(query),
+        None => store.archived_links(),
+    }
+}
+
+/// Result of checking saved links for changed content
+pub struct ContentCheck {
+    /// Links checked
+    pub total: usize,
+    /// Links whose content changed, with the fingerprint distance
+    pub changed: Vec<(Link, u32)>,
+    /// Links given their first fingerprint
+    pub baselined: usize,
+    /// Links whose page couldn't be fetched
+    pub unreachable: usize,
+    /// Links with a placeholder title given a better one (see [`retitle`])
+    pub retitled: usize,
+}
+
+/// Check saved links for changes
+///
+/// With `content_changes`, every link's page is fetched and its content
+/// fingerprint compared with the one recorded when it was saved. Links whose
+/// content changed significantly are flagged. Links saved before fingerprints
+/// were recorded get a baseline on their first check. With `accept`, changed
+/// pages have their current content recorded as the new baseline instead.
+/// Links whose page can't be fetched are marked unreachable until a later
+/// check succeeds.
+pub async fn check(
+    store: &mut Store,
+    content_changes: bool,
+    accept: bool,
+    output: &Output,
+) -> Result<()> {
+    if !content_changes {
+        bail!(t(
+            "Nothing to check. Use --content-changes to detect changed pages."
+        ));
+    }
+
+    let ContentCheck {
+        total,
+        changed,
+        baselined,
+        unreachable,
+        retitled,
+    } = check_content(store, accept).await?;
+
+    match output.format {
+        OutputFormat::Json => {
+            let json: Vec<_> = changed
+                .iter()
+                .map(|(link, distance)| {
+                    serde_json::json!({
+                        "id": link.id,
+                        "title": link.title,
+                        "url": link.url,
+                        "distance": distance,
+                        "accepted": accept,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&json).unwrap());
+        }
+        OutputFormat::Quiet => {
+            for (link, _) in &changed {
+                println!("{}", link.id);
+            }
+        }
+        OutputFormat::Human => {
+            for (link, distance) in &changed {
+                println!(
+                    "{}",
+                    tf(
+                        "{} | {} | {} ({} bits)",
+                        &[
+                            &&link.id.to_string()[..8],
+                            &link.title,
+                            &link.url,
+                            &distance
+                        ]
+                    )
+                );
+            }
+            if !changed.is_empty() {
+                println!();
+            }
+            let verb = if accept { t("accepted") } else { t("changed") };
+            println!(
+                "{}",
+                tf(
+                    "Checked {} link(s): {} {}, {} new baseline(s), {} unreachable, {} retitled",
+                    &[
+                        &total,
+                        &changed.len(),
+                        &verb,
+                        &baselined,
+                        &unreachable,
+                        &retitled
+                    ]
+                )
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Fetch every saved link and compare its content fingerprint
+///
+/// Shared by `rott link check --content-changes` and the scheduled link
+/// check job. The checked links are saved together, once every page has
+/// been fetched, and undo as one edit.
+pub async fn check_content(store: &mut Store, accept: bool) -> Result<ContentCheck> {
+    let links = store.get_all_links(LinkSort::default())?;
+    let total = links.len();
+    let mut updates = Vec::new();
+    let mut changed = Vec::new();
+    let mut baselined = 0;
+    let mut unreachable = 0;
+    let mut retitled = 0;
+
+    // Always fetched fresh: the cache would hide the very changes being checked for
+    let limits = MetadataLimits::from_config(store.config());
+    for mut link in links {
+        let metadata = fetch_metadata(&link.url, &limits).await;
+        // A page that now loads may have the title it lacked when saved
+        let titled = link.has_placeholder_title() && retitle(&mut link, metadata.title);
+        if titled {
+            retitled += 1;
+        }
+        let Some(current) = metadata.content_hash else {
+            unreachable += 1;
+            if !link.unreachable || titled {
+                link.unreachable = true;
+                updates.push(link);
+            }
+            continue;
+        };
+        link.unreachable = false;
+
+        let saved = link
+            .content_hash
+            .as_deref()
+            .and_then(|h| h.parse::<Fingerprint>().ok());
+
+        match saved {
+            None => {
+                link.set_content_hash(Some(current.to_string()));
+                baselined += 1;
+            }
+            Some(saved) => {
+                let distance = saved.distance(&current);
+                if saved.differs_significantly(&current) {
+                    if accept {
+                        link.set_content_hash(Some(current.to_string()));
+                    } else {
+                        link.content_changed = true;
+                        link.content_checked_at = Some(chrono::Utc::now());
+                    }
+                    changed.push((link.clone(), distance));
+                } else {
+                    link.content_changed = false;
+                    link.content_checked_at = Some(chrono::Utc::now());
+                }
+            }
+        }
+
+        updates.push(link);
+    }
+    store
+        .write_links(&updates, &[], format!("check {} links", updates.len()))
+        .context(t("Failed to update links"))?;
+
+    Ok(ContentCheck {
+        total,
+        changed,
+        baselined,
+        unreachable,
+        retitled,
+    })
+}
+
+/// Give links with a placeholder title (their URL, or blank) a real one
+///
+/// Each page is fetched again, since metadata often fails for reasons that
+/// pass (a timeout, a page that was down). Links whose page still has no
+/// title get one made from their URL, such as "Understanding rust
+/// lifetimes (example.com)"; they're tried again on later refreshes.
+///
+/// With `failed_only`, links whose last metadata fetch failed in whole or in
+/// part are fetched again instead (see [`refresh_failed`]).
+pub async fn refresh(
+    store: &mut Store,
+    untitled: bool,
+    failed_only: bool,
+    output: &Output,
+) -> Result<()> {
+    if failed_only {
+        return refresh_failed(store, output).await;
+    }
+    if !untitled {
+        bail!(t(
+            "Nothing to refresh. Use --untitled to retitle links saved without a title, \
+             or --failed-only to fetch again links whose metadata failed."
+        ));
+    }
+
+    let links: Vec<Link> = store
+        .get_all_links(LinkSort::default())?
+        .into_iter()
+        .filter(Link::has_placeholder_title)
+        .collect();
+    let total = links.len();
+
+    // Fetched fresh: a cached fetch would have the same missing title
+    let limits = MetadataLimits::from_config(store.config());
+    let mut retitled = Vec::new();
+    for mut link in links {
+        let fetched = fetch_metadata(&link.url, &limits).await.title;
+        let from_page = fetched.as_deref().is_some_and(|t| !t.trim().is_empty());
+        if retitle(&mut link, fetched) {
+            retitled.push((link, from_page));
+        }
+    }
+    let updated: Vec<Link> = retitled.iter().map(|(link, _)| link.clone()).collect();
+    store
+        .write_links(&updated, &[], format!("retitle {} links", updated.len()))
+        .context(t("Failed to update links"))?;
+
+    match output.format {
+        OutputFormat::Json => {
+            let json: Vec<_> = retitled
+                .iter()
+                .map(|(link, from_page)| {
+                    serde_json::json!({
+                        "id": link.id,
+                        "url": link.url,
+                        "title": link.title,
+                        "source": if *from_page { "page" } else { "url" },
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        OutputFormat::Quiet => {
+            for (link, _) in &retitled {
+                println!("{}", link.id);
+            }
+        }
+        OutputFormat::Human => {
+            for (link, _) in &retitled {
+                println!("{} | {}", &link.id.to_string()[..8], link.title);
+            }
+            if !retitled.is_empty() {
+                println!();
+            }
+            let from_page = retitled.iter().filter(|(_, from_page)| *from_page).count();
+            println!(
+                "{}",
+                tf(
+                    "Retitled {} of {} untitled link(s): {} from the page, {} from the URL",
+                    &[
+                        &retitled.len(),
+                        &total,
+                        &from_page,
+                        &(retitled.len() - from_page)
+                    ]
+                )
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Fetch again the links whose last metadata fetch failed, filling in the
+/// parts that failed
+///
+/// Fetched fresh, like `refresh --untitled`. Links that now fetch
+/// completely are dropped from the record; deleted links are forgotten.
+async fn refresh_failed(store: &mut Store, output: &Output) -> Result<()> {
+    let mut failures = FetchFailures::load(store.config())?;
+    let pending: Vec<(Uuid, Vec<String>)> = failures
+        .iter()
+        .map(|(id, failure)| (id, failure.failed.keys().cloned().collect()))
+        .collect();
+    let total = pending.len();
+
+    let limits = MetadataLimits::from_config(store.config());
+    let mut refreshed = Vec::new();
+    let mut still_failing = 0;
+    for (id, failed) in pending {
+        let Some(mut link) = store.get_link(id)? else {
+            failures.remove(id);
+            continue;
+        };
+        let metadata = fetch_metadata(&link.url, &limits).await;
+        let still_failed = metadata.failed.clone();
+        let filled = fill_failed(&mut link, &failed, metadata);
+        if !filled.is_empty() {
+            refreshed.push((link.clone(), filled));
+        }
+        if !still_failed.is_empty() {
+            still_failing += 1;
+        }
+        failures.record(id, &link.url, still_failed, chrono::Utc::now());
+    }
+    let updated: Vec<Link> = refreshed.iter().map(|(link, _)| link.clone()).collect();
+    store
+        .write_links(&updated, &[], format!("refresh {} links", updated.len()))
+        .context(t("Failed to update links"))?;
+    failures.save()?;
+
+    match output.format {
+        OutputFormat::Json => {
+            let json: Vec<_> = refreshed
+                .iter()
+                .map(|(link, filled)| {
+                    serde_json::json!({
+                        "id": link.id,
+                        "url": link.url,
+                        "title": link.title,
+                        "filled": filled,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        OutputFormat::Quiet => {
+            for (link, _) in &refreshed {
+                println!("{}", link.id);
+            }
+        }
+        OutputFormat::Human => {
+            for (link, filled) in &refreshed {
+                println!(
+                    "{} | {} ({})",
+                    &link.id.to_string()[..8],
+                    link.title,
+                    filled.join(", ")
+                );
+            }
+            if !refreshed.is_empty() {
+                println!();
+            }
+            println!(
+                "{}",
+                tf(
+                    "Refreshed {} of {} link(s) with failed metadata; {} still failing",
+                    &[&refreshed.len(), &total, &still_failing]
+                )
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Fill in the parts of a link that failed to fetch before from a new
+/// fetch, without overwriting anything set since
+///
+/// Returns the parts filled in.
+fn fill_failed(link: &mut Link, failed: &[String], metadata: UrlMetadata) -> Vec<&'static str> {
+    let failed_before = |part: &str| failed.iter().any(|f| f == FAILED_PAGE || f == part);
+    let mut filled = Vec::new();
+
+    if failed_before(FAILED_TITLE) && link.has_placeholder_title() {
+        if let Some(title) = metadata.title {
+            link.set_title(title);
+            filled.push(FAILED_TITLE);
+        }
+    }
+    if failed_before(FAILED_DESCRIPTION) && link.description.is_none() {
+        if let Some(description) = metadata.description {
+            link.set_description(Some(description));
+            filled.push(FAILED_DESCRIPTION);
+        }
+    }
+    if failed_before(FAILED_PAGE) && !metadata.failed.contains_key(FAILED_PAGE) {
+        if link.author.is_empty() && !metadata.author.is_empty() {
+            link.set_author(metadata.author);
+        }
+        if link.published_at.is_none() && metadata.published_at.is_some() {
+            link.set_published_at(metadata.published_at);
+        }
+        if link.canonical_url.is_none() && metadata.canonical_url.is_some() {
+            link.set_canonical_url(metadata.canonical_url);
+        }
+        link.language = link.language.take().or(metadata.language);
+        link.paywalled = metadata.paywalled;
+        if link.content_hash.is_none() {
+            if let Some(fingerprint) = metadata.content_hash {
+                link.set_content_hash(Some(fingerprint.to_string()));
+            }
+        }
+        filled.push(FAILED_PAGE);
+    }
+    filled
+}
+
+/// Replace a placeholder title with `fetched`, or else one made from the
+/// URL
+///
+/// Returns whether the title changed.
+pub(crate) fn retitle(link: &mut Link, fetched: Option<String>) -> bool {
+    let title = fetched
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty() && *t != link.url)
+        .or_else(|| title_from_url(&link.url));
+    match title {
+        Some(title) if title != link.title => {
+            link.set_title(title);
+            true
+        }
+        _ => false,
+    }
+}
+
 /// Parse a link ID (supports full UUID or prefix)
-fn parse_link_id(id: &str, store: &Store) -> Result<Uuid> {
+pub(crate) fn parse_link_id(id: &str, store: &Store) -> Result<Uuid> {
     // Try full UUID first
     if let Ok(uuid) = Uuid::parse_str(id) {
         return Ok(uuid);
     }
 
     // Try prefix match
-    let links = store.get_all_links()?;
+    let links = store.get_all_links(LinkSort::default())?;
     let matches: Vec<_> = links
         .iter()
         .filter(|l| l.id.to_string().starts_with(id))
         .collect();
 
     match matches.len() {
-        0 => bail!("No link found matching: {}", id),
+        0 => bail!(tf("No link found matching: {}", &[&id])),
         1 => Ok(matches[0].id),
         _ => {
-            eprintln!("Multiple links match '{}':", id);
+            eprintln!("{}", tf("Multiple links match '{}':", &[&id]));
             for link in &matches {
                 eprintln!("  {} - {}", link.id, link.title);
             }
-            bail!("Ambiguous ID. Please provide more characters.");
+            bail!(t("Ambiguous ID. Please provide more characters."));
         }
     }
 }
@@ -0,0 +1,145 @@
+//! Privacy lock command handlers
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+
+use rott_core::lock::{hash_passphrase, verify_passphrase};
+use rott_core::{Config, LockSession};
+
+use crate::i18n::{t, tf};
+use crate::output::Output;
+
+/// Set a passphrase and enable the privacy lock
+pub fn enable(config_path: Option<&PathBuf>, output: &Output) -> Result<()> {
+    let mut config = load_config(config_path)?;
+
+    if config.is_lock_enabled() {
+        // Changing the passphrase requires the current one
+        let current = read_passphrase(t("Current passphrase:"))?;
+        if !verify_passphrase(&current, config.lock_hash.as_deref().unwrap_or("")) {
+            bail!(t("Incorrect passphrase"));
+        }
+    }
+
+    let passphrase = read_passphrase(t("New passphrase:"))?;
+    if passphrase.is_empty() {
+        bail!(t("Passphrase cannot be empty"));
+    }
+    if read_passphrase(t("Confirm passphrase:"))? != passphrase {
+        bail!(t("Passphrases don't match"));
+    }
+
+    config.lock_hash = Some(hash_passphrase(&passphrase)?);
+    save_config(&config, config_path)?;
+
+    // Keep the current session usable; it locks after the idle timeout
+    LockSession::new(&config).unlock()?;
+
+    output.success(&tf(
+        "Privacy lock enabled (locks after {} minute(s) idle)",
+        &[&config.lock_timeout_minutes],
+    ));
+    Ok(())
+}
+
+/// Remove the passphrase and disable the privacy lock
+pub fn disable(config_path: Option<&PathBuf>, output: &Output) -> Result<()> {
+    let mut config = load_config(config_path)?;
+
+    let Some(ref hash) = config.lock_hash else {
+        output.message(t("Privacy lock is not enabled."));
+        return Ok(());
+    };
+
+    if !verify_passphrase(&read_passphrase(t("Passphrase:"))?, hash) {
+        bail!(t("Incorrect passphrase"));
+    }
+
+    config.lock_hash = None;
+    save_config(&config, config_path)?;
+    LockSession::new(&config).lock()?;
+
+    output.success(t("Privacy lock disabled"));
+    Ok(())
+}
+
+/// Lock immediately
+pub fn lock(config_path: Option<&PathBuf>, output: &Output) -> Result<()> {
+    let config = load_config(config_path)?;
+
+    if !config.is_lock_enabled() {
+        bail!(t(
+            "Privacy lock is not enabled. Set a passphrase with `rott lock enable`."
+        ));
+    }
+
+    LockSession::new(&config).lock()?;
+    output.success(t("Locked"));
+    Ok(())
+}
+
+/// Enter the passphrase and start an unlocked session
+pub fn unlock(config_path: Option<&PathBuf>, output: &Output) -> Result<()> {
+    let config = load_config(config_path)?;
+
+    if !config.is_lock_enabled() {
+        output.message(t("Privacy lock is not enabled."));
+        return Ok(());
+    }
+
+    prompt_unlock(&config)?;
+    output.success(&tf(
+        "Unlocked (locks after {} minute(s) idle)",
+        &[&config.lock_timeout_minutes],
+    ));
+    Ok(())
+}
+
+/// Make sure the privacy lock is open before showing any data
+///
+/// Extends an unlocked session. Otherwise prompts for the passphrase when
+/// interactive, and fails when not (scripts should run `rott unlock` first).
+pub fn ensure_unlocked(config: &Config, output: &Output) -> Result<()> {
+    if !config.is_lock_enabled() {
+        return Ok(());
+    }
+
+    let session = LockSession::new(config);
+    if session.is_unlocked() {
+        return session.touch();
+    }
+
+    if output.should_prompt() && atty::is(atty::Stream::Stdin) {
+        return prompt_unlock(config);
+    }
+
+    bail!(t("ROTT is locked. Run `rott unlock` first."));
+}
+
+/// Prompt for the passphrase and start a session if it's correct
+fn prompt_unlock(config: &Config) -> Result<()> {
+    let hash = config.lock_hash.as_deref().unwrap_or("");
+    if !verify_passphrase(&read_passphrase(t("Passphrase:"))?, hash) {
+        bail!(t("Incorrect passphrase"));
+    }
+    LockSession::new(config).unlock()
+}
+
+/// Read a passphrase from the terminal without echoing it
+fn read_passphrase(prompt: &str) -> Result<String> {
+    rpassword::prompt_password(format!("{} ", prompt)).context(t("Failed to read passphrase"))
+}
+
+fn load_config(config_path: Option<&PathBuf>) -> Result<Config> {
+    Config::load_with_cli_override(config_path).context(t("Failed to load configuration"))
+}
+
+fn save_config(config: &Config, config_path: Option<&PathBuf>) -> Result<()> {
+    let save_path = config_path
+        .cloned()
+        .unwrap_or_else(Config::config_file_path);
+    config
+        .save_to_path(&save_path)
+        .context(t("Failed to save configuration"))
+}
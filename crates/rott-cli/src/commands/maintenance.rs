@@ -0,0 +1,573 @@
+//! Maintenance command handlers
+//!
+//! Diagnostics for the underlying Automerge document, and the runner for
+//! periodic jobs (see `rott_core::maintenance`).
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use rott_core::attachments::AttachmentStore;
+use rott_core::dedupe::find_duplicate_notes;
+use rott_core::events::{projection_path, projections, projections_named};
+use rott_core::maintenance::{
+    prune_backups, report_name, scheduled_backup_name, Job, MaintenanceLog,
+};
+use rott_core::metadata_cache::MetadataCache;
+use rott_core::{LinkSort, Store};
+
+use crate::commands::backup::write_backup;
+use crate::commands::link::check_content;
+use crate::editor::confirm;
+use crate::history::parse_since;
+use crate::i18n::{t, tf};
+use crate::output::{human_size, Output, OutputFormat};
+use crate::preview::{Change, ChangeKind, Preview};
+
+/// Report document history and size statistics
+///
+/// Useful for spotting runaway growth and which device is causing it.
+pub fn doc_stats(store: &Store, top: usize, output: &Output) -> Result<()> {
+    let stats = store.document_stats(top)?;
+
+    match output.format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&stats).unwrap());
+        }
+        OutputFormat::Quiet => {
+            println!("{}", stats.byte_size);
+        }
+        OutputFormat::Human => {
+            let title = t("Document Statistics");
+            println!("{}", title);
+            println!("{}", "=".repeat(title.chars().count()));
+            println!();
+            let rows = [
+                (t("Size:"), human_size(stats.byte_size)),
+                (t("Changes:"), stats.change_count.to_string()),
+                (t("Operations:"), stats.op_count.to_string()),
+                (t("Actors:"), stats.actors.len().to_string()),
+            ];
+            let width = rows
+                .iter()
+                .map(|(label, _)| label.chars().count())
+                .max()
+                .unwrap_or(0);
+            for (label, value) in &rows {
+                println!("  {:<width$} {}", label, value, width = width);
+            }
+            println!();
+            println!("{}", t("Changes by actor:"));
+            for actor in &stats.actors {
+                let last = actor
+                    .last_change_at
+                    .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_else(|| t("unknown").to_string());
+                println!(
+                    "  {}",
+                    tf(
+                        "{}  {} changes  {} ops  last {}",
+                        &[
+                            &&actor.actor_id[..actor.actor_id.len().min(16)],
+                            &format!("{:>6}", actor.change_count),
+                            &format!("{:>8}", actor.op_count),
+                            &last
+                        ]
+                    )
+                );
+            }
+            if !stats.largest_links.is_empty() {
+                println!();
+                println!("{}", t("Largest links:"));
+                for link in &stats.largest_links {
+                    println!(
+                        "  {}  {:>9}  {}",
+                        &link.link_id.to_string()[..8],
+                        human_size(link.bytes),
+                        link.title
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Run the maintenance jobs that are due
+///
+/// Each job's outcome is recorded in the maintenance log; a failing job
+/// doesn't stop the others and is retried on the next run. With
+/// `dry_run`, only lists what would run.
+pub async fn run_due(store: &mut Store, dry_run: bool, output: &Output) -> Result<()> {
+    let config = store.config().clone();
+    let mut log = MaintenanceLog::load(&config)?;
+    let due: Vec<Job> = log
+        .status(&config, Utc::now())
+        .into_iter()
+        .filter(|status| status.due)
+        .map(|status| status.job)
+        .collect();
+
+    let mut results = Vec::new();
+    for job in due {
+        if dry_run {
+            results.push((job, Ok(t("due").to_string())));
+            continue;
+        }
+        let started = Utc::now();
+        let result = run_job(store, job).await;
+        log.record(job, started, &result);
+        log.save()?;
+        results.push((job, result));
+    }
+
+    match output.format {
+        OutputFormat::Json => {
+            let json: Vec<_> = results
+                .iter()
+                .map(|(job, result)| {
+                    serde_json::json!({
+                        "job": job.name(),
+                        "succeeded": result.is_ok(),
+                        "message": match result {
+                            Ok(summary) => summary.clone(),
+                            Err(e) => format!("{:#}", e),
+                        },
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&json).unwrap());
+        }
+        OutputFormat::Quiet => {
+            for (job, result) in &results {
+                if result.is_err() {
+                    println!("{}", job.name());
+                }
+            }
+        }
+        OutputFormat::Human => {
+            if results.is_empty() {
+                println!("{}", t("No maintenance jobs are due"));
+            }
+            for (job, result) in &results {
+                match result {
+                    Ok(summary) => println!("{}: {}", job.name(), summary),
+                    Err(e) => println!(
+                        "{}",
+                        tf("{}: failed: {}", &[&job.name(), &format!("{:#}", e)])
+                    ),
+                }
+            }
+        }
+    }
+
+    let failed = results.iter().filter(|(_, result)| result.is_err()).count();
+    if failed > 0 {
+        bail!(tf("{} maintenance job(s) failed", &[&failed]));
+    }
+    Ok(())
+}
+
+/// Run one job, returning a summary of what it did
+async fn run_job(store: &mut Store, job: Job) -> Result<String> {
+    match job {
+        Job::Backup => {
+            let dir = store.config().backup_dir();
+            fs::create_dir_all(&dir)
+                .with_context(|| tf("Failed to create {}", &[&dir.display()]))?;
+            let file = dir.join(scheduled_backup_name(Utc::now()));
+            let (backup, bytes) = write_backup(store, &file)?;
+            let pruned = prune_backups(&dir, store.config().backup_keep.max(1))?;
+            Ok(tf(
+                "backed up {} link(s) to {} ({}), removed {} old backup(s)",
+                &[
+                    &backup.link_count()?,
+                    &file.display(),
+                    &human_size(bytes),
+                    &pruned.len(),
+                ],
+            ))
+        }
+        Job::LinkCheck => {
+            let check = check_content(store, false).await?;
+            Ok(tf(
+                "checked {} link(s): {} changed, {} new baseline(s), {} unreachable, {} retitled",
+                &[
+                    &check.total,
+                    &check.changed.len(),
+                    &check.baselined,
+                    &check.unreachable,
+                    &check.retitled,
+                ],
+            ))
+        }
+        Job::ArchiveExpired => {
+            let ids: Vec<Uuid> = store
+                .expired_links(Utc::now())?
+                .iter()
+                .map(|link| link.id)
+                .collect();
+            let moved = store.archive_links(&ids)?;
+            Ok(tf("archived {} expired link(s)", &[&moved]))
+        }
+        Job::Report => {
+            let now = Utc::now();
+            let config = store.config().clone();
+            // Pick up where the last report stopped
+            let since = MaintenanceLog::load(&config)?
+                .last_run(Job::Report)
+                .filter(|record| record.succeeded)
+                .map(|record| record.last_run)
+                .unwrap_or_else(|| {
+                    now - config
+                        .report_interval
+                        .map_or(Duration::days(7), |interval| interval.duration())
+                });
+            let report = store.change_report(since, now)?;
+            let dir = config.report_dir();
+            fs::create_dir_all(&dir)
+                .with_context(|| tf("Failed to create {}", &[&dir.display()]))?;
+            let file = dir.join(report_name(now));
+            fs::write(&file, report.to_markdown())
+                .with_context(|| tf("Failed to write {}", &[&file.display()]))?;
+            Ok(tf(
+                "wrote {} ({} added, {} archived, {} deleted, {} device(s))",
+                &[
+                    &file.display(),
+                    &report.added.len(),
+                    &report.archived.len(),
+                    &report.deleted.len(),
+                    &report.devices.len(),
+                ],
+            ))
+        }
+    }
+}
+
+/// Find identical notes across links and offer to merge them
+///
+/// Each group keeps its oldest note; copies on other links become a short
+/// reference to it. Asks before merging each group unless `yes` is set.
+/// With `dry_run`, previews the merge without asking or saving.
+pub fn dedupe_notes(store: &mut Store, yes: bool, dry_run: bool, output: &Output) -> Result<()> {
+    let groups = find_duplicate_notes(&store.get_all_links(LinkSort::default())?);
+    if !dry_run && !yes && !output.should_prompt() {
+        bail!(t(
+            "Merging without prompting needs --yes (or use --dry-run to list duplicates)"
+        ));
+    }
+
+    if groups.is_empty() {
+        output.success(t("No duplicate notes found"));
+        return Ok(());
+    }
+
+    if dry_run {
+        let copies = groups.iter().map(|g| g.duplicates.len()).sum();
+        let mut preview = Preview::new("Merge duplicate notes")
+            .count("group(s)", groups.len())
+            .count("copies", copies);
+        for group in &groups {
+            for duplicate in &group.duplicates {
+                let on = format!(
+                    "\"{}\" on {} ({})",
+                    preview_line(&duplicate.note.body),
+                    duplicate.link_title,
+                    &duplicate.link_id.to_string()[..8]
+                );
+                // Copies on the kept note's own link are removed
+                let change = if duplicate.link_id == group.keep.link_id {
+                    Change::new(ChangeKind::Delete, on)
+                } else {
+                    Change::new(ChangeKind::Change, on)
+                        .diff(&duplicate.note.body, group.reference_body())
+                };
+                preview.push(change.link(duplicate.link_id));
+            }
+        }
+        return preview.print(output);
+    }
+
+    let mut merged_groups = 0;
+    let mut merged_notes = 0;
+    for group in &groups {
+        if output.format == OutputFormat::Human {
+            println!("\n\"{}\"", preview_line(&group.keep.note.body));
+            println!(
+                "  {}",
+                tf(
+                    "keep on: {} ({})",
+                    &[
+                        &group.keep.link_title,
+                        &&group.keep.link_id.to_string()[..8]
+                    ]
+                )
+            );
+            for duplicate in &group.duplicates {
+                println!(
+                    "  {}",
+                    tf(
+                        "copy on: {} ({})",
+                        &[&duplicate.link_title, &&duplicate.link_id.to_string()[..8]]
+                    )
+                );
+            }
+        }
+        if !yes && !confirm(t("Merge these notes?"))? {
+            continue;
+        }
+        store.merge_duplicate_notes(group)?;
+        merged_groups += 1;
+        merged_notes += group.duplicates.len();
+    }
+
+    output.success(&tf(
+        "Merged {} duplicate note(s) in {} group(s)",
+        &[&merged_notes, &merged_groups],
+    ));
+    Ok(())
+}
+
+/// Merge tags that differ only in case or punctuation, on every link
+pub fn normalize_tags(store: &mut Store, output: &Output) -> Result<()> {
+    let changed = store.normalize_tags()?;
+    output.success(&tf("Normalized tags on {} link(s)", &[&changed]));
+    Ok(())
+}
+
+/// Empty the fetched metadata cache (or just its expired entries)
+pub fn clear_cache(store: &Store, expired: bool, output: &Output) -> Result<()> {
+    let Some(cache) = MetadataCache::from_config(store.config()) else {
+        output.message(t(
+            "The metadata cache is turned off (metadata_cache_hours = 0)",
+        ));
+        return Ok(());
+    };
+    let removed = if expired {
+        cache.prune(Utc::now())?
+    } else {
+        cache.clear()?
+    };
+    output.success(&if expired {
+        tf("Removed {} expired cached page(s)", &[&removed])
+    } else {
+        tf("Removed {} cached page(s)", &[&removed])
+    });
+    Ok(())
+}
+
+/// Remove attachment blobs no link has referred to for `grace_days`
+pub fn gc(store: &Store, grace_days: u32, dry_run: bool, output: &Output) -> Result<()> {
+    let referenced = store.referenced_attachments()?;
+    let report = AttachmentStore::from_config(store.config()).collect_garbage(
+        &referenced,
+        Duration::days(i64::from(grace_days)),
+        Utc::now(),
+        dry_run,
+    )?;
+    let reclaimed = usize::try_from(report.reclaimed_bytes()).unwrap_or(usize::MAX);
+
+    match output.format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::json!({
+                    "dry_run": dry_run,
+                    "referenced": report.referenced,
+                    "removed": report.removed.iter().map(|(hash, _)| hash).collect::<Vec<_>>(),
+                    "reclaimed_bytes": report.reclaimed_bytes(),
+                    "pending": report.pending,
+                })
+            );
+        }
+        OutputFormat::Quiet => {}
+        OutputFormat::Human => {
+            let verb = if dry_run {
+                t("Would remove")
+            } else {
+                t("Removed")
+            };
+            println!(
+                "{}",
+                tf(
+                    "{} {} unreferenced attachment(s), reclaiming {}",
+                    &[&verb, &report.removed.len(), &human_size(reclaimed)]
+                )
+            );
+            println!("{}", tf("{} attachment(s) in use", &[&report.referenced]));
+            if report.pending > 0 {
+                println!(
+                    "{}",
+                    tf(
+                        "{} unreferenced attachment(s) kept until they've been unused for {} \
+                         day(s)",
+                        &[&report.pending, &grace_days]
+                    )
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Rebuild projections from the event log, or list them
+pub fn reindex(store: &Store, projection: Option<String>, output: &Output) -> Result<()> {
+    let Some(name) = projection else {
+        let data_dir = &store.config().store_dir();
+        let listed: Vec<serde_json::Value> = projections()
+            .iter()
+            .map(|p| {
+                let path = projection_path(data_dir, p.name());
+                serde_json::json!({
+                    "name": p.name(),
+                    "description": p.description(),
+                    "file": path.exists().then_some(path),
+                })
+            })
+            .collect();
+        match output.format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&listed)?),
+            OutputFormat::Quiet => {
+                for p in &listed {
+                    println!("{}", p["name"].as_str().unwrap_or_default());
+                }
+            }
+            OutputFormat::Human => {
+                for p in &listed {
+                    println!(
+                        "{:<10} {}{}",
+                        p["name"].as_str().unwrap_or_default(),
+                        p["description"].as_str().unwrap_or_default(),
+                        if p["file"].is_null() {
+                            format!(" {}", t("(not built)"))
+                        } else {
+                            String::new()
+                        }
+                    );
+                }
+                println!();
+                println!(
+                    "{}",
+                    t("Rebuild one with: rott maintenance reindex <name> (or all)")
+                );
+            }
+        }
+        return Ok(());
+    };
+
+    let mut projections = projections_named(&name)?;
+    let (events, paths) = store.reindex(&mut projections)?;
+    match output.format {
+        OutputFormat::Json => {
+            let rebuilt: Vec<serde_json::Value> = projections
+                .iter()
+                .zip(&paths)
+                .map(|(p, path)| {
+                    serde_json::json!({
+                        "name": p.name(),
+                        "file": path,
+                        "summary": p.summary(),
+                    })
+                })
+                .collect();
+            println!(
+                "{}",
+                serde_json::json!({ "events": events, "projections": rebuilt })
+            );
+        }
+        OutputFormat::Quiet => {}
+        OutputFormat::Human => {
+            output.success(&tf("Replayed {} event(s)", &[&events]));
+            for (p, path) in projections.iter().zip(&paths) {
+                println!("  {:<10} {} ({})", p.name(), p.summary(), path.display());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Print the event log as JSON lines
+pub fn events(store: &Store) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+    let mut written = Ok(());
+    store.replay_events(|event| {
+        if written.is_ok() {
+            written = serde_json::to_string(&event)
+                .map_err(anyhow::Error::from)
+                .and_then(|line| writeln!(stdout, "{}", line).map_err(anyhow::Error::from));
+        }
+    })?;
+    written
+}
+
+/// Report what changed over the last `since` (see `rott_core::report`)
+pub fn report(store: &Store, since: &str, out: Option<PathBuf>, output: &Output) -> Result<()> {
+    let until = Utc::now();
+    let report = store.change_report(until - parse_since(since)?, until)?;
+
+    if output.format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+    let markdown = report.to_markdown();
+    match out {
+        Some(file) => {
+            fs::write(&file, &markdown)
+                .with_context(|| tf("Failed to write {}", &[&file.display()]))?;
+            output.success(&tf("Wrote the report to {}", &[&file.display()]));
+        }
+        None if output.format == OutputFormat::Quiet => {}
+        None => print!("{}", markdown),
+    }
+    Ok(())
+}
+
+/// Write an anonymized copy of the document (an Automerge file)
+pub fn anonymize(store: &Store, out: PathBuf, output: &Output) -> Result<()> {
+    let mut copy = store.anonymized()?;
+    let bytes = copy.save();
+    fs::write(&out, &bytes).with_context(|| tf("Failed to write {}", &[&out.display()]))?;
+
+    let links = copy.link_count()?;
+    let notes = copy.note_count()?;
+    match output.format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::json!({
+                    "file": out,
+                    "links": links,
+                    "notes": notes,
+                    "bytes": bytes.len(),
+                })
+            );
+        }
+        OutputFormat::Quiet => {}
+        OutputFormat::Human => {
+            output.success(&tf(
+                "Wrote an anonymized copy of {} link(s) and {} note(s) to {} ({})",
+                &[&links, &notes, &out.display(), &human_size(bytes.len())],
+            ));
+            output.message(t(
+                "Titles, URLs, notes and tags are replaced; IDs and timestamps are kept, \
+                 history isn't",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// First line of a note, shortened for listing
+fn preview_line(body: &str) -> String {
+    let line = body.trim().lines().next().unwrap_or_default();
+    if line.chars().count() > 60 {
+        format!("{}…", line.chars().take(59).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
@@ -1,8 +1,22 @@
 //! CLI command handlers
 
+pub mod archive;
+pub mod author;
+pub mod backup;
+pub mod capture;
+pub mod collection;
 pub mod config;
+pub mod doctor;
+pub mod export;
+pub mod grep;
+pub mod hypothesis;
+pub mod import;
 pub mod link;
+pub mod lock;
+pub mod maintenance;
 pub mod note;
+pub mod script;
+pub mod stats;
 pub mod status;
 pub mod sync;
 pub mod tag;
@@ -3,19 +3,28 @@
 //! Notes are children of links, providing annotations and comments.
 
 use anyhow::{bail, Context, Result};
+use chrono::Local;
 use uuid::Uuid;
 
-use rott_core::{Note, Store};
+use rott_core::template::{link_vars, render};
+use rott_core::{Link, LinkSort, Note, NoteVersion, Store};
 
+use crate::diff::changed_lines;
 use crate::editor::{confirm, edit_text};
-use crate::output::Output;
+use crate::i18n::{t, tf};
+use crate::output::{Output, OutputFormat};
+use crate::preview::{Change, ChangeKind, Preview};
 
 /// Create a new note on a link
+///
+/// With `reply_to`, the note is threaded under that note (or, if it's
+/// itself a reply, under the note it replies to).
 pub fn create(
     store: &mut Store,
     link_id: String,
     title: Option<String>,
     body: Option<String>,
+    reply_to: Option<String>,
     output: &Output,
 ) -> Result<()> {
     let link_uuid = parse_link_id(&link_id, store)?;
@@ -23,18 +32,36 @@ pub fn create(
     // Get the link to show context
     let link = store
         .get_link(link_uuid)?
-        .ok_or_else(|| anyhow::anyhow!("Link not found: {}", link_id))?;
+        .ok_or_else(|| anyhow::anyhow!(tf("Link not found: {}", &[&link_id])))?;
+
+    let parent_id = match reply_to {
+        Some(ref note_id) => Some(
+            link.reply_parent(parse_note_id(note_id, &link)?)
+                .ok_or_else(|| anyhow::anyhow!(tf("Note not found: {}", &[&note_id])))?,
+        ),
+        None => None,
+    };
 
     // Get body content
     let body_content = match body {
         Some(b) => b,
         None => {
             // Open editor for body
+            // Start from the configured note template, if there is one
+            let template = match store.config().note_template {
+                Some(ref template) => {
+                    render(template, &link_vars(&link.url, &link.title, Local::now()))
+                        .context(t("Failed to render note_template"))?
+                }
+                None => String::new(),
+            };
             let initial = format!(
-                "<!-- Adding note to: {} -->\n<!-- {} -->\n\n",
-                link.title, link.url
+                "<!-- {} -->\n<!-- {} -->\n\n{}",
+                tf("Adding note to: {}", &[&link.title]),
+                link.url,
+                template
             );
-            let edited = edit_text(&initial).context("Failed to edit note")?;
+            let edited = edit_text(&initial).context(t("Failed to edit note"))?;
 
             // Remove the comment lines
             edited
@@ -48,23 +75,23 @@ pub fn create(
     };
 
     if body_content.is_empty() {
-        bail!("Note body cannot be empty");
+        bail!(t("Note body cannot be empty"));
     }
 
-    let note = match title {
+    let mut note = match title {
         Some(t) => Note::with_title(t, body_content),
         None => Note::new(body_content),
     };
+    note.parent_id = parent_id;
 
     let note_id = note.id;
     store
         .add_note_to_link(link_uuid, &note)
-        .context("Failed to add note to link")?;
+        .context(t("Failed to add note to link"))?;
 
-    output.success(&format!(
+    output.success(&tf(
         "Added note {} to link {}",
-        &note_id.to_string()[..8],
-        &link_uuid.to_string()[..8]
+        &[&&note_id.to_string()[..8], &&link_uuid.to_string()[..8]],
     ));
 
     Ok(())
@@ -76,25 +103,94 @@ pub fn list(store: &Store, link_id: String, output: &Output) -> Result<()> {
 
     let link = store
         .get_link(link_uuid)?
-        .ok_or_else(|| anyhow::anyhow!("Link not found: {}", link_id))?;
+        .ok_or_else(|| anyhow::anyhow!(tf("Link not found: {}", &[&link_id])))?;
 
     output.print_link_notes(&link);
     Ok(())
 }
 
+/// Edit a note's title or body
+///
+/// Without `title` or `body`, the body is opened in the editor.
+pub fn edit(
+    store: &mut Store,
+    link_id: String,
+    note_id: String,
+    title: Option<String>,
+    body: Option<String>,
+    output: &Output,
+) -> Result<()> {
+    let link_uuid = parse_link_id(&link_id, store)?;
+
+    let link = store
+        .get_link(link_uuid)?
+        .ok_or_else(|| anyhow::anyhow!(tf("Link not found: {}", &[&link_id])))?;
+
+    let note_uuid = parse_note_id(&note_id, &link)?;
+    let mut note = link
+        .get_note(note_uuid)
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!(tf("Note not found: {}", &[&note_id])))?;
+
+    let new_title = match title {
+        Some(t) => Some(t).filter(|t| !t.trim().is_empty()),
+        None => note.title.clone(),
+    };
+    let new_body = match body {
+        Some(b) => b,
+        None if new_title != note.title => note.body.clone(),
+        None => {
+            let initial = format!(
+                "<!-- {} -->\n<!-- {} -->\n\n{}",
+                tf("Editing note on: {}", &[&link.title]),
+                link.url,
+                note.body
+            );
+            let edited = edit_text(&initial).context(t("Failed to edit note"))?;
+
+            // Remove the comment lines
+            edited
+                .lines()
+                .filter(|line| !line.starts_with("<!--"))
+                .collect::<Vec<_>>()
+                .join("\n")
+                .trim()
+                .to_string()
+        }
+    };
+
+    if new_body.trim().is_empty() {
+        bail!(t("Note body cannot be empty"));
+    }
+    if new_title == note.title && new_body == note.body {
+        output.message(t("Note unchanged."));
+        return Ok(());
+    }
+
+    note.set_title(new_title);
+    note.set_body(new_body);
+    store
+        .update_note(link_uuid, &note)
+        .context(t("Failed to update note"))?;
+
+    output.success(&tf("Updated note: {}", &[&&note_uuid.to_string()[..8]]));
+
+    Ok(())
+}
+
 /// Delete a note from a link
 pub fn delete(store: &mut Store, link_id: String, note_id: String, output: &Output) -> Result<()> {
     let link_uuid = parse_link_id(&link_id, store)?;
 
     let link = store
         .get_link(link_uuid)?
-        .ok_or_else(|| anyhow::anyhow!("Link not found: {}", link_id))?;
+        .ok_or_else(|| anyhow::anyhow!(tf("Link not found: {}", &[&link_id])))?;
 
     let note_uuid = parse_note_id(&note_id, &link)?;
 
     let note = link
         .get_note(note_uuid)
-        .ok_or_else(|| anyhow::anyhow!("Note not found: {}", note_id))?;
+        .ok_or_else(|| anyhow::anyhow!(tf("Note not found: {}", &[&note_id])))?;
 
     // Confirm deletion
     if output.should_prompt() {
@@ -104,22 +200,334 @@ pub fn delete(store: &mut Store, link_id: String, note_id: String, output: &Outp
             note.body.clone()
         };
         println!(
-            "Delete note: {} - {}",
-            &note.id.to_string()[..8],
-            preview.replace('\n', " ")
+            "{}",
+            tf(
+                "Delete note: {} - {}",
+                &[&&note.id.to_string()[..8], &preview.replace('\n', " ")]
+            )
         );
-        if !confirm("Are you sure?")? {
-            println!("Cancelled.");
+        if !confirm(t("Are you sure?"))? {
+            println!("{}", t("Cancelled."));
             return Ok(());
         }
     }
 
     store
         .remove_note_from_link(link_uuid, note_uuid)
-        .context("Failed to delete note")?;
+        .context(t("Failed to delete note"))?;
+
+    output.success(&tf("Deleted note: {}", &[&&note_uuid.to_string()[..8]]));
+
+    Ok(())
+}
+
+/// Show the version history of a note
+///
+/// Each version is shown as a diff against the one before it, or in full
+/// with `full`. Versions are numbered from 1 (the oldest).
+pub fn history(
+    store: &Store,
+    link_id: String,
+    note_id: String,
+    full: bool,
+    output: &Output,
+) -> Result<()> {
+    let link_uuid = parse_link_id(&link_id, store)?;
+
+    let link = store
+        .get_link(link_uuid)?
+        .ok_or_else(|| anyhow::anyhow!(tf("Link not found: {}", &[&link_id])))?;
+
+    let note_uuid = parse_note_id(&note_id, &link)?;
+    let versions = store.note_history(link_uuid, note_uuid)?;
+
+    match output.format {
+        OutputFormat::Json => {
+            let json: Vec<_> = versions
+                .iter()
+                .enumerate()
+                .map(|(i, v)| {
+                    serde_json::json!({
+                        "version": i + 1,
+                        "change_hash": v.change_hash,
+                        "timestamp": v.timestamp,
+                        "title": v.title,
+                        "body": v.body,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&json).unwrap());
+        }
+        OutputFormat::Quiet => {
+            for version in &versions {
+                println!("{}", version.change_hash);
+            }
+        }
+        OutputFormat::Human => {
+            if versions.is_empty() {
+                println!(
+                    "{}",
+                    tf("No history for note {}.", &[&&note_uuid.to_string()[..8]])
+                );
+            }
+            let mut previous: Option<&NoteVersion> = None;
+            for (i, version) in versions.iter().enumerate() {
+                let when = version
+                    .timestamp
+                    .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_else(|| t("unknown time").to_string());
+                println!(
+                    "{}",
+                    tf(
+                        "Version {} ({}, {})",
+                        &[&(i + 1), &&version.change_hash[..8], &when]
+                    )
+                );
+
+                let old_title = previous.and_then(|p| p.title.as_deref());
+                if previous.is_none() || old_title != version.title.as_deref() {
+                    println!(
+                        "  {}",
+                        tf(
+                            "Title: {} -> {}",
+                            &[
+                                &old_title.unwrap_or(t("(none)")),
+                                &version.title.as_deref().unwrap_or(t("(none)"))
+                            ]
+                        )
+                    );
+                }
+
+                match previous {
+                    Some(prev) if !full => {
+                        for line in changed_lines(&prev.body, &version.body) {
+                            println!("  {}", line);
+                        }
+                    }
+                    _ => {
+                        for line in version.body.lines() {
+                            println!("    {}", line);
+                        }
+                    }
+                }
+                println!();
+                previous = Some(version);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Restore a note to an earlier version
+///
+/// The restored content is written as a new change, so the versions in
+/// between stay in the history.
+pub fn restore(
+    store: &mut Store,
+    link_id: String,
+    note_id: String,
+    version: usize,
+    output: &Output,
+) -> Result<()> {
+    let link_uuid = parse_link_id(&link_id, store)?;
+
+    let link = store
+        .get_link(link_uuid)?
+        .ok_or_else(|| anyhow::anyhow!(tf("Link not found: {}", &[&link_id])))?;
+
+    let note_uuid = parse_note_id(&note_id, &link)?;
+    let mut note = link
+        .get_note(note_uuid)
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!(tf("Note not found: {}", &[&note_id])))?;
+
+    let versions = store.note_history(link_uuid, note_uuid)?;
+    let Some(target) = version.checked_sub(1).and_then(|i| versions.get(i)) else {
+        bail!(tf(
+            "No version {} (note has {} version(s))",
+            &[&version, &versions.len()]
+        ));
+    };
+
+    if note.title == target.title && note.body == target.body {
+        output.message(&tf("Note already matches version {}.", &[&version]));
+        return Ok(());
+    }
+
+    note.set_title(target.title.clone());
+    note.set_body(target.body.clone());
+    store
+        .update_note(link_uuid, &note)
+        .context(t("Failed to restore note"))?;
+
+    output.success(&tf(
+        "Restored note {} to version {}",
+        &[&&note_uuid.to_string()[..8], &version],
+    ));
 
-    output.success(&format!("Deleted note: {}", &note_uuid.to_string()[..8]));
+    Ok(())
+}
+
+/// Split a note into one note per top-level Markdown heading
+pub fn split(store: &mut Store, link_id: String, note_id: String, output: &Output) -> Result<()> {
+    let link_uuid = parse_link_id(&link_id, store)?;
+
+    let link = store
+        .get_link(link_uuid)?
+        .ok_or_else(|| anyhow::anyhow!(tf("Link not found: {}", &[&link_id])))?;
+    let note_uuid = parse_note_id(&note_id, &link)?;
+
+    let added = store
+        .split_note(link_uuid, note_uuid)
+        .context(t("Failed to split note"))?;
+    if added.is_empty() {
+        output.message(t(
+            "Nothing to split: the note has no more than one section.",
+        ));
+        return Ok(());
+    }
 
+    match output.format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&added)?),
+        OutputFormat::Quiet => {
+            for note in &added {
+                println!("{}", note.id);
+            }
+        }
+        OutputFormat::Human => {
+            output.success(&tf(
+                "Split note {} into {} notes",
+                &[&&note_uuid.to_string()[..8], &(added.len() + 1)],
+            ));
+            for note in &added {
+                println!(
+                    "  {}  {}",
+                    &note.id.to_string()[..8],
+                    note.title.as_deref().unwrap_or_default()
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A note whose body a find-and-replace changes
+struct Replacement<'a> {
+    link: &'a Link,
+    note: Note,
+    before: &'a str,
+    occurrences: usize,
+}
+
+/// Find the notes containing `find`, with `replace` applied to their bodies
+fn plan_replacements<'a>(links: &'a [Link], find: &str, replace: &str) -> Vec<Replacement<'a>> {
+    let mut replacements = Vec::new();
+    for link in links {
+        for note in &link.notes {
+            let occurrences = note.body.matches(find).count();
+            if occurrences == 0 {
+                continue;
+            }
+            let mut updated = note.clone();
+            updated.set_body(note.body.replace(find, replace));
+            replacements.push(Replacement {
+                link,
+                note: updated,
+                before: &note.body,
+                occurrences,
+            });
+        }
+    }
+    replacements
+}
+
+/// Find and replace text across note bodies
+///
+/// Every change is previewed as a diff, then all of them are applied
+/// together in one save.
+pub fn replace(
+    store: &mut Store,
+    find: &str,
+    replace: &str,
+    tag: Option<String>,
+    yes: bool,
+    dry_run: bool,
+    output: &Output,
+) -> Result<()> {
+    if find.is_empty() {
+        bail!(t("--find can't be empty"));
+    }
+    if !dry_run && !yes && !output.should_prompt() {
+        bail!(t(
+            "Replacing without prompting needs --yes (or use --dry-run to preview)"
+        ));
+    }
+
+    let links = match tag {
+        Some(ref tag) => store.get_links_by_tag(tag, LinkSort::default())?,
+        None => store.get_all_links(LinkSort::default())?,
+    };
+    let replacements = plan_replacements(&links, find, replace);
+    let occurrences: usize = replacements.iter().map(|r| r.occurrences).sum();
+
+    if replacements.is_empty() {
+        output.success(&tf("No notes contain \"{}\"", &[&find]));
+        return Ok(());
+    }
+
+    let mut preview = Preview::new("Replace \"{}\" in notes")
+        .subject(find)
+        .count("note(s)", replacements.len())
+        .count("occurrence(s)", occurrences);
+    for r in &replacements {
+        preview.push(
+            Change::new(
+                ChangeKind::Change,
+                tf(
+                    "{} ({}) note {}",
+                    &[
+                        &r.link.title,
+                        &&r.link.id.to_string()[..8],
+                        &&r.note.id.to_string()[..8],
+                    ],
+                ),
+            )
+            .link(r.link.id)
+            .diff(r.before, &r.note.body),
+        );
+    }
+    if dry_run {
+        return preview.print(output);
+    }
+
+    if output.format == OutputFormat::Human {
+        for change in &preview.changes {
+            println!("\n{}", change.summary);
+            let (before, after) = (change.before.as_deref(), change.after.as_deref());
+            for line in changed_lines(before.unwrap_or_default(), after.unwrap_or_default()) {
+                println!("  {}", line);
+            }
+        }
+    }
+    let summary = tf(
+        "{} occurrence(s) in {} note(s)",
+        &[&occurrences, &replacements.len()],
+    );
+    if !yes && !confirm(&tf("Replace {}?", &[&summary]))? {
+        output.message(t("Nothing changed."));
+        return Ok(());
+    }
+
+    let edits: Vec<(Uuid, Note)> = replacements
+        .into_iter()
+        .map(|r| (r.link.id, r.note))
+        .collect();
+    store
+        .update_notes(&edits)
+        .context(t("Failed to update notes"))?;
+
+    output.success(&tf("Replaced {}", &[&summary]));
     Ok(())
 }
 
@@ -131,21 +539,21 @@ fn parse_link_id(id: &str, store: &Store) -> Result<Uuid> {
     }
 
     // Try prefix match
-    let links = store.get_all_links()?;
+    let links = store.get_all_links(LinkSort::default())?;
     let matches: Vec<_> = links
         .iter()
         .filter(|l| l.id.to_string().starts_with(id))
         .collect();
 
     match matches.len() {
-        0 => bail!("No link found matching: {}", id),
+        0 => bail!(tf("No link found matching: {}", &[&id])),
         1 => Ok(matches[0].id),
         _ => {
-            eprintln!("Multiple links match '{}':", id);
+            eprintln!("{}", tf("Multiple links match '{}':", &[&id]));
             for link in &matches {
                 eprintln!("  {} - {}", link.id, link.title);
             }
-            bail!("Ambiguous ID. Please provide more characters.");
+            bail!(t("Ambiguous ID. Please provide more characters."));
         }
     }
 }
@@ -165,10 +573,10 @@ fn parse_note_id(id: &str, link: &rott_core::Link) -> Result<Uuid> {
         .collect();
 
     match matches.len() {
-        0 => bail!("No note found matching: {}", id),
+        0 => bail!(tf("No note found matching: {}", &[&id])),
         1 => Ok(matches[0].id),
         _ => {
-            eprintln!("Multiple notes match '{}':", id);
+            eprintln!("{}", tf("Multiple notes match '{}':", &[&id]));
             for note in &matches {
                 let preview = if note.body.len() > 30 {
                     format!("{}...", &note.body[..30])
@@ -177,7 +585,32 @@ fn parse_note_id(id: &str, link: &rott_core::Link) -> Result<Uuid> {
                 };
                 eprintln!("  {} - {}", &note.id.to_string()[..8], preview);
             }
-            bail!("Ambiguous ID. Please provide more characters.");
+            bail!(t("Ambiguous ID. Please provide more characters."));
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_replacements() {
+        let mut a = Link::new("https://a.example.com");
+        a.add_note(Note::new("Project Foo\nFoo is done, Foo ships"));
+        a.add_note(Note::new("Nothing to see"));
+        let mut b = Link::new("https://b.example.com");
+        b.add_note(Note::new("foo in lowercase"));
+        let links = vec![a.clone(), b];
+
+        let replacements = plan_replacements(&links, "Foo", "Bar");
+        assert_eq!(replacements.len(), 1);
+        assert_eq!(replacements[0].link.id, a.id);
+        assert_eq!(replacements[0].note.id, a.notes[0].id);
+        assert_eq!(replacements[0].occurrences, 3);
+        assert_eq!(
+            replacements[0].note.body,
+            "Project Bar\nBar is done, Bar ships"
+        );
+    }
+}
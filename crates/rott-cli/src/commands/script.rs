@@ -0,0 +1,90 @@
+//! Script command handlers
+//!
+//! Runs user scripts (see [`crate::script`]) and saves what they changed.
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+use rott_core::{Link, LinkSort, Store};
+
+use crate::i18n::{t, tf};
+use crate::output::Output;
+use crate::preview::{Change, ChangeKind, Preview};
+use crate::script::run_script;
+
+/// Run a script, then save its changes in one go
+///
+/// With `dry_run`, the changes are previewed but not saved.
+pub fn run(
+    store: &mut Store,
+    file: PathBuf,
+    args: Vec<String>,
+    dry_run: bool,
+    output: &Output,
+) -> Result<()> {
+    let source =
+        fs::read_to_string(&file).with_context(|| tf("Failed to read {}", &[&file.display()]))?;
+    let links = store.get_all_links(LinkSort::default())?;
+    let before = links.clone();
+
+    let changes = run_script(&source, links, args, |line| println!("{}", line))
+        .with_context(|| tf("Error in {}", &[&file.display()]))?;
+
+    if changes.is_empty() {
+        output.message(t("Script made no changes"));
+        return Ok(());
+    }
+
+    if dry_run {
+        let mut preview = Preview::new("Run {}")
+            .subject(file.display())
+            .count("saved", changes.updates.len())
+            .count("deleted", changes.deletes.len());
+        for link in &changes.updates {
+            let summary = format!("{} ({})", link.title, &link.id.to_string()[..8]);
+            let change = match before.iter().find(|l| l.id == link.id) {
+                Some(old) => Change::new(ChangeKind::Change, summary)
+                    .diff(describe_link(old), describe_link(link)),
+                None => Change::new(ChangeKind::Add, summary),
+            };
+            preview.push(change.link(link.id));
+        }
+        for id in &changes.deletes {
+            let title = before
+                .iter()
+                .find(|l| l.id == *id)
+                .map_or("", |l| l.title.as_str());
+            preview.push(
+                Change::new(
+                    ChangeKind::Delete,
+                    format!("{} ({})", title, &id.to_string()[..8]),
+                )
+                .link(*id),
+            );
+        }
+        return preview.print(output);
+    }
+
+    store
+        .update_links(&changes.updates, &changes.deletes)
+        .context(t("Failed to save the script's changes"))?;
+
+    output.success(&tf(
+        "Script saved {} link(s), deleted {}",
+        &[&changes.updates.len(), &changes.deletes.len()],
+    ));
+    Ok(())
+}
+
+/// The fields a script can change, one per line, for diffing
+fn describe_link(link: &Link) -> String {
+    let mut lines = vec![
+        format!("title: {}", link.title),
+        format!("description: {}", link.description.as_deref().unwrap_or("")),
+        format!("tags: {}", link.tags.join(", ")),
+    ];
+    lines.extend(link.notes.iter().map(|n| format!("note: {}", n.body)));
+    lines.join("\n")
+}
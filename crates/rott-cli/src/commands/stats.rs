@@ -0,0 +1,207 @@
+//! Reading statistics
+//!
+//! Summarizes reading sessions, how much has been written about saved
+//! links, how the links break down by language, and which are opened most.
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+
+use rott_core::language::{language_counts, language_name};
+use rott_core::reading::SessionLog;
+use rott_core::{Link, LinkSort, Store};
+
+use crate::i18n::{t, tf};
+use crate::output::{human_size, Output, OutputFormat};
+
+/// How many of the most opened links to show
+const MOST_OPENED: usize = 5;
+
+/// Summarize reading sessions over the last `days` days
+pub fn show(store: &Store, days: u32, output: &Output) -> Result<()> {
+    let now = Utc::now();
+    let stats = SessionLog::load(store.config())?.stats(now - Duration::days(days.into()), now);
+    let links = store.get_all_links(LinkSort::default())?;
+    let annotations = Annotations::of(&links);
+    let languages = language_breakdown(&links);
+    let most_opened = store.most_opened_links(MOST_OPENED)?;
+
+    match output.format {
+        OutputFormat::Json => {
+            let mut json = serde_json::to_value(&stats)?;
+            json["annotations"] = serde_json::json!({
+                "links": annotations.links,
+                "annotated": annotations.annotated,
+                "notes": annotations.notes,
+                "words": annotations.words,
+                "bytes": annotations.bytes,
+            });
+            json["languages"] = serde_json::json!(languages
+                .iter()
+                .map(|l| serde_json::json!({
+                    "language": l.language,
+                    "links": l.links,
+                    "unread": l.unread,
+                }))
+                .collect::<Vec<_>>());
+            json["most_opened"] = serde_json::json!(most_opened
+                .iter()
+                .map(|l| serde_json::json!({
+                    "id": l.id,
+                    "title": l.title,
+                    "url": l.url,
+                    "open_count": l.open_count,
+                }))
+                .collect::<Vec<_>>());
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        OutputFormat::Quiet => println!("{}", stats.articles),
+        OutputFormat::Human => {
+            let period = if days == 7 {
+                "this week".to_string()
+            } else {
+                format!("in the last {} days", days)
+            };
+            println!("{}", stats.summary(&period));
+            if stats.sessions > 0 {
+                println!(
+                    "{}",
+                    tf(
+                        "{} session(s), {} min reading in total",
+                        &[&stats.sessions, &stats.total_minutes]
+                    )
+                );
+            } else {
+                println!("{}", t("Start a reading session with :read in the TUI."));
+            }
+            if annotations.links > 0 {
+                println!();
+                println!(
+                    "{}",
+                    tf(
+                        "{} of {} link(s) have notes ({}%): {} note(s), {} words, {} written",
+                        &[
+                            &annotations.annotated,
+                            &annotations.links,
+                            &(annotations.annotated * 100 / annotations.links),
+                            &annotations.notes,
+                            &annotations.words,
+                            &human_size(annotations.bytes)
+                        ]
+                    )
+                );
+                if annotations.annotated > 0 {
+                    println!(
+                        "{}",
+                        tf(
+                            "{} words per annotated link on average",
+                            &[&(annotations.words / annotations.annotated)]
+                        )
+                    );
+                }
+            }
+            if !languages.is_empty() {
+                println!();
+                println!("{}", t("Links by language:"));
+                for language in &languages {
+                    let name = language
+                        .language
+                        .as_deref()
+                        .map(language_name)
+                        .unwrap_or(t("Unknown"));
+                    println!(
+                        "  {}",
+                        tf(
+                            "{} {} saved  {} unread",
+                            &[
+                                &format!("{:<12}", name),
+                                &format!("{:>5}", language.links),
+                                &format!("{:>5}", language.unread),
+                            ],
+                        )
+                    );
+                }
+            }
+            if !most_opened.is_empty() {
+                println!();
+                println!("{}", t("Most opened:"));
+                for link in &most_opened {
+                    println!("  {:>5}x  {}", link.open_count, link.title);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// How much has been written about saved links
+#[derive(Default)]
+struct Annotations {
+    links: usize,
+    /// Links with at least one note (the rest are bare bookmarks)
+    annotated: usize,
+    notes: usize,
+    /// Words across all notes
+    words: usize,
+    /// Bytes of descriptions and notes (see [`Link::content_size`])
+    bytes: usize,
+}
+
+impl Annotations {
+    fn of(links: &[Link]) -> Self {
+        let mut annotations = Self {
+            links: links.len(),
+            ..Self::default()
+        };
+        for link in links {
+            if !link.notes.is_empty() {
+                annotations.annotated += 1;
+            }
+            annotations.notes += link.notes.len();
+            annotations.words += link.note_word_count();
+            annotations.bytes += link.content_size();
+        }
+        annotations
+    }
+}
+
+/// Saved and unread links in one language
+struct LanguageCount {
+    /// Language code, or None for links with no detected language
+    language: Option<String>,
+    links: usize,
+    unread: usize,
+}
+
+/// Links per language, most common first, with undetected ones last
+///
+/// Empty if no link has a detected language.
+fn language_breakdown(links: &[Link]) -> Vec<LanguageCount> {
+    let counts = language_counts(links);
+    if counts.is_empty() {
+        return Vec::new();
+    }
+    let unread = |language: Option<&str>| {
+        links
+            .iter()
+            .filter(|l| l.language.as_deref() == language && l.opened_at.is_none())
+            .count()
+    };
+
+    let mut breakdown: Vec<LanguageCount> = counts
+        .into_iter()
+        .map(|(language, links)| LanguageCount {
+            unread: unread(Some(&language)),
+            language: Some(language),
+            links,
+        })
+        .collect();
+    let unknown = links.iter().filter(|l| l.language.is_none()).count();
+    if unknown > 0 {
+        breakdown.push(LanguageCount {
+            language: None,
+            links: unknown,
+            unread: unread(None),
+        });
+    }
+    breakdown
+}
@@ -1,15 +1,25 @@
 //! Status command handler
 
 use anyhow::Result;
+use chrono::{Local, Utc};
 
+use rott_core::maintenance::MaintenanceLog;
+use rott_core::sync::LastSync;
 use rott_core::Store;
 
-use crate::output::{Output, OutputFormat};
+use crate::i18n::{t, tf};
+use crate::output::{sync_summary, time_ago, Output, OutputFormat};
 
 /// Show status information
 pub fn show(store: &Store, output: &Output) -> Result<()> {
     let stats = store.storage_stats();
     let config = store.config();
+    // A damaged log only loses the schedule summary
+    let maintenance = MaintenanceLog::load(config)
+        .unwrap_or_default()
+        .status(config, Utc::now());
+    // Likewise, a damaged record only loses when this device last synced
+    let last_sync = LastSync::load(&config.last_sync_path()).unwrap_or_default();
 
     match output.format {
         OutputFormat::Json => {
@@ -20,6 +30,7 @@ pub fn show(store: &Store, output: &Output) -> Result<()> {
                     "root_url": store.root_url(),
                     "sync_enabled": config.sync_enabled,
                     "sync_url": config.sync_url,
+                    "last_sync": last_sync,
                     "storage": {
                         "document_exists": stats.document_exists,
                         "document_size": stats.document_size,
@@ -28,7 +39,18 @@ pub fn show(store: &Store, output: &Output) -> Result<()> {
                     "counts": {
                         "links": store.link_count().unwrap_or(0),
                         "notes": store.note_count().unwrap_or(0)
-                    }
+                    },
+                    "maintenance": maintenance.iter().map(|status| {
+                        serde_json::json!({
+                            "job": status.job.name(),
+                            "interval": status.interval,
+                            "last_run": status.last.as_ref().map(|r| r.last_run),
+                            "last_succeeded": status.last.as_ref().map(|r| r.succeeded),
+                            "last_message": status.last.as_ref().map(|r| &r.message),
+                            "next_due": status.next_due,
+                            "due": status.due
+                        })
+                    }).collect::<Vec<_>>()
                 })
             );
         }
@@ -36,33 +58,89 @@ pub fn show(store: &Store, output: &Output) -> Result<()> {
             println!("{}", store.root_id());
         }
         OutputFormat::Human => {
-            println!("ROTT Status");
+            println!("{}", t("ROTT Status"));
             println!("===========");
             println!();
-            println!("Root Document:");
-            println!("  ID:  {}", store.root_id());
-            println!("  URL: {}", store.root_url());
+            println!("{}", t("Root Document:"));
+            println!("  {}", tf("ID:  {}", &[&store.root_id()]));
+            println!("  {}", tf("URL: {}", &[&store.root_url()]));
             println!();
-            println!("Sync:");
+            println!("{}", t("Sync:"));
             println!(
-                "  Status: {}",
-                if config.sync_enabled {
-                    "enabled"
-                } else {
-                    "disabled"
-                }
+                "  {}",
+                tf(
+                    "Status: {}",
+                    &[&(if config.sync_enabled {
+                        t("enabled")
+                    } else {
+                        t("disabled")
+                    })]
+                )
             );
             if let Some(ref url) = config.sync_url {
-                println!("  Server: {}", url);
+                println!("  {}", tf("Server: {}", &[&url]));
+            }
+            match last_sync {
+                Some(last) => {
+                    println!(
+                        "  {}",
+                        tf(
+                            "Last synced {} ({})",
+                            &[
+                                &time_ago(last.at, Utc::now()),
+                                &last.at.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+                            ]
+                        )
+                    );
+                    println!("    {}", sync_summary(&last.progress));
+                }
+                None if config.sync_enabled => println!("  {}", t("Never synced")),
+                None => {}
             }
             println!();
-            println!("Storage:");
-            println!("  Location: {}", config.data_dir.display());
-            println!("  Size:     {}", stats.total_size_human());
+            println!("{}", t("Storage:"));
+            println!("  {}", tf("Location: {}", &[&config.data_dir.display()]));
+            println!("  {}", tf("Size:     {}", &[&stats.total_size_human()]));
             println!();
-            println!("Contents:");
-            println!("  Links: {}", store.link_count().unwrap_or(0));
-            println!("  Notes: {}", store.note_count().unwrap_or(0));
+            println!("{}", t("Contents:"));
+            println!("  {}", tf("Links: {}", &[&store.link_count().unwrap_or(0)]));
+            println!("  {}", tf("Notes: {}", &[&store.note_count().unwrap_or(0)]));
+            if !maintenance.is_empty() {
+                println!();
+                println!("{}", t("Maintenance:"));
+                for status in &maintenance {
+                    let last = match &status.last {
+                        Some(record) => {
+                            let at = record.last_run.with_timezone(&Local);
+                            let at = at.format("%Y-%m-%d %H:%M");
+                            if record.succeeded {
+                                tf("last run {}", &[&at])
+                            } else {
+                                tf("last run {} (failed)", &[&at])
+                            }
+                        }
+                        None => t("never run").to_string(),
+                    };
+                    let next = if status.due {
+                        t("due now").to_string()
+                    } else {
+                        tf(
+                            "next {}",
+                            &[&status
+                                .next_due
+                                .with_timezone(&Local)
+                                .format("%Y-%m-%d %H:%M")],
+                        )
+                    };
+                    println!(
+                        "  {:<11} {:<8} {}, {}",
+                        status.job.name(),
+                        status.interval.to_string(),
+                        last,
+                        next
+                    );
+                }
+            }
         }
     }
 
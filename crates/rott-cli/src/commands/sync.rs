@@ -2,32 +2,45 @@
 
 use std::path::PathBuf;
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
+use chrono::Local;
 
-use rott_core::sync::{SyncClient, SyncState};
-use rott_core::{Config, Store};
+use rott_core::sync::trace::{latest_trace, read_trace, TraceSummary};
+use rott_core::sync::{SyncClient, SyncLock, SyncState, SyncTrace};
+use rott_core::{Config, DocumentId, RottDocument, Store};
 
-use crate::output::Output;
+use crate::i18n::{t, tf};
+use crate::output::{human_size, sync_summary, Output, OutputFormat};
 
 /// Perform initial sync for pending sync state (after join)
 pub async fn initial_sync(config: &Config, output: &Output) -> Result<()> {
-    output.message("Performing initial sync to pull document from server...");
+    output.message(t("Performing initial sync to pull document from server..."));
 
     match Store::initial_sync(config).await {
         Ok(()) => {
-            output.success("Initial sync complete! Your data has been downloaded.");
-            output.message("You can now use rott normally.");
+            output.success(t("Initial sync complete! Your data has been downloaded."));
+            output.message(t("You can now use rott normally."));
             Ok(())
         }
         Err(e) => {
-            output.message(&format!("Initial sync failed: {}", e));
+            output.message(&tf("Initial sync failed: {}", &[&e]));
             Err(e)
         }
     }
 }
 
 /// Sync with the remote server
-pub async fn sync(store: &mut Store, config_path: Option<&PathBuf>, output: &Output) -> Result<()> {
+///
+/// Only one process syncs at a time (see [`SyncLock`]). If another is
+/// syncing, this skips, or with `wait` syncs once it has finished. With
+/// `trace` (or `trace_sync` in the config) the session is traced.
+pub async fn sync(
+    store: &mut Store,
+    config_path: Option<&PathBuf>,
+    wait: bool,
+    trace: bool,
+    output: &Output,
+) -> Result<()> {
     // Use CLI config path if provided, otherwise use store's config
     let config = match config_path {
         Some(path) => Config::load_with_cli_override(Some(path))?,
@@ -35,75 +48,176 @@ pub async fn sync(store: &mut Store, config_path: Option<&PathBuf>, output: &Out
     };
 
     if !config.sync_enabled {
-        bail!(
-            "Sync is not enabled. Enable it with:\n  \
+        bail!(t("Sync is not enabled. Enable it with:\n  \
              rott config set sync_enabled true\n  \
-             rott config set sync_url ws://your-server:3030"
-        );
+             rott config set sync_url ws://your-server:3030"));
     }
 
     let Some(ref sync_url) = config.sync_url else {
-        bail!(
-            "Sync URL not configured. Set it with:\n  \
-             rott config set sync_url ws://your-server:3030"
-        );
+        bail!(t("Sync URL not configured. Set it with:\n  \
+             rott config set sync_url ws://your-server:3030"));
+    };
+
+    let _lock = if wait {
+        let lock = match SyncLock::try_acquire(&config.store_dir())? {
+            Some(lock) => lock,
+            None => {
+                output.message(t("Waiting for another sync to finish..."));
+                SyncLock::acquire(&config.store_dir(), SyncLock::STALE_AFTER)
+                    .await?
+                    .context(t("Timed out waiting for another sync to finish"))?
+            }
+        };
+        // Pick up whatever the other sync saved
+        store.reload_and_merge()?;
+        lock
+    } else {
+        match SyncLock::try_acquire(&config.store_dir())? {
+            Some(lock) => lock,
+            None => {
+                output.message(t(
+                    "Sync already in progress, skipping (use --wait to wait for it)",
+                ));
+                return Ok(());
+            }
+        }
     };
 
-    output.message("Connecting to sync server...");
+    output.message(t("Connecting to sync server..."));
 
     // Create sync state with persistence
-    let sync_state_path = config.data_dir.join("sync_state.json");
+    let sync_state_path = config.sync_state_path();
     let sync_state = SyncState::with_path(sync_state_path).unwrap_or_else(|_| SyncState::new());
 
     let root_id = store.root_id();
 
     // Create sync client
-    let client = SyncClient::new(sync_url, root_id).with_sync_state(sync_state);
+    let trace = trace || config.trace_sync;
+    let client = traced(
+        SyncClient::new(sync_url, root_id)
+            .with_sync_state(sync_state)
+            .with_read_only(store.is_read_only())
+            .with_compression(config.sync_compression)
+            .with_last_sync_path(config.last_sync_path()),
+        &config,
+        trace,
+        &root_id,
+    );
 
-    output.message(&format!("Syncing document {}...", root_id));
+    output.message(&tf("Syncing document {}...", &[&root_id]));
 
     // Get shared document and sync
     let shared_doc = store.shared_document();
     let mut doc = shared_doc.lock().await;
-    match client.sync_once(&mut doc).await {
+    let result = client.sync_once(&mut doc).await;
+    if let Some(path) = client.trace_path() {
+        output.message(&tf("Sync trace written to {}", &[&path.display()]));
+    }
+    match result {
         Ok(updated) => {
             drop(doc); // Release lock before saving
             if updated {
                 // Save the updated document to disk
                 store.save()?;
-                output.success("Sync complete - document updated");
+                output.success(t("Sync complete - document updated"));
 
                 // Show new counts
                 let links = store.link_count()?;
                 let notes = store.note_count()?;
-                output.message(&format!("  Links: {}, Notes: {}", links, notes));
+                output.message(&format!(
+                    "  {}",
+                    tf("Links: {}, Notes: {}", &[&links, &notes])
+                ));
             } else {
-                output.success("Sync complete - already up to date");
+                output.success(t("Sync complete - already up to date"));
             }
+            output.message(&format!("  {}", sync_summary(&client.progress())));
         }
         Err(e) => {
-            output.message(&format!("Sync failed: {}", e));
+            output.message(&tf("Sync failed: {}", &[&e]));
             return Err(e);
         }
     }
 
+    sync_archives(store, sync_url, &config, trace, output).await
+}
+
+/// Sync each year's archive document
+///
+/// Only done by `rott sync`, not auto-sync: archives rarely change, so
+/// everyday syncs only carry the root document. Archives recorded by
+/// another device are downloaded here the first time.
+async fn sync_archives(
+    store: &mut Store,
+    sync_url: &str,
+    config: &Config,
+    trace: bool,
+    output: &Output,
+) -> Result<()> {
+    for (year, id) in store.archives()? {
+        let (mut archive, downloaded) = match store.load_archive(&id)? {
+            Some(archive) => (archive, true),
+            None => (RottDocument::empty_for_sync(id), false),
+        };
+        // Archives are synced whole; their sync state isn't kept
+        let client = traced(
+            SyncClient::new(sync_url, id)
+                .with_sync_state(SyncState::new())
+                .with_read_only(store.is_read_only())
+                .with_compression(config.sync_compression),
+            config,
+            trace,
+            &id,
+        );
+        match client.sync_once(&mut archive).await {
+            Ok(true) => {
+                store.save_archive(&mut archive)?;
+                if downloaded {
+                    output.message(&format!("  {}", tf("Archive {} updated", &[&year])));
+                } else {
+                    output.message(&format!("  {}", tf("Archive {} downloaded", &[&year])));
+                }
+            }
+            Ok(false) => {}
+            Err(e) => output.message(&format!(
+                "  {}",
+                tf("Archive {} failed to sync: {}", &[&year, &e])
+            )),
+        }
+    }
     Ok(())
 }
 
 /// Sync quietly (for auto-sync) - no output on success
-pub async fn sync_quiet(store: &mut Store, config: &Config) -> Result<()> {
+///
+/// Skipped if another process is syncing: the changes are already saved,
+/// and the next sync carries them.
+pub async fn sync_quiet(store: &mut Store, config: &Config, trace: bool) -> Result<()> {
     let Some(ref sync_url) = config.sync_url else {
         return Ok(());
     };
+    let Some(_lock) = SyncLock::try_acquire(&config.store_dir())? else {
+        tracing::info!("Sync already in progress, skipping");
+        return Ok(());
+    };
 
     // Create sync state with persistence
-    let sync_state_path = config.data_dir.join("sync_state.json");
+    let sync_state_path = config.sync_state_path();
     let sync_state = SyncState::with_path(sync_state_path).unwrap_or_else(|_| SyncState::new());
 
     let root_id = store.root_id();
 
     // Create sync client
-    let client = SyncClient::new(sync_url, root_id).with_sync_state(sync_state);
+    let client = traced(
+        SyncClient::new(sync_url, root_id)
+            .with_sync_state(sync_state)
+            .with_read_only(store.is_read_only())
+            .with_compression(config.sync_compression)
+            .with_last_sync_path(config.last_sync_path()),
+        config,
+        trace || config.trace_sync,
+        &root_id,
+    );
 
     // Get shared document and sync
     let shared_doc = store.shared_document();
@@ -118,3 +232,116 @@ pub async fn sync_quiet(store: &mut Store, config: &Config) -> Result<()> {
 
     Ok(())
 }
+
+/// Trace a client's session if asked to
+///
+/// A trace that can't be started is logged rather than failing the sync.
+fn traced(client: SyncClient, config: &Config, trace: bool, doc_id: &DocumentId) -> SyncClient {
+    if !trace {
+        return client;
+    }
+    match SyncTrace::create(&config.data_dir, doc_id) {
+        Ok(trace) => client.with_trace(trace),
+        Err(e) => {
+            tracing::warn!("Couldn't start a sync trace: {:#}", e);
+            client
+        }
+    }
+}
+
+/// Summarize the most recent sync trace
+pub fn debug_last(config: &Config, output: &Output) -> Result<()> {
+    let Some(path) = latest_trace(&config.data_dir)? else {
+        bail!(t("No sync traces yet. Trace a sync with:\n  \
+             rott sync --trace-sync\n\
+             or trace every sync with:\n  \
+             rott config set trace_sync true"));
+    };
+    let entries = read_trace(&path)?;
+    let Some(summary) = TraceSummary::of(&entries) else {
+        bail!(tf("Sync trace {} is empty", &[&path.display()]));
+    };
+
+    match output.format {
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "file": path,
+                "summary": summary,
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        OutputFormat::Quiet => println!("{}", path.display()),
+        OutputFormat::Human => {
+            let seconds =
+                (summary.ended_at - summary.started_at).num_milliseconds() as f64 / 1000.0;
+            let types: Vec<String> = summary
+                .message_types
+                .iter()
+                .map(|(kind, count)| format!("{} {}", kind, count))
+                .collect();
+            let heads = if summary.document_changed() {
+                format!(
+                    "{} -> {}",
+                    short_heads(&summary.heads_start),
+                    short_heads(&summary.heads_end)
+                )
+            } else {
+                tf("{} (unchanged)", &[&short_heads(&summary.heads_end)])
+            };
+
+            output.print_field("Trace", &path.display().to_string(), None);
+            output.print_field(
+                "Started",
+                &format!(
+                    "{} ({:.1}s)",
+                    summary
+                        .started_at
+                        .with_timezone(&Local)
+                        .format("%Y-%m-%d %H:%M:%S"),
+                    seconds
+                ),
+                None,
+            );
+            output.print_field(
+                "Sent",
+                &tf(
+                    "{} message(s), {}",
+                    &[&summary.sent, &human_size(summary.bytes_sent)],
+                ),
+                None,
+            );
+            output.print_field(
+                "Received",
+                &tf(
+                    "{} message(s), {}",
+                    &[&summary.received, &human_size(summary.bytes_received)],
+                ),
+                None,
+            );
+            if !types.is_empty() {
+                output.print_field("Messages", &types.join(", "), None);
+            }
+            output.print_field("Heads", &heads, None);
+            if !summary.events.is_empty() {
+                println!();
+                println!("{}", t("Events:"));
+                for event in &summary.events {
+                    println!("  {}", event);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Change hashes shortened for display ("(none)" for an empty document)
+fn short_heads(heads: &[String]) -> String {
+    if heads.is_empty() {
+        return "(none)".to_string();
+    }
+    heads
+        .iter()
+        .map(|hash| &hash[..hash.len().min(8)])
+        .collect::<Vec<_>>()
+        .join(",")
+}
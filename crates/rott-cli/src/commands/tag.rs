@@ -1,10 +1,16 @@
 //! Tag command handlers
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, Context, Result};
 
-use rott_core::Store;
+use rott_core::query::TagFilter;
+use rott_core::tag_tree::TagNode;
+use rott_core::validate::normalize_tag;
+use rott_core::visibility::TagVisibility;
+use rott_core::{LinkSort, Store};
 
-use crate::output::Output;
+use crate::editor::edit_text;
+use crate::i18n::{t, tf};
+use crate::output::{Output, OutputFormat};
 
 /// List all tags with usage counts
 pub fn list(store: &Store, output: &Output) -> Result<()> {
@@ -12,3 +18,260 @@ pub fn list(store: &Store, output: &Output) -> Result<()> {
     output.print_tags(&tags);
     Ok(())
 }
+
+/// List the links matching a combination of tags
+///
+/// With `ids_only`, prints just their IDs, one per line, for scripts.
+pub fn links(store: &Store, filter: &TagFilter, ids_only: bool, output: &Output) -> Result<()> {
+    if filter.is_empty() {
+        bail!(t("Give at least one of --all-of, --any-of, or --none-of"));
+    }
+    let mut links = store.get_all_links(store.config().default_sort)?;
+    links.retain(|link| filter.matches(link));
+
+    if ids_only {
+        for link in &links {
+            println!("{}", link.id);
+        }
+    } else {
+        output.print_links(&links);
+    }
+    Ok(())
+}
+
+/// Show tags as a tree, with link counts
+///
+/// A node's count includes the links tagged below it.
+pub fn tree(store: &Store, output: &Output) -> Result<()> {
+    let tree = store.tag_tree()?;
+    match output.format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&tree)?),
+        OutputFormat::Quiet => print_tags(&tree),
+        OutputFormat::Human => {
+            if tree.is_empty() {
+                println!("{}", t("No tags found."));
+            }
+            for node in &tree {
+                println!("{} ({})", node.name, node.total);
+                print_branches(&node.children, "");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Print every tag in a tree that links use, one per line
+fn print_tags(nodes: &[TagNode]) {
+    for node in nodes {
+        if node.count > 0 {
+            println!("{}", node.tag);
+        }
+        print_tags(&node.children);
+    }
+}
+
+/// Print the nodes below a tag, with tree connectors
+fn print_branches(nodes: &[TagNode], indent: &str) {
+    for (i, node) in nodes.iter().enumerate() {
+        let last = i + 1 == nodes.len();
+        let connector = if last { "└── " } else { "├── " };
+        println!("{}{}{} ({})", indent, connector, node.name, node.total);
+        let indent = format!("{}{}", indent, if last { "    " } else { "│   " });
+        print_branches(&node.children, &indent);
+    }
+}
+
+/// Remove descriptions and visibility marks of tags no link uses
+///
+/// Tags in `favorite_tags` or `triage_tags` that no link uses are reported
+/// but left in the config.
+pub fn prune(store: &mut Store, dry_run: bool, output: &Output) -> Result<()> {
+    let pruned = store.prune_tags(dry_run)?;
+    let used = store.get_all_tags()?;
+    let config = store.config();
+    let mut configured: Vec<String> = config
+        .favorite_tags
+        .iter()
+        .chain(&config.triage_tags)
+        .filter_map(|tag| normalize_tag(tag))
+        .filter(|tag| !used.contains(tag))
+        .collect();
+    configured.sort();
+    configured.dedup();
+
+    match output.format {
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "dry_run": dry_run,
+                "pruned": pruned,
+                "unused_in_config": configured,
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        OutputFormat::Quiet => {
+            for tag in &pruned {
+                println!("{}", tag);
+            }
+        }
+        OutputFormat::Human => {
+            if pruned.is_empty() {
+                println!("{}", t("No unused tags to prune"));
+            } else {
+                let verb = if dry_run {
+                    t("Would prune")
+                } else {
+                    t("Pruned")
+                };
+                println!("{}", tf("{} {} unused tag(s):", &[&verb, &pruned.len()]));
+                for tag in &pruned {
+                    println!("  {}", tag);
+                }
+            }
+            if !configured.is_empty() {
+                println!(
+                    "{}",
+                    tf(
+                        "Unused, but set in favorite_tags or triage_tags (left alone): {}",
+                        &[&configured.join(", ")]
+                    )
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Set or clear a tag's description
+///
+/// Without text (and without `clear`), opens the current description in
+/// $EDITOR. Saving an empty description clears it.
+pub fn describe(
+    store: &mut Store,
+    tag: String,
+    text: Option<String>,
+    clear: bool,
+    output: &Output,
+) -> Result<()> {
+    let description = if clear {
+        None
+    } else {
+        match text {
+            Some(text) => Some(text),
+            None => {
+                let current = store.tag_description(&tag)?.unwrap_or_default();
+                let initial = format!(
+                    "<!-- {} -->\n{}",
+                    tf("Description of tag: {} (Markdown)", &[&tag]),
+                    current
+                );
+                let edited = edit_text(&initial).context(t("Failed to edit description"))?;
+                Some(
+                    edited
+                        .lines()
+                        .filter(|line| !line.starts_with("<!--"))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                )
+            }
+        }
+    };
+
+    store.set_tag_description(&tag, description.as_deref())?;
+    if store.tag_description(&tag)?.is_some() {
+        output.success(&tf("Described tag '{}'", &[&tag]));
+    } else {
+        output.success(&tf("Cleared description of tag '{}'", &[&tag]));
+    }
+    Ok(())
+}
+
+/// Show a tag's page: its description, then its links
+pub fn show(store: &Store, tag: String, output: &Output) -> Result<()> {
+    let description = store.tag_description(&tag)?;
+    let visibility = store.tag_visibility()?.of_tag(&tag);
+    let links = store.get_links_by_tag(&tag, LinkSort::default())?;
+
+    match output.format {
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "tag": tag,
+                "description": description,
+                "visibility": visibility,
+                "links": links,
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        OutputFormat::Quiet => output.print_links(&links),
+        OutputFormat::Human => {
+            match visibility {
+                Some(visibility) => println!("# {} ({})", tag, visibility),
+                None => println!("# {}", tag),
+            }
+            if let Some(description) = description {
+                println!();
+                println!("{}", description);
+            }
+            println!();
+            output.print_links(&links);
+        }
+    }
+    Ok(())
+}
+
+/// Mark a tag private or public, show a tag's mark, or list marked tags
+///
+/// `default` removes the mark.
+pub fn visibility(
+    store: &mut Store,
+    tag: Option<String>,
+    visibility: Option<String>,
+    output: &Output,
+) -> Result<()> {
+    let Some(tag) = tag else {
+        let marks = store.tag_visibility()?;
+        match output.format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(marks.tags())?),
+            OutputFormat::Quiet => {
+                for tag in marks.tags().keys() {
+                    println!("{}", tag);
+                }
+            }
+            OutputFormat::Human => {
+                if marks.tags().is_empty() {
+                    println!("{}", t("No tags are marked private or public"));
+                }
+                for (tag, visibility) in marks.tags() {
+                    println!("{:<8} {}", visibility, tag);
+                }
+            }
+        }
+        return Ok(());
+    };
+
+    let Some(visibility) = visibility else {
+        let mark = store.tag_visibility()?.of_tag(&tag);
+        match output.format {
+            OutputFormat::Json => {
+                println!("{}", serde_json::json!({ "tag": tag, "visibility": mark }))
+            }
+            _ => println!("{}", mark.map_or("default", TagVisibility::as_str)),
+        }
+        return Ok(());
+    };
+
+    let mark = match visibility.trim().to_lowercase().as_str() {
+        "default" | "none" => None,
+        other => Some(other.parse::<TagVisibility>().map_err(|_| {
+            anyhow!(tf(
+                "Unknown visibility '{}' (use private, public, or default)",
+                &[&other]
+            ))
+        })?),
+    };
+    store.set_tag_visibility(&tag, mark)?;
+    match mark {
+        Some(mark) => output.success(&tf("Marked tag '{}' {}", &[&tag, &mark])),
+        None => output.success(&tf("Removed the visibility mark from tag '{}'", &[&tag])),
+    }
+    Ok(())
+}
@@ -0,0 +1,64 @@
+//! Offline copies of page text
+//!
+//! `rott link archive-content <id>` (and, with `archive_content_on_save`,
+//! saving a link) fetches the page and keeps its readable text (see
+//! [`metadata::readable_text`]), so the link is still worth something once
+//! the page changes or disappears. The text is stored as the link's
+//! `content.txt` attachment (see [`rott_core::attachments`]); archiving
+//! again replaces it, and maintenance gc removes the old copy later.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use rott_core::attachments::AttachmentStore;
+use rott_core::{Config, Link, Store};
+use uuid::Uuid;
+
+use crate::metadata;
+
+/// Attachment name of a link's archived page text
+pub const CONTENT_ATTACHMENT: &str = "content.txt";
+
+/// Fetch a link's page and attach its readable text
+///
+/// Returns the archived text's path.
+pub async fn archive(store: &mut Store, id: Uuid) -> Result<PathBuf> {
+    let url = store
+        .get_link(id)?
+        .with_context(|| format!("Link not found: {}", id))?
+        .url;
+    let text = metadata::fetch_readable_text(&url)
+        .await
+        .with_context(|| format!("Failed to archive {}", url))?;
+
+    let attachments = AttachmentStore::from_config(store.config());
+    let hash = attachments.put(text.as_bytes())?;
+    // Read again: the link may have changed during the fetch
+    let mut link = store
+        .get_link(id)?
+        .with_context(|| format!("Link not found: {}", id))?;
+    link.attachments
+        .insert(CONTENT_ATTACHMENT.to_string(), hash.clone());
+    store.update_link(&link)?;
+    Ok(attachments.path(&hash))
+}
+
+/// Archive a newly saved link's page text, if configured to
+///
+/// A failed fetch doesn't fail saving the link; the error is returned for
+/// the caller to report.
+pub async fn archive_on_save(store: &mut Store, id: Uuid) -> Option<Result<PathBuf>> {
+    if !store.config().archive_content_on_save {
+        return None;
+    }
+    Some(archive(store, id).await)
+}
+
+/// A link's archived page text, if it has some on this device
+pub fn archived_text(config: &Config, link: &Link) -> Result<Option<String>> {
+    let Some(hash) = link.attachments.get(CONTENT_ATTACHMENT) else {
+        return Ok(None);
+    };
+    let content = AttachmentStore::from_config(config).get(hash)?;
+    Ok(content.map(|bytes| String::from_utf8_lossy(&bytes).into_owned()))
+}
@@ -0,0 +1,93 @@
+//! Line-based text diffs
+//!
+//! A small longest-common-subsequence diff, good enough for note bodies.
+
+/// One line of a diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    /// Present in both versions
+    Same(&'a str),
+    /// Only in the old version
+    Removed(&'a str),
+    /// Only in the new version
+    Added(&'a str),
+}
+
+/// Diff two texts line by line
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // lcs[i][j] = length of the LCS of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut result = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            result.push(DiffLine::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            result.push(DiffLine::Removed(old[i]));
+            i += 1;
+        } else {
+            result.push(DiffLine::Added(new[j]));
+            j += 1;
+        }
+    }
+    result.extend(old[i..].iter().map(|l| DiffLine::Removed(l)));
+    result.extend(new[j..].iter().map(|l| DiffLine::Added(l)));
+    result
+}
+
+/// The lines that differ, as `- old` and `+ new`, for printing
+pub fn changed_lines(old: &str, new: &str) -> Vec<String> {
+    diff_lines(old, new)
+        .into_iter()
+        .filter_map(|line| match line {
+            DiffLine::Same(_) => None,
+            DiffLine::Removed(text) => Some(format!("- {}", text)),
+            DiffLine::Added(text) => Some(format!("+ {}", text)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines() {
+        let diff = diff_lines("a\nb\nc", "a\nB\nc\nd");
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine::Same("a"),
+                DiffLine::Removed("b"),
+                DiffLine::Added("B"),
+                DiffLine::Same("c"),
+                DiffLine::Added("d"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_from_empty() {
+        assert_eq!(diff_lines("", "new"), vec![DiffLine::Added("new")]);
+        assert!(diff_lines("same", "same")
+            .iter()
+            .all(|l| matches!(l, DiffLine::Same(_))));
+        assert!(changed_lines("same", "same").is_empty());
+        assert_eq!(changed_lines("a\nb", "a\nc"), vec!["- b", "+ c"]);
+    }
+}
@@ -8,6 +8,8 @@ use std::fs;
 use std::io::{self, Write};
 use std::process::Command;
 
+use crate::i18n::{t, tf};
+
 /// Open content in the user's preferred editor
 ///
 /// Uses $EDITOR, $VISUAL, or falls back to common editors.
@@ -19,26 +21,26 @@ pub fn edit_text(initial_content: &str) -> Result<String> {
     let temp_path = temp_dir.join(format!("rott_edit_{}.md", std::process::id()));
 
     fs::write(&temp_path, initial_content)
-        .with_context(|| format!("Failed to create temp file: {:?}", temp_path))?;
+        .with_context(|| tf("Failed to create temp file: {}", &[&temp_path.display()]))?;
 
     // Open editor
     let status = Command::new(&editor)
         .arg(&temp_path)
         .status()
-        .with_context(|| format!("Failed to run editor: {}", editor))?;
+        .with_context(|| tf("Failed to run editor: {}", &[&editor]))?;
 
     if !status.success() {
         // Clean up temp file
         let _ = fs::remove_file(&temp_path);
-        bail!(
+        bail!(tf(
             "Editor '{}' exited with non-zero status. Check that your editor is configured correctly.",
-            editor
-        );
+            &[&editor]
+        ));
     }
 
     // Read edited content
     let content = fs::read_to_string(&temp_path)
-        .with_context(|| format!("Failed to read edited file: {:?}", temp_path))?;
+        .with_context(|| tf("Failed to read edited file: {}", &[&temp_path.display()]))?;
 
     // Clean up
     let _ = fs::remove_file(&temp_path);
@@ -70,10 +72,8 @@ fn find_editor() -> Result<String> {
         }
     }
 
-    bail!(
-        "No editor found. Set $EDITOR environment variable.\n\
-         Example: export EDITOR=nano"
-    )
+    bail!(t("No editor found. Set $EDITOR environment variable.\n\
+         Example: export EDITOR=nano"))
 }
 
 /// Check if a command exists in PATH
@@ -87,22 +87,23 @@ fn command_exists(cmd: &str) -> bool {
 
 /// Prompt for confirmation
 ///
-/// Returns true if user confirms, false otherwise.
-/// In non-interactive mode (no TTY), returns false.
+/// Returns true if user confirms (with "y", "yes", or the current
+/// locale's answer), false otherwise. In non-interactive mode (no TTY),
+/// returns false.
 pub fn confirm(prompt: &str) -> Result<bool> {
     // Check if stdin is a TTY
     if !atty::is(atty::Stream::Stdin) {
         return Ok(false);
     }
 
-    print!("{} [y/N] ", prompt);
+    print!("{} {} ", prompt, t("[y/N]"));
     io::stdout().flush()?;
 
     let mut input = String::new();
     io::stdin().read_line(&mut input)?;
 
     let input = input.trim().to_lowercase();
-    Ok(input == "y" || input == "yes")
+    Ok(input == "y" || input == "yes" || input == t("y") || input == t("yes"))
 }
 
 #[cfg(test)]
@@ -0,0 +1,322 @@
+//! Browser history reading
+//!
+//! Reads visit history from Firefox and Chrome profile databases so that
+//! frequently-visited pages can be suggested for saving.
+//!
+//! Browser history is stored in SQLite databases. Rather than linking SQLite
+//! into the binary, the `sqlite3` command-line tool is used to query a copy
+//! of the database (browsers keep the live file locked while running).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::Deserialize;
+
+/// Supported browsers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Browser {
+    Firefox,
+    Chrome,
+}
+
+/// A page from browser history with its visit frequency
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub url: String,
+    pub title: Option<String>,
+    pub visits: u32,
+    pub last_visit: DateTime<Utc>,
+}
+
+/// Row shape returned by `sqlite3 -json`
+#[derive(Debug, Deserialize)]
+struct HistoryRow {
+    url: String,
+    title: Option<String>,
+    visits: u32,
+    last_visit: i64,
+}
+
+/// Microseconds between 1601-01-01 (WebKit epoch) and 1970-01-01
+const WEBKIT_EPOCH_OFFSET_MICROS: i64 = 11_644_473_600 * 1_000_000;
+
+/// Read frequently-visited pages from a browser's history
+///
+/// Returns pages visited at least `min_visits` times since `since`,
+/// ordered by visit count (most visited first). Only http(s) URLs are returned.
+pub fn read_history(
+    browser: Browser,
+    profile: Option<&Path>,
+    min_visits: u32,
+    since: DateTime<Utc>,
+) -> Result<Vec<HistoryEntry>> {
+    let db_path = match profile {
+        Some(p) => history_db_in_profile(browser, p),
+        None => find_history_db(browser)?,
+    };
+
+    if !db_path.exists() {
+        bail!("History database not found: {}", db_path.display());
+    }
+
+    // Copy the database so we don't contend with the browser's lock
+    let temp_dir = tempfile::tempdir().context("Failed to create a temporary directory")?;
+    let temp_path = copy_database(&db_path, temp_dir.path())?;
+
+    let query = build_query(browser, min_visits, since);
+    let rows = run_sqlite_json(&temp_path, &query)?;
+    Ok(rows
+        .into_iter()
+        .filter(|row| row.url.starts_with("http://") || row.url.starts_with("https://"))
+        .map(|row| HistoryEntry {
+            last_visit: to_datetime(browser, row.last_visit),
+            url: row.url,
+            title: row.title.filter(|t| !t.trim().is_empty()),
+            visits: row.visits,
+        })
+        .collect())
+}
+
+/// Copy a SQLite database into `dir`, with its write-ahead log
+///
+/// A running browser keeps recent visits in the `-wal` file until it
+/// checkpoints them; without it the copy would miss them. Returns the
+/// copy's path.
+fn copy_database(db_path: &Path, dir: &Path) -> Result<PathBuf> {
+    let name = db_path
+        .file_name()
+        .context("History database path has no file name")?;
+    let copy = dir.join(name);
+    fs::copy(db_path, &copy)
+        .with_context(|| format!("Failed to copy history database: {:?}", db_path))?;
+
+    for suffix in ["-wal", "-shm"] {
+        let mut sidecar = db_path.as_os_str().to_owned();
+        sidecar.push(suffix);
+        let sidecar = PathBuf::from(sidecar);
+        if sidecar.exists() {
+            let mut target = copy.as_os_str().to_owned();
+            target.push(suffix);
+            fs::copy(&sidecar, PathBuf::from(target))
+                .with_context(|| format!("Failed to copy {:?}", sidecar))?;
+        }
+    }
+    Ok(copy)
+}
+
+/// Parse a relative time window like `90d`, `12w`, or `48h`
+pub fn parse_since(value: &str) -> Result<Duration> {
+    let value = value.trim();
+    let Some((unit_idx, _)) = value.char_indices().last() else {
+        bail!("Time window cannot be empty. Use e.g. 90d, 12w, 48h");
+    };
+    let (num, unit) = value.split_at(unit_idx);
+    let amount: i64 = num
+        .parse()
+        .with_context(|| format!("Invalid time window '{}'. Use e.g. 90d, 12w, 48h", value))?;
+
+    match unit {
+        "h" => Ok(Duration::hours(amount)),
+        "d" => Ok(Duration::days(amount)),
+        "w" => Ok(Duration::weeks(amount)),
+        _ => bail!("Invalid time window '{}'. Use e.g. 90d, 12w, 48h", value),
+    }
+}
+
+/// Build the SQL query for a browser's history schema
+fn build_query(browser: Browser, min_visits: u32, since: DateTime<Utc>) -> String {
+    let since_micros = since.timestamp_micros();
+    match browser {
+        Browser::Firefox => format!(
+            "SELECT p.url AS url, p.title AS title, COUNT(v.id) AS visits, \
+             MAX(v.visit_date) AS last_visit \
+             FROM moz_places p JOIN moz_historyvisits v ON v.place_id = p.id \
+             WHERE v.visit_date >= {} \
+             GROUP BY p.id HAVING COUNT(v.id) >= {} \
+             ORDER BY visits DESC;",
+            since_micros, min_visits
+        ),
+        Browser::Chrome => format!(
+            "SELECT u.url AS url, u.title AS title, COUNT(v.id) AS visits, \
+             MAX(v.visit_time) AS last_visit \
+             FROM urls u JOIN visits v ON v.url = u.id \
+             WHERE v.visit_time >= {} \
+             GROUP BY u.id HAVING COUNT(v.id) >= {} \
+             ORDER BY visits DESC;",
+            since_micros + WEBKIT_EPOCH_OFFSET_MICROS,
+            min_visits
+        ),
+    }
+}
+
+/// Convert a browser timestamp (microseconds) to a UTC datetime
+fn to_datetime(browser: Browser, micros: i64) -> DateTime<Utc> {
+    let unix_micros = match browser {
+        Browser::Firefox => micros,
+        Browser::Chrome => micros - WEBKIT_EPOCH_OFFSET_MICROS,
+    };
+    Utc.timestamp_micros(unix_micros)
+        .single()
+        .unwrap_or_else(Utc::now)
+}
+
+/// Run a query with the sqlite3 CLI and parse its JSON output
+fn run_sqlite_json(db_path: &Path, query: &str) -> Result<Vec<HistoryRow>> {
+    let output = Command::new("sqlite3")
+        .arg("-readonly")
+        .arg("-json")
+        .arg(db_path)
+        .arg(query)
+        .output()
+        .context("Failed to run sqlite3. Install the sqlite3 command-line tool.")?;
+
+    if !output.status.success() {
+        bail!(
+            "sqlite3 failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if stdout.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    serde_json::from_str(&stdout).context("Failed to parse sqlite3 output")
+}
+
+/// Get the history database path within a specific profile directory
+fn history_db_in_profile(browser: Browser, profile: &Path) -> PathBuf {
+    match browser {
+        Browser::Firefox => profile.join("places.sqlite"),
+        Browser::Chrome => profile.join("History"),
+    }
+}
+
+/// Locate the default profile's history database
+fn find_history_db(browser: Browser) -> Result<PathBuf> {
+    match browser {
+        Browser::Firefox => {
+            let profiles_dir =
+                firefox_profiles_dir().context("Could not determine Firefox profile directory")?;
+            let entries = fs::read_dir(&profiles_dir)
+                .with_context(|| format!("Failed to read Firefox profiles: {:?}", profiles_dir))?;
+
+            // Prefer the default-release profile, then any profile with history
+            let mut candidates: Vec<PathBuf> = entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.join("places.sqlite").exists())
+                .collect();
+            candidates.sort_by_key(|p| {
+                let name = p.file_name().and_then(|n| n.to_str()).unwrap_or("");
+                !name.ends_with(".default-release")
+            });
+
+            candidates
+                .first()
+                .map(|p| p.join("places.sqlite"))
+                .context("No Firefox profile with history found. Use --profile to specify one.")
+        }
+        Browser::Chrome => {
+            let profile =
+                chrome_profile_dir().context("Could not determine Chrome profile directory")?;
+            Ok(profile.join("History"))
+        }
+    }
+}
+
+fn firefox_profiles_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    {
+        dirs::data_dir().map(|d| d.join("Firefox").join("Profiles"))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        dirs::config_dir().map(|d| d.join("Mozilla").join("Firefox").join("Profiles"))
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        dirs::home_dir().map(|d| d.join(".mozilla").join("firefox"))
+    }
+}
+
+fn chrome_profile_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    {
+        dirs::data_dir().map(|d| d.join("Google").join("Chrome").join("Default"))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        dirs::data_local_dir().map(|d| {
+            d.join("Google")
+                .join("Chrome")
+                .join("User Data")
+                .join("Default")
+        })
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        dirs::config_dir().map(|d| d.join("google-chrome").join("Default"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_since() {
+        assert_eq!(parse_since("90d").unwrap(), Duration::days(90));
+        assert_eq!(parse_since("2w").unwrap(), Duration::weeks(2));
+        assert_eq!(parse_since("48h").unwrap(), Duration::hours(48));
+        assert!(parse_since("90").is_err());
+        assert!(parse_since("d").is_err());
+        assert!(parse_since("5y").is_err());
+        assert!(parse_since("").is_err());
+    }
+
+    #[test]
+    fn test_chrome_timestamp_conversion() {
+        let now = Utc.timestamp_micros(1_700_000_000_000_000).unwrap();
+        let webkit = now.timestamp_micros() + WEBKIT_EPOCH_OFFSET_MICROS;
+        assert_eq!(to_datetime(Browser::Chrome, webkit), now);
+        assert_eq!(to_datetime(Browser::Firefox, now.timestamp_micros()), now);
+    }
+
+    #[test]
+    fn test_build_query_uses_threshold() {
+        let since = Utc.timestamp_opt(0, 0).unwrap();
+        let query = build_query(Browser::Firefox, 5, since);
+        assert!(query.contains("moz_places"));
+        assert!(query.contains(">= 5"));
+
+        let query = build_query(Browser::Chrome, 3, since);
+        assert!(query.contains("FROM urls"));
+        assert!(query.contains(&WEBKIT_EPOCH_OFFSET_MICROS.to_string()));
+    }
+
+    #[test]
+    fn test_copy_database_brings_its_wal() {
+        let profile = tempfile::tempdir().unwrap();
+        let db = profile.path().join("places.sqlite");
+        fs::write(&db, "db").unwrap();
+        fs::write(profile.path().join("places.sqlite-wal"), "wal").unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let copy = copy_database(&db, dir.path()).unwrap();
+        assert_eq!(fs::read_to_string(&copy).unwrap(), "db");
+        assert_eq!(
+            fs::read_to_string(dir.path().join("places.sqlite-wal")).unwrap(),
+            "wal"
+        );
+        assert!(!dir.path().join("places.sqlite-shm").exists());
+    }
+}
@@ -0,0 +1,313 @@
+//! Hypothes.is annotation sync
+//!
+//! Bridges a link's notes with annotations on the same page in
+//! [Hypothes.is](https://web.hypothes.is/), using the API token set with
+//! `rott config set hypothesis_token <token>`:
+//!
+//! - **push** creates a page note for each note not already annotated
+//! - **pull** adds the user's annotations (highlights with their quote) as
+//!   notes, skipping ones already there
+//!
+//! New links pull automatically when a token is set. Pushed annotations are
+//! private (visible only to the token's user). Notes and annotations are
+//! matched by text, so running either direction twice doesn't duplicate
+//! anything.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use rott_core::{Config, Link, Note};
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Hypothes.is API root
+const API_URL: &str = "https://api.hypothes.is/api";
+
+/// Request timeout in seconds
+const TIMEOUT: u64 = 10;
+
+/// Most annotations fetched for one page
+const SEARCH_LIMIT: usize = 200;
+
+/// Client for the Hypothes.is API
+pub struct Hypothesis {
+    client: reqwest::Client,
+    token: String,
+}
+
+/// An annotation as returned by the API
+#[derive(Debug, Clone, Deserialize)]
+pub struct Annotation {
+    pub id: String,
+    #[serde(default)]
+    pub text: String,
+    pub created: DateTime<Utc>,
+    #[serde(default)]
+    pub target: Vec<Target>,
+}
+
+/// What part of the page an annotation is anchored to
+#[derive(Debug, Clone, Deserialize)]
+pub struct Target {
+    #[serde(default)]
+    pub selector: Vec<Selector>,
+}
+
+/// One way of locating an annotation's anchor
+#[derive(Debug, Clone, Deserialize)]
+pub struct Selector {
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Quoted text (TextQuoteSelector)
+    #[serde(default)]
+    pub exact: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Profile {
+    userid: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SearchResults {
+    rows: Vec<Annotation>,
+}
+
+impl Annotation {
+    /// The highlighted text, if this annotation is a highlight
+    pub fn quote(&self) -> Option<&str> {
+        self.target
+            .iter()
+            .flat_map(|t| &t.selector)
+            .find(|s| s.kind == "TextQuoteSelector")
+            .and_then(|s| s.exact.as_deref())
+            .map(str::trim)
+            .filter(|q| !q.is_empty())
+    }
+
+    /// Note body for this annotation: the quote (as a blockquote), then the text
+    pub fn note_body(&self) -> String {
+        let text = self.text.trim();
+        match self.quote() {
+            Some(quote) => {
+                let quoted: Vec<String> = quote.lines().map(|l| format!("> {}", l)).collect();
+                if text.is_empty() {
+                    quoted.join("\n")
+                } else {
+                    format!("{}\n\n{}", quoted.join("\n"), text)
+                }
+            }
+            None => text.to_string(),
+        }
+    }
+
+    /// The annotation as a note (dated when it was annotated)
+    pub fn to_note(&self) -> Note {
+        let mut note = Note::new(self.note_body());
+        note.created_at = self.created;
+        note
+    }
+}
+
+/// Annotation text for a note (its title, if any, then its body)
+pub fn annotation_text(note: &Note) -> String {
+    match note.title {
+        Some(ref title) => format!("{}\n\n{}", title, note.body.trim()),
+        None => note.body.trim().to_string(),
+    }
+}
+
+/// Annotations not yet on a link, as notes
+pub fn new_notes(link: &Link, annotations: &[Annotation]) -> Vec<Note> {
+    annotations
+        .iter()
+        .filter(|a| {
+            let body = a.note_body();
+            !body.is_empty()
+                && !link
+                    .notes
+                    .iter()
+                    .any(|n| n.body.trim() == body || annotation_text(n) == body)
+        })
+        .map(Annotation::to_note)
+        .collect()
+}
+
+/// A link's notes that have no matching annotation yet
+pub fn unpushed_notes<'a>(link: &'a Link, annotations: &[Annotation]) -> Vec<&'a Note> {
+    link.notes
+        .iter()
+        .filter(|n| {
+            let text = annotation_text(n);
+            !annotations
+                .iter()
+                .any(|a| a.text.trim() == text || a.note_body() == n.body.trim())
+        })
+        .collect()
+}
+
+impl Hypothesis {
+    /// Create a client if a token is configured
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        let Some(ref token) = config.hypothesis_token else {
+            return Ok(None);
+        };
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(TIMEOUT))
+            .user_agent("ROTT/1.0")
+            .build()?;
+        Ok(Some(Self {
+            client,
+            token: token.clone(),
+        }))
+    }
+
+    /// The token's user ID (`acct:name@hypothes.is`)
+    pub async fn user_id(&self) -> Result<String> {
+        let profile: Profile = self
+            .client
+            .get(format!("{}/profile", API_URL))
+            .bearer_auth(&self.token)
+            .send()
+            .await?
+            .error_for_status()
+            .context("Hypothes.is request failed")?
+            .json()
+            .await?;
+        match profile.userid {
+            Some(userid) => Ok(userid),
+            None => bail!("Hypothes.is didn't accept the token (check hypothesis_token)"),
+        }
+    }
+
+    /// The user's annotations on a page
+    pub async fn annotations(&self, url: &str, user: &str) -> Result<Vec<Annotation>> {
+        let limit = SEARCH_LIMIT.to_string();
+        let results: SearchResults = self
+            .client
+            .get(format!("{}/search", API_URL))
+            .bearer_auth(&self.token)
+            .query(&[("uri", url), ("user", user), ("limit", &limit)])
+            .send()
+            .await?
+            .error_for_status()
+            .context("Hypothes.is request failed")?
+            .json()
+            .await?;
+        Ok(results.rows)
+    }
+
+    /// Create a private page note on a page
+    pub async fn create(
+        &self,
+        url: &str,
+        user: &str,
+        text: &str,
+        tags: &[String],
+    ) -> Result<Annotation> {
+        let only_me = [user];
+        let body = serde_json::json!({
+            "uri": url,
+            "text": text,
+            "tags": tags,
+            "group": "__world__",
+            "permissions": {
+                "read": only_me,
+                "update": only_me,
+                "delete": only_me,
+                "admin": only_me,
+            },
+        });
+        let annotation = self
+            .client
+            .post(format!("{}/annotations", API_URL))
+            .bearer_auth(&self.token)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()
+            .context("Hypothes.is request failed")?
+            .json()
+            .await?;
+        Ok(annotation)
+    }
+}
+
+/// Fetch the user's annotations for a link being saved, as notes
+///
+/// Returns no notes if no token is configured or the request fails (saving
+/// the link shouldn't depend on Hypothes.is being reachable).
+pub async fn fetch_notes(config: &Config, link: &Link) -> Vec<Note> {
+    let fetch = async {
+        let Some(hypothesis) = Hypothesis::from_config(config)? else {
+            return Ok(Vec::new());
+        };
+        let user = hypothesis.user_id().await?;
+        let annotations = hypothesis.annotations(&link.url, &user).await?;
+        Ok::<_, anyhow::Error>(new_notes(link, &annotations))
+    };
+    match fetch.await {
+        Ok(notes) => notes,
+        Err(e) => {
+            tracing::warn!("Couldn't fetch Hypothes.is annotations: {:#}", e);
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn annotation(text: &str, quote: Option<&str>) -> Annotation {
+        let json = serde_json::json!({
+            "id": "abc",
+            "text": text,
+            "created": "2024-03-01T12:00:00.000000+00:00",
+            "target": [{
+                "source": "https://example.com",
+                "selector": quote.map(|q| vec![
+                    serde_json::json!({"type": "TextPositionSelector", "start": 1, "end": 5}),
+                    serde_json::json!({"type": "TextQuoteSelector", "exact": q}),
+                ]).unwrap_or_default(),
+            }],
+        });
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_annotation_to_note() {
+        let highlight = annotation("Worth rereading", Some("Line one\nline two"));
+        assert_eq!(highlight.quote(), Some("Line one\nline two"));
+        assert_eq!(
+            highlight.note_body(),
+            "> Line one\n> line two\n\nWorth rereading"
+        );
+        assert_eq!(
+            highlight.to_note().created_at.to_rfc3339(),
+            "2024-03-01T12:00:00+00:00"
+        );
+
+        let page_note = annotation("Just a comment", None);
+        assert_eq!(page_note.quote(), None);
+        assert_eq!(page_note.note_body(), "Just a comment");
+    }
+
+    #[test]
+    fn test_matching_skips_duplicates() {
+        let mut link = Link::new("https://example.com");
+        link.add_note(Note::with_title("Summary", "Short and good."));
+        link.add_note(Note::new("> quoted\n\nmine"));
+
+        let annotations = vec![
+            annotation("Summary\n\nShort and good.", None),
+            annotation("mine", Some("quoted")),
+            annotation("Something new", None),
+        ];
+        let notes = new_notes(&link, &annotations);
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].body, "Something new");
+
+        assert!(unpushed_notes(&link, &annotations).is_empty());
+        assert_eq!(unpushed_notes(&link, &annotations[2..]).len(), 2);
+    }
+}
@@ -0,0 +1,293 @@
+//! Translated user-facing text
+//!
+//! Text is written in English where it's used and passed through [`t`],
+//! which looks it up in the current locale's catalog and falls back to the
+//! English. Text with values in it uses `{}` placeholders, filled in order by
+//! [`tf`]:
+//!
+//! ```text
+//! app.set_status(tf("Added '{}'", &[&link.title]));
+//! ```
+//!
+//! The locale is set once at startup ([`init`]) from the `locale` config key
+//! (or `ROTT_LOCALE`), or else from `LC_ALL`, `LC_MESSAGES` or `LANG`.
+//! Locales without a catalog get English.
+//!
+//! Command help comes from clap's doc comments, so it can't be wrapped;
+//! [`localize_command`] looks each help text up in the catalog instead,
+//! keyed by the text clap shows (doc comment lines joined by spaces,
+//! without the final period).
+//!
+//! To translate a new string, wrap it in `t`/`tf` and add it to each
+//! catalog (`i18n/<code>.rs`). To add a language, add a catalog and a
+//! [`Locale`] variant.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+mod de;
+
+/// A language the CLI and TUI can be shown in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    English,
+    German,
+}
+
+impl Locale {
+    /// Every locale, English first
+    pub const ALL: &'static [Locale] = &[Locale::English, Locale::German];
+
+    /// The language code, as written in the config
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::German => "de",
+        }
+    }
+
+    /// Parse a language code or POSIX locale ("de", "de-AT", "de_DE.UTF-8")
+    ///
+    /// "C" and "POSIX" are English. Returns None for other languages.
+    pub fn parse(tag: &str) -> Option<Self> {
+        let tag = tag.trim();
+        if tag == "C" || tag == "POSIX" || tag.starts_with("C.") {
+            return Some(Locale::English);
+        }
+        let language = tag
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        Self::ALL.iter().copied().find(|l| l.code() == language)
+    }
+
+    /// The locale from the environment, as POSIX programs pick it
+    ///
+    /// The first of `LC_ALL`, `LC_MESSAGES` and `LANG` that's set decides.
+    pub fn from_env() -> Option<Self> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::parse(&value))
+    }
+
+    /// The locale to use: the config's if set, else the environment's,
+    /// else English
+    pub fn resolve(configured: Option<&str>) -> Self {
+        configured
+            .and_then(Self::parse)
+            .or_else(Self::from_env)
+            .unwrap_or_default()
+    }
+
+    /// Translations from English, by English text
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::English => &[],
+            Locale::German => de::MESSAGES,
+        }
+    }
+}
+
+/// The current locale's translations, set by [`init`]
+static CATALOG: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+
+/// Set the locale for the rest of the process
+///
+/// Only the first call has an effect. Text translated before it is English.
+pub fn init(locale: Locale) {
+    CATALOG.get_or_init(|| locale.catalog().iter().copied().collect());
+}
+
+/// `text` in the current locale (the English if it has no translation)
+pub fn t(text: &'static str) -> &'static str {
+    translate(text)
+}
+
+/// Like [`t`], for text only known at runtime, such as a label passed in
+/// by the caller
+pub fn translate(text: &str) -> &str {
+    lookup(text).unwrap_or(text)
+}
+
+/// Translate the help of a command, its arguments and its subcommands
+///
+/// Text without a translation is left as it is.
+pub fn localize_command(command: clap::Command) -> clap::Command {
+    let about = command
+        .get_about()
+        .and_then(|about| translate_help(&about.to_string(), lookup));
+    let long_about = command
+        .get_long_about()
+        .and_then(|about| translate_help(&about.to_string(), lookup));
+    let mut command = command.mut_args(|mut arg| {
+        if let Some(help) = arg
+            .get_help()
+            .and_then(|help| translate_help(&help.to_string(), lookup))
+        {
+            arg = arg.help(help);
+        }
+        if let Some(help) = arg
+            .get_long_help()
+            .and_then(|help| translate_help(&help.to_string(), lookup))
+        {
+            arg = arg.long_help(help);
+        }
+        arg
+    });
+    if let Some(about) = about {
+        command = command.about(about);
+    }
+    if let Some(long_about) = long_about {
+        command = command.long_about(long_about);
+    }
+    command.mut_subcommands(localize_command)
+}
+
+/// The current locale's translation of text only known at runtime
+fn lookup(text: &str) -> Option<&'static str> {
+    CATALOG
+        .get()
+        .and_then(|messages| messages.get(text).copied())
+}
+
+/// Help text with each paragraph translated, or None if none of them is
+///
+/// A paragraph is also found by its translation without the final period,
+/// since clap's one-line help drops it.
+fn translate_help(text: &str, lookup: impl Fn(&str) -> Option<&'static str>) -> Option<String> {
+    let mut translated = false;
+    let paragraphs: Vec<String> = text
+        .split("\n\n")
+        .map(|paragraph| {
+            let found = lookup(paragraph).map(str::to_string).or_else(|| {
+                let sentence = paragraph.strip_suffix('.')?;
+                lookup(sentence).map(|translation| format!("{}.", translation))
+            });
+            translated |= found.is_some();
+            found.unwrap_or_else(|| paragraph.to_string())
+        })
+        .collect();
+    translated.then(|| paragraphs.join("\n\n"))
+}
+
+/// `text` in the current locale, with its `{}` placeholders filled by `args`
+pub fn tf(text: &'static str, args: &[&dyn Display]) -> String {
+    fill(t(text), args)
+}
+
+/// Replace each `{}` in `template` with the next of `args`
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut args = args.iter();
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(at) = rest.find("{}") {
+        out.push_str(&rest[..at]);
+        match args.next() {
+            Some(arg) => out.push_str(&arg.to_string()),
+            None => out.push_str("{}"),
+        }
+        rest = &rest[at + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_locales() {
+        assert_eq!(Locale::parse("de"), Some(Locale::German));
+        assert_eq!(Locale::parse("de_DE.UTF-8"), Some(Locale::German));
+        assert_eq!(Locale::parse("DE-at"), Some(Locale::German));
+        assert_eq!(Locale::parse("en_GB.UTF-8"), Some(Locale::English));
+        assert_eq!(Locale::parse("C.UTF-8"), Some(Locale::English));
+        assert_eq!(Locale::parse("POSIX"), Some(Locale::English));
+        assert_eq!(Locale::parse("fr_FR"), None);
+        assert_eq!(Locale::parse(""), None);
+
+        assert_eq!(Locale::resolve(Some("de")), Locale::German);
+    }
+
+    #[test]
+    fn test_untranslated_text_is_english() {
+        // Tests don't call init, so everything is English
+        assert_eq!(t("Description"), "Description");
+        assert_eq!(tf("Added '{}'", &[&"Rust"]), "Added 'Rust'");
+
+        let german: HashMap<_, _> = Locale::German.catalog().iter().copied().collect();
+        assert_eq!(german["Description"], "Beschreibung");
+        assert!(!german.contains_key("Tags"));
+    }
+
+    #[test]
+    fn test_fill() {
+        assert_eq!(fill("Added '{}'", &[&"Rust"]), "Added 'Rust'");
+        assert_eq!(fill("{} of {}", &[&1, &3]), "1 of 3");
+        assert_eq!(fill("{} and {}", &[&1]), "1 and {}");
+        assert_eq!(fill("none", &[&1]), "none");
+    }
+
+    #[test]
+    fn test_translate_help() {
+        let german: HashMap<_, _> = Locale::German.catalog().iter().copied().collect();
+        let lookup = |text: &str| german.get(text).copied();
+        assert_eq!(
+            translate_help("Manage links", lookup).as_deref(),
+            Some("Links verwalten")
+        );
+        assert_eq!(
+            translate_help("Manage links.\n\nNot translated", lookup).as_deref(),
+            Some("Links verwalten.\n\nNot translated")
+        );
+        assert_eq!(translate_help("Not translated", lookup), None);
+    }
+
+    #[test]
+    fn test_command_help_is_translated() {
+        use clap::CommandFactory;
+
+        fn missing(command: &clap::Command, catalog: &HashMap<&str, &str>) -> Vec<String> {
+            let mut texts: Vec<String> = command
+                .get_about()
+                .into_iter()
+                .chain(command.get_arguments().filter_map(|arg| arg.get_help()))
+                .map(|text| text.to_string())
+                .filter(|text| !catalog.contains_key(text.as_str()))
+                .collect();
+            for subcommand in command.get_subcommands() {
+                texts.extend(missing(subcommand, catalog));
+            }
+            texts
+        }
+
+        for locale in &Locale::ALL[1..] {
+            let catalog: HashMap<_, _> = locale.catalog().iter().copied().collect();
+            let missing = missing(&crate::Cli::command(), &catalog);
+            assert!(missing.is_empty(), "{:?} lacks help: {:?}", locale, missing);
+        }
+    }
+
+    #[test]
+    fn test_catalogs_match_english() {
+        for locale in Locale::ALL {
+            let mut seen = std::collections::HashSet::new();
+            for (english, translated) in locale.catalog() {
+                assert!(seen.insert(english), "{:?} twice in {:?}", english, locale);
+                assert_eq!(
+                    english.matches("{}").count(),
+                    translated.matches("{}").count(),
+                    "placeholders differ for {:?} in {:?}",
+                    english,
+                    locale
+                );
+            }
+        }
+    }
+}
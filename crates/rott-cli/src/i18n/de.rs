@@ -0,0 +1,2251 @@
+//! German
+
+pub(super) const MESSAGES: &[(&str, &str)] = &[
+    // Help overlay: categories
+    ("Navigation", "Navigation"),
+    ("Links", "Links"),
+    ("Notes (Detail pane)", "Notizen (Detailbereich)"),
+    ("Search and commands", "Suche und Befehle"),
+    ("Macros", "Makros"),
+    ("General", "Allgemein"),
+    // Help overlay: actions
+    ("Move down/up", "Nach unten/oben"),
+    ("Jump to first item", "Zum ersten Eintrag"),
+    ("Jump to last item", "Zum letzten Eintrag"),
+    ("Move to left/right pane", "Zum linken/rechten Bereich"),
+    ("Next pane", "Nächster Bereich"),
+    ("Previous pane", "Vorheriger Bereich"),
+    ("Open link / apply filter", "Link öffnen / Filter anwenden"),
+    (
+        "Expand filter group / toggle item group",
+        "Filtergruppe aufklappen / Gruppe umschalten",
+    ),
+    (
+        "Collapse/expand the selected group",
+        "Gewählte Gruppe zu-/aufklappen",
+    ),
+    ("Collapse/expand all groups", "Alle Gruppen zu-/aufklappen"),
+    (
+        "Cycle the sort order (updated/newest/oldest/title/domain)",
+        "Sortierung wechseln (geändert/neueste/älteste/Titel/Domain)",
+    ),
+    ("Add link", "Link hinzufügen"),
+    ("Edit link", "Link bearbeiten"),
+    ("Edit tags", "Tags bearbeiten"),
+    ("Add note", "Notiz hinzufügen"),
+    ("Delete link", "Link löschen"),
+    ("Undo the last edit", "Letzte Änderung rückgängig machen"),
+    (
+        "Redo the last undone edit",
+        "Rückgängig gemachte Änderung wiederherstellen",
+    ),
+    (
+        "Select previous/next note",
+        "Vorherige/nächste Notiz wählen",
+    ),
+    (
+        "Focus previous/next section of a long note",
+        "Vorherigen/nächsten Abschnitt einer langen Notiz fokussieren",
+    ),
+    (
+        "Expand/collapse the focused section (all if none)",
+        "Fokussierten Abschnitt auf-/zuklappen (ohne Fokus alle)",
+    ),
+    ("Move note up/down", "Notiz nach oben/unten verschieben"),
+    ("Pin/unpin note", "Notiz anheften/lösen"),
+    ("Reply to note", "Auf Notiz antworten"),
+    ("Edit note", "Notiz bearbeiten"),
+    ("Load more notes", "Weitere Notizen laden"),
+    (
+        "Read the archived text (resumes where you stopped)",
+        "Archivierten Text lesen (setzt an der letzten Stelle fort)",
+    ),
+    (
+        "Focus next/previous URL in note",
+        "Nächste/vorherige URL der Notiz fokussieren",
+    ),
+    ("Open the focused URL", "Fokussierte URL öffnen"),
+    (
+        "Save the focused URL as a link (:add-related <url>)",
+        "Fokussierte URL als Link speichern (:add-related <url>)",
+    ),
+    ("Filter view", "Ansicht filtern"),
+    ("Command mode", "Befehlsmodus"),
+    (
+        "Search (author:name, has:paywall, lang:de)",
+        "Suchen (author:name, has:paywall, lang:de)",
+    ),
+    (
+        "Group by day/domain/tag/published/none",
+        "Gruppieren nach day/domain/tag/published/none",
+    ),
+    (
+        "Start a reading session on unread links",
+        "Lesesitzung mit ungelesenen Links starten",
+    ),
+    ("End the reading session", "Lesesitzung beenden"),
+    ("This week's reading", "Gelesenes dieser Woche"),
+    (
+        "Tag untagged links with 1-9 (Esc ends)",
+        "Links ohne Tags mit 1-9 taggen (Esc beendet)",
+    ),
+    ("Triage", "Sichten"),
+    (
+        "Describe the selected tag (empty clears)",
+        "Gewählten Tag beschreiben (leer entfernt)",
+    ),
+    ("Lock screen", "Bildschirm sperren"),
+    ("Take the guided tour", "Geführte Tour starten"),
+    (
+        "Record a macro into register a–z",
+        "Makro in Register a–z aufnehmen",
+    ),
+    (
+        "Replay a macro (3@a three times)",
+        "Makro abspielen (3@a dreimal)",
+    ),
+    (
+        "Replay the last macro again",
+        "Letztes Makro erneut abspielen",
+    ),
+    ("Show this help", "Diese Hilfe anzeigen"),
+    ("Sync now", "Jetzt synchronisieren"),
+    ("Device settings", "Geräteeinstellungen"),
+    ("Quit", "Beenden"),
+    // Help overlay: frame
+    ("Keyboard Shortcuts", "Tastenkürzel"),
+    (
+        "Keyboard Shortcuts ({}-{} of {})",
+        "Tastenkürzel ({}-{} von {})",
+    ),
+    ("No matching keys", "Keine passenden Tasten"),
+    (
+        "Matching \"{}\" · Esc clear · / search again",
+        "Treffer für \"{}\" · Esc leeren · / erneut suchen",
+    ),
+    (
+        "j/k scroll · PgUp/PgDn page · / search · other keys close",
+        "j/k blättern · Bild↑/Bild↓ Seite · / suchen · andere Tasten schließen",
+    ),
+    // Filters pane
+    ("Filters", "Filter"),
+    ("Favorites", "Favoriten"),
+    ("Recent", "Neueste"),
+    ("Untagged", "Ohne Tags"),
+    ("Unread", "Ungelesen"),
+    ("Needs Attention", "Braucht Aufmerksamkeit"),
+    ("Expired", "Abgelaufen"),
+    ("By Tag...", "Nach Tag..."),
+    ("By Author...", "Nach Autor..."),
+    // Items pane
+    ("Items ({})", "Einträge ({})"),
+    ("Items ({}) by {}", "Einträge ({}) nach {}"),
+    ("day", "Datum"),
+    ("domain", "Domain"),
+    ("tag", "Tag"),
+    ("published", "Veröffentlichung"),
+    ("recently updated", "zuletzt geändert"),
+    ("newest first", "neueste zuerst"),
+    ("oldest first", "älteste zuerst"),
+    ("by title", "nach Titel"),
+    ("by domain", "nach Domain"),
+    // Link details (TUI and CLI)
+    ("Detail", "Details"),
+    ("Title", "Titel"),
+    ("URL", "URL"),
+    ("Saved as", "Gespeichert"),
+    ("Description", "Beschreibung"),
+    ("Author", "Autor"),
+    ("Via", "Über"),
+    ("Source", "Quelle"),
+    ("Published", "Erschienen"),
+    ("Language", "Sprache"),
+    ("Expires", "Läuft ab"),
+    ("Created", "Erstellt"),
+    ("Updated", "Geändert"),
+    ("Access", "Zugang"),
+    ("Content", "Inhalt"),
+    ("Size", "Größe"),
+    ("Health", "Zustand"),
+    (
+        "paywall or login required",
+        "Bezahlschranke oder Anmeldung nötig",
+    ),
+    ("changed since saved", "seit dem Speichern geändert"),
+    (
+        "changed since saved (checked {})",
+        "seit dem Speichern geändert (geprüft {})",
+    ),
+    ("No notes", "Keine Notizen"),
+    ("Notes ({})", "Notizen ({})"),
+    (
+        "{} more notes (m to load more)",
+        "{} weitere Notizen (m lädt mehr)",
+    ),
+    ("Archived page text", "Archivierter Seitentext"),
+    (
+        "Select a link to view details",
+        "Link wählen, um Details zu sehen",
+    ),
+    // Status bar
+    ("Adding link...", "Link wird hinzugefügt..."),
+    (
+        "a:add  t:tag  n:note  e:edit  d:del  u:undo  /:filter  ?:help  q:quit",
+        "a:neu  t:tag  n:notiz  e:bearb.  d:lösch.  u:zurück  /:filter  ?:hilfe  q:ende",
+    ),
+    ("recording @{}  {}", "Aufnahme @{}  {}"),
+    (
+        "reading ({} read, {} min)  {}",
+        "Lesen ({} gelesen, {} Min.)  {}",
+    ),
+    (
+        "{} other device(s) online  {}",
+        "{} weitere(s) Gerät(e) online  {}",
+    ),
+    (
+        "{} other device(s) online, last change from {} at {}  {}",
+        "{} weitere(s) Gerät(e) online, letzte Änderung von {} um {}  {}",
+    ),
+    ("({} matches)", "({} Treffer)"),
+    ("synced", "synchron"),
+    ("syncing", "synchronisiert"),
+    ("offline", "offline"),
+    ("no sync", "kein Sync"),
+    ("sync error", "Sync-Fehler"),
+    ("{} {} ({} changes)", "{} {} ({} Änderungen)"),
+    // Status messages
+    ("Opened '{}'", "'{}' geöffnet"),
+    (
+        "No archived text (archive it with `rott link archive-content`)",
+        "Kein archivierter Text (archivieren mit `rott link archive-content`)",
+    ),
+    ("Resumed at {}%", "Fortgesetzt bei {} %"),
+    (
+        "j/k scroll · Space/PgUp page · q close",
+        "j/k blättern · Leertaste/Bild↑ Seite · q schließen",
+    ),
+    ("[{}% read]", "[{} % gelesen]"),
+    ("Opened {}", "{} geöffnet"),
+    ("Failed to open: {}", "Öffnen fehlgeschlagen: {}"),
+    (
+        "Deleted '{}'. Press u to undo",
+        "'{}' gelöscht. u macht es rückgängig",
+    ),
+    ("Undid {}", "Rückgängig gemacht: {}"),
+    ("Redid {}", "Wiederhergestellt: {}"),
+    ("Nothing to undo", "Nichts rückgängig zu machen"),
+    ("Nothing to redo", "Nichts wiederherzustellen"),
+    ("Added '{}'", "'{}' hinzugefügt"),
+    ("Tags updated", "Tags aktualisiert"),
+    ("Reply added", "Antwort hinzugefügt"),
+    ("Note added", "Notiz hinzugefügt"),
+    ("Note updated", "Notiz aktualisiert"),
+    ("{}, edited", "{}, bearbeitet"),
+    ("No URLs in this note", "Keine URLs in dieser Notiz"),
+    (
+        "This note isn't shown in sections",
+        "Diese Notiz wird nicht in Abschnitten angezeigt",
+    ),
+    ("({} lines)", "({} Zeilen)"),
+    ("Found {} results", "{} Ergebnisse gefunden"),
+    ("Select a link first", "Zuerst einen Link wählen"),
+    ("Grouping off", "Gruppierung aus"),
+    ("Grouped by {}", "Gruppiert nach {}"),
+    ("Sorted: {}", "Sortiert: {}"),
+    ("Unknown command: {}", "Unbekannter Befehl: {}"),
+    // Lock screen
+    ("Locked", "Gesperrt"),
+    (
+        "Enter passphrase to unlock:",
+        "Passphrase zum Entsperren eingeben:",
+    ),
+    ("Ctrl+C to quit", "Strg+C zum Beenden"),
+    // Device panel
+    ("Device Settings (Ctrl+D)", "Geräteeinstellungen (Strg+D)"),
+    ("Device Information", "Geräteinformationen"),
+    ("Root Document ID", "Stammdokument-ID"),
+    ("Sync Server", "Sync-Server"),
+    ("Not configured", "Nicht eingerichtet"),
+    ("Sync Status", "Sync-Status"),
+    ("Connected", "Verbunden"),
+    ("Syncing", "Synchronisiert"),
+    ("Offline", "Offline"),
+    ("Disabled", "Deaktiviert"),
+    ("Error", "Fehler"),
+    ("Last Synced", "Zuletzt synchronisiert"),
+    ("Never", "Nie"),
+    ("Copy ID to clipboard", "ID in die Zwischenablage kopieren"),
+    ("Close", "Schließen"),
+    (
+        "Use this ID to set up ROTT on other devices",
+        "Mit dieser ID ROTT auf anderen Geräten einrichten",
+    ),
+    ("Press any key to dismiss", "Beliebige Taste zum Schließen"),
+    // Tour
+    ("◀ Filters pane (left)", "◀ Filterbereich (links)"),
+    ("◀ Links pane (middle)", "◀ Linkbereich (Mitte)"),
+    ("Detail pane (right) ▶", "Detailbereich (rechts) ▶"),
+    ("Enter finish", "Enter beenden"),
+    ("→ next", "→ weiter"),
+    ("{} · ← back · Esc skip", "{} · ← zurück · Esc überspringen"),
+    ("Tour step {}: {}. {}", "Tour-Schritt {}: {}. {}"),
+    ("Welcome to ROTT", "Willkommen bei ROTT"),
+    (
+        "ROTT keeps your links and notes on this device and syncs them to your others. \
+         The sample links are tagged 'demo'; delete them whenever you like.",
+        "ROTT speichert deine Links und Notizen auf diesem Gerät und synchronisiert sie \
+         mit deinen anderen. Die Beispiellinks haben den Tag 'demo'; lösche sie, wann \
+         du willst.",
+    ),
+    (
+        "The left pane narrows the list: Favorites, Recent, Untagged, Unread, \
+         and links by tag or author. Enter applies a filter; Space expands Tags \
+         and Authors.",
+        "Der linke Bereich grenzt die Liste ein: Favoriten, Neueste, Ohne Tags, \
+         Ungelesen und Links nach Tag oder Autor. Enter wendet einen Filter an; \
+         Leertaste klappt Tags und Autoren auf.",
+    ),
+    (
+        "The middle pane lists links. j/k move, Enter opens in your browser, \
+         a adds a link, t edits tags, and d deletes (u undoes).",
+        "Der mittlere Bereich listet Links. j/k bewegen, Enter öffnet im Browser, \
+         a fügt einen Link hinzu, t bearbeitet Tags und d löscht (u macht es \
+         rückgängig).",
+    ),
+    ("Details and notes", "Details und Notizen"),
+    (
+        "The right pane shows the selected link and its notes. n adds a note, \
+         [ and ] select notes, and p pins one to the top.",
+        "Der rechte Bereich zeigt den gewählten Link und seine Notizen. n fügt eine \
+         Notiz hinzu, [ und ] wählen Notizen und p heftet eine oben an.",
+    ),
+    (
+        "/ filters the current view as you type. : runs a command, such as \
+         :search author:kleppmann or :group tag.",
+        "/ filtert die aktuelle Ansicht beim Tippen. : führt einen Befehl aus, etwa \
+         :search author:kleppmann oder :group tag.",
+    ),
+    ("That's it", "Das war's"),
+    (
+        "Press ? for every key, Ctrl+S to sync, and q to quit. \
+         Run :tour to see this again.",
+        "? zeigt alle Tasten, Strg+S synchronisiert und q beendet. \
+         :tour zeigt diese Tour erneut.",
+    ),
+    // CLI output
+    ("No links found.", "Keine Links gefunden."),
+    (
+        "No links need attention.",
+        "Keine Links brauchen Aufmerksamkeit.",
+    ),
+    ("Notes for: {} - {}", "Notizen zu: {} - {}"),
+    ("No notes on this link.", "Keine Notizen zu diesem Link."),
+    // Command help (clap)
+    (
+        "ROTT - Local-first links and notes management",
+        "ROTT - Links und Notizen, lokal zuerst",
+    ),
+    ("Path to config file", "Pfad zur Konfigurationsdatei"),
+    ("Output as JSON", "Ausgabe als JSON"),
+    (
+        "Quiet mode - minimal output",
+        "Stiller Modus - minimale Ausgabe",
+    ),
+    (
+        "Verbose output (-v info, -vv debug, -vvv trace)",
+        "Ausführliche Ausgabe (-v Info, -vv Debug, -vvv Trace)",
+    ),
+    (
+        "Print how long each startup phase took (config, load, first query)",
+        "Anzeigen, wie lange jede Startphase gedauert hat (Konfiguration, Laden, erste Abfrage)",
+    ),
+    (
+        "Use the collection on another machine (ssh://[user@]host[:port][/path/to/rott])",
+        "Die Sammlung auf einem anderen Rechner verwenden \
+         (ssh://[user@]host[:port][/pfad/zu/rott])",
+    ),
+    (
+        "Write each sync message to a trace file (see `rott sync debug last`)",
+        "Jede Sync-Nachricht in eine Trace-Datei schreiben (siehe `rott sync debug last`)",
+    ),
+    ("Start the TUI interface", "Die TUI starten"),
+    (
+        "Initialize ROTT (first-time setup)",
+        "ROTT einrichten (erste Einrichtung)",
+    ),
+    (
+        "Create a new identity (skip interactive prompt)",
+        "Eine neue Identität anlegen (ohne Rückfrage)",
+    ),
+    (
+        "Join an existing identity by providing root document ID",
+        "Einer bestehenden Identität über die ID des Stammdokuments beitreten",
+    ),
+    (
+        "Create a new identity seeded with sample links and a TUI tour",
+        "Eine neue Identität mit Beispiellinks und einer TUI-Tour anlegen",
+    ),
+    (
+        "With --join: only pull and read the document, never change it",
+        "Mit --join: das Dokument nur abrufen und lesen, nie ändern",
+    ),
+    ("Device identity management", "Geräteidentität verwalten"),
+    (
+        "Create, list, or switch between collections",
+        "Sammlungen anlegen, auflisten oder wechseln",
+    ),
+    ("Manage links", "Links verwalten"),
+    (
+        "Work with notes across all links",
+        "Mit Notizen über alle Links hinweg arbeiten",
+    ),
+    (
+        "Save a link with a capture alias's tags and note template",
+        "Einen Link mit den Tags und der Notizvorlage eines Capture-Alias speichern",
+    ),
+    (
+        "Capture alias (defined under [captures.<name>] in the config)",
+        "Capture-Alias (in der Konfiguration unter [captures.<name>] definiert)",
+    ),
+    ("URL to save", "Zu speichernde URL"),
+    (
+        "Values for the note template, as key=value",
+        "Werte für die Notizvorlage, als schlüssel=wert",
+    ),
+    (
+        "Save URLs found in the tmux buffer or the primary selection instead",
+        "Stattdessen URLs aus dem tmux-Puffer oder der primären Auswahl speichern",
+    ),
+    (
+        "Tags for the links saved from the selection",
+        "Tags für die aus der Auswahl gespeicherten Links",
+    ),
+    (
+        "Import links from external sources",
+        "Links aus externen Quellen importieren",
+    ),
+    ("Export the collection", "Die Sammlung exportieren"),
+    (
+        "Export the collection as it was at these document heads (comma-separated, as written \
+         by --heads-file)",
+        "Die Sammlung so exportieren, wie sie bei diesen Dokument-Heads war (durch Kommas \
+         getrennt, wie von --heads-file geschrieben)",
+    ),
+    (
+        "Write the document heads the export was generated from to this file",
+        "Die Dokument-Heads, aus denen der Export erzeugt wurde, in diese Datei schreiben",
+    ),
+    (
+        "Search titles, URLs, tags, and notes with a regular expression",
+        "Titel, URLs, Tags und Notizen mit einem regulären Ausdruck durchsuchen",
+    ),
+    ("Pattern to search for", "Zu suchendes Muster"),
+    (
+        "Case-insensitive matching",
+        "Groß-/Kleinschreibung ignorieren",
+    ),
+    (
+        "Only print IDs of matching links",
+        "Nur die IDs passender Links ausgeben",
+    ),
+    (
+        "List all tags, show them as a tree, or prune unused ones",
+        "Alle Tags auflisten, als Baum zeigen oder unbenutzte entfernen",
+    ),
+    (
+        "Describe a tag or show its page",
+        "Einen Tag beschreiben oder seine Seite zeigen",
+    ),
+    ("List all authors", "Alle Autoren auflisten"),
+    (
+        "Push notes to or pull annotations from Hypothes.is",
+        "Notizen zu Hypothes.is senden oder Anmerkungen von dort holen",
+    ),
+    (
+        "Show or set configuration",
+        "Konfiguration anzeigen oder setzen",
+    ),
+    (
+        "Show status (root doc ID, sync status)",
+        "Status anzeigen (ID des Stammdokuments, Sync-Status)",
+    ),
+    (
+        "Keep running and show the status again whenever the document changes",
+        "Weiterlaufen und den Status bei jeder Änderung des Dokuments erneut zeigen",
+    ),
+    (
+        "Check the document for records that can't be read in full",
+        "Das Dokument auf Einträge prüfen, die sich nicht vollständig lesen lassen",
+    ),
+    (
+        "Undo the last edit (from the CLI or the TUI)",
+        "Die letzte Änderung rückgängig machen (aus CLI oder TUI)",
+    ),
+    (
+        "Answer requests from `rott --remote` (run over SSH), or serve the web viewer or the \
+         JSON API",
+        "Anfragen von `rott --remote` beantworten (über SSH) oder den Web-Viewer bzw. die \
+         JSON-API bereitstellen",
+    ),
+    (
+        "Read requests from stdin and answer on stdout",
+        "Anfragen von stdin lesen und auf stdout beantworten",
+    ),
+    (
+        "Summarize reading sessions (articles read, median minutes per article)",
+        "Lesesitzungen zusammenfassen (gelesene Artikel, Median der Minuten pro Artikel)",
+    ),
+    (
+        "Number of days to summarize",
+        "Anzahl der zusammenzufassenden Tage",
+    ),
+    (
+        "Lock now, or manage the privacy lock passphrase",
+        "Jetzt sperren oder die Passphrase der Privatsperre verwalten",
+    ),
+    (
+        "Enter the privacy lock passphrase to access data",
+        "Die Passphrase der Privatsperre eingeben, um auf die Daten zuzugreifen",
+    ),
+    (
+        "Back up or restore everything (document, identity, config)",
+        "Alles sichern oder wiederherstellen (Dokument, Identität, Konfiguration)",
+    ),
+    (
+        "Bring selected links and notes back from a backup",
+        "Ausgewählte Links und Notizen aus einer Sicherung zurückholen",
+    ),
+    (
+        "Backup archive or saved document to restore from",
+        "Sicherungsarchiv oder gespeichertes Dokument, aus dem wiederhergestellt wird",
+    ),
+    (
+        "Link to restore (full ID or prefix, repeatable)",
+        "Wiederherzustellender Link (volle ID oder Präfix, wiederholbar)",
+    ),
+    (
+        "Restore every link with this tag (repeatable)",
+        "Jeden Link mit diesem Tag wiederherstellen (wiederholbar)",
+    ),
+    (
+        "Run automation scripts against the collection",
+        "Automatisierungsskripte auf der Sammlung ausführen",
+    ),
+    (
+        "Move old links into per-year archives, or bring them back",
+        "Alte Links in Jahresarchive verschieben oder zurückholen",
+    ),
+    (
+        "Diagnostics and upkeep for the document",
+        "Diagnose und Pflege des Dokuments",
+    ),
+    ("Sync with remote server", "Mit dem Server synchronisieren"),
+    (
+        "If another rott process is syncing, wait for it to finish and then sync (instead of \
+         skipping)",
+        "Wenn ein anderer rott-Prozess synchronisiert, auf dessen Ende warten und dann \
+         synchronisieren (statt zu überspringen)",
+    ),
+    (
+        "Capture aliases can be run directly: `rott <alias> <url> [key=value...]`",
+        "Capture-Aliase lassen sich direkt ausführen: `rott <alias> <url> [schlüssel=wert...]`",
+    ),
+    ("Show root document ID", "ID des Stammdokuments anzeigen"),
+    (
+        "List collections (* marks the active one)",
+        "Sammlungen auflisten (* markiert die aktive)",
+    ),
+    ("Create a collection", "Eine Sammlung anlegen"),
+    (
+        "Collection name (letters, digits, '-' and '_')",
+        "Name der Sammlung (Buchstaben, Ziffern, '-' und '_')",
+    ),
+    (
+        "Join a collection from another device by its document ID",
+        "Einer Sammlung von einem anderen Gerät über ihre Dokument-ID beitreten",
+    ),
+    (
+        "Make a collection the active one (\"default\" for the original)",
+        "Eine Sammlung zur aktiven machen (\"default\" für die ursprüngliche)",
+    ),
+    ("Collection name", "Name der Sammlung"),
+    ("Create a new link", "Einen neuen Link anlegen"),
+    ("Tags to add", "Hinzuzufügende Tags"),
+    ("Who recommended it", "Wer ihn empfohlen hat"),
+    (
+        "Where it was found (e.g. \"HN thread\", optionally with its URL)",
+        "Wo er gefunden wurde (z. B. \"HN-Thread\", optional mit dessen URL)",
+    ),
+    (
+        "Date the link stops being useful, e.g. an event or deadline (YYYY-MM-DD)",
+        "Datum, ab dem der Link nicht mehr nützlich ist, z. B. ein Termin oder eine Frist \
+         (JJJJ-MM-TT)",
+    ),
+    ("List all links", "Alle Links auflisten"),
+    ("Filter by tag", "Nach Tag filtern"),
+    (
+        "Only favorite links (see the favorite_tags config key)",
+        "Nur Favoriten (siehe den Konfigurationsschlüssel favorite_tags)",
+    ),
+    ("Only links without tags", "Nur Links ohne Tags"),
+    (
+        "Only links never opened (on any synced device)",
+        "Nur nie geöffnete Links (auf keinem synchronisierten Gerät)",
+    ),
+    (
+        "Only links by this author (ignoring case)",
+        "Nur Links dieses Autors (ohne Groß-/Kleinschreibung)",
+    ),
+    (
+        "Only links in this language (code like `de`, or name like `german`)",
+        "Nur Links in dieser Sprache (Code wie `de` oder Name wie `german`)",
+    ),
+    (
+        "Only links published on or after this date (YYYY-MM-DD)",
+        "Nur Links, die an oder nach diesem Datum veröffentlicht wurden (JJJJ-MM-TT)",
+    ),
+    (
+        "Only links published before this date (YYYY-MM-DD)",
+        "Nur Links, die vor diesem Datum veröffentlicht wurden (JJJJ-MM-TT)",
+    ),
+    ("Most recently updated first", "Zuletzt geänderte zuerst"),
+    (
+        "Most recently published first (links without a date last)",
+        "Zuletzt veröffentlichte zuerst (Links ohne Datum zuletzt)",
+    ),
+    (
+        "Only links whose expiry date has passed",
+        "Nur Links, deren Ablaufdatum vorbei ist",
+    ),
+    (
+        "Only links needing attention (unreachable, changed, forgotten), worst first",
+        "Nur Links, die Aufmerksamkeit brauchen (nicht erreichbar, geändert, vergessen), \
+         schlimmste zuerst",
+    ),
+    ("Include archived links", "Archivierte Links einschließen"),
+    (
+        "Keep running and list again whenever the document changes",
+        "Weiterlaufen und bei jeder Änderung des Dokuments erneut auflisten",
+    ),
+    (
+        "Show link details (including notes)",
+        "Linkdetails anzeigen (mit Notizen)",
+    ),
+    (
+        "Link ID (full UUID or prefix)",
+        "Link-ID (volle UUID oder Präfix)",
+    ),
+    (
+        "Open a link with its configured opener (see `openers`), or in the browser",
+        "Einen Link mit seinem eingestellten Öffner (siehe `openers`) oder im Browser öffnen",
+    ),
+    (
+        "Edit a link, or several at once in $EDITOR with --bulk",
+        "Einen Link bearbeiten, oder mit --bulk mehrere auf einmal in $EDITOR",
+    ),
+    (
+        "Edit links as a table in $EDITOR (title, url, tags, status per row)",
+        "Links als Tabelle in $EDITOR bearbeiten (Titel, URL, Tags, Status je Zeile)",
+    ),
+    (
+        "With --bulk, only links with this tag",
+        "Mit --bulk nur Links mit diesem Tag",
+    ),
+    (
+        "Add a tag (can be repeated)",
+        "Einen Tag hinzufügen (wiederholbar)",
+    ),
+    (
+        "Remove a tag (can be repeated)",
+        "Einen Tag entfernen (wiederholbar)",
+    ),
+    (
+        "Set the date the link expires (YYYY-MM-DD)",
+        "Das Ablaufdatum des Links setzen (JJJJ-MM-TT)",
+    ),
+    (
+        "Clear the link's expiry date",
+        "Das Ablaufdatum des Links löschen",
+    ),
+    ("Delete a link", "Einen Link löschen"),
+    ("Search links", "Links durchsuchen"),
+    ("Search query", "Suchanfrage"),
+    (
+        "Search archived links too",
+        "Auch archivierte Links durchsuchen",
+    ),
+    (
+        "Check saved links for changes",
+        "Gespeicherte Links auf Änderungen prüfen",
+    ),
+    (
+        "Report pages whose content changed significantly since saving",
+        "Seiten melden, deren Inhalt sich seit dem Speichern deutlich geändert hat",
+    ),
+    (
+        "Accept the current content of changed pages as the new baseline",
+        "Den aktuellen Inhalt geänderter Seiten als neue Grundlage übernehmen",
+    ),
+    (
+        "Screenshot a link's page with the screenshot_command and attach it",
+        "Die Seite eines Links mit dem screenshot_command aufnehmen und anhängen",
+    ),
+    (
+        "Keep an offline copy of a link's page text",
+        "Eine Offline-Kopie des Seitentexts eines Links behalten",
+    ),
+    (
+        "Merge a duplicate link into another (tags, authors, notes) and delete it",
+        "Einen doppelten Link in einen anderen zusammenführen (Tags, Autoren, Notizen) und \
+         löschen",
+    ),
+    (
+        "Link to keep (full UUID or prefix)",
+        "Zu behaltender Link (volle UUID oder Präfix)",
+    ),
+    (
+        "Duplicate to merge into it and delete (full UUID or prefix)",
+        "Duplikat, das zusammengeführt und gelöscht wird (volle UUID oder Präfix)",
+    ),
+    (
+        "Undo the last link merge, if it's the last edit (see `rott undo`)",
+        "Die letzte Zusammenführung rückgängig machen, wenn sie die letzte Änderung ist (siehe \
+         `rott undo`)",
+    ),
+    (
+        "Find links saved more than once (by normalized URL) and merge them",
+        "Mehrfach gespeicherte Links finden (nach normalisierter URL) und zusammenführen",
+    ),
+    (
+        "Merge every group without asking",
+        "Jede Gruppe ohne Rückfrage zusammenführen",
+    ),
+    (
+        "List duplicate links without merging them",
+        "Doppelte Links auflisten, ohne sie zusammenzuführen",
+    ),
+    (
+        "Fetch pages again to fix up saved links",
+        "Seiten erneut abrufen, um gespeicherte Links zu korrigieren",
+    ),
+    (
+        "Retitle links saved without a title (the page's title, or one made from the URL)",
+        "Links ohne Titel neu betiteln (mit dem Titel der Seite oder einem aus der URL)",
+    ),
+    (
+        "Fetch again only links whose metadata failed, filling in the parts that failed",
+        "Nur Links erneut abrufen, deren Metadaten fehlschlugen, und die fehlenden Teile ergänzen",
+    ),
+    ("Manage notes on a link", "Notizen zu einem Link verwalten"),
+    ("Add a note to a link", "Einem Link eine Notiz hinzufügen"),
+    ("Note title (optional)", "Titel der Notiz (optional)"),
+    (
+        "Note body (opens editor if not provided)",
+        "Text der Notiz (öffnet den Editor, falls nicht angegeben)",
+    ),
+    (
+        "Reply to a note (ID or prefix), threading it under that note",
+        "Auf eine Notiz antworten (ID oder Präfix) und darunter einordnen",
+    ),
+    ("List notes on a link", "Notizen zu einem Link auflisten"),
+    (
+        "Edit a note's title or body",
+        "Titel oder Text einer Notiz bearbeiten",
+    ),
+    (
+        "Note ID (full UUID or prefix)",
+        "Notiz-ID (volle UUID oder Präfix)",
+    ),
+    (
+        "New title (an empty title removes it)",
+        "Neuer Titel (ein leerer Titel entfernt ihn)",
+    ),
+    (
+        "New body (opens editor if neither it nor --title is given)",
+        "Neuer Text (öffnet den Editor, wenn weder er noch --title angegeben ist)",
+    ),
+    (
+        "Delete a note from a link",
+        "Eine Notiz von einem Link löschen",
+    ),
+    (
+        "Show the edit history of a note",
+        "Den Bearbeitungsverlauf einer Notiz anzeigen",
+    ),
+    (
+        "Show each version in full instead of as a diff",
+        "Jede Version vollständig statt als Diff zeigen",
+    ),
+    (
+        "Restore a note to an earlier version",
+        "Eine Notiz auf eine frühere Version zurücksetzen",
+    ),
+    (
+        "Version number from `rott link note history`",
+        "Versionsnummer aus `rott link note history`",
+    ),
+    (
+        "Split a note into one note per top-level Markdown heading",
+        "Eine Notiz in je eine Notiz pro Markdown-Überschrift erster Ebene aufteilen",
+    ),
+    (
+        "Find and replace text in note bodies, in a single change",
+        "Text in Notizen suchen und ersetzen, in einer einzigen Änderung",
+    ),
+    (
+        "Text to find (matched exactly, including case)",
+        "Zu suchender Text (genau, mit Groß-/Kleinschreibung)",
+    ),
+    (
+        "Text to put in its place",
+        "Text, der an seine Stelle tritt",
+    ),
+    (
+        "Only change notes on links with this tag",
+        "Nur Notizen zu Links mit diesem Tag ändern",
+    ),
+    ("Apply without asking", "Ohne Rückfrage anwenden"),
+    (
+        "Show the changes without making them",
+        "Die Änderungen zeigen, ohne sie vorzunehmen",
+    ),
+    (
+        "Run a Rhai script; its changes are saved together when it finishes",
+        "Ein Rhai-Skript ausführen; seine Änderungen werden am Ende gemeinsam gespeichert",
+    ),
+    (
+        "Script file (e.g. cleanup.rhai)",
+        "Skriptdatei (z. B. cleanup.rhai)",
+    ),
+    (
+        "Arguments passed to the script as `args`",
+        "Argumente, die das Skript als `args` erhält",
+    ),
+    (
+        "List what the script would change without saving it",
+        "Auflisten, was das Skript ändern würde, ohne zu speichern",
+    ),
+    (
+        "Write a self-contained backup file",
+        "Eine eigenständige Sicherungsdatei schreiben",
+    ),
+    (
+        "Backup file to write (e.g. rott.rottpack)",
+        "Zu schreibende Sicherungsdatei (z. B. rott.rottpack)",
+    ),
+    (
+        "Restore from a backup file",
+        "Aus einer Sicherungsdatei wiederherstellen",
+    ),
+    ("Backup file to read", "Zu lesende Sicherungsdatei"),
+    (
+        "Replace existing data on this machine",
+        "Vorhandene Daten auf diesem Rechner ersetzen",
+    ),
+    (
+        "Keep this machine's configuration instead of restoring the backed-up one",
+        "Die Konfiguration dieses Rechners behalten, statt die gesicherte wiederherzustellen",
+    ),
+    (
+        "Move links into the archive for the year they were saved",
+        "Links in das Archiv des Jahres verschieben, in dem sie gespeichert wurden",
+    ),
+    (
+        "Links saved more than this many years ago",
+        "Links, die vor mehr als so vielen Jahren gespeichert wurden",
+    ),
+    (
+        "Links with this tag (with --older-than, only old links with it)",
+        "Links mit diesem Tag (mit --older-than nur alte Links mit diesem Tag)",
+    ),
+    ("Don't ask for confirmation", "Nicht nachfragen"),
+    (
+        "List the links that would be archived without moving them",
+        "Die Links auflisten, die archiviert würden, ohne sie zu verschieben",
+    ),
+    ("List archives by year", "Archive nach Jahr auflisten"),
+    (
+        "Move an archived link back",
+        "Einen archivierten Link zurückholen",
+    ),
+    (
+        "Show document history and size statistics",
+        "Verlauf und Größenstatistik des Dokuments anzeigen",
+    ),
+    (
+        "Number of largest links to list",
+        "Anzahl der größten aufzulistenden Links",
+    ),
+    (
+        "Run the scheduled jobs (backups, link checks, reports) that are due",
+        "Die fälligen geplanten Aufgaben ausführen (Sicherungen, Linkprüfungen, Berichte)",
+    ),
+    (
+        "List the jobs that are due without running them",
+        "Die fälligen Aufgaben auflisten, ohne sie auszuführen",
+    ),
+    (
+        "Merge identical notes duplicated across links",
+        "Gleiche Notizen zusammenführen, die auf mehreren Links doppelt vorkommen",
+    ),
+    (
+        "List duplicate notes without merging them",
+        "Doppelte Notizen auflisten, ohne sie zusammenzuführen",
+    ),
+    (
+        "Merge tags that differ only in case or punctuation (e.g. synced from an older version)",
+        "Tags zusammenführen, die sich nur in Schreibung oder Satzzeichen unterscheiden (z. B. \
+         von einer älteren Version synchronisiert)",
+    ),
+    (
+        "Empty the cache of fetched page metadata",
+        "Den Cache abgerufener Seitenmetadaten leeren",
+    ),
+    (
+        "Only remove entries older than metadata_cache_hours",
+        "Nur Einträge entfernen, die älter als metadata_cache_hours sind",
+    ),
+    (
+        "Delete attachment files no link refers to any more",
+        "Anhangdateien löschen, auf die kein Link mehr verweist",
+    ),
+    (
+        "Only delete files unreferenced for at least this many days",
+        "Nur Dateien löschen, auf die seit mindestens so vielen Tagen nichts verweist",
+    ),
+    (
+        "Report what would be deleted without deleting it",
+        "Melden, was gelöscht würde, ohne zu löschen",
+    ),
+    (
+        "Rebuild a projection (activity, backlinks, or all) from the document's history; lists \
+         them if none is given",
+        "Eine Projektion (activity, backlinks oder all) aus dem Verlauf des Dokuments neu \
+         aufbauen; ohne Angabe werden sie aufgelistet",
+    ),
+    (
+        "Projection to rebuild, or \"all\"",
+        "Neu aufzubauende Projektion oder \"all\"",
+    ),
+    (
+        "Print the document's history as events, one JSON object per line",
+        "Den Verlauf des Dokuments als Ereignisse ausgeben, ein JSON-Objekt pro Zeile",
+    ),
+    (
+        "Report what changed: links added, archived and deleted, new tags, and each device's \
+         activity",
+        "Berichten, was sich geändert hat: hinzugefügte, archivierte und gelöschte Links, neue \
+         Tags und die Aktivität jedes Geräts",
+    ),
+    (
+        "Period to report on, up to now (e.g. 7d, 4w, 48h)",
+        "Berichtszeitraum bis jetzt (z. B. 7d, 4w, 48h)",
+    ),
+    (
+        "Write the Markdown report to this file instead of printing it",
+        "Den Markdown-Bericht in diese Datei schreiben, statt ihn auszugeben",
+    ),
+    (
+        "Write a copy of the document with all text replaced by placeholders, to attach to bug \
+         reports",
+        "Eine Kopie des Dokuments schreiben, in der aller Text durch Platzhalter ersetzt ist, \
+         zum Anhängen an Fehlerberichte",
+    ),
+    (
+        "File to write the copy to (e.g. repro.automerge)",
+        "Datei für die Kopie (z. B. repro.automerge)",
+    ),
+    (
+        "Suggest frequently-visited pages from browser history",
+        "Häufig besuchte Seiten aus dem Browserverlauf vorschlagen",
+    ),
+    (
+        "Browser to read history from",
+        "Browser, dessen Verlauf gelesen wird",
+    ),
+    (
+        "Browser profile directory (defaults to the default profile)",
+        "Browser-Profilverzeichnis (Standard: das Standardprofil)",
+    ),
+    (
+        "Minimum number of visits for a page to be suggested",
+        "Mindestzahl an Besuchen, damit eine Seite vorgeschlagen wird",
+    ),
+    (
+        "Only consider visits within this window (e.g. 90d, 12w, 48h)",
+        "Nur Besuche in diesem Zeitraum berücksichtigen (z. B. 90d, 12w, 48h)",
+    ),
+    (
+        "Tags to add to imported links",
+        "Tags für importierte Links",
+    ),
+    (
+        "Save all suggestions without prompting",
+        "Alle Vorschläge ohne Rückfrage speichern",
+    ),
+    (
+        "Preview the links that would be saved without saving any",
+        "Die Links zeigen, die gespeichert würden, ohne etwas zu speichern",
+    ),
+    (
+        "Save bookmarks from a browser's bookmark HTML export, folders as tags",
+        "Lesezeichen aus dem HTML-Export eines Browsers speichern, Ordner als Tags",
+    ),
+    (
+        "Exported bookmarks file (Netscape bookmark HTML)",
+        "Exportierte Lesezeichendatei (Netscape-Lesezeichen-HTML)",
+    ),
+    (
+        "Review and merge links and notes from someone else's document or backup",
+        "Links und Notizen aus dem Dokument oder der Sicherung von jemand anderem prüfen und \
+         übernehmen",
+    ),
+    (
+        "Saved document or backup archive shared with you",
+        "Mit dir geteiltes gespeichertes Dokument oder Sicherungsarchiv",
+    ),
+    (
+        "Only consider links with this tag (repeatable)",
+        "Nur Links mit diesem Tag berücksichtigen (wiederholbar)",
+    ),
+    (
+        "Accept every incoming item without reviewing",
+        "Jeden eingehenden Eintrag ohne Prüfung übernehmen",
+    ),
+    (
+        "List incoming items without merging anything",
+        "Eingehende Einträge auflisten, ohne etwas zu übernehmen",
+    ),
+    (
+        "Push a link's notes as private Hypothes.is page notes",
+        "Die Notizen eines Links als private Hypothes.is-Seitennotizen senden",
+    ),
+    (
+        "Add a link's Hypothes.is annotations as notes",
+        "Die Hypothes.is-Anmerkungen eines Links als Notizen hinzufügen",
+    ),
+    (
+        "Show tags as a tree (`lang-rust` under `lang`), with link counts",
+        "Tags als Baum zeigen (`lang-rust` unter `lang`), mit Linkanzahl",
+    ),
+    (
+        "Remove descriptions and visibility marks of tags no link uses",
+        "Beschreibungen und Sichtbarkeitsmarkierungen von Tags entfernen, die kein Link verwendet",
+    ),
+    (
+        "Show what would be removed without removing it",
+        "Zeigen, was entfernt würde, ohne zu entfernen",
+    ),
+    (
+        "List links by a combination of tags, e.g. for piping into `xargs rott link ...`",
+        "Links nach einer Kombination von Tags auflisten, z. B. zum Weiterleiten an `xargs \
+         rott link ...`",
+    ),
+    (
+        "Tags a link must have all of (comma-separated)",
+        "Tags, die ein Link alle haben muss (durch Kommas getrennt)",
+    ),
+    (
+        "Tags a link must have at least one of (comma-separated)",
+        "Tags, von denen ein Link mindestens einen haben muss (durch Kommas getrennt)",
+    ),
+    (
+        "Tags a link must have none of (comma-separated)",
+        "Tags, von denen ein Link keinen haben darf (durch Kommas getrennt)",
+    ),
+    (
+        "Print only the links' IDs, one per line",
+        "Nur die IDs der Links ausgeben, eine pro Zeile",
+    ),
+    (
+        "Set a tag's description (Markdown); opens $EDITOR if no text is given",
+        "Die Beschreibung eines Tags setzen (Markdown); öffnet $EDITOR, wenn kein Text \
+         angegeben ist",
+    ),
+    ("Tag name", "Tag-Name"),
+    ("Description text", "Beschreibungstext"),
+    ("Remove the description", "Die Beschreibung entfernen"),
+    (
+        "Show a tag's description and links",
+        "Die Beschreibung und die Links eines Tags zeigen",
+    ),
+    (
+        "Mark a tag private (left out of exports) or public; without arguments, list marked tags",
+        "Einen Tag als privat (nicht exportiert) oder öffentlich markieren; ohne Argumente die \
+         markierten Tags auflisten",
+    ),
+    (
+        "private, public, or default (removes the mark)",
+        "private, public oder default (entfernt die Markierung)",
+    ),
+    (
+        "Serve a read-only web viewer of the collection, for browsing from other devices on \
+         the same network",
+        "Einen schreibgeschützten Web-Viewer der Sammlung bereitstellen, zum Stöbern von \
+         anderen Geräten im selben Netz",
+    ),
+    ("Port to listen on", "Port, auf dem gelauscht wird"),
+    (
+        "Address to listen on (127.0.0.1 keeps the viewer to this machine)",
+        "Adresse, auf der gelauscht wird (127.0.0.1 beschränkt den Viewer auf diesen Rechner)",
+    ),
+    (
+        "Show links with a private tag too",
+        "Auch Links mit einem privaten Tag zeigen",
+    ),
+    (
+        "Serve a JSON API for browser extensions, shortcuts and scripts (needs `api_token`)",
+        "Eine JSON-API für Browser-Erweiterungen, Kurzbefehle und Skripte bereitstellen \
+         (braucht `api_token`)",
+    ),
+    (
+        "Address to listen on (0.0.0.0 to take requests from other devices)",
+        "Adresse, auf der gelauscht wird (0.0.0.0, um Anfragen anderer Geräte anzunehmen)",
+    ),
+    (
+        "Inspect traces written with --trace-sync",
+        "Mit --trace-sync geschriebene Traces untersuchen",
+    ),
+    (
+        "Summarize the most recent sync trace",
+        "Den neuesten Sync-Trace zusammenfassen",
+    ),
+    (
+        "Flat, greppable text: one line per field, prefixed by link ID",
+        "Flacher, durchsuchbarer Text: eine Zeile pro Feld, mit der Link-ID davor",
+    ),
+    (
+        "Output file (defaults to stdout)",
+        "Ausgabedatei (Standard: stdout)",
+    ),
+    (
+        "Include links with a private tag",
+        "Links mit einem privaten Tag einschließen",
+    ),
+    (
+        "Standalone SQLite database for analysis",
+        "Eigenständige SQLite-Datenbank zur Auswertung",
+    ),
+    (
+        "Output file (replaced if it exists)",
+        "Ausgabedatei (wird ersetzt, falls vorhanden)",
+    ),
+    (
+        "Every field of every link and note, as a JSON array",
+        "Jedes Feld jedes Links und jeder Notiz, als JSON-Array",
+    ),
+    ("Only links with this tag", "Nur Links mit diesem Tag"),
+    (
+        "One Markdown file per link, with YAML frontmatter",
+        "Eine Markdown-Datei pro Link, mit YAML-Frontmatter",
+    ),
+    (
+        "Directory to write the files to (created if needed)",
+        "Verzeichnis für die Dateien (wird bei Bedarf angelegt)",
+    ),
+    (
+        "Only write files that changed since the last export to the directory, and remove \
+         those of links no longer exported",
+        "Nur Dateien schreiben, die sich seit dem letzten Export in das Verzeichnis geändert \
+         haben, und die von nicht mehr exportierten Links entfernen",
+    ),
+    (
+        "CSV with one row per link (notes in the last column)",
+        "CSV mit einer Zeile pro Link (Notizen in der letzten Spalte)",
+    ),
+    (
+        "Set a passphrase (or change it) and enable the lock",
+        "Eine Passphrase setzen (oder ändern) und die Sperre aktivieren",
+    ),
+    (
+        "Remove the passphrase and disable the lock",
+        "Die Passphrase entfernen und die Sperre deaktivieren",
+    ),
+    (
+        "Show current configuration",
+        "Aktuelle Konfiguration anzeigen",
+    ),
+    (
+        "Set a configuration value",
+        "Einen Konfigurationswert setzen",
+    ),
+    (
+        "Configuration key (data_dir, sync_url, sync_enabled)",
+        "Konfigurationsschlüssel (data_dir, sync_url, sync_enabled)",
+    ),
+    ("Configuration value", "Konfigurationswert"),
+    (
+        "Export settings as a bundle for another machine",
+        "Einstellungen als Paket für einen anderen Rechner exportieren",
+    ),
+    (
+        "File to write (prints to stdout if omitted)",
+        "Zu schreibende Datei (ohne Angabe auf stdout)",
+    ),
+    (
+        "Include the privacy lock passphrase hash, Hypothes.is token and add_token",
+        "Den Hash der Sperr-Passphrase, das Hypothes.is-Token und add_token einschließen",
+    ),
+    (
+        "Replace settings with an exported bundle (keeps this machine's data_dir)",
+        "Einstellungen durch ein exportiertes Paket ersetzen (behält das data_dir dieses \
+         Rechners)",
+    ),
+    (
+        "Bundle written by `rott config export`",
+        "Von `rott config export` geschriebenes Paket",
+    ),
+    // CLI: editor, archives
+    ("Dry run: {}", "Probelauf: {}"),
+    ("... and {} more", "... und {} weitere"),
+    ("Nothing was changed.", "Es wurde nichts geändert."),
+    (
+        "Failed to create temp file: {}",
+        "Temporäre Datei konnte nicht angelegt werden: {}",
+    ),
+    (
+        "Failed to run editor: {}",
+        "Editor konnte nicht gestartet werden: {}",
+    ),
+    (
+        "Editor '{}' exited with non-zero status. Check that your editor is configured correctly.",
+        "Editor '{}' wurde mit einem Fehlerstatus beendet. Prüfe, ob dein Editor richtig \
+         eingerichtet ist.",
+    ),
+    (
+        "Failed to read edited file: {}",
+        "Bearbeitete Datei konnte nicht gelesen werden: {}",
+    ),
+    (
+        "No editor found. Set $EDITOR environment variable.\nExample: export EDITOR=nano",
+        "Kein Editor gefunden. Setze die Umgebungsvariable $EDITOR.\nBeispiel: export EDITOR=nano",
+    ),
+    ("[y/N]", "[j/N]"),
+    ("y", "j"),
+    ("yes", "ja"),
+    (
+        "Archiving without prompting needs --yes (or use --dry-run to list the links)",
+        "Archivieren ohne Rückfrage braucht --yes (oder --dry-run, um die Links aufzulisten)",
+    ),
+    ("No links to archive", "Keine Links zu archivieren"),
+    ("Archive links", "Links archivieren"),
+    ("links", "Links"),
+    ("{} (to {} archive)", "{} (ins Archiv {})"),
+    ("Archive {} link(s)?", "{} Link(s) archivieren?"),
+    ("Cancelled", "Abgebrochen"),
+    ("Archived {} link(s)", "{} Link(s) archiviert"),
+    ("No archives", "Keine Archive"),
+    ("{} link(s)", "{} Link(s)"),
+    (
+        "not on this device yet (run `rott sync`)",
+        "noch nicht auf diesem Gerät (`rott sync` ausführen)",
+    ),
+    (
+        "Restored '{}' from the archive",
+        "'{}' aus dem Archiv zurückgeholt",
+    ),
+    (
+        "No archived link found matching: {}",
+        "Kein archivierter Link passt zu: {}",
+    ),
+    (
+        "Multiple archived links match '{}':",
+        "Mehrere archivierte Links passen zu '{}':",
+    ),
+    (
+        "Ambiguous ID. Please provide more characters.",
+        "Mehrdeutige ID. Bitte gib mehr Zeichen an.",
+    ),
+    // CLI: backups
+    (
+        "Backed up {} link(s), {} archived link(s) and {} attachment(s) to {} ({} bytes)",
+        "{} Link(s), {} archivierte Link(s) und {} Anhang/Anhänge nach {} gesichert ({} Bytes)",
+    ),
+    (
+        "Warning: the {} archive isn't on this device yet, so it isn't in the backup; run \
+         `rott sync` and back up again",
+        "Warnung: Das Archiv {} ist noch nicht auf diesem Gerät und fehlt daher in der \
+         Sicherung; führe `rott sync` aus und sichere erneut",
+    ),
+    ("Failed to write {}", "{} konnte nicht geschrieben werden"),
+    ("Failed to read {}", "{} konnte nicht gelesen werden"),
+    (
+        "Backup verification failed",
+        "Überprüfung der Sicherung fehlgeschlagen",
+    ),
+    (
+        "Failed to load configuration",
+        "Konfiguration konnte nicht geladen werden",
+    ),
+    (
+        "This machine already has data (root document ID {}).\nUse --force to replace it with \
+         the backup.",
+        "Auf diesem Rechner sind bereits Daten (Root-Dokument-ID {}).\nMit --force werden sie \
+         durch die Sicherung ersetzt.",
+    ),
+    (
+        "Replace the data in {} with the backup from {} ({} link(s))?",
+        "Daten in {} durch die Sicherung vom {} ersetzen ({} Link(s))?",
+    ),
+    ("Are you sure?", "Bist du sicher?"),
+    ("Cancelled.", "Abgebrochen."),
+    (
+        "Restored {} link(s) and {} archived link(s) from backup made {}",
+        "{} Link(s) und {} archivierte Link(s) aus der Sicherung vom {} wiederhergestellt",
+    ),
+    ("Root document ID: {}", "Root-Dokument-ID: {}"),
+    (
+        "Choose what to restore with --link <id> and/or --tag <tag>",
+        "Wähle mit --link <id> und/oder --tag <tag> aus, was wiederhergestellt werden soll",
+    ),
+    (
+        "No links tagged '{}' in {}",
+        "Keine Links mit dem Tag '{}' in {}",
+    ),
+    (
+        "Restored {} link(s) and {} note(s)",
+        "{} Link(s) und {} Notiz(en) wiederhergestellt",
+    ),
+    (
+        "{} link(s) already up to date",
+        "{} Link(s) bereits aktuell",
+    ),
+    (
+        "{} link(s) skipped: URL saved again since the backup",
+        "{} Link(s) übersprungen: URL wurde seit der Sicherung erneut gespeichert",
+    ),
+    ("Not restored: {} ({})", "Nicht wiederhergestellt: {} ({})"),
+    (
+        "No link in the backup matches: {}",
+        "Kein Link in der Sicherung passt zu: {}",
+    ),
+    (
+        "Multiple links match '{}':",
+        "Mehrere Links passen zu '{}':",
+    ),
+    // CLI: capture
+    (
+        "No capture alias named '{}'. Define one under [captures.{}] in the config file.",
+        "Kein Erfassungsalias namens '{}'. Lege einen unter [captures.{}] in der \
+         Konfigurationsdatei an.",
+    ),
+    (
+        "Invalid template value '{}'. Use key=value (e.g. source=\"a friend\").",
+        "Ungültiger Vorlagenwert '{}'. Verwende key=value (z. B. source=\"eine Freundin\").",
+    ),
+    (
+        "Invalid note in capture '{}'",
+        "Ungültige Notiz im Erfassungsalias '{}'",
+    ),
+    (
+        "Capture '{}' needs a value for '{}' (pass it as {}=<value>)",
+        "Erfassungsalias '{}' braucht einen Wert für '{}' (übergib ihn als {}=<Wert>)",
+    ),
+    ("Failed to create link", "Link konnte nicht angelegt werden"),
+    ("Captured link with '{}': {}", "Link mit '{}' erfasst: {}"),
+    (
+        "Every URL in the {} is saved already ({})",
+        "Jede URL aus {} ist bereits gespeichert ({})",
+    ),
+    ("No URLs in the {}", "Keine URLs in {}"),
+    ("URLs in the {}:", "URLs in {}:"),
+    ("({} already saved)", "({} bereits gespeichert)"),
+    (
+        "Save which? (e.g. 1,3-5; Enter for all, 'none' to cancel)",
+        "Welche speichern? (z. B. 1,3-5; Enter für alle, 'none' zum Abbrechen)",
+    ),
+    ("Nothing saved.", "Nichts gespeichert."),
+    (
+        "Failed to save {}: {}",
+        "{} konnte nicht gespeichert werden: {}",
+    ),
+    (
+        "Saved {} link(s) from the {}",
+        "{} Link(s) aus {} gespeichert",
+    ),
+    ("Failed to run tmux", "tmux konnte nicht ausgeführt werden"),
+    ("tmux buffer", "dem tmux-Puffer"),
+    ("selection", "der Auswahl"),
+    (
+        "'{}' isn't a number from 1 to {}",
+        "'{}' ist keine Zahl von 1 bis {}",
+    ),
+    ("Invalid range '{}'", "Ungültiger Bereich '{}'"),
+    // CLI: collections
+    ("{}  (not synced yet)", "{}  (noch nicht synchronisiert)"),
+    (
+        "not set up (run `rott init`)",
+        "nicht eingerichtet (führe `rott init` aus)",
+    ),
+    ("Invalid document ID: {}", "Ungültige Dokument-ID: {}"),
+    (
+        "Created collection '{}' ({})",
+        "Sammlung '{}' angelegt ({})",
+    ),
+    (
+        "Switch to it with: rott collection switch {}",
+        "Wechsle zu ihr mit: rott collection switch {}",
+    ),
+    (
+        "Joined collection '{}'; switch to it and run `rott sync` to pull it",
+        "Sammlung '{}' beigetreten; wechsle zu ihr und führe `rott sync` aus, um sie zu laden",
+    ),
+    (
+        "No collection '{}'. Create it with: rott collection create {}",
+        "Keine Sammlung '{}'. Lege sie an mit: rott collection create {}",
+    ),
+    (
+        "Failed to save configuration",
+        "Konfiguration konnte nicht gespeichert werden",
+    ),
+    (
+        "Switched to collection '{}'",
+        "Zur Sammlung '{}' gewechselt",
+    ),
+    // CLI: configuration
+    ("Configuration:", "Konfiguration:"),
+    ("(not set)", "(nicht gesetzt)"),
+    ("(host name)", "(Rechnername)"),
+    ("{} (match {})", "{} (Übereinstimmung: {})"),
+    (
+        "(not set, links open in the browser)",
+        "(nicht gesetzt, Links öffnen sich im Browser)",
+    ),
+    (
+        "enabled ({} minute(s) idle timeout)",
+        "aktiviert (Sperre nach {} Minute(n) ohne Eingabe)",
+    ),
+    ("disabled", "deaktiviert"),
+    ("0 (cache off)", "0 (Cache aus)"),
+    ("(most used)", "(meistverwendete)"),
+    (
+        "(not set, from environment)",
+        "(nicht gesetzt, aus der Umgebung)",
+    ),
+    ("enabled (token set)", "aktiviert (Token gesetzt)"),
+    ("enabled (add_token set)", "aktiviert (add_token gesetzt)"),
+    ("enabled (api_token set)", "aktiviert (api_token gesetzt)"),
+    ("(no tags)", "(keine Tags)"),
+    ("Config file: {}", "Konfigurationsdatei: {}"),
+    (
+        "Invalid value for sync_batch_ms. Use a number of milliseconds.",
+        "Ungültiger Wert für sync_batch_ms. Gib eine Anzahl Millisekunden an.",
+    ),
+    (
+        "Invalid value for favorite_match. Use 'any' or 'all'.",
+        "Ungültiger Wert für favorite_match. Verwende 'any' oder 'all'.",
+    ),
+    (
+        "Invalid value for url_trailing_slash: '{}'. Use 'strip' or 'keep'.",
+        "Ungültiger Wert für url_trailing_slash: '{}'. Verwende 'strip' oder 'keep'.",
+    ),
+    (
+        "Invalid value for lock_timeout_minutes. Use a number of minutes.",
+        "Ungültiger Wert für lock_timeout_minutes. Gib eine Anzahl Minuten an.",
+    ),
+    (
+        "Invalid value for metadata_cache_hours. Use a number of hours (0 turns the cache off).",
+        "Ungültiger Wert für metadata_cache_hours. Gib eine Anzahl Stunden an (0 schaltet den \
+         Cache aus).",
+    ),
+    (
+        "Invalid value for backup_keep. Use a number of backups.",
+        "Ungültiger Wert für backup_keep. Gib eine Anzahl Sicherungen an.",
+    ),
+    (
+        "Invalid value for default_sort: '{}'. Use updated, created, oldest, title, or domain.",
+        "Ungültiger Wert für default_sort: '{}'. Verwende updated, created, oldest, title oder \
+         domain.",
+    ),
+    (
+        "Invalid value for search_provider: '{}'. Use scan or sqlite.",
+        "Ungültiger Wert für search_provider: '{}'. Verwende scan oder sqlite.",
+    ),
+    (
+        "Warning: this build of rott doesn't include the {} search provider (build with \
+         --features sqlite-search); searches will scan the document.",
+        "Warnung: Dieser Build von rott enthält den Suchanbieter {} nicht (baue mit --features \
+         sqlite-search); Suchen durchlaufen das Dokument.",
+    ),
+    (
+        "Too many triage_tags: only the keys 1-9 can be bound.",
+        "Zu viele triage_tags: Nur die Tasten 1-9 können belegt werden.",
+    ),
+    (
+        "Unsupported locale '{}'. Use one of: {} (or none for the environment's)",
+        "Nicht unterstützte Sprache '{}'. Verwende eine von: {} (oder none für die der Umgebung)",
+    ),
+    ("Invalid note_template", "Ungültige note_template"),
+    (
+        "Invalid value for note_section_threshold. Use a number of bytes (0 to turn sections \
+         off).",
+        "Ungültiger Wert für note_section_threshold. Gib eine Anzahl Bytes an (0 schaltet \
+         Abschnitte aus).",
+    ),
+    (
+        "Unknown configuration key: '{}'\nValid keys: {}",
+        "Unbekannter Konfigurationsschlüssel: '{}'\nGültige Schlüssel: {}",
+    ),
+    ("Set {}", "{} gesetzt"),
+    ("Set {} = {}", "{} = {} gesetzt"),
+    (
+        "Exported configuration to {}",
+        "Konfiguration nach {} exportiert",
+    ),
+    (
+        "Imported configuration from {} into {}",
+        "Konfiguration aus {} nach {} importiert",
+    ),
+    (
+        "Invalid value for {}. Use 'true' or 'false'.",
+        "Ungültiger Wert für {}. Verwende 'true' oder 'false'.",
+    ),
+    (
+        "Invalid value for {}. Use a number of characters (at least 1).",
+        "Ungültiger Wert für {}. Gib eine Anzahl Zeichen an (mindestens 1).",
+    ),
+    (
+        "Invalid value for {}. Use daily, weekly, monthly, or a number of days (e.g. 10d).",
+        "Ungültiger Wert für {}. Verwende daily, weekly, monthly oder eine Anzahl Tage (z. B. \
+         10d).",
+    ),
+    (
+        "Invalid domain_tags entry '{}'. Use: domain=tag1,tag2;other.com=tag3",
+        "Ungültiger domain_tags-Eintrag '{}'. Format: domain=tag1,tag2;other.com=tag3",
+    ),
+    (
+        "Invalid openers entry '{}'. Use: video=mpv {url};arxiv.org=zathura {url};default=firefox",
+        "Ungültiger openers-Eintrag '{}'. Format: video=mpv {url};arxiv.org=zathura \
+         {url};default=firefox",
+    ),
+    // CLI: doctor, export, grep, Hypothes.is, import, scripts
+    ("No problems found", "Keine Probleme gefunden"),
+    ("1 problem found:", "1 Problem gefunden:"),
+    ("{} problems found:", "{} Probleme gefunden:"),
+    (
+        "These records are shown with defaults for the parts that couldn't be read.",
+        "Diese Einträge werden für die nicht lesbaren Teile mit Standardwerten angezeigt.",
+    ),
+    ("Failed to create {}", "{} konnte nicht angelegt werden"),
+    ("Exported {} link(s) to {}", "{} Link(s) nach {} exportiert"),
+    (
+        "Left out {} link(s) with a private tag (use --include-private to export them)",
+        "{} Link(s) mit privatem Tag ausgelassen (mit --include-private werden sie exportiert)",
+    ),
+    ("Failed to remove {}", "{} konnte nicht entfernt werden"),
+    (
+        "Exported {} changed link(s) to {} ({} unchanged, {} removed)",
+        "{} geänderte(n) Link(s) nach {} exportiert ({} unverändert, {} entfernt)",
+    ),
+    (
+        "Can't export incrementally from {}",
+        "Inkrementeller Export aus {} nicht möglich",
+    ),
+    (
+        "Failed to create tables",
+        "Tabellen konnten nicht angelegt werden",
+    ),
+    (
+        "Failed to export link {}",
+        "Link {} konnte nicht exportiert werden",
+    ),
+    (
+        "Failed to write the database",
+        "Die Datenbank konnte nicht geschrieben werden",
+    ),
+    ("Invalid pattern: {}", "Ungültiges Muster: {}"),
+    ("No matches.", "Keine Treffer."),
+    ("Link not found: {}", "Link nicht gefunden: {}"),
+    (
+        "Failed to push note {}",
+        "Notiz {} konnte nicht übertragen werden",
+    ),
+    (
+        "Pushed {} note(s) to Hypothes.is ({} already there)",
+        "{} Notiz(en) an Hypothes.is übertragen ({} bereits vorhanden)",
+    ),
+    (
+        "Failed to add note to link",
+        "Notiz konnte nicht zum Link hinzugefügt werden",
+    ),
+    (
+        "Added {} note(s) from {} Hypothes.is annotation(s)",
+        "{} Notiz(en) aus {} Hypothes.is-Anmerkung(en) hinzugefügt",
+    ),
+    (
+        "No Hypothes.is token configured. Create one at https://hypothes.is/account/developer \
+         and set it with `rott config set hypothesis_token <token>`.",
+        "Kein Hypothes.is-Token konfiguriert. Erstelle eines unter \
+         https://hypothes.is/account/developer und setze es mit `rott config set \
+         hypothesis_token <token>`.",
+    ),
+    (
+        "Failed to read browser history",
+        "Browserverlauf konnte nicht gelesen werden",
+    ),
+    (
+        "No new suggestions from browser history.",
+        "Keine neuen Vorschläge aus dem Browserverlauf.",
+    ),
+    ("(untitled)", "(ohne Titel)"),
+    (
+        "Found {} frequently-visited page(s) not yet saved.",
+        "{} häufig besuchte Seite(n) gefunden, die noch nicht gespeichert sind.",
+    ),
+    (
+        "Save each one? [y]es / [N]o / [q]uit",
+        "Jede speichern? [y] ja / [N] nein / [q] beenden",
+    ),
+    (
+        "Failed to save links",
+        "Links konnten nicht gespeichert werden",
+    ),
+    (
+        "Imported {} link(s) from history",
+        "{} Link(s) aus dem Verlauf importiert",
+    ),
+    ("Skipped {}: {}", "{} übersprungen: {}"),
+    (
+        "No bookmarks found in {}",
+        "Keine Lesezeichen in {} gefunden",
+    ),
+    ("Import bookmarks from {}", "Lesezeichen aus {} importieren"),
+    (
+        "Failed to save bookmarks",
+        "Lesezeichen konnten nicht gespeichert werden",
+    ),
+    (
+        "Imported {} link(s) from {}",
+        "{} Link(s) aus {} importiert",
+    ),
+    (
+        "Skipped {} already saved link(s)",
+        "{} bereits gespeicherte(n) Link(s) übersprungen",
+    ),
+    (
+        "Skipped {} invalid link(s):",
+        "{} ungültige(n) Link(s) übersprungen:",
+    ),
+    ("Nothing new in {}", "Nichts Neues in {}"),
+    ("Merge {}", "{} zusammenführen"),
+    (
+        "Run in a terminal to review each item, or pass --yes to accept all.",
+        "Führe den Befehl in einem Terminal aus, um jeden Eintrag zu prüfen, oder übergib \
+         --yes, um alle zu übernehmen.",
+    ),
+    (
+        "Review each item: [y]es / [N]o / [a]ccept the rest / [q]uit (reject the rest)",
+        "Jeden Eintrag prüfen: [y] ja / [N] nein / [a] Rest übernehmen / [q] beenden (Rest \
+         ablehnen)",
+    ),
+    (
+        "Nothing accepted; no changes made.",
+        "Nichts übernommen; keine Änderungen vorgenommen.",
+    ),
+    (
+        "Failed to save incoming links",
+        "Eingehende Links konnten nicht gespeichert werden",
+    ),
+    (
+        "Merged {} item(s) from {}",
+        "{} Eintrag/Einträge aus {} zusammengeführt",
+    ),
+    ("Added ({}):", "Hinzugefügt ({}):"),
+    ("Changed ({}):", "Geändert ({}):"),
+    ("Accept? [y/N/a/q]", "Übernehmen? [y/N/a/q]"),
+    ("{} visits | {} | {}", "{} Besuche | {} | {}"),
+    ("{} ({} visits, last {})", "{} ({} Besuche, zuletzt {})"),
+    ("Save? [y/N/q]", "Speichern? [y/N/q]"),
+    ("Error in {}", "Fehler in {}"),
+    ("Script made no changes", "Das Skript hat nichts geändert"),
+    ("saved", "gespeichert"),
+    ("deleted", "gelöscht"),
+    (
+        "Failed to save the script's changes",
+        "Die Änderungen des Skripts konnten nicht gespeichert werden",
+    ),
+    (
+        "Script saved {} link(s), deleted {}",
+        "Skript hat {} Link(s) gespeichert und {} gelöscht",
+    ),
+    (
+        "Import from browser history",
+        "Aus dem Browserverlauf importieren",
+    ),
+    ("link {} note {}", "Link {} Notiz {}"),
+    ("link {}", "Link {}"),
+    ("tags: {}", "Tags: {}"),
+    ("{} note(s)", "{} Notiz(en)"),
+    ("+tags: {}", "+Tags: {}"),
+    ("+{} note(s)", "+{} Notiz(en)"),
+    ("Run {}", "{} ausführen"),
+    ("link(s)", "Link(s)"),
+    ("already saved", "bereits gespeichert"),
+    ("invalid", "ungültig"),
+    ("added", "hinzugefügt"),
+    ("changed", "geändert"),
+    // CLI: links
+    ("Created link: {}", "Link angelegt: {}"),
+    ("Screenshot failed: {}", "Screenshot fehlgeschlagen: {}"),
+    (
+        "Archiving the page text failed: {}",
+        "Archivieren des Seitentexts fehlgeschlagen: {}",
+    ),
+    (
+        "No favorite tags configured. Set them with `rott config set favorite_tags <tag,...>`.",
+        "Keine Favoriten-Tags konfiguriert. Setze sie mit `rott config set favorite_tags \
+         <tag,...>`.",
+    ),
+    (
+        "Unknown language '{}'. Use a code like 'de' or a name like 'german'.",
+        "Unbekannte Sprache '{}'. Verwende einen Code wie 'de' oder einen Namen wie 'german'.",
+    ),
+    ("Capturing screenshot...", "Screenshot wird aufgenommen..."),
+    ("Saved screenshot: {}", "Screenshot gespeichert: {}"),
+    ("Fetching page...", "Seite wird abgerufen..."),
+    ("Archived page text: {}", "Seitentext archiviert: {}"),
+    (
+        "Failed to update link",
+        "Link konnte nicht aktualisiert werden",
+    ),
+    ("Link updated", "Link aktualisiert"),
+    ("Editing link: {}", "Link wird bearbeitet: {}"),
+    (
+        "Press Enter to keep current value, or type new value.",
+        "Drücke Enter, um den aktuellen Wert zu behalten, oder gib einen neuen Wert ein.",
+    ),
+    ("Current tags: {}", "Aktuelle Tags: {}"),
+    ("(none)", "(keine)"),
+    (
+        "New tags (comma-separated)",
+        "Neue Tags (durch Kommas getrennt)",
+    ),
+    ("No links to edit", "Keine Links zum Bearbeiten"),
+    ("{} row(s) have problems:", "{} Zeile(n) mit Problemen:"),
+    ("Edit again?", "Erneut bearbeiten?"),
+    ("No changes saved", "Keine Änderungen gespeichert"),
+    ("No changes", "Keine Änderungen"),
+    (
+        "Failed to save changes",
+        "Änderungen konnten nicht gespeichert werden",
+    ),
+    (
+        "Updated {} link(s), deleted {}",
+        "{} Link(s) aktualisiert, {} gelöscht",
+    ),
+    ("Delete link: {} - {}", "Link löschen: {} - {}"),
+    ("Failed to delete link", "Link konnte nicht gelöscht werden"),
+    ("Deleted link: {}", "Link gelöscht: {}"),
+    (
+        "Merged '{}' into '{}' ({} note(s) moved)",
+        "'{}' in '{}' zusammengeführt ({} Notiz(en) verschoben)",
+    ),
+    (
+        "Undo with: rott link unmerge",
+        "Rückgängig mit: rott link unmerge",
+    ),
+    (
+        "No merge to undo (the last edit isn't a merge)",
+        "Keine Zusammenführung zum Rückgängigmachen (die letzte Änderung ist keine \
+         Zusammenführung)",
+    ),
+    (
+        "Merging without prompting needs --yes (or use --dry-run to list duplicates)",
+        "Zusammenführen ohne Nachfrage braucht --yes (oder liste Duplikate mit --dry-run auf)",
+    ),
+    ("No duplicate links found", "Keine doppelten Links gefunden"),
+    ("Merge duplicate links", "Doppelte Links zusammenführen"),
+    ("group(s)", "Gruppe(n)"),
+    ("duplicates", "Duplikate"),
+    ("{} ({}) into {} ({})", "{} ({}) in {} ({})"),
+    ("keep: {} ({}) {}", "behalten: {} ({}) {}"),
+    (
+        "copy: {} ({}) {}, {} tag(s), {} note(s)",
+        "Kopie: {} ({}) {}, {} Tag(s), {} Notiz(en)",
+    ),
+    ("Merge these links?", "Diese Links zusammenführen?"),
+    (
+        "Merged {} duplicate link(s) in {} group(s)",
+        "{} doppelte(n) Link(s) in {} Gruppe(n) zusammengeführt",
+    ),
+    (
+        "Undo the last merge with: rott link unmerge",
+        "Letzte Zusammenführung rückgängig machen mit: rott link unmerge",
+    ),
+    ("Redo with: rott redo", "Wiederherstellen mit: rott redo"),
+    (
+        "⚠ Archives for {} aren't on this device yet; run `rott sync` to include them",
+        "⚠ Archive für {} sind noch nicht auf diesem Gerät; führe `rott sync` aus, um sie \
+         einzubeziehen",
+    ),
+    (
+        "Nothing to check. Use --content-changes to detect changed pages.",
+        "Nichts zu prüfen. Verwende --content-changes, um geänderte Seiten zu erkennen.",
+    ),
+    ("{} | {} | {} ({} bits)", "{} | {} | {} ({} Bit)"),
+    ("accepted", "übernommen"),
+    (
+        "Checked {} link(s): {} {}, {} new baseline(s), {} unreachable, {} retitled",
+        "{} Link(s) geprüft: {} {}, {} neue Referenz(en), {} nicht erreichbar, {} neu betitelt",
+    ),
+    (
+        "Failed to update links",
+        "Links konnten nicht aktualisiert werden",
+    ),
+    (
+        "Nothing to refresh. Use --untitled to retitle links saved without a title, or \
+         --failed-only to fetch again links whose metadata failed.",
+        "Nichts zu aktualisieren. Verwende --untitled, um Links ohne Titel neu zu betiteln, \
+         oder --failed-only, um Links mit fehlgeschlagenen Metadaten erneut abzurufen.",
+    ),
+    (
+        "Retitled {} of {} untitled link(s): {} from the page, {} from the URL",
+        "{} von {} Link(s) ohne Titel neu betitelt: {} von der Seite, {} aus der URL",
+    ),
+    (
+        "Refreshed {} of {} link(s) with failed metadata; {} still failing",
+        "{} von {} Link(s) mit fehlgeschlagenen Metadaten aktualisiert; bei {} schlägt es \
+         weiterhin fehl",
+    ),
+    (
+        "No link found matching: {}",
+        "Kein passender Link gefunden: {}",
+    ),
+    // CLI: privacy lock
+    ("Current passphrase:", "Aktuelle Passphrase:"),
+    ("Incorrect passphrase", "Falsche Passphrase"),
+    ("New passphrase:", "Neue Passphrase:"),
+    (
+        "Passphrase cannot be empty",
+        "Die Passphrase darf nicht leer sein",
+    ),
+    ("Confirm passphrase:", "Passphrase bestätigen:"),
+    (
+        "Passphrases don't match",
+        "Die Passphrasen stimmen nicht überein",
+    ),
+    (
+        "Privacy lock enabled (locks after {} minute(s) idle)",
+        "Sperre aktiviert (sperrt nach {} Minute(n) Inaktivität)",
+    ),
+    (
+        "Privacy lock is not enabled.",
+        "Die Sperre ist nicht aktiviert.",
+    ),
+    ("Passphrase:", "Passphrase:"),
+    ("Privacy lock disabled", "Sperre deaktiviert"),
+    (
+        "Privacy lock is not enabled. Set a passphrase with `rott lock enable`.",
+        "Die Sperre ist nicht aktiviert. Lege eine Passphrase mit `rott lock enable` fest.",
+    ),
+    (
+        "Unlocked (locks after {} minute(s) idle)",
+        "Entsperrt (sperrt nach {} Minute(n) Inaktivität)",
+    ),
+    (
+        "ROTT is locked. Run `rott unlock` first.",
+        "ROTT ist gesperrt. Führe zuerst `rott unlock` aus.",
+    ),
+    (
+        "Failed to read passphrase",
+        "Passphrase konnte nicht gelesen werden",
+    ),
+    // CLI: maintenance
+    ("Document Statistics", "Dokumentstatistik"),
+    ("Size:", "Größe:"),
+    ("Changes:", "Änderungen:"),
+    ("Operations:", "Operationen:"),
+    ("Actors:", "Akteure:"),
+    ("Changes by actor:", "Änderungen nach Akteur:"),
+    ("unknown", "unbekannt"),
+    (
+        "{}  {} changes  {} ops  last {}",
+        "{}  {} Änderungen  {} Ops  zuletzt {}",
+    ),
+    ("Largest links:", "Größte Links:"),
+    ("due", "fällig"),
+    (
+        "No maintenance jobs are due",
+        "Keine Wartungsaufgaben sind fällig",
+    ),
+    ("{}: failed: {}", "{}: fehlgeschlagen: {}"),
+    (
+        "{} maintenance job(s) failed",
+        "{} Wartungsaufgabe(n) fehlgeschlagen",
+    ),
+    (
+        "backed up {} link(s) to {} ({}), removed {} old backup(s)",
+        "{} Link(s) nach {} gesichert ({}), {} alte Sicherung(en) entfernt",
+    ),
+    (
+        "checked {} link(s): {} changed, {} new baseline(s), {} unreachable, {} retitled",
+        "{} Link(s) geprüft: {} geändert, {} neue Vergleichsbasis/-basen, {} nicht erreichbar, \
+         {} umbenannt",
+    ),
+    (
+        "archived {} expired link(s)",
+        "{} abgelaufene(n) Link(s) archiviert",
+    ),
+    (
+        "wrote {} ({} added, {} archived, {} deleted, {} device(s))",
+        "{} geschrieben ({} hinzugefügt, {} archiviert, {} gelöscht, {} Gerät(e))",
+    ),
+    (
+        "No duplicate notes found",
+        "Keine doppelten Notizen gefunden",
+    ),
+    ("Merge duplicate notes", "Doppelte Notizen zusammenführen"),
+    ("copies", "Kopien"),
+    ("keep on: {} ({})", "behalten bei: {} ({})"),
+    ("copy on: {} ({})", "Kopie bei: {} ({})"),
+    ("Merge these notes?", "Diese Notizen zusammenführen?"),
+    (
+        "Merged {} duplicate note(s) in {} group(s)",
+        "{} doppelte Notiz(en) in {} Gruppe(n) zusammengeführt",
+    ),
+    (
+        "Normalized tags on {} link(s)",
+        "Tags bei {} Link(s) vereinheitlicht",
+    ),
+    (
+        "The metadata cache is turned off (metadata_cache_hours = 0)",
+        "Der Metadaten-Cache ist ausgeschaltet (metadata_cache_hours = 0)",
+    ),
+    (
+        "Removed {} expired cached page(s)",
+        "{} abgelaufene zwischengespeicherte Seite(n) entfernt",
+    ),
+    (
+        "Removed {} cached page(s)",
+        "{} zwischengespeicherte Seite(n) entfernt",
+    ),
+    ("Would remove", "Würde entfernen"),
+    ("Removed", "Entfernt"),
+    (
+        "{} {} unreferenced attachment(s), reclaiming {}",
+        "{}: {} nicht referenzierte(r) Anhang/Anhänge, {} werden frei",
+    ),
+    ("{} attachment(s) in use", "{} Anhang/Anhänge in Verwendung"),
+    (
+        "{} unreferenced attachment(s) kept until they've been unused for {} day(s)",
+        "{} nicht referenzierte(r) Anhang/Anhänge bleiben, bis sie {} Tag(e) unbenutzt sind",
+    ),
+    ("(not built)", "(nicht erstellt)"),
+    (
+        "Rebuild one with: rott maintenance reindex <name> (or all)",
+        "Neu erstellen mit: rott maintenance reindex <name> (oder all)",
+    ),
+    ("Replayed {} event(s)", "{} Ereignis(se) wiederholt"),
+    ("Wrote the report to {}", "Bericht nach {} geschrieben"),
+    (
+        "Wrote an anonymized copy of {} link(s) and {} note(s) to {} ({})",
+        "Anonymisierte Kopie von {} Link(s) und {} Notiz(en) nach {} geschrieben ({})",
+    ),
+    (
+        "Titles, URLs, notes and tags are replaced; IDs and timestamps are kept, history isn't",
+        "Titel, URLs, Notizen und Tags werden ersetzt; IDs und Zeitstempel bleiben erhalten, \
+         der Verlauf nicht",
+    ),
+    // CLI: notes
+    ("Note not found: {}", "Notiz nicht gefunden: {}"),
+    (
+        "Failed to render note_template",
+        "note_template konnte nicht gerendert werden",
+    ),
+    ("Adding note to: {}", "Notiz hinzufügen zu: {}"),
+    (
+        "Failed to edit note",
+        "Notiz konnte nicht bearbeitet werden",
+    ),
+    (
+        "Note body cannot be empty",
+        "Der Notiztext darf nicht leer sein",
+    ),
+    (
+        "Added note {} to link {}",
+        "Notiz {} zu Link {} hinzugefügt",
+    ),
+    ("Editing note on: {}", "Notiz bearbeiten zu: {}"),
+    ("Note unchanged.", "Notiz unverändert."),
+    (
+        "Failed to update note",
+        "Notiz konnte nicht aktualisiert werden",
+    ),
+    ("Updated note: {}", "Notiz aktualisiert: {}"),
+    ("Delete note: {} - {}", "Notiz löschen: {} - {}"),
+    (
+        "Failed to delete note",
+        "Notiz konnte nicht gelöscht werden",
+    ),
+    ("Deleted note: {}", "Notiz gelöscht: {}"),
+    ("No history for note {}.", "Kein Verlauf für Notiz {}."),
+    ("unknown time", "unbekannte Zeit"),
+    ("Version {} ({}, {})", "Version {} ({}, {})"),
+    ("Title: {} -> {}", "Titel: {} -> {}"),
+    (
+        "No version {} (note has {} version(s))",
+        "Keine Version {} (die Notiz hat {} Version(en))",
+    ),
+    (
+        "Note already matches version {}.",
+        "Die Notiz entspricht bereits Version {}.",
+    ),
+    (
+        "Failed to restore note",
+        "Notiz konnte nicht wiederhergestellt werden",
+    ),
+    (
+        "Restored note {} to version {}",
+        "Notiz {} auf Version {} zurückgesetzt",
+    ),
+    (
+        "Failed to split note",
+        "Notiz konnte nicht aufgeteilt werden",
+    ),
+    (
+        "Nothing to split: the note has no more than one section.",
+        "Nichts aufzuteilen: Die Notiz hat nicht mehr als einen Abschnitt.",
+    ),
+    (
+        "Split note {} into {} notes",
+        "Notiz {} in {} Notizen aufgeteilt",
+    ),
+    ("--find can't be empty", "--find darf nicht leer sein"),
+    (
+        "Replacing without prompting needs --yes (or use --dry-run to preview)",
+        "Ersetzen ohne Nachfrage braucht --yes (oder nutze --dry-run für eine Vorschau)",
+    ),
+    ("No notes contain \"{}\"", "Keine Notiz enthält \"{}\""),
+    ("Replace \"{}\" in notes", "\"{}\" in Notizen ersetzen"),
+    ("note(s)", "Notiz(en)"),
+    ("occurrence(s)", "Vorkommen"),
+    ("{} ({}) note {}", "{} ({}) Notiz {}"),
+    (
+        "{} occurrence(s) in {} note(s)",
+        "{} Vorkommen in {} Notiz(en)",
+    ),
+    ("Replace {}?", "{} ersetzen?"),
+    ("Nothing changed.", "Nichts geändert."),
+    (
+        "Failed to update notes",
+        "Notizen konnten nicht aktualisiert werden",
+    ),
+    ("Replaced {}", "{} ersetzt"),
+    (
+        "No note found matching: {}",
+        "Keine passende Notiz gefunden: {}",
+    ),
+    (
+        "Multiple notes match '{}':",
+        "Mehrere Notizen passen zu '{}':",
+    ),
+    ("Replace text in notes", "Text in Notizen ersetzen"),
+    ("notes", "Notizen"),
+    // CLI: reading statistics
+    (
+        "{} session(s), {} min reading in total",
+        "{} Sitzung(en), insgesamt {} Min. gelesen",
+    ),
+    (
+        "Start a reading session with :read in the TUI.",
+        "Starte eine Lesesitzung mit :read in der TUI.",
+    ),
+    (
+        "{} of {} link(s) have notes ({}%): {} note(s), {} words, {} written",
+        "{} von {} Link(s) haben Notizen ({} %): {} Notiz(en), {} Wörter, {} geschrieben",
+    ),
+    (
+        "{} words per annotated link on average",
+        "Im Schnitt {} Wörter pro Link mit Notizen",
+    ),
+    ("Links by language:", "Links nach Sprache:"),
+    ("Unknown", "Unbekannt"),
+    ("{} {} saved  {} unread", "{} {} gespeichert  {} ungelesen"),
+    ("Most opened:", "Am häufigsten geöffnet:"),
+    // CLI: status
+    ("ROTT Status", "ROTT-Status"),
+    ("Root Document:", "Stammdokument:"),
+    ("ID:  {}", "ID:  {}"),
+    ("URL: {}", "URL: {}"),
+    ("Sync:", "Synchronisierung:"),
+    ("Status: {}", "Status: {}"),
+    ("enabled", "aktiviert"),
+    ("Server: {}", "Server: {}"),
+    ("Last synced {} ({})", "Zuletzt synchronisiert {} ({})"),
+    ("Never synced", "Noch nie synchronisiert"),
+    ("Storage:", "Speicher:"),
+    ("Location: {}", "Ort:   {}"),
+    ("Size:     {}", "Größe: {}"),
+    ("Contents:", "Inhalt:"),
+    ("Links: {}", "Links:    {}"),
+    ("Notes: {}", "Notizen:  {}"),
+    ("Maintenance:", "Wartung:"),
+    ("last run {}", "zuletzt ausgeführt {}"),
+    (
+        "last run {} (failed)",
+        "zuletzt ausgeführt {} (fehlgeschlagen)",
+    ),
+    ("never run", "noch nie ausgeführt"),
+    ("due now", "jetzt fällig"),
+    ("next {}", "nächste {}"),
+    // CLI: sync
+    (
+        "Performing initial sync to pull document from server...",
+        "Erste Synchronisierung lädt das Dokument vom Server...",
+    ),
+    (
+        "Initial sync complete! Your data has been downloaded.",
+        "Erste Synchronisierung abgeschlossen! Deine Daten wurden heruntergeladen.",
+    ),
+    (
+        "You can now use rott normally.",
+        "Du kannst rott jetzt normal verwenden.",
+    ),
+    (
+        "Initial sync failed: {}",
+        "Erste Synchronisierung fehlgeschlagen: {}",
+    ),
+    (
+        "Sync is not enabled. Enable it with:\n  rott config set sync_enabled true\n  rott \
+         config set sync_url ws://your-server:3030",
+        "Synchronisierung ist nicht aktiviert. Aktiviere sie mit:\n  rott config set \
+         sync_enabled true\n  rott config set sync_url ws://your-server:3030",
+    ),
+    (
+        "Sync URL not configured. Set it with:\n  rott config set sync_url ws://your-server:3030",
+        "Sync-URL nicht konfiguriert. Lege sie fest mit:\n  rott config set sync_url \
+         ws://your-server:3030",
+    ),
+    (
+        "Waiting for another sync to finish...",
+        "Warte auf das Ende einer anderen Synchronisierung...",
+    ),
+    (
+        "Timed out waiting for another sync to finish",
+        "Zeitüberschreitung beim Warten auf eine andere Synchronisierung",
+    ),
+    (
+        "Sync already in progress, skipping (use --wait to wait for it)",
+        "Synchronisierung läuft bereits, wird übersprungen (mit --wait darauf warten)",
+    ),
+    (
+        "Connecting to sync server...",
+        "Verbinde mit dem Sync-Server...",
+    ),
+    ("Syncing document {}...", "Synchronisiere Dokument {}..."),
+    ("Sync trace written to {}", "Sync-Trace nach {} geschrieben"),
+    (
+        "Sync complete - document updated",
+        "Synchronisierung abgeschlossen - Dokument aktualisiert",
+    ),
+    ("Links: {}, Notes: {}", "Links: {}, Notizen: {}"),
+    (
+        "Sync complete - already up to date",
+        "Synchronisierung abgeschlossen - bereits aktuell",
+    ),
+    ("Sync failed: {}", "Synchronisierung fehlgeschlagen: {}"),
+    ("Archive {} updated", "Archiv {} aktualisiert"),
+    ("Archive {} downloaded", "Archiv {} heruntergeladen"),
+    (
+        "Archive {} failed to sync: {}",
+        "Archiv {} konnte nicht synchronisiert werden: {}",
+    ),
+    (
+        "No sync traces yet. Trace a sync with:\n  rott sync --trace-sync\nor trace every sync \
+         with:\n  rott config set trace_sync true",
+        "Noch keine Sync-Traces. Zeichne eine Synchronisierung auf mit:\n  rott sync \
+         --trace-sync\noder zeichne jede Synchronisierung auf mit:\n  rott config set \
+         trace_sync true",
+    ),
+    ("Sync trace {} is empty", "Sync-Trace {} ist leer"),
+    ("{} (unchanged)", "{} (unverändert)"),
+    ("{} message(s), {}", "{} Nachricht(en), {}"),
+    ("Events:", "Ereignisse:"),
+    ("Trace", "Trace"),
+    ("Started", "Gestartet"),
+    ("Sent", "Gesendet"),
+    ("Received", "Empfangen"),
+    ("Messages", "Nachrichten"),
+    ("Heads", "Heads"),
+    // CLI: tags
+    (
+        "Give at least one of --all-of, --any-of, or --none-of",
+        "Gib mindestens eins von --all-of, --any-of oder --none-of an",
+    ),
+    ("No tags found.", "Keine Tags gefunden."),
+    (
+        "No unused tags to prune",
+        "Keine unbenutzten Tags zum Aufräumen",
+    ),
+    ("Would prune", "Würde aufräumen"),
+    ("Pruned", "Aufgeräumt"),
+    ("{} {} unused tag(s):", "{}: {} unbenutzte(s) Tag(s):"),
+    (
+        "Unused, but set in favorite_tags or triage_tags (left alone): {}",
+        "Unbenutzt, aber in favorite_tags oder triage_tags eingetragen (bleiben erhalten): {}",
+    ),
+    (
+        "Description of tag: {} (Markdown)",
+        "Beschreibung des Tags: {} (Markdown)",
+    ),
+    (
+        "Failed to edit description",
+        "Beschreibung konnte nicht bearbeitet werden",
+    ),
+    ("Described tag '{}'", "Tag '{}' beschrieben"),
+    (
+        "Cleared description of tag '{}'",
+        "Beschreibung von Tag '{}' entfernt",
+    ),
+    (
+        "No tags are marked private or public",
+        "Keine Tags sind als private oder public markiert",
+    ),
+    (
+        "Unknown visibility '{}' (use private, public, or default)",
+        "Unbekannte Sichtbarkeit '{}' (nutze private, public oder default)",
+    ),
+    ("Marked tag '{}' {}", "Tag '{}' als {} markiert"),
+    (
+        "Removed the visibility mark from tag '{}'",
+        "Sichtbarkeitsmarkierung von Tag '{}' entfernt",
+    ),
+    // CLI: tables and counts
+    ("ID", "ID"),
+    ("TITLE", "TITEL"),
+    ("NOTES", "NOTIZEN"),
+    ("FLAGS", "MARKER"),
+    ("SCORE", "PUNKTE"),
+    ("ISSUES", "PROBLEME"),
+    ("TAG", "TAG"),
+    ("AUTHOR", "AUTOR"),
+    ("LINKS", "LINKS"),
+    ("paywall", "Paywall"),
+    ("expired", "abgelaufen"),
+    ("No authors found.", "Keine Autoren gefunden."),
+    ("{} tag(s)", "{} Tag(s)"),
+    ("{} author(s)", "{} Autor(en)"),
+    ("Attachments", "Anhänge"),
+    ("Screenshot", "Screenshot"),
+    ("Tags", "Tags"),
+    ("Import", "Import"),
+    ("{} note", "{} Notiz"),
+    ("{} notes", "{} Notizen"),
+    ("{} word", "{} Wort"),
+    ("{} words", "{} Wörter"),
+    ("just now", "gerade eben"),
+    ("{} minute ago", "vor {} Minute"),
+    ("{} minutes ago", "vor {} Minuten"),
+    ("{} hour ago", "vor {} Stunde"),
+    ("{} hours ago", "vor {} Stunden"),
+    ("{} day ago", "vor {} Tag"),
+    ("{} days ago", "vor {} Tagen"),
+    ("{} change", "{} Änderung"),
+    ("{} changes", "{} Änderungen"),
+    ("{} round trip", "{} Roundtrip"),
+    ("{} round trips", "{} Roundtrips"),
+    (
+        "{} sent, {} received, {} in {}",
+        "{} gesendet, {} empfangen, {} in {}",
+    ),
+];
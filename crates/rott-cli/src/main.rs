@@ -3,19 +3,40 @@
 //! Command-line interface for ROTT - links and notes management.
 
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use chrono::NaiveDate;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use std::path::PathBuf;
+use std::process::ExitCode;
 use tracing_subscriber::EnvFilter;
 
-use rott_core::{Config, DocumentId, Identity, Store};
+use rott_core::query::TagFilter;
+use rott_core::{Config, DocumentId, Identity, Link, Store};
 
+mod api;
+mod bulk;
 mod commands;
+mod content;
+mod diff;
 mod editor;
+mod history;
+mod hypothesis;
+mod i18n;
 mod metadata;
 mod output;
+mod preview;
+mod profile;
+mod remote;
+mod screenshot;
+mod script;
+mod table;
 mod tui;
+mod viewer;
+mod watch;
 
+use i18n::{t, Locale};
 use output::{Output, OutputFormat};
+use profile::StartupProfile;
+use remote::{AddedNote, RemoteClient, RemoteTarget, Request};
 
 #[derive(Parser)]
 #[command(name = "rott")]
@@ -39,6 +60,18 @@ struct Cli {
     #[arg(short, long, global = true, action = clap::ArgAction::Count)]
     verbose: u8,
 
+    /// Print how long each startup phase took (config, load, first query)
+    #[arg(long, global = true)]
+    profile_startup: bool,
+
+    /// Use the collection on another machine (ssh://[user@]host[:port][/path/to/rott])
+    #[arg(long, global = true, value_name = "URL")]
+    remote: Option<String>,
+
+    /// Write each sync message to a trace file (see `rott sync debug last`)
+    #[arg(long, global = true)]
+    trace_sync: bool,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -55,28 +88,179 @@ enum Commands {
         /// Join an existing identity by providing root document ID
         #[arg(long, conflicts_with = "new")]
         join: Option<String>,
+        /// Create a new identity seeded with sample links and a TUI tour
+        #[arg(long, conflicts_with = "join")]
+        demo: bool,
+        /// With --join: only pull and read the document, never change it
+        #[arg(long, requires = "join")]
+        read_only: bool,
     },
     /// Device identity management
     Device {
         #[command(subcommand)]
         command: Option<DeviceCommands>,
     },
+    /// Create, list, or switch between collections
+    Collection {
+        #[command(subcommand)]
+        command: CollectionCommands,
+    },
     /// Manage links
     Link {
         #[command(subcommand)]
         command: LinkCommands,
     },
-    /// List all tags
-    Tags,
+    /// Work with notes across all links
+    Note {
+        #[command(subcommand)]
+        command: NotesCommands,
+    },
+    /// Save a link with a capture alias's tags and note template
+    Capture {
+        /// Capture alias (defined under [captures.<name>] in the config)
+        #[arg(required_unless_present = "from_selection")]
+        name: Option<String>,
+        /// URL to save
+        #[arg(required_unless_present = "from_selection")]
+        url: Option<String>,
+        /// Values for the note template, as key=value
+        vars: Vec<String>,
+        /// Save URLs found in the tmux buffer or the primary selection instead
+        #[arg(long, conflicts_with_all = ["name", "url", "vars"])]
+        from_selection: bool,
+        /// Tags for the links saved from the selection
+        #[arg(short, long, requires = "from_selection")]
+        tag: Vec<String>,
+    },
+    /// Import links from external sources
+    Import {
+        #[command(subcommand)]
+        command: ImportCommands,
+    },
+    /// Export the collection
+    Export {
+        /// Export the collection as it was at these document heads
+        /// (comma-separated, as written by --heads-file)
+        #[arg(long, global = true, value_name = "HEADS")]
+        at_heads: Option<String>,
+        /// Write the document heads the export was generated from to this file
+        #[arg(long, global = true, value_name = "FILE")]
+        heads_file: Option<PathBuf>,
+        #[command(subcommand)]
+        command: ExportCommands,
+    },
+    /// Search titles, URLs, tags, and notes with a regular expression
+    Grep {
+        /// Pattern to search for
+        pattern: String,
+        /// Case-insensitive matching
+        #[arg(short, long)]
+        ignore_case: bool,
+        /// Only print IDs of matching links
+        #[arg(short = 'l', long)]
+        links_only: bool,
+    },
+    /// List all tags, show them as a tree, or prune unused ones
+    Tags {
+        #[command(subcommand)]
+        command: Option<TagsCommands>,
+    },
+    /// Describe a tag or show its page
+    Tag {
+        #[command(subcommand)]
+        command: TagCommands,
+    },
+    /// List all authors
+    Authors,
+    /// Push notes to or pull annotations from Hypothes.is
+    Hypothesis {
+        #[command(subcommand)]
+        command: HypothesisCommands,
+    },
     /// Show or set configuration
     Config {
         #[command(subcommand)]
         command: Option<ConfigCommands>,
     },
     /// Show status (root doc ID, sync status)
-    Status,
+    Status {
+        /// Keep running and show the status again whenever the document changes
+        #[arg(long)]
+        watch: bool,
+    },
+    /// Check the document for records that can't be read in full
+    Doctor,
+    /// Undo the last edit (from the CLI or the TUI)
+    Undo,
+    /// Redo the last undone edit
+    Redo,
+    /// Answer requests from `rott --remote` (run over SSH), or serve the
+    /// web viewer or the JSON API
+    #[command(arg_required_else_help = true, args_conflicts_with_subcommands = true)]
+    Serve {
+        #[command(subcommand)]
+        command: Option<ServeCommands>,
+        /// Read requests from stdin and answer on stdout
+        #[arg(long)]
+        stdio: bool,
+    },
+    /// Summarize reading sessions (articles read, median minutes per article)
+    Stats {
+        /// Number of days to summarize
+        #[arg(long, default_value_t = 7)]
+        days: u32,
+    },
+    /// Lock now, or manage the privacy lock passphrase
+    Lock {
+        #[command(subcommand)]
+        command: Option<LockCommands>,
+    },
+    /// Enter the privacy lock passphrase to access data
+    Unlock,
+    /// Back up or restore everything (document, identity, config)
+    Backup {
+        #[command(subcommand)]
+        command: BackupCommands,
+    },
+    /// Bring selected links and notes back from a backup
+    Restore {
+        /// Backup archive or saved document to restore from
+        #[arg(long)]
+        from: PathBuf,
+        /// Link to restore (full ID or prefix, repeatable)
+        #[arg(long = "link")]
+        links: Vec<String>,
+        /// Restore every link with this tag (repeatable)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
+    /// Run automation scripts against the collection
+    Script {
+        #[command(subcommand)]
+        command: ScriptCommands,
+    },
+    /// Move old links into per-year archives, or bring them back
+    Archive {
+        #[command(subcommand)]
+        command: ArchiveCommands,
+    },
+    /// Diagnostics and upkeep for the document
+    Maintenance {
+        #[command(subcommand)]
+        command: MaintenanceCommands,
+    },
     /// Sync with remote server
-    Sync,
+    Sync {
+        #[command(subcommand)]
+        command: Option<SyncCommands>,
+        /// If another rott process is syncing, wait for it to finish and
+        /// then sync (instead of skipping)
+        #[arg(long)]
+        wait: bool,
+    },
+    /// Capture aliases can be run directly: `rott <alias> <url> [key=value...]`
+    #[command(external_subcommand)]
+    External(Vec<String>),
 }
 
 #[derive(Subcommand, Clone)]
@@ -85,6 +269,26 @@ enum DeviceCommands {
     Show,
 }
 
+#[derive(Subcommand, Clone)]
+enum CollectionCommands {
+    /// List collections (* marks the active one)
+    #[command(alias = "ls")]
+    List,
+    /// Create a collection
+    Create {
+        /// Collection name (letters, digits, '-' and '_')
+        name: String,
+        /// Join a collection from another device by its document ID
+        #[arg(long, value_name = "ID")]
+        join: Option<String>,
+    },
+    /// Make a collection the active one ("default" for the original)
+    Switch {
+        /// Collection name
+        name: String,
+    },
+}
+
 #[derive(Subcommand)]
 enum LinkCommands {
     /// Create a new link
@@ -95,29 +299,95 @@ enum LinkCommands {
         /// Tags to add
         #[arg(short, long)]
         tag: Vec<String>,
+        /// Who recommended it
+        #[arg(long)]
+        via: Option<String>,
+        /// Where it was found (e.g. "HN thread", optionally with its URL)
+        #[arg(long)]
+        source: Option<String>,
+        /// Date the link stops being useful, e.g. an event or deadline (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        expires: Option<NaiveDate>,
     },
     /// List all links
     #[command(alias = "ls")]
     List {
         /// Filter by tag
-        #[arg(short, long)]
+        #[arg(short, long, conflicts_with_all = ["favorites", "untagged"])]
         tag: Option<String>,
+        /// Only favorite links (see the favorite_tags config key)
+        #[arg(long, conflicts_with = "untagged")]
+        favorites: bool,
+        /// Only links without tags
+        #[arg(long)]
+        untagged: bool,
+        /// Only links never opened (on any synced device)
+        #[arg(long)]
+        unread: bool,
+        /// Only links by this author (ignoring case)
+        #[arg(long)]
+        author: Option<String>,
+        /// Only links in this language (code like `de`, or name like `german`)
+        #[arg(long)]
+        lang: Option<String>,
+        /// Only links published on or after this date (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        published_after: Option<NaiveDate>,
+        /// Only links published before this date (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        published_before: Option<NaiveDate>,
+        /// Most recently updated first
+        #[arg(long, conflicts_with_all = ["stale", "by_published"])]
+        recent: bool,
+        /// Most recently published first (links without a date last)
+        #[arg(long, conflicts_with = "stale")]
+        by_published: bool,
+        /// Only links whose expiry date has passed
+        #[arg(long)]
+        expired: bool,
+        /// Only links needing attention (unreachable, changed, forgotten), worst first
+        #[arg(long)]
+        stale: bool,
+        /// Include archived links
+        #[arg(long)]
+        archived: bool,
+        /// Keep running and list again whenever the document changes
+        #[arg(long)]
+        watch: bool,
     },
     /// Show link details (including notes)
     Show {
         /// Link ID (full UUID or prefix)
         id: String,
     },
-    /// Edit a link
-    Edit {
+    /// Open a link with its configured opener (see `openers`), or in the browser
+    Open {
         /// Link ID (full UUID or prefix)
         id: String,
+    },
+    /// Edit a link, or several at once in $EDITOR with --bulk
+    Edit {
+        /// Link ID (full UUID or prefix)
+        #[arg(required_unless_present = "bulk")]
+        id: Option<String>,
+        /// Edit links as a table in $EDITOR (title, url, tags, status per row)
+        #[arg(long, conflicts_with_all = ["id", "add_tags", "remove_tags", "expires", "no_expires"])]
+        bulk: bool,
+        /// With --bulk, only links with this tag
+        #[arg(long, requires = "bulk")]
+        tag: Option<String>,
         /// Add a tag (can be repeated)
         #[arg(long = "add-tag")]
         add_tags: Vec<String>,
         /// Remove a tag (can be repeated)
         #[arg(long = "remove-tag")]
         remove_tags: Vec<String>,
+        /// Set the date the link expires (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        expires: Option<NaiveDate>,
+        /// Clear the link's expiry date
+        #[arg(long, conflicts_with = "expires")]
+        no_expires: bool,
     },
     /// Delete a link
     #[command(alias = "rm")]
@@ -129,6 +399,55 @@ enum LinkCommands {
     Search {
         /// Search query
         query: String,
+        /// Search archived links too
+        #[arg(long)]
+        archived: bool,
+    },
+    /// Check saved links for changes
+    Check {
+        /// Report pages whose content changed significantly since saving
+        #[arg(long)]
+        content_changes: bool,
+        /// Accept the current content of changed pages as the new baseline
+        #[arg(long, requires = "content_changes")]
+        accept: bool,
+    },
+    /// Screenshot a link's page with the screenshot_command and attach it
+    Snapshot {
+        /// Link ID (full UUID or prefix)
+        id: String,
+    },
+    /// Keep an offline copy of a link's page text
+    ArchiveContent {
+        /// Link ID (full UUID or prefix)
+        id: String,
+    },
+    /// Merge a duplicate link into another (tags, authors, notes) and delete it
+    Merge {
+        /// Link to keep (full UUID or prefix)
+        keep: String,
+        /// Duplicate to merge into it and delete (full UUID or prefix)
+        duplicate: String,
+    },
+    /// Undo the last link merge, if it's the last edit (see `rott undo`)
+    Unmerge,
+    /// Find links saved more than once (by normalized URL) and merge them
+    Dedupe {
+        /// Merge every group without asking
+        #[arg(short, long)]
+        yes: bool,
+        /// List duplicate links without merging them
+        #[arg(long, conflicts_with = "yes")]
+        dry_run: bool,
+    },
+    /// Fetch pages again to fix up saved links
+    Refresh {
+        /// Retitle links saved without a title (the page's title, or one made from the URL)
+        #[arg(long)]
+        untitled: bool,
+        /// Fetch again only links whose metadata failed, filling in the parts that failed
+        #[arg(long, conflicts_with = "untitled")]
+        failed_only: bool,
     },
     /// Manage notes on a link
     Note {
@@ -150,6 +469,9 @@ enum NoteCommands {
         /// Note body (opens editor if not provided)
         #[arg(short, long)]
         body: Option<String>,
+        /// Reply to a note (ID or prefix), threading it under that note
+        #[arg(short, long = "reply-to")]
+        reply_to: Option<String>,
     },
     /// List notes on a link
     #[command(alias = "ls")]
@@ -157,6 +479,19 @@ enum NoteCommands {
         /// Link ID (full UUID or prefix)
         link_id: String,
     },
+    /// Edit a note's title or body
+    Edit {
+        /// Link ID (full UUID or prefix)
+        link_id: String,
+        /// Note ID (full UUID or prefix)
+        note_id: String,
+        /// New title (an empty title removes it)
+        #[arg(short = 'T', long)]
+        title: Option<String>,
+        /// New body (opens editor if neither it nor --title is given)
+        #[arg(short, long)]
+        body: Option<String>,
+    },
     /// Delete a note from a link
     #[command(alias = "rm")]
     Delete {
@@ -165,6 +500,419 @@ enum NoteCommands {
         /// Note ID (full UUID or prefix)
         note_id: String,
     },
+    /// Show the edit history of a note
+    History {
+        /// Link ID (full UUID or prefix)
+        link_id: String,
+        /// Note ID (full UUID or prefix)
+        note_id: String,
+        /// Show each version in full instead of as a diff
+        #[arg(long)]
+        full: bool,
+    },
+    /// Restore a note to an earlier version
+    Restore {
+        /// Link ID (full UUID or prefix)
+        link_id: String,
+        /// Note ID (full UUID or prefix)
+        note_id: String,
+        /// Version number from `rott link note history`
+        version: usize,
+    },
+    /// Split a note into one note per top-level Markdown heading
+    Split {
+        /// Link ID (full UUID or prefix)
+        link_id: String,
+        /// Note ID (full UUID or prefix)
+        note_id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum NotesCommands {
+    /// Find and replace text in note bodies, in a single change
+    Replace {
+        /// Text to find (matched exactly, including case)
+        #[arg(long)]
+        find: String,
+        /// Text to put in its place
+        #[arg(long)]
+        replace: String,
+        /// Only change notes on links with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Apply without asking
+        #[arg(short, long)]
+        yes: bool,
+        /// Show the changes without making them
+        #[arg(long, conflicts_with = "yes")]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ScriptCommands {
+    /// Run a Rhai script; its changes are saved together when it finishes
+    Run {
+        /// Script file (e.g. cleanup.rhai)
+        file: PathBuf,
+        /// Arguments passed to the script as `args`
+        #[arg(trailing_var_arg = true)]
+        args: Vec<String>,
+        /// List what the script would change without saving it
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum BackupCommands {
+    /// Write a self-contained backup file
+    Export {
+        /// Backup file to write (e.g. rott.rottpack)
+        file: PathBuf,
+    },
+    /// Restore from a backup file
+    Import {
+        /// Backup file to read
+        file: PathBuf,
+        /// Replace existing data on this machine
+        #[arg(long)]
+        force: bool,
+        /// Keep this machine's configuration instead of restoring the backed-up one
+        #[arg(long)]
+        no_config: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ArchiveCommands {
+    /// Move links into the archive for the year they were saved
+    Move {
+        /// Links saved more than this many years ago
+        #[arg(long, value_name = "YEARS", required_unless_present = "tag")]
+        older_than: Option<u32>,
+        /// Links with this tag (with --older-than, only old links with it)
+        #[arg(long)]
+        tag: Option<String>,
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+        /// List the links that would be archived without moving them
+        #[arg(long, conflicts_with = "yes")]
+        dry_run: bool,
+    },
+    /// List archives by year
+    #[command(alias = "ls")]
+    List,
+    /// Move an archived link back
+    Restore {
+        /// Link ID (full UUID or prefix)
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum MaintenanceCommands {
+    /// Show document history and size statistics
+    DocStats {
+        /// Number of largest links to list
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+    },
+    /// Run the scheduled jobs (backups, link checks, reports) that are due
+    RunDue {
+        /// List the jobs that are due without running them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Merge identical notes duplicated across links
+    DedupeNotes {
+        /// Merge every group without asking
+        #[arg(short, long)]
+        yes: bool,
+        /// List duplicate notes without merging them
+        #[arg(long, conflicts_with = "yes")]
+        dry_run: bool,
+    },
+    /// Merge tags that differ only in case or punctuation (e.g. synced
+    /// from an older version)
+    NormalizeTags,
+    /// Empty the cache of fetched page metadata
+    ClearCache {
+        /// Only remove entries older than metadata_cache_hours
+        #[arg(long)]
+        expired: bool,
+    },
+    /// Delete attachment files no link refers to any more
+    Gc {
+        /// Only delete files unreferenced for at least this many days
+        #[arg(long, value_name = "DAYS", default_value_t = rott_core::attachments::DEFAULT_GRACE_DAYS)]
+        grace_days: u32,
+        /// Report what would be deleted without deleting it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Rebuild a projection (activity, backlinks, or all) from the document's
+    /// history; lists them if none is given
+    Reindex {
+        /// Projection to rebuild, or "all"
+        projection: Option<String>,
+    },
+    /// Print the document's history as events, one JSON object per line
+    Events,
+    /// Report what changed: links added, archived and deleted, new tags,
+    /// and each device's activity
+    Report {
+        /// Period to report on, up to now (e.g. 7d, 4w, 48h)
+        #[arg(long, default_value = "7d")]
+        since: String,
+        /// Write the Markdown report to this file instead of printing it
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Write a copy of the document with all text replaced by placeholders,
+    /// to attach to bug reports
+    Anonymize {
+        /// File to write the copy to (e.g. repro.automerge)
+        #[arg(long)]
+        out: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum ImportCommands {
+    /// Suggest frequently-visited pages from browser history
+    History {
+        /// Browser to read history from
+        #[arg(long, value_enum)]
+        browser: history::Browser,
+        /// Browser profile directory (defaults to the default profile)
+        #[arg(long)]
+        profile: Option<PathBuf>,
+        /// Minimum number of visits for a page to be suggested
+        #[arg(long, default_value_t = 5)]
+        min_visits: u32,
+        /// Only consider visits within this window (e.g. 90d, 12w, 48h)
+        #[arg(long, default_value = "90d")]
+        since: String,
+        /// Tags to add to imported links
+        #[arg(short, long)]
+        tag: Vec<String>,
+        /// Save all suggestions without prompting
+        #[arg(short, long)]
+        yes: bool,
+        /// Preview the links that would be saved without saving any
+        #[arg(long, conflicts_with = "yes")]
+        dry_run: bool,
+    },
+    /// Save bookmarks from a browser's bookmark HTML export, folders as tags
+    Bookmarks {
+        /// Exported bookmarks file (Netscape bookmark HTML)
+        file: PathBuf,
+        /// Tags to add to imported links
+        #[arg(short, long)]
+        tag: Vec<String>,
+        /// Preview the links that would be saved without saving any
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Review and merge links and notes from someone else's document or backup
+    Document {
+        /// Saved document or backup archive shared with you
+        file: PathBuf,
+        /// Only consider links with this tag (repeatable)
+        #[arg(long)]
+        tag: Vec<String>,
+        /// Accept every incoming item without reviewing
+        #[arg(short, long)]
+        yes: bool,
+        /// List incoming items without merging anything
+        #[arg(long, conflicts_with = "yes")]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum HypothesisCommands {
+    /// Push a link's notes as private Hypothes.is page notes
+    Push {
+        /// Link ID (full UUID or prefix)
+        id: String,
+    },
+    /// Add a link's Hypothes.is annotations as notes
+    Pull {
+        /// Link ID (full UUID or prefix)
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum TagsCommands {
+    /// Show tags as a tree (`lang-rust` under `lang`), with link counts
+    Tree,
+    /// Remove descriptions and visibility marks of tags no link uses
+    Prune {
+        /// Show what would be removed without removing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// List links by a combination of tags, e.g. for piping into
+    /// `xargs rott link ...`
+    Links {
+        /// Tags a link must have all of (comma-separated)
+        #[arg(long, value_delimiter = ',', value_name = "TAGS")]
+        all_of: Vec<String>,
+        /// Tags a link must have at least one of (comma-separated)
+        #[arg(long, value_delimiter = ',', value_name = "TAGS")]
+        any_of: Vec<String>,
+        /// Tags a link must have none of (comma-separated)
+        #[arg(long, value_delimiter = ',', value_name = "TAGS")]
+        none_of: Vec<String>,
+        /// Print only the links' IDs, one per line
+        #[arg(long)]
+        ids_only: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum TagCommands {
+    /// Set a tag's description (Markdown); opens $EDITOR if no text is given
+    Describe {
+        /// Tag name
+        tag: String,
+        /// Description text
+        text: Option<String>,
+        /// Remove the description
+        #[arg(long, conflicts_with = "text")]
+        clear: bool,
+    },
+    /// Show a tag's description and links
+    Show {
+        /// Tag name
+        tag: String,
+    },
+    /// Mark a tag private (left out of exports) or public; without
+    /// arguments, list marked tags
+    Visibility {
+        /// Tag name
+        tag: Option<String>,
+        /// private, public, or default (removes the mark)
+        #[arg(requires = "tag")]
+        visibility: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ServeCommands {
+    /// Serve a read-only web viewer of the collection, for browsing from
+    /// other devices on the same network
+    Viewer {
+        /// Port to listen on
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+        /// Address to listen on (127.0.0.1 keeps the viewer to this machine)
+        #[arg(long, default_value = "0.0.0.0")]
+        bind: String,
+        /// Show links with a private tag too
+        #[arg(long)]
+        include_private: bool,
+    },
+    /// Serve a JSON API for browser extensions, shortcuts and scripts
+    /// (needs `api_token`)
+    Api {
+        /// Port to listen on
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+        /// Address to listen on (0.0.0.0 to take requests from other devices)
+        #[arg(long, default_value = "127.0.0.1")]
+        bind: String,
+    },
+}
+
+#[derive(Subcommand, Clone)]
+enum SyncCommands {
+    /// Inspect traces written with --trace-sync
+    Debug {
+        #[command(subcommand)]
+        command: SyncDebugCommands,
+    },
+}
+
+#[derive(Subcommand, Clone)]
+enum SyncDebugCommands {
+    /// Summarize the most recent sync trace
+    Last,
+}
+
+#[derive(Subcommand)]
+enum ExportCommands {
+    /// Flat, greppable text: one line per field, prefixed by link ID
+    Text {
+        /// Output file (defaults to stdout)
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+        /// Include links with a private tag
+        #[arg(long)]
+        include_private: bool,
+    },
+    /// Standalone SQLite database for analysis
+    Sqlite {
+        /// Output file (replaced if it exists)
+        #[arg(short, long)]
+        out: PathBuf,
+        /// Include links with a private tag
+        #[arg(long)]
+        include_private: bool,
+    },
+    /// Every field of every link and note, as a JSON array
+    Json {
+        /// Output file (defaults to stdout)
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+        /// Only links with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Include links with a private tag
+        #[arg(long)]
+        include_private: bool,
+    },
+    /// One Markdown file per link, with YAML frontmatter
+    Markdown {
+        /// Directory to write the files to (created if needed)
+        #[arg(short, long)]
+        out: PathBuf,
+        /// Only links with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Include links with a private tag
+        #[arg(long)]
+        include_private: bool,
+        /// Only write files that changed since the last export to the
+        /// directory, and remove those of links no longer exported
+        #[arg(long)]
+        incremental: bool,
+    },
+    /// CSV with one row per link (notes in the last column)
+    Csv {
+        /// Output file (defaults to stdout)
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+        /// Only links with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Include links with a private tag
+        #[arg(long)]
+        include_private: bool,
+    },
+}
+
+#[derive(Subcommand, Clone)]
+enum LockCommands {
+    /// Set a passphrase (or change it) and enable the lock
+    Enable,
+    /// Remove the passphrase and disable the lock
+    Disable,
 }
 
 #[derive(Subcommand, Clone)]
@@ -178,11 +926,60 @@ enum ConfigCommands {
         /// Configuration value
         value: String,
     },
+    /// Export settings as a bundle for another machine
+    Export {
+        /// File to write (prints to stdout if omitted)
+        file: Option<PathBuf>,
+        /// Include the privacy lock passphrase hash, Hypothes.is token and add_token
+        #[arg(long)]
+        include_secrets: bool,
+    },
+    /// Replace settings with an exported bundle (keeps this machine's data_dir)
+    Import {
+        /// Bundle written by `rott config export`
+        file: PathBuf,
+    },
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
+async fn main() -> ExitCode {
+    // Pick the language before anything is printed, help included
+    let configured = Config::load_with_cli_override(config_arg().as_ref())
+        .ok()
+        .and_then(|config| config.locale);
+    i18n::init(Locale::resolve(configured.as_deref()));
+
+    let matches = i18n::localize_command(Cli::command()).get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}: {:?}", t("Error"), e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// The `--config` path, read from the arguments before clap parses them
+fn config_arg() -> Option<PathBuf> {
+    let mut args = std::env::args_os().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        }
+        if arg == "-c" || arg == "--config" {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.to_str().and_then(|arg| arg.strip_prefix("--config=")) {
+            return Some(PathBuf::from(path));
+        }
+    }
+    None
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    let mut profile = StartupProfile::new(cli.profile_startup);
 
     // Initialize logging for CLI (TUI initializes its own)
     let is_tui = matches!(&cli.command, Some(Commands::Tui) | None);
@@ -192,13 +989,78 @@ async fn main() -> Result<()> {
 
     let output = Output::new(OutputFormat::from_flags(cli.json, cli.quiet));
 
+    if let Some(ref remote) = cli.remote {
+        return handle_remote_command(remote, cli.command, &output);
+    }
+
     // Commands that don't need initialization or the store
     match &cli.command {
         Some(Commands::Config { command }) => {
             return handle_config_command(command.clone(), cli.config.as_ref(), &output);
         }
-        Some(Commands::Init { new, join }) => {
-            return handle_init_command(*new, join.clone(), cli.config.as_ref(), &output);
+        Some(Commands::Init {
+            new,
+            join,
+            demo,
+            read_only,
+        }) => {
+            return handle_init_command(
+                *new,
+                join.clone(),
+                *demo,
+                *read_only,
+                cli.config.as_ref(),
+                &output,
+            );
+        }
+        Some(Commands::Lock { command }) => {
+            return handle_lock_command(command.clone(), cli.config.as_ref(), &output);
+        }
+        Some(Commands::Collection { command }) => {
+            return handle_collection_command(command.clone(), cli.config.as_ref(), &output);
+        }
+        Some(Commands::Unlock) => {
+            return commands::lock::unlock(cli.config.as_ref(), &output);
+        }
+        Some(Commands::Sync {
+            command: Some(SyncCommands::Debug { command }),
+            ..
+        }) => {
+            let config = Config::load_with_cli_override(cli.config.as_ref())?;
+            return match command {
+                SyncDebugCommands::Last => commands::sync::debug_last(&config, &output),
+            };
+        }
+        Some(Commands::External(args)) => {
+            // Only capture aliases are run this way
+            let config = Config::load_with_cli_override(cli.config.as_ref())?;
+            match args.first() {
+                Some(name) if config.captures.contains_key(name) => {}
+                Some(name) => anyhow::bail!(
+                    "Unknown command '{}'. Run `rott --help` for commands, or define a \
+                     capture alias under [captures.{}] in the config file.",
+                    name,
+                    name
+                ),
+                None => anyhow::bail!("No command given"),
+            }
+        }
+        Some(Commands::Backup {
+            command:
+                BackupCommands::Import {
+                    file,
+                    force,
+                    no_config,
+                },
+        }) => {
+            // Restoring works before init, so it can set up a new machine
+            return commands::backup::import(
+                file.clone(),
+                *force,
+                *no_config,
+                cli.config.as_ref(),
+                &output,
+            );
         }
         _ => {}
     }
@@ -209,6 +1071,9 @@ async fn main() -> Result<()> {
         // For TUI, we'll handle setup there
         if matches!(&cli.command, Some(Commands::Tui) | None) {
             // TUI will handle its own setup flow
+        } else if matches!(&cli.command, Some(Commands::Serve { stdio: true, .. })) {
+            // Setup prompts would be sent to the remote client
+            anyhow::bail!("ROTT isn't set up on this machine. Run `rott init` here first.");
         } else {
             // For CLI commands, run interactive setup first
             run_first_time_setup(&output)?;
@@ -217,9 +1082,13 @@ async fn main() -> Result<()> {
 
     // Handle TUI (default when no command given)
     if matches!(&cli.command, Some(Commands::Tui) | None) {
-        return tui::run(cli.config.as_ref()).await;
+        return tui::run(cli.config.as_ref(), cli.trace_sync, profile).await;
     }
 
+    // Enforce the privacy lock before showing any data (the TUI has its own prompt)
+    let lock_config = Config::load_with_cli_override(cli.config.as_ref())?;
+    commands::lock::ensure_unlocked(&lock_config, &output)?;
+
     // Handle device command (doesn't need full store)
     if let Some(Commands::Device { command }) = &cli.command {
         return handle_device_command(command.clone(), &output);
@@ -228,7 +1097,7 @@ async fn main() -> Result<()> {
     // Check for pending sync state (joined but not yet synced)
     // Sync command should work in this state to perform initial sync
     if identity.is_pending_sync()? {
-        if matches!(&cli.command, Some(Commands::Sync)) {
+        if matches!(&cli.command, Some(Commands::Sync { .. })) {
             let config = Config::load_with_cli_override(cli.config.as_ref())?;
             return commands::sync::initial_sync(&config, &output).await;
         } else {
@@ -246,7 +1115,9 @@ async fn main() -> Result<()> {
 
     // Open store for commands that need it
     let config = Config::load_with_cli_override(cli.config.as_ref())?;
+    profile.mark("config");
     let mut store = Store::open_with_config(config)?;
+    profile.mark("load document");
 
     // Determine if this is a read or write command
     let is_write = matches!(
@@ -257,59 +1128,431 @@ async fn main() -> Result<()> {
             command: LinkCommands::Edit { .. }
         }) | Some(Commands::Link {
             command: LinkCommands::Delete { .. }
+        }) | Some(Commands::Link {
+            command: LinkCommands::Check { .. }
+        }) | Some(Commands::Link {
+            command: LinkCommands::Refresh { .. }
+        }) | Some(Commands::Link {
+            command: LinkCommands::Snapshot { .. }
+        }) | Some(Commands::Link {
+            command: LinkCommands::ArchiveContent { .. }
+        }) | Some(Commands::Link {
+            command: LinkCommands::Merge { .. }
+        }) | Some(Commands::Link {
+            command: LinkCommands::Unmerge
+        }) | Some(Commands::Link {
+            command: LinkCommands::Dedupe { dry_run: false, .. }
         }) | Some(Commands::Link {
             command: LinkCommands::Note {
                 command: NoteCommands::Create { .. }
             }
+        }) | Some(Commands::Link {
+            command: LinkCommands::Note {
+                command: NoteCommands::Edit { .. }
+            }
         }) | Some(Commands::Link {
             command: LinkCommands::Note {
                 command: NoteCommands::Delete { .. }
             }
-        })
+        }) | Some(Commands::Link {
+            command: LinkCommands::Note {
+                command: NoteCommands::Restore { .. }
+            }
+        }) | Some(Commands::Link {
+            command: LinkCommands::Note {
+                command: NoteCommands::Split { .. }
+            }
+        }) | Some(Commands::Note {
+            command: NotesCommands::Replace { dry_run: false, .. }
+        }) | Some(Commands::Tags {
+            command: Some(TagsCommands::Prune { dry_run: false })
+        }) | Some(Commands::Tag {
+            command: TagCommands::Describe { .. }
+        }) | Some(Commands::Tag {
+            command: TagCommands::Visibility {
+                visibility: Some(_),
+                ..
+            }
+        }) | Some(Commands::Hypothesis {
+            command: HypothesisCommands::Pull { .. }
+        }) | Some(Commands::Capture { .. })
+            | Some(Commands::Undo)
+            | Some(Commands::Redo)
+            | Some(Commands::External(_))
+            | Some(Commands::Serve { stdio: true, .. })
+            | Some(Commands::Import {
+                command: ImportCommands::History { dry_run: false, .. }
+            })
+            | Some(Commands::Import {
+                command: ImportCommands::Bookmarks { dry_run: false, .. }
+            })
+            | Some(Commands::Import {
+                command: ImportCommands::Document { dry_run: false, .. }
+            })
+            | Some(Commands::Restore { .. })
+            | Some(Commands::Script {
+                command: ScriptCommands::Run { dry_run: false, .. }
+            })
+            | Some(Commands::Archive {
+                command: ArchiveCommands::Move { dry_run: false, .. }
+            })
+            | Some(Commands::Archive {
+                command: ArchiveCommands::Restore { .. }
+            })
+            | Some(Commands::Maintenance {
+                command: MaintenanceCommands::RunDue { dry_run: false }
+            })
+            | Some(Commands::Maintenance {
+                command: MaintenanceCommands::DedupeNotes { dry_run: false, .. }
+            })
+            | Some(Commands::Maintenance {
+                command: MaintenanceCommands::NormalizeTags
+            })
     );
 
-    let is_manual_sync = matches!(&cli.command, Some(Commands::Sync));
+    let is_manual_sync = matches!(&cli.command, Some(Commands::Sync { .. }));
 
     // Sync before read commands (to get latest data)
     if !is_write && !is_manual_sync {
-        auto_sync(&mut store, cli.config.as_ref(), &output).await;
+        auto_sync(&mut store, cli.config.as_ref(), cli.trace_sync, &output).await;
+        profile.mark("sync");
     }
 
     let result = match cli.command.unwrap() {
-        Commands::Tui => unreachable!(),           // Handled above
-        Commands::Init { .. } => unreachable!(),   // Handled above
-        Commands::Device { .. } => unreachable!(), // Handled above
+        Commands::Tui => unreachable!(),               // Handled above
+        Commands::Init { .. } => unreachable!(),       // Handled above
+        Commands::Device { .. } => unreachable!(),     // Handled above
+        Commands::Collection { .. } => unreachable!(), // Handled above
         Commands::Link { command } => handle_link_command(command, &mut store, &output).await,
-        Commands::Tags => commands::tag::list(&store, &output),
+        Commands::Note { command } => handle_notes_command(command, &mut store, &output),
+        Commands::Capture {
+            name,
+            url,
+            vars,
+            from_selection,
+            tag,
+        } => match (name, url) {
+            (Some(name), Some(url)) if !from_selection => {
+                commands::capture::capture(&mut store, name, url, vars, &output).await
+            }
+            _ => commands::capture::from_selection(&mut store, tag, &output).await,
+        },
+        Commands::External(args) => handle_capture_alias(args, &mut store, &output).await,
+        Commands::Import { command } => handle_import_command(command, &mut store, &output),
+        Commands::Export {
+            at_heads,
+            heads_file,
+            command,
+        } => handle_export_command(command, at_heads, heads_file, &store, &output),
+        Commands::Grep {
+            pattern,
+            ignore_case,
+            links_only,
+        } => commands::grep::grep(&store, pattern, ignore_case, links_only, &output),
+        Commands::Tags { command } => handle_tags_command(command, &mut store, &output),
+        Commands::Tag { command } => handle_tag_command(command, &mut store, &output),
+        Commands::Authors => commands::author::list(&store, &output),
+        Commands::Hypothesis { command } => {
+            handle_hypothesis_command(command, &mut store, &output).await
+        }
         Commands::Config { .. } => unreachable!(), // Handled above
-        Commands::Status => commands::status::show(&store, &output),
-        Commands::Sync => commands::sync::sync(&mut store, cli.config.as_ref(), &output).await,
+        Commands::Lock { .. } => unreachable!(),   // Handled above
+        Commands::Unlock => unreachable!(),        // Handled above
+        Commands::Status { watch: false } => commands::status::show(&store, &output),
+        Commands::Status { watch: true } => {
+            watch::watch(&mut store, &output, |store| {
+                commands::status::show(store, &output)
+            })
+            .await
+        }
+        Commands::Doctor => commands::doctor::check(&store, &output),
+        Commands::Undo => commands::link::undo(&mut store, &output),
+        Commands::Redo => commands::link::redo(&mut store, &output),
+        Commands::Serve {
+            command:
+                Some(ServeCommands::Viewer {
+                    port,
+                    bind,
+                    include_private,
+                }),
+            ..
+        } => viewer::serve(&mut store, &bind, port, include_private, &output).await,
+        Commands::Serve {
+            command: Some(ServeCommands::Api { port, bind }),
+            ..
+        } => api::serve(&mut store, &bind, port, &output).await,
+        Commands::Serve { stdio: true, .. } => remote::serve_stdio(&mut store).await,
+        Commands::Serve { .. } => {
+            anyhow::bail!(
+                "Use `rott serve viewer`, `rott serve api`, or `rott serve --stdio` for `rott --remote`"
+            )
+        }
+        Commands::Stats { days } => commands::stats::show(&store, days, &output),
+        Commands::Backup { command } => handle_backup_command(command, &store, &output),
+        Commands::Restore { from, links, tags } => {
+            commands::backup::restore(&mut store, from, links, tags, &output)
+        }
+        Commands::Script {
+            command:
+                ScriptCommands::Run {
+                    file,
+                    args,
+                    dry_run,
+                },
+        } => commands::script::run(&mut store, file, args, dry_run, &output),
+        Commands::Archive { command } => handle_archive_command(command, &mut store, &output),
+        Commands::Maintenance { command } => {
+            handle_maintenance_command(command, &mut store, &output).await
+        }
+        Commands::Sync { wait, .. } => {
+            commands::sync::sync(
+                &mut store,
+                cli.config.as_ref(),
+                wait,
+                cli.trace_sync,
+                &output,
+            )
+            .await
+        }
     };
 
+    profile.mark("command");
+
     // Sync after write commands (to push changes)
     if is_write {
-        auto_sync(&mut store, cli.config.as_ref(), &output).await;
+        auto_sync(&mut store, cli.config.as_ref(), cli.trace_sync, &output).await;
+        profile.mark("sync");
     }
 
+    profile.report();
     result
 }
 
+/// Run a command against the collection on another machine
+///
+/// Only commands with a matching [`Request`] work remotely; the rest are
+/// refused before connecting.
+fn handle_remote_command(remote: &str, command: Option<Commands>, output: &Output) -> Result<()> {
+    let Some(request) = command.and_then(remote_request) else {
+        anyhow::bail!(
+            "This command isn't available with --remote. Remote commands: link list \
+             [--tag], link search, link show, link create, link note create --body, tags"
+        );
+    };
+    let target = RemoteTarget::parse(remote)?;
+    let mut client = RemoteClient::connect(&target)?;
+
+    match request {
+        Request::ListLinks { .. } | Request::Search { .. } => {
+            let links: Vec<Link> = client.call(&request)?;
+            output.print_links(&links);
+        }
+        Request::GetLink { .. } => {
+            let link: Link = client.call(&request)?;
+            output.print_link(&link);
+        }
+        Request::AddLink { .. } => {
+            let link: Link = client.call(&request)?;
+            output.success(&format!("Created link: {}", link.id));
+            if !output.is_quiet() {
+                output.print_link(&link);
+            }
+        }
+        Request::AddNote { .. } => {
+            let added: AddedNote = client.call(&request)?;
+            output.success(&format!(
+                "Added note {} to link {}",
+                &added.note_id.to_string()[..8],
+                &added.link_id.to_string()[..8]
+            ));
+        }
+        Request::Tags => {
+            let tags: Vec<(String, i64)> = client.call(&request)?;
+            output.print_tags(&tags);
+        }
+        Request::Hello => {}
+    }
+    Ok(())
+}
+
+/// The request a command makes when run with --remote, if it can be
+fn remote_request(command: Commands) -> Option<Request> {
+    let request = match command {
+        Commands::Link { command } => match command {
+            LinkCommands::List {
+                tag,
+                favorites: false,
+                untagged: false,
+                unread: false,
+                author: None,
+                lang: None,
+                published_after: None,
+                published_before: None,
+                recent: false,
+                by_published: false,
+                expired: false,
+                stale: false,
+                archived: false,
+                watch: false,
+            } => Request::ListLinks { tag },
+            LinkCommands::Search {
+                query,
+                archived: false,
+            } => Request::Search { query },
+            LinkCommands::Show { id } => Request::GetLink { id },
+            LinkCommands::Create {
+                url,
+                tag,
+                via,
+                source,
+                expires: None,
+            } => Request::AddLink {
+                url,
+                tags: tag,
+                via,
+                source,
+            },
+            LinkCommands::Note {
+                command:
+                    NoteCommands::Create {
+                        link_id,
+                        title,
+                        body: Some(body),
+                        reply_to: None,
+                    },
+            } => Request::AddNote {
+                link_id,
+                title,
+                body,
+            },
+            _ => return None,
+        },
+        Commands::Tags { command: None } => Request::Tags,
+        _ => return None,
+    };
+    Some(request)
+}
+
+/// Run `rott <alias> <url> [key=value...]` as `rott capture <alias> ...`
+async fn handle_capture_alias(args: Vec<String>, store: &mut Store, output: &Output) -> Result<()> {
+    let mut args = args.into_iter();
+    let Some(name) = args.next() else {
+        anyhow::bail!("No command given");
+    };
+    let Some(url) = args.next() else {
+        anyhow::bail!("Usage: rott {} <url> [key=value...]", name);
+    };
+    commands::capture::capture(store, name, url, args.collect(), output).await
+}
+
+async fn handle_hypothesis_command(
+    command: HypothesisCommands,
+    store: &mut Store,
+    output: &Output,
+) -> Result<()> {
+    match command {
+        HypothesisCommands::Push { id } => commands::hypothesis::push(store, id, output).await,
+        HypothesisCommands::Pull { id } => commands::hypothesis::pull(store, id, output).await,
+    }
+}
+
 async fn handle_link_command(
     command: LinkCommands,
     store: &mut Store,
     output: &Output,
 ) -> Result<()> {
     match command {
-        LinkCommands::Create { url, tag } => commands::link::create(store, url, tag, output).await,
-        LinkCommands::List { tag } => commands::link::list(store, tag, output),
+        LinkCommands::Create {
+            url,
+            tag,
+            via,
+            source,
+            expires,
+        } => commands::link::create(store, url, tag, via, source, expires, output).await,
+        LinkCommands::List {
+            tag,
+            favorites,
+            untagged,
+            unread,
+            author,
+            lang,
+            published_after,
+            published_before,
+            recent,
+            by_published,
+            expired,
+            stale,
+            archived,
+            watch,
+        } => {
+            let filter = commands::link::ListFilter {
+                tag,
+                favorites,
+                untagged,
+                unread,
+                author,
+                lang,
+                published_after,
+                published_before,
+                recent,
+                by_published,
+                expired,
+                stale,
+                archived,
+            };
+            if watch {
+                watch::watch(store, output, |store| {
+                    commands::link::list(store, filter.clone(), output)
+                })
+                .await
+            } else {
+                commands::link::list(store, filter, output)
+            }
+        }
         LinkCommands::Show { id } => commands::link::show(store, id, output),
+        LinkCommands::Open { id } => commands::link::open(store, id, output),
+        LinkCommands::Edit {
+            bulk: true, tag, ..
+        } => commands::link::bulk_edit(store, tag, output),
         LinkCommands::Edit {
             id,
             add_tags,
             remove_tags,
-        } => commands::link::edit(store, id, add_tags, remove_tags, output),
+            expires,
+            no_expires,
+            ..
+        } => commands::link::edit(
+            store,
+            id.unwrap_or_default(),
+            add_tags,
+            remove_tags,
+            expires,
+            no_expires,
+            output,
+        ),
         LinkCommands::Delete { id } => commands::link::delete(store, id, output),
-        LinkCommands::Search { query } => commands::link::search(store, query, output),
+        LinkCommands::Search { query, archived } => {
+            commands::link::search(store, query, archived, output)
+        }
+        LinkCommands::Check {
+            content_changes,
+            accept,
+        } => commands::link::check(store, content_changes, accept, output).await,
+        LinkCommands::Refresh {
+            untitled,
+            failed_only,
+        } => commands::link::refresh(store, untitled, failed_only, output).await,
+        LinkCommands::Snapshot { id } => commands::link::snapshot(store, id, output).await,
+        LinkCommands::ArchiveContent { id } => {
+            commands::link::archive_content(store, id, output).await
+        }
+        LinkCommands::Merge { keep, duplicate } => {
+            commands::link::merge(store, keep, duplicate, output)
+        }
+        LinkCommands::Unmerge => commands::link::unmerge(store, output),
+        LinkCommands::Dedupe { yes, dry_run } => {
+            commands::link::dedupe(store, yes, dry_run, output)
+        }
         LinkCommands::Note { command } => handle_note_command(command, store, output),
     }
 }
@@ -320,11 +1563,247 @@ fn handle_note_command(command: NoteCommands, store: &mut Store, output: &Output
             link_id,
             title,
             body,
-        } => commands::note::create(store, link_id, title, body, output),
+            reply_to,
+        } => commands::note::create(store, link_id, title, body, reply_to, output),
         NoteCommands::List { link_id } => commands::note::list(store, link_id, output),
+        NoteCommands::Edit {
+            link_id,
+            note_id,
+            title,
+            body,
+        } => commands::note::edit(store, link_id, note_id, title, body, output),
         NoteCommands::Delete { link_id, note_id } => {
             commands::note::delete(store, link_id, note_id, output)
         }
+        NoteCommands::History {
+            link_id,
+            note_id,
+            full,
+        } => commands::note::history(store, link_id, note_id, full, output),
+        NoteCommands::Restore {
+            link_id,
+            note_id,
+            version,
+        } => commands::note::restore(store, link_id, note_id, version, output),
+        NoteCommands::Split { link_id, note_id } => {
+            commands::note::split(store, link_id, note_id, output)
+        }
+    }
+}
+
+fn handle_notes_command(command: NotesCommands, store: &mut Store, output: &Output) -> Result<()> {
+    match command {
+        NotesCommands::Replace {
+            find,
+            replace,
+            tag,
+            yes,
+            dry_run,
+        } => commands::note::replace(store, &find, &replace, tag, yes, dry_run, output),
+    }
+}
+
+fn handle_import_command(
+    command: ImportCommands,
+    store: &mut Store,
+    output: &Output,
+) -> Result<()> {
+    match command {
+        ImportCommands::History {
+            browser,
+            profile,
+            min_visits,
+            since,
+            tag,
+            yes,
+            dry_run,
+        } => commands::import::history(
+            store, browser, profile, min_visits, since, tag, yes, dry_run, output,
+        ),
+        ImportCommands::Bookmarks { file, tag, dry_run } => {
+            commands::import::bookmarks(store, file, tag, dry_run, output)
+        }
+        ImportCommands::Document {
+            file,
+            tag,
+            yes,
+            dry_run,
+        } => commands::import::document(store, file, tag, yes, dry_run, output),
+    }
+}
+
+fn handle_tags_command(
+    command: Option<TagsCommands>,
+    store: &mut Store,
+    output: &Output,
+) -> Result<()> {
+    match command {
+        None => commands::tag::list(store, output),
+        Some(TagsCommands::Tree) => commands::tag::tree(store, output),
+        Some(TagsCommands::Prune { dry_run }) => commands::tag::prune(store, dry_run, output),
+        Some(TagsCommands::Links {
+            all_of,
+            any_of,
+            none_of,
+            ids_only,
+        }) => {
+            let filter = TagFilter {
+                all_of,
+                any_of,
+                none_of,
+            };
+            commands::tag::links(store, &filter, ids_only, output)
+        }
+    }
+}
+
+fn handle_tag_command(command: TagCommands, store: &mut Store, output: &Output) -> Result<()> {
+    match command {
+        TagCommands::Describe { tag, text, clear } => {
+            commands::tag::describe(store, tag, text, clear, output)
+        }
+        TagCommands::Show { tag } => commands::tag::show(store, tag, output),
+        TagCommands::Visibility { tag, visibility } => {
+            commands::tag::visibility(store, tag, visibility, output)
+        }
+    }
+}
+
+fn handle_export_command(
+    command: ExportCommands,
+    at_heads: Option<String>,
+    heads_file: Option<PathBuf>,
+    store: &Store,
+    output: &Output,
+) -> Result<()> {
+    let pinned;
+    let store = match at_heads {
+        Some(heads) => {
+            pinned = store.at_heads(&commands::export::parse_heads(&heads))?;
+            &pinned
+        }
+        None => store,
+    };
+    let heads = store.heads();
+
+    match command {
+        ExportCommands::Text {
+            out,
+            include_private,
+        } => commands::export::text(store, out, include_private, output),
+        ExportCommands::Sqlite {
+            out,
+            include_private,
+        } => commands::export::sqlite(store, out, include_private, output),
+        ExportCommands::Json {
+            out,
+            tag,
+            include_private,
+        } => commands::export::json(store, out, tag, include_private, output),
+        ExportCommands::Markdown {
+            out,
+            tag,
+            include_private,
+            incremental,
+        } => commands::export::markdown(store, out, tag, include_private, incremental, output),
+        ExportCommands::Csv {
+            out,
+            tag,
+            include_private,
+        } => commands::export::csv(store, out, tag, include_private, output),
+    }?;
+
+    if let Some(path) = heads_file {
+        commands::export::write_heads(&path, &heads)?;
+    }
+    Ok(())
+}
+
+fn handle_backup_command(command: BackupCommands, store: &Store, output: &Output) -> Result<()> {
+    match command {
+        BackupCommands::Export { file } => commands::backup::export(store, file, output),
+        BackupCommands::Import { .. } => unreachable!(), // Handled above
+    }
+}
+
+fn handle_collection_command(
+    command: CollectionCommands,
+    config_path: Option<&PathBuf>,
+    output: &Output,
+) -> Result<()> {
+    match command {
+        CollectionCommands::List => commands::collection::list(config_path, output),
+        CollectionCommands::Create { name, join } => {
+            commands::collection::create(name, join, config_path, output)
+        }
+        CollectionCommands::Switch { name } => {
+            commands::collection::switch(name, config_path, output)
+        }
+    }
+}
+
+fn handle_archive_command(
+    command: ArchiveCommands,
+    store: &mut Store,
+    output: &Output,
+) -> Result<()> {
+    match command {
+        ArchiveCommands::Move {
+            older_than,
+            tag,
+            yes,
+            dry_run,
+        } => commands::archive::archive(store, older_than, tag, yes, dry_run, output),
+        ArchiveCommands::List => commands::archive::list(store, output),
+        ArchiveCommands::Restore { id } => commands::archive::restore(store, id, output),
+    }
+}
+
+async fn handle_maintenance_command(
+    command: MaintenanceCommands,
+    store: &mut Store,
+    output: &Output,
+) -> Result<()> {
+    match command {
+        MaintenanceCommands::DocStats { top } => {
+            commands::maintenance::doc_stats(store, top, output)
+        }
+        MaintenanceCommands::RunDue { dry_run } => {
+            commands::maintenance::run_due(store, dry_run, output).await
+        }
+        MaintenanceCommands::DedupeNotes { yes, dry_run } => {
+            commands::maintenance::dedupe_notes(store, yes, dry_run, output)
+        }
+        MaintenanceCommands::NormalizeTags => commands::maintenance::normalize_tags(store, output),
+        MaintenanceCommands::ClearCache { expired } => {
+            commands::maintenance::clear_cache(store, expired, output)
+        }
+        MaintenanceCommands::Gc {
+            grace_days,
+            dry_run,
+        } => commands::maintenance::gc(store, grace_days, dry_run, output),
+        MaintenanceCommands::Reindex { projection } => {
+            commands::maintenance::reindex(store, projection, output)
+        }
+        MaintenanceCommands::Events => commands::maintenance::events(store),
+        MaintenanceCommands::Report { since, out } => {
+            commands::maintenance::report(store, &since, out, output)
+        }
+        MaintenanceCommands::Anonymize { out } => {
+            commands::maintenance::anonymize(store, out, output)
+        }
+    }
+}
+
+fn handle_lock_command(
+    command: Option<LockCommands>,
+    config_path: Option<&PathBuf>,
+    output: &Output,
+) -> Result<()> {
+    match command {
+        None => commands::lock::lock(config_path, output),
+        Some(LockCommands::Enable) => commands::lock::enable(config_path, output),
+        Some(LockCommands::Disable) => commands::lock::disable(config_path, output),
     }
 }
 
@@ -338,12 +1817,21 @@ fn handle_config_command(
         Some(ConfigCommands::Set { key, value }) => {
             commands::config::set(key, value, config_path, output)
         }
+        Some(ConfigCommands::Export {
+            file,
+            include_secrets,
+        }) => commands::config::export(file, include_secrets, config_path, output),
+        Some(ConfigCommands::Import { file }) => {
+            commands::config::import(file, config_path, output)
+        }
     }
 }
 
 fn handle_init_command(
     new: bool,
     join: Option<String>,
+    demo: bool,
+    read_only: bool,
     config_path: Option<&PathBuf>,
     output: &Output,
 ) -> Result<()> {
@@ -366,18 +1854,27 @@ fn handle_init_command(
             .map_err(|e| anyhow::anyhow!("Invalid root document ID: {}", e))?;
 
         let result = identity.initialize_join(root_id)?;
+        if read_only {
+            identity.set_read_only(true)?;
+        }
 
         if output.is_json() {
             println!(
                 "{}",
                 serde_json::json!({
                     "root_id": result.root_id.to_bs58check(),
-                    "is_new": false
+                    "is_new": false,
+                    "read_only": read_only
                 })
             );
         } else if !output.is_quiet() {
             println!();
             println!("Identity configured.");
+            if read_only {
+                println!(
+                    "This device is read-only: it pulls the document but never changes or pushes it."
+                );
+            }
             println!();
             let config = Config::load_with_cli_override(config_path)?;
             if config.sync_url.is_none() {
@@ -385,16 +1882,18 @@ fn handle_init_command(
                 println!("  rott config set sync_url ws://your-server:3030");
             }
         }
-    } else if new {
+    } else if new || demo {
         // --new: Create new identity (no prompt)
         let result = identity.initialize_new()?;
+        let demo_links = if demo { seed_demo(config_path)? } else { 0 };
 
         if output.is_json() {
             println!(
                 "{}",
                 serde_json::json!({
                     "root_id": result.root_id.to_bs58check(),
-                    "is_new": true
+                    "is_new": true,
+                    "demo_links": demo_links
                 })
             );
         } else if !output.is_quiet() {
@@ -405,6 +1904,19 @@ fn handle_init_command(
             println!();
             println!("This ID is stored in: {}", identity.data_dir().display());
             println!("View it anytime with: rott device show");
+            if demo {
+                println!();
+                println!(
+                    "Added {} sample links tagged '{}'.",
+                    demo_links,
+                    rott_core::demo::DEMO_TAG
+                );
+                println!("Run `rott` to start the TUI; a short tour will show you around.");
+                println!(
+                    "List them (to delete later) with: rott link list --tag {}",
+                    rott_core::demo::DEMO_TAG
+                );
+            }
         } else {
             // Quiet mode - just print the ID
             println!("{}", result.root_id);
@@ -417,6 +1929,22 @@ fn handle_init_command(
     Ok(())
 }
 
+/// Add the demo links to a new store and queue the TUI tour
+///
+/// Returns the number of links added.
+fn seed_demo(config_path: Option<&PathBuf>) -> Result<usize> {
+    let config = Config::load_with_cli_override(config_path)?;
+    let marker = config.tour_marker_path();
+    let mut store = Store::open_with_config(config)?;
+
+    let links = rott_core::demo::demo_links();
+    for link in &links {
+        store.add_link(link)?;
+    }
+    std::fs::write(&marker, "")?;
+    Ok(links.len())
+}
+
 fn handle_device_command(command: Option<DeviceCommands>, output: &Output) -> Result<()> {
     let identity = Identity::new()?;
 
@@ -433,7 +1961,8 @@ fn handle_device_command(command: Option<DeviceCommands>, output: &Output) -> Re
                     "{}",
                     serde_json::json!({
                         "root_id": root_id.to_bs58check(),
-                        "root_url": root_id.to_url()
+                        "root_url": root_id.to_url(),
+                        "read_only": identity.is_read_only()
                     })
                 );
             } else if output.is_quiet() {
@@ -442,6 +1971,9 @@ fn handle_device_command(command: Option<DeviceCommands>, output: &Output) -> Re
                 println!();
                 println!("Root document ID: {}", root_id);
                 println!("Automerge URL:    {}", root_id.to_url());
+                if identity.is_read_only() {
+                    println!("Access:           read-only (pulls, never pushes)");
+                }
                 println!();
                 println!("Use this ID to set up ROTT on another device:");
                 println!("  rott init --join {}", root_id);
@@ -518,7 +2050,7 @@ fn run_first_time_setup(_output: &Output) -> Result<()> {
 }
 
 /// Auto-sync if sync is enabled, silently handles errors
-async fn auto_sync(store: &mut Store, config_path: Option<&PathBuf>, output: &Output) {
+async fn auto_sync(store: &mut Store, config_path: Option<&PathBuf>, trace: bool, output: &Output) {
     let config = match Config::load_with_cli_override(config_path) {
         Ok(c) => c,
         Err(_) => return,
@@ -529,7 +2061,7 @@ async fn auto_sync(store: &mut Store, config_path: Option<&PathBuf>, output: &Ou
     }
 
     // Sync silently (errors shown only in non-quiet mode)
-    if let Err(e) = commands::sync::sync_quiet(store, &config).await {
+    if let Err(e) = commands::sync::sync_quiet(store, &config, trace).await {
         if !output.is_quiet() {
             eprintln!("⚠ Auto-sync failed: {}", e);
         }
@@ -1,59 +1,684 @@
 //! URL metadata fetching
 //!
-//! Fetches title, description, and author from URLs when creating links.
+//! Fetches title, description, author, publication date, and canonical URL
+//! from URLs when creating links, along with a fingerprint of the page text
+//! for change detection, whether the page is behind a paywall or login, and
+//! the language the page declares.
+//!
+//! Fetched text is sanitized before it reaches the document: markup and
+//! entities are stripped, whitespace is collapsed, and overlong values are
+//! truncated with an ellipsis (see [`MetadataLimits`]).
+//!
+//! Each part is extracted on its own, so a page without (say) a description
+//! still gives its title; what couldn't be had is listed in
+//! [`UrlMetadata::failed`] and recorded with `rott_core::fetch_failures` for
+//! `rott link refresh --failed-only`. Connection errors, timeouts and
+//! overloaded servers are retried a few times, with backoff, first.
+//!
+//! Successful fetches are kept in the on-disk metadata cache (see
+//! `rott_core::metadata_cache`) by [`fetch_metadata_cached`], so saving the
+//! same URL again soon after doesn't download it again. It also fetches the
+//! site's favicon, once per domain (see `rott_core::favicons`).
+
+use std::collections::BTreeMap;
 
 use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use rott_core::domains::extract_domain;
+use rott_core::favicons::{Favicon, FaviconCache, MAX_FAVICON_BYTES};
+use rott_core::fetch_failures::FetchFailures;
+use rott_core::language::normalize_language;
+use rott_core::metadata_cache::MetadataCache;
+use rott_core::{Config, Fingerprint, Link};
 use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
+use tracing::{debug, warn};
 
 /// Metadata extracted from a URL
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct UrlMetadata {
     pub title: Option<String>,
     pub description: Option<String>,
     pub author: Vec<String>,
+    /// When the content was published
+    pub published_at: Option<DateTime<Utc>>,
+    /// The page's canonical URL (`<link rel="canonical">`, made absolute)
+    pub canonical_url: Option<String>,
+    /// Whether the page is behind a paywall or login
+    pub paywalled: bool,
+    /// Language code the page declares (see `rott_core::language`)
+    pub language: Option<String>,
+    /// Fingerprint of the page's visible text
+    pub content_hash: Option<Fingerprint>,
+    /// The site's icon (`<link rel="icon">` made absolute, or `/favicon.ico`)
+    #[serde(default)]
+    pub favicon_url: Option<String>,
+    /// Parts that couldn't be had ([`FAILED_PAGE`] when the page couldn't
+    /// be fetched at all), each with why
+    #[serde(default)]
+    pub failed: BTreeMap<String, String>,
+}
+
+impl UrlMetadata {
+    /// Metadata for a page that couldn't be fetched
+    fn fetch_failed(error: &anyhow::Error) -> Self {
+        Self {
+            failed: BTreeMap::from([(FAILED_PAGE.to_string(), format!("{:#}", error))]),
+            ..Self::default()
+        }
+    }
+}
+
+/// Key in [`UrlMetadata::failed`] for a page that couldn't be fetched
+pub const FAILED_PAGE: &str = "page";
+/// Key in [`UrlMetadata::failed`] for a page without a title
+pub const FAILED_TITLE: &str = "title";
+/// Key in [`UrlMetadata::failed`] for a page without a description
+pub const FAILED_DESCRIPTION: &str = "description";
+
+/// Maximum lengths for fetched text fields, in characters
+#[derive(Debug, Clone, Copy)]
+pub struct MetadataLimits {
+    /// Limit for the title and each author
+    pub max_title_length: usize,
+    /// Limit for the description
+    pub max_description_length: usize,
+}
+
+impl MetadataLimits {
+    /// Get the limits from the configuration
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_title_length: config.max_title_length,
+            max_description_length: config.max_description_length,
+        }
+    }
+}
+
+impl Default for MetadataLimits {
+    fn default() -> Self {
+        Self::from_config(&Config::default())
+    }
 }
 
 /// Fetch timeout in seconds
 const FETCH_TIMEOUT: u64 = 10;
 
+/// Appended to text that was cut short
+const TRUNCATION_MARKER: char = '…';
+
+/// Attempts at a request that fails transiently
+const FETCH_ATTEMPTS: u32 = 3;
+
+/// Wait before the first retry, in milliseconds; doubled for each retry
+const RETRY_BACKOFF_MS: u64 = 500;
+
 /// Fetch metadata from a URL (async)
 ///
-/// Returns empty metadata on failure (graceful degradation).
-pub async fn fetch_metadata(url: &str) -> UrlMetadata {
-    fetch_metadata_inner(url).await.unwrap_or_default()
+/// Returns empty metadata, with the error in [`UrlMetadata::failed`], on
+/// failure (graceful degradation).
+pub async fn fetch_metadata(url: &str, limits: &MetadataLimits) -> UrlMetadata {
+    fetch_metadata_inner(url, limits)
+        .await
+        .unwrap_or_else(|e| UrlMetadata::fetch_failed(&e))
+}
+
+/// Fetch metadata from a URL, reusing a recent fetch from the cache
+///
+/// Only successful fetches are cached, so a page that failed to load is
+/// tried again next time. Returns empty metadata on failure.
+pub async fn fetch_metadata_cached(url: &str, config: &Config) -> UrlMetadata {
+    let cache = MetadataCache::from_config(config);
+    if let Some(metadata) = cache.as_ref().and_then(|c| c.get(url, Utc::now())) {
+        debug!("Using cached metadata for {}", url);
+        return metadata;
+    }
+
+    match fetch_metadata_inner(url, &MetadataLimits::from_config(config)).await {
+        Ok(metadata) => {
+            if let Some(cache) = cache {
+                if let Err(e) = cache.put(url, &metadata, Utc::now()) {
+                    warn!("Failed to cache metadata for {}: {:#}", url, e);
+                }
+            }
+            if let Some(favicon_url) = metadata.favicon_url.as_deref() {
+                cache_favicon(url, favicon_url, config).await;
+            }
+            metadata
+        }
+        Err(e) => {
+            debug!("Failed to fetch metadata for {}: {:#}", url, e);
+            UrlMetadata::fetch_failed(&e)
+        }
+    }
+}
+
+/// Record what failed when fetching a link's metadata, for
+/// `rott link refresh --failed-only`
+///
+/// A complete fetch clears what was recorded before. Failing to record is
+/// only logged.
+pub fn record_failures(config: &Config, link: &Link, metadata: &UrlMetadata) {
+    let result = FetchFailures::load(config).and_then(|mut failures| {
+        if failures.get(link.id).is_none() && metadata.failed.is_empty() {
+            return Ok(());
+        }
+        failures.record(link.id, &link.url, metadata.failed.clone(), Utc::now());
+        failures.save()
+    });
+    if let Err(e) = result {
+        warn!(
+            "Failed to record metadata failures for {}: {:#}",
+            link.url, e
+        );
+    }
+}
+
+/// Send a GET request, retrying transient failures with backoff
+///
+/// Connection errors, timeouts, 429 Too Many Requests and 5xx responses
+/// are retried; anything else is returned as is.
+async fn get_with_retry(client: &reqwest::Client, url: &str) -> Result<reqwest::Response> {
+    let mut attempt = 1;
+    loop {
+        let result = client.get(url).send().await;
+        let transient = match &result {
+            Ok(response) => is_transient_status(response.status().as_u16()),
+            Err(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+        };
+        if !transient || attempt >= FETCH_ATTEMPTS {
+            return Ok(result?);
+        }
+        let delay = RETRY_BACKOFF_MS << (attempt - 1);
+        debug!(
+            "Fetching {} failed (attempt {}), retrying in {}ms",
+            url, attempt, delay
+        );
+        tokio::time::sleep(Duration::from_millis(delay)).await;
+        attempt += 1;
+    }
+}
+
+/// Whether an HTTP status is worth retrying
+fn is_transient_status(status: u16) -> bool {
+    status == 429 || (500..600).contains(&status)
 }
 
 /// Inner fetch function that can fail
-async fn fetch_metadata_inner(url: &str) -> Result<UrlMetadata> {
+async fn fetch_metadata_inner(url: &str, limits: &MetadataLimits) -> Result<UrlMetadata> {
     let client = reqwest::Client::builder()
         .timeout(Duration::from_secs(FETCH_TIMEOUT))
         .user_agent("Mozilla/5.0 (compatible; ROTT/1.0)")
         .build()?;
 
-    let response = client.get(url).send().await?;
+    let response = get_with_retry(&client, url).await?;
 
+    // 401 Unauthorized and 402 Payment Required, or a redirect to a login page
+    let status = response.status().as_u16();
+    let final_url = response.url().to_string();
+    if status == 401 || status == 402 || (is_login_url(&final_url) && !is_login_url(url)) {
+        return Ok(UrlMetadata {
+            paywalled: true,
+            ..UrlMetadata::default()
+        });
+    }
     if !response.status().is_success() {
-        return Ok(UrlMetadata::default());
+        anyhow::bail!("HTTP {}", status);
     }
 
+    // The Content-Language header is used if the page doesn't declare one
+    let header_language = response
+        .headers()
+        .get(reqwest::header::CONTENT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next())
+        .and_then(normalize_language);
+
+    // Relative canonical URLs are resolved against where we ended up
     let html = response.text().await?;
-    Ok(parse_metadata(&html))
+    let mut metadata = parse_metadata(&html, limits);
+    metadata.language = metadata.language.or(header_language);
+    metadata.canonical_url = metadata
+        .canonical_url
+        .and_then(|href| resolve_url(&final_url, &href));
+    metadata.favicon_url = metadata
+        .favicon_url
+        .and_then(|href| resolve_url(&final_url, &href))
+        .or_else(|| resolve_url(&final_url, "/favicon.ico"));
+    Ok(metadata)
+}
+
+/// Fetch a page and extract its readable text (see [`readable_text`])
+pub async fn fetch_readable_text(url: &str) -> Result<String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(FETCH_TIMEOUT))
+        .user_agent("Mozilla/5.0 (compatible; ROTT/1.0)")
+        .build()?;
+    let response = get_with_retry(&client, url).await?;
+    if !response.status().is_success() {
+        anyhow::bail!("HTTP {}", response.status().as_u16());
+    }
+    let html = response.text().await?;
+    readable_text(&html).ok_or_else(|| anyhow::anyhow!("No readable text on the page"))
+}
+
+/// Fetch and cache the favicon of the site `page_url` is on, unless it's
+/// cached already
+///
+/// Failures are only logged: without a favicon, the site's initials are
+/// shown instead.
+pub async fn cache_favicon(page_url: &str, favicon_url: &str, config: &Config) {
+    let Some(domain) = extract_domain(page_url) else {
+        return;
+    };
+    let cache = FaviconCache::from_config(config);
+    let now = Utc::now();
+    if cache.get(&domain, now).is_some() {
+        return;
+    }
+    match fetch_favicon(favicon_url).await {
+        Ok(favicon) => {
+            if let Err(e) = cache.put(&domain, &favicon, now) {
+                warn!("Failed to cache favicon for {}: {:#}", domain, e);
+            }
+        }
+        Err(e) => debug!("No favicon for {} from {}: {:#}", domain, favicon_url, e),
+    }
+}
+
+async fn fetch_favicon(url: &str) -> Result<Favicon> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(FETCH_TIMEOUT))
+        .user_agent("Mozilla/5.0 (compatible; ROTT/1.0)")
+        .build()?;
+    let response = client.get(url).send().await?;
+    if !response.status().is_success() {
+        anyhow::bail!("HTTP {}", response.status().as_u16());
+    }
+    // Servers often send .ico files without a type
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(|value| value.trim().to_ascii_lowercase())
+        .unwrap_or_else(|| "image/x-icon".to_string());
+    if !content_type.starts_with("image/") {
+        anyhow::bail!("not an image ({})", content_type);
+    }
+    let image = response.bytes().await?;
+    if image.is_empty() || image.len() > MAX_FAVICON_BYTES {
+        anyhow::bail!("{} bytes", image.len());
+    }
+    Ok(Favicon::new(content_type, &image))
 }
 
 /// Parse metadata from HTML content
-fn parse_metadata(html: &str) -> UrlMetadata {
+fn parse_metadata(html: &str, limits: &MetadataLimits) -> UrlMetadata {
     let document = Html::parse_document(html);
 
-    let title = extract_title(&document);
-    let description = extract_description(&document);
-    let author = extract_author(&document);
+    let title = extract_title(&document).and_then(|t| sanitize(&t, limits.max_title_length));
+    let description =
+        extract_description(&document).and_then(|d| sanitize(&d, limits.max_description_length));
+    let mut author: Vec<String> = Vec::new();
+    for name in extract_author(&document) {
+        if let Some(name) = sanitize(&name, limits.max_title_length) {
+            if !author.contains(&name) {
+                author.push(name);
+            }
+        }
+    }
+    let published_at = extract_published(&document);
+    let canonical_url = extract_canonical(&document);
+    let favicon_url = extract_favicon(&document);
+    let paywalled = detect_paywall(&document);
+    let language = extract_language(&document);
+    let content_hash = Fingerprint::of_text(&extract_text(&document));
+
+    let mut failed = BTreeMap::new();
+    if title.is_none() {
+        failed.insert(FAILED_TITLE.to_string(), "no title on the page".to_string());
+    }
+    if description.is_none() {
+        failed.insert(
+            FAILED_DESCRIPTION.to_string(),
+            "no description on the page".to_string(),
+        );
+    }
 
     UrlMetadata {
         title,
         description,
         author,
+        published_at,
+        canonical_url,
+        paywalled,
+        language,
+        content_hash,
+        favicon_url,
+        failed,
+    }
+}
+
+/// Clean up a fetched text value for storage and display
+///
+/// Strips any markup (dropping script and style contents), decodes
+/// entities, collapses whitespace and control characters, and truncates to
+/// `max_len` characters with a trailing ellipsis. Returns `None` if nothing
+/// is left.
+fn sanitize(text: &str, max_len: usize) -> Option<String> {
+    let plain = if text.contains('<') || text.contains('&') {
+        let fragment = Html::parse_fragment(text);
+        fragment
+            .root_element()
+            .descendants()
+            .filter_map(|node| {
+                let text = node.value().as_text()?;
+                let parent = node.parent()?;
+                match parent.value().as_element().map(|el| el.name()) {
+                    Some("script" | "style" | "noscript" | "template") => None,
+                    _ => Some(&**text),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    } else {
+        text.to_string()
+    };
+
+    let collapsed = plain
+        .split(|c: char| c.is_whitespace() || c.is_control())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if collapsed.is_empty() {
+        return None;
+    }
+
+    if collapsed.chars().count() <= max_len {
+        return Some(collapsed);
+    }
+    let mut truncated: String = collapsed.chars().take(max_len.saturating_sub(1)).collect();
+    truncated.truncate(truncated.trim_end().len());
+    truncated.push(TRUNCATION_MARKER);
+    Some(truncated)
+}
+
+/// Extract the visible text of the page body
+fn extract_text(document: &Html) -> String {
+    let Ok(selector) = Selector::parse("body") else {
+        return String::new();
+    };
+    let Some(body) = document.select(&selector).next() else {
+        return String::new();
+    };
+
+    body.descendants()
+        .filter_map(|node| {
+            let text = node.value().as_text()?;
+            let parent = node.parent()?;
+            match parent.value().as_element().map(|el| el.name()) {
+                Some("script" | "style" | "noscript" | "template") => None,
+                _ => Some(&**text),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Elements whose text makes up the readable text of a page
+const READABLE_BLOCKS: [&str; 10] = [
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "p",
+    "li",
+    "blockquote",
+    "pre",
+];
+
+/// Extract a page's readable text, for keeping an offline copy
+///
+/// The text of headings, paragraphs, list items, quotes and code blocks in
+/// the page's `<article>` (or `<main>`, or body), one block per paragraph;
+/// headings are marked with `#` and list items with `-`. Pages without
+/// such blocks give all their visible text. `None` if there's no text.
+pub fn readable_text(html: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+    let root = ["article", "main", "body"].iter().find_map(|name| {
+        let selector = Selector::parse(name).ok()?;
+        document.select(&selector).next()
+    })?;
+    let blocks = Selector::parse(&READABLE_BLOCKS.join(", ")).ok()?;
+
+    let mut paragraphs = Vec::new();
+    for element in root.select(&blocks) {
+        // Blocks inside another block (a paragraph in a list item) are part
+        // of its text
+        let nested = element.ancestors().any(|node| {
+            node.value()
+                .as_element()
+                .is_some_and(|el| READABLE_BLOCKS.contains(&el.name()))
+        });
+        if nested {
+            continue;
+        }
+        let name = element.value().name();
+        let text: String = element
+            .descendants()
+            .filter_map(|node| {
+                let text = node.value().as_text()?;
+                let parent = node.parent()?;
+                match parent.value().as_element().map(|el| el.name()) {
+                    Some("script" | "style" | "noscript" | "template") => None,
+                    _ => Some(&**text),
+                }
+            })
+            .collect();
+        let text = if name == "pre" {
+            text.trim_matches('\n').to_string()
+        } else {
+            text.split_whitespace().collect::<Vec<_>>().join(" ")
+        };
+        if text.trim().is_empty() {
+            continue;
+        }
+        paragraphs.push(match name {
+            "li" => format!("- {}", text),
+            heading if heading.starts_with('h') => {
+                let level = heading[1..].parse().unwrap_or(1);
+                format!("{} {}", "#".repeat(level), text)
+            }
+            _ => text,
+        });
+    }
+
+    if paragraphs.is_empty() {
+        let text = extract_text(&document);
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        return (!text.is_empty()).then_some(text);
+    }
+    Some(paragraphs.join("\n\n"))
+}
+
+/// Extract the canonical URL from `<link rel="canonical">` (as written)
+fn extract_canonical(document: &Html) -> Option<String> {
+    let selector = Selector::parse("link[rel]").ok()?;
+    document
+        .select(&selector)
+        .find(|el| {
+            el.value().attr("rel").is_some_and(|rel| {
+                rel.split_whitespace()
+                    .any(|r| r.eq_ignore_ascii_case("canonical"))
+            })
+        })
+        .and_then(|el| el.value().attr("href"))
+        .map(str::trim)
+        .filter(|href| !href.is_empty())
+        .map(String::from)
+}
+
+/// Extract the site icon from `<link rel="icon">` (as written), or else
+/// `<link rel="apple-touch-icon">`
+fn extract_favicon(document: &Html) -> Option<String> {
+    let selector = Selector::parse("link[rel][href]").ok()?;
+    let with_rel = |name: &str| {
+        document
+            .select(&selector)
+            .find(|el| {
+                el.value()
+                    .attr("rel")
+                    .is_some_and(|rel| rel.split_whitespace().any(|r| r.eq_ignore_ascii_case(name)))
+            })
+            .and_then(|el| el.value().attr("href"))
+            .map(str::trim)
+            .filter(|href| !href.is_empty())
+            .map(String::from)
+    };
+    with_rel("icon").or_else(|| with_rel("apple-touch-icon"))
+}
+
+/// Extract the page's declared language
+///
+/// From `<html lang>`, then `<meta http-equiv="content-language">`, then
+/// `og:locale`.
+fn extract_language(document: &Html) -> Option<String> {
+    let html_lang = Selector::parse("html[lang]").ok().and_then(|selector| {
+        document
+            .select(&selector)
+            .next()
+            .and_then(|el| el.value().attr("lang"))
+            .map(String::from)
+    });
+    let http_equiv = Selector::parse("meta[http-equiv]")
+        .ok()
+        .and_then(|selector| {
+            document
+                .select(&selector)
+                .find(|el| {
+                    el.value()
+                        .attr("http-equiv")
+                        .is_some_and(|v| v.eq_ignore_ascii_case("content-language"))
+                })
+                .and_then(|el| el.value().attr("content"))
+                .map(String::from)
+        });
+
+    [
+        html_lang,
+        http_equiv,
+        extract_meta_content(document, "og:locale"),
+    ]
+    .into_iter()
+    .flatten()
+    .find_map(|value| normalize_language(value.split(',').next().unwrap_or_default()))
+}
+
+/// Resolve an href against the page URL
+///
+/// Handles absolute, scheme-relative (`//host/path`), and root-relative
+/// (`/path`) hrefs; anything else (including non-web schemes) gives None.
+fn resolve_url(base: &str, href: &str) -> Option<String> {
+    let lower = href.to_ascii_lowercase();
+    if lower.starts_with("http://") || lower.starts_with("https://") {
+        return Some(href.to_string());
+    }
+    let (scheme, rest) = base.split_once("://")?;
+    if let Some(host_and_path) = href.strip_prefix("//") {
+        return Some(format!("{}://{}", scheme, host_and_path));
+    }
+    if href.starts_with('/') {
+        let host = rest.split(['/', '?', '#']).next()?;
+        return Some(format!("{}://{}{}", scheme, host, href));
+    }
+    None
+}
+
+/// `article:content_tier` values that mean the content isn't free
+const PAYWALLED_TIERS: [&str; 2] = ["locked", "metered"];
+
+/// Class or id fragments used by common paywall and registration-wall markup
+const PAYWALL_MARKERS: [&str; 5] = [
+    "paywall",
+    "regwall",
+    "registration-wall",
+    "login-wall",
+    "subscriber-only",
+];
+
+/// Whether the page marks itself as paywalled
+///
+/// Looks for schema.org `isAccessibleForFree: false` in JSON-LD (what
+/// publishers declare for search engines), a locked or metered
+/// `article:content_tier`, and elements whose class or id names a paywall.
+fn detect_paywall(document: &Html) -> bool {
+    if let Ok(selector) = Selector::parse(r#"script[type="application/ld+json"]"#) {
+        for el in document.select(&selector) {
+            let json = el.text().collect::<String>();
+            if let Ok(value) = serde_json::from_str::<serde_json::Value>(&json) {
+                if declares_not_free(&value) {
+                    return true;
+                }
+            }
+        }
+    }
+
+    if extract_meta_content(document, "article:content_tier")
+        .is_some_and(|tier| PAYWALLED_TIERS.contains(&tier.to_ascii_lowercase().as_str()))
+    {
+        return true;
     }
+
+    let Ok(selector) = Selector::parse("[class], [id]") else {
+        return false;
+    };
+    document.select(&selector).any(|el| {
+        let names = [el.value().attr("class"), el.value().attr("id")];
+        names.into_iter().flatten().any(|name| {
+            let name = name.to_ascii_lowercase();
+            PAYWALL_MARKERS.iter().any(|marker| name.contains(marker))
+        })
+    })
+}
+
+/// Whether a JSON-LD value has `isAccessibleForFree: false` (including `@graph`)
+fn declares_not_free(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Object(map) => {
+            let not_free = match map.get("isAccessibleForFree") {
+                Some(serde_json::Value::Bool(free)) => !free,
+                Some(serde_json::Value::String(free)) => free.eq_ignore_ascii_case("false"),
+                _ => false,
+            };
+            not_free || map.values().any(declares_not_free)
+        }
+        serde_json::Value::Array(items) => items.iter().any(declares_not_free),
+        _ => false,
+    }
+}
+
+/// Whether a URL looks like a login page
+fn is_login_url(url: &str) -> bool {
+    let path = url
+        .split_once("://")
+        .map_or(url, |(_, rest)| rest.find('/').map_or("", |i| &rest[i..]))
+        .to_ascii_lowercase();
+    [
+        "/login",
+        "/signin",
+        "/sign-in",
+        "/sign_in",
+        "/account/login",
+    ]
+    .iter()
+    .any(|prefix| {
+        path.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(['/', '?', '#']))
+    })
 }
 
 /// Extract title from HTML
@@ -135,6 +760,63 @@ fn extract_author(document: &Html) -> Vec<String> {
     authors
 }
 
+/// Meta tags giving the publication date, in order of preference
+const PUBLISHED_META: [&str; 5] = [
+    "article:published_time",
+    "og:published_time",
+    "citation_publication_date",
+    "dc.date",
+    "date",
+];
+
+/// Extract the publication date from JSON-LD or meta tags
+fn extract_published(document: &Html) -> Option<DateTime<Utc>> {
+    // JSON-LD (schema.org Article and friends)
+    if let Ok(selector) = Selector::parse(r#"script[type="application/ld+json"]"#) {
+        for el in document.select(&selector) {
+            let json = el.text().collect::<String>();
+            let Ok(value) = serde_json::from_str::<serde_json::Value>(&json) else {
+                continue;
+            };
+            if let Some(date) = find_date_published(&value).and_then(parse_date) {
+                return Some(date);
+            }
+        }
+    }
+
+    PUBLISHED_META
+        .iter()
+        .filter_map(|property| extract_meta_content(document, property))
+        .find_map(|content| parse_date(&content))
+}
+
+/// Find the first `datePublished` in a JSON-LD value (including `@graph`)
+fn find_date_published(value: &serde_json::Value) -> Option<&str> {
+    match value {
+        serde_json::Value::Object(map) => map
+            .get("datePublished")
+            .and_then(|v| v.as_str())
+            .or_else(|| map.values().find_map(find_date_published)),
+        serde_json::Value::Array(items) => items.iter().find_map(find_date_published),
+        _ => None,
+    }
+}
+
+/// Parse a published date (RFC 3339, or a plain date like 2024-03-05 or
+/// 2024/03/05)
+fn parse_date(text: &str) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+        return Some(date.with_timezone(&Utc));
+    }
+    let day = text.get(..10).unwrap_or(text);
+    ["%Y-%m-%d", "%Y/%m/%d"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(day, format).ok())
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|date| date.and_utc())
+}
+
 /// Extract content from a meta tag by property or name
 fn extract_meta_content(document: &Html, property: &str) -> Option<String> {
     // Try property attribute (for Open Graph)
@@ -184,7 +866,7 @@ mod tests {
             </html>
         "#;
 
-        let metadata = parse_metadata(html);
+        let metadata = parse_metadata(html, &MetadataLimits::default());
         assert_eq!(metadata.title, Some("Test Page".to_string()));
         assert_eq!(metadata.description, Some("A test description".to_string()));
         assert_eq!(metadata.author, vec!["Test Author".to_string()]);
@@ -204,19 +886,225 @@ mod tests {
             </html>
         "#;
 
-        let metadata = parse_metadata(html);
+        let metadata = parse_metadata(html, &MetadataLimits::default());
         // OG takes precedence
         assert_eq!(metadata.title, Some("OG Title".to_string()));
         assert_eq!(metadata.description, Some("OG Description".to_string()));
     }
 
+    #[test]
+    fn test_parse_published_date() {
+        let html = r#"
+            <html><head>
+                <meta property="article:published_time" content="2021-06-01T08:00:00+02:00">
+                <script type="application/ld+json">
+                    {"@context": "https://schema.org", "@graph": [
+                        {"@type": "WebSite"},
+                        {"@type": "Article", "datePublished": "2020-01-15"}
+                    ]}
+                </script>
+            </head><body></body></html>
+        "#;
+        let metadata = parse_metadata(html, &MetadataLimits::default());
+        // JSON-LD takes precedence
+        assert_eq!(
+            metadata.published_at.map(|d| d.to_rfc3339()),
+            Some("2020-01-15T00:00:00+00:00".to_string())
+        );
+
+        let html = r#"<html><head>
+            <meta property="article:published_time" content="2021-06-01T08:00:00+02:00">
+        </head></html>"#;
+        let metadata = parse_metadata(html, &MetadataLimits::default());
+        assert_eq!(
+            metadata.published_at.map(|d| d.to_rfc3339()),
+            Some("2021-06-01T06:00:00+00:00".to_string())
+        );
+
+        assert_eq!(
+            parse_date("2019/12/31").map(|d| d.to_rfc3339()),
+            Some("2019-12-31T00:00:00+00:00".to_string())
+        );
+        assert!(parse_date("last Tuesday").is_none());
+    }
+
+    #[test]
+    fn test_parse_canonical_url() {
+        let html = r#"<html><head>
+            <link rel="stylesheet" href="/style.css">
+            <link rel="Canonical" href=" /2024/05/story ">
+        </head></html>"#;
+        let metadata = parse_metadata(html, &MetadataLimits::default());
+        assert_eq!(metadata.canonical_url.as_deref(), Some("/2024/05/story"));
+
+        let base = "https://amp.example.com/amp/story?utm_source=feed";
+        assert_eq!(
+            resolve_url(base, "/2024/05/story").as_deref(),
+            Some("https://amp.example.com/2024/05/story")
+        );
+        assert_eq!(
+            resolve_url(base, "//example.com/story").as_deref(),
+            Some("https://example.com/story")
+        );
+        assert_eq!(
+            resolve_url(base, "https://example.com/story").as_deref(),
+            Some("https://example.com/story")
+        );
+        assert!(resolve_url(base, "story.html").is_none());
+        assert!(resolve_url(base, "javascript:void(0)").is_none());
+    }
+
+    #[test]
+    fn test_extract_favicon() {
+        let html = r#"<html><head>
+            <link rel="apple-touch-icon" href="/touch.png">
+            <link rel="shortcut icon" href=" /static/favicon.png ">
+        </head></html>"#;
+        let metadata = parse_metadata(html, &MetadataLimits::default());
+        assert_eq!(metadata.favicon_url.as_deref(), Some("/static/favicon.png"));
+
+        let html = r#"<link rel="apple-touch-icon" href="/touch.png">"#;
+        let metadata = parse_metadata(html, &MetadataLimits::default());
+        assert_eq!(metadata.favicon_url.as_deref(), Some("/touch.png"));
+
+        let metadata = parse_metadata("<title>T</title>", &MetadataLimits::default());
+        assert!(metadata.favicon_url.is_none());
+    }
+
+    #[test]
+    fn test_extract_language() {
+        let limits = MetadataLimits::default();
+        let html = r#"<html lang="en-GB"><head><title>T</title></head></html>"#;
+        assert_eq!(
+            parse_metadata(html, &limits).language.as_deref(),
+            Some("en")
+        );
+
+        let meta = r#"<html><head>
+            <meta http-equiv="Content-Language" content="de, en">
+        </head></html>"#;
+        assert_eq!(
+            parse_metadata(meta, &limits).language.as_deref(),
+            Some("de")
+        );
+
+        let og = r#"<html lang=""><head><meta property="og:locale" content="pt_BR"></head></html>"#;
+        assert_eq!(parse_metadata(og, &limits).language.as_deref(), Some("pt"));
+
+        let none = r#"<html><head><title>T</title></head></html>"#;
+        assert_eq!(parse_metadata(none, &limits).language, None);
+    }
+
+    #[test]
+    fn test_detect_paywall() {
+        let limits = MetadataLimits::default();
+        let free = r#"<html><head><title>Open</title></head>
+            <body><article class="story-body">Text</article></body></html>"#;
+        assert!(!parse_metadata(free, &limits).paywalled);
+
+        let json_ld = r#"<html><head><script type="application/ld+json">
+            {"@type": "NewsArticle", "isAccessibleForFree": "False",
+             "hasPart": {"@type": "WebPageElement", "cssSelector": ".locked"}}
+        </script></head></html>"#;
+        assert!(parse_metadata(json_ld, &limits).paywalled);
+
+        let tier =
+            r#"<html><head><meta property="article:content_tier" content="locked"></head></html>"#;
+        assert!(parse_metadata(tier, &limits).paywalled);
+
+        let markup = r#"<html><body><div id="Paywall-Overlay">Subscribe</div></body></html>"#;
+        assert!(parse_metadata(markup, &limits).paywalled);
+
+        assert!(is_login_url("https://news.example.com/login?next=/story"));
+        assert!(is_login_url("https://example.com/account/login"));
+        assert!(!is_login_url("https://example.com/blog/login"));
+        assert!(!is_login_url("https://example.com/loginless-auth"));
+    }
+
     #[test]
     fn test_parse_metadata_empty() {
         let html = "<html><head></head><body></body></html>";
-        let metadata = parse_metadata(html);
+        let metadata = parse_metadata(html, &MetadataLimits::default());
         assert!(metadata.title.is_none());
         assert!(metadata.description.is_none());
+        assert!(metadata.published_at.is_none());
+        assert!(metadata.canonical_url.is_none());
+        assert!(!metadata.paywalled);
         assert!(metadata.author.is_empty());
+        assert!(metadata.content_hash.is_none());
+        assert_eq!(
+            metadata.failed.keys().collect::<Vec<_>>(),
+            [FAILED_DESCRIPTION, FAILED_TITLE]
+        );
+    }
+
+    #[test]
+    fn test_partial_metadata() {
+        let html = "<html><head><title>Only a title</title></head><body></body></html>";
+        let metadata = parse_metadata(html, &MetadataLimits::default());
+        assert_eq!(metadata.title.as_deref(), Some("Only a title"));
+        assert!(!metadata.failed.contains_key(FAILED_TITLE));
+        assert!(metadata.failed.contains_key(FAILED_DESCRIPTION));
+    }
+
+    #[test]
+    fn test_readable_text() {
+        let html = r#"<html><body>
+            <nav><p>Menu</p></nav>
+            <article>
+              <h2>Getting   started</h2>
+              <p>First <em>paragraph</em>.<script>track()</script></p>
+              <ul><li><p>One</p></li><li>Two</li></ul>
+              <pre>let x = 1;
+let y = 2;</pre>
+            </article>
+        </body></html>"#;
+        assert_eq!(
+            readable_text(html).unwrap(),
+            "## Getting started\n\nFirst paragraph.\n\n- One\n\n- Two\n\nlet x = 1;\nlet y = 2;"
+        );
+
+        // Pages without paragraphs give their visible text
+        let html = "<html><body><div>Just  text</div></body></html>";
+        assert_eq!(readable_text(html).unwrap(), "Just text");
+        assert!(readable_text("<html><body></body></html>").is_none());
+    }
+
+    #[test]
+    fn test_transient_status() {
+        assert!(is_transient_status(429));
+        assert!(is_transient_status(503));
+        assert!(!is_transient_status(404));
+        assert!(!is_transient_status(200));
+    }
+
+    #[test]
+    fn test_content_hash_ignores_scripts_and_markup() {
+        let limits = MetadataLimits::default();
+        let plain = parse_metadata(
+            "<html><body><p>Starter plan: $10/month</p></body></html>",
+            &limits,
+        );
+        let styled = parse_metadata(
+            r#"
+            <html>
+            <head><title>Pricing</title></head>
+            <body>
+                <script>trackVisit("abc123");</script>
+                <div class="price"><b>Starter plan</b>: $10/month</div>
+            </body>
+            </html>
+        "#,
+            &limits,
+        );
+        assert!(plain.content_hash.is_some());
+        assert_eq!(plain.content_hash, styled.content_hash);
+
+        let changed = parse_metadata(
+            "<html><body><p>Starter plan: $15/month</p></body></html>",
+            &limits,
+        );
+        assert_ne!(plain.content_hash, changed.content_hash);
     }
 
     #[test]
@@ -232,9 +1120,38 @@ mod tests {
             </html>
         "#;
 
-        let metadata = parse_metadata(html);
+        let metadata = parse_metadata(html, &MetadataLimits::default());
         assert_eq!(metadata.author.len(), 2);
         assert!(metadata.author.contains(&"Author One".to_string()));
         assert!(metadata.author.contains(&"Author Two".to_string()));
     }
+
+    #[test]
+    fn test_sanitize_strips_markup_and_entities() {
+        let html = r#"
+            <html>
+            <head>
+                <title>Rust &amp;amp; Friends</title>
+                <meta name="description" content="&lt;p&gt;Hello&lt;script&gt;alert(1)&lt;/script&gt; &lt;b&gt;world&lt;/b&gt;&lt;/p&gt;">
+            </head>
+            <body></body>
+            </html>
+        "#;
+
+        let metadata = parse_metadata(html, &MetadataLimits::default());
+        assert_eq!(metadata.title.as_deref(), Some("Rust & Friends"));
+        assert_eq!(metadata.description.as_deref(), Some("Hello world"));
+    }
+
+    #[test]
+    fn test_sanitize_truncates() {
+        assert_eq!(sanitize("short", 10).as_deref(), Some("short"));
+        assert_eq!(sanitize("a  b\n\tc", 10).as_deref(), Some("a b c"));
+        assert_eq!(
+            sanitize("the quick brown fox", 10).as_deref(),
+            Some("the quick…")
+        );
+        assert_eq!(sanitize("ééééé", 3).as_deref(), Some("éé…"));
+        assert_eq!(sanitize(" <br> ", 10), None);
+    }
 }
@@ -4,8 +4,29 @@
 //! - Human-readable default output
 //! - JSON output (--json flag)
 //! - Quiet mode for scripting (--quiet flag)
+//!
+//! Human output fits the terminal: lists are aligned tables whose titles and
+//! URLs are truncated to fit, and link details wrap long values under their
+//! label (see [`crate::table`]).
+
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use unicode_width::UnicodeWidthStr;
 
-use rott_core::Link;
+use rott_core::language::language_name;
+use rott_core::models::thread_notes;
+use rott_core::sync::SyncProgress;
+use rott_core::{Health, Link};
+
+use crate::i18n::{t, tf};
+use crate::table::{colors_enabled, paint, terminal_width, truncate, wrap, Color, Column, Table};
+
+/// Width of the labels in link details: the longest, "Description: ", in
+/// the current locale
+fn label_width() -> usize {
+    t("Description").width() + 2
+}
 
 /// Output format options
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -35,11 +56,19 @@ impl OutputFormat {
 pub struct Output {
     /// The output format
     pub format: OutputFormat,
+    /// Terminal width, for laying out human output
+    width: usize,
+    /// Whether human output is colored
+    colors: bool,
 }
 
 impl Output {
     pub fn new(format: OutputFormat) -> Self {
-        Self { format }
+        Self {
+            format,
+            width: terminal_width(),
+            colors: colors_enabled(),
+        }
     }
 
     /// Check if output is in quiet mode
@@ -54,39 +83,113 @@ impl Output {
 
     /// Print a single link (with notes summary)
     pub fn print_link(&self, link: &Link) {
+        self.print_link_with_health(link, None, None);
+    }
+
+    /// Print a single link, noting its health if it needs attention and
+    /// where its screenshot is, if it has one
+    pub fn print_link_with_health(
+        &self,
+        link: &Link,
+        health: Option<&Health>,
+        screenshot: Option<&Path>,
+    ) {
         match self.format {
             OutputFormat::Human => {
-                println!("ID:          {}", link.id);
-                println!("Title:       {}", link.title);
-                println!("URL:         {}", link.url);
+                self.print_field("ID", &link.id.to_string(), Some(Color::Yellow));
+                self.print_field("Title", &link.title, Some(Color::Bold));
+                self.print_field("URL", link.display_url(), None);
+                if link.canonical_url.is_some() {
+                    self.print_field("Saved as", &link.url, None);
+                }
                 if let Some(ref desc) = link.description {
-                    println!("Description: {}", desc);
+                    self.print_field("Description", desc, None);
                 }
                 if !link.author.is_empty() {
-                    println!("Author:      {}", link.author.join(", "));
+                    self.print_field("Author", &link.author.join(", "), None);
                 }
                 if !link.tags.is_empty() {
-                    println!("Tags:        {}", link.tags.join(", "));
+                    self.print_field("Tags", &link.tags.join(", "), Some(Color::Cyan));
+                }
+                if let Some(ref via) = link.via {
+                    self.print_field("Via", via, None);
+                }
+                if let Some(ref source) = link.source_context {
+                    self.print_field("Source", source, None);
+                }
+                if let Some(published) = link.published_at {
+                    self.print_field("Published", &published.format("%Y-%m-%d").to_string(), None);
+                }
+                if let Some(ref language) = link.language {
+                    self.print_field("Language", &language_name(language), None);
+                }
+                if let Some(expires) = link.expires_at {
+                    let color = link.is_expired(Utc::now()).then_some(Color::Red);
+                    self.print_field("Expires", &expires.format("%Y-%m-%d").to_string(), color);
+                }
+                self.print_field(
+                    "Created",
+                    &link.created_at.format("%Y-%m-%d %H:%M").to_string(),
+                    None,
+                );
+                self.print_field(
+                    "Updated",
+                    &link.updated_at.format("%Y-%m-%d %H:%M").to_string(),
+                    None,
+                );
+                if link.paywalled {
+                    self.print_field("Access", t("paywall or login required"), Some(Color::Red));
+                }
+                if link.content_changed {
+                    self.print_field("Content", t("changed since saved"), Some(Color::Red));
+                }
+                let size = link.content_size();
+                if size > 0 {
+                    self.print_field("Size", &human_size(size), None);
+                }
+                if !link.attachments.is_empty() {
+                    let names: Vec<_> = link.attachments.keys().map(String::as_str).collect();
+                    self.print_field("Attachments", &names.join(", "), None);
+                }
+                if let Some(screenshot) = screenshot {
+                    self.print_field("Screenshot", &screenshot.display().to_string(), None);
+                }
+                if let Some(health) = health.filter(|h| h.needs_attention()) {
+                    let issues: Vec<_> = health.issues.iter().map(|i| i.label()).collect();
+                    self.print_field(
+                        "Health",
+                        &format!("{} ({})", health.score, issues.join(", ")),
+                        Some(Color::Red),
+                    );
                 }
-                println!("Created:     {}", link.created_at.format("%Y-%m-%d %H:%M"));
-                println!("Updated:     {}", link.updated_at.format("%Y-%m-%d %H:%M"));
 
                 // Show notes
                 if !link.notes.is_empty() {
                     println!();
-                    println!("── Notes ({}) ──", link.notes.len());
-                    for note in &link.notes {
-                        let preview = truncate_line(&note.body, 60);
-                        if let Some(ref title) = note.title {
-                            println!(
-                                "[{}] {} - {}",
-                                note.created_at.format("%Y-%m-%d"),
-                                title,
-                                preview
-                            );
-                        } else {
-                            println!("[{}] {}", note.created_at.format("%Y-%m-%d"), preview);
-                        }
+                    println!(
+                        "{}",
+                        paint(
+                            &format!("── Notes ({}) ──", notes_summary(link)),
+                            Color::Bold,
+                            self.colors
+                        )
+                    );
+                    for (note, depth) in thread_notes(&link.notes) {
+                        let pinned = if note.pinned { "[pinned] " } else { "" };
+                        let indent = if depth > 0 { "  ↳ " } else { "" };
+                        let date = format!("[{}]", note.created_at.format("%Y-%m-%d"));
+                        let text = match note.title {
+                            Some(ref title) => format!("{} - {}", title, first_line(&note.body)),
+                            None => first_line(&note.body).to_string(),
+                        };
+                        let prefix_width = indent.width() + pinned.width() + date.width() + 1;
+                        println!(
+                            "{}{}{} {}",
+                            indent,
+                            pinned,
+                            paint(&date, Color::Dim, self.colors),
+                            truncate_line(&text, self.width.saturating_sub(prefix_width).max(20))
+                        );
                     }
                 }
             }
@@ -104,23 +207,41 @@ impl Output {
         match self.format {
             OutputFormat::Human => {
                 if links.is_empty() {
-                    println!("No links found.");
+                    println!("{}", t("No links found."));
                     return;
                 }
+                let now = Utc::now();
+                let mut table = Table::new(vec![
+                    Column::new("ID").color(Color::Yellow),
+                    Column::new("TITLE").flex(),
+                    Column::new("NOTES").right(),
+                    Column::new("FLAGS").color(Color::Red),
+                    Column::new("URL").flex().color(Color::Dim),
+                ]);
                 for link in links {
-                    let notes_indicator = if link.notes.is_empty() {
-                        String::new()
-                    } else {
-                        format!(" [{}]", link.notes.len())
+                    let notes = match link.notes.len() {
+                        0 => String::new(),
+                        n => n.to_string(),
                     };
-                    println!(
-                        "{} | {}{} | {}",
-                        &link.id.to_string()[..8],
-                        truncate(&link.title, 35),
-                        notes_indicator,
-                        truncate(&link.url, 45)
-                    );
+                    let mut flags = Vec::new();
+                    if link.content_changed {
+                        flags.push(t("changed"));
+                    }
+                    if link.paywalled {
+                        flags.push(t("paywall"));
+                    }
+                    if link.is_expired(now) {
+                        flags.push(t("expired"));
+                    }
+                    table.row(vec![
+                        link.id.to_string()[..8].to_string(),
+                        link.title.clone(),
+                        notes,
+                        flags.join(","),
+                        link.display_url().to_string(),
+                    ]);
                 }
+                self.print_table(&table);
                 println!("\n{} link(s)", links.len());
             }
             OutputFormat::Json => {
@@ -134,30 +255,105 @@ impl Output {
         }
     }
 
+    /// Print links with their health, as from `link list --stale`
+    pub fn print_stale_links(&self, links: &[(Link, Health)]) {
+        match self.format {
+            OutputFormat::Human => {
+                if links.is_empty() {
+                    println!("{}", t("No links need attention."));
+                    return;
+                }
+                let mut table = Table::new(vec![
+                    Column::new("ID").color(Color::Yellow),
+                    Column::new("SCORE").right(),
+                    Column::new("TITLE").flex(),
+                    Column::new("ISSUES").color(Color::Red),
+                ]);
+                for (link, health) in links {
+                    let issues: Vec<_> = health.issues.iter().map(|i| i.label()).collect();
+                    table.row(vec![
+                        link.id.to_string()[..8].to_string(),
+                        health.score.to_string(),
+                        link.title.clone(),
+                        issues.join(", "),
+                    ]);
+                }
+                self.print_table(&table);
+                println!("\n{} link(s) need attention", links.len());
+            }
+            OutputFormat::Json => {
+                let json: Vec<_> = links
+                    .iter()
+                    .map(|(link, health)| {
+                        serde_json::json!({
+                            "link": link,
+                            "health": health,
+                        })
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&json).unwrap());
+            }
+            OutputFormat::Quiet => {
+                for (link, _) in links {
+                    println!("{}", link.id);
+                }
+            }
+        }
+    }
+
     /// Print notes for a specific link
     pub fn print_link_notes(&self, link: &Link) {
         match self.format {
             OutputFormat::Human => {
-                println!("Notes for: {} - {}", &link.id.to_string()[..8], link.title);
+                println!(
+                    "{}",
+                    tf(
+                        "Notes for: {} - {}",
+                        &[&&link.id.to_string()[..8], &link.title]
+                    )
+                );
                 println!();
 
                 if link.notes.is_empty() {
-                    println!("No notes on this link.");
+                    println!("{}", t("No notes on this link."));
                     return;
                 }
 
-                for note in &link.notes {
-                    println!("────────────────────────────────────────");
+                for (note, depth) in thread_notes(&link.notes) {
+                    // Replies are indented under the note they reply to
+                    let indent = "    ".repeat(depth);
+                    let rule = self.width.min(40).saturating_sub(indent.len()).max(8);
                     println!(
-                        "ID: {}  Created: {}",
-                        &note.id.to_string()[..8],
-                        note.created_at.format("%Y-%m-%d %H:%M")
+                        "{}{}",
+                        indent,
+                        paint(&"─".repeat(rule), Color::Dim, self.colors)
                     );
+                    match note.parent_id.filter(|_| depth > 0) {
+                        Some(parent) => println!(
+                            "{}ID: {}  Created: {}  Reply to: {}",
+                            indent,
+                            &note.id.to_string()[..8],
+                            note.created_at.format("%Y-%m-%d %H:%M"),
+                            &parent.to_string()[..8]
+                        ),
+                        None => println!(
+                            "{}ID: {}  Created: {}",
+                            indent,
+                            &note.id.to_string()[..8],
+                            note.created_at.format("%Y-%m-%d %H:%M")
+                        ),
+                    }
                     if let Some(ref title) = note.title {
-                        println!("Title: {}", title);
+                        println!("{}Title: {}", indent, title);
+                    }
+                    if let Some(updated_at) = note.updated_at {
+                        println!("{}Edited: {}", indent, updated_at.format("%Y-%m-%d %H:%M"));
                     }
                     println!();
-                    println!("{}", note.body);
+                    let body_width = self.width.saturating_sub(indent.len()).max(20);
+                    for line in wrap(&note.body, body_width) {
+                        println!("{}{}", indent, line);
+                    }
                     println!();
                 }
                 println!("{} note(s)", link.notes.len());
@@ -175,26 +371,50 @@ impl Output {
 
     /// Print a list of tags
     pub fn print_tags(&self, tags: &[(String, i64)]) {
+        self.print_counts(tags, "TAG", t("No tags found."), "{} tag(s)");
+    }
+
+    /// Print a list of authors
+    pub fn print_authors(&self, authors: &[(String, i64)]) {
+        self.print_counts(authors, "AUTHOR", t("No authors found."), "{} author(s)");
+    }
+
+    /// Print names with usage counts
+    ///
+    /// `total` is the catalog entry for the line under the table, with a
+    /// `{}` for the number of names.
+    fn print_counts(
+        &self,
+        counts: &[(String, i64)],
+        header: &'static str,
+        empty: &str,
+        total: &'static str,
+    ) {
         match self.format {
             OutputFormat::Human => {
-                if tags.is_empty() {
-                    println!("No tags found.");
+                if counts.is_empty() {
+                    println!("{}", empty);
                     return;
                 }
-                for (name, count) in tags {
-                    println!("{} ({})", name, count);
+                let mut table = Table::new(vec![
+                    Column::new(header).flex().color(Color::Cyan),
+                    Column::new("LINKS").right(),
+                ]);
+                for (name, count) in counts {
+                    table.row(vec![name.clone(), count.to_string()]);
                 }
-                println!("\n{} tag(s)", tags.len());
+                self.print_table(&table);
+                println!("\n{}", tf(total, &[&counts.len()]));
             }
             OutputFormat::Json => {
-                let json_tags: Vec<_> = tags
+                let json: Vec<_> = counts
                     .iter()
                     .map(|(name, count)| serde_json::json!({"name": name, "count": count}))
                     .collect();
-                println!("{}", serde_json::to_string_pretty(&json_tags).unwrap());
+                println!("{}", serde_json::to_string_pretty(&json).unwrap());
             }
             OutputFormat::Quiet => {
-                for (name, _) in tags {
+                for (name, _) in counts {
                     println!("{}", name);
                 }
             }
@@ -220,6 +440,31 @@ impl Output {
         self.format == OutputFormat::Human
     }
 
+    /// Print a table laid out for the terminal
+    fn print_table(&self, table: &Table) {
+        for line in table.render(self.width, self.colors) {
+            println!("{}", line);
+        }
+    }
+
+    /// Print a labelled value of link details, wrapping it under the label
+    pub fn print_field(&self, label: &'static str, value: &str, color: Option<Color>) {
+        let label_width = label_width();
+        let label = format!("{:<width$}", format!("{}:", t(label)), width = label_width);
+        let value_width = self.width.saturating_sub(label_width).max(20);
+        for (i, line) in wrap(value, value_width).iter().enumerate() {
+            let line = match color {
+                Some(color) => paint(line, color, self.colors),
+                None => line.clone(),
+            };
+            if i == 0 {
+                println!("{}{}", paint(&label, Color::Bold, self.colors), line);
+            } else {
+                println!("{}{}", " ".repeat(label_width), line);
+            }
+        }
+    }
+
     /// Print an informational message
     pub fn message(&self, msg: &str) {
         match self.format {
@@ -232,19 +477,72 @@ impl Output {
     }
 }
 
-/// Truncate a string to max length, adding "..." if truncated
-fn truncate(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
+/// A byte count in B, KB, or MB
+pub fn human_size(bytes: usize) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
     } else {
-        format!("{}...", &s[..max_len - 3])
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
     }
 }
 
+/// "3 notes, 412 words"
+pub fn notes_summary(link: &Link) -> String {
+    let notes = link.notes.len();
+    let words = link.note_word_count();
+    format!(
+        "{}, {}",
+        plural(notes, "{} note", "{} notes"),
+        plural(words, "{} word", "{} words")
+    )
+}
+
+/// "5 minutes ago", for a time before `now`
+pub fn time_ago(at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let elapsed = now.signed_duration_since(at);
+    if elapsed.num_minutes() < 1 {
+        t("just now").to_string()
+    } else if elapsed.num_hours() < 1 {
+        plural(
+            elapsed.num_minutes() as usize,
+            "{} minute ago",
+            "{} minutes ago",
+        )
+    } else if elapsed.num_days() < 1 {
+        plural(elapsed.num_hours() as usize, "{} hour ago", "{} hours ago")
+    } else {
+        plural(elapsed.num_days() as usize, "{} day ago", "{} days ago")
+    }
+}
+
+/// "2 changes sent, 5 received, 1.2 KB in 3 round trips"
+pub fn sync_summary(progress: &SyncProgress) -> String {
+    tf(
+        "{} sent, {} received, {} in {}",
+        &[
+            &plural(progress.changes_sent, "{} change", "{} changes"),
+            &progress.changes_received,
+            &human_size(progress.bytes_sent + progress.bytes_received),
+            &plural(progress.round_trips, "{} round trip", "{} round trips"),
+        ],
+    )
+}
+
+/// `count` filled into the catalog entry for one or for several
+fn plural(count: usize, one: &'static str, many: &'static str) -> String {
+    tf(if count == 1 { one } else { many }, &[&count])
+}
+
+/// The first line of some text
+fn first_line(s: &str) -> &str {
+    s.lines().next().unwrap_or("")
+}
+
 /// Truncate to first line and max length
 fn truncate_line(s: &str, max_len: usize) -> String {
-    let first_line = s.lines().next().unwrap_or("");
-    truncate(first_line, max_len)
+    truncate(first_line(s), max_len)
 }
 
 #[cfg(test)]
@@ -266,6 +564,37 @@ mod tests {
         assert_eq!(truncate("this is a long string", 10), "this is...");
     }
 
+    #[test]
+    fn test_sizes_and_counts() {
+        assert_eq!(human_size(512), "512 B");
+        assert_eq!(human_size(2560), "2.5 KB");
+
+        let mut link = Link::new("https://example.com");
+        link.add_note(rott_core::Note::new("One"));
+        assert_eq!(notes_summary(&link), "1 note, 1 word");
+        link.add_note(rott_core::Note::new("two more"));
+        assert_eq!(notes_summary(&link), "2 notes, 3 words");
+
+        let mut progress = SyncProgress::default();
+        progress.record_sent(600, 1);
+        progress.record_received(2000, 5);
+        assert_eq!(
+            sync_summary(&progress),
+            "1 change sent, 5 received, 2.5 KB in 1 round trip"
+        );
+    }
+
+    #[test]
+    fn test_time_ago() {
+        let now = Utc::now();
+        let ago = |minutes| time_ago(now - chrono::Duration::minutes(minutes), now);
+        assert_eq!(ago(0), "just now");
+        assert_eq!(ago(1), "1 minute ago");
+        assert_eq!(ago(5), "5 minutes ago");
+        assert_eq!(ago(150), "2 hours ago");
+        assert_eq!(ago(3 * 24 * 60), "3 days ago");
+    }
+
     #[test]
     fn test_truncate_line() {
         assert_eq!(truncate_line("single line", 20), "single line");
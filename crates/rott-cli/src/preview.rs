@@ -0,0 +1,239 @@
+//! Previews of bulk changes
+//!
+//! Commands that change many links at once (imports, note deduplication,
+//! find-and-replace, scripts) take `--dry-run`. They describe what they
+//! would do as a [`Preview`] and print it instead of saving anything, so
+//! every dry run looks the same: a line of counts, then a sample of the
+//! changes, with diffs where text would change.
+//!
+//! Human output is translated (see [`crate::i18n`]), so actions and count
+//! labels should be catalog entries; an action naming a file or search
+//! text keeps it out of the entry with a `{}` filled in by
+//! [`Preview::subject`]. With `--json` the preview is printed in full as
+//! one object, with the action and count labels in English:
+//!
+//! ```text
+//! { "dry_run": true, "action": "...", "counts": { "<label>": n, ... },
+//!   "changes": [ { "kind": "add" | "change" | "delete", "summary": "...",
+//!                  "link_id"?, "before"?, "after"? }, ... ] }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use anyhow::Result;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::diff::changed_lines;
+use crate::i18n::{fill, t, tf, translate};
+use crate::output::{Output, OutputFormat};
+
+/// Changes listed in human output before the rest are summarized
+const SAMPLE_SIZE: usize = 20;
+
+/// What a change would do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Add,
+    Change,
+    Delete,
+}
+
+impl ChangeKind {
+    fn marker(self) -> char {
+        match self {
+            ChangeKind::Add => '+',
+            ChangeKind::Change => '~',
+            ChangeKind::Delete => '-',
+        }
+    }
+}
+
+/// One change a command would make
+#[derive(Debug, Clone, Serialize)]
+pub struct Change {
+    pub kind: ChangeKind,
+    /// One line describing the change
+    pub summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_id: Option<Uuid>,
+    /// Text before and after, for changes to text
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+
+impl Change {
+    pub fn new(kind: ChangeKind, summary: impl Into<String>) -> Self {
+        Self {
+            kind,
+            summary: summary.into(),
+            link_id: None,
+            before: None,
+            after: None,
+        }
+    }
+
+    /// The link the change is to
+    pub fn link(mut self, id: Uuid) -> Self {
+        self.link_id = Some(id);
+        self
+    }
+
+    /// Show the change as a diff of `before` and `after`
+    pub fn diff(mut self, before: impl Into<String>, after: impl Into<String>) -> Self {
+        self.before = Some(before.into());
+        self.after = Some(after.into());
+        self
+    }
+}
+
+/// What a bulk command would do, without doing it
+#[derive(Debug, Clone, Serialize)]
+pub struct Preview {
+    /// What the command does, e.g. "Merge duplicate notes"
+    pub action: String,
+    /// What fills the `{}` in `action`, if it has one
+    #[serde(skip)]
+    pub subject: Option<String>,
+    /// Totals by label, in the order they were added
+    #[serde(serialize_with = "serialize_counts")]
+    pub counts: Vec<(String, usize)>,
+    pub changes: Vec<Change>,
+}
+
+impl Preview {
+    pub fn new(action: impl Into<String>) -> Self {
+        Self {
+            action: action.into(),
+            subject: None,
+            counts: Vec::new(),
+            changes: Vec::new(),
+        }
+    }
+
+    /// Fill the action's `{}`, e.g. the file in "Merge {}"
+    pub fn subject(mut self, subject: impl Display) -> Self {
+        self.subject = Some(subject.to_string());
+        self
+    }
+
+    /// Add a total, e.g. `count("notes", 3)`
+    pub fn count(mut self, label: impl Into<String>, count: usize) -> Self {
+        self.counts.push((label.into(), count));
+        self
+    }
+
+    pub fn push(&mut self, change: Change) {
+        self.changes.push(change);
+    }
+
+    /// Print the preview (nothing in quiet mode)
+    pub fn print(&self, output: &Output) -> Result<()> {
+        match output.format {
+            OutputFormat::Json => {
+                let mut json = serde_json::to_value(self)?;
+                json["action"] = self.filled_action(&self.action).into();
+                json["dry_run"] = serde_json::Value::Bool(true);
+                println!("{}", serde_json::to_string_pretty(&json)?);
+            }
+            OutputFormat::Quiet => {}
+            OutputFormat::Human => {
+                for line in self.render(SAMPLE_SIZE) {
+                    println!("{}", line);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Human output, listing at most `sample` changes
+    fn render(&self, sample: usize) -> Vec<String> {
+        let action = self.filled_action(translate(&self.action));
+        let mut lines = vec![tf("Dry run: {}", &[&action])];
+        if !self.counts.is_empty() {
+            let counts: Vec<String> = self
+                .counts
+                .iter()
+                .map(|(label, count)| format!("{} {}", count, translate(label)))
+                .collect();
+            lines.push(format!("  {}", counts.join(", ")));
+        }
+        for change in self.changes.iter().take(sample) {
+            lines.push(format!("  {} {}", change.kind.marker(), change.summary));
+            if let (Some(before), Some(after)) = (&change.before, &change.after) {
+                lines.extend(
+                    changed_lines(before, after)
+                        .into_iter()
+                        .map(|line| format!("      {}", line)),
+                );
+            }
+        }
+        if self.changes.len() > sample {
+            lines.push(format!(
+                "  {}",
+                tf("... and {} more", &[&(self.changes.len() - sample)])
+            ));
+        }
+        lines.push(t("Nothing was changed.").to_string());
+        lines
+    }
+
+    /// `action` (or its translation) with the subject filled in
+    fn filled_action(&self, action: &str) -> String {
+        match &self.subject {
+            Some(subject) => fill(action, &[subject]),
+            None => action.to_string(),
+        }
+    }
+}
+
+fn serialize_counts<S: serde::Serializer>(
+    counts: &[(String, usize)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let map: BTreeMap<&str, usize> = counts.iter().map(|(l, c)| (l.as_str(), *c)).collect();
+    map.serialize(serializer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_samples_and_diffs() {
+        let mut preview = Preview::new("Replace text in notes").count("notes", 3);
+        preview.push(Change::new(ChangeKind::Change, "First").diff("a\nold", "a\nnew"));
+        preview.push(Change::new(ChangeKind::Add, "Second"));
+        preview.push(Change::new(ChangeKind::Delete, "Third"));
+
+        assert_eq!(
+            preview.render(2),
+            vec![
+                "Dry run: Replace text in notes",
+                "  3 notes",
+                "  ~ First",
+                "      - old",
+                "      + new",
+                "  + Second",
+                "  ... and 1 more",
+                "Nothing was changed.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json_shape() {
+        let id = Uuid::new_v4();
+        let mut preview = Preview::new("Import").count("links", 1);
+        preview.push(Change::new(ChangeKind::Add, "Example").link(id));
+        let json = serde_json::to_value(&preview).unwrap();
+        assert_eq!(json["counts"]["links"], 1);
+        assert_eq!(json["changes"][0]["kind"], "add");
+        assert_eq!(json["changes"][0]["link_id"], id.to_string());
+        assert!(json["changes"][0].get("before").is_none());
+    }
+}
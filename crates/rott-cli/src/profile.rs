@@ -0,0 +1,79 @@
+//! Startup timing for `--profile-startup`
+//!
+//! Records how long each phase of startup takes (config, document load,
+//! first query) and prints the breakdown to stderr, so slow starts can be
+//! narrowed down without a profiler.
+
+use std::time::{Duration, Instant};
+
+/// Phase timings collected during startup
+pub struct StartupProfile {
+    enabled: bool,
+    started: Instant,
+    last: Instant,
+    phases: Vec<(&'static str, Duration)>,
+}
+
+impl StartupProfile {
+    /// Start timing (does nothing unless `enabled`)
+    pub fn new(enabled: bool) -> Self {
+        let now = Instant::now();
+        Self {
+            enabled,
+            started: now,
+            last: now,
+            phases: Vec::new(),
+        }
+    }
+
+    /// Record the end of a phase, timed from the previous mark
+    pub fn mark(&mut self, phase: &'static str) {
+        if !self.enabled {
+            return;
+        }
+        let now = Instant::now();
+        self.phases.push((phase, now - self.last));
+        self.last = now;
+    }
+
+    /// Print the breakdown to stderr
+    pub fn report(&self) {
+        if !self.enabled {
+            return;
+        }
+        eprintln!("Startup profile:");
+        for (phase, duration) in &self.phases {
+            eprintln!("  {:<16} {:>9.2} ms", phase, millis(*duration));
+        }
+        eprintln!(
+            "  {:<16} {:>9.2} ms",
+            "total",
+            millis(self.last - self.started)
+        );
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_marks_only_when_enabled() {
+        let mut profile = StartupProfile::new(false);
+        profile.mark("config");
+        assert!(profile.phases.is_empty());
+
+        let mut profile = StartupProfile::new(true);
+        profile.mark("config");
+        profile.mark("load document");
+        let names: Vec<_> = profile.phases.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, vec!["config", "load document"]);
+
+        let total: Duration = profile.phases.iter().map(|(_, d)| *d).sum();
+        assert_eq!(total, profile.last - profile.started);
+    }
+}
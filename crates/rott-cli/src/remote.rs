@@ -0,0 +1,369 @@
+//! Using a collection on another machine over SSH
+//!
+//! `rott --remote ssh://host <command>` runs `rott serve --stdio` on the
+//! host over SSH and sends it requests, so a collection can be searched and
+//! added to from a server shell without syncing the document there. Only
+//! the requests and their results cross the connection; the host does the
+//! work (including fetching page metadata) and syncs as usual.
+//!
+//! The protocol is one JSON object per line in each direction. A request
+//! names its `method` alongside its arguments:
+//!
+//! ```text
+//! -> {"method":"search","query":"lang:de rust"}
+//! <- {"result":[{...link...}]}
+//! -> {"method":"get_link","id":"a1b2"}
+//! <- {"error":"Link not found: a1b2"}
+//! ```
+//!
+//! Targets are `ssh://[user@]host[:port][/path/to/rott]`; the path is only
+//! needed when `rott` isn't on the host's `PATH`. SSH itself (keys, agent,
+//! `~/.ssh/config` aliases) works as it does for `ssh`.
+
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+use uuid::Uuid;
+
+use rott_core::{LinkSort, Note, Store};
+
+use crate::commands::link::{new_link, parse_link_id};
+
+/// Bumped when requests or results change incompatibly
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// A request from the client
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum Request {
+    /// Check the host speaks this protocol; returns `{"version": n}`
+    Hello,
+    /// Every link, or those with a tag
+    ListLinks { tag: Option<String> },
+    /// Links matching a search (operators work)
+    Search { query: String },
+    /// One link, by full ID or prefix
+    GetLink { id: String },
+    /// Save a URL (metadata is fetched on the host); returns the new link
+    AddLink {
+        url: String,
+        #[serde(default)]
+        tags: Vec<String>,
+        via: Option<String>,
+        source: Option<String>,
+    },
+    /// Add a note to a link; returns an [`AddedNote`]
+    AddNote {
+        link_id: String,
+        title: Option<String>,
+        body: String,
+    },
+    /// Tags with their link counts
+    Tags,
+}
+
+/// Where a note added with [`Request::AddNote`] went
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddedNote {
+    pub link_id: Uuid,
+    pub note_id: Uuid,
+}
+
+/// The host's answer to one request
+#[derive(Debug, Serialize, Deserialize)]
+struct Response {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+// ==================== Host ====================
+
+/// Answer requests on stdin until it closes
+///
+/// A failed request is reported to the client and the session carries on.
+pub async fn serve_stdio(store: &mut Store) -> Result<()> {
+    let mut lines = tokio::io::BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => match handle(store, request).await {
+                Ok(result) => Response {
+                    result: Some(result),
+                    error: None,
+                },
+                Err(e) => Response {
+                    result: None,
+                    error: Some(format!("{:#}", e)),
+                },
+            },
+            Err(e) => Response {
+                result: None,
+                error: Some(format!("Invalid request: {}", e)),
+            },
+        };
+        let mut data = serde_json::to_vec(&response)?;
+        data.push(b'\n');
+        stdout.write_all(&data).await?;
+        stdout.flush().await?;
+    }
+    Ok(())
+}
+
+async fn handle(store: &mut Store, request: Request) -> Result<Value> {
+    let result = match request {
+        Request::Hello => serde_json::json!({ "version": PROTOCOL_VERSION }),
+        Request::ListLinks { tag } => {
+            let links = match tag {
+                Some(ref tag) => store.get_links_by_tag(tag, LinkSort::default())?,
+                None => store.get_all_links(LinkSort::default())?,
+            };
+            serde_json::to_value(links)?
+        }
+        Request::Search { query } => serde_json::to_value(store.search_links(&query)?)?,
+        Request::GetLink { id } => {
+            let uuid = parse_link_id(&id, store)?;
+            let link = store
+                .get_link(uuid)?
+                .ok_or_else(|| anyhow!("Link not found: {}", id))?;
+            serde_json::to_value(link)?
+        }
+        Request::AddLink {
+            url,
+            tags,
+            via,
+            source,
+        } => {
+            let mut link = new_link(store, &url, tags).await?;
+            if via.is_some() || source.is_some() {
+                link.set_source(via, source);
+            }
+            store.add_link(&link).context("Failed to create link")?;
+            serde_json::to_value(link)?
+        }
+        Request::AddNote {
+            link_id,
+            title,
+            body,
+        } => {
+            if body.trim().is_empty() {
+                bail!("Note body is empty");
+            }
+            let uuid = parse_link_id(&link_id, store)?;
+            let note = match title {
+                Some(title) => Note::with_title(title, body),
+                None => Note::new(body),
+            };
+            store
+                .add_note_to_link(uuid, &note)
+                .context("Failed to add note to link")?;
+            serde_json::to_value(AddedNote {
+                link_id: uuid,
+                note_id: note.id,
+            })?
+        }
+        Request::Tags => serde_json::to_value(store.get_tags_with_counts()?)?,
+    };
+    Ok(result)
+}
+
+// ==================== Client ====================
+
+/// Where to reach a remote collection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteTarget {
+    /// `host` or `user@host`, as passed to ssh
+    pub destination: String,
+    pub port: Option<u16>,
+    /// Command that runs rott on the host
+    pub command: String,
+}
+
+impl RemoteTarget {
+    /// Parse `ssh://[user@]host[:port][/path/to/rott]`
+    pub fn parse(target: &str) -> Result<Self> {
+        let Some(rest) = target.strip_prefix("ssh://") else {
+            bail!("Remote must be an ssh:// URL, e.g. ssh://desktop");
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, ""),
+        };
+        let (destination, port) = match authority.rsplit_once(':') {
+            Some((destination, port)) => {
+                let port = port
+                    .parse()
+                    .with_context(|| format!("Invalid port in {}", target))?;
+                (destination, Some(port))
+            }
+            None => (authority, None),
+        };
+        let host = destination.rsplit('@').next().unwrap_or_default();
+        if host.is_empty() {
+            bail!("No host in {}", target);
+        }
+        // ssh would read these as options (e.g. -oProxyCommand=...)
+        if destination.starts_with('-') || host.starts_with('-') {
+            bail!("Invalid host in {}", target);
+        }
+        let command = match path {
+            "" | "/" => "rott".to_string(),
+            path => path.to_string(),
+        };
+        Ok(Self {
+            destination: destination.to_string(),
+            port,
+            command,
+        })
+    }
+}
+
+/// A session with `rott serve --stdio` on another machine
+pub struct RemoteClient {
+    child: Child,
+    /// Closed when the client is dropped, which ends the host's session
+    stdin: Option<ChildStdin>,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl RemoteClient {
+    /// Start the host's rott over SSH and check it speaks this protocol
+    pub fn connect(target: &RemoteTarget) -> Result<Self> {
+        let mut ssh = Command::new("ssh");
+        if let Some(port) = target.port {
+            ssh.arg("-p").arg(port.to_string());
+        }
+        // SSH errors and the host's logs go to our stderr
+        let mut child = ssh
+            .arg(&target.destination)
+            .arg("--")
+            .arg(&target.command)
+            .args(["serve", "--stdio"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .context("Failed to run ssh")?;
+        let stdin = child.stdin.take().context("No stdin for ssh")?;
+        let stdout = BufReader::new(child.stdout.take().context("No stdout for ssh")?);
+
+        let mut client = Self {
+            child,
+            stdin: Some(stdin),
+            stdout,
+        };
+        let hello: Value = client
+            .call(&Request::Hello)
+            .with_context(|| format!("Couldn't start rott on {}", target.destination))?;
+        let version = hello["version"].as_u64().unwrap_or_default();
+        if version != u64::from(PROTOCOL_VERSION) {
+            bail!(
+                "rott on {} speaks protocol version {}, this one speaks {}; update the older one",
+                target.destination,
+                version,
+                PROTOCOL_VERSION
+            );
+        }
+        Ok(client)
+    }
+
+    /// Send a request and wait for its result
+    pub fn call<T: DeserializeOwned>(&mut self, request: &Request) -> Result<T> {
+        let mut data = serde_json::to_vec(request)?;
+        data.push(b'\n');
+        let stdin = self
+            .stdin
+            .as_mut()
+            .context("Connection to the remote closed")?;
+        stdin
+            .write_all(&data)
+            .and_then(|_| stdin.flush())
+            .context("Connection to the remote closed")?;
+
+        let mut line = String::new();
+        if self.stdout.read_line(&mut line)? == 0 {
+            bail!("Connection to the remote closed");
+        }
+        let response: Response =
+            serde_json::from_str(&line).context("Invalid response from the remote")?;
+        if let Some(error) = response.error {
+            bail!("{}", error);
+        }
+        serde_json::from_value(response.result.unwrap_or(Value::Null))
+            .context("Unexpected response from the remote")
+    }
+}
+
+impl Drop for RemoteClient {
+    fn drop(&mut self) {
+        drop(self.stdin.take());
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(
+            RemoteTarget::parse("ssh://desktop").unwrap(),
+            RemoteTarget {
+                destination: "desktop".into(),
+                port: None,
+                command: "rott".into(),
+            }
+        );
+        assert_eq!(
+            RemoteTarget::parse("ssh://me@desktop.lan:2222/home/me/.cargo/bin/rott").unwrap(),
+            RemoteTarget {
+                destination: "me@desktop.lan".into(),
+                port: Some(2222),
+                command: "/home/me/.cargo/bin/rott".into(),
+            }
+        );
+        assert!(RemoteTarget::parse("desktop").is_err());
+        assert!(RemoteTarget::parse("ssh://").is_err());
+        assert!(RemoteTarget::parse("ssh://desktop:port").is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_ssh_options() {
+        assert!(RemoteTarget::parse("ssh://-oProxyCommand=touch%20pwned").is_err());
+        assert!(RemoteTarget::parse("ssh://-oProxyCommand=id@desktop").is_err());
+        assert!(RemoteTarget::parse("ssh://me@-oProxyCommand=id").is_err());
+    }
+
+    #[test]
+    fn test_request_format() {
+        let request = Request::Search {
+            query: "rust".into(),
+        };
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"method":"search","query":"rust"}"#
+        );
+        let request: Request =
+            serde_json::from_str(r#"{"method":"add_link","url":"https://example.com"}"#).unwrap();
+        assert_eq!(
+            request,
+            Request::AddLink {
+                url: "https://example.com".into(),
+                tags: Vec::new(),
+                via: None,
+                source: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! Page screenshots through a headless browser
+//!
+//! With `screenshot_command` set, `rott link snapshot <id>` (and, with
+//! `screenshot_on_save`, saving a link) runs the command to capture the
+//! page, for pages that will change or disappear. The command's arguments
+//! may use `{url}` (the page) and `{output}` (the file to write), e.g.
+//!
+//! ```text
+//! chromium --headless --disable-gpu --window-size=1280,1024 --screenshot={output} {url}
+//! ```
+//!
+//! The image is stored as the link's `screenshot.png` attachment (see
+//! [`rott_core::attachments`]); a new capture replaces the link's previous
+//! one, which maintenance gc removes later. `rott link show`, the TUI's
+//! detail pane and the web viewer show it with the rest of the link.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use rott_core::attachments::AttachmentStore;
+use rott_core::{Link, Store};
+use tokio::process::Command;
+use uuid::Uuid;
+
+/// Attachment name of a link's screenshot
+pub const SCREENSHOT_ATTACHMENT: &str = "screenshot.png";
+
+/// How long the browser gets to capture a page, in seconds
+const TIMEOUT: u64 = 60;
+
+/// Screenshot a link's page and attach it to the link
+///
+/// Returns the screenshot's path.
+pub async fn snapshot(store: &mut Store, id: Uuid) -> Result<PathBuf> {
+    let command = store.config().screenshot_command.clone().context(
+        "No screenshot command. Set one with: rott config set screenshot_command '<command>'",
+    )?;
+    let url = store
+        .get_link(id)?
+        .with_context(|| format!("Link not found: {}", id))?
+        .url;
+    let image = capture(&command, &url).await?;
+
+    let attachments = AttachmentStore::from_config(store.config());
+    let hash = attachments.put(&image)?;
+    // Read again: the link may have changed during the capture
+    let mut link = store
+        .get_link(id)?
+        .with_context(|| format!("Link not found: {}", id))?;
+    link.attachments
+        .insert(SCREENSHOT_ATTACHMENT.to_string(), hash.clone());
+    store.update_link(&link)?;
+    Ok(attachments.path(&hash))
+}
+
+/// Screenshot a newly saved link, if configured to
+///
+/// A failed capture doesn't fail saving the link; the error is returned for
+/// the caller to report.
+pub async fn snapshot_on_save(store: &mut Store, id: Uuid) -> Option<Result<PathBuf>> {
+    let config = store.config();
+    if !config.screenshot_on_save || config.screenshot_command.is_none() {
+        return None;
+    }
+    Some(snapshot(store, id).await)
+}
+
+/// The stored screenshot of a link, if it has one on this device
+pub fn screenshot_path(attachments: &AttachmentStore, link: &Link) -> Option<PathBuf> {
+    let hash = link.attachments.get(SCREENSHOT_ATTACHMENT)?;
+    let path = attachments.path(hash);
+    path.exists().then_some(path)
+}
+
+/// Run the screenshot command for a URL, returning the image
+async fn capture(command: &str, url: &str) -> Result<Vec<u8>> {
+    // A fresh file per capture (removed when dropped), so captures running
+    // at the same time don't write over each other
+    let output = tempfile::Builder::new()
+        .prefix("rott_screenshot_")
+        .suffix(".png")
+        .tempfile()
+        .context("Failed to create a file for the screenshot")?;
+    let output = output.path();
+
+    let args = command_args(command, url, output)?;
+    let mut child = Command::new(&args[0]);
+    child.args(&args[1..]).kill_on_drop(true);
+    let result = tokio::time::timeout(Duration::from_secs(TIMEOUT), child.output())
+        .await
+        .with_context(|| format!("Screenshot command timed out after {}s", TIMEOUT))?
+        .with_context(|| format!("Failed to run screenshot command: {}", args[0]))?;
+    if !result.status.success() {
+        bail!(
+            "Screenshot command failed ({}): {}",
+            result.status,
+            String::from_utf8_lossy(&result.stderr).trim()
+        );
+    }
+
+    let image = std::fs::read(output)
+        .with_context(|| format!("Failed to read the screenshot {}", output.display()))?;
+    if image.is_empty() {
+        bail!(
+            "Screenshot command didn't write {} (does it use {{output}}?)",
+            output.display()
+        );
+    }
+    Ok(image)
+}
+
+/// Split the command into arguments and fill in the placeholders
+///
+/// Arguments are split on whitespace before substituting, so a URL is
+/// always one argument whatever it contains.
+fn command_args(command: &str, url: &str, output: &Path) -> Result<Vec<String>> {
+    let output = output.to_string_lossy();
+    let args: Vec<String> = command
+        .split_whitespace()
+        .map(|arg| arg.replace("{url}", url).replace("{output}", &output))
+        .collect();
+    if args.is_empty() {
+        bail!("The screenshot command is empty");
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_args() {
+        let args = command_args(
+            "chromium --headless --screenshot={output} {url}",
+            "https://example.com/a b?q=1",
+            Path::new("/tmp/shot.png"),
+        )
+        .unwrap();
+        assert_eq!(
+            args,
+            [
+                "chromium",
+                "--headless",
+                "--screenshot=/tmp/shot.png",
+                "https://example.com/a b?q=1"
+            ]
+        );
+        assert!(command_args("  ", "https://example.com", Path::new("x")).is_err());
+    }
+}
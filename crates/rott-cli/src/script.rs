@@ -0,0 +1,268 @@
+//! User scripts
+//!
+//! `rott script run <file>` runs a [Rhai](https://rhai.rs) script against
+//! the collection, for rules that don't warrant a built-in feature:
+//!
+//! ```text
+//! // Retag everything from one site
+//! for link in tagged("to-read") {
+//!     if link.domain == "news.example.com" {
+//!         link.remove_tag("to-read");
+//!         link.add_tag("news");
+//!         save(link);
+//!     }
+//! }
+//! ```
+//!
+//! Scripts see a snapshot of the links taken before they start and can
+//! only reach the collection through the functions below; there's no file,
+//! network, or process access, and `import` and `eval` are disabled.
+//!
+//! | Function | |
+//! |---|---|
+//! | `links()` | Every link |
+//! | `tagged(tag)` | Links with a tag |
+//! | `search(query)` | Links matching a search (operators like `lang:de` work) |
+//! | `save(link)` | Save changes made to a link |
+//! | `delete(link)` | Delete a link |
+//! | `print(value)` | Write a line to stdout (for custom exports) |
+//!
+//! Links have `id`, `url`, `domain`, `title`, `description`, `tags`,
+//! `author`, `language`, `created_at`, `age_days`, `opened`, `note_count`,
+//! and `notes` (note bodies), and the methods `has_tag`, `add_tag`,
+//! `remove_tag`, and `add_note`. `title` and `description` can be assigned.
+//!
+//! Changes are collected while the script runs and written together when
+//! it finishes, so a script that fails partway changes nothing. Extra
+//! command-line arguments are available to the script as `args`.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{Array, Dynamic, Engine, Scope};
+use uuid::Uuid;
+
+use rott_core::domains::extract_domain;
+use rott_core::query::SearchQuery;
+use rott_core::{Link, Note};
+
+/// Operations a script may run before it's stopped (guards against loops
+/// that never end)
+const MAX_OPERATIONS: u64 = 50_000_000;
+
+/// Changes a script asked for
+#[derive(Debug, Default)]
+pub struct ScriptChanges {
+    /// Links to save, in the order they were first saved
+    pub updates: Vec<Link>,
+    /// Links to delete
+    pub deletes: Vec<Uuid>,
+}
+
+impl ScriptChanges {
+    /// Whether the script changed anything
+    pub fn is_empty(&self) -> bool {
+        self.updates.is_empty() && self.deletes.is_empty()
+    }
+
+    fn save(&mut self, link: Link) {
+        self.deletes.retain(|id| *id != link.id);
+        match self.updates.iter_mut().find(|l| l.id == link.id) {
+            Some(existing) => *existing = link,
+            None => self.updates.push(link),
+        }
+    }
+
+    fn delete(&mut self, id: Uuid) {
+        self.updates.retain(|l| l.id != id);
+        if !self.deletes.contains(&id) {
+            self.deletes.push(id);
+        }
+    }
+}
+
+/// Run a script against a snapshot of the links
+///
+/// `print` output is passed to `print`. Returns the changes the script
+/// made, without applying them.
+pub fn run_script(
+    source: &str,
+    links: Vec<Link>,
+    args: Vec<String>,
+    print: impl Fn(&str) + 'static,
+) -> Result<ScriptChanges> {
+    let links = Rc::new(links);
+    let changes = Rc::new(RefCell::new(ScriptChanges::default()));
+
+    let mut engine = Engine::new();
+    engine.set_module_resolver(DummyModuleResolver::new());
+    engine.disable_symbol("eval");
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.on_print(print);
+    register_link_type(&mut engine);
+
+    let all = Rc::clone(&links);
+    engine.register_fn("links", move || -> Array {
+        all.iter().cloned().map(Dynamic::from).collect()
+    });
+    let all = Rc::clone(&links);
+    engine.register_fn("tagged", move |tag: &str| -> Array {
+        all.iter()
+            .filter(|l| l.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+            .cloned()
+            .map(Dynamic::from)
+            .collect()
+    });
+    let all = Rc::clone(&links);
+    engine.register_fn("search", move |query: &str| -> Array {
+        let query = SearchQuery::parse(query);
+        all.iter()
+            .filter(|l| query.matches(l))
+            .cloned()
+            .map(Dynamic::from)
+            .collect()
+    });
+    let pending = Rc::clone(&changes);
+    engine.register_fn("save", move |link: Link| pending.borrow_mut().save(link));
+    let pending = Rc::clone(&changes);
+    engine.register_fn("delete", move |link: Link| {
+        pending.borrow_mut().delete(link.id)
+    });
+
+    let mut scope = Scope::new();
+    let args: Array = args.into_iter().map(Dynamic::from).collect();
+    scope.push_constant("args", args);
+
+    engine
+        .run_with_scope(&mut scope, source)
+        .map_err(|e| anyhow!("Script failed: {}", e))?;
+    drop(engine);
+
+    let changes = Rc::try_unwrap(changes)
+        .map_err(|_| anyhow!("Script state is still in use"))?
+        .into_inner();
+    Ok(changes)
+}
+
+/// Expose links to scripts as the `Link` type
+fn register_link_type(engine: &mut Engine) {
+    engine
+        .register_type_with_name::<Link>("Link")
+        .register_get("id", |l: &mut Link| l.id.to_string())
+        .register_get("url", |l: &mut Link| l.url.clone())
+        .register_get("domain", |l: &mut Link| {
+            extract_domain(&l.url).unwrap_or_default()
+        })
+        .register_get_set(
+            "title",
+            |l: &mut Link| l.title.clone(),
+            |l: &mut Link, title: String| l.set_title(title),
+        )
+        .register_get_set(
+            "description",
+            |l: &mut Link| l.description.clone().unwrap_or_default(),
+            |l: &mut Link, description: String| {
+                let description = description.trim();
+                l.set_description((!description.is_empty()).then(|| description.to_string()));
+            },
+        )
+        .register_get("tags", |l: &mut Link| strings(&l.tags))
+        .register_get("author", |l: &mut Link| strings(&l.author))
+        .register_get("language", |l: &mut Link| {
+            l.language.clone().unwrap_or_default()
+        })
+        .register_get("created_at", |l: &mut Link| l.created_at.to_rfc3339())
+        .register_get("age_days", |l: &mut Link| {
+            (Utc::now() - l.created_at).num_days()
+        })
+        .register_get("opened", |l: &mut Link| l.opened_at.is_some())
+        .register_get("note_count", |l: &mut Link| l.notes.len() as i64)
+        .register_get("notes", |l: &mut Link| {
+            l.notes
+                .iter()
+                .map(|n| Dynamic::from(n.body.clone()))
+                .collect::<Array>()
+        })
+        .register_fn("has_tag", |l: &mut Link, tag: &str| {
+            l.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+        })
+        .register_fn("add_tag", |l: &mut Link, tag: &str| l.add_tag(tag))
+        .register_fn("remove_tag", |l: &mut Link, tag: &str| l.remove_tag(tag))
+        .register_fn("add_note", |l: &mut Link, body: &str| {
+            l.add_note(Note::new(body))
+        })
+        .register_fn("to_string", |l: &mut Link| {
+            format!("{} <{}>", l.title, l.url)
+        });
+}
+
+fn strings(values: &[String]) -> Array {
+    values.iter().cloned().map(Dynamic::from).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<Link> {
+        let mut a = Link::new("https://news.example.com/1");
+        a.set_title("First");
+        a.add_tag("to-read");
+        let mut b = Link::new("https://blog.example.org/2");
+        b.set_title("Second");
+        b.add_tag("to-read");
+        vec![a, b]
+    }
+
+    #[test]
+    fn test_script_collects_changes() {
+        let links = sample();
+        let script = r#"
+            for link in tagged("to-read") {
+                if link.domain == "news.example.com" {
+                    link.remove_tag("to-read");
+                    link.add_tag(args[0]);
+                    save(link);
+                } else {
+                    delete(link);
+                }
+            }
+        "#;
+        let changes = run_script(script, links.clone(), vec!["news".into()], |_| {}).unwrap();
+
+        assert_eq!(changes.updates.len(), 1);
+        assert_eq!(changes.updates[0].id, links[0].id);
+        assert_eq!(changes.updates[0].tags, vec!["news"]);
+        assert_eq!(changes.deletes, vec![links[1].id]);
+    }
+
+    #[test]
+    fn test_script_print_and_errors() {
+        let printed = Rc::new(RefCell::new(Vec::new()));
+        let sink = Rc::clone(&printed);
+        let changes = run_script(
+            r#"for link in search("second") { print(link.title + "\t" + link.url); }"#,
+            sample(),
+            Vec::new(),
+            move |line| sink.borrow_mut().push(line.to_string()),
+        )
+        .unwrap();
+        assert!(changes.is_empty());
+        assert_eq!(
+            *printed.borrow(),
+            vec!["Second\thttps://blog.example.org/2".to_string()]
+        );
+
+        assert!(run_script("import \"other\" as o;", sample(), Vec::new(), |_| {}).is_err());
+        assert!(run_script(
+            "save(links()[0]); undefined()",
+            sample(),
+            Vec::new(),
+            |_| {}
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,361 @@
+//! Aligned, width-aware text for human output
+//!
+//! Tables size each column to its contents. When a table is wider than the
+//! terminal, its flexible columns (titles, URLs) give up space and their
+//! cells are truncated, so every row stays on one line. Longer free text is
+//! word-wrapped instead. Widths are measured in terminal cells, so wide
+//! characters line up too.
+//!
+//! Colors are only used on a terminal, and never with `NO_COLOR` set
+//! (<https://no-color.org>) or `TERM=dumb`.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::i18n::t;
+
+/// Width used when it can't be read from the terminal (e.g. when piped)
+const DEFAULT_WIDTH: usize = 100;
+
+/// Flexible columns aren't narrowed below this
+const MIN_FLEX_WIDTH: usize = 12;
+
+/// Space between columns
+const GAP: &str = "  ";
+
+/// The terminal's width in cells (`COLUMNS` overrides it)
+pub fn terminal_width() -> usize {
+    let columns = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse::<usize>().ok())
+        .filter(|&c| c > 0);
+    if let Some(columns) = columns {
+        return columns;
+    }
+    if atty::is(atty::Stream::Stdout) {
+        if let Ok((columns, _)) = crossterm::terminal::size() {
+            if columns > 0 {
+                return usize::from(columns);
+            }
+        }
+    }
+    DEFAULT_WIDTH
+}
+
+/// Whether output should be colored
+pub fn colors_enabled() -> bool {
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    let dumb = std::env::var("TERM").is_ok_and(|t| t == "dumb");
+    !no_color && !dumb && atty::is(atty::Stream::Stdout)
+}
+
+/// A text style
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Bold,
+    Dim,
+    Red,
+    Yellow,
+    Cyan,
+}
+
+impl Color {
+    fn code(self) -> &'static str {
+        match self {
+            Color::Bold => "1",
+            Color::Dim => "2",
+            Color::Red => "31",
+            Color::Yellow => "33",
+            Color::Cyan => "36",
+        }
+    }
+}
+
+/// `text` in a color, if colors are on
+pub fn paint(text: &str, color: Color, colors: bool) -> String {
+    if colors && !text.trim().is_empty() {
+        format!("\x1b[{}m{}\x1b[0m", color.code(), text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Cut `s` to at most `width` cells, ending in "..." if it was cut
+pub fn truncate(s: &str, width: usize) -> String {
+    if s.width() <= width {
+        return s.to_string();
+    }
+    if width <= 3 {
+        return ".".repeat(width);
+    }
+
+    let mut truncated = String::new();
+    let mut used = 0;
+    for grapheme in s.graphemes(true) {
+        let w = grapheme.width();
+        if used + w > width - 3 {
+            break;
+        }
+        truncated.push_str(grapheme);
+        used += w;
+    }
+    truncated.truncate(truncated.trim_end().len());
+    truncated.push_str("...");
+    truncated
+}
+
+/// Word-wrap `s` into lines at most `width` cells wide
+///
+/// Line breaks in `s` are kept; words longer than a line are split.
+pub fn wrap(s: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+
+    for paragraph in s.lines() {
+        let mut line = String::new();
+        let mut used = 0;
+        for word in paragraph.split_whitespace() {
+            let w = word.width();
+            if used > 0 && used + 1 + w > width {
+                lines.push(std::mem::take(&mut line));
+                used = 0;
+            }
+            if w > width {
+                for grapheme in word.graphemes(true) {
+                    let gw = grapheme.width();
+                    if used > 0 && used + gw > width {
+                        lines.push(std::mem::take(&mut line));
+                        used = 0;
+                    }
+                    line.push_str(grapheme);
+                    used += gw;
+                }
+                continue;
+            }
+            if used > 0 {
+                line.push(' ');
+                used += 1;
+            }
+            line.push_str(word);
+            used += w;
+        }
+        lines.push(line);
+    }
+
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines
+}
+
+/// Pad `s` with spaces to `width` cells
+fn pad(s: &str, width: usize, right: bool) -> String {
+    let padding = " ".repeat(width.saturating_sub(s.width()));
+    if right {
+        format!("{}{}", padding, s)
+    } else {
+        format!("{}{}", s, padding)
+    }
+}
+
+/// A table column
+#[derive(Debug, Clone)]
+pub struct Column {
+    header: &'static str,
+    right: bool,
+    flex: bool,
+    color: Option<Color>,
+}
+
+impl Column {
+    /// A column headed by `header`, translated (see [`crate::i18n`])
+    pub fn new(header: &'static str) -> Self {
+        Self {
+            header: t(header),
+            right: false,
+            flex: false,
+            color: None,
+        }
+    }
+
+    /// Align cells to the right (for numbers)
+    pub fn right(mut self) -> Self {
+        self.right = true;
+        self
+    }
+
+    /// Let the column shrink, truncating its cells, to fit the terminal
+    pub fn flex(mut self) -> Self {
+        self.flex = true;
+        self
+    }
+
+    /// Color every cell in the column
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+/// Rows of cells under column headers
+#[derive(Debug, Clone)]
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(columns: Vec<Column>) -> Self {
+        Self {
+            columns,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Add a row, one cell per column
+    pub fn row(&mut self, cells: Vec<String>) {
+        self.rows.push(cells);
+    }
+
+    /// Lay the table out to fit in `width` cells
+    ///
+    /// Columns with no text in any row are left out.
+    pub fn render(&self, width: usize, colors: bool) -> Vec<String> {
+        let cell = |row: &Vec<String>, i: usize| row.get(i).map(String::as_str).unwrap_or("");
+        let shown: Vec<usize> = (0..self.columns.len())
+            .filter(|&i| self.rows.iter().any(|row| !cell(row, i).is_empty()))
+            .collect();
+        if shown.is_empty() {
+            return Vec::new();
+        }
+
+        let mut widths: Vec<usize> = shown
+            .iter()
+            .map(|&i| {
+                self.rows
+                    .iter()
+                    .map(|row| cell(row, i).width())
+                    .chain([self.columns[i].header.width()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        // Narrow the widest flexible column a cell at a time until it fits
+        let total =
+            |widths: &[usize]| widths.iter().sum::<usize>() + GAP.len() * (widths.len() - 1);
+        while total(&widths) > width {
+            let widest = (0..shown.len())
+                .filter(|&j| self.columns[shown[j]].flex && widths[j] > MIN_FLEX_WIDTH)
+                .max_by_key(|&j| widths[j]);
+            match widest {
+                Some(j) => widths[j] -= 1,
+                None => break,
+            }
+        }
+
+        let line = |cells: Vec<String>, header: bool| {
+            let last = shown.len() - 1;
+            let parts: Vec<String> = shown
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(j, (&i, &w))| {
+                    let column = &self.columns[i];
+                    let text = truncate(&cells[j], w);
+                    // No trailing spaces after the last column
+                    let text = if j == last && !column.right {
+                        text
+                    } else {
+                        pad(&text, w, column.right)
+                    };
+                    let color = if header {
+                        Some(Color::Bold)
+                    } else {
+                        column.color
+                    };
+                    match color {
+                        Some(color) => paint(&text, color, colors),
+                        None => text,
+                    }
+                })
+                .collect();
+            parts.join(GAP).trim_end().to_string()
+        };
+
+        let mut lines = Vec::with_capacity(self.rows.len() + 1);
+        if shown.iter().any(|&i| !self.columns[i].header.is_empty()) {
+            let headers = shown
+                .iter()
+                .map(|&i| self.columns[i].header.to_string())
+                .collect();
+            lines.push(line(headers, true));
+        }
+        for row in &self.rows {
+            let cells = shown.iter().map(|&i| cell(row, i).to_string()).collect();
+            lines.push(line(cells, false));
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_counts_cells() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("this is a long string", 10), "this is...");
+        // Wide characters take two cells each, and aren't split
+        assert_eq!(truncate("日本語のタイトル", 9), "日本語...");
+        assert_eq!(truncate("abc", 2), "..");
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(
+            wrap("the quick brown fox jumps", 10),
+            vec!["the quick", "brown fox", "jumps"]
+        );
+        assert_eq!(wrap("one\n\ntwo", 10), vec!["one", "", "two"]);
+        assert_eq!(wrap("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(wrap("", 10), vec![""]);
+    }
+
+    #[test]
+    fn test_table_fits_width() {
+        let mut table = Table::new(vec![
+            Column::new("ID"),
+            Column::new("TITLE").flex(),
+            Column::new("NOTES").right(),
+            Column::new("FLAGS"),
+        ]);
+        table.row(vec![
+            "a1b2c3d4".into(),
+            "A rather long title that won't fit".into(),
+            "3".into(),
+            String::new(),
+        ]);
+        table.row(vec![
+            "e5f6a7b8".into(),
+            "Short".into(),
+            String::new(),
+            String::new(),
+        ]);
+
+        let lines = table.render(40, false);
+        assert_eq!(
+            lines,
+            vec![
+                "ID        TITLE                    NOTES",
+                "a1b2c3d4  A rather long title...       3",
+                "e5f6a7b8  Short",
+            ]
+        );
+        assert!(lines.iter().all(|l| l.width() <= 40));
+
+        // Wide enough: nothing is cut
+        assert!(table.render(80, false)[1].contains("won't fit"));
+    }
+}
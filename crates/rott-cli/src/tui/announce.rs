@@ -0,0 +1,54 @@
+//! Plain-text announcements for screen readers
+//!
+//! When `announce_path` is configured, the TUI writes one line per state
+//! change (focus moves, selections, status messages, errors) to that file.
+//! Pointing it at a FIFO lets a screen reader or speech tool follow along:
+//!
+//! ```text
+//! mkfifo ~/.rott-announce
+//! rott config set announce_path ~/.rott-announce
+//! tail -f ~/.rott-announce | espeak   # in another terminal
+//! ```
+//!
+//! Writes happen on a background thread, so a FIFO with no reader never
+//! blocks the interface.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+
+/// Sends announcements to a file or FIFO
+pub struct Announcer {
+    tx: Sender<String>,
+}
+
+impl Announcer {
+    /// Start writing announcements to `path`
+    pub fn new(path: PathBuf) -> Self {
+        let (tx, rx) = mpsc::channel::<String>();
+
+        thread::spawn(move || {
+            // Opening a FIFO blocks until a reader appears
+            let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&path) else {
+                return;
+            };
+            for message in rx {
+                if writeln!(file, "{}", message)
+                    .and_then(|_| file.flush())
+                    .is_err()
+                {
+                    return;
+                }
+            }
+        });
+
+        Self { tx }
+    }
+
+    /// Queue an announcement
+    pub fn announce(&self, message: &str) {
+        let _ = self.tx.send(message.to_string());
+    }
+}
@@ -1,11 +1,48 @@
 //! Application state and logic
 
-use rott_core::{Link, Note, Store};
-use std::process::{Command, Stdio};
+use chrono::Local;
+use crossterm::event::KeyCode;
+use rott_core::attachments::AttachmentStore;
+use rott_core::desktop::SystemClipboard;
+use rott_core::fuzzy::fuzzy_match_link;
+use rott_core::health::needs_attention;
+use rott_core::language::{language_counts, language_name};
+use rott_core::lock::verify_passphrase;
+use rott_core::openers::Openers;
+use rott_core::query::SearchQuery;
+use rott_core::reading::{ReadAction, ReadingSession, SessionLog};
+use rott_core::sections::{is_long, NoteSections};
+use rott_core::sync::{LastSync, PeerActivity, SyncProgress};
+use rott_core::urls::url_strs;
+use rott_core::{collections, Identity, Link, LinkSort, LockSession, Note, NotesPage, Store};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
 
 // Re-export UrlMetadata from crate's metadata module
 pub use crate::metadata::UrlMetadata;
 
+use crate::content;
+use crate::i18n::{t, tf};
+
+use super::announce::Announcer;
+use super::grouping::{build_rows, group_name, sort_into_groups, sort_label, GroupBy, ListRow};
+use super::help::HelpView;
+use super::input::TextInput;
+use super::macros::Macros;
+use super::reader::Reader;
+use super::tour::{Tour, TourKey};
+
+/// Notes loaded into the detail pane at a time
+pub const NOTES_PAGE_SIZE: usize = 50;
+
+/// Links loaded into the Items pane at a time, for lists of every link
+pub const LINKS_PAGE_SIZE: usize = 200;
+
+/// How close the selection gets to the last loaded link before the next
+/// page is loaded
+const LINKS_PREFETCH: usize = 20;
+
 /// Input mode for the application
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InputMode {
@@ -28,6 +65,10 @@ pub enum CommandType {
     Tag,
     /// Add note to selected link
     Note,
+    /// Reply to the selected note
+    Reply,
+    /// Edit the selected note
+    EditNote,
     /// Edit selected link
     Edit,
 }
@@ -66,10 +107,42 @@ pub enum Filter {
     Favorites,
     Recent,
     Untagged,
+    /// Links never opened on any synced device
+    Unread,
+    /// Unread links in one language (a reading queue; listed under Unread
+    /// when links are in more than one language)
+    UnreadIn(String),
+    /// Links with a low health score, worst first
+    NeedsAttention,
+    /// Links whose expiry date has passed
+    Expired,
     /// The "By Tag..." accordion header
     TagsHeader,
     /// An individual tag filter
     ByTag(String),
+    /// The "By Author..." accordion header
+    AuthorsHeader,
+    /// An individual author filter
+    ByAuthor(String),
+}
+
+impl Filter {
+    /// Plain-text name of the filter (for announcements)
+    pub fn label(&self) -> String {
+        match self {
+            Filter::Favorites => "Favorites".to_string(),
+            Filter::Recent => "Recent".to_string(),
+            Filter::Untagged => "Untagged".to_string(),
+            Filter::Unread => "Unread".to_string(),
+            Filter::UnreadIn(language) => format!("Unread in {}", language_name(language)),
+            Filter::NeedsAttention => "Needs Attention".to_string(),
+            Filter::Expired => "Expired".to_string(),
+            Filter::TagsHeader => "By Tag".to_string(),
+            Filter::ByTag(tag) => format!("Tag {}", tag),
+            Filter::AuthorsHeader => "By Author".to_string(),
+            Filter::ByAuthor(author) => format!("Author {}", author),
+        }
+    }
 }
 
 /// Application state
@@ -80,10 +153,8 @@ pub struct App {
     pub input_mode: InputMode,
     /// Type of command being entered
     pub command_type: Option<CommandType>,
-    /// Command input buffer
-    pub command_input: String,
-    /// Cursor position in command input
-    pub command_cursor: usize,
+    /// Command/filter input line
+    pub command_input: TextInput,
     /// Which pane has focus
     pub active_pane: ActivePane,
     /// Available filters (includes expanded tags)
@@ -94,28 +165,90 @@ pub struct App {
     pub tags_expanded: bool,
     /// All available tags
     pub all_tags: Vec<String>,
+    /// Whether the "By Author..." accordion is expanded
+    pub authors_expanded: bool,
+    /// All authors, alphabetically
+    pub all_authors: Vec<String>,
     /// All links (unfiltered, for search)
     pub all_links: Vec<Link>,
+    /// Languages with a reading queue under "Unread", most common first
+    pub all_languages: Vec<String>,
     /// Current list of links (filtered)
     pub links: Vec<Link>,
+    /// Number of links in the list when it's loaded a page at a time (see
+    /// [`App::load_more_links`]); None when it's loaded at once
+    pub links_total: Option<usize>,
     /// Currently selected link index
     pub link_index: usize,
+    /// How the Items pane groups links
+    pub group_by: GroupBy,
+    /// Order of the links in the Items pane (`o` cycles through them)
+    pub sort: LinkSort,
+    /// Names of collapsed groups
+    collapsed_groups: HashSet<String>,
+    /// Rows shown in the Items pane (group headers and links)
+    pub rows: Vec<ListRow>,
+    /// Currently selected row in the Items pane
+    pub row_index: usize,
     /// Status message to display temporarily
     pub status_message: Option<String>,
-    /// Last deleted link (for undo)
-    pub deleted_link: Option<Link>,
     /// Filter text for real-time filtering
     pub filter_text: String,
+    /// Whether the realtime filter matches fuzzily and ranks its results
+    pub fuzzy_filter: bool,
+    /// Title characters matched by the fuzzy filter, by link (for highlighting)
+    pub filter_highlights: HashMap<Uuid, Vec<usize>>,
+    /// The note each link was found by, from the filter or `:search` (for
+    /// highlighting in the Detail pane)
+    pub note_hits: HashMap<Uuid, Uuid>,
     /// Whether we're currently adding a link (async operation)
     pub is_loading: bool,
     /// Scroll offset for detail pane
     pub detail_scroll: u16,
+    /// Selected note of the current link (Detail pane)
+    pub note_index: usize,
+    /// URL focused within the selected note's body (Detail pane)
+    pub url_index: Option<usize>,
+    /// Section focused within the selected note, if it's long (Detail pane)
+    pub section_index: Option<usize>,
+    /// Sections expanded in long notes, by note and section index
+    pub expanded_sections: HashSet<(Uuid, usize)>,
+    /// Notes longer than this many bytes are shown in sections (0: never)
+    pub note_section_threshold: usize,
+    /// Commands links are opened with (the browser unless configured)
+    pub openers: Openers,
+    /// Where attachments (screenshots, archived pages) are stored
+    pub attachments: AttachmentStore,
+    /// Notes of the current link loaded so far, in the order shown (loaded
+    /// a page at a time, see [`App::load_detail_notes`])
+    pub detail_notes: Option<NotesPage>,
+    /// Archived page text of the current link, with the hash of the
+    /// attachment it was read from (see [`App::load_archived_content`])
+    pub archived_content: Option<(String, String)>,
+    /// The current link's archived text, while it's open in the reader
+    pub reader: Option<Reader>,
     /// When the status message was set (for auto-dismiss)
     pub status_message_time: Option<std::time::Instant>,
     /// Whether help overlay is visible
     pub show_help: bool,
+    /// Scroll position and search of the help overlay
+    pub help: HelpView,
+    /// Guided tour, while it's shown
+    pub tour: Option<Tour>,
+    /// Reading session in progress (`:read`)
+    pub reading: Option<ReadingSession>,
+    /// Tags bound to the keys 1-9 while triaging untagged links (`:triage`)
+    pub triage: Option<Vec<String>>,
+    /// Description of the tag being filtered by, shown above its links
+    pub tag_description: Option<String>,
     /// Sync status indicator
     pub sync_status: SyncIndicator,
+    /// Other devices syncing the collection, while connected
+    pub peers: PeerActivity,
+    /// What the exchange in progress has carried so far
+    pub sync_progress: Option<SyncProgress>,
+    /// When this device last finished syncing (kept across restarts)
+    pub last_sync: Option<LastSync>,
     /// Pending 'g' keypress for gg sequence (with timestamp)
     pub pending_g: Option<std::time::Instant>,
     /// Error message to display in modal
@@ -124,11 +257,39 @@ pub struct App {
     pub show_device_panel: bool,
     /// Device info for display in settings panel
     pub device_info: DeviceInfo,
+    /// Whether the privacy lock screen is showing (hides all data)
+    pub locked: bool,
+    /// Passphrase being typed on the lock screen
+    pub lock_input: String,
+    /// Error shown on the lock screen after a wrong passphrase
+    pub lock_error: Option<String>,
+    /// When the user last pressed a key (for idle auto-lock)
+    pub last_activity: Instant,
+    /// Privacy lock passphrase hash (None if the lock is disabled)
+    lock_hash: Option<String>,
+    /// Idle time before the privacy lock re-engages
+    lock_timeout: Duration,
+    /// Unlock session shared with the CLI
+    lock_session: LockSession,
+    /// Accessibility mode: text markers instead of color-only cues
+    pub accessible: bool,
+    /// Whether opens are recorded (affects health scores)
+    pub record_opens: bool,
+    /// Where plain-text announcements go (None if not configured)
+    announcer: Option<Announcer>,
+    /// The last focus description announced
+    last_focus: Option<String>,
+    /// System clipboard, connected on first use and kept for the session
+    clipboard: Option<SystemClipboard>,
+    /// Keyboard macro registers and recording state
+    pub macros: Macros,
 }
 
 /// Device information for settings panel
 #[derive(Debug, Clone)]
 pub struct DeviceInfo {
+    /// Active collection
+    pub collection: String,
     /// Root document ID
     pub root_id: String,
     /// Sync server URL (if configured)
@@ -154,54 +315,333 @@ impl App {
     /// Create a new app with data from store
     pub fn new(store: &Store) -> anyhow::Result<Self> {
         let all_tags = store.get_all_tags()?;
-        let all_links = store.get_all_links()?;
-        let links = all_links.clone();
+        let all_authors = author_names(store)?;
+        let all_links = store.get_all_links(LinkSort::default())?;
+        let all_languages = reading_languages(&all_links);
+        let sort = store.config().default_sort;
+        let page = store.get_links_page(0, LINKS_PAGE_SIZE, sort)?;
+        let links_total = Some(page.total);
+        let links = page.links;
+        let rows = (0..links.len()).map(ListRow::Link).collect();
 
         // Build initial filters list
         let mut filters = vec![Filter::Favorites, Filter::Recent, Filter::Untagged];
+        filters.extend(unread_filters(&all_languages));
+        filters.push(Filter::NeedsAttention);
+        filters.push(Filter::Expired);
         if !all_tags.is_empty() {
             filters.push(Filter::TagsHeader);
         }
+        if !all_authors.is_empty() {
+            filters.push(Filter::AuthorsHeader);
+        }
 
         Ok(Self {
             should_quit: false,
             input_mode: InputMode::Normal,
             command_type: None,
-            command_input: String::new(),
-            command_cursor: 0,
+            command_input: TextInput::new(),
             active_pane: ActivePane::Items,
             filters,
             filter_index: 0, // Start on "Favorites"
             tags_expanded: false,
             all_tags,
+            authors_expanded: false,
+            all_authors,
             all_links,
+            all_languages,
             links,
+            links_total,
             link_index: 0,
+            group_by: GroupBy::None,
+            sort,
+            collapsed_groups: HashSet::new(),
+            rows,
+            row_index: 0,
             status_message: None,
-            deleted_link: None,
             filter_text: String::new(),
+            fuzzy_filter: store.config().fuzzy_filter,
+            filter_highlights: HashMap::new(),
+            note_hits: HashMap::new(),
             is_loading: false,
             detail_scroll: 0,
+            note_index: 0,
+            url_index: None,
+            section_index: None,
+            expanded_sections: HashSet::new(),
+            note_section_threshold: store.config().note_section_threshold,
+            openers: Openers::from_config(store.config()),
+            attachments: AttachmentStore::from_config(store.config()),
+            detail_notes: None,
+            archived_content: None,
+            reader: None,
             status_message_time: None,
             show_help: false,
+            help: HelpView::default(),
+            tour: None,
+            reading: None,
+            triage: None,
+            tag_description: None,
             sync_status: if store.config().sync_enabled {
                 SyncIndicator::Syncing
             } else {
                 SyncIndicator::Disabled
             },
+            peers: PeerActivity::default(),
+            sync_progress: None,
+            // A damaged record only loses when this device last synced
+            last_sync: LastSync::load(&store.config().last_sync_path()).unwrap_or_default(),
             pending_g: None,
             error_message: None,
             show_device_panel: false,
             device_info: DeviceInfo {
+                collection: store.config().collection_name().to_string(),
                 root_id: store.root_id().to_string(),
                 sync_url: store.config().sync_url.clone(),
             },
+            locked: store.config().is_lock_enabled()
+                && !LockSession::new(store.config()).is_unlocked(),
+            lock_input: String::new(),
+            lock_error: None,
+            last_activity: Instant::now(),
+            lock_hash: store.config().lock_hash.clone(),
+            lock_timeout: Duration::from_secs(store.config().lock_timeout_minutes * 60),
+            lock_session: LockSession::new(store.config()),
+            accessible: store.config().accessible_mode,
+            record_opens: store.config().record_opens,
+            announcer: store.config().announce_path.clone().map(Announcer::new),
+            last_focus: None,
+            clipboard: None,
+            macros: Macros::new(),
         })
     }
 
+    /// Switch to another collection, or list them if no name is given
+    ///
+    /// The switch lasts for this session; `rott collection switch` sets the
+    /// collection the TUI starts in.
+    pub fn switch_collection(&mut self, store: &mut Store, name: &str) -> anyhow::Result<()> {
+        if name.is_empty() {
+            let names: Vec<String> = collections::list(store.config())?
+                .into_iter()
+                .map(|c| {
+                    if c.active {
+                        format!("*{}", c.name)
+                    } else {
+                        c.name
+                    }
+                })
+                .collect();
+            self.set_status(tf("Collections: {}", &[&names.join(", ")]));
+            return Ok(());
+        }
+        if name == store.config().collection_name() {
+            self.set_status(tf("Already in collection {}", &[&name]));
+            return Ok(());
+        }
+
+        let config = store.config().with_collection(name);
+        let identity = Identity::with_config(config.clone());
+        if !identity.is_initialized() {
+            self.set_status(format!(
+                "No collection '{}' (rott collection create {})",
+                name, name
+            ));
+            return Ok(());
+        }
+        if !identity.has_local_document() {
+            self.set_status(format!(
+                "Collection '{}' hasn't synced to this device yet",
+                name
+            ));
+            return Ok(());
+        }
+
+        // A reading session belongs to the collection being left
+        if self.reading.is_some() {
+            self.end_reading(store)?;
+        }
+        *store = Store::open_with_config(config)?;
+        self.triage = None;
+        self.filter_text.clear();
+        self.filter_index = 0;
+        self.device_info = DeviceInfo {
+            collection: store.config().collection_name().to_string(),
+            root_id: store.root_id().to_string(),
+            sync_url: store.config().sync_url.clone(),
+        };
+        self.refresh(store)?;
+        self.set_status(tf("Switched to collection {}", &[&name]));
+        Ok(())
+    }
+
+    /// Whether a privacy lock passphrase is configured
+    pub fn is_lock_enabled(&self) -> bool {
+        self.lock_hash.is_some()
+    }
+
+    /// Show the lock screen and end the unlock session
+    pub fn lock(&mut self) {
+        self.locked = true;
+        self.lock_input.clear();
+        self.lock_error = None;
+        self.show_help = false;
+        self.show_device_panel = false;
+        self.tour = None;
+        self.exit_input_mode();
+        let _ = self.lock_session.lock();
+    }
+
+    /// Try to unlock with the passphrase typed on the lock screen
+    pub fn try_unlock(&mut self) {
+        let hash = self.lock_hash.as_deref().unwrap_or("");
+        if verify_passphrase(&self.lock_input, hash) {
+            self.locked = false;
+            self.lock_error = None;
+            self.last_activity = Instant::now();
+            let _ = self.lock_session.unlock();
+        } else {
+            self.lock_error = Some("Incorrect passphrase".to_string());
+        }
+        self.lock_input.clear();
+    }
+
+    /// Copy text to the system clipboard
+    ///
+    /// Returns false if no clipboard is available.
+    pub fn copy_to_clipboard(&mut self, text: &str) -> bool {
+        if self.clipboard.is_none() {
+            self.clipboard = SystemClipboard::new().ok();
+        }
+        self.clipboard
+            .as_mut()
+            .is_some_and(|clipboard| clipboard.copy(text).is_ok())
+    }
+
+    /// Send a plain-text announcement (if announcements are configured)
+    pub fn announce(&self, message: &str) {
+        if let Some(ref announcer) = self.announcer {
+            announcer.announce(message);
+        }
+    }
+
+    /// Announce what has focus, if it changed since the last call
+    pub fn announce_focus_change(&mut self) {
+        if self.announcer.is_none() {
+            return;
+        }
+        let description = self.focus_description();
+        if self.last_focus.as_deref() != Some(description.as_str()) {
+            self.announce(&description);
+            self.last_focus = Some(description);
+        }
+    }
+
+    /// Describe what currently has focus, in plain text
+    pub fn focus_description(&self) -> String {
+        if self.locked {
+            return match self.lock_error {
+                Some(ref error) => format!("Locked. {}. Type passphrase and press Enter", error),
+                None => "Locked. Type passphrase and press Enter".to_string(),
+            };
+        }
+        if let Some(ref tour) = self.tour {
+            let step = tour.current();
+            return tf(
+                "Tour step {}: {}. {}",
+                &[&tour.number(), &t(step.title), &t(step.text)],
+            );
+        }
+        if self.show_help {
+            return "Help".to_string();
+        }
+        if self.show_device_panel {
+            return "Device panel".to_string();
+        }
+
+        match self.input_mode {
+            InputMode::Command => "Command input".to_string(),
+            InputMode::Filter => format!("Filter, {} matches", self.links.len()),
+            InputMode::Normal => match self.active_pane {
+                ActivePane::Filters => {
+                    let label = match self.current_filter() {
+                        Some(Filter::TagsHeader) if self.tags_expanded => {
+                            "By Tag, expanded".to_string()
+                        }
+                        Some(Filter::TagsHeader) => "By Tag, collapsed".to_string(),
+                        Some(Filter::AuthorsHeader) if self.authors_expanded => {
+                            "By Author, expanded".to_string()
+                        }
+                        Some(Filter::AuthorsHeader) => "By Author, collapsed".to_string(),
+                        Some(filter) => filter.label(),
+                        None => "none".to_string(),
+                    };
+                    format!(
+                        "Filters pane: {}, {} of {}",
+                        label,
+                        self.filter_index + 1,
+                        self.filters.len()
+                    )
+                }
+                ActivePane::Items => match (self.current_link(), self.selected_header()) {
+                    (_, Some((name, count, collapsed))) => format!(
+                        "Links pane: group {}, {} links, {}",
+                        name,
+                        count,
+                        if collapsed { "collapsed" } else { "expanded" }
+                    ),
+                    (Some(link), None) => format!(
+                        "Links pane: {}, {} of {}{}",
+                        link.title,
+                        self.link_index + 1,
+                        self.links.len(),
+                        if link.content_changed {
+                            ", content changed"
+                        } else {
+                            ""
+                        }
+                    ),
+                    (None, None) => "Links pane: empty".to_string(),
+                },
+                ActivePane::Detail => match self.current_link() {
+                    Some(link) => format!(
+                        "Details pane: {}, {} tags, {} notes",
+                        link.title,
+                        link.tags.len(),
+                        link.notes.len()
+                    ),
+                    None => "Details pane: nothing selected".to_string(),
+                },
+            },
+        }
+    }
+
+    /// Record a keypress, resetting the idle timer
+    ///
+    /// Also keeps the shared unlock session alive (at most once a minute).
+    pub fn record_activity(&mut self) {
+        if self.last_activity.elapsed() >= Duration::from_secs(60) {
+            let _ = self.lock_session.touch();
+        }
+        self.last_activity = Instant::now();
+    }
+
+    /// Engage the privacy lock if idle for longer than the timeout
+    pub fn check_idle_lock(&mut self) {
+        if self.is_lock_enabled()
+            && !self.locked
+            && self.last_activity.elapsed() >= self.lock_timeout
+        {
+            self.lock();
+        }
+    }
+
     /// Rebuild filters list based on expanded state
     fn rebuild_filters(&mut self) {
         let mut filters = vec![Filter::Favorites, Filter::Recent, Filter::Untagged];
+        filters.extend(unread_filters(&self.all_languages));
+        filters.push(Filter::NeedsAttention);
+        filters.push(Filter::Expired);
 
         // Only show "By Tag..." if there are tags
         if !self.all_tags.is_empty() {
@@ -214,6 +654,17 @@ impl App {
             }
         }
 
+        // Likewise "By Author..."
+        if !self.all_authors.is_empty() {
+            filters.push(Filter::AuthorsHeader);
+
+            if self.authors_expanded {
+                for author in &self.all_authors {
+                    filters.push(Filter::ByAuthor(author.clone()));
+                }
+            }
+        }
+
         self.filters = filters;
     }
 
@@ -223,6 +674,24 @@ impl App {
         self.rebuild_filters();
     }
 
+    /// Toggle the authors accordion
+    pub fn toggle_authors_accordion(&mut self) {
+        self.authors_expanded = !self.authors_expanded;
+        self.rebuild_filters();
+    }
+
+    /// Toggle the accordion under the selected filter, if it's a header
+    ///
+    /// Returns whether a header was selected.
+    pub fn toggle_selected_accordion(&mut self) -> bool {
+        match self.current_filter() {
+            Some(Filter::TagsHeader) => self.toggle_tags_accordion(),
+            Some(Filter::AuthorsHeader) => self.toggle_authors_accordion(),
+            _ => return false,
+        }
+        true
+    }
+
     /// Get the currently selected filter
     pub fn current_filter(&self) -> Option<&Filter> {
         self.filters.get(self.filter_index)
@@ -230,7 +699,9 @@ impl App {
 
     /// Set a status message (will auto-dismiss after 3 seconds)
     pub fn set_status(&mut self, message: impl Into<String>) {
-        self.status_message = Some(message.into());
+        let message = message.into();
+        self.announce(&message);
+        self.status_message = Some(message);
         self.status_message_time = Some(std::time::Instant::now());
     }
 
@@ -246,7 +717,9 @@ impl App {
 
     /// Set an error message (displayed in modal, must be dismissed)
     pub fn set_error(&mut self, message: impl Into<String>) {
-        self.error_message = Some(message.into());
+        let message = message.into();
+        self.announce(&format!("Error: {}", message));
+        self.error_message = Some(message);
     }
 
     /// Clear the error message
@@ -259,9 +732,36 @@ impl App {
         self.error_message.is_some()
     }
 
-    /// Toggle help overlay
+    /// Toggle help overlay (opening it at the top, without a search)
     pub fn toggle_help(&mut self) {
         self.show_help = !self.show_help;
+        self.help = HelpView::default();
+    }
+
+    /// Start the guided tour from its first step
+    pub fn start_tour(&mut self) {
+        self.show_help = false;
+        self.show_device_panel = false;
+        self.tour = Some(Tour::new());
+        self.focus_tour_pane();
+    }
+
+    /// Handle a key while the tour is shown
+    pub fn handle_tour_key(&mut self, code: KeyCode) {
+        let Some(tour) = self.tour.as_mut() else {
+            return;
+        };
+        match tour.handle_key(code) {
+            TourKey::Handled => self.focus_tour_pane(),
+            TourKey::Close => self.tour = None,
+        }
+    }
+
+    /// Focus the pane the current tour step describes
+    fn focus_tour_pane(&mut self) {
+        if let Some(pane) = self.tour.as_ref().and_then(|t| t.current().pane) {
+            self.active_pane = pane;
+        }
     }
 
     /// Toggle device settings panel
@@ -270,8 +770,154 @@ impl App {
     }
 
     /// Get the currently selected link
+    ///
+    /// `None` when a group header is selected.
     pub fn current_link(&self) -> Option<&Link> {
-        self.links.get(self.link_index)
+        match self.rows.get(self.row_index) {
+            Some(ListRow::Link(index)) => self.links.get(*index),
+            _ => None,
+        }
+    }
+
+    /// The selected group header as (name, link count, collapsed)
+    fn selected_header(&self) -> Option<(&str, usize, bool)> {
+        match self.rows.get(self.row_index) {
+            Some(ListRow::Header {
+                name,
+                count,
+                collapsed,
+            }) => Some((name, *count, *collapsed)),
+            _ => None,
+        }
+    }
+
+    /// Select a row in the Items pane
+    fn select_row(&mut self, row_index: usize) {
+        self.row_index = row_index;
+        if let Some(ListRow::Link(index)) = self.rows.get(row_index) {
+            self.link_index = *index;
+        }
+        self.detail_scroll = 0; // Reset scroll when changing selection
+        self.note_index = 0;
+        self.url_index = None;
+        self.section_index = None;
+    }
+
+    /// Select a link, or its group header if the group is collapsed
+    pub fn select_link(&mut self, index: usize) {
+        self.link_index = index;
+
+        let mut group_start = 0;
+        for (row_index, row) in self.rows.iter().enumerate() {
+            match row {
+                ListRow::Header {
+                    count, collapsed, ..
+                } => {
+                    if *collapsed && (group_start..group_start + count).contains(&index) {
+                        self.row_index = row_index;
+                        return;
+                    }
+                    group_start += count;
+                }
+                ListRow::Link(i) if *i == index => {
+                    self.row_index = row_index;
+                    return;
+                }
+                ListRow::Link(_) => {}
+            }
+        }
+        self.row_index = 0;
+    }
+
+    /// Rebuild the Items pane rows after the list of links changed
+    fn regroup(&mut self) {
+        let today = Local::now().date_naive();
+        sort_into_groups(&mut self.links, self.group_by, today);
+        self.rows = build_rows(&self.links, self.group_by, &self.collapsed_groups, today);
+        self.select_link(self.link_index.min(self.links.len().saturating_sub(1)));
+    }
+
+    /// Change how the Items pane is grouped, keeping the selected link
+    pub fn set_group_by(&mut self, group_by: GroupBy) {
+        let selected = self.current_link().map(|link| link.id);
+        self.group_by = group_by;
+        self.collapsed_groups.clear();
+        self.regroup();
+        if let Some(index) = selected.and_then(|id| self.links.iter().position(|l| l.id == id)) {
+            self.select_link(index);
+        }
+    }
+
+    /// Switch to the next sort order, keeping the selected link
+    pub fn cycle_sort(&mut self, store: &Store) -> anyhow::Result<()> {
+        let selected = self.current_link().map(|link| link.id);
+        self.sort = self.sort.next();
+        self.apply_filter(store)?;
+        if let Some(index) = selected.and_then(|id| self.links.iter().position(|l| l.id == id)) {
+            self.select_link(index);
+        }
+        self.set_status(tf("Sorted: {}", &[&t(sort_label(self.sort))]));
+        Ok(())
+    }
+
+    /// Name of the group at the selection (a header or a link's group)
+    fn selected_group(&self) -> Option<String> {
+        if self.group_by == GroupBy::None {
+            return None;
+        }
+        if let Some((name, _, _)) = self.selected_header() {
+            return Some(name.to_string());
+        }
+        self.current_link()
+            .map(|link| group_name(link, self.group_by, Local::now().date_naive()))
+    }
+
+    /// Collapse or expand the group at the selection
+    pub fn toggle_group(&mut self) {
+        let Some(name) = self.selected_group() else {
+            return;
+        };
+        if !self.collapsed_groups.remove(&name) {
+            self.collapsed_groups.insert(name.clone());
+        }
+        self.rebuild_rows_at_header(&name);
+    }
+
+    /// Collapse every group, or expand them all if they're all collapsed
+    pub fn toggle_all_groups(&mut self) {
+        let Some(current) = self.selected_group() else {
+            return;
+        };
+        let names: Vec<String> = self
+            .rows
+            .iter()
+            .filter_map(|row| match row {
+                ListRow::Header { name, .. } => Some(name.clone()),
+                ListRow::Link(_) => None,
+            })
+            .collect();
+
+        if names
+            .iter()
+            .all(|name| self.collapsed_groups.contains(name))
+        {
+            self.collapsed_groups.clear();
+        } else {
+            self.collapsed_groups.extend(names);
+        }
+        self.rebuild_rows_at_header(&current);
+    }
+
+    /// Rebuild rows (links are already in group order) and select a header
+    fn rebuild_rows_at_header(&mut self, name: &str) {
+        let today = Local::now().date_naive();
+        self.rows = build_rows(&self.links, self.group_by, &self.collapsed_groups, today);
+        let row = self
+            .rows
+            .iter()
+            .position(|row| matches!(row, ListRow::Header { name: n, .. } if n == name))
+            .unwrap_or(0);
+        self.select_row(row);
     }
 
     /// Move selection up in the current pane
@@ -283,9 +929,8 @@ impl App {
                 }
             }
             ActivePane::Items => {
-                if self.link_index > 0 {
-                    self.link_index -= 1;
-                    self.detail_scroll = 0; // Reset scroll when changing selection
+                if self.row_index > 0 {
+                    self.select_row(self.row_index - 1);
                 }
             }
             ActivePane::Detail => {
@@ -304,9 +949,8 @@ impl App {
                 }
             }
             ActivePane::Items => {
-                if self.link_index < self.links.len().saturating_sub(1) {
-                    self.link_index += 1;
-                    self.detail_scroll = 0; // Reset scroll when changing selection
+                if self.row_index < self.rows.len().saturating_sub(1) {
+                    self.select_row(self.row_index + 1);
                 }
             }
             ActivePane::Detail => {
@@ -323,8 +967,7 @@ impl App {
                 self.filter_index = 0;
             }
             ActivePane::Items => {
-                self.link_index = 0;
-                self.detail_scroll = 0;
+                self.select_row(0);
             }
             ActivePane::Detail => {
                 self.detail_scroll = 0;
@@ -339,8 +982,7 @@ impl App {
                 self.filter_index = self.filters.len().saturating_sub(1);
             }
             ActivePane::Items => {
-                self.link_index = self.links.len().saturating_sub(1);
-                self.detail_scroll = 0;
+                self.select_row(self.rows.len().saturating_sub(1));
             }
             ActivePane::Detail => {
                 // For detail pane, we can't easily know max scroll, so just add a large value
@@ -361,35 +1003,39 @@ impl App {
     }
 
     /// Handle Enter key in current pane
-    pub fn handle_enter(&mut self, store: &Store) -> anyhow::Result<()> {
+    pub fn handle_enter(&mut self, store: &mut Store) -> anyhow::Result<()> {
         match self.active_pane {
             ActivePane::Filters => {
-                // Check if we're on the TagsHeader
-                if let Some(Filter::TagsHeader) = self.current_filter() {
-                    self.toggle_tags_accordion();
-                } else {
+                // Headers toggle their accordion
+                if !self.toggle_selected_accordion() {
                     self.apply_filter(store)?;
                     // Auto-switch to Items pane after selecting a filter
                     self.active_pane = ActivePane::Items;
                 }
             }
             ActivePane::Items => {
-                // Open link in browser
-                if let Some(link) = self.current_link() {
-                    let url = link.url.clone();
-                    let title = link.title.clone();
-                    match open_url(&url) {
+                // Expand or collapse a group header, or open link in browser
+                if self.selected_header().is_some() {
+                    self.toggle_group();
+                } else if let Some(link) = self.current_link().cloned() {
+                    let id = link.id;
+                    match self.openers.open(&link) {
                         Ok(_) => {
-                            self.set_status(format!("Opened '{}'", title));
+                            self.set_status(tf("Opened '{}'", &[&link.title]));
+                            store.record_open(id)?;
+                            if let Some(ref mut session) = self.reading {
+                                session.record(id, ReadAction::Opened, chrono::Utc::now());
+                            }
                         }
                         Err(e) => {
-                            self.set_status(format!("Failed to open: {}", e));
+                            self.set_status(tf("Failed to open: {}", &[&e]));
                         }
                     }
                 }
             }
             ActivePane::Detail => {
-                // Could expand notes or similar
+                // Expand or collapse sections of a long note
+                self.toggle_note_section();
             }
         }
         Ok(())
@@ -398,35 +1044,74 @@ impl App {
     /// Apply the currently selected filter
     pub fn apply_filter(&mut self, store: &Store) -> anyhow::Result<()> {
         let filter = self.current_filter().cloned();
+        if !matches!(
+            filter,
+            Some(Filter::TagsHeader) | Some(Filter::AuthorsHeader)
+        ) {
+            self.tag_description = match filter {
+                Some(Filter::ByTag(ref tag)) => store.tag_description(tag)?,
+                _ => None,
+            };
+        }
 
+        let mut links_total = None;
         self.links = match filter {
+            // Empty until favorite tags are configured
             Some(Filter::Favorites) => {
-                if let Some(tag) = &store.config().favorite_tag {
-                    store.get_links_by_tag(tag)?
-                } else {
-                    // No favorite tag configured, show empty
-                    Vec::new()
-                }
+                let mut links = store.favorite_links()?;
+                self.sort.sort(&mut links);
+                links
             }
             Some(Filter::Recent) => {
-                let mut links = store.get_all_links()?;
-                links.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
-                links
+                // Every link, so read a page at a time as the list scrolls,
+                // keeping the selection loaded
+                let limit = LINKS_PAGE_SIZE.max(self.link_index + 1);
+                let page = store.get_links_page(0, limit, self.sort)?;
+                links_total = Some(page.total);
+                page.links
             }
             Some(Filter::Untagged) => {
-                let all_links = store.get_all_links()?;
+                let all_links = store.get_all_links(self.sort)?;
                 all_links
                     .into_iter()
                     .filter(|l| l.tags.is_empty())
                     .collect()
             }
-            Some(Filter::TagsHeader) => {
-                // TagsHeader doesn't filter, just toggles accordion
+            Some(Filter::Unread) => store
+                .get_all_links(self.sort)?
+                .into_iter()
+                .filter(|l| l.opened_at.is_none())
+                .collect(),
+            Some(Filter::UnreadIn(language)) => store
+                .get_all_links(self.sort)?
+                .into_iter()
+                .filter(|l| l.opened_at.is_none() && l.language.as_deref() == Some(&language))
+                .collect(),
+            Some(Filter::NeedsAttention) => {
+                let opens_tracked = store.config().record_opens;
+                needs_attention(
+                    store.get_all_links(LinkSort::default())?,
+                    chrono::Utc::now(),
+                    opens_tracked,
+                )
+                .into_iter()
+                .map(|(link, _)| link)
+                .collect()
+            }
+            Some(Filter::Expired) => store.expired_links(chrono::Utc::now())?,
+            Some(Filter::TagsHeader) | Some(Filter::AuthorsHeader) => {
+                // Headers don't filter, just toggle their accordion
                 return Ok(());
             }
-            Some(Filter::ByTag(tag)) => store.get_links_by_tag(&tag)?,
-            None => store.get_all_links()?,
+            Some(Filter::ByTag(tag)) => store.get_links_by_tag(&tag, self.sort)?,
+            Some(Filter::ByAuthor(author)) => {
+                let mut links = store.get_links_by_author(&author)?;
+                self.sort.sort(&mut links);
+                links
+            }
+            None => store.get_all_links(self.sort)?,
         };
+        self.links_total = links_total;
 
         // Clamp link selection to new list bounds (preserve position when possible)
         if self.links.is_empty() {
@@ -434,6 +1119,7 @@ impl App {
         } else {
             self.link_index = self.link_index.min(self.links.len() - 1);
         }
+        self.regroup();
 
         Ok(())
     }
@@ -441,9 +1127,194 @@ impl App {
     /// Refresh data from store
     pub fn refresh(&mut self, store: &Store) -> anyhow::Result<()> {
         self.all_tags = store.get_all_tags()?;
-        self.all_links = store.get_all_links()?;
+        self.all_authors = author_names(store)?;
+        self.all_links = store.get_all_links(LinkSort::default())?;
+        self.all_languages = reading_languages(&self.all_links);
         self.rebuild_filters();
         self.apply_filter(store)?;
+
+        // Reload the notes shown, keeping as many pages as were loaded
+        let shown = self
+            .detail_notes
+            .take()
+            .filter(|page| Some(page.link_id) == self.current_link().map(|l| l.id))
+            .map_or(NOTES_PAGE_SIZE, |page| {
+                page.notes.len().max(NOTES_PAGE_SIZE)
+            });
+        self.fetch_detail_notes(store, shown)?;
+        Ok(())
+    }
+
+    /// Load the next page of links when the selection nears the end of
+    /// those loaded (for lists loaded a page at a time)
+    ///
+    /// Called before each draw, like [`App::load_detail_notes`].
+    pub fn load_more_links(&mut self, store: &Store) {
+        let Some(total) = self.links_total else {
+            return;
+        };
+        let loaded = self.links.len();
+        if loaded >= total || self.link_index + LINKS_PREFETCH < loaded {
+            return;
+        }
+        match store.get_links_page(loaded, LINKS_PAGE_SIZE, self.sort) {
+            Ok(page) => {
+                let selected = self.current_link().map(|link| link.id);
+                self.links_total = Some(page.total);
+                self.links.extend(page.links);
+                self.regroup();
+                if let Some(index) =
+                    selected.and_then(|id| self.links.iter().position(|l| l.id == id))
+                {
+                    self.select_link(index);
+                }
+            }
+            Err(e) => {
+                // Shown once; the list stays as loaded
+                self.links_total = None;
+                self.set_error(format!("Failed to load links: {}", e));
+            }
+        }
+    }
+
+    /// Load the first page of the current link's notes, unless it's loaded
+    ///
+    /// Called before each draw; notes are read only when the selection
+    /// moves to another link.
+    pub fn load_detail_notes(&mut self, store: &Store) {
+        let current = self.current_link().map(|l| l.id);
+        if self.detail_notes.as_ref().map(|page| page.link_id) == current && current.is_some() {
+            return;
+        }
+        if let Err(e) = self.fetch_detail_notes(store, NOTES_PAGE_SIZE) {
+            self.set_error(format!("Failed to load notes: {}", e));
+        }
+        // Start at the note the link was found by
+        if let Some(note_id) = current.and_then(|id| self.note_hits.get(&id)).copied() {
+            self.select_note_by_id(note_id);
+        }
+    }
+
+    /// Read the current link's archived page text, unless it's read already
+    ///
+    /// Called before each draw, like [`App::load_detail_notes`]; the file is
+    /// read only when the selection moves to a link with other text.
+    pub fn load_archived_content(&mut self, store: &Store) {
+        let Some(link) = self.current_link() else {
+            self.archived_content = None;
+            return;
+        };
+        let Some(hash) = link.attachments.get(content::CONTENT_ATTACHMENT).cloned() else {
+            self.archived_content = None;
+            return;
+        };
+        if self.archived_content.as_ref().map(|(h, _)| h) == Some(&hash) {
+            return;
+        }
+        match content::archived_text(store.config(), link) {
+            Ok(text) => self.archived_content = text.map(|text| (hash, text)),
+            Err(e) => {
+                // Remembered, so the error is shown once
+                self.archived_content = Some((hash, String::new()));
+                self.set_error(format!("Failed to read archived text: {}", e));
+            }
+        }
+    }
+
+    /// Open the current link's archived text in the reader
+    pub fn open_reader(&mut self) {
+        let Some(link) = self.current_link() else {
+            return;
+        };
+        let text = self
+            .archived_content
+            .as_ref()
+            .filter(|(hash, _)| link.attachments.get(content::CONTENT_ATTACHMENT) == Some(hash))
+            .map(|(_, text)| text)
+            .filter(|text| !text.is_empty());
+        let Some(text) = text else {
+            self.set_status(t(
+                "No archived text (archive it with `rott link archive-content`)",
+            ));
+            return;
+        };
+        let reader = Reader::open(link, text);
+        let resumed = link
+            .read_position
+            .filter(|position| position.line > 0 && !position.is_finished());
+        self.reader = Some(reader);
+        if let Some(position) = resumed {
+            self.set_status(tf(
+                "Resumed at {}%",
+                &[&((position.progress() * 100.0).round() as usize)],
+            ));
+        }
+    }
+
+    /// Close the reader, saving where reading got to
+    ///
+    /// `width` and `height` are the reader's text size. Returns whether the
+    /// position was saved (and needs pushing).
+    pub fn close_reader(&mut self, store: &mut Store, width: usize, height: usize) -> bool {
+        let Some(reader) = self.reader.take() else {
+            return false;
+        };
+        let position = reader.position(width, height);
+        let saved = store
+            .get_link(reader.link_id)
+            .ok()
+            .flatten()
+            .and_then(|link| link.read_position);
+        if saved.is_some_and(|saved| (saved.line, saved.lines) == (position.line, position.lines))
+            || (saved.is_none() && position.line == 0)
+        {
+            return false;
+        }
+        match store.set_read_position(reader.link_id, position) {
+            Ok(recorded) => {
+                if let Err(e) = self.refresh(store) {
+                    self.set_error(format!("Failed to refresh: {}", e));
+                }
+                recorded
+            }
+            Err(e) => {
+                self.set_error(format!("Failed to save read position: {}", e));
+                false
+            }
+        }
+    }
+
+    /// Load up to `limit` of the current link's notes, replacing those loaded
+    fn fetch_detail_notes(&mut self, store: &Store, limit: usize) -> anyhow::Result<()> {
+        let Some(link_id) = self.current_link().map(|l| l.id) else {
+            self.detail_notes = None;
+            return Ok(());
+        };
+        // Remembered even if loading fails, so the error is shown once
+        self.detail_notes = Some(NotesPage {
+            link_id,
+            offset: 0,
+            notes: Vec::new(),
+            total: 0,
+        });
+        self.detail_notes = store.get_notes_page(link_id, 0, limit)?;
+        Ok(())
+    }
+
+    /// Load the next page of the current link's notes
+    pub fn load_more_notes(&mut self, store: &Store) -> anyhow::Result<()> {
+        let Some(page) = self.detail_notes.as_mut() else {
+            return Ok(());
+        };
+        if !page.has_more() {
+            self.set_status("All notes are shown");
+            return Ok(());
+        }
+        if let Some(next) = store.get_notes_page(page.link_id, page.notes.len(), NOTES_PAGE_SIZE)? {
+            page.extend(next);
+        }
+        let message = format!("Showing {} of {} notes", page.notes.len(), page.total);
+        self.set_status(message);
         Ok(())
     }
 
@@ -452,23 +1323,25 @@ impl App {
         self.input_mode = InputMode::Command;
         self.command_type = Some(cmd_type.clone());
         self.command_input.clear();
-        self.command_cursor = 0;
 
         // Pre-fill based on command type
         match cmd_type {
             CommandType::Add => {
-                self.command_input = "add ".to_string();
-                self.command_cursor = 4;
+                self.command_input.set_value("add ");
             }
             CommandType::Tag => {
                 // Pre-fill with current tags
-                if let Some(link) = self.current_link() {
-                    self.command_input = format!("tag {}", link.tags.join(", "));
-                    self.command_cursor = self.command_input.len();
-                } else {
-                    self.command_input = "tag ".to_string();
-                    self.command_cursor = 4;
-                }
+                let tags = self
+                    .current_link()
+                    .map(|link| link.tags.join(", "))
+                    .unwrap_or_default();
+                self.command_input.set_value(format!("tag {}", tags));
+            }
+            CommandType::Reply => {
+                self.command_input.set_value("reply");
+            }
+            CommandType::EditNote => {
+                self.command_input.set_value("edit-note");
             }
             CommandType::Generic => {
                 // Just the colon prefix, user types command
@@ -484,7 +1357,6 @@ impl App {
         self.input_mode = InputMode::Filter;
         self.filter_text.clear();
         self.command_input.clear();
-        self.command_cursor = 0;
     }
 
     /// Exit command/filter mode
@@ -492,121 +1364,315 @@ impl App {
         self.input_mode = InputMode::Normal;
         self.command_type = None;
         self.command_input.clear();
-        self.command_cursor = 0;
     }
 
     /// Clear filter and show all items
     pub fn clear_filter(&mut self, store: &Store) -> anyhow::Result<()> {
         self.filter_text.clear();
+        self.filter_highlights.clear();
+        self.note_hits.clear();
         self.apply_filter(store)?;
         Ok(())
     }
 
     /// Apply real-time filter to current view
+    ///
+    /// With `fuzzy_filter`, each word of the filter matches fuzzily and the
+    /// best matches come first; otherwise the text is matched as a substring.
     pub fn apply_realtime_filter(&mut self) {
+        self.filter_highlights.clear();
+        self.note_hits.clear();
         if self.filter_text.is_empty() {
             // No filter, show based on current filter selection
             return;
         }
 
-        let filter_lower = self.filter_text.to_lowercase();
-        self.links = self
+        let query = SearchQuery::parse(&self.filter_text);
+        let candidates = self
             .all_links
             .iter()
-            .filter(|link| {
-                link.title.to_lowercase().contains(&filter_lower)
+            .filter(|link| query.matches_operators(link));
+
+        if self.fuzzy_filter {
+            let terms: Vec<&str> = query.text.split_whitespace().collect();
+            let mut matches: Vec<(i32, &Link)> = Vec::new();
+            for link in candidates {
+                if let Some(m) = fuzzy_match_link(&terms, link) {
+                    if !m.title_positions.is_empty() {
+                        self.filter_highlights.insert(link.id, m.title_positions);
+                    }
+                    matches.push((m.score, link));
+                } else if let Some(note) = query.matching_note(link) {
+                    // Notes aren't matched fuzzily; a link found by one
+                    // comes after the fuzzy matches
+                    self.note_hits.insert(link.id, note.id);
+                    matches.push((i32::MIN, link));
+                }
+            }
+            // Stable, so equal scores keep their order
+            matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+            self.links = matches.into_iter().map(|(_, link)| link.clone()).collect();
+            self.links_total = None;
+        } else {
+            let filter_lower = query.text.to_lowercase();
+            let mut links = Vec::new();
+            for link in candidates {
+                let note = query.matching_note(link);
+                if let Some(note) = note {
+                    self.note_hits.insert(link.id, note.id);
+                }
+                if note.is_some()
+                    || link.title.to_lowercase().contains(&filter_lower)
                     || link.url.to_lowercase().contains(&filter_lower)
                     || link
                         .tags
                         .iter()
                         .any(|t| t.to_lowercase().contains(&filter_lower))
-            })
-            .cloned()
-            .collect();
+                {
+                    links.push(link.clone());
+                }
+            }
+            self.links = links;
+            self.links_total = None;
+        }
 
         // Reset selection if out of bounds
         if self.link_index >= self.links.len() {
             self.link_index = 0;
         }
+        self.regroup();
     }
 
     /// Insert character at cursor position
     pub fn insert_char(&mut self, c: char) {
-        self.command_input.insert(self.command_cursor, c);
-        self.command_cursor += 1;
-
-        // Update filter in real-time if in filter mode
-        if self.input_mode == InputMode::Filter {
-            self.filter_text = self.command_input.clone();
-            self.apply_realtime_filter();
-        }
+        self.command_input.insert(c);
+        self.input_changed();
     }
 
     /// Delete character before cursor
     pub fn delete_char(&mut self) {
-        if self.command_cursor > 0 {
-            self.command_cursor -= 1;
-            self.command_input.remove(self.command_cursor);
+        if self.command_input.backspace() {
+            self.input_changed();
+        }
+    }
 
-            // Update filter in real-time if in filter mode
-            if self.input_mode == InputMode::Filter {
-                self.filter_text = self.command_input.clone();
-                self.apply_realtime_filter();
-            }
+    /// Delete character under cursor
+    pub fn delete_char_forward(&mut self) {
+        if self.command_input.delete() {
+            self.input_changed();
         }
     }
 
     /// Move cursor left
     pub fn cursor_left(&mut self) {
-        if self.command_cursor > 0 {
-            self.command_cursor -= 1;
-        }
+        self.command_input.move_left();
     }
 
     /// Move cursor right
     pub fn cursor_right(&mut self) {
-        if self.command_cursor < self.command_input.len() {
-            self.command_cursor += 1;
+        self.command_input.move_right();
+    }
+
+    /// Move cursor to the start of the input
+    pub fn cursor_home(&mut self) {
+        self.command_input.move_home();
+    }
+
+    /// Move cursor to the end of the input
+    pub fn cursor_end(&mut self) {
+        self.command_input.move_end();
+    }
+
+    /// Update filter in real-time if in filter mode
+    fn input_changed(&mut self) {
+        if self.input_mode == InputMode::Filter {
+            self.filter_text = self.command_input.value().to_string();
+            self.apply_realtime_filter();
         }
     }
 
-    /// Delete a link and store for undo
+    /// Delete the selected link (u undoes it)
     pub fn delete_current_link(&mut self, store: &mut Store) -> anyhow::Result<()> {
         if let Some(link) = self.current_link().cloned() {
             let saved_index = self.link_index;
             store.delete_link(link.id)?;
-            self.deleted_link = Some(link.clone());
-            self.set_status(format!("Deleted '{}'. Press u to undo", link.title));
+            if let Some(ref mut session) = self.reading {
+                session.record(link.id, ReadAction::Cleared, chrono::Utc::now());
+            }
+            self.set_status(tf("Deleted '{}'. Press u to undo", &[&link.title]));
             self.refresh(store)?;
             // Restore index, clamped to new list bounds
             if !self.links.is_empty() {
-                self.link_index = saved_index.min(self.links.len() - 1);
+                self.select_link(saved_index.min(self.links.len() - 1));
             }
         }
         Ok(())
     }
 
-    /// Undo last delete
-    pub fn undo_delete(&mut self, store: &mut Store) -> anyhow::Result<()> {
-        if let Some(link) = self.deleted_link.take() {
-            store.add_link(&link)?;
-            self.set_status(format!("Restored '{}'", link.title));
-            self.refresh(store)?;
+    /// Start a reading session on the unread links
+    pub fn start_reading(&mut self, store: &Store) -> anyhow::Result<()> {
+        if self.reading.is_some() {
+            self.set_status("Already reading (:done ends the session)".to_string());
+            return Ok(());
+        }
+        if let Some(index) = self.filters.iter().position(|f| *f == Filter::Unread) {
+            self.filter_index = index;
+            self.apply_filter(store)?;
+        }
+        self.active_pane = ActivePane::Items;
+        self.reading = Some(ReadingSession::start(chrono::Utc::now()));
+        self.set_status(format!(
+            "Reading session started: {} unread. Enter opens, d clears, :done ends",
+            self.links.len()
+        ));
+        Ok(())
+    }
+
+    /// End the reading session, saving it to the session log
+    pub fn end_reading(&mut self, store: &Store) -> anyhow::Result<()> {
+        let Some(mut session) = self.reading.take() else {
+            self.set_status("No reading session (:read starts one)".to_string());
+            return Ok(());
+        };
+        let now = chrono::Utc::now();
+        session.end(now);
+        let read = session.links_read().len();
+        let minutes = session.reading_time(now).num_minutes();
+
+        let mut log = SessionLog::load(store.config())?;
+        log.push(session, now);
+        log.save()?;
+
+        self.set_status(format!(
+            "Session ended: read {} in {} min",
+            if read == 1 {
+                "1 link".to_string()
+            } else {
+                format!("{} links", read)
+            },
+            minutes
+        ));
+        Ok(())
+    }
+
+    /// Start tag triage: the Untagged links, with 1-9 tagging the selected
+    /// one and moving on
+    pub fn start_triage(&mut self, store: &Store) -> anyhow::Result<()> {
+        let tags = triage_tags(&store.config().triage_tags, &store.get_tags_with_counts()?);
+        if tags.is_empty() {
+            self.set_status("No tags to triage with (rott config set triage_tags ...)".to_string());
+            return Ok(());
+        }
+        if let Some(index) = self.filters.iter().position(|f| *f == Filter::Untagged) {
+            self.filter_index = index;
+            self.apply_filter(store)?;
+        }
+        self.active_pane = ActivePane::Items;
+        self.triage = Some(tags);
+        self.set_status(format!(
+            "Triage: {} untagged. 1-9 tags and moves on, Esc ends",
+            self.links.len()
+        ));
+        Ok(())
+    }
+
+    /// End tag triage
+    pub fn end_triage(&mut self) {
+        if self.triage.take().is_some() {
+            self.set_status("Triage ended".to_string());
+        }
+    }
+
+    /// Add the tag bound to `key` (1-9) to the selected link and select the
+    /// next one
+    ///
+    /// Returns whether a link was tagged.
+    pub fn triage_tag(&mut self, store: &mut Store, key: usize) -> anyhow::Result<bool> {
+        let Some(tag) = self
+            .triage
+            .as_ref()
+            .and_then(|tags| tags.get(key.checked_sub(1)?))
+            .cloned()
+        else {
+            self.set_status(format!("No tag on {}", key));
+            return Ok(false);
+        };
+        let Some(mut link) = self.current_link().cloned() else {
+            return Ok(false);
+        };
+        let index = self.link_index;
+        link.add_tag(tag.clone());
+        store.update_link(&link)?;
+        self.refresh(store)?;
+
+        // In the Untagged filter the tagged link leaves the list, so the
+        // next one has taken its place
+        let next = if self.links.iter().any(|l| l.id == link.id) {
+            index + 1
+        } else {
+            index
+        };
+        if self.links.is_empty() {
+            self.triage = None;
+            self.set_status(format!("Tagged '{}' #{}. Triage done", link.title, tag));
         } else {
-            self.set_status("Nothing to undo".to_string());
+            self.select_link(next.min(self.links.len() - 1));
+            self.set_status(format!(
+                "Tagged '{}' #{} ({} left)",
+                link.title,
+                tag,
+                self.links.len()
+            ));
         }
+        Ok(true)
+    }
+
+    /// Show this week's reading in the status bar
+    pub fn show_reading_stats(&mut self, store: &Store) -> anyhow::Result<()> {
+        let now = chrono::Utc::now();
+        let stats = SessionLog::load(store.config())?.stats(now - chrono::Duration::days(7), now);
+        self.set_status(stats.summary("this week"));
         Ok(())
     }
 
+    /// Undo the last edit, or redo the last undone one
+    ///
+    /// Returns whether anything changed.
+    pub fn undo(&mut self, store: &mut Store, redo: bool) -> anyhow::Result<bool> {
+        let edit = if redo { store.redo()? } else { store.undo()? };
+        let Some(edit) = edit else {
+            self.set_status(if redo {
+                t("Nothing to redo")
+            } else {
+                t("Nothing to undo")
+            });
+            return Ok(false);
+        };
+        self.set_status(if redo {
+            tf("Redid {}", &[&edit.label])
+        } else {
+            tf("Undid {}", &[&edit.label])
+        });
+        self.refresh(store)?;
+        Ok(true)
+    }
+
     /// Add a new link with the given URL
     pub fn add_link(
         &mut self,
         store: &mut Store,
         url: &str,
         metadata: Option<UrlMetadata>,
+        notes: Vec<Note>,
     ) -> anyhow::Result<()> {
         let mut link = Link::new(url);
+        for note in notes {
+            link.add_note(note);
+        }
 
+        if let Some(ref meta) = metadata {
+            crate::metadata::record_failures(store.config(), &link, meta);
+        }
         if let Some(meta) = metadata {
             if let Some(title) = meta.title {
                 link.set_title(title);
@@ -617,10 +1683,21 @@ impl App {
             if !meta.author.is_empty() {
                 link.set_author(meta.author);
             }
+            if meta.published_at.is_some() {
+                link.set_published_at(meta.published_at);
+            }
+            if meta.canonical_url.is_some() {
+                link.set_canonical_url(meta.canonical_url);
+            }
+            link.paywalled = meta.paywalled;
+            link.language = meta.language;
+            if let Some(fingerprint) = meta.content_hash {
+                link.set_content_hash(Some(fingerprint.to_string()));
+            }
         }
 
         store.add_link(&link)?;
-        self.set_status(format!("Added '{}'", link.title));
+        self.set_status(tf("Added '{}'", &[&link.title]));
         self.refresh(store)?;
         Ok(())
     }
@@ -636,38 +1713,275 @@ impl App {
                 .collect();
             updated_link.set_tags(tags);
             store.update_link(&updated_link)?;
-            self.set_status("Tags updated".to_string());
+            self.set_status(t("Tags updated"));
             self.refresh(store)?;
         }
         Ok(())
     }
 
-    /// Add a note to the current link
-    pub fn add_note_to_current(&mut self, store: &mut Store, body: &str) -> anyhow::Result<()> {
+    /// Add a note to the current link, as a reply to the selected note if
+    /// `reply` is set
+    pub fn add_note_to_current(
+        &mut self,
+        store: &mut Store,
+        body: &str,
+        reply: bool,
+    ) -> anyhow::Result<()> {
+        let parent_id = if reply {
+            self.selected_note_ids()
+                .and_then(|(_, note_id)| self.current_link()?.reply_parent(note_id))
+        } else {
+            None
+        };
         if let Some(link) = self.current_link() {
-            let note = Note::new(body);
+            let mut note = Note::new(body);
+            note.parent_id = parent_id;
+            let note_id = note.id;
             store.add_note_to_link(link.id, &note)?;
-            self.set_status("Note added".to_string());
             self.refresh(store)?;
+            if parent_id.is_some() {
+                self.select_note_by_id(note_id);
+                self.set_status(t("Reply added"));
+            } else {
+                self.set_status(t("Note added"));
+            }
         }
         Ok(())
     }
 
+    /// The selected note, as shown in the detail pane
+    pub fn selected_note(&self) -> Option<&Note> {
+        let page = self.detail_notes.as_ref()?;
+        page.notes.get(self.note_index).map(|(note, _)| note)
+    }
+
+    /// Replace the selected note's body, keeping it selected
+    pub fn edit_selected_note(&mut self, store: &mut Store, body: &str) -> anyhow::Result<()> {
+        let Some((link_id, note_id)) = self.selected_note_ids() else {
+            return Ok(());
+        };
+        let mut note = store
+            .get_link(link_id)?
+            .and_then(|link| link.get_note(note_id).cloned())
+            .ok_or_else(|| anyhow::anyhow!("Note not found"))?;
+        note.set_body(body);
+        store.update_note(link_id, &note)?;
+        self.refresh(store)?;
+        self.select_note_by_id(note_id);
+        self.set_status(t("Note updated"));
+        Ok(())
+    }
+
+    /// Select the previous or next note of the current link
+    pub fn select_note(&mut self, forward: bool) {
+        let count = self
+            .detail_notes
+            .as_ref()
+            .map_or(0, |page| page.notes.len());
+        if count == 0 {
+            return;
+        }
+        self.note_index = if forward {
+            (self.note_index + 1).min(count - 1)
+        } else {
+            self.note_index.saturating_sub(1)
+        };
+        self.url_index = None;
+        self.section_index = None;
+    }
+
+    /// The selected note's sections, if it's long enough to show in them
+    fn selected_note_sections(&self) -> Option<(Uuid, NoteSections)> {
+        let (note, _) = self.detail_notes.as_ref()?.notes.get(self.note_index)?;
+        if !is_long(&note.body, self.note_section_threshold) {
+            return None;
+        }
+        let parsed = NoteSections::parse(&note.body);
+        (!parsed.sections.is_empty()).then_some((note.id, parsed))
+    }
+
+    /// Focus the next or previous section of the selected note, wrapping
+    /// around
+    pub fn focus_note_section(&mut self, forward: bool) {
+        let count = self
+            .selected_note_sections()
+            .map_or(0, |(_, parsed)| parsed.sections.len());
+        if count == 0 {
+            self.set_status(t("This note isn't shown in sections"));
+            return;
+        }
+        self.section_index = Some(match self.section_index {
+            Some(index) if forward => (index + 1) % count,
+            Some(index) => (index + count - 1) % count,
+            None if forward => 0,
+            None => count - 1,
+        });
+    }
+
+    /// Expand or collapse the focused section of the selected note, or all
+    /// of its sections if none is focused
+    pub fn toggle_note_section(&mut self) {
+        let Some((note_id, parsed)) = self.selected_note_sections() else {
+            return;
+        };
+        let count = parsed.sections.len();
+        match self.section_index.filter(|index| *index < count) {
+            Some(index) => {
+                if !self.expanded_sections.remove(&(note_id, index)) {
+                    self.expanded_sections.insert((note_id, index));
+                }
+            }
+            None => {
+                let all: Vec<_> = (0..count).map(|index| (note_id, index)).collect();
+                if all.iter().all(|key| self.expanded_sections.contains(key)) {
+                    for key in &all {
+                        self.expanded_sections.remove(key);
+                    }
+                } else {
+                    self.expanded_sections.extend(all);
+                }
+            }
+        }
+    }
+
+    /// URLs mentioned in the selected note's body
+    fn selected_note_urls(&self) -> Vec<String> {
+        self.detail_notes
+            .as_ref()
+            .and_then(|page| page.notes.get(self.note_index))
+            .map(|(note, _)| url_strs(&note.body).into_iter().map(String::from).collect())
+            .unwrap_or_default()
+    }
+
+    /// Focus the next or previous URL in the selected note, wrapping around
+    pub fn focus_note_url(&mut self, forward: bool) {
+        let count = self.selected_note_urls().len();
+        if count == 0 {
+            self.set_status(t("No URLs in this note"));
+            return;
+        }
+        self.url_index = Some(match self.url_index {
+            Some(i) if forward => (i + 1) % count,
+            Some(i) => (i + count - 1) % count,
+            None if forward => 0,
+            None => count - 1,
+        });
+    }
+
+    /// The focused URL, or the note's first URL if none is focused
+    fn focused_note_url(&self) -> Option<String> {
+        let urls = self.selected_note_urls();
+        let index = self.url_index.unwrap_or(0);
+        urls.into_iter().nth(index)
+    }
+
+    /// Open the focused URL of the selected note in the browser
+    pub fn open_note_url(&mut self) {
+        let Some(url) = self.focused_note_url() else {
+            self.set_status(t("No URLs in this note"));
+            return;
+        };
+        match self.openers.open_url(&url) {
+            Ok(_) => self.set_status(tf("Opened {}", &[&url])),
+            Err(e) => self.set_status(tf("Failed to open: {}", &[&e])),
+        }
+    }
+
+    /// Offer to save the focused URL as a new link
+    ///
+    /// Fills in the command line, so the user can confirm with Enter (or
+    /// back out with Esc).
+    pub fn offer_save_note_url(&mut self) {
+        let Some(url) = self.focused_note_url() else {
+            self.set_status(t("No URLs in this note"));
+            return;
+        };
+        self.enter_command_mode(CommandType::Generic);
+        self.command_input.set_value(format!("add-related {}", url));
+    }
+
+    /// Move the selected note up or down, keeping it selected
+    pub fn move_selected_note(&mut self, store: &mut Store, up: bool) -> anyhow::Result<bool> {
+        let Some((link_id, note_id)) = self.selected_note_ids() else {
+            return Ok(false);
+        };
+        if !store.move_note(link_id, note_id, up)? {
+            self.set_status(format!(
+                "Note is already {}",
+                if up { "first" } else { "last" }
+            ));
+            return Ok(false);
+        }
+        self.refresh(store)?;
+        self.select_note_by_id(note_id);
+        self.set_status(format!("Moved note {}", if up { "up" } else { "down" }));
+        Ok(true)
+    }
+
+    /// Pin or unpin the selected note, keeping it selected
+    pub fn toggle_selected_note_pin(&mut self, store: &mut Store) -> anyhow::Result<bool> {
+        let Some((link_id, note_id)) = self.selected_note_ids() else {
+            return Ok(false);
+        };
+        let pinned = self
+            .current_link()
+            .and_then(|link| link.get_note(note_id))
+            .is_some_and(|note| note.pinned);
+        store.pin_note(link_id, note_id, !pinned)?;
+        self.refresh(store)?;
+        self.select_note_by_id(note_id);
+        self.set_status(
+            if pinned {
+                "Note unpinned"
+            } else {
+                "Note pinned"
+            }
+            .to_string(),
+        );
+        Ok(true)
+    }
+
+    /// The current link and selected note (notes are counted in the
+    /// threaded order they're shown in)
+    fn selected_note_ids(&self) -> Option<(Uuid, Uuid)> {
+        let page = self.detail_notes.as_ref()?;
+        let (note, _) = page.notes.get(self.note_index)?;
+        Some((page.link_id, note.id))
+    }
+
+    fn select_note_by_id(&mut self, note_id: Uuid) {
+        if let Some(index) = self
+            .detail_notes
+            .as_ref()
+            .and_then(|page| page.notes.iter().position(|(n, _)| n.id == note_id))
+        {
+            self.note_index = index;
+        }
+    }
+
     /// Search all links
     pub fn search(&mut self, store: &Store, query: &str) -> anyhow::Result<()> {
+        self.note_hits.clear();
         if query.is_empty() {
             self.apply_filter(store)?;
         } else {
-            self.links = store.search_links(query)?;
+            let hits = store.search(query)?;
+            self.note_hits = hits
+                .iter()
+                .filter_map(|hit| Some((hit.link.id, hit.note_id?)))
+                .collect();
+            self.links = hits.into_iter().map(|hit| hit.link).collect();
+            self.links_total = None;
             self.link_index = 0;
-            self.set_status(format!("Found {} results", self.links.len()));
+            self.regroup();
+            self.set_status(tf("Found {} results", &[&self.links.len()]));
         }
         Ok(())
     }
 
     /// Parse and execute command from input
     pub fn execute_command(&mut self, store: &mut Store) -> anyhow::Result<CommandResult> {
-        let input = self.command_input.trim().to_string();
+        let input = self.command_input.value().trim().to_string();
 
         // Parse command
         if input.starts_with("add ") {
@@ -676,21 +1990,101 @@ impl App {
                 self.set_status("Usage: add <url>".to_string());
                 return Ok(CommandResult::Done);
             }
-            return Ok(CommandResult::NeedMetadata(url.to_string()));
+            return Ok(CommandResult::NeedMetadata {
+                url: url.to_string(),
+                related_to: None,
+            });
+        } else if input.starts_with("add-related ") {
+            let url = input.strip_prefix("add-related ").unwrap().trim();
+            let Some(link) = self.current_link() else {
+                self.set_status(t("Select a link first"));
+                return Ok(CommandResult::Done);
+            };
+            if url.is_empty() {
+                self.set_status("Usage: add-related <url>".to_string());
+                return Ok(CommandResult::Done);
+            }
+            return Ok(CommandResult::NeedMetadata {
+                url: url.to_string(),
+                related_to: Some(link.id),
+            });
         } else if input.starts_with("tag ") {
             let tags = input.strip_prefix("tag ").unwrap().trim();
             self.update_tags(store, tags)?;
         } else if input == "note" || input.starts_with("note ") {
             return Ok(CommandResult::NeedEditor(EditorTask::Note));
+        } else if input == "reply" {
+            if self.selected_note_ids().is_none() {
+                self.set_status(
+                    "Select a note to reply to ([ and ] in the detail pane)".to_string(),
+                );
+                return Ok(CommandResult::Done);
+            }
+            return Ok(CommandResult::NeedEditor(EditorTask::Reply));
+        } else if input == "edit-note" {
+            if self.selected_note_ids().is_none() {
+                self.set_status("Select a note to edit ([ and ] in the detail pane)".to_string());
+                return Ok(CommandResult::Done);
+            }
+            return Ok(CommandResult::NeedEditor(EditorTask::EditNote));
         } else if input == "edit" {
             return Ok(CommandResult::NeedEditor(EditorTask::EditLink));
+        } else if input == "q" || input == "quit" {
+            self.should_quit = true;
         } else if input == "delete" || input == "d" {
             self.delete_current_link(store)?;
+        } else if input == "read" {
+            self.start_reading(store)?;
+        } else if input == "done" {
+            self.end_reading(store)?;
+        } else if input == "triage" {
+            self.start_triage(store)?;
+        } else if input == "describe" || input.starts_with("describe ") {
+            let Some(Filter::ByTag(tag)) = self.current_filter().cloned() else {
+                self.set_status("Select a tag filter to describe".to_string());
+                return Ok(CommandResult::Done);
+            };
+            let text = input.strip_prefix("describe").unwrap_or_default().trim();
+            store.set_tag_description(&tag, Some(text))?;
+            self.tag_description = store.tag_description(&tag)?;
+            self.set_status(if self.tag_description.is_some() {
+                format!("Described tag {}", tag)
+            } else {
+                format!("Cleared description of tag {}", tag)
+            });
+        } else if input == "more" {
+            self.load_more_notes(store)?;
+        } else if input == "stats" {
+            self.show_reading_stats(store)?;
+        } else if input == "tour" {
+            self.start_tour();
+        } else if input == "lock" {
+            if self.is_lock_enabled() {
+                self.lock();
+            } else {
+                self.set_status("Privacy lock not enabled (rott lock enable)".to_string());
+            }
+        } else if input == "group" || input.starts_with("group ") {
+            let name = input.strip_prefix("group").unwrap().trim();
+            match GroupBy::parse(name) {
+                Some(group_by) => {
+                    self.set_group_by(group_by);
+                    if group_by == GroupBy::None {
+                        self.set_status(t("Grouping off"));
+                    } else {
+                        self.set_status(tf("Grouped by {}", &[&t(group_by.label())]));
+                    }
+                }
+                None => self.set_status("Usage: group day|domain|tag|published|none".to_string()),
+            }
         } else if input.starts_with("search ") {
             let query = input.strip_prefix("search ").unwrap().trim();
             self.search(store, query)?;
+        } else if input == "collection" || input.starts_with("collection ") {
+            let name = input.strip_prefix("collection").unwrap().trim();
+            self.switch_collection(store, name)?;
         } else if !input.is_empty() {
-            self.set_status(format!("Unknown command: {}", input));
+            self.set_status(tf("Unknown command: {}", &[&input]));
         }
 
         Ok(CommandResult::Done)
@@ -702,47 +2096,71 @@ impl App {
 pub enum CommandResult {
     /// Command completed
     Done,
-    /// Need to fetch metadata for URL
-    NeedMetadata(String),
+    /// Need to fetch metadata for URL (and, for a URL saved from a note,
+    /// the link whose note it came from)
+    NeedMetadata {
+        url: String,
+        related_to: Option<Uuid>,
+    },
     /// Need to open editor
     NeedEditor(EditorTask),
 }
 
 /// Type of editor task
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub enum EditorTask {
     /// Add/edit a note
     Note,
+    /// Reply to the selected note
+    Reply,
+    /// Edit the selected note
+    EditNote,
     /// Edit link details
     EditLink,
 }
 
-/// Open a URL in the default browser
-///
-/// Uses xdg-open on Linux, open on macOS, start on Windows.
-/// Spawns as a detached process with null stdio to avoid
-/// interfering with the TUI.
-fn open_url(url: &str) -> std::io::Result<()> {
-    #[cfg(target_os = "linux")]
-    let mut cmd = Command::new("xdg-open");
-
-    #[cfg(target_os = "macos")]
-    let mut cmd = Command::new("open");
-
-    #[cfg(target_os = "windows")]
-    let mut cmd = {
-        let mut c = Command::new("cmd");
-        c.args(["/C", "start", ""]);
-        c
+/// Tags for the keys 1-9 in tag triage: the configured ones, or else the
+/// most used (`counts` is sorted by count, as the store returns it)
+fn triage_tags(configured: &[String], counts: &[(String, i64)]) -> Vec<String> {
+    let tags = if configured.is_empty() {
+        counts.iter().map(|(tag, _)| tag.clone()).collect()
+    } else {
+        configured.to_vec()
     };
+    tags.into_iter().take(9).collect()
+}
 
-    cmd.arg(url)
-        .stdin(Stdio::null())
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .spawn()?;
+/// Author names for the "By Author..." accordion, alphabetically
+fn author_names(store: &Store) -> anyhow::Result<Vec<String>> {
+    let mut names: Vec<String> = store
+        .get_authors_with_counts()?
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    names.sort_by_key(|name| name.to_lowercase());
+    Ok(names)
+}
 
-    Ok(())
+/// Languages for the per-language "Unread" queues, most common first
+///
+/// Empty unless links are in more than one language.
+fn reading_languages(links: &[Link]) -> Vec<String> {
+    let languages: Vec<String> = language_counts(links)
+        .into_iter()
+        .map(|(language, _)| language)
+        .collect();
+    if languages.len() > 1 {
+        languages
+    } else {
+        Vec::new()
+    }
+}
+
+/// "Unread", followed by its per-language queues
+fn unread_filters(languages: &[String]) -> Vec<Filter> {
+    std::iter::once(Filter::Unread)
+        .chain(languages.iter().cloned().map(Filter::UnreadIn))
+        .collect()
 }
 
 #[cfg(test)]
@@ -763,6 +2181,61 @@ mod tests {
         assert_eq!(ActivePane::Detail.prev(), ActivePane::Items);
     }
 
+    #[test]
+    fn test_filter_labels() {
+        assert_eq!(Filter::Favorites.label(), "Favorites");
+        assert_eq!(Filter::TagsHeader.label(), "By Tag");
+        assert_eq!(Filter::Unread.label(), "Unread");
+        assert_eq!(Filter::NeedsAttention.label(), "Needs Attention");
+        assert_eq!(Filter::Expired.label(), "Expired");
+        assert_eq!(Filter::ByTag("rust".to_string()).label(), "Tag rust");
+        assert_eq!(Filter::AuthorsHeader.label(), "By Author");
+        assert_eq!(
+            Filter::ByAuthor("Jane Doe".to_string()).label(),
+            "Author Jane Doe"
+        );
+        assert_eq!(
+            Filter::UnreadIn("de".to_string()).label(),
+            "Unread in German"
+        );
+    }
+
+    #[test]
+    fn test_language_queues_need_two_languages() {
+        let mut links = vec![
+            Link::new("https://a.example.com"),
+            Link::new("https://b.example.com"),
+        ];
+        links[0].language = Some("en".to_string());
+        assert_eq!(
+            unread_filters(&reading_languages(&links)),
+            vec![Filter::Unread]
+        );
+
+        links[1].language = Some("de".to_string());
+        assert_eq!(
+            unread_filters(&reading_languages(&links)),
+            vec![
+                Filter::Unread,
+                Filter::UnreadIn("de".to_string()),
+                Filter::UnreadIn("en".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_triage_tags() {
+        let counts: Vec<(String, i64)> = (1..=12).rev().map(|n| (format!("t{}", n), n)).collect();
+        let most_used = triage_tags(&[], &counts);
+        assert_eq!(most_used.len(), 9);
+        assert_eq!(most_used[0], "t12");
+        assert_eq!(most_used[8], "t4");
+
+        let configured = vec!["rust".to_string(), "reading".to_string()];
+        assert_eq!(triage_tags(&configured, &counts), configured);
+        assert!(triage_tags(&[], &[]).is_empty());
+    }
+
     #[test]
     fn test_filter_variants() {
         let fav = Filter::Favorites;
@@ -0,0 +1,291 @@
+//! Grouped rows for the Items pane
+//!
+//! With grouping on, links are gathered under headers ("Today", a domain,
+//! a tag, a publication month) that can be collapsed. The pane shows a list of rows — headers
+//! and links — and selection moves over rows, so a collapsed header can be
+//! selected and expanded again.
+
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+use chrono::{Local, NaiveDate};
+use rott_core::{Link, LinkSort};
+
+/// How links in the Items pane are grouped
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GroupBy {
+    /// A flat list (default)
+    #[default]
+    None,
+    /// By when the link was last updated: Today, Yesterday, Last week, ...
+    Day,
+    /// By the link's domain
+    Domain,
+    /// By the link's first tag
+    Tag,
+    /// A timeline by publication month, newest first
+    Published,
+}
+
+impl GroupBy {
+    /// Parse the argument of the `:group` command
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "none" | "off" => Some(GroupBy::None),
+            "day" | "date" => Some(GroupBy::Day),
+            "domain" => Some(GroupBy::Domain),
+            "tag" => Some(GroupBy::Tag),
+            "published" | "timeline" => Some(GroupBy::Published),
+            _ => None,
+        }
+    }
+
+    /// Plain-text name
+    pub fn label(self) -> &'static str {
+        match self {
+            GroupBy::None => "none",
+            GroupBy::Day => "day",
+            GroupBy::Domain => "domain",
+            GroupBy::Tag => "tag",
+            GroupBy::Published => "published",
+        }
+    }
+}
+
+/// Plain-text name of a sort order, for the Items pane's title
+pub fn sort_label(sort: LinkSort) -> &'static str {
+    match sort {
+        LinkSort::Updated => "recently updated",
+        LinkSort::Created => "newest first",
+        LinkSort::Oldest => "oldest first",
+        LinkSort::Title => "by title",
+        LinkSort::Domain => "by domain",
+    }
+}
+
+/// One row of the Items pane
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListRow {
+    /// A group header
+    Header {
+        name: String,
+        count: usize,
+        collapsed: bool,
+    },
+    /// A link, by index into the current list
+    Link(usize),
+}
+
+const DAY_GROUPS: [&str; 5] = ["Today", "Yesterday", "Last week", "Last month", "Older"];
+const NO_DOMAIN: &str = "No domain";
+const UNTAGGED: &str = "Untagged";
+const UNDATED: &str = "Publication date unknown";
+
+/// Name of the group a link belongs to
+pub fn group_name(link: &Link, group_by: GroupBy, today: NaiveDate) -> String {
+    match group_by {
+        GroupBy::None => String::new(),
+        GroupBy::Day => {
+            let date = link.updated_at.with_timezone(&Local).date_naive();
+            let days = (today - date).num_days();
+            let group = match days {
+                ..=0 => DAY_GROUPS[0],
+                1 => DAY_GROUPS[1],
+                2..=7 => DAY_GROUPS[2],
+                8..=31 => DAY_GROUPS[3],
+                _ => DAY_GROUPS[4],
+            };
+            group.to_string()
+        }
+        GroupBy::Domain => link
+            .derived
+            .get("domain")
+            .cloned()
+            .unwrap_or_else(|| NO_DOMAIN.to_string()),
+        GroupBy::Tag => link
+            .tags
+            .first()
+            .cloned()
+            .unwrap_or_else(|| UNTAGGED.to_string()),
+        GroupBy::Published => link
+            .published_at
+            .map(|date| date.format("%B %Y").to_string())
+            .unwrap_or_else(|| UNDATED.to_string()),
+    }
+}
+
+/// Reorder links so each group is contiguous
+///
+/// Day groups run newest first; domains and tags are alphabetical, with
+/// links lacking one at the end. Order within a group is kept, except in
+/// the publication timeline, which runs newest first throughout (undated
+/// links at the end).
+pub fn sort_into_groups(links: &mut [Link], group_by: GroupBy, today: NaiveDate) {
+    if group_by == GroupBy::Published {
+        links.sort_by_key(|link| (link.published_at.is_none(), Reverse(link.published_at)));
+        return;
+    }
+
+    let rank = |link: &Link| {
+        let name = group_name(link, group_by, today);
+        match group_by {
+            GroupBy::None | GroupBy::Published => (0, String::new()),
+            GroupBy::Day => (
+                DAY_GROUPS.iter().position(|g| *g == name).unwrap_or(0),
+                name,
+            ),
+            GroupBy::Domain | GroupBy::Tag => {
+                let missing = name == NO_DOMAIN || name == UNTAGGED;
+                (usize::from(missing), name)
+            }
+        }
+    };
+    links.sort_by_cached_key(rank);
+}
+
+/// Build the rows for links already sorted into groups
+pub fn build_rows(
+    links: &[Link],
+    group_by: GroupBy,
+    collapsed: &HashSet<String>,
+    today: NaiveDate,
+) -> Vec<ListRow> {
+    if group_by == GroupBy::None {
+        return (0..links.len()).map(ListRow::Link).collect();
+    }
+
+    let mut rows = Vec::new();
+    let mut start = 0;
+    while start < links.len() {
+        let name = group_name(&links[start], group_by, today);
+        let end = links[start..]
+            .iter()
+            .position(|link| group_name(link, group_by, today) != name)
+            .map_or(links.len(), |offset| start + offset);
+
+        let is_collapsed = collapsed.contains(&name);
+        rows.push(ListRow::Header {
+            name,
+            count: end - start,
+            collapsed: is_collapsed,
+        });
+        if !is_collapsed {
+            rows.extend((start..end).map(ListRow::Link));
+        }
+        start = end;
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, Duration, Utc};
+
+    fn link(url: &str, tags: &[&str], days_ago: i64) -> Link {
+        let mut link = Link::new(url);
+        link.set_tags(tags.iter().map(|t| t.to_string()).collect());
+        link.updated_at = Utc::now() - Duration::days(days_ago);
+        link
+    }
+
+    fn header_names(rows: &[ListRow]) -> Vec<&str> {
+        rows.iter()
+            .filter_map(|row| match row {
+                ListRow::Header { name, .. } => Some(name.as_str()),
+                ListRow::Link(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_group_by_day() {
+        let today = Local::now().date_naive();
+        let mut links = vec![
+            link("https://a.com", &[], 40),
+            link("https://b.com", &[], 0),
+            link("https://c.com", &[], 1),
+            link("https://d.com", &[], 0),
+        ];
+        sort_into_groups(&mut links, GroupBy::Day, today);
+        let rows = build_rows(&links, GroupBy::Day, &HashSet::new(), today);
+
+        assert_eq!(header_names(&rows), vec!["Today", "Yesterday", "Older"]);
+        assert_eq!(
+            rows[0],
+            ListRow::Header {
+                name: "Today".to_string(),
+                count: 2,
+                collapsed: false
+            }
+        );
+        // Order within a group is kept
+        assert_eq!(links[0].url, "https://b.com");
+        assert_eq!(links[1].url, "https://d.com");
+    }
+
+    #[test]
+    fn test_group_by_tag_collapsed() {
+        let today = Local::now().date_naive();
+        let mut links = vec![
+            link("https://a.com", &[], 0),
+            link("https://b.com", &["rust"], 0),
+            link("https://c.com", &["go"], 0),
+            link("https://d.com", &["rust", "web"], 0),
+        ];
+        sort_into_groups(&mut links, GroupBy::Tag, today);
+
+        let collapsed = HashSet::from(["rust".to_string()]);
+        let rows = build_rows(&links, GroupBy::Tag, &collapsed, today);
+
+        assert_eq!(header_names(&rows), vec!["go", "rust", "Untagged"]);
+        // go header + link, rust header only, Untagged header + link
+        assert_eq!(rows.len(), 5);
+        assert_eq!(
+            rows[2],
+            ListRow::Header {
+                name: "rust".to_string(),
+                count: 2,
+                collapsed: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_publication_timeline() {
+        let today = Local::now().date_naive();
+        let published = |year, month| {
+            let mut link = link("https://example.com", &[], 0);
+            link.published_at = NaiveDate::from_ymd_opt(year, month, 10)
+                .and_then(|d| d.and_hms_opt(12, 0, 0))
+                .map(|d| d.and_utc());
+            link
+        };
+        let mut links = vec![
+            published(2023, 5),
+            link("https://undated.com", &[], 0),
+            published(2024, 1),
+            published(2023, 5),
+        ];
+        sort_into_groups(&mut links, GroupBy::Published, today);
+        let rows = build_rows(&links, GroupBy::Published, &HashSet::new(), today);
+
+        assert_eq!(
+            header_names(&rows),
+            vec!["January 2024", "May 2023", "Publication date unknown"]
+        );
+        assert_eq!(links[3].url, "https://undated.com");
+        assert_eq!(links[0].published_at.map(|d| d.year()), Some(2024));
+        assert_eq!(GroupBy::parse("timeline"), Some(GroupBy::Published));
+    }
+
+    #[test]
+    fn test_ungrouped_rows_are_links() {
+        let today = Local::now().date_naive();
+        let links = vec![link("https://a.com", &[], 0), link("https://b.com", &[], 3)];
+        let rows = build_rows(&links, GroupBy::None, &HashSet::new(), today);
+        assert_eq!(rows, vec![ListRow::Link(0), ListRow::Link(1)]);
+        assert_eq!(GroupBy::parse("domain"), Some(GroupBy::Domain));
+        assert_eq!(GroupBy::parse("size"), None);
+    }
+}
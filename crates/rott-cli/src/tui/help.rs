@@ -0,0 +1,306 @@
+//! The help overlay
+//!
+//! Help is generated from [`BINDINGS`], the list of every key binding and
+//! command, grouped by category. The overlay scrolls (`j`/`k`, `PgUp`/`PgDn`,
+//! `g`/`G`) and can be searched with `/`, which narrows it to bindings whose
+//! keys, description, or category match.
+//!
+//! When adding a key to the TUI, add it here too (and its category and
+//! description to the translation catalogs, see [`crate::i18n`]).
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::i18n::t;
+
+use super::input::TextInput;
+
+/// One key binding or command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Binding {
+    pub category: &'static str,
+    pub keys: &'static str,
+    pub action: &'static str,
+}
+
+const fn bind(category: &'static str, keys: &'static str, action: &'static str) -> Binding {
+    Binding {
+        category,
+        keys,
+        action,
+    }
+}
+
+/// Every binding, in the order shown
+pub const BINDINGS: &[Binding] = &[
+    bind("Navigation", "j/k, ↓/↑", "Move down/up"),
+    bind("Navigation", "gg", "Jump to first item"),
+    bind("Navigation", "G", "Jump to last item"),
+    bind("Navigation", "h/l, ←/→", "Move to left/right pane"),
+    bind("Navigation", "Tab", "Next pane"),
+    bind("Navigation", "Shift+Tab", "Previous pane"),
+    bind("Navigation", "Enter", "Open link / apply filter"),
+    bind(
+        "Navigation",
+        "Space",
+        "Expand filter group / toggle item group",
+    ),
+    bind("Navigation", "z", "Collapse/expand the selected group"),
+    bind("Navigation", "Z", "Collapse/expand all groups"),
+    bind(
+        "Navigation",
+        "o",
+        "Cycle the sort order (updated/newest/oldest/title/domain)",
+    ),
+    bind("Links", "a", "Add link"),
+    bind("Links", "e", "Edit link"),
+    bind("Links", "t", "Edit tags"),
+    bind("Links", "n", "Add note"),
+    bind("Links", "d", "Delete link"),
+    bind(
+        "Links",
+        "v",
+        "Read the archived text (resumes where you stopped)",
+    ),
+    bind("Links", "u", "Undo the last edit"),
+    bind("Links", "Ctrl+R", "Redo the last undone edit"),
+    bind("Notes (Detail pane)", "[ / ]", "Select previous/next note"),
+    bind(
+        "Notes (Detail pane)",
+        "{ / }",
+        "Focus previous/next section of a long note",
+    ),
+    bind(
+        "Notes (Detail pane)",
+        "Enter",
+        "Expand/collapse the focused section (all if none)",
+    ),
+    bind("Notes (Detail pane)", "K / J", "Move note up/down"),
+    bind("Notes (Detail pane)", "p", "Pin/unpin note"),
+    bind("Notes (Detail pane)", "r", "Reply to note"),
+    bind("Notes (Detail pane)", "E", "Edit note"),
+    bind("Notes (Detail pane)", "m", "Load more notes"),
+    bind(
+        "Notes (Detail pane)",
+        "f / F",
+        "Focus next/previous URL in note",
+    ),
+    bind("Notes (Detail pane)", "o", "Open the focused URL"),
+    bind(
+        "Notes (Detail pane)",
+        "S",
+        "Save the focused URL as a link (:add-related <url>)",
+    ),
+    bind("Search and commands", "/", "Filter view"),
+    bind("Search and commands", ":", "Command mode"),
+    bind(
+        "Search and commands",
+        ":search <q>",
+        "Search (author:name, has:paywall, lang:de)",
+    ),
+    bind(
+        "Search and commands",
+        ":group <by>",
+        "Group by day/domain/tag/published/none",
+    ),
+    bind(
+        "Search and commands",
+        ":read",
+        "Start a reading session on unread links",
+    ),
+    bind("Search and commands", ":done", "End the reading session"),
+    bind("Search and commands", ":stats", "This week's reading"),
+    bind(
+        "Search and commands",
+        ":triage",
+        "Tag untagged links with 1-9 (Esc ends)",
+    ),
+    bind(
+        "Search and commands",
+        ":describe <text>",
+        "Describe the selected tag (empty clears)",
+    ),
+    bind(
+        "Search and commands",
+        ":collection <name>",
+        "Switch collection (no name lists them)",
+    ),
+    bind("Search and commands", ":lock", "Lock screen"),
+    bind("Search and commands", ":tour", "Take the guided tour"),
+    bind("Macros", "q<reg> … q", "Record a macro into register a–z"),
+    bind("Macros", "@<reg>", "Replay a macro (3@a three times)"),
+    bind("Macros", "@@", "Replay the last macro again"),
+    bind("General", "?", "Show this help"),
+    bind("General", "Ctrl+S", "Sync now"),
+    bind("General", "Ctrl+D", "Device settings"),
+    bind("General", "q, Ctrl+C", "Quit"),
+];
+
+/// A line of the help overlay
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelpLine {
+    /// A category heading
+    Category(&'static str),
+    /// A binding under the last heading
+    Binding(&'static Binding),
+    /// Space between categories
+    Blank,
+}
+
+/// What the overlay did with a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelpKey {
+    /// Handled; keep the overlay open
+    Handled,
+    /// Close the overlay
+    Close,
+}
+
+/// Scroll position and search of the help overlay
+#[derive(Debug, Clone, Default)]
+pub struct HelpView {
+    /// First visible line
+    pub scroll: usize,
+    /// Search text (bindings matching it are shown)
+    pub query: TextInput,
+    /// Whether keys are going to the search input
+    pub searching: bool,
+}
+
+impl HelpView {
+    /// Lines to show, narrowed by the search
+    pub fn lines(&self) -> Vec<HelpLine> {
+        let query = self.query.value().trim().to_lowercase();
+        let mut lines = Vec::new();
+        let mut category = None;
+        for binding in BINDINGS.iter().filter(|b| matches(b, &query)) {
+            if category != Some(binding.category) {
+                if category.is_some() {
+                    lines.push(HelpLine::Blank);
+                }
+                lines.push(HelpLine::Category(binding.category));
+                category = Some(binding.category);
+            }
+            lines.push(HelpLine::Binding(binding));
+        }
+        lines
+    }
+
+    /// Handle a key while the overlay is open
+    ///
+    /// `page` is the number of lines scrolled by a page.
+    pub fn handle_key(&mut self, key: KeyEvent, page: usize) -> HelpKey {
+        if self.searching {
+            match key.code {
+                KeyCode::Enter => self.searching = false,
+                KeyCode::Esc => {
+                    self.searching = false;
+                    self.query.clear();
+                }
+                KeyCode::Backspace => {
+                    self.query.backspace();
+                }
+                KeyCode::Left => self.query.move_left(),
+                KeyCode::Right => self.query.move_right(),
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.query.insert(c);
+                }
+                _ => {}
+            }
+            self.scroll = 0;
+            return HelpKey::Handled;
+        }
+
+        let last = self.lines().len().saturating_sub(1);
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.scroll = (self.scroll + 1).min(last),
+            KeyCode::Char('k') | KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::PageDown | KeyCode::Char(' ') => {
+                self.scroll = (self.scroll + page.max(1)).min(last)
+            }
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(page.max(1)),
+            KeyCode::Char('g') | KeyCode::Home => self.scroll = 0,
+            KeyCode::Char('G') | KeyCode::End => self.scroll = last,
+            KeyCode::Char('/') => {
+                self.searching = true;
+                self.query.clear();
+            }
+            // Esc clears a search before closing
+            KeyCode::Esc if !self.query.is_empty() => {
+                self.query.clear();
+                self.scroll = 0;
+            }
+            _ => return HelpKey::Close,
+        }
+        HelpKey::Handled
+    }
+}
+
+/// Whether a binding matches a (lowercased) search, as shown in the
+/// current locale
+fn matches(binding: &Binding, query: &str) -> bool {
+    query.is_empty()
+        || binding.keys.to_lowercase().contains(query)
+        || t(binding.action).to_lowercase().contains(query)
+        || t(binding.category).to_lowercase().contains(query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_lines_grouped_by_category() {
+        let lines = HelpView::default().lines();
+        assert_eq!(lines[0], HelpLine::Category("Navigation"));
+        let categories = lines
+            .iter()
+            .filter(|l| matches!(l, HelpLine::Category(_)))
+            .count();
+        let blanks = lines.iter().filter(|l| **l == HelpLine::Blank).count();
+        assert_eq!(blanks, categories - 1);
+        assert_eq!(lines.len(), BINDINGS.len() + categories + blanks);
+    }
+
+    #[test]
+    fn test_search_narrows_lines() {
+        let mut view = HelpView::default();
+        assert_eq!(
+            view.handle_key(key(KeyCode::Char('/')), 10),
+            HelpKey::Handled
+        );
+        for c in "macro".chars() {
+            view.handle_key(key(KeyCode::Char(c)), 10);
+        }
+        view.handle_key(key(KeyCode::Enter), 10);
+        assert!(!view.searching);
+
+        let lines = view.lines();
+        assert_eq!(lines[0], HelpLine::Category("Macros"));
+        assert!(lines
+            .iter()
+            .all(|l| !matches!(l, HelpLine::Category(c) if *c != "Macros")));
+
+        // Esc clears the search, then closes
+        assert_eq!(view.handle_key(key(KeyCode::Esc), 10), HelpKey::Handled);
+        assert!(view.query.is_empty());
+        assert_eq!(view.handle_key(key(KeyCode::Esc), 10), HelpKey::Close);
+    }
+
+    #[test]
+    fn test_scroll_is_clamped() {
+        let mut view = HelpView::default();
+        view.handle_key(key(KeyCode::Char('k')), 10);
+        assert_eq!(view.scroll, 0);
+        view.handle_key(key(KeyCode::PageDown), 10);
+        assert_eq!(view.scroll, 10);
+        view.handle_key(key(KeyCode::Char('G')), 10);
+        assert_eq!(view.scroll, view.lines().len() - 1);
+        view.handle_key(key(KeyCode::Char('j')), 10);
+        assert_eq!(view.scroll, view.lines().len() - 1);
+        assert_eq!(view.handle_key(key(KeyCode::Char('x')), 10), HelpKey::Close);
+    }
+}
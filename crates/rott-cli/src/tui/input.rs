@@ -0,0 +1,176 @@
+//! Single-line text input shared by the TUI's input widgets
+//!
+//! The cursor moves over grapheme clusters rather than bytes or chars, so
+//! CJK text, emoji, and combining marks are edited as the user sees them.
+//! Cursor placement on screen uses display width, since wide glyphs take up
+//! two terminal cells.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// An editable line of text with a cursor
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextInput {
+    value: String,
+    /// Byte offset of the cursor, always on a grapheme boundary
+    cursor: usize,
+}
+
+impl TextInput {
+    /// Create an empty input
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current text
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Whether the input is empty
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    /// Replace the text, placing the cursor at the end
+    pub fn set_value(&mut self, value: impl Into<String>) {
+        self.value = value.into();
+        self.cursor = self.value.len();
+    }
+
+    /// Clear the text
+    pub fn clear(&mut self) {
+        self.value.clear();
+        self.cursor = 0;
+    }
+
+    /// Insert a character at the cursor
+    pub fn insert(&mut self, c: char) {
+        self.value.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    /// Delete the grapheme before the cursor
+    ///
+    /// Returns whether anything was deleted.
+    pub fn backspace(&mut self) -> bool {
+        match self.prev_boundary() {
+            Some(start) => {
+                self.value.replace_range(start..self.cursor, "");
+                self.cursor = start;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Delete the grapheme under the cursor
+    ///
+    /// Returns whether anything was deleted.
+    pub fn delete(&mut self) -> bool {
+        match self.next_boundary() {
+            Some(end) => {
+                self.value.replace_range(self.cursor..end, "");
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Move the cursor one grapheme left
+    pub fn move_left(&mut self) {
+        if let Some(start) = self.prev_boundary() {
+            self.cursor = start;
+        }
+    }
+
+    /// Move the cursor one grapheme right
+    pub fn move_right(&mut self) {
+        if let Some(end) = self.next_boundary() {
+            self.cursor = end;
+        }
+    }
+
+    /// Move the cursor to the start
+    pub fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    /// Move the cursor to the end
+    pub fn move_end(&mut self) {
+        self.cursor = self.value.len();
+    }
+
+    /// Display width of the text before the cursor, in terminal cells
+    pub fn cursor_width(&self) -> usize {
+        self.value[..self.cursor].width()
+    }
+
+    fn prev_boundary(&self) -> Option<usize> {
+        self.value[..self.cursor]
+            .graphemes(true)
+            .next_back()
+            .map(|g| self.cursor - g.len())
+    }
+
+    fn next_boundary(&self) -> Option<usize> {
+        self.value[self.cursor..]
+            .graphemes(true)
+            .next()
+            .map(|g| self.cursor + g.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typed(text: &str) -> TextInput {
+        let mut input = TextInput::new();
+        for c in text.chars() {
+            input.insert(c);
+        }
+        input
+    }
+
+    #[test]
+    fn test_edit_multibyte_text() {
+        let mut input = typed("日本語");
+        input.move_left();
+        input.insert('x');
+        assert_eq!(input.value(), "日本x語");
+
+        input.backspace();
+        input.backspace();
+        assert_eq!(input.value(), "日語");
+
+        input.move_home();
+        input.delete();
+        assert_eq!(input.value(), "語");
+    }
+
+    #[test]
+    fn test_graphemes_move_as_one() {
+        // Family emoji (joined with ZWJ) and e + combining acute accent
+        let mut input = typed("a👨‍👩‍👧e\u{301}");
+        input.move_left();
+        input.move_left();
+        input.insert('|');
+        assert_eq!(input.value(), "a|👨‍👩‍👧e\u{301}");
+
+        input.move_end();
+        assert!(input.backspace());
+        assert_eq!(input.value(), "a|👨‍👩‍👧");
+    }
+
+    #[test]
+    fn test_cursor_width_counts_wide_glyphs() {
+        let mut input = typed("ab日本");
+        assert_eq!(input.cursor_width(), 6);
+        input.move_left();
+        assert_eq!(input.cursor_width(), 4);
+        input.move_home();
+        assert_eq!(input.cursor_width(), 0);
+        assert!(!input.backspace());
+    }
+}
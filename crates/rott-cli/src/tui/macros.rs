@@ -0,0 +1,234 @@
+//! Vim-style keyboard macros
+//!
+//! `q<reg>` starts recording into register `a`–`z`, `q` stops, and
+//! `[count]@<reg>` replays (`@@` repeats the last replayed register).
+//! Macros record keys as they reach the TUI's key handler, in every input
+//! mode, so a replay goes through exactly the same path as typing: "tag,
+//! archive, next" works on whatever link is selected when it runs.
+//!
+//! A `q` that isn't followed by a register still quits, once the next key
+//! arrives or after a short pause.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// How long a lone `q` waits for a register before quitting
+const PENDING_QUIT_DELAY: Duration = Duration::from_millis(500);
+
+/// Keys replayed in one go, at most (stops macros that replay themselves)
+pub const MAX_REPLAY_KEYS: usize = 10_000;
+
+/// What the macro layer did with a key
+#[derive(Debug, PartialEq, Eq)]
+pub enum MacroKey {
+    /// Not a macro key; handle it normally
+    Pass,
+    /// Consumed (a pending `q`/`@`, or a count digit)
+    Consumed,
+    /// Started recording into a register
+    Started(char),
+    /// Stopped recording; the register and number of keys recorded
+    Stopped(char, usize),
+    /// Replay these keys
+    Replay(Vec<KeyEvent>),
+    /// Nothing recorded in this register
+    Empty(char),
+    /// A `q` not followed by a register
+    Quit,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Pending {
+    /// `q` pressed, waiting for a register
+    Record(Instant),
+    /// `@` pressed, waiting for a register
+    Replay,
+}
+
+/// Macro registers and recording state
+#[derive(Debug, Default)]
+pub struct Macros {
+    registers: HashMap<char, Vec<KeyEvent>>,
+    recording: Option<(char, Vec<KeyEvent>)>,
+    pending: Option<Pending>,
+    count: Option<usize>,
+    last_replayed: Option<char>,
+}
+
+impl Macros {
+    /// Create empty registers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register being recorded into, if any
+    pub fn recording(&self) -> Option<char> {
+        self.recording.as_ref().map(|(register, _)| *register)
+    }
+
+    /// Handle a key in normal mode
+    pub fn handle_key(&mut self, key: KeyEvent) -> MacroKey {
+        let register = match key.code {
+            KeyCode::Char(c) if is_register(c) && no_modifiers(key) => Some(c),
+            _ => None,
+        };
+
+        match self.pending.take() {
+            Some(Pending::Record(_)) => {
+                return match register {
+                    Some(register) => {
+                        self.recording = Some((register, Vec::new()));
+                        MacroKey::Started(register)
+                    }
+                    None => MacroKey::Quit,
+                };
+            }
+            Some(Pending::Replay) => {
+                let count = self.count.take().unwrap_or(1);
+                let register = match key.code {
+                    KeyCode::Char('@') => self.last_replayed,
+                    _ => register,
+                };
+                let Some(register) = register else {
+                    return MacroKey::Consumed;
+                };
+                return match self.registers.get(&register) {
+                    Some(keys) if !keys.is_empty() => {
+                        self.last_replayed = Some(register);
+                        MacroKey::Replay(keys.repeat(count))
+                    }
+                    _ => MacroKey::Empty(register),
+                };
+            }
+            None => {}
+        }
+
+        if !no_modifiers(key) {
+            self.count = None;
+            return MacroKey::Pass;
+        }
+
+        match key.code {
+            KeyCode::Char('q') => match self.recording.take() {
+                Some((register, keys)) => {
+                    let len = keys.len();
+                    self.registers.insert(register, keys);
+                    MacroKey::Stopped(register, len)
+                }
+                None => {
+                    self.pending = Some(Pending::Record(Instant::now()));
+                    MacroKey::Consumed
+                }
+            },
+            KeyCode::Char('@') => {
+                self.pending = Some(Pending::Replay);
+                MacroKey::Consumed
+            }
+            KeyCode::Char(c @ '0'..='9') if c != '0' || self.count.is_some() => {
+                let digit = c.to_digit(10).unwrap_or(0) as usize;
+                let count = self.count.unwrap_or(0).saturating_mul(10) + digit;
+                self.count = Some(count.min(MAX_REPLAY_KEYS));
+                MacroKey::Consumed
+            }
+            _ => {
+                self.count = None;
+                MacroKey::Pass
+            }
+        }
+    }
+
+    /// Record a key handled by the app (if recording)
+    pub fn record(&mut self, key: KeyEvent) {
+        if let Some((_, keys)) = self.recording.as_mut() {
+            keys.push(key);
+        }
+    }
+
+    /// Whether a lone `q` has waited long enough to mean "quit"
+    ///
+    /// Clears the pending `q` when it returns true.
+    pub fn pending_quit_expired(&mut self) -> bool {
+        match self.pending {
+            Some(Pending::Record(at)) if at.elapsed() >= PENDING_QUIT_DELAY => {
+                self.pending = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn is_register(c: char) -> bool {
+    c.is_ascii_lowercase()
+}
+
+fn no_modifiers(key: KeyEvent) -> bool {
+    key.modifiers.difference(KeyModifiers::SHIFT).is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    fn record(macros: &mut Macros, keys: &str) {
+        for c in keys.chars() {
+            if macros.handle_key(key(c)) == MacroKey::Pass {
+                macros.record(key(c));
+            }
+        }
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let mut macros = Macros::new();
+        assert_eq!(macros.handle_key(key('q')), MacroKey::Consumed);
+        assert_eq!(macros.handle_key(key('a')), MacroKey::Started('a'));
+        assert_eq!(macros.recording(), Some('a'));
+
+        record(&mut macros, "tj");
+        assert_eq!(macros.handle_key(key('q')), MacroKey::Stopped('a', 2));
+        assert_eq!(macros.recording(), None);
+
+        assert_eq!(macros.handle_key(key('@')), MacroKey::Consumed);
+        assert_eq!(
+            macros.handle_key(key('a')),
+            MacroKey::Replay(vec![key('t'), key('j')])
+        );
+
+        // Count and @@
+        record(&mut macros, "3@");
+        assert_eq!(
+            macros.handle_key(key('@')),
+            MacroKey::Replay([key('t'), key('j')].repeat(3))
+        );
+    }
+
+    #[test]
+    fn test_lone_q_quits() {
+        let mut macros = Macros::new();
+        macros.handle_key(key('q'));
+        assert_eq!(
+            macros.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)),
+            MacroKey::Quit
+        );
+
+        macros.handle_key(key('q'));
+        assert!(!macros.pending_quit_expired());
+        macros.pending = Some(Pending::Record(Instant::now() - PENDING_QUIT_DELAY));
+        assert!(macros.pending_quit_expired());
+    }
+
+    #[test]
+    fn test_empty_register() {
+        let mut macros = Macros::new();
+        macros.handle_key(key('@'));
+        assert_eq!(macros.handle_key(key('b')), MacroKey::Empty('b'));
+        assert_eq!(macros.handle_key(key('j')), MacroKey::Pass);
+    }
+}
@@ -14,8 +14,8 @@
 //! - j/k or ↑/↓: Move selection up/down
 //! - h/l or ←/→: Switch focus between panes
 //! - Tab: Cycle through panes
-//! - Enter: Select filter / Open link in browser
-//! - q: Quit
+//! - Enter: Select filter / Open link (in the browser unless an opener is configured)
+//! - q: Quit (q<reg> records a macro, @<reg> replays it)
 //!
 //! ## Commands
 //!
@@ -24,39 +24,73 @@
 //! - n: Add note
 //! - e: Edit link
 //! - d: Delete link
-//! - u: Undo delete
+//! - u: Undo the last edit
+//! - Ctrl+R: Redo
 //! - /: Filter current view
 //! - :: Command mode
-
+//! - :tour: Guided tour (shown once after `rott init --demo`)
+//! - :read / :done: Start/end a reading session (:stats summarizes the week)
+//! - :triage: Tag the Untagged links with 1-9 (bound to tags), Esc ends
+//! - :describe <text>: Describe the tag being filtered by (shown above its links)
+//! - :collection [name]: Switch to another collection (no name lists them)
+//! - v: Read the link's archived text in the reader, resuming where
+//!   reading stopped (on any device)
+//! - m / :more: Load more notes of a link with many (Detail pane)
+//! - f/F, o, S: Focus, open, or save a URL mentioned in the selected note
+//!   (Detail pane; saving fills in `:add-related <url>` to confirm)
+
+mod announce;
 mod app;
+mod grouping;
+mod help;
+mod input;
+mod macros;
+mod reader;
 mod setup;
 mod sync;
+mod tour;
 mod ui;
 
+use std::collections::VecDeque;
 use std::fs::File;
 
 use anyhow::Result;
 use crossterm::{
     cursor,
-    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
 use ratatui::prelude::*;
-use rott_core::{Config, Identity, Store};
+use rott_core::{Config, Identity, ReadOnlyError, Store};
 use std::io::stdout;
 use tracing::info;
 use tracing_subscriber::EnvFilter;
 
 use app::{App, CommandResult, CommandType, EditorTask, InputMode, SyncIndicator};
-use rott_core::sync::{PersistentSyncHandle, SyncCommand, SyncTaskEvent};
+use help::HelpKey;
+use macros::{MacroKey, MAX_REPLAY_KEYS};
+use reader::ReaderKey;
+use rott_core::sync::{
+    ConnectionStatus, PeerActivity, PersistentSyncHandle, SyncCommand, SyncTaskEvent,
+};
+use rott_core::template::{link_vars, render};
 use setup::{SetupResult, SetupWizard};
 
 use crate::editor;
+use crate::hypothesis;
 use crate::metadata;
+use crate::profile::StartupProfile;
 
 /// Run the TUI application
-pub async fn run(config_path: Option<&std::path::PathBuf>) -> Result<()> {
+///
+/// With `trace_sync`, the real-time sync connection is traced as if
+/// `trace_sync` were set in the config.
+pub async fn run(
+    config_path: Option<&std::path::PathBuf>,
+    trace_sync: bool,
+    mut profile: StartupProfile,
+) -> Result<()> {
     // Load config first (respecting CLI override)
     let config = Config::load_with_cli_override(config_path)?;
 
@@ -146,8 +180,11 @@ pub async fn run(config_path: Option<&std::path::PathBuf>) -> Result<()> {
     }
 
     // Now open the store normally (reload config in case wizard modified it)
-    let config = Config::load_with_cli_override(config_path)?;
+    let mut config = Config::load_with_cli_override(config_path)?;
+    config.trace_sync |= trace_sync;
+    profile.mark("config");
     let mut store = Store::open_with_config(config.clone())?;
+    profile.mark("load document");
 
     // Initialize TUI logging (file-based, only if ROTT_LOG is set)
     init_tui_logging(&config);
@@ -159,6 +196,7 @@ pub async fn run(config_path: Option<&std::path::PathBuf>) -> Result<()> {
 
     // Create app
     let mut app = App::new(&store)?;
+    profile.mark("first query");
 
     // Start sync if enabled
     let sync_handle = if sync::is_sync_enabled(&config) {
@@ -174,13 +212,41 @@ pub async fn run(config_path: Option<&std::path::PathBuf>) -> Result<()> {
     // Apply initial filter (Favorites)
     app.apply_filter(&store)?;
 
+    // First launch after `rott init --demo`: show the tour once
+    let tour_marker = config.tour_marker_path();
+    if tour_marker.exists() {
+        let _ = std::fs::remove_file(&tour_marker);
+        app.start_tour();
+    }
+    app.load_detail_notes(&store);
+    app.load_archived_content(&store);
+    terminal.draw(|frame| ui::draw(frame, &app))?;
+    profile.mark("first draw");
+
     // Run app
     let result = run_app(&mut terminal, &mut app, &mut store, sync_handle).await;
 
+    // Quitting from the reader saves where reading got to
+    if app.reader.is_some() {
+        let size = terminal.size()?;
+        let (width, height) = ui::reader_page_size(size.width, size.height);
+        app.close_reader(&mut store, width, height);
+    }
+
+    // Quitting ends a reading session in progress
+    if app.reading.is_some() {
+        if let Err(e) = app.end_reading(&store) {
+            tracing::warn!("Failed to save reading session: {:#}", e);
+        }
+    }
+
     // Restore terminal
     disable_raw_mode()?;
     stdout().execute(LeaveAlternateScreen)?;
 
+    // Printed once the alternate screen is gone, so it stays visible
+    profile.report();
+
     result
 }
 
@@ -192,10 +258,20 @@ async fn run_app<B: Backend>(
 ) -> Result<()> {
     // Track if we need to push changes after this iteration
     let mut pending_push = false;
+    // The document being synced (`:collection` switches to another)
+    let mut synced_id = store.root_id();
 
     loop {
-        // Check for status message timeout
+        // Check for status message timeout and idle auto-lock
         app.check_status_timeout();
+        app.check_idle_lock();
+        if app.macros.pending_quit_expired() {
+            app.should_quit = true;
+        }
+        app.announce_focus_change();
+        app.load_more_links(store);
+        app.load_detail_notes(store);
+        app.load_archived_content(store);
 
         // Draw UI
         terminal.draw(|frame| ui::draw(frame, app))?;
@@ -217,6 +293,20 @@ async fn run_app<B: Backend>(
                     match event {
                         SyncTaskEvent::StatusChanged(status) => {
                             app.sync_status = sync::status_to_indicator(status);
+                            if status == ConnectionStatus::Disconnected {
+                                app.peers = PeerActivity::default();
+                                app.sync_progress = None;
+                            }
+                        }
+                        SyncTaskEvent::PeersChanged(activity) => {
+                            app.peers = activity;
+                        }
+                        SyncTaskEvent::Progress(progress) => {
+                            app.sync_progress = Some(progress);
+                        }
+                        SyncTaskEvent::Synced(last) => {
+                            app.sync_progress = None;
+                            app.last_sync = Some(last);
                         }
                         SyncTaskEvent::DocumentUpdated => {
                             // Remote changes received - save to disk and refresh UI
@@ -255,61 +345,34 @@ async fn run_app<B: Backend>(
                             continue;
                         }
 
-                        // If error modal is showing, any key dismisses it
-                        if app.has_error() {
-                            app.clear_error();
-                            continue;
-                        }
-
-                        // If help is showing, any key dismisses it
-                        if app.show_help {
-                            app.show_help = false;
-                            continue;
-                        }
-
-                        // If device panel is showing, handle its keys
-                        if app.show_device_panel {
-                            match key.code {
-                                KeyCode::Esc | KeyCode::Char('q') => {
-                                    app.show_device_panel = false;
-                                }
-                                KeyCode::Char('y') => {
-                                    // Copy root ID to clipboard
-                                    if setup::clipboard_copy(&app.device_info.root_id) {
-                                        app.set_status("Root document ID copied to clipboard".to_string());
-                                    } else {
-                                        app.set_status("Clipboard not available - copy manually".to_string());
-                                    }
-                                    app.show_device_panel = false;
-                                }
-                                KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                                    app.show_device_panel = false;
+                        // Replayed macro keys go through the same handler as typed keys
+                        let mut queue = VecDeque::from([key]);
+                        let mut replayed = 0;
+                        while let Some(key) = queue.pop_front() {
+                            let outcome = match handle_key(terminal, app, store, key).await {
+                                // Edits on a read-only device are refused, not fatal
+                                Err(e) if e.is::<ReadOnlyError>() => {
+                                    app.set_error(e.to_string());
+                                    KeyOutcome::Handled { needs_push: false }
                                 }
-                                _ => {}
-                            }
-                            continue;
-                        }
-
-                        // Handle based on input mode
-                        match app.input_mode {
-                            InputMode::Normal => {
-                                if let Some(needs_push) = handle_normal_mode(app, store, key.code, key.modifiers).await? {
-                                    if needs_push {
-                                        pending_push = true;
-                                    }
+                                outcome => outcome?,
+                            };
+                            match outcome {
+                                KeyOutcome::Handled { needs_push } => {
+                                    pending_push |= needs_push;
                                 }
-                            }
-                            InputMode::Command => {
-                                if let Some(needs_push) = handle_command_mode(terminal, app, store, key.code, key.modifiers).await? {
-                                    if needs_push {
-                                        pending_push = true;
+                                KeyOutcome::Replay(keys) => {
+                                    replayed += keys.len();
+                                    if replayed > MAX_REPLAY_KEYS {
+                                        app.set_error("Macro stopped: too many keys (does it replay itself?)");
+                                        break;
                                     }
+                                    queue.extend(keys);
                                 }
                             }
-                            InputMode::Filter => {
-                                if let Err(e) = handle_filter_mode(app, store, key.code) {
-                                    app.set_error(format!("Filter error: {}", e));
-                                }
+                            // A failing step stops the rest of the macro
+                            if app.has_error() || app.should_quit {
+                                break;
                             }
                         }
                     }
@@ -317,6 +380,21 @@ async fn run_app<B: Backend>(
             }
         }
 
+        // Sync the collection switched to instead
+        if store.root_id() != synced_id {
+            synced_id = store.root_id();
+            if let Some(handle) = sync_handle.take() {
+                let _ = handle.command_tx.send(SyncCommand::Shutdown).await;
+            }
+            sync_handle = sync::spawn_persistent_sync(store, store.config());
+            app.sync_status = if sync_handle.is_some() {
+                SyncIndicator::Syncing
+            } else {
+                SyncIndicator::Disabled
+            };
+            pending_push = false;
+        }
+
         if app.should_quit {
             // Shutdown sync task
             if let Some(handle) = sync_handle.take() {
@@ -329,6 +407,158 @@ async fn run_app<B: Backend>(
     Ok(())
 }
 
+/// Outcome of handling one key
+enum KeyOutcome {
+    /// Key handled; whether local changes need to be pushed
+    Handled { needs_push: bool },
+    /// A macro to replay
+    Replay(Vec<KeyEvent>),
+}
+
+/// Handle one key press
+///
+/// Every key that reaches the app, typed or replayed from a macro, goes
+/// through here, and keys dispatched to an input mode are what macros
+/// record.
+async fn handle_key<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    store: &mut Store,
+    key: KeyEvent,
+) -> Result<KeyOutcome> {
+    let handled = Ok(KeyOutcome::Handled { needs_push: false });
+
+    // Lock screen takes all input until unlocked
+    if app.locked {
+        handle_lock_mode(app, key.code, key.modifiers);
+        return handled;
+    }
+    app.record_activity();
+
+    // If error modal is showing, any key dismisses it
+    if app.has_error() {
+        app.clear_error();
+        return handled;
+    }
+
+    // The tour takes keys until it's finished or skipped
+    if app.tour.is_some() {
+        app.handle_tour_key(key.code);
+        return handled;
+    }
+
+    // Help takes keys for scrolling and search; others dismiss it
+    if app.show_help {
+        let page = ui::help_page_height(terminal.size()?.height);
+        if app.help.handle_key(key, page) == HelpKey::Close {
+            app.show_help = false;
+        }
+        return handled;
+    }
+
+    // The reader takes keys until it's closed
+    if let Some(ref mut reader) = app.reader {
+        let size = terminal.size()?;
+        let (width, height) = ui::reader_page_size(size.width, size.height);
+        if reader.handle_key(key, width, height) == ReaderKey::Close {
+            let needs_push = app.close_reader(store, width, height);
+            return Ok(KeyOutcome::Handled { needs_push });
+        }
+        return handled;
+    }
+
+    // If device panel is showing, handle its keys
+    if app.show_device_panel {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                app.show_device_panel = false;
+            }
+            KeyCode::Char('y') => {
+                // Copy root ID to clipboard
+                let root_id = app.device_info.root_id.clone();
+                if app.copy_to_clipboard(&root_id) {
+                    app.set_status("Root document ID copied to clipboard".to_string());
+                } else {
+                    app.set_status("Clipboard not available - copy manually".to_string());
+                }
+                app.show_device_panel = false;
+            }
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                app.show_device_panel = false;
+            }
+            _ => {}
+        }
+        return handled;
+    }
+
+    // Tag triage binds 1-9 to tags (ahead of macro counts); Esc ends it
+    if app.input_mode == InputMode::Normal && app.triage.is_some() {
+        match key.code {
+            KeyCode::Char(c @ '1'..='9') => {
+                app.macros.record(key);
+                let key = c.to_digit(10).unwrap_or(0) as usize;
+                let needs_push = match app.triage_tag(store, key) {
+                    Ok(tagged) => tagged,
+                    Err(e) => {
+                        app.set_error(format!("Failed to tag link: {}", e));
+                        false
+                    }
+                };
+                return Ok(KeyOutcome::Handled { needs_push });
+            }
+            KeyCode::Esc => {
+                app.macros.record(key);
+                app.end_triage();
+                return handled;
+            }
+            _ => {}
+        }
+    }
+
+    // Macro keys: q<reg> records, [count]@<reg> replays
+    if app.input_mode == InputMode::Normal {
+        match app.macros.handle_key(key) {
+            MacroKey::Pass => {}
+            MacroKey::Consumed => return handled,
+            MacroKey::Started(register) => {
+                app.set_status(format!("Recording @{} (q to stop)", register));
+                return handled;
+            }
+            MacroKey::Stopped(register, len) => {
+                app.set_status(format!("Recorded {} keys into @{}", len, register));
+                return handled;
+            }
+            MacroKey::Replay(keys) => return Ok(KeyOutcome::Replay(keys)),
+            MacroKey::Empty(register) => {
+                app.set_status(format!("Nothing recorded in @{}", register));
+                return handled;
+            }
+            MacroKey::Quit => {
+                app.should_quit = true;
+                return handled;
+            }
+        }
+    }
+    app.macros.record(key);
+
+    // Handle based on input mode
+    let needs_push = match app.input_mode {
+        InputMode::Normal => handle_normal_mode(app, store, key.code, key.modifiers)
+            .await?
+            .unwrap_or(false),
+        InputMode::Command => handle_command_mode(terminal, app, store, key.code, key.modifiers)
+            .await?
+            .unwrap_or(false),
+        InputMode::Filter => {
+            if let Err(e) = handle_filter_mode(app, store, key.code) {
+                app.set_error(format!("Filter error: {}", e));
+            }
+            false
+        }
+    };
+    Ok(KeyOutcome::Handled { needs_push })
+}
+
 /// Handle key events in normal mode
 /// Returns Some(true) if local changes need to be pushed, Some(false) if not, None for no action
 async fn handle_normal_mode(
@@ -364,10 +594,7 @@ async fn handle_normal_mode(
     }
 
     match code {
-        // Quit
-        KeyCode::Char('q') => {
-            app.should_quit = true;
-        }
+        // Quit (a lone q is handled by the macro layer, see macros.rs)
         KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
             app.should_quit = true;
         }
@@ -409,16 +636,82 @@ async fn handle_normal_mode(
             }
         }
 
-        // Space: toggle accordion (when in filters pane on TagsHeader)
+        // Space: toggle accordion (when in filters pane on a header)
+        // or a group (when on a group header in the items pane)
         KeyCode::Char(' ') => {
             if app.active_pane == app::ActivePane::Filters {
-                if let Some(app::Filter::TagsHeader) = app.current_filter() {
-                    app.toggle_tags_accordion();
-                }
+                app.toggle_selected_accordion();
+            } else if app.active_pane == app::ActivePane::Items && app.current_link().is_none() {
+                app.toggle_group();
+            }
+        }
+
+        // Collapse/expand the selected group, or all groups
+        KeyCode::Char('z') => {
+            app.toggle_group();
+        }
+        KeyCode::Char('Z') => {
+            app.toggle_all_groups();
+        }
+
+        // Notes (Detail pane): select with [ and ], reorder with K/J, pin with p,
+        // reply with r, edit with E, load more with m
+        KeyCode::Char('[') | KeyCode::Char(']') if app.active_pane == app::ActivePane::Detail => {
+            app.select_note(code == KeyCode::Char(']'));
+        }
+        // Sections of a long note: focus with { and }, expand or collapse with Enter
+        KeyCode::Char('{') | KeyCode::Char('}') if app.active_pane == app::ActivePane::Detail => {
+            app.focus_note_section(code == KeyCode::Char('}'));
+        }
+        KeyCode::Char('K') | KeyCode::Char('J') if app.active_pane == app::ActivePane::Detail => {
+            match app.move_selected_note(store, code == KeyCode::Char('K')) {
+                Ok(true) => return Ok(Some(true)), // Needs push
+                Ok(false) => {}
+                Err(e) => app.set_error(format!("Failed to move note: {}", e)),
+            }
+        }
+        KeyCode::Char('r')
+            if app.active_pane == app::ActivePane::Detail
+                && !modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            app.enter_command_mode(CommandType::Reply);
+        }
+        KeyCode::Char('E') if app.active_pane == app::ActivePane::Detail => {
+            app.enter_command_mode(CommandType::EditNote);
+        }
+        KeyCode::Char('m') if app.active_pane == app::ActivePane::Detail => {
+            if let Err(e) = app.load_more_notes(store) {
+                app.set_error(format!("Failed to load notes: {}", e));
+            }
+        }
+        // URLs in the selected note: focus with f/F, open with o, save with S
+        KeyCode::Char('f') | KeyCode::Char('F') if app.active_pane == app::ActivePane::Detail => {
+            app.focus_note_url(code == KeyCode::Char('f'));
+        }
+        KeyCode::Char('o') if app.active_pane == app::ActivePane::Detail => {
+            app.open_note_url();
+        }
+        KeyCode::Char('o') => {
+            if let Err(e) = app.cycle_sort(store) {
+                app.set_error(format!("Failed to sort links: {}", e));
+            }
+        }
+        KeyCode::Char('S') if app.active_pane == app::ActivePane::Detail => {
+            app.offer_save_note_url();
+        }
+        KeyCode::Char('p') if app.active_pane == app::ActivePane::Detail => {
+            match app.toggle_selected_note_pin(store) {
+                Ok(true) => return Ok(Some(true)), // Needs push
+                Ok(false) => {}
+                Err(e) => app.set_error(format!("Failed to pin note: {}", e)),
             }
         }
 
         // Command shortcuts
+        KeyCode::Char('v') => {
+            app.open_reader();
+        }
+
         KeyCode::Char('a') => {
             app.enter_command_mode(CommandType::Add);
         }
@@ -441,11 +734,16 @@ async fn handle_normal_mode(
                 return Ok(Some(true)); // Needs push
             }
         }
-        KeyCode::Char('u') => {
-            if let Err(e) = app.undo_delete(store) {
-                app.set_error(format!("Failed to undo delete: {}", e));
-            } else {
-                return Ok(Some(true)); // Needs push
+        KeyCode::Char('u') => match app.undo(store, false) {
+            Ok(true) => return Ok(Some(true)), // Needs push
+            Ok(false) => {}
+            Err(e) => app.set_error(format!("Failed to undo: {:#}", e)),
+        },
+        KeyCode::Char('r') if modifiers.contains(KeyModifiers::CONTROL) => {
+            match app.undo(store, true) {
+                Ok(true) => return Ok(Some(true)), // Needs push
+                Ok(false) => {}
+                Err(e) => app.set_error(format!("Failed to redo: {:#}", e)),
             }
         }
 
@@ -528,8 +826,17 @@ async fn handle_command_mode<B: Backend>(
 
             match result {
                 CommandResult::Done => {}
-                CommandResult::NeedMetadata(url) => {
-                    // Check for duplicate URL first (before slow metadata fetch)
+                CommandResult::NeedMetadata { url, related_to } => {
+                    // Check domain rules, URL policy, and duplicates first
+                    // (before slow metadata fetch)
+                    if let Some(rule) = store.domain_rules().blocking_rule(&url) {
+                        app.set_status(format!("Links from '{}' are blocked", rule));
+                        return Ok(Some(false));
+                    }
+                    if let Err(e) = store.validator().check_url(&url) {
+                        app.set_error(format!("Can't add link: {}", e));
+                        return Ok(Some(false));
+                    }
                     if let Ok(Some(existing)) = store.get_link_by_url(&url) {
                         app.set_status(format!("Link already exists: '{}'", existing.title));
                         return Ok(Some(false));
@@ -539,8 +846,20 @@ async fn handle_command_mode<B: Backend>(
                     app.is_loading = true;
                     terminal.draw(|frame| ui::draw(frame, app))?;
 
-                    let metadata = metadata::fetch_metadata(&url).await;
-                    match app.add_link(store, &url, Some(metadata)) {
+                    let metadata = metadata::fetch_metadata_cached(&url, store.config()).await;
+                    let mut notes =
+                        hypothesis::fetch_notes(store.config(), &rott_core::Link::new(&url)).await;
+                    // A URL saved from a note points back at the link it came from
+                    if let Some(source) =
+                        related_to.and_then(|id| store.get_link(id).ok().flatten())
+                    {
+                        notes.push(rott_core::Note::new(format!(
+                            "Saved from a note on \"{}\" ({})",
+                            source.title,
+                            &source.id.to_string()[..8]
+                        )));
+                    }
+                    match app.add_link(store, &url, Some(metadata), notes) {
                         Ok(_) => {
                             app.is_loading = false;
                             return Ok(Some(true)); // Needs push
@@ -561,19 +880,33 @@ async fn handle_command_mode<B: Backend>(
                     let mut needs_push = false;
 
                     match task {
-                        EditorTask::Note => {
-                            let content =
-                                match editor::edit_text("# Note\n\nEnter your note here...") {
-                                    Ok(c) => c,
-                                    Err(e) => {
-                                        // Re-enter TUI before showing error
-                                        enable_raw_mode()?;
-                                        stdout().execute(EnterAlternateScreen)?;
-                                        terminal.clear()?;
-                                        app.set_error(format!("Editor failed: {}", e));
-                                        return Ok(Some(false));
-                                    }
-                                };
+                        EditorTask::Note | EditorTask::Reply => {
+                            let reply = task == EditorTask::Reply;
+                            // Start from the configured note template, if there is one
+                            let template = match (&store.config().note_template, app.current_link())
+                            {
+                                (Some(template), Some(link)) => render(
+                                    template,
+                                    &link_vars(&link.url, &link.title, chrono::Local::now()),
+                                )
+                                .ok(),
+                                _ => None,
+                            };
+                            let initial = format!(
+                                "# Note\n\n{}",
+                                template.as_deref().unwrap_or("Enter your note here...")
+                            );
+                            let content = match editor::edit_text(&initial) {
+                                Ok(c) => c,
+                                Err(e) => {
+                                    // Re-enter TUI before showing error
+                                    enable_raw_mode()?;
+                                    stdout().execute(EnterAlternateScreen)?;
+                                    terminal.clear()?;
+                                    app.set_error(format!("Editor failed: {}", e));
+                                    return Ok(Some(false));
+                                }
+                            };
                             let body: String = content
                                 .lines()
                                 .filter(|line| {
@@ -591,7 +924,7 @@ async fn handle_command_mode<B: Backend>(
                             terminal.clear()?;
 
                             if !body.is_empty() {
-                                if let Err(e) = app.add_note_to_current(store, &body) {
+                                if let Err(e) = app.add_note_to_current(store, &body, reply) {
                                     app.set_error(format!("Failed to add note: {}", e));
                                 } else {
                                     needs_push = true;
@@ -600,6 +933,52 @@ async fn handle_command_mode<B: Backend>(
                                 app.set_status("Note cancelled (empty)".to_string());
                             }
                         }
+                        EditorTask::EditNote => {
+                            let Some(original) = app.selected_note().map(|n| n.body.clone()) else {
+                                enable_raw_mode()?;
+                                stdout().execute(EnterAlternateScreen)?;
+                                terminal.clear()?;
+                                app.set_status("No note selected".to_string());
+                                return Ok(Some(false));
+                            };
+                            let initial = format!(
+                                "<!-- Editing note on: {} -->\n\n{}",
+                                app.current_link().map_or("", |l| l.title.as_str()),
+                                original
+                            );
+                            let content = match editor::edit_text(&initial) {
+                                Ok(c) => c,
+                                Err(e) => {
+                                    // Re-enter TUI before showing error
+                                    enable_raw_mode()?;
+                                    stdout().execute(EnterAlternateScreen)?;
+                                    terminal.clear()?;
+                                    app.set_error(format!("Editor failed: {}", e));
+                                    return Ok(Some(false));
+                                }
+                            };
+                            let body: String = content
+                                .lines()
+                                .filter(|line| !line.starts_with("<!--"))
+                                .collect::<Vec<_>>()
+                                .join("\n")
+                                .trim()
+                                .to_string();
+
+                            enable_raw_mode()?;
+                            stdout().execute(EnterAlternateScreen)?;
+                            terminal.clear()?;
+
+                            if body.is_empty() {
+                                app.set_status("Edit cancelled (empty)".to_string());
+                            } else if body == original.trim() {
+                                app.set_status("Note unchanged".to_string());
+                            } else if let Err(e) = app.edit_selected_note(store, &body) {
+                                app.set_error(format!("Failed to update note: {}", e));
+                            } else {
+                                needs_push = true;
+                            }
+                        }
                         EditorTask::EditLink => {
                             if let Some(link) = app.current_link() {
                                 let template = format!(
@@ -670,6 +1049,15 @@ async fn handle_command_mode<B: Backend>(
         KeyCode::Right => {
             app.cursor_right();
         }
+        KeyCode::Delete => {
+            app.delete_char_forward();
+        }
+        KeyCode::Home => {
+            app.cursor_home();
+        }
+        KeyCode::End => {
+            app.cursor_end();
+        }
 
         _ => {}
     }
@@ -677,6 +1065,22 @@ async fn handle_command_mode<B: Backend>(
     Ok(Some(false))
 }
 
+/// Handle key events on the privacy lock screen
+fn handle_lock_mode(app: &mut App, key: KeyCode, modifiers: KeyModifiers) {
+    match key {
+        KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+            app.should_quit = true;
+        }
+        KeyCode::Enter => app.try_unlock(),
+        KeyCode::Backspace => {
+            app.lock_input.pop();
+        }
+        KeyCode::Esc => app.lock_input.clear(),
+        KeyCode::Char(c) => app.lock_input.push(c),
+        _ => {}
+    }
+}
+
 /// Handle key events in filter mode
 fn handle_filter_mode(app: &mut App, store: &Store, code: KeyCode) -> Result<()> {
     match code {
@@ -706,6 +1110,15 @@ fn handle_filter_mode(app: &mut App, store: &Store, code: KeyCode) -> Result<()>
         KeyCode::Right => {
             app.cursor_right();
         }
+        KeyCode::Delete => {
+            app.delete_char_forward();
+        }
+        KeyCode::Home => {
+            app.cursor_home();
+        }
+        KeyCode::End => {
+            app.cursor_end();
+        }
 
         _ => {}
     }
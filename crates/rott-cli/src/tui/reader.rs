@@ -0,0 +1,211 @@
+//! The reader
+//!
+//! `v` opens the selected link's archived page text (see
+//! [`crate::content`]) full screen, wrapped to the terminal. It scrolls
+//! like the help overlay (`j`/`k`, `Space`/`PgDn`, `PgUp`, `g`/`G`), and
+//! `q`, `Esc` or `v` close it.
+//!
+//! Where reading stopped is saved to the link when the reader closes (as a
+//! line of the text, so it doesn't depend on the terminal's width) and
+//! synced, so reading resumes there on any device. The Items pane shows a
+//! progress bar on links read part way.
+
+use chrono::Utc;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use rott_core::{Link, ReadPosition};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+use uuid::Uuid;
+
+/// What the reader did with a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReaderKey {
+    /// Scrolled (or ignored the key)
+    Handled,
+    /// Close the reader
+    Close,
+}
+
+/// A link's archived text being read
+#[derive(Debug, Clone)]
+pub struct Reader {
+    /// The link being read
+    pub link_id: Uuid,
+    /// The link's title, shown above the text
+    pub title: String,
+    /// The text's lines
+    lines: Vec<String>,
+    /// The line at the top of the screen
+    line: usize,
+    /// Rows of that line scrolled past (when it wraps)
+    offset: usize,
+}
+
+impl Reader {
+    /// Open a link's text where reading stopped last time
+    ///
+    /// A text read to the end starts over from the top.
+    pub fn open(link: &Link, text: &str) -> Self {
+        let lines: Vec<String> = text.lines().map(str::to_string).collect();
+        let line = link
+            .read_position
+            .filter(|position| !position.is_finished())
+            .map_or(0, |position| position.line_in(lines.len()))
+            .min(lines.len().saturating_sub(1));
+        Self {
+            link_id: link.id,
+            title: link.title.clone(),
+            lines,
+            line,
+            offset: 0,
+        }
+    }
+
+    /// The text wrapped to `width` cells: each row, with the line it's part of
+    pub fn rows(&self, width: usize) -> Vec<(usize, String)> {
+        self.lines
+            .iter()
+            .enumerate()
+            .flat_map(|(index, line)| wrap(line, width).into_iter().map(move |row| (index, row)))
+            .collect()
+    }
+
+    /// Index of the row at the top of the screen
+    pub fn top_row(&self, rows: &[(usize, String)]) -> usize {
+        let first = rows.partition_point(|(line, _)| *line < self.line);
+        (first + self.offset).min(rows.len().saturating_sub(1))
+    }
+
+    /// Handle a key, for a screen of `width` by `height` cells
+    pub fn handle_key(&mut self, key: KeyEvent, width: usize, height: usize) -> ReaderKey {
+        let rows = self.rows(width);
+        let top = self.top_row(&rows);
+        let last = rows.len().saturating_sub(height.max(1));
+        let page = height.saturating_sub(1).max(1);
+        let row = match key.code {
+            KeyCode::Char('j') | KeyCode::Down => top + 1,
+            KeyCode::Char('k') | KeyCode::Up => top.saturating_sub(1),
+            KeyCode::PageDown | KeyCode::Char(' ') => top + page,
+            KeyCode::PageUp | KeyCode::Char('b') => top.saturating_sub(page),
+            KeyCode::Char('g') | KeyCode::Home => 0,
+            KeyCode::Char('G') | KeyCode::End => last,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return ReaderKey::Close
+            }
+            KeyCode::Char('q') | KeyCode::Char('v') | KeyCode::Esc => return ReaderKey::Close,
+            _ => return ReaderKey::Handled,
+        };
+        self.scroll_to(&rows, row.min(last));
+        ReaderKey::Handled
+    }
+
+    /// Scroll so `row` is at the top
+    fn scroll_to(&mut self, rows: &[(usize, String)], row: usize) {
+        let Some(&(line, _)) = rows.get(row) else {
+            return;
+        };
+        self.line = line;
+        self.offset = row - rows.partition_point(|(l, _)| *l < line);
+    }
+
+    /// Where reading has got to, on a screen of `width` by `height` cells
+    ///
+    /// Once the end of the text is on screen, it's read to the end.
+    pub fn position(&self, width: usize, height: usize) -> ReadPosition {
+        let rows = self.rows(width);
+        let at_end = self.top_row(&rows) + height >= rows.len();
+        ReadPosition {
+            line: if at_end { self.lines.len() } else { self.line },
+            lines: self.lines.len(),
+            at: Utc::now(),
+        }
+    }
+}
+
+/// Wrap a line to rows of at most `width` cells, breaking at spaces
+///
+/// Words longer than a row are broken wherever they reach its end. An
+/// empty line is one empty row.
+fn wrap(line: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut rows = Vec::new();
+    let mut row = String::new();
+    let mut row_width = 0;
+    for word in line.split_inclusive(' ') {
+        if row_width > 0 && row_width + word.trim_end().width() > width {
+            rows.push(row.trim_end().to_string());
+            row.clear();
+            row_width = 0;
+        }
+        for c in word.chars() {
+            let char_width = c.width().unwrap_or(0);
+            // Spaces at the end of a row are dropped rather than wrapped
+            if row_width + char_width > width && c != ' ' {
+                rows.push(std::mem::take(&mut row).trim_end().to_string());
+                row_width = 0;
+            }
+            row.push(c);
+            row_width += char_width;
+        }
+    }
+    rows.push(row.trim_end().to_string());
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("the quick brown fox", 10), ["the quick", "brown fox"]);
+        assert_eq!(wrap("abcdefghij klm", 4), ["abcd", "efgh", "ij", "klm"]);
+        assert_eq!(wrap("", 10), [""]);
+    }
+
+    #[test]
+    fn test_scroll_and_position() {
+        let text: Vec<String> = (0..20).map(|i| format!("line {}", i)).collect();
+        let mut link = Link::new("https://example.com");
+        let mut reader = Reader::open(&link, &text.join("\n"));
+        assert_eq!(reader.line, 0);
+
+        reader.handle_key(key(KeyCode::PageDown), 40, 5);
+        assert_eq!(reader.line, 4);
+        assert_eq!(reader.position(40, 5).line, 4);
+
+        // Once the end is on screen, the text counts as read
+        reader.handle_key(key(KeyCode::Char('G')), 40, 5);
+        assert_eq!(reader.line, 15);
+        assert!(reader.position(40, 5).is_finished());
+
+        // Resumes where reading stopped, and starts over once finished
+        link.read_position = Some(ReadPosition {
+            line: 7,
+            lines: 20,
+            at: Utc::now(),
+        });
+        assert_eq!(Reader::open(&link, &text.join("\n")).line, 7);
+        link.read_position = Some(reader.position(40, 5));
+        assert_eq!(Reader::open(&link, &text.join("\n")).line, 0);
+    }
+
+    #[test]
+    fn test_scroll_within_wrapped_line() {
+        let link = Link::new("https://example.com");
+        let mut reader = Reader::open(&link, "one two three four five six\nlast");
+        // The first line wraps to three rows of 10 cells
+        reader.handle_key(key(KeyCode::Down), 10, 2);
+        assert_eq!((reader.line, reader.offset), (0, 1));
+        reader.handle_key(key(KeyCode::Down), 10, 2);
+        assert_eq!((reader.line, reader.offset), (0, 2));
+        // The last row can't scroll past the bottom of the screen
+        reader.handle_key(key(KeyCode::Down), 10, 2);
+        assert_eq!((reader.line, reader.offset), (0, 2));
+        reader.handle_key(key(KeyCode::Char('k')), 10, 2);
+        assert_eq!((reader.line, reader.offset), (0, 1));
+    }
+}
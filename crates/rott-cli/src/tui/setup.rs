@@ -15,16 +15,17 @@ use ratatui::{
     widgets::{Block, Borders, Clear, Paragraph, Wrap},
     Frame,
 };
+use rott_core::desktop::SystemClipboard;
 use rott_core::{Config, DocumentId, Identity, Store};
 
+use super::input::TextInput;
+
 /// Setup wizard state
 pub struct SetupWizard {
     /// Current screen
     screen: Screen,
-    /// Input buffer for join flow
-    input: String,
-    /// Cursor position in input
-    cursor: usize,
+    /// Input line for join flow
+    input: TextInput,
     /// Error message to display
     error: Option<String>,
     /// Generated root document ID (for new identity flow)
@@ -33,6 +34,8 @@ pub struct SetupWizard {
     id_acknowledged: bool,
     /// Config for sync operations
     config: Config,
+    /// System clipboard, connected on first use
+    clipboard: Option<SystemClipboard>,
 }
 
 /// Wizard screens
@@ -63,12 +66,12 @@ impl SetupWizard {
     pub fn new(config: Config) -> Self {
         Self {
             screen: Screen::Welcome,
-            input: String::new(),
-            cursor: 0,
+            input: TextInput::new(),
             error: None,
             generated_id: None,
             id_acknowledged: false,
             config,
+            clipboard: None,
         }
     }
 
@@ -159,8 +162,8 @@ impl SetupWizard {
         match code {
             KeyCode::Char('y') | KeyCode::Char('Y') => {
                 // Copy to clipboard
-                if let Some(id) = &self.generated_id {
-                    if copy_to_clipboard(&id.to_string()) {
+                if let Some(id) = self.generated_id.as_ref().map(|id| id.to_string()) {
+                    if self.copy_to_clipboard(&id) {
                         self.error = Some("Copied to clipboard!".to_string());
                     } else {
                         self.error = Some("Clipboard not available - copy manually".to_string());
@@ -190,6 +193,24 @@ impl SetupWizard {
         Ok(None)
     }
 
+    /// Copy text to the clipboard, returning false if it's unavailable
+    fn copy_to_clipboard(&mut self, text: &str) -> bool {
+        self.clipboard()
+            .is_some_and(|clipboard| clipboard.copy(text).is_ok())
+    }
+
+    /// Read text from the clipboard
+    fn paste_from_clipboard(&mut self) -> Option<String> {
+        self.clipboard()?.paste().ok()
+    }
+
+    fn clipboard(&mut self) -> Option<&mut SystemClipboard> {
+        if self.clipboard.is_none() {
+            self.clipboard = SystemClipboard::new().ok();
+        }
+        self.clipboard.as_mut()
+    }
+
     async fn handle_join_input(
         &mut self,
         code: KeyCode,
@@ -199,12 +220,11 @@ impl SetupWizard {
             KeyCode::Esc => {
                 self.screen = Screen::Welcome;
                 self.input.clear();
-                self.cursor = 0;
                 self.error = None;
             }
             KeyCode::Enter => {
                 // Validate and attempt join
-                let input = self.input.trim();
+                let input = self.input.value().trim();
                 if input.is_empty() {
                     self.error = Some("Please enter a root document ID".to_string());
                     return Ok(None);
@@ -260,39 +280,36 @@ impl SetupWizard {
             }
             KeyCode::Char('v') if modifiers.contains(KeyModifiers::CONTROL) => {
                 // Paste from clipboard
-                if let Some(text) = paste_from_clipboard() {
-                    self.input = text.trim().to_string();
-                    self.cursor = self.input.len();
+                if let Some(text) = self.paste_from_clipboard() {
+                    self.input.set_value(text.trim());
                     self.error = None;
                 }
             }
             KeyCode::Char(c) => {
-                self.input.insert(self.cursor, c);
-                self.cursor += 1;
+                self.input.insert(c);
                 self.error = None;
             }
             KeyCode::Backspace => {
-                if self.cursor > 0 {
-                    self.cursor -= 1;
-                    self.input.remove(self.cursor);
+                if self.input.backspace() {
                     self.error = None;
                 }
             }
-            KeyCode::Left => {
-                if self.cursor > 0 {
-                    self.cursor -= 1;
+            KeyCode::Delete => {
+                if self.input.delete() {
+                    self.error = None;
                 }
             }
+            KeyCode::Left => {
+                self.input.move_left();
+            }
             KeyCode::Right => {
-                if self.cursor < self.input.len() {
-                    self.cursor += 1;
-                }
+                self.input.move_right();
             }
             KeyCode::Home => {
-                self.cursor = 0;
+                self.input.move_home();
             }
             KeyCode::End => {
-                self.cursor = self.input.len();
+                self.input.move_end();
             }
             _ => {}
         }
@@ -507,7 +524,7 @@ impl SetupWizard {
             Line::from(""),
             Line::from(vec![
                 Span::styled("> ", Style::default().fg(Color::Yellow)),
-                Span::raw(&self.input),
+                Span::raw(self.input.value()),
                 Span::styled("█", Style::default().add_modifier(Modifier::SLOW_BLINK)),
             ]),
             Line::from(""),
@@ -537,7 +554,7 @@ impl SetupWizard {
         frame.render_widget(footer, chunks[2]);
 
         // Set cursor position
-        let input_x = chunks[1].x + 3 + self.cursor as u16;
+        let input_x = chunks[1].x + 3 + self.input.cursor_width() as u16;
         let input_y = chunks[1].y + 4;
         frame.set_cursor_position((input_x, input_y));
     }
@@ -569,149 +586,6 @@ impl SetupWizard {
     }
 }
 
-/// Copy text to clipboard (platform-specific)
-pub fn copy_to_clipboard(text: &str) -> bool {
-    // Try using external clipboard tools
-    #[cfg(target_os = "linux")]
-    {
-        use std::io::Write;
-        use std::process::{Command, Stdio};
-
-        // Try xclip first, then xsel
-        for cmd in &["xclip", "xsel"] {
-            let args = if *cmd == "xclip" {
-                vec!["-selection", "clipboard"]
-            } else {
-                vec!["--clipboard", "--input"]
-            };
-
-            if let Ok(mut child) = Command::new(cmd)
-                .args(&args)
-                .stdin(Stdio::piped())
-                .stdout(Stdio::null())
-                .stderr(Stdio::null())
-                .spawn()
-            {
-                if let Some(mut stdin) = child.stdin.take() {
-                    if stdin.write_all(text.as_bytes()).is_ok() {
-                        let _ = stdin.flush();
-                        drop(stdin);
-                        if child.wait().map(|s| s.success()).unwrap_or(false) {
-                            return true;
-                        }
-                    }
-                }
-            }
-        }
-        false
-    }
-
-    #[cfg(target_os = "macos")]
-    {
-        use std::io::Write;
-        use std::process::{Command, Stdio};
-
-        if let Ok(mut child) = Command::new("pbcopy")
-            .stdin(Stdio::piped())
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .spawn()
-        {
-            if let Some(mut stdin) = child.stdin.take() {
-                if stdin.write_all(text.as_bytes()).is_ok() {
-                    let _ = stdin.flush();
-                    drop(stdin);
-                    return child.wait().map(|s| s.success()).unwrap_or(false);
-                }
-            }
-        }
-        false
-    }
-
-    #[cfg(target_os = "windows")]
-    {
-        // Windows clipboard handling via clip.exe
-        use std::io::Write;
-        use std::process::{Command, Stdio};
-
-        if let Ok(mut child) = Command::new("clip")
-            .stdin(Stdio::piped())
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .spawn()
-        {
-            if let Some(mut stdin) = child.stdin.take() {
-                if stdin.write_all(text.as_bytes()).is_ok() {
-                    let _ = stdin.flush();
-                    drop(stdin);
-                    return child.wait().map(|s| s.success()).unwrap_or(false);
-                }
-            }
-        }
-        false
-    }
-
-    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
-    {
-        let _ = text;
-        false
-    }
-}
-
-/// Paste from clipboard (platform-specific)
-fn paste_from_clipboard() -> Option<String> {
-    #[cfg(target_os = "linux")]
-    {
-        use std::process::Command;
-
-        // Try xclip first, then xsel
-        for (cmd, args) in &[
-            ("xclip", vec!["-selection", "clipboard", "-o"]),
-            ("xsel", vec!["--clipboard", "--output"]),
-        ] {
-            if let Ok(output) = Command::new(cmd).args(args).output() {
-                if output.status.success() {
-                    return String::from_utf8(output.stdout).ok();
-                }
-            }
-        }
-        None
-    }
-
-    #[cfg(target_os = "macos")]
-    {
-        use std::process::Command;
-
-        if let Ok(output) = Command::new("pbpaste").output() {
-            if output.status.success() {
-                return String::from_utf8(output.stdout).ok();
-            }
-        }
-        None
-    }
-
-    #[cfg(target_os = "windows")]
-    {
-        use std::process::Command;
-
-        // PowerShell method for clipboard read
-        if let Ok(output) = Command::new("powershell")
-            .args(["-command", "Get-Clipboard"])
-            .output()
-        {
-            if output.status.success() {
-                return String::from_utf8(output.stdout).ok();
-            }
-        }
-        None
-    }
-
-    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
-    {
-        None
-    }
-}
-
 /// Helper to create a centered rect
 fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
     let popup_layout = Layout::default()
@@ -732,6 +606,3 @@ fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
         ])
         .split(popup_layout[1])[1]
 }
-
-// Re-export clipboard functions for use in device panel
-pub use copy_to_clipboard as clipboard_copy;
@@ -3,10 +3,12 @@
 //! Uses rott-core's persistent sync for real-time bidirectional sync.
 
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
 
 use rott_core::sync::{
     spawn_sync_task, ConnectionStatus, PersistentSyncConfig, PersistentSyncHandle, SyncState,
+    SyncTrace,
 };
 use rott_core::{Config, Store};
 
@@ -23,7 +25,7 @@ pub fn spawn_persistent_sync(store: &Store, config: &Config) -> Option<Persisten
     let sync_url = config.sync_url.as_ref()?;
 
     // Create sync state with persistence
-    let sync_state_path = config.data_dir.join("sync_state.json");
+    let sync_state_path = config.sync_state_path();
     let sync_state = SyncState::with_path(sync_state_path).unwrap_or_else(|_| SyncState::new());
 
     // Get shared document from store
@@ -33,6 +35,13 @@ pub fn spawn_persistent_sync(store: &Store, config: &Config) -> Option<Persisten
     let sync_config = PersistentSyncConfig {
         url: sync_url.clone(),
         doc_id: store.root_id(),
+        read_only: store.is_read_only(),
+        compression: config.sync_compression,
+        batch_delay: Duration::from_millis(config.sync_batch_ms),
+        // Empty if unknown, in which case others show the sync peer ID
+        device_name: config.device_label(),
+        last_sync_path: Some(config.last_sync_path()),
+        trace: trace(config, store),
         ..Default::default()
     };
 
@@ -44,6 +53,22 @@ pub fn spawn_persistent_sync(store: &Store, config: &Config) -> Option<Persisten
     ))
 }
 
+/// Start a trace of the connection, if asked to
+///
+/// A trace that can't be started is logged rather than keeping sync off.
+fn trace(config: &Config, store: &Store) -> Option<Arc<std::sync::Mutex<SyncTrace>>> {
+    if !config.trace_sync {
+        return None;
+    }
+    match SyncTrace::create(&config.data_dir, &store.root_id()) {
+        Ok(trace) => Some(Arc::new(std::sync::Mutex::new(trace))),
+        Err(e) => {
+            tracing::warn!("Couldn't start a sync trace: {:#}", e);
+            None
+        }
+    }
+}
+
 /// Convert core ConnectionStatus to TUI SyncIndicator
 pub fn status_to_indicator(status: ConnectionStatus) -> SyncIndicator {
     match status {
@@ -0,0 +1,155 @@
+//! The guided tour
+//!
+//! A short walk through the panes and main keys, shown on first launch after
+//! `rott init --demo` and any time with `:tour`. Each step can focus a pane,
+//! so the pane being described is highlighted while its card is shown.
+
+use crossterm::event::KeyCode;
+
+use super::app::ActivePane;
+
+/// One card of the tour
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TourStep {
+    pub title: &'static str,
+    pub text: &'static str,
+    /// Pane focused while this step is shown
+    pub pane: Option<ActivePane>,
+}
+
+const fn step(title: &'static str, text: &'static str, pane: Option<ActivePane>) -> TourStep {
+    TourStep { title, text, pane }
+}
+
+/// Every step, in order
+pub const STEPS: &[TourStep] = &[
+    step(
+        "Welcome to ROTT",
+        "ROTT keeps your links and notes on this device and syncs them to your others. \
+         The sample links are tagged 'demo'; delete them whenever you like.",
+        None,
+    ),
+    step(
+        "Filters",
+        "The left pane narrows the list: Favorites, Recent, Untagged, Unread, \
+         and links by tag or author. Enter applies a filter; Space expands Tags \
+         and Authors.",
+        Some(ActivePane::Filters),
+    ),
+    step(
+        "Links",
+        "The middle pane lists links. j/k move, Enter opens in your browser, \
+         a adds a link, t edits tags, and d deletes (u undoes).",
+        Some(ActivePane::Items),
+    ),
+    step(
+        "Details and notes",
+        "The right pane shows the selected link and its notes. n adds a note, \
+         [ and ] select notes, and p pins one to the top.",
+        Some(ActivePane::Detail),
+    ),
+    step(
+        "Search and commands",
+        "/ filters the current view as you type. : runs a command, such as \
+         :search author:kleppmann or :group tag.",
+        Some(ActivePane::Items),
+    ),
+    step(
+        "That's it",
+        "Press ? for every key, Ctrl+S to sync, and q to quit. \
+         Run :tour to see this again.",
+        None,
+    ),
+];
+
+/// What the tour did with a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TourKey {
+    /// Moved to another step (or stayed put)
+    Handled,
+    /// Finished or skipped
+    Close,
+}
+
+/// Position in the tour
+#[derive(Debug, Clone, Default)]
+pub struct Tour {
+    step: usize,
+}
+
+impl Tour {
+    /// Start at the first step
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The step being shown
+    pub fn current(&self) -> &'static TourStep {
+        &STEPS[self.step]
+    }
+
+    /// 1-based step number
+    pub fn number(&self) -> usize {
+        self.step + 1
+    }
+
+    /// Whether this is the last step
+    pub fn is_last(&self) -> bool {
+        self.step + 1 == STEPS.len()
+    }
+
+    /// Handle a key while the tour is shown
+    ///
+    /// Enter, Space, l, or → go forward (finishing on the last step); h or ←
+    /// go back; Esc or q skip the rest. Other keys are ignored so a stray
+    /// keypress doesn't end the tour.
+    pub fn handle_key(&mut self, code: KeyCode) -> TourKey {
+        match code {
+            KeyCode::Enter | KeyCode::Char(' ') | KeyCode::Char('l') | KeyCode::Right => {
+                if self.is_last() {
+                    return TourKey::Close;
+                }
+                self.step += 1;
+            }
+            KeyCode::Char('h') | KeyCode::Left => self.step = self.step.saturating_sub(1),
+            KeyCode::Esc | KeyCode::Char('q') => return TourKey::Close,
+            _ => {}
+        }
+        TourKey::Handled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tour_steps_through_and_closes() {
+        let mut tour = Tour::new();
+        assert_eq!(tour.number(), 1);
+        assert_eq!(tour.handle_key(KeyCode::Left), TourKey::Handled);
+        assert_eq!(tour.number(), 1);
+
+        assert_eq!(tour.handle_key(KeyCode::Char('x')), TourKey::Handled);
+        assert_eq!(tour.number(), 1);
+
+        tour.handle_key(KeyCode::Enter);
+        assert_eq!(tour.current().pane, Some(ActivePane::Filters));
+        tour.handle_key(KeyCode::Char('h'));
+        assert_eq!(tour.number(), 1);
+
+        for _ in 1..STEPS.len() {
+            assert_eq!(tour.handle_key(KeyCode::Right), TourKey::Handled);
+        }
+        assert!(tour.is_last());
+        assert_eq!(tour.handle_key(KeyCode::Enter), TourKey::Close);
+    }
+
+    #[test]
+    fn test_tour_can_be_skipped() {
+        let mut tour = Tour::new();
+        tour.handle_key(KeyCode::Enter);
+        assert_eq!(tour.handle_key(KeyCode::Esc), TourKey::Close);
+        assert_eq!(Tour::new().handle_key(KeyCode::Char('q')), TourKey::Close);
+    }
+}
@@ -8,14 +8,42 @@ use ratatui::{
     Frame,
 };
 
+use rott_core::collections;
+use rott_core::domains::extract_domain;
+use rott_core::favicons::{domain_color, initials, DOMAIN_COLORS};
+use rott_core::language::language_name;
+use rott_core::sections::{is_long, NoteSections};
+use rott_core::urls::find_urls;
+use rott_core::{Health, Link};
+
+use crate::content::CONTENT_ATTACHMENT;
+use crate::i18n::{t, tf};
+use crate::output::{human_size, notes_summary, sync_summary, time_ago};
+use crate::screenshot::screenshot_path;
+
 use super::app::{ActivePane, App, Filter, InputMode, SyncIndicator};
+use super::grouping::{sort_label, GroupBy, ListRow};
+use super::help::HelpLine;
+use super::reader::Reader;
 
 /// Main UI rendering function
 pub fn draw(frame: &mut Frame, app: &App) {
-    // Create vertical layout for status bar at the bottom
+    // The lock screen replaces everything so no data is visible
+    if app.locked {
+        draw_lock_screen(frame, app);
+        return;
+    }
+
+    // Create vertical layout for status bar at the bottom (with the triage
+    // legend above it while triaging)
+    let legend_height = if app.triage.is_some() { 1 } else { 0 };
     let outer_chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(legend_height),
+            Constraint::Length(1),
+        ])
         .split(frame.area());
 
     // Split the main area into three panes
@@ -36,16 +64,30 @@ pub fn draw(frame: &mut Frame, app: &App) {
     // Draw sync indicator in top-right corner
     draw_sync_indicator(frame, app);
 
+    if let Some(tags) = &app.triage {
+        draw_triage_legend(frame, tags, outer_chunks[1]);
+    }
+
     // Draw status bar or command input
     match app.input_mode {
-        InputMode::Normal => draw_status_bar(frame, app, outer_chunks[1]),
-        InputMode::Command => draw_command_input(frame, app, outer_chunks[1]),
-        InputMode::Filter => draw_filter_input(frame, app, outer_chunks[1]),
+        InputMode::Normal => draw_status_bar(frame, app, outer_chunks[2]),
+        InputMode::Command => draw_command_input(frame, app, outer_chunks[2]),
+        InputMode::Filter => draw_filter_input(frame, app, outer_chunks[2]),
+    }
+
+    // The reader covers the panes, leaving the status bar
+    if let Some(ref reader) = app.reader {
+        draw_reader(frame, reader, reader_area(frame.area()));
     }
 
     // Draw help overlay if visible
     if app.show_help {
-        draw_help_overlay(frame);
+        draw_help_overlay(frame, app);
+    }
+
+    // Draw the tour card beside the pane it describes
+    if app.tour.is_some() {
+        draw_tour_overlay(frame, app, &pane_chunks);
     }
 
     // Draw device panel if visible
@@ -68,17 +110,23 @@ fn draw_filters_pane(frame: &mut Frame, app: &App, area: Rect) {
         .iter()
         .map(|filter| {
             let name = match filter {
-                Filter::Favorites => "★ Favorites".to_string(),
-                Filter::Recent => "⏱ Recent".to_string(),
-                Filter::Untagged => "○ Untagged".to_string(),
+                Filter::Favorites => format!("★ {}", t("Favorites")),
+                Filter::Recent => format!("⏱ {}", t("Recent")),
+                Filter::Untagged => format!("○ {}", t("Untagged")),
+                Filter::Unread => format!("● {}", t("Unread")),
+                Filter::UnreadIn(language) => format!("    {}", language_name(language)),
+                Filter::NeedsAttention => format!("⚠ {}", t("Needs Attention")),
+                Filter::Expired => format!("⌛ {}", t("Expired")),
                 Filter::TagsHeader => {
-                    if app.tags_expanded {
-                        "▼ By Tag...".to_string()
-                    } else {
-                        "▶ By Tag...".to_string()
-                    }
+                    let marker = if app.tags_expanded { "▼" } else { "▶" };
+                    format!("{} {}", marker, t("By Tag..."))
                 }
                 Filter::ByTag(tag) => format!("    #{}", tag),
+                Filter::AuthorsHeader => {
+                    let marker = if app.authors_expanded { "▼" } else { "▶" };
+                    format!("{} {}", marker, t("By Author..."))
+                }
+                Filter::ByAuthor(author) => format!("    {}", author),
             };
 
             ListItem::new(name)
@@ -91,8 +139,14 @@ fn draw_filters_pane(frame: &mut Frame, app: &App, area: Rect) {
         Style::default()
     };
 
+    // Name the collection unless it's the default one
+    let heading = if app.device_info.collection == collections::DEFAULT_COLLECTION {
+        t("Filters").to_string()
+    } else {
+        format!("{} · {}", t("Filters"), app.device_info.collection)
+    };
     let block = Block::default()
-        .title(" Filters ")
+        .title(pane_title(app, &heading, is_active))
         .borders(Borders::ALL)
         .border_style(border_style);
 
@@ -104,9 +158,12 @@ fn draw_filters_pane(frame: &mut Frame, app: &App, area: Rect) {
         Style::default().add_modifier(Modifier::REVERSED)
     };
 
-    let list = List::new(items)
-        .block(block)
-        .highlight_style(highlight_style);
+    let list = with_selection_marker(
+        app,
+        List::new(items)
+            .block(block)
+            .highlight_style(highlight_style),
+    );
 
     let mut state = ListState::default();
     state.select(Some(app.filter_index));
@@ -118,34 +175,109 @@ fn draw_filters_pane(frame: &mut Frame, app: &App, area: Rect) {
 fn draw_items_pane(frame: &mut Frame, app: &App, area: Rect) {
     let is_active = app.active_pane == ActivePane::Items;
 
+    // A described tag gets its description above its links, like a topic page
+    let area = match app.tag_description {
+        Some(ref description) if area.height > 8 => {
+            let height = (description.lines().count() as u16 + 2).min(area.height / 3);
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(height), Constraint::Min(0)])
+                .split(area);
+            let blurb = Paragraph::new(description.as_str())
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().add_modifier(Modifier::DIM)),
+                )
+                .wrap(Wrap { trim: false });
+            frame.render_widget(blurb, chunks[0]);
+            chunks[1]
+        }
+        _ => area,
+    };
+
+    let now = chrono::Utc::now();
     let items: Vec<ListItem> = app
-        .links
+        .rows
         .iter()
-        .map(|link| {
-            // Truncate title if too long
-            let max_len = area.width.saturating_sub(4) as usize;
-            let title = if link.title.len() > max_len {
-                format!("{}…", &link.title[..max_len.saturating_sub(1)])
-            } else {
-                link.title.clone()
+        .map(|row| {
+            let link = match row {
+                ListRow::Header {
+                    name,
+                    count,
+                    collapsed,
+                } => {
+                    let marker = if *collapsed { "▸" } else { "▾" };
+                    return ListItem::new(Line::from(Span::styled(
+                        format!("{} {} ({})", marker, name, count),
+                        Style::default()
+                            .fg(Color::Cyan)
+                            .add_modifier(Modifier::BOLD),
+                    )));
+                }
+                ListRow::Link(index) => &app.links[*index],
             };
 
-            // Truncate URL
-            let url_max = max_len.saturating_sub(2);
-            let url = if link.url.len() > url_max {
-                format!("{}…", &link.url[..url_max.saturating_sub(1)])
+            // Truncate title if too long
+            let max_len = area.width.saturating_sub(4) as usize;
+            let highlights = app
+                .filter_highlights
+                .get(&link.id)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let mut spans = title_spans(&link.title, max_len, highlights);
+
+            // Truncate URL (after the site's initials, standing in for its
+            // favicon, and before the reading progress)
+            let badge = domain_badge(&link.url);
+            let progress = read_progress(app, link);
+            let progress_width = progress.as_ref().map_or(0, |span| span.width());
+            let url_max = max_len
+                .saturating_sub(if badge.is_some() { 5 } else { 2 })
+                .saturating_sub(progress_width);
+            let display_url = link.display_url();
+            let url = if display_url.len() > url_max {
+                format!("{}…", &display_url[..url_max.saturating_sub(1)])
             } else {
-                link.url.clone()
+                display_url.to_string()
             };
 
-            let content = Line::from(vec![Span::styled(title, Style::default())]);
+            if link.paywalled {
+                // A lock reads poorly (or not at all) with a screen reader
+                let marker = if app.accessible {
+                    " [paywall]"
+                } else {
+                    " 🔒"
+                };
+                spans.push(Span::styled(marker, Style::default().fg(Color::Magenta)));
+            }
+            if link.content_changed {
+                spans.push(Span::styled(
+                    " [changed]",
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+            // Expired links are dimmed, which a screen reader can't convey
+            let expired = link.is_expired(now);
+            if expired && app.accessible {
+                spans.push(Span::raw(" [expired]"));
+            }
+            let content = Line::from(spans);
 
-            let url_line = Line::from(vec![Span::styled(
+            let mut url_spans: Vec<Span> = badge.into_iter().collect();
+            url_spans.push(Span::styled(
                 url,
                 Style::default().add_modifier(Modifier::DIM),
-            )]);
+            ));
+            url_spans.extend(progress);
+            let url_line = Line::from(url_spans);
 
-            ListItem::new(vec![content, url_line])
+            let item = ListItem::new(vec![content, url_line]);
+            if expired {
+                item.style(Style::default().add_modifier(Modifier::DIM))
+            } else {
+                item
+            }
         })
         .collect();
 
@@ -155,7 +287,14 @@ fn draw_items_pane(frame: &mut Frame, app: &App, area: Rect) {
         Style::default()
     };
 
-    let title = format!(" Items ({}) ", app.links.len());
+    // Lists loaded a page at a time count every link, not just those loaded
+    let count = app.links_total.unwrap_or(app.links.len());
+    let heading = match app.group_by {
+        GroupBy::None => tf("Items ({})", &[&count]),
+        group_by => tf("Items ({}) by {}", &[&count, &t(group_by.label())]),
+    };
+    let heading = format!("{} · {}", heading, t(sort_label(app.sort)));
+    let title = pane_title(app, &heading, is_active);
     let block = Block::default()
         .title(title)
         .borders(Borders::ALL)
@@ -169,18 +308,70 @@ fn draw_items_pane(frame: &mut Frame, app: &App, area: Rect) {
         Style::default().add_modifier(Modifier::REVERSED)
     };
 
-    let list = List::new(items)
-        .block(block)
-        .highlight_style(highlight_style);
+    let list = with_selection_marker(
+        app,
+        List::new(items)
+            .block(block)
+            .highlight_style(highlight_style),
+    );
 
     let mut state = ListState::default();
-    if !app.links.is_empty() {
-        state.select(Some(app.link_index));
+    if !app.rows.is_empty() {
+        state.select(Some(app.row_index));
     }
 
     frame.render_stateful_widget(list, area, &mut state);
 }
 
+/// Cells of the reading progress bar in the Items pane
+const PROGRESS_WIDTH: usize = 5;
+
+/// How far a link's archived text has been read in the reader, for links
+/// read part way
+fn read_progress(app: &App, link: &Link) -> Option<Span<'static>> {
+    let position = link
+        .read_position
+        .filter(|position| position.line > 0 && !position.is_finished())?;
+    let progress = position.progress();
+    if app.accessible {
+        return Some(Span::raw(format!(
+            " {}",
+            tf("[{}% read]", &[&((progress * 100.0).round() as usize)])
+        )));
+    }
+    let filled = ((progress * PROGRESS_WIDTH as f64).round() as usize).clamp(1, PROGRESS_WIDTH - 1);
+    Some(Span::styled(
+        format!(
+            " {}{}",
+            "▰".repeat(filled),
+            "▱".repeat(PROGRESS_WIDTH - filled)
+        ),
+        Style::default()
+            .fg(Color::Green)
+            .add_modifier(Modifier::DIM),
+    ))
+}
+
+/// Colors of the site initials in the Items pane, by [`domain_color`]
+const BADGE_COLORS: [Color; DOMAIN_COLORS] = [
+    Color::Cyan,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Red,
+];
+
+/// A link's site initials, colored by domain (terminals can't show favicons)
+fn domain_badge(url: &str) -> Option<Span<'static>> {
+    let domain = extract_domain(url)?;
+    let color = BADGE_COLORS[domain_color(&domain)];
+    Some(Span::styled(
+        format!("{:<2} ", initials(&domain)),
+        Style::default().fg(color).add_modifier(Modifier::BOLD),
+    ))
+}
+
 /// Draw the detail pane (right)
 fn draw_detail_pane(frame: &mut Frame, app: &App, area: Rect) {
     let is_active = app.active_pane == ActivePane::Detail;
@@ -192,30 +383,24 @@ fn draw_detail_pane(frame: &mut Frame, app: &App, area: Rect) {
     };
 
     let block = Block::default()
-        .title(" Detail ")
+        .title(pane_title(app, t("Detail"), is_active))
         .borders(Borders::ALL)
         .border_style(border_style);
 
     let content = if let Some(link) = app.current_link() {
         let mut lines = vec![
-            Line::from(vec![
-                Span::styled("Title: ", Style::default().add_modifier(Modifier::BOLD)),
-                Span::raw(&link.title),
-            ]),
+            Line::from(vec![label("Title"), Span::raw(&link.title)]),
             Line::from(""),
-            Line::from(vec![
-                Span::styled("URL: ", Style::default().add_modifier(Modifier::BOLD)),
-                Span::raw(&link.url),
-            ]),
+            Line::from(vec![label("URL"), Span::raw(link.display_url())]),
         ];
+        if link.canonical_url.is_some() {
+            lines.push(Line::from(vec![label("Saved as"), Span::raw(&link.url)]));
+        }
 
         // Description
         lines.push(Line::from(""));
         lines.push(Line::from(vec![
-            Span::styled(
-                "Description: ",
-                Style::default().add_modifier(Modifier::BOLD),
-            ),
+            label("Description"),
             Span::raw(link.description.as_deref().unwrap_or("-")),
         ]));
 
@@ -226,10 +411,15 @@ fn draw_detail_pane(frame: &mut Frame, app: &App, area: Rect) {
         } else {
             link.author.join(", ")
         };
-        lines.push(Line::from(vec![
-            Span::styled("Author: ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(author_str),
-        ]));
+        lines.push(Line::from(vec![label("Author"), Span::raw(author_str)]));
+
+        // Provenance
+        if let Some(ref via) = link.via {
+            lines.push(Line::from(vec![label("Via"), Span::raw(via.clone())]));
+        }
+        if let Some(ref source) = link.source_context {
+            lines.push(Line::from(vec![label("Source"), Span::raw(source.clone())]));
+        }
 
         // Tags
         lines.push(Line::from(""));
@@ -238,65 +428,267 @@ fn draw_detail_pane(frame: &mut Frame, app: &App, area: Rect) {
         } else {
             link.tags.join(", ")
         };
-        lines.push(Line::from(vec![
-            Span::styled("Tags: ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(tags_str),
-        ]));
+        lines.push(Line::from(vec![label("Tags"), Span::raw(tags_str)]));
 
         // Dates
         lines.push(Line::from(""));
+        if let Some(published) = link.published_at {
+            lines.push(Line::from(vec![
+                label("Published"),
+                Span::raw(published.format("%Y-%m-%d").to_string()),
+            ]));
+        }
+        if let Some(ref language) = link.language {
+            lines.push(Line::from(vec![
+                label("Language"),
+                Span::raw(language_name(language).to_string()),
+            ]));
+        }
+        if let Some(expires) = link.expires_at {
+            let style = if link.is_expired(chrono::Utc::now()) {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default()
+            };
+            lines.push(Line::from(vec![
+                label("Expires"),
+                Span::styled(expires.format("%Y-%m-%d").to_string(), style),
+            ]));
+        }
         lines.push(Line::from(vec![
-            Span::styled("Created: ", Style::default().add_modifier(Modifier::BOLD)),
+            label("Created"),
             Span::raw(link.created_at.format("%Y-%m-%d %H:%M").to_string()),
         ]));
         lines.push(Line::from(vec![
-            Span::styled("Updated: ", Style::default().add_modifier(Modifier::BOLD)),
+            label("Updated"),
             Span::raw(link.updated_at.format("%Y-%m-%d %H:%M").to_string()),
         ]));
+        if link.paywalled {
+            lines.push(Line::from(vec![
+                label("Access"),
+                Span::styled(
+                    t("paywall or login required"),
+                    Style::default().fg(Color::Magenta),
+                ),
+            ]));
+        }
+        if link.content_changed {
+            let changed = match link.content_checked_at {
+                Some(checked) => tf(
+                    "changed since saved (checked {})",
+                    &[&checked.format("%Y-%m-%d %H:%M")],
+                ),
+                None => t("changed since saved").to_string(),
+            };
+            lines.push(Line::from(vec![
+                label("Content"),
+                Span::styled(changed, Style::default().fg(Color::Yellow)),
+            ]));
+        }
+
+        let size = link.content_size();
+        if size > 0 {
+            lines.push(Line::from(vec![label("Size"), Span::raw(human_size(size))]));
+        }
+        if !link.attachments.is_empty() {
+            let names: Vec<_> = link.attachments.keys().map(String::as_str).collect();
+            lines.push(Line::from(vec![
+                label("Attachments"),
+                Span::raw(names.join(", ")),
+            ]));
+        }
+        if let Some(path) = screenshot_path(&app.attachments, link) {
+            lines.push(Line::from(vec![
+                label("Screenshot"),
+                Span::raw(path.display().to_string()),
+            ]));
+        }
+
+        let health = Health::of(link, chrono::Utc::now(), app.record_opens);
+        if health.needs_attention() {
+            let issues: Vec<_> = health.issues.iter().map(|i| i.label()).collect();
+            lines.push(Line::from(vec![
+                label("Health"),
+                Span::styled(
+                    format!("{} ({})", health.score, issues.join(", ")),
+                    Style::default().fg(Color::Yellow),
+                ),
+            ]));
+        }
 
         // Notes section with separator
         lines.push(Line::from(""));
         if link.notes.is_empty() {
             lines.push(Line::from(vec![Span::styled(
-                "── No notes ──",
+                format!("── {} ──", t("No notes")),
                 Style::default().add_modifier(Modifier::DIM),
             )]));
         } else {
             // Create separator line that fits width
-            let note_header = format!("── Notes ({}) ", link.notes.len());
-            let remaining = area.width.saturating_sub(note_header.len() as u16 + 2) as usize;
+            let note_header = format!("── {} ", tf("Notes ({})", &[&notes_summary(link)]));
+            let remaining = area
+                .width
+                .saturating_sub(note_header.chars().count() as u16 + 2)
+                as usize;
             let separator = format!("{}{}", note_header, "─".repeat(remaining));
             lines.push(Line::from(vec![Span::styled(
                 separator,
                 Style::default().add_modifier(Modifier::DIM),
             )]));
 
-            for note in &link.notes {
+            // Only the notes loaded so far (a page at a time) are drawn
+            let page = app
+                .detail_notes
+                .as_ref()
+                .filter(|page| page.link_id == link.id);
+            let notes = page.map_or(&[][..], |page| page.notes.as_slice());
+            let hit = app.note_hits.get(&link.id);
+            for (index, (note, depth)) in notes.iter().enumerate() {
+                let depth = *depth;
                 lines.push(Line::from(""));
-                let timestamp = note.created_at.format("%Y-%m-%d").to_string();
+                let mut timestamp = note.created_at.format("%Y-%m-%d").to_string();
+                if note.is_edited() {
+                    timestamp = tf("{}, edited", &[&timestamp]);
+                }
+                // Replies are indented under the note they reply to
+                let indent = "    ".repeat(depth);
+                let mut header = vec![Span::raw(indent.clone())];
+                if depth > 0 {
+                    header.push(Span::styled(
+                        if app.accessible { "reply: " } else { "↳ " },
+                        Style::default().add_modifier(Modifier::DIM),
+                    ));
+                }
+                // Selection marker for reordering (only while the pane is focused)
+                if is_active && index == app.note_index {
+                    header.push(Span::styled(
+                        "▶ ",
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ));
+                }
+                if note.pinned {
+                    header.push(Span::styled(
+                        "[pinned] ",
+                        Style::default().fg(Color::Yellow),
+                    ));
+                }
+                // The note the search or filter found this link by
+                if hit == Some(&note.id) {
+                    header.push(Span::styled(
+                        if app.accessible { "[match] " } else { "» " },
+                        Style::default()
+                            .fg(Color::Magenta)
+                            .add_modifier(Modifier::BOLD),
+                    ));
+                }
                 if let Some(title) = &note.title {
-                    lines.push(Line::from(vec![
-                        Span::styled(
-                            format!("[{}] ", timestamp),
-                            Style::default().add_modifier(Modifier::DIM),
-                        ),
-                        Span::styled(title, Style::default().add_modifier(Modifier::BOLD)),
-                    ]));
-                    // Show body indented below title
-                    for body_line in note.body.lines() {
-                        lines.push(Line::from(format!("  {}", body_line)));
-                    }
+                    header.push(Span::styled(
+                        format!("[{}] ", timestamp),
+                        Style::default().add_modifier(Modifier::DIM),
+                    ));
+                    header.push(Span::styled(
+                        title,
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ));
                 } else {
-                    lines.push(Line::from(vec![Span::styled(
+                    header.push(Span::styled(
                         format!("[{}]", timestamp),
                         Style::default().add_modifier(Modifier::DIM),
-                    )]));
-                    // Show body indented
+                    ));
+                }
+                lines.push(Line::from(header));
+                // Show body indented, with URLs marked (the focused one
+                // highlighted while the note is selected)
+                let selected = is_active && index == app.note_index;
+                let mut url_count = 0;
+                let sections = is_long(&note.body, app.note_section_threshold)
+                    .then(|| NoteSections::parse(&note.body))
+                    .filter(|parsed| !parsed.sections.is_empty());
+                let Some(parsed) = sections else {
                     for body_line in note.body.lines() {
-                        lines.push(Line::from(format!("  {}", body_line)));
+                        lines.push(note_line(app, &indent, body_line, selected, &mut url_count));
+                    }
+                    continue;
+                };
+
+                // A long note: its preamble, then collapsible sections
+                for body_line in parsed.preamble.lines() {
+                    lines.push(note_line(app, &indent, body_line, selected, &mut url_count));
+                }
+                for (section_index, section) in parsed.sections.iter().enumerate() {
+                    let expanded = app.expanded_sections.contains(&(note.id, section_index));
+                    let focused = selected && app.section_index == Some(section_index);
+                    let marker = match (expanded, app.accessible) {
+                        (true, false) => "▾",
+                        (false, false) => "▸",
+                        (true, true) => "[-]",
+                        (false, true) => "[+]",
+                    };
+                    let mut heading = format!("{}  {} {}", indent, marker, section.heading);
+                    if !expanded {
+                        heading.push_str(&format!(
+                            " {}",
+                            tf("({} lines)", &[&section.body.lines().count()])
+                        ));
+                    }
+                    let style = if focused {
+                        Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED)
+                    } else {
+                        Style::default().add_modifier(Modifier::BOLD)
+                    };
+                    lines.push(Line::from(Span::styled(heading, style)));
+                    // URLs are numbered across the whole body, shown or not
+                    url_count += find_urls(&section.heading).len();
+                    if expanded {
+                        for body_line in section.body.lines() {
+                            lines.push(note_line(
+                                app,
+                                &indent,
+                                body_line,
+                                selected,
+                                &mut url_count,
+                            ));
+                        }
+                    } else {
+                        url_count += section
+                            .body
+                            .lines()
+                            .map(|line| find_urls(line).len())
+                            .sum::<usize>();
                     }
                 }
             }
+            if let Some(page) = page.filter(|page| page.has_more()) {
+                lines.push(Line::from(""));
+                lines.push(Line::from(vec![Span::styled(
+                    format!(
+                        "── {} ──",
+                        tf(
+                            "{} more notes (m to load more)",
+                            &[&(page.total - page.notes.len())]
+                        )
+                    ),
+                    Style::default().add_modifier(Modifier::DIM),
+                )]));
+            }
+        }
+
+        // Archived page text, after the notes
+        let archived = app
+            .archived_content
+            .as_ref()
+            .filter(|(hash, _)| link.attachments.get(CONTENT_ATTACHMENT) == Some(hash));
+        if let Some((_, text)) = archived.filter(|(_, text)| !text.is_empty()) {
+            lines.push(Line::from(""));
+            let header = format!("── {} ", t("Archived page text"));
+            let remaining = area.width.saturating_sub(header.chars().count() as u16 + 2) as usize;
+            lines.push(Line::from(vec![Span::styled(
+                format!("{}{}", header, "─".repeat(remaining)),
+                Style::default().add_modifier(Modifier::DIM),
+            )]));
+            for text_line in text.lines() {
+                lines.push(Line::from(Span::raw(text_line)));
+            }
         }
 
         lines
@@ -304,7 +696,7 @@ fn draw_detail_pane(frame: &mut Frame, app: &App, area: Rect) {
         vec![
             Line::from(""),
             Line::from(vec![Span::styled(
-                "Select a link to view details",
+                t("Select a link to view details"),
                 Style::default().add_modifier(Modifier::DIM),
             )]),
         ]
@@ -318,14 +710,200 @@ fn draw_detail_pane(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(paragraph, area);
 }
 
+/// A line of a note's body, indented, with URLs marked (the focused one
+/// highlighted while the note is selected)
+///
+/// `url_count` is the number of URLs in the body before this line, and is
+/// advanced past this line's.
+fn note_line(
+    app: &App,
+    indent: &str,
+    body_line: &str,
+    selected: bool,
+    url_count: &mut usize,
+) -> Line<'static> {
+    let mut spans = vec![Span::raw(format!("{}  ", indent))];
+    let mut last = 0;
+    for range in find_urls(body_line) {
+        let focused = selected && app.url_index == Some(*url_count);
+        spans.push(Span::raw(body_line[last..range.start].to_string()));
+        if focused && app.accessible {
+            spans.push(Span::raw("[focused] "));
+        }
+        let style = if focused {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default()
+                .fg(Color::Blue)
+                .add_modifier(Modifier::UNDERLINED)
+        };
+        spans.push(Span::styled(body_line[range.clone()].to_string(), style));
+        last = range.end;
+        *url_count += 1;
+    }
+    spans.push(Span::raw(body_line[last..].to_string()));
+    Line::from(spans)
+}
+
+/// A bold field label ("Title: ") in the current locale
+fn label(name: &'static str) -> Span<'static> {
+    Span::styled(
+        format!("{}: ", t(name)),
+        Style::default().add_modifier(Modifier::BOLD),
+    )
+}
+
+/// Pane title, with a text focus marker in accessible mode
+fn pane_title(app: &App, name: &str, is_active: bool) -> String {
+    if app.accessible && is_active {
+        format!(" {} [focused] ", name)
+    } else {
+        format!(" {} ", name)
+    }
+}
+
+/// Mark the selected list item with a symbol in accessible mode
+fn with_selection_marker<'a>(app: &App, list: List<'a>) -> List<'a> {
+    if app.accessible {
+        list.highlight_symbol("> ")
+    } else {
+        list
+    }
+}
+
+/// A link title cut to `max_len` characters, with the characters matched by
+/// the filter highlighted
+fn title_spans(title: &str, max_len: usize, highlights: &[usize]) -> Vec<Span<'static>> {
+    let highlight = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    let truncated = title.chars().count() > max_len;
+    let shown = if truncated {
+        max_len.saturating_sub(1)
+    } else {
+        max_len
+    };
+
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_highlighted = false;
+    for (i, c) in title.chars().take(shown).enumerate() {
+        let highlighted = highlights.binary_search(&i).is_ok();
+        if highlighted != run_highlighted && !run.is_empty() {
+            let style = if run_highlighted {
+                highlight
+            } else {
+                Style::default()
+            };
+            spans.push(Span::styled(std::mem::take(&mut run), style));
+        }
+        run_highlighted = highlighted;
+        run.push(c);
+    }
+    if truncated {
+        if run_highlighted {
+            spans.push(Span::styled(std::mem::take(&mut run), highlight));
+        }
+        run.push('…');
+        run_highlighted = false;
+    }
+    let style = if run_highlighted {
+        highlight
+    } else {
+        Style::default()
+    };
+    spans.push(Span::styled(run, style));
+    spans
+}
+
+/// Where the reader is drawn: everything but the status bar
+fn reader_area(area: Rect) -> Rect {
+    Rect {
+        height: area.height.saturating_sub(1),
+        ..area
+    }
+}
+
+/// Width and height of the text in the reader, for a terminal's size
+pub fn reader_page_size(terminal_width: u16, terminal_height: u16) -> (usize, usize) {
+    let area = reader_area(Rect::new(0, 0, terminal_width, terminal_height));
+    // Inside the border
+    (
+        usize::from(area.width.saturating_sub(2)),
+        usize::from(area.height.saturating_sub(2)),
+    )
+}
+
+/// Draw the reader over the panes
+fn draw_reader(frame: &mut Frame, reader: &Reader, area: Rect) {
+    let (width, height) = reader_page_size(frame.area().width, frame.area().height);
+    let rows = reader.rows(width);
+    let top = reader.top_row(&rows);
+    let text: Vec<Line> = rows
+        .iter()
+        .skip(top)
+        .take(height)
+        .map(|(_, row)| Line::from(row.as_str()))
+        .collect();
+
+    let percent = if rows.len() <= height {
+        100
+    } else {
+        (top + height).min(rows.len()) * 100 / rows.len()
+    };
+    let block = Block::default()
+        .title(format!(" {} ", reader.title))
+        .title_bottom(
+            Line::from(format!(
+                " {}% · {} ",
+                percent,
+                t("j/k scroll · Space/PgUp page · q close")
+            ))
+            .right_aligned(),
+        )
+        .borders(Borders::ALL)
+        .border_style(Style::default().add_modifier(Modifier::BOLD));
+
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(Paragraph::new(text).block(block), area);
+}
+
 /// Draw the status bar at the bottom
 fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect) {
     let content = if app.is_loading {
-        "Adding link...".to_string()
+        t("Adding link...").to_string()
     } else if let Some(msg) = &app.status_message {
         msg.clone()
     } else {
-        "a:add  t:tag  n:note  e:edit  d:del  u:undo  /:filter  ?:help  q:quit".to_string()
+        t("a:add  t:tag  n:note  e:edit  d:del  u:undo  /:filter  ?:help  q:quit").to_string()
+    };
+    let content = match app.macros.recording() {
+        Some(register) => tf("recording @{}  {}", &[&register, &content]),
+        None => content,
+    };
+    let content = match app.reading {
+        Some(ref session) => tf(
+            "reading ({} read, {} min)  {}",
+            &[
+                &session.links_read().len(),
+                &session.reading_time(chrono::Utc::now()).num_minutes(),
+                &content,
+            ],
+        ),
+        None => content,
+    };
+    let content = match (app.peers.online, &app.peers.last_change) {
+        (0, _) => content,
+        (online, None) => tf("{} other device(s) online  {}", &[&online, &content]),
+        (online, Some((device, at))) => tf(
+            "{} other device(s) online, last change from {} at {}  {}",
+            &[
+                &online,
+                device,
+                &at.with_timezone(&chrono::Local).format("%H:%M"),
+                &content,
+            ],
+        ),
     };
 
     let paragraph = Paragraph::new(content).style(Style::default().add_modifier(Modifier::DIM));
@@ -333,6 +911,25 @@ fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(paragraph, area);
 }
 
+/// Draw the keys bound to tags while triaging
+fn draw_triage_legend(frame: &mut Frame, tags: &[String], area: Rect) {
+    let mut spans = vec![Span::styled(
+        format!("{}:", t("Triage")),
+        Style::default().add_modifier(Modifier::BOLD),
+    )];
+    for (index, tag) in tags.iter().enumerate() {
+        spans.push(Span::raw("  "));
+        spans.push(Span::styled(
+            (index + 1).to_string(),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::raw(format!(" #{}", tag)));
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
 /// Draw command input at the bottom
 fn draw_command_input(frame: &mut Frame, app: &App, area: Rect) {
     // Build the input line with cursor
@@ -341,14 +938,14 @@ fn draw_command_input(frame: &mut Frame, app: &App, area: Rect) {
 
     let line = Line::from(vec![
         Span::styled(prefix, Style::default().fg(Color::Yellow)),
-        Span::raw(input.as_str()),
+        Span::raw(input.value()),
     ]);
 
     let paragraph = Paragraph::new(line);
     frame.render_widget(paragraph, area);
 
     // Position cursor
-    let cursor_x = area.x + prefix.len() as u16 + app.command_cursor as u16;
+    let cursor_x = area.x + prefix.len() as u16 + input.cursor_width() as u16;
     frame.set_cursor_position((cursor_x, area.y));
 }
 
@@ -359,9 +956,9 @@ fn draw_filter_input(frame: &mut Frame, app: &App, area: Rect) {
 
     let line = Line::from(vec![
         Span::styled(prefix, Style::default().fg(Color::Cyan)),
-        Span::raw(input.as_str()),
+        Span::raw(input.value()),
         Span::styled(
-            format!("  ({} matches)", app.links.len()),
+            format!("  {}", tf("({} matches)", &[&app.links.len()])),
             Style::default().add_modifier(Modifier::DIM),
         ),
     ]);
@@ -370,7 +967,7 @@ fn draw_filter_input(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(paragraph, area);
 
     // Position cursor
-    let cursor_x = area.x + prefix.len() as u16 + app.command_cursor as u16;
+    let cursor_x = area.x + prefix.len() as u16 + input.cursor_width() as u16;
     frame.set_cursor_position((cursor_x, area.y));
 }
 
@@ -381,73 +978,182 @@ fn draw_sync_indicator(frame: &mut Frame, app: &App) {
         return;
     }
 
-    let (icon, style) = match app.sync_status {
-        SyncIndicator::Synced => ("✓", Style::default().fg(Color::Green)),
-        SyncIndicator::Syncing => ("↻", Style::default().fg(Color::Yellow)),
-        SyncIndicator::Offline => ("⚡", Style::default().fg(Color::DarkGray)),
-        SyncIndicator::Disabled => ("○", Style::default().add_modifier(Modifier::DIM)),
-        SyncIndicator::Error => ("✗", Style::default().fg(Color::Red)),
+    let (icon, label, style) = match app.sync_status {
+        SyncIndicator::Synced => ("✓", "synced", Style::default().fg(Color::Green)),
+        SyncIndicator::Syncing => ("↻", "syncing", Style::default().fg(Color::Yellow)),
+        SyncIndicator::Offline => ("⚡", "offline", Style::default().fg(Color::DarkGray)),
+        SyncIndicator::Disabled => ("○", "no sync", Style::default().add_modifier(Modifier::DIM)),
+        SyncIndicator::Error => ("✗", "sync error", Style::default().fg(Color::Red)),
     };
 
-    let indicator = Paragraph::new(Span::styled(icon, style));
-    let indicator_area = Rect::new(area.width - 2, 0, 1, 1);
+    // Accessible mode spells the state out rather than relying on icon color
+    let text = if app.accessible {
+        match app.sync_progress {
+            Some(progress) if app.sync_status == SyncIndicator::Syncing => tf(
+                "{} {} ({} changes)",
+                &[
+                    &icon,
+                    &t(label),
+                    &(progress.changes_sent + progress.changes_received),
+                ],
+            ),
+            _ => format!("{} {}", icon, t(label)),
+        }
+    } else {
+        icon.to_string()
+    };
+    let width = (text.chars().count() as u16).min(area.width - 1);
+    let indicator = Paragraph::new(Span::styled(text, style));
+    let indicator_area = Rect::new(area.width - 1 - width, 0, width, 1);
     frame.render_widget(indicator, indicator_area);
 }
 
-/// Draw help overlay
-fn draw_help_overlay(frame: &mut Frame) {
+/// Draw the privacy lock screen
+fn draw_lock_screen(frame: &mut Frame, app: &App) {
     let area = frame.area();
 
-    // Calculate centered popup area
     let popup_width = 50.min(area.width.saturating_sub(4));
-    let popup_height = 20.min(area.height.saturating_sub(4));
+    let popup_height = 9.min(area.height.saturating_sub(2));
     let popup_x = (area.width.saturating_sub(popup_width)) / 2;
     let popup_y = (area.height.saturating_sub(popup_height)) / 2;
     let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
 
-    // Clear the popup area
-    frame.render_widget(ratatui::widgets::Clear, popup_area);
-
-    let help_text = vec![
-        Line::from(vec![Span::styled(
-            "Keyboard Shortcuts",
-            Style::default().add_modifier(Modifier::BOLD),
-        )]),
-        Line::from(""),
-        Line::from("Navigation:"),
-        Line::from("  j/k, ↑/↓    Move up/down"),
-        Line::from("  gg          Jump to first item"),
-        Line::from("  G           Jump to last item"),
-        Line::from("  h/l, ←/→    Switch panes"),
-        Line::from("  Tab         Cycle panes"),
-        Line::from("  Enter       Open link / Apply filter"),
+    let mut lines = vec![
         Line::from(""),
-        Line::from("Commands:"),
-        Line::from("  a           Add link"),
-        Line::from("  t           Edit tags"),
-        Line::from("  n           Add note"),
-        Line::from("  e           Edit link"),
-        Line::from("  d           Delete link"),
-        Line::from("  u           Undo delete"),
+        Line::from(t("Enter passphrase to unlock:")),
         Line::from(""),
-        Line::from("  /           Filter view"),
-        Line::from("  :           Command mode"),
-        Line::from("  Ctrl+D      Device settings"),
-        Line::from("  q           Quit"),
+        Line::from(vec![
+            Span::raw("> "),
+            Span::raw("•".repeat(app.lock_input.chars().count())),
+        ]),
         Line::from(""),
-        Line::from(vec![Span::styled(
-            "Press any key to close",
-            Style::default().add_modifier(Modifier::DIM),
-        )]),
     ];
+    match app.lock_error {
+        Some(ref error) => lines.push(Line::from(vec![Span::styled(
+            error.as_str(),
+            Style::default().fg(Color::Red),
+        )])),
+        None => lines.push(Line::from(vec![Span::styled(
+            t("Ctrl+C to quit"),
+            Style::default().add_modifier(Modifier::DIM),
+        )])),
+    }
+
+    let block = Block::default()
+        .title(format!(" {} ", t("Locked")))
+        .borders(Borders::ALL)
+        .border_style(Style::default().add_modifier(Modifier::BOLD));
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .alignment(ratatui::layout::Alignment::Center);
+    frame.render_widget(paragraph, popup_area);
+}
+
+/// Size of the help popup for a terminal area
+fn help_popup_size(area: Rect) -> (u16, u16) {
+    (
+        60.min(area.width.saturating_sub(4)),
+        40.min(area.height.saturating_sub(4)),
+    )
+}
+
+/// Lines of bindings visible in the help popup (scrolled by a page)
+pub fn help_page_height(terminal_height: u16) -> usize {
+    let (_, height) = help_popup_size(Rect::new(0, 0, u16::MAX, terminal_height));
+    // Borders and the footer
+    usize::from(height.saturating_sub(4))
+}
+
+/// Draw help overlay
+fn draw_help_overlay(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+
+    // Calculate centered popup area
+    let (popup_width, popup_height) = help_popup_size(area);
+    let popup_x = (area.width.saturating_sub(popup_width)) / 2;
+    let popup_y = (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
+
+    // Clear the popup area
+    frame.render_widget(ratatui::widgets::Clear, popup_area);
+
+    let help = &app.help;
+    let lines = help.lines();
+    let page = help_page_height(area.height);
+    let scroll = help.scroll.min(lines.len().saturating_sub(page));
+
+    let mut help_text: Vec<Line> = lines
+        .iter()
+        .skip(scroll)
+        .take(page)
+        .map(|line| match line {
+            HelpLine::Category(name) => Line::from(Span::styled(
+                format!("{}:", t(*name)),
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            HelpLine::Binding(binding) => {
+                Line::from(format!("  {:<13}{}", binding.keys, t(binding.action)))
+            }
+            HelpLine::Blank => Line::from(""),
+        })
+        .collect();
+    if lines.is_empty() {
+        help_text.push(Line::from(format!("  {}", t("No matching keys"))));
+    }
 
+    // Footer: search input or hints
+    help_text.resize(page, Line::from(""));
+    help_text.push(Line::from(""));
+    help_text.push(if help.searching {
+        Line::from(vec![
+            Span::styled("/", Style::default().fg(Color::Cyan)),
+            Span::raw(help.query.value()),
+        ])
+    } else if !help.query.is_empty() {
+        Line::from(Span::styled(
+            tf(
+                "Matching \"{}\" · Esc clear · / search again",
+                &[&help.query.value()],
+            ),
+            Style::default().add_modifier(Modifier::DIM),
+        ))
+    } else {
+        Line::from(Span::styled(
+            t("j/k scroll · PgUp/PgDn page · / search · other keys close"),
+            Style::default().add_modifier(Modifier::DIM),
+        ))
+    });
+
+    let title = if lines.len() > page {
+        format!(
+            " {} ",
+            tf(
+                "Keyboard Shortcuts ({}-{} of {})",
+                &[
+                    &(scroll + 1),
+                    &(scroll + page).min(lines.len()),
+                    &lines.len()
+                ],
+            )
+        )
+    } else {
+        format!(" {} ", t("Keyboard Shortcuts"))
+    };
     let block = Block::default()
-        .title(" Help ")
+        .title(title)
         .borders(Borders::ALL)
         .border_style(Style::default().add_modifier(Modifier::BOLD));
 
     let paragraph = Paragraph::new(help_text).block(block);
     frame.render_widget(paragraph, popup_area);
+
+    if help.searching {
+        // Inside the border, on the footer line
+        let cursor_x = popup_area.x + 2 + help.query.cursor_width() as u16;
+        let cursor_y = popup_area.y + popup_area.height.saturating_sub(2);
+        frame.set_cursor_position((cursor_x, cursor_y));
+    }
 }
 
 /// Draw device settings panel
@@ -466,23 +1172,24 @@ fn draw_device_panel(frame: &mut Frame, app: &App) {
 
     // Sync status display
     let sync_status_str = match app.sync_status {
-        SyncIndicator::Synced => ("✓ Connected", Color::Green),
-        SyncIndicator::Syncing => ("↻ Syncing", Color::Yellow),
-        SyncIndicator::Offline => ("⚡ Offline", Color::Gray),
-        SyncIndicator::Disabled => ("○ Disabled", Color::Gray),
-        SyncIndicator::Error => ("✗ Error", Color::Red),
+        SyncIndicator::Synced => ("✓", "Connected", Color::Green),
+        SyncIndicator::Syncing => ("↻", "Syncing", Color::Yellow),
+        SyncIndicator::Offline => ("⚡", "Offline", Color::Gray),
+        SyncIndicator::Disabled => ("○", "Disabled", Color::Gray),
+        SyncIndicator::Error => ("✗", "Error", Color::Red),
     };
 
     let device_text = vec![
         Line::from(vec![Span::styled(
-            "Device Information",
+            t("Device Information"),
             Style::default().add_modifier(Modifier::BOLD),
         )]),
         Line::from(""),
-        Line::from(vec![Span::styled(
-            "Root Document ID: ",
-            Style::default().add_modifier(Modifier::BOLD),
-        )]),
+        Line::from(vec![
+            label("Collection"),
+            Span::raw(&app.device_info.collection),
+        ]),
+        Line::from(vec![label("Root Document ID")]),
         Line::from(vec![
             Span::raw("  "),
             Span::styled(
@@ -494,43 +1201,56 @@ fn draw_device_panel(frame: &mut Frame, app: &App) {
         ]),
         Line::from(""),
         Line::from(vec![
-            Span::styled(
-                "Sync Server: ",
-                Style::default().add_modifier(Modifier::BOLD),
-            ),
+            label("Sync Server"),
             Span::raw(
                 app.device_info
                     .sync_url
                     .as_deref()
-                    .unwrap_or("Not configured"),
+                    .unwrap_or(t("Not configured")),
             ),
         ]),
         Line::from(vec![
+            label("Sync Status"),
             Span::styled(
-                "Sync Status: ",
-                Style::default().add_modifier(Modifier::BOLD),
+                format!("{} {}", sync_status_str.0, t(sync_status_str.1)),
+                Style::default().fg(sync_status_str.2),
             ),
-            Span::styled(sync_status_str.0, Style::default().fg(sync_status_str.1)),
         ]),
-        Line::from(""),
+        Line::from(vec![
+            label("Last Synced"),
+            Span::raw(match app.last_sync {
+                Some(last) => time_ago(last.at, chrono::Utc::now()),
+                None => t("Never").to_string(),
+            }),
+        ]),
+        Line::from(match app.last_sync {
+            Some(last) => vec![
+                Span::raw("  "),
+                Span::styled(
+                    sync_summary(&last.progress),
+                    Style::default().add_modifier(Modifier::DIM),
+                ),
+            ],
+            None => vec![],
+        }),
         Line::from(""),
         Line::from(vec![
             Span::styled("[y] ", Style::default().fg(Color::Yellow)),
-            Span::raw("Copy ID to clipboard"),
+            Span::raw(t("Copy ID to clipboard")),
         ]),
         Line::from(vec![
             Span::styled("[Esc] ", Style::default().fg(Color::Yellow)),
-            Span::raw("Close"),
+            Span::raw(t("Close")),
         ]),
         Line::from(""),
         Line::from(vec![Span::styled(
-            "Use this ID to set up ROTT on other devices",
+            t("Use this ID to set up ROTT on other devices"),
             Style::default().add_modifier(Modifier::DIM),
         )]),
     ];
 
     let block = Block::default()
-        .title(" Device Settings (Ctrl+D) ")
+        .title(format!(" {} ", t("Device Settings (Ctrl+D)")))
         .borders(Borders::ALL)
         .border_style(
             Style::default()
@@ -542,6 +1262,71 @@ fn draw_device_panel(frame: &mut Frame, app: &App) {
     frame.render_widget(paragraph, popup_area);
 }
 
+/// Draw the current tour step
+///
+/// The card sits over a pane the step isn't about, so the focused pane
+/// stays visible: over the detail pane for the filters and items panes,
+/// over the filters and items panes for the detail pane, and centered
+/// otherwise.
+fn draw_tour_overlay(frame: &mut Frame, app: &App, panes: &[Rect]) {
+    let Some(ref tour) = app.tour else {
+        return;
+    };
+    let step = tour.current();
+
+    let host = match step.pane {
+        Some(ActivePane::Filters) | Some(ActivePane::Items) => panes[2],
+        Some(ActivePane::Detail) => panes[0].union(panes[1]),
+        None => frame.area(),
+    };
+    let popup_width = 56.min(host.width.saturating_sub(2));
+    let popup_height = 11.min(host.height.saturating_sub(2));
+    let popup_x = host.x + (host.width.saturating_sub(popup_width)) / 2;
+    let popup_y = host.y + (host.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
+
+    frame.render_widget(ratatui::widgets::Clear, popup_area);
+
+    let pointer = match step.pane {
+        Some(ActivePane::Filters) => t("◀ Filters pane (left)"),
+        Some(ActivePane::Items) => t("◀ Links pane (middle)"),
+        Some(ActivePane::Detail) => t("Detail pane (right) ▶"),
+        None => "",
+    };
+    let next = if tour.is_last() {
+        t("Enter finish")
+    } else {
+        t("→ next")
+    };
+    let lines = vec![
+        Line::from(Span::styled(pointer, Style::default().fg(Color::Yellow))),
+        Line::from(""),
+        Line::from(t(step.text)),
+        Line::from(""),
+        Line::from(Span::styled(
+            tf("{} · ← back · Esc skip", &[&next]),
+            Style::default().add_modifier(Modifier::DIM),
+        )),
+    ];
+
+    let block = Block::default()
+        .title(format!(
+            " {} ({}/{}) ",
+            t(step.title),
+            tour.number(),
+            super::tour::STEPS.len()
+        ))
+        .borders(Borders::ALL)
+        .border_style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: true });
+    frame.render_widget(paragraph, popup_area);
+}
+
 /// Draw error modal
 fn draw_error_modal(frame: &mut Frame, error: &str) {
     let area = frame.area();
@@ -566,12 +1351,12 @@ fn draw_error_modal(frame: &mut Frame, error: &str) {
     lines.push(Line::from(""));
     lines.push(Line::from(""));
     lines.push(Line::from(vec![Span::styled(
-        "Press any key to dismiss",
+        t("Press any key to dismiss"),
         Style::default().fg(Color::Gray).add_modifier(Modifier::DIM),
     )]));
 
     let block = Block::default()
-        .title(" Error ")
+        .title(format!(" {} ", t("Error")))
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD));
 
@@ -0,0 +1,742 @@
+//! Read-only web viewer
+//!
+//! `rott serve viewer` serves a small single-page viewer for browsing the
+//! collection from another device on the same network, such as a phone,
+//! without installing anything there. The page is embedded in the binary;
+//! it asks a few JSON endpoints for its data:
+//!
+//! ```text
+//! GET /                           the viewer
+//! GET /api/links?q=&tag=          links, newest first (q is a search; operators work)
+//! GET /api/links/<id>             one link with its notes (ID or prefix)
+//! GET /api/links/<id>/screenshot  the link's screenshot (see `rott link snapshot`)
+//! GET /api/tags                   tags with their link counts
+//! GET /api/favicons               cached site favicons (data: URIs), by domain
+//! GET /add                        quick add form
+//! POST /add                       save a URL (needs the add_token)
+//! ```
+//!
+//! The only change that can be made through the viewer is saving a link
+//! with `/add`, the target for phone share sheets (iOS Shortcuts, Android
+//! HTTP apps). It takes a form or a JSON body with `url`, optional `tags`
+//! (comma-separated or a list) and `token`, which must match `add_token`
+//! in the config; without one set, quick add is off. The token can also
+//! be sent as `Authorization: Bearer <token>`. If the `url` field holds
+//! shared text rather than a URL, the first URL in it is saved.
+//!
+//! Links with a private tag are left out unless asked for. Changes saved by
+//! other rott processes (the TUI, a sync) are picked up before each request.
+//! Requests are answered one at a time, which is plenty for browsing.
+
+use std::collections::{BTreeMap, HashSet};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use rott_core::attachments::AttachmentStore;
+use rott_core::domains::extract_domain;
+use rott_core::favicons::{initials, FaviconCache};
+use rott_core::urls::url_strs;
+use rott_core::{Link, LinkSort, Store};
+
+use crate::commands::link::{new_link, parse_link_id};
+use crate::commands::sync::sync_quiet;
+use crate::output::Output;
+use crate::screenshot::screenshot_path;
+
+/// The viewer page (HTML, CSS and script in one file)
+const INDEX_HTML: &str = include_str!("viewer/index.html");
+
+/// The quick add form
+const ADD_HTML: &str = include_str!("viewer/add.html");
+
+/// Largest request head read; the viewer only sends short requests
+const MAX_REQUEST: usize = 8 * 1024;
+
+/// Largest request body read (a quick add)
+const MAX_BODY: usize = 16 * 1024;
+
+/// How long a connection may take to send its request
+pub(crate) const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A link as listed by the viewer
+#[derive(Debug, Serialize)]
+struct LinkSummary<'a> {
+    id: Uuid,
+    title: &'a str,
+    url: &'a str,
+    tags: &'a [String],
+    created_at: chrono::DateTime<chrono::Utc>,
+    notes: usize,
+    /// The site, to look up its favicon
+    domain: Option<String>,
+    /// Letters shown when the site has no favicon
+    initials: Option<String>,
+}
+
+impl<'a> From<&'a Link> for LinkSummary<'a> {
+    fn from(link: &'a Link) -> Self {
+        let domain = extract_domain(&link.url);
+        Self {
+            id: link.id,
+            title: &link.title,
+            url: &link.url,
+            tags: &link.tags,
+            created_at: link.created_at,
+            notes: link.notes.len(),
+            initials: domain.as_deref().map(initials),
+            domain,
+        }
+    }
+}
+
+/// An HTTP request
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Request {
+    pub(crate) method: String,
+    pub(crate) target: String,
+    /// Headers, by lowercased name
+    pub(crate) headers: BTreeMap<String, String>,
+    pub(crate) body: Vec<u8>,
+}
+
+impl Request {
+    /// The token sent as `Authorization: Bearer <token>`, if any
+    pub(crate) fn bearer_token(&self) -> Option<String> {
+        self.headers
+            .get("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(|token| token.trim().to_string())
+    }
+}
+
+/// A link to save, from `POST /add`
+#[derive(Debug, Default, PartialEq)]
+struct QuickAdd {
+    url: String,
+    tags: Vec<String>,
+    token: Option<String>,
+}
+
+/// An HTTP response
+#[derive(Debug)]
+pub(crate) struct Response {
+    pub(crate) status: &'static str,
+    content_type: &'static str,
+    pub(crate) body: Vec<u8>,
+}
+
+impl Response {
+    fn html(body: &str) -> Self {
+        Self {
+            status: "200 OK",
+            content_type: "text/html; charset=utf-8",
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    pub(crate) fn json(value: &impl Serialize) -> Result<Self> {
+        Self::json_with_status("200 OK", value)
+    }
+
+    pub(crate) fn json_with_status(status: &'static str, value: &impl Serialize) -> Result<Self> {
+        Ok(Self {
+            status,
+            content_type: "application/json",
+            body: serde_json::to_vec(value)?,
+        })
+    }
+
+    fn bytes(content_type: &'static str, body: Vec<u8>) -> Self {
+        Self {
+            status: "200 OK",
+            content_type,
+            body,
+        }
+    }
+
+    pub(crate) fn error(status: &'static str, message: &str) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: serde_json::json!({ "error": message })
+                .to_string()
+                .into_bytes(),
+        }
+    }
+}
+
+/// Serve the viewer until Ctrl-C
+pub async fn serve(
+    store: &mut Store,
+    bind: &str,
+    port: u16,
+    include_private: bool,
+    output: &Output,
+) -> Result<()> {
+    let listener = TcpListener::bind((bind, port))
+        .await
+        .with_context(|| format!("Failed to listen on {}:{}", bind, port))?;
+    let address = listener.local_addr()?;
+
+    output.message(&format!(
+        "Serving the viewer on http://{} (Ctrl-C to stop)",
+        address
+    ));
+    if address.ip().is_unspecified() {
+        output.message(&format!(
+            "Open http://<this machine's address>:{} on other devices on the same network",
+            address.port()
+        ));
+    }
+    if include_private {
+        output.message("Links with a private tag are shown");
+    }
+    if store.config().add_token.is_some() {
+        output.message(&format!(
+            "Quick add is on: share URLs to http://<this machine's address>:{}/add",
+            address.port()
+        ));
+    }
+
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    let mut version = store.stored_version();
+
+    loop {
+        let stream = tokio::select! {
+            _ = &mut ctrl_c => return Ok(()),
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    debug!("Failed to accept viewer connection: {}", e);
+                    continue;
+                }
+            },
+        };
+
+        // Pick up changes saved by other processes
+        let current = store.stored_version();
+        if current != version {
+            version = current;
+            store.reload_and_merge()?;
+        }
+
+        if let Err(e) = answer(store, stream, include_private).await {
+            debug!("Viewer request failed: {:#}", e);
+        }
+    }
+}
+
+/// Read one request from `stream` and answer it
+async fn answer(store: &mut Store, mut stream: TcpStream, include_private: bool) -> Result<()> {
+    let request = tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream, MAX_BODY))
+        .await
+        .context("Timed out reading request")??;
+
+    let response = match request {
+        Some(request) => {
+            let target = request.target.as_str();
+            let (path, query) = target.split_once('?').unwrap_or((target, ""));
+            let result = match (request.method.as_str(), path) {
+                ("GET", _) => route(store, path, &parse_query(query), include_private),
+                ("POST", "/add") => quick_add(store, &request).await,
+                _ => Ok(Response::error(
+                    "405 Method Not Allowed",
+                    "The viewer is read-only, apart from POST /add",
+                )),
+            };
+            result.unwrap_or_else(|e| {
+                Response::error("500 Internal Server Error", &format!("{:#}", e))
+            })
+        }
+        None => Response::error("400 Bad Request", "Malformed request"),
+    };
+    write_response(&mut stream, &response).await
+}
+
+/// Send a response and close the connection
+pub(crate) async fn write_response(stream: &mut TcpStream, response: &Response) -> Result<()> {
+    let header = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\
+         Cache-Control: no-store\r\nX-Content-Type-Options: nosniff\r\n\
+         Connection: close\r\n\r\n",
+        response.status,
+        response.content_type,
+        response.body.len()
+    );
+    stream.write_all(header.as_bytes()).await?;
+    stream.write_all(&response.body).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+/// Read a request: its head, then a body of up to `max_body` bytes
+/// (`Content-Length`)
+///
+/// Returns `None` for a request that isn't well-formed HTTP.
+pub(crate) async fn read_request(
+    stream: &mut TcpStream,
+    max_body: usize,
+) -> Result<Option<Request>> {
+    let mut data = Vec::new();
+    let mut buf = [0u8; 1024];
+    let head_end = loop {
+        if let Some(end) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end;
+        }
+        if data.len() > MAX_REQUEST {
+            anyhow::bail!("Request too large");
+        }
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        data.extend_from_slice(&buf[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&data[..head_end]).into_owned();
+    let Some((method, target)) = parse_request_line(&head) else {
+        return Ok(None);
+    };
+    let mut request = Request {
+        method: method.to_string(),
+        target: target.to_string(),
+        headers: parse_headers(&head),
+        body: data[head_end + 4..].to_vec(),
+    };
+
+    let length: usize = match request.headers.get("content-length") {
+        Some(length) => match length.parse() {
+            Ok(length) => length,
+            Err(_) => return Ok(None),
+        },
+        None => 0,
+    };
+    if length > max_body {
+        anyhow::bail!("Request body too large");
+    }
+    while request.body.len() < length {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        request.body.extend_from_slice(&buf[..n]);
+    }
+    request.body.truncate(length);
+    Ok(Some(request))
+}
+
+/// The method and target of a request (`GET /api/tags HTTP/1.1`)
+fn parse_request_line(head: &str) -> Option<(&str, &str)> {
+    let mut parts = head.lines().next()?.split_whitespace();
+    let method = parts.next()?;
+    let target = parts.next()?;
+    parts
+        .next()?
+        .starts_with("HTTP/")
+        .then_some((method, target))
+}
+
+/// Headers after the request line, by lowercased name
+fn parse_headers(head: &str) -> BTreeMap<String, String> {
+    head.lines()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect()
+}
+
+/// Decode a query string (`q=rust+async&tag=dev`)
+pub(crate) fn parse_query(query: &str) -> BTreeMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect()
+}
+
+/// Decode `%XX` escapes, and `+` as a space
+pub(crate) fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => match (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                (Some(high), Some(low)) => {
+                    decoded.push(high << 4 | low);
+                    i += 2;
+                }
+                _ => decoded.push(b'%'),
+            },
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Value of a hex digit
+fn hex(digit: u8) -> Option<u8> {
+    (digit as char).to_digit(16).map(|d| d as u8)
+}
+
+/// Answer a GET for `path`
+fn route(
+    store: &Store,
+    path: &str,
+    query: &BTreeMap<String, String>,
+    include_private: bool,
+) -> Result<Response> {
+    let visibility = store.tag_visibility()?;
+    let visible = |link: &Link| include_private || !visibility.is_private(link);
+
+    match path {
+        "/" | "/index.html" => Ok(Response::html(INDEX_HTML)),
+        "/add" => Ok(Response::html(ADD_HTML)),
+        "/api/links" => {
+            let search = query.get("q").map(|q| q.trim()).filter(|q| !q.is_empty());
+            let tag = query.get("tag").filter(|t| !t.is_empty());
+            let mut links = match (search, tag) {
+                (Some(search), Some(tag)) => {
+                    let tagged: HashSet<Uuid> = store
+                        .get_links_by_tag(tag, LinkSort::default())?
+                        .iter()
+                        .map(|link| link.id)
+                        .collect();
+                    let mut links = store.search_links(search)?;
+                    links.retain(|link| tagged.contains(&link.id));
+                    links
+                }
+                (Some(search), None) => store.search_links(search)?,
+                (None, Some(tag)) => store.get_links_by_tag(tag, LinkSort::default())?,
+                (None, None) => store.get_all_links(LinkSort::default())?,
+            };
+            links.retain(|link| visible(link));
+            links.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+            let summaries: Vec<LinkSummary> = links.iter().map(LinkSummary::from).collect();
+            Response::json(&summaries)
+        }
+        "/api/tags" => {
+            let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+            for link in store
+                .get_all_links(LinkSort::default())?
+                .iter()
+                .filter(|link| visible(link))
+            {
+                for tag in &link.tags {
+                    *counts.entry(tag.clone()).or_default() += 1;
+                }
+            }
+            Response::json(&counts)
+        }
+        "/api/favicons" => {
+            let cache = FaviconCache::from_config(store.config());
+            let now = chrono::Utc::now();
+            let mut favicons: BTreeMap<String, String> = BTreeMap::new();
+            for link in store
+                .get_all_links(LinkSort::default())?
+                .iter()
+                .filter(|link| visible(link))
+            {
+                let Some(domain) = extract_domain(&link.url) else {
+                    continue;
+                };
+                if favicons.contains_key(&domain) {
+                    continue;
+                }
+                if let Some(favicon) = cache.get(&domain, now) {
+                    favicons.insert(domain, favicon.data_uri());
+                }
+            }
+            Response::json(&favicons)
+        }
+        _ => {
+            let Some(id) = path.strip_prefix("/api/links/") else {
+                return Ok(Response::error("404 Not Found", "Not found"));
+            };
+            let (id, screenshot) = match id.strip_suffix("/screenshot") {
+                Some(id) => (id, true),
+                None => (id, false),
+            };
+            let link = parse_link_id(&percent_decode(id), store)
+                .ok()
+                .and_then(|uuid| store.get_link(uuid).transpose())
+                .transpose()?
+                .filter(|link| visible(link));
+            let Some(link) = link else {
+                return Ok(Response::error("404 Not Found", "Link not found"));
+            };
+            if !screenshot {
+                return Response::json(&link);
+            }
+            match screenshot_path(&AttachmentStore::from_config(store.config()), &link) {
+                Some(path) => {
+                    let image = std::fs::read(&path)
+                        .with_context(|| format!("Failed to read {}", path.display()))?;
+                    Ok(Response::bytes("image/png", image))
+                }
+                None => Ok(Response::error("404 Not Found", "No screenshot")),
+            }
+        }
+    }
+}
+
+/// Save a link from `POST /add`
+async fn quick_add(store: &mut Store, request: &Request) -> Result<Response> {
+    let Some(expected) = store.config().add_token.clone() else {
+        return Ok(Response::error(
+            "403 Forbidden",
+            "Quick add is off. Turn it on with `rott config set add_token generate`.",
+        ));
+    };
+    let add = match parse_quick_add(request) {
+        Ok(add) => add,
+        Err(e) => return Ok(Response::error("400 Bad Request", &format!("{:#}", e))),
+    };
+    let token = add.token.clone().or_else(|| request.bearer_token());
+    if !token.is_some_and(|token| tokens_match(&token, &expected)) {
+        return Ok(Response::error(
+            "401 Unauthorized",
+            "Wrong or missing token",
+        ));
+    }
+
+    // Share sheets often send "Page title https://..." as text
+    let shared = add.url.trim();
+    let url = if shared.contains(char::is_whitespace) || !shared.contains("://") {
+        match url_strs(shared).first() {
+            Some(url) => url.to_string(),
+            None => return Ok(Response::error("400 Bad Request", "No URL given")),
+        }
+    } else {
+        shared.to_string()
+    };
+
+    if let Some(existing) = store.get_link_by_url(&url)? {
+        return Response::json(&serde_json::json!({
+            "status": "exists",
+            "link": LinkSummary::from(&existing),
+        }));
+    }
+
+    let link = match new_link(store, &url, add.tags).await {
+        Ok(link) => link,
+        Err(e) => return Ok(Response::error("400 Bad Request", &format!("{:#}", e))),
+    };
+    store.add_link(&link).context("Failed to create link")?;
+    info!("Quick add saved {}", link.url);
+
+    let config = store.config().clone();
+    if config.sync_enabled {
+        if let Err(e) = sync_quiet(store, &config, false).await {
+            warn!("Sync after quick add failed: {:#}", e);
+        }
+    }
+
+    Response::json_with_status(
+        "201 Created",
+        &serde_json::json!({
+            "status": "added",
+            "link": LinkSummary::from(&link),
+        }),
+    )
+}
+
+/// The fields of a quick add, from a form or a JSON body
+fn parse_quick_add(request: &Request) -> Result<QuickAdd> {
+    let content_type = request
+        .headers
+        .get("content-type")
+        .map(String::as_str)
+        .unwrap_or_default();
+    let body = String::from_utf8_lossy(&request.body);
+
+    let mut add = QuickAdd::default();
+    if content_type.starts_with("application/json") {
+        let value: serde_json::Value = serde_json::from_str(&body).context("Invalid JSON body")?;
+        add.url = value["url"].as_str().unwrap_or_default().to_string();
+        add.tags = match &value["tags"] {
+            serde_json::Value::Array(tags) => tags
+                .iter()
+                .filter_map(|tag| tag.as_str().map(str::to_string))
+                .collect(),
+            serde_json::Value::String(tags) => split_tags(tags),
+            _ => Vec::new(),
+        };
+        add.token = value["token"].as_str().map(str::to_string);
+    } else {
+        let fields = parse_query(&body);
+        add.url = fields.get("url").cloned().unwrap_or_default();
+        add.tags = fields
+            .get("tags")
+            .map(|t| split_tags(t))
+            .unwrap_or_default();
+        add.token = fields.get("token").cloned();
+    }
+    add.token = add.token.filter(|token| !token.is_empty());
+    Ok(add)
+}
+
+/// Tags from a comma-separated list
+fn split_tags(tags: &str) -> Vec<String> {
+    tags.split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Compare tokens without stopping at the first difference
+pub(crate) fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request_line() {
+        assert_eq!(
+            parse_request_line("GET /api/tags HTTP/1.1\r\nHost: x\r\n\r\n"),
+            Some(("GET", "/api/tags"))
+        );
+        assert_eq!(parse_request_line("GET /\r\n\r\n"), None);
+        assert_eq!(parse_request_line(""), None);
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let query = parse_query("q=rust+async%21&tag=dev%2Fops&empty");
+        assert_eq!(query["q"], "rust async!");
+        assert_eq!(query["tag"], "dev/ops");
+        assert_eq!(query["empty"], "");
+
+        assert_eq!(percent_decode("caf%C3%A9"), "café");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz"), "%zz");
+    }
+
+    fn post(content_type: &str, body: &str) -> Request {
+        Request {
+            method: "POST".to_string(),
+            target: "/add".to_string(),
+            headers: BTreeMap::from([("content-type".to_string(), content_type.to_string())]),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_parse_quick_add() {
+        let form = post(
+            "application/x-www-form-urlencoded",
+            "url=https%3A%2F%2Fexample.com%2Fa&tags=rust%2C+later&token=abc",
+        );
+        assert_eq!(
+            parse_quick_add(&form).unwrap(),
+            QuickAdd {
+                url: "https://example.com/a".to_string(),
+                tags: vec!["rust".to_string(), "later".to_string()],
+                token: Some("abc".to_string()),
+            }
+        );
+
+        let json = post(
+            "application/json",
+            r#"{"url": "https://example.com", "tags": ["a", "b"], "token": ""}"#,
+        );
+        let add = parse_quick_add(&json).unwrap();
+        assert_eq!(add.tags, vec!["a", "b"]);
+        assert_eq!(add.token, None);
+
+        assert!(parse_quick_add(&post("application/json", "{")).is_err());
+    }
+
+    #[test]
+    fn test_parse_headers_and_tokens() {
+        let headers = parse_headers("POST /add HTTP/1.1\r\nContent-Type: text/plain\r\nX-A:b");
+        assert_eq!(headers["content-type"], "text/plain");
+        assert_eq!(headers["x-a"], "b");
+
+        assert!(tokens_match("secret", "secret"));
+        assert!(!tokens_match("secreT", "secret"));
+        assert!(!tokens_match("secret", "secret2"));
+    }
+
+    #[test]
+    fn test_route_hides_private_links() {
+        let mut fixtures = rott_testing::Fixtures::default();
+        let work = fixtures.link("https://example.com/review", "Review", &["work"]);
+        let essay = fixtures.link("https://example.com/essay", "Essay", &["essays"]);
+        let store = rott_testing::StoreBuilder::new()
+            .links([work.clone(), essay])
+            .private_tag("work")
+            .build()
+            .unwrap();
+
+        let get = |path: &str, include_private: bool| {
+            let response = route(&store, path, &BTreeMap::new(), include_private).unwrap();
+            (
+                response.status,
+                serde_json::from_slice::<serde_json::Value>(&response.body).unwrap(),
+            )
+        };
+
+        let (_, links) = get("/api/links", false);
+        assert_eq!(links.as_array().unwrap().len(), 1);
+        assert_eq!(links[0]["title"], "Essay");
+        let (_, tags) = get("/api/tags", false);
+        assert!(tags.get("work").is_none());
+        let (status, _) = get(&format!("/api/links/{}", work.id), false);
+        assert_eq!(status, "404 Not Found");
+
+        let (_, links) = get("/api/links", true);
+        assert_eq!(links.as_array().unwrap().len(), 2);
+        let (status, _) = get(&format!("/api/links/{}", work.id), true);
+        assert_eq!(status, "200 OK");
+    }
+
+    #[test]
+    fn test_route_serves_screenshots() {
+        let mut fixtures = rott_testing::Fixtures::default();
+        let shot = fixtures.link("https://example.com/shot", "Shot", &[]);
+        let plain = fixtures.link("https://example.com/plain", "Plain", &[]);
+        let mut store = rott_testing::StoreBuilder::new()
+            .links([shot.clone(), plain.clone()])
+            .build()
+            .unwrap();
+        let hash = AttachmentStore::from_config(store.config())
+            .put(b"\x89PNG image")
+            .unwrap();
+        let mut link = store.get_link(shot.id).unwrap().unwrap();
+        link.attachments
+            .insert(crate::screenshot::SCREENSHOT_ATTACHMENT.to_string(), hash);
+        store.update_link(&link).unwrap();
+
+        let path = format!("/api/links/{}/screenshot", shot.id);
+        let response = route(&store, &path, &BTreeMap::new(), false).unwrap();
+        assert_eq!(response.status, "200 OK");
+        assert_eq!(response.content_type, "image/png");
+        assert_eq!(response.body, b"\x89PNG image");
+
+        let path = format!("/api/links/{}/screenshot", plain.id);
+        let response = route(&store, &path, &BTreeMap::new(), false).unwrap();
+        assert_eq!(response.status, "404 Not Found");
+    }
+}
@@ -0,0 +1,60 @@
+//! Live output for `--watch`
+//!
+//! `rott link list --watch` and `rott status --watch` print their output,
+//! then print it again whenever the document is saved by another rott
+//! process (the TUI, another command, a sync), so a second terminal can
+//! act as a dashboard. Processes don't notify each other of changes, so the
+//! data directory is polled; that only lists the saved chunk files, not
+//! the document itself.
+
+use std::io::{self, Write};
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Local;
+
+use rott_core::Store;
+
+use crate::output::{Output, OutputFormat};
+
+/// How often the data directory is checked for saves
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Run `render`, then run it again after each change, until Ctrl-C
+///
+/// In human output the screen is cleared before each render. JSON and
+/// quiet output are printed one render after another, for piping.
+pub async fn watch(
+    store: &mut Store,
+    output: &Output,
+    mut render: impl FnMut(&Store) -> Result<()>,
+) -> Result<()> {
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    let mut version = store.stored_version();
+
+    loop {
+        if output.format == OutputFormat::Human {
+            print!("\x1b[2J\x1b[H");
+            println!(
+                "Updated {} (watching for changes, Ctrl-C to stop)\n",
+                Local::now().format("%H:%M:%S")
+            );
+        }
+        render(store)?;
+        io::stdout().flush()?;
+
+        loop {
+            tokio::select! {
+                _ = &mut ctrl_c => return Ok(()),
+                _ = tokio::time::sleep(POLL_INTERVAL) => {}
+            }
+            let current = store.stored_version();
+            if current != version {
+                version = current;
+                break;
+            }
+        }
+        store.reload_and_merge()?;
+    }
+}
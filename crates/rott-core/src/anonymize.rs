@@ -0,0 +1,246 @@
+//! Anonymized copies of a document for bug reports
+//!
+//! A performance or sync problem is easiest to reproduce with the document
+//! it happens on, but that document is a reading history. An anonymized
+//! copy has the same links, notes, tags and timestamps, with every piece of
+//! text replaced by placeholder text derived from a hash of it:
+//!
+//! - Titles, descriptions, notes and other free text keep their length and
+//!   line breaks, so the document keeps its size.
+//! - URLs become `https://<host hash>.example/<hash>`, so links on the same
+//!   site stay on the same (placeholder) site and duplicates stay
+//!   duplicates.
+//! - Tags, authors and recommenders become `tag-<hash>`, `author-<hash>` and
+//!   `via-<hash>`, the same placeholder wherever the same one appears.
+//!
+//! Hashes are salted with a random value for each copy, so a placeholder
+//! can't be checked against a guessed URL or title. IDs, timestamps and
+//! flags are kept as they are. The copy is a new document: it has the
+//! current state of the original but none of its history.
+
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::document::{DocumentError, RottDocument};
+use crate::domains::extract_domain;
+use crate::models::{Link, Note};
+use crate::validate::normalize_tag;
+
+/// Length of placeholder words in anonymized text
+const WORD_LEN: usize = 7;
+
+/// Replaces text with salted-hash placeholders
+#[derive(Debug, Clone)]
+pub struct Anonymizer {
+    salt: [u8; 16],
+}
+
+impl Default for Anonymizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Anonymizer {
+    /// An anonymizer with a fresh random salt
+    pub fn new() -> Self {
+        Self {
+            salt: *Uuid::new_v4().as_bytes(),
+        }
+    }
+
+    /// Hex digest of `text`, separate for each kind of value
+    fn digest(&self, kind: &str, text: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.salt);
+        hasher.update(kind.as_bytes());
+        hasher.update([0]);
+        hasher.update(text.as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Placeholder text with the same line breaks, and as many characters
+    /// on each line, as `text`
+    pub fn text(&self, text: &str) -> String {
+        let digest = self.digest("text", text);
+        text.split('\n')
+            .enumerate()
+            .map(|(i, line)| {
+                let len = line.chars().count();
+                let mut placeholder = String::with_capacity(len);
+                let mut block = 0;
+                let mut hex = String::new();
+                while placeholder.len() < len {
+                    if hex.is_empty() {
+                        hex = self.digest(&format!("line {} {}", i, block), &digest);
+                        block += 1;
+                    }
+                    if placeholder.len() % (WORD_LEN + 1) == WORD_LEN {
+                        placeholder.push(' ');
+                    } else {
+                        placeholder.push(hex.remove(0));
+                    }
+                }
+                placeholder
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// A placeholder URL on a placeholder host for the URL's host
+    pub fn url(&self, url: &str) -> String {
+        let host = extract_domain(url).unwrap_or_default();
+        format!(
+            "https://{}.example/{}",
+            &self.digest("host", &host)[..8],
+            &self.digest("url", url.trim())[..16]
+        )
+    }
+
+    /// A placeholder tag, the same for tags that normalize the same
+    pub fn tag(&self, tag: &str) -> String {
+        let key = normalize_tag(tag).unwrap_or_else(|| tag.trim().to_lowercase());
+        format!("tag-{}", &self.digest("tag", &key)[..8])
+    }
+
+    /// A placeholder for a person's name (`kind` is `author` or `via`)
+    fn name(&self, kind: &str, name: &str) -> String {
+        format!(
+            "{}-{}",
+            kind,
+            &self.digest(kind, &name.trim().to_lowercase())[..8]
+        )
+    }
+
+    /// A copy of a note with its title and body replaced
+    pub fn note(&self, note: &Note) -> Note {
+        Note {
+            title: note.title.as_deref().map(|t| self.text(t)),
+            body: self.text(&note.body),
+            ..note.clone()
+        }
+    }
+
+    /// A copy of a link with all its text replaced
+    pub fn link(&self, link: &Link) -> Link {
+        Link {
+            title: self.text(&link.title),
+            url: self.url(&link.url),
+            description: link.description.as_deref().map(|d| self.text(d)),
+            author: link.author.iter().map(|a| self.name("author", a)).collect(),
+            tags: link.tags.iter().map(|t| self.tag(t)).collect(),
+            notes: link.notes.iter().map(|n| self.note(n)).collect(),
+            content_hash: link
+                .content_hash
+                .as_deref()
+                .map(|h| self.digest("content", h)[..h.len().min(64)].to_string()),
+            canonical_url: link.canonical_url.as_deref().map(|u| self.url(u)),
+            via: link.via.as_deref().map(|v| self.name("via", v)),
+            source_context: link.source_context.as_deref().map(|s| self.text(s)),
+            derived: link
+                .derived
+                .iter()
+                .map(|(key, value)| (key.clone(), self.text(value)))
+                .collect(),
+            ..link.clone()
+        }
+    }
+}
+
+/// An anonymized copy of a document's links, tag descriptions and tag
+/// visibility (see the [module docs](self))
+///
+/// The copy gets a new document ID. Archives are separate documents and
+/// aren't included.
+pub fn anonymize_document(doc: &RottDocument) -> Result<RottDocument, DocumentError> {
+    let anonymizer = Anonymizer::new();
+    let mut copy = RottDocument::new();
+
+    for link in doc.get_all_links()? {
+        copy.add_link(&anonymizer.link(&link))?;
+    }
+    for (tag, description) in doc.get_tag_descriptions()? {
+        copy.set_tag_description(&anonymizer.tag(&tag), Some(&anonymizer.text(&description)))?;
+    }
+    for (tag, mark) in doc.get_tag_visibility()? {
+        copy.set_tag_visibility(&anonymizer.tag(&tag), Some(mark))?;
+    }
+    Ok(copy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_keeps_shape() {
+        let anonymizer = Anonymizer::new();
+        let text = "A title\n\nwith a much longer second line, to span hash blocks many times over and over";
+        let placeholder = anonymizer.text(text);
+
+        assert_ne!(placeholder, text);
+        let lines: Vec<&str> = placeholder.split('\n').collect();
+        assert_eq!(lines.len(), 3);
+        for (line, original) in lines.iter().zip(text.split('\n')) {
+            assert_eq!(line.chars().count(), original.chars().count());
+        }
+        assert_eq!(anonymizer.text(text), placeholder);
+        assert_eq!(anonymizer.text(""), "");
+        assert_eq!(anonymizer.text("ünï").chars().count(), 3);
+
+        // A different salt gives different placeholders
+        assert_ne!(Anonymizer::new().text(text), placeholder);
+    }
+
+    #[test]
+    fn test_urls_and_tags_keep_grouping() {
+        let anonymizer = Anonymizer::new();
+        let a = anonymizer.url("https://example.com/a");
+        let b = anonymizer.url("https://example.com/b");
+        let other = anonymizer.url("https://other.org/a");
+
+        let host = |url: &str| extract_domain(url).unwrap();
+        assert_eq!(host(&a), host(&b));
+        assert_ne!(host(&a), host(&other));
+        assert_ne!(a, b);
+        assert!(!a.contains("example.com"));
+
+        assert_eq!(anonymizer.tag("Rust Lang"), anonymizer.tag("rust-lang"));
+        assert_ne!(anonymizer.tag("rust"), anonymizer.tag("go"));
+    }
+
+    #[test]
+    fn test_anonymize_document() {
+        let mut doc = RottDocument::new();
+        let mut link = Link::new("https://secret.example.com/reading");
+        link.set_title("My secret reading");
+        link.add_tag("private-stuff");
+        link.author = vec!["Jane Doe".to_string()];
+        let mut note = Note::new("What I really think");
+        note.title = Some("Thoughts".to_string());
+        link.notes.push(note);
+        doc.add_link(&link).unwrap();
+        doc.set_tag_description("private-stuff", Some("Things I read"))
+            .unwrap();
+
+        let copy = anonymize_document(&doc).unwrap();
+        assert_ne!(copy.id(), doc.id());
+
+        let links = copy.get_all_links().unwrap();
+        assert_eq!(links.len(), 1);
+        let anonymized = &links[0];
+        assert_eq!(anonymized.id, link.id);
+        assert_eq!(anonymized.created_at, link.created_at);
+        assert_eq!(anonymized.notes.len(), 1);
+        assert_eq!(anonymized.notes[0].id, link.notes[0].id);
+        assert_eq!(anonymized.tags.len(), 1);
+
+        let descriptions = copy.get_tag_descriptions().unwrap();
+        assert!(descriptions.contains_key(&anonymized.tags[0]));
+
+        let everything = format!("{:?} {:?}", links, descriptions);
+        for secret in ["secret", "Jane", "really", "Thoughts", "private", "Things"] {
+            assert!(!everything.contains(secret), "{} leaked", secret);
+        }
+    }
+}
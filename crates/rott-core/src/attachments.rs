@@ -0,0 +1,277 @@
+//! Files attached to links
+//!
+//! Attachment content is kept out of the document: each file is a blob
+//! under `attachments/` in the collection's directory, named by the SHA-256
+//! of its content, and links refer to blobs by that hash
+//! ([`Link::attachments`](crate::Link::attachments)). The same content
+//! attached twice is stored once.
+//!
+//! Blobs stay behind when the links referring to them are deleted or
+//! changed. [`AttachmentStore::collect_garbage`] removes them, but only
+//! once they've gone unreferenced for a grace period: a blob written just
+//! before the link referring to it is saved, or referred to by a change
+//! that hasn't synced to this device yet, is left alone. When each blob
+//! was first found unreferenced is kept in `attachments/orphans.json`.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use sha2::{Digest, Sha256};
+
+use crate::config::Config;
+
+/// How long a blob must go unreferenced before it's removed, by default
+pub const DEFAULT_GRACE_DAYS: u32 = 7;
+
+/// File recording when each unreferenced blob was first found
+const ORPHANS_FILE: &str = "orphans.json";
+
+/// What [`AttachmentStore::collect_garbage`] removed and kept
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GcReport {
+    /// Blobs still referenced by a link
+    pub referenced: usize,
+    /// Unreferenced blobs removed (or, on a dry run, that would be), with
+    /// their sizes
+    pub removed: Vec<(String, u64)>,
+    /// Unreferenced blobs kept because the grace period hasn't passed
+    pub pending: usize,
+}
+
+impl GcReport {
+    /// Bytes freed by removing blobs
+    pub fn reclaimed_bytes(&self) -> u64 {
+        self.removed.iter().map(|(_, size)| size).sum()
+    }
+}
+
+/// Content-addressed storage for attachment blobs
+#[derive(Debug, Clone)]
+pub struct AttachmentStore {
+    dir: PathBuf,
+}
+
+impl AttachmentStore {
+    /// Create a store for blobs in `dir`
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The attachment store of a configuration's active collection
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(config.attachments_dir())
+    }
+
+    /// Store content, returning its hash
+    ///
+    /// Content that's already stored isn't written again.
+    pub fn put(&self, content: &[u8]) -> Result<String> {
+        let hash = hash_content(content);
+        let path = self.path(&hash);
+        if path.exists() {
+            return Ok(hash);
+        }
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+
+        // A temporary name unique to this process, renamed into place
+        let temp_path = path.with_extension(format!("{}.tmp", std::process::id()));
+        let mut file = fs::File::create(&temp_path)
+            .with_context(|| format!("Failed to create {}", temp_path.display()))?;
+        file.write_all(content)
+            .with_context(|| format!("Failed to write {}", temp_path.display()))?;
+        fs::rename(&temp_path, &path)
+            .with_context(|| format!("Failed to rename {}", temp_path.display()))?;
+        Ok(hash)
+    }
+
+    /// Read a blob's content, if it's on this device
+    pub fn get(&self, hash: &str) -> Result<Option<Vec<u8>>> {
+        match fs::read(self.path(hash)) {
+            Ok(content) => Ok(Some(content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("Failed to read attachment {}", hash)),
+        }
+    }
+
+    /// Path of a blob
+    pub fn path(&self, hash: &str) -> PathBuf {
+        self.dir.join(hash)
+    }
+
+    /// Every stored blob, with its size
+    pub fn blobs(&self) -> Result<Vec<(String, u64)>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read {}", self.dir.display()))
+            }
+        };
+        let mut blobs = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if is_hash(&name) {
+                blobs.push((name, entry.metadata()?.len()));
+            }
+        }
+        blobs.sort();
+        Ok(blobs)
+    }
+
+    /// Remove blobs no link refers to any more
+    ///
+    /// `referenced` is every hash links refer to, archived links included.
+    /// Blobs are removed once they've been found unreferenced for `grace`;
+    /// ones found for the first time are only recorded. A dry run reports
+    /// what would be removed without removing or recording anything.
+    pub fn collect_garbage(
+        &self,
+        referenced: &HashSet<String>,
+        grace: Duration,
+        now: DateTime<Utc>,
+        dry_run: bool,
+    ) -> Result<GcReport> {
+        let previous = self.read_orphans();
+        let mut orphans = BTreeMap::new();
+        let mut report = GcReport::default();
+
+        for (hash, size) in self.blobs()? {
+            if referenced.contains(&hash) {
+                report.referenced += 1;
+                continue;
+            }
+            let first_seen = previous.get(&hash).copied().unwrap_or(now);
+            if now - first_seen < grace {
+                report.pending += 1;
+                orphans.insert(hash, first_seen);
+                continue;
+            }
+            if !dry_run {
+                fs::remove_file(self.path(&hash))
+                    .with_context(|| format!("Failed to remove attachment {}", hash))?;
+            }
+            report.removed.push((hash, size));
+        }
+
+        if !dry_run && (!orphans.is_empty() || !previous.is_empty()) {
+            self.write_orphans(&orphans)?;
+        }
+        Ok(report)
+    }
+
+    /// When each unreferenced blob was first found (empty if unreadable)
+    fn read_orphans(&self) -> BTreeMap<String, DateTime<Utc>> {
+        fs::read(self.dir.join(ORPHANS_FILE))
+            .ok()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .unwrap_or_default()
+    }
+
+    fn write_orphans(&self, orphans: &BTreeMap<String, DateTime<Utc>>) -> Result<()> {
+        let path = self.dir.join(ORPHANS_FILE);
+        let data = serde_json::to_vec_pretty(orphans).context("Failed to serialize orphans")?;
+        fs::write(&path, data).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Hex SHA-256 of some content
+pub fn hash_content(content: &[u8]) -> String {
+    Sha256::digest(content)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Whether a file name is a blob's (rather than a temporary or the orphans file)
+fn is_hash(name: &str) -> bool {
+    name.len() == 64 && name.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_put_and_get() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = AttachmentStore::new(temp_dir.path().join("attachments"));
+
+        let hash = store.put(b"screenshot").unwrap();
+        assert_eq!(hash, hash_content(b"screenshot"));
+        assert_eq!(store.put(b"screenshot").unwrap(), hash);
+        assert_eq!(
+            store.get(&hash).unwrap().as_deref(),
+            Some(&b"screenshot"[..])
+        );
+        assert_eq!(store.get(&hash_content(b"other")).unwrap(), None);
+        assert_eq!(store.blobs().unwrap(), vec![(hash, 10)]);
+    }
+
+    #[test]
+    fn test_collect_garbage_after_grace_period() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = AttachmentStore::new(temp_dir.path());
+        let kept = store.put(b"kept").unwrap();
+        let orphan = store.put(b"orphan").unwrap();
+        let referenced: HashSet<String> = [kept.clone()].into();
+        let grace = Duration::days(i64::from(DEFAULT_GRACE_DAYS));
+        let now = Utc::now();
+
+        // First found unreferenced: recorded, not removed
+        let report = store
+            .collect_garbage(&referenced, grace, now, false)
+            .unwrap();
+        assert_eq!(report.referenced, 1);
+        assert_eq!(report.pending, 1);
+        assert!(report.removed.is_empty());
+
+        // Still within the grace period
+        let report = store
+            .collect_garbage(&referenced, grace, now + Duration::days(1), false)
+            .unwrap();
+        assert_eq!(report.pending, 1);
+
+        // A dry run reports it without removing it
+        let later = now + grace;
+        let report = store
+            .collect_garbage(&referenced, grace, later, true)
+            .unwrap();
+        assert_eq!(report.removed, vec![(orphan.clone(), 6)]);
+        assert!(store.get(&orphan).unwrap().is_some());
+
+        let report = store
+            .collect_garbage(&referenced, grace, later, false)
+            .unwrap();
+        assert_eq!(report.reclaimed_bytes(), 6);
+        assert!(store.get(&orphan).unwrap().is_none());
+        assert!(store.get(&kept).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_referenced_again_restarts_grace_period() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = AttachmentStore::new(temp_dir.path());
+        let hash = store.put(b"blob").unwrap();
+        let grace = Duration::days(1);
+        let now = Utc::now();
+
+        store
+            .collect_garbage(&HashSet::new(), grace, now, false)
+            .unwrap();
+        store
+            .collect_garbage(&[hash.clone()].into(), grace, now, false)
+            .unwrap();
+        let report = store
+            .collect_garbage(&HashSet::new(), grace, now + grace, false)
+            .unwrap();
+        assert_eq!(report.pending, 1);
+        assert!(report.removed.is_empty());
+    }
+}
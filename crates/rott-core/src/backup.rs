@@ -0,0 +1,397 @@
+//! Full backup archives (`.rottpack`)
+//!
+//! A backup is a single self-contained file with everything needed to
+//! restore ROTT on a new machine: the Automerge document (with its full
+//! history), the root document ID (the identity), the per-year archive
+//! documents and attachment blobs on this device, and the configuration.
+//! Restoring one is an alternative to joining and resyncing.
+//!
+//! File layout:
+//!
+//! ```text
+//! "ROTTPACK" | format version (1 byte) | SHA-256 of payload (32 bytes) | payload (CBOR)
+//! ```
+//!
+//! The checksum is verified before anything is decoded, the document must
+//! load and match the recorded root document ID, each archive must load,
+//! and each attachment must match its hash.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_bytes::ByteBuf;
+use sha2::{Digest, Sha256};
+
+use crate::attachments::{hash_content, AttachmentStore};
+use crate::config::Config;
+use crate::document::RottDocument;
+use crate::document_id::DocumentId;
+use crate::lock::LockSession;
+use crate::storage::AutomergePersistence;
+
+/// File extension for backup archives
+pub const BACKUP_EXTENSION: &str = "rottpack";
+
+/// Current backup format version
+pub const FORMAT_VERSION: u8 = 1;
+
+const MAGIC: &[u8; 8] = b"ROTTPACK";
+const HEADER_LEN: usize = MAGIC.len() + 1 + 32;
+
+/// What's stored inside the archive
+#[derive(Serialize, Deserialize)]
+struct Payload {
+    created_at: DateTime<Utc>,
+    root_doc_id: String,
+    #[serde(with = "serde_bytes")]
+    document: Vec<u8>,
+    /// Saved archive documents (absent in backups made before archives)
+    #[serde(default)]
+    archives: Vec<ByteBuf>,
+    /// Attachment blobs by hash (absent in backups made before attachments)
+    #[serde(default)]
+    attachments: BTreeMap<String, ByteBuf>,
+    /// Configuration as TOML
+    config: String,
+}
+
+/// A verified backup, ready to write out or restore
+#[derive(Debug, Clone)]
+pub struct Backup {
+    /// When the backup was made
+    pub created_at: DateTime<Utc>,
+    /// Identity of the backed-up collection
+    pub root_id: DocumentId,
+    /// The saved Automerge document
+    pub document: Vec<u8>,
+    /// Saved archive documents (see
+    /// [`Store::archive_links`](crate::Store::archive_links))
+    pub archives: Vec<Vec<u8>>,
+    /// Attachment blobs by hash (see [`crate::attachments`])
+    pub attachments: BTreeMap<String, Vec<u8>>,
+    /// Configuration at the time of the backup
+    pub config: Config,
+}
+
+impl Backup {
+    /// Create a backup from a saved document and its configuration
+    pub fn new(root_id: DocumentId, document: Vec<u8>, config: &Config) -> Self {
+        Self {
+            created_at: Utc::now(),
+            root_id,
+            document,
+            archives: Vec::new(),
+            attachments: BTreeMap::new(),
+            config: config.clone(),
+        }
+    }
+
+    /// Include saved archive documents
+    pub fn with_archives(mut self, archives: Vec<Vec<u8>>) -> Self {
+        self.archives = archives;
+        self
+    }
+
+    /// Include attachment blobs, by hash
+    pub fn with_attachments(mut self, attachments: BTreeMap<String, Vec<u8>>) -> Self {
+        self.attachments = attachments;
+        self
+    }
+
+    /// Encode the backup as an archive
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let payload = Payload {
+            created_at: self.created_at,
+            root_doc_id: self.root_id.to_bs58check(),
+            document: self.document.clone(),
+            archives: self.archives.iter().cloned().map(ByteBuf::from).collect(),
+            attachments: self
+                .attachments
+                .iter()
+                .map(|(hash, content)| (hash.clone(), ByteBuf::from(content.clone())))
+                .collect(),
+            config: toml::to_string_pretty(&self.config)
+                .context("Failed to serialize configuration")?,
+        };
+
+        let mut body = Vec::new();
+        ciborium::into_writer(&payload, &mut body).context("Failed to encode backup")?;
+
+        let mut bytes = Vec::with_capacity(HEADER_LEN + body.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(FORMAT_VERSION);
+        bytes.extend_from_slice(&Sha256::digest(&body));
+        bytes.extend_from_slice(&body);
+        Ok(bytes)
+    }
+
+    /// Decode and verify an archive
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < HEADER_LEN || &bytes[..MAGIC.len()] != MAGIC {
+            bail!("Not a ROTT backup file");
+        }
+
+        let version = bytes[MAGIC.len()];
+        if version != FORMAT_VERSION {
+            bail!(
+                "Unsupported backup format version {} (this version of ROTT reads version {})",
+                version,
+                FORMAT_VERSION
+            );
+        }
+
+        let checksum = &bytes[MAGIC.len() + 1..HEADER_LEN];
+        let body = &bytes[HEADER_LEN..];
+        if Sha256::digest(body).as_slice() != checksum {
+            bail!("Backup is corrupt: checksum mismatch");
+        }
+
+        let payload: Payload =
+            ciborium::from_reader(body).context("Backup is corrupt: invalid contents")?;
+
+        let root_id = DocumentId::from_bs58check(&payload.root_doc_id)
+            .context("Backup is corrupt: invalid root document ID")?;
+        let doc = RottDocument::load(&payload.document)
+            .context("Backup is corrupt: document can't be loaded")?;
+        if *doc.id() != root_id {
+            bail!("Backup is corrupt: document doesn't match its root document ID");
+        }
+        let archives: Vec<Vec<u8>> = payload
+            .archives
+            .into_iter()
+            .map(ByteBuf::into_vec)
+            .collect();
+        for archive in &archives {
+            RottDocument::load(archive).context("Backup is corrupt: archive can't be loaded")?;
+        }
+        let mut attachments = BTreeMap::new();
+        for (hash, content) in payload.attachments {
+            if hash_content(&content) != hash {
+                bail!(
+                    "Backup is corrupt: attachment {} doesn't match its hash",
+                    hash
+                );
+            }
+            attachments.insert(hash, content.into_vec());
+        }
+
+        // Parsed directly: environment overrides belong to the restoring machine
+        let config: Config =
+            toml::from_str(&payload.config).context("Backup is corrupt: invalid config")?;
+
+        Ok(Self {
+            created_at: payload.created_at,
+            root_id,
+            document: payload.document,
+            archives,
+            attachments,
+            config,
+        })
+    }
+
+    /// Number of links in the backed-up document
+    pub fn link_count(&self) -> Result<usize> {
+        let doc = RottDocument::load(&self.document).context("Failed to load document")?;
+        Ok(doc.link_count()?)
+    }
+
+    /// Number of links in the backed-up archives
+    pub fn archived_link_count(&self) -> Result<usize> {
+        let mut count = 0;
+        for archive in &self.archives {
+            let doc = RottDocument::load(archive).context("Failed to load archive")?;
+            count += doc.link_count()?;
+        }
+        Ok(count)
+    }
+
+    /// Restore the backup into the data directory of `target`
+    ///
+    /// Replaces any existing document and identity, and writes the backed-up
+    /// archives and attachments. The existing data is moved aside first and only deleted once
+    /// the restored documents are saved; if saving fails, it's put back. If `config_path` is given, the
+    /// backed-up configuration is written there too, keeping `target`'s data
+    /// directory (it's specific to this machine).
+    pub fn restore(&self, target: &Config, config_path: Option<&PathBuf>) -> Result<()> {
+        let persistence = AutomergePersistence::new(target.clone());
+        persistence
+            .validate_storage()
+            .context("Storage validation failed")?;
+
+        let mut doc = RottDocument::load(&self.document).context("Failed to load document")?;
+        let replaced = persistence
+            .set_aside()
+            .context("Failed to move existing data aside")?;
+        if let Err(e) = self.write_documents(&persistence, &mut doc) {
+            if let Err(put_back) = replaced.put_back() {
+                tracing::warn!("Couldn't put back the replaced data: {:#}", put_back);
+            }
+            return Err(e.context("Failed to restore document"));
+        }
+        replaced
+            .discard()
+            .context("Failed to remove the replaced data")?;
+
+        if let Some(path) = config_path {
+            let config = Config {
+                data_dir: target.data_dir.clone(),
+                ..self.config.clone()
+            };
+            config
+                .save_to_path(path)
+                .context("Failed to restore configuration")?;
+        }
+
+        // A restored machine starts locked if the backup had a passphrase
+        LockSession::new(target).lock()?;
+
+        Ok(())
+    }
+
+    /// Save the root document, the archives and the attachments
+    fn write_documents(
+        &self,
+        persistence: &AutomergePersistence,
+        doc: &mut RottDocument,
+    ) -> Result<()> {
+        persistence.save(doc)?;
+        for archive in &self.archives {
+            let mut archive = RottDocument::load(archive).context("Failed to load archive")?;
+            persistence
+                .save_document(&mut archive)
+                .context("Failed to restore archive")?;
+        }
+        let blobs = AttachmentStore::new(persistence.config().attachments_dir());
+        for content in self.attachments.values() {
+            blobs.put(content).context("Failed to restore attachment")?;
+        }
+        Ok(())
+    }
+}
+
+/// Load the document from a backup archive or a raw saved document
+///
+/// Used for selective restores, where either kind of file will do.
+pub fn load_document(bytes: &[u8]) -> Result<RottDocument> {
+    if bytes.starts_with(MAGIC) {
+        let backup = Backup::from_bytes(bytes)?;
+        return RottDocument::load(&backup.document).context("Failed to load document");
+    }
+    RottDocument::load(bytes).context("Not a ROTT backup or document file")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Link;
+    use tempfile::TempDir;
+
+    fn sample_backup() -> Backup {
+        let mut doc = RottDocument::new();
+        let mut link = Link::new("https://example.com");
+        link.set_title("Example");
+        doc.add_link(&link).unwrap();
+
+        let mut archive = RottDocument::new();
+        archive
+            .add_link(&Link::new("https://archived.example.com"))
+            .unwrap();
+        doc.set_archive(2020, archive.id()).unwrap();
+
+        let config = Config {
+            favorite_tags: vec!["starred".to_string()],
+            ..Config::default()
+        };
+        let attachment = b"attached".to_vec();
+        Backup::new(*doc.id(), doc.save(), &config)
+            .with_archives(vec![archive.save()])
+            .with_attachments(BTreeMap::from([(hash_content(&attachment), attachment)]))
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let backup = sample_backup();
+        let restored = Backup::from_bytes(&backup.to_bytes().unwrap()).unwrap();
+
+        assert_eq!(restored.root_id, backup.root_id);
+        assert_eq!(restored.document, backup.document);
+        assert_eq!(restored.archives, backup.archives);
+        assert_eq!(restored.attachments, backup.attachments);
+        assert_eq!(restored.config.favorite_tags, ["starred"]);
+        assert_eq!(restored.link_count().unwrap(), 1);
+        assert_eq!(restored.archived_link_count().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_rejects_damaged_files() {
+        let mut bytes = sample_backup().to_bytes().unwrap();
+
+        assert!(Backup::from_bytes(b"not a backup").is_err());
+
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        let err = Backup::from_bytes(&bytes).unwrap_err();
+        assert!(err.to_string().contains("checksum"));
+
+        bytes[last] ^= 0xff;
+        bytes[MAGIC.len()] = FORMAT_VERSION + 1;
+        let err = Backup::from_bytes(&bytes).unwrap_err();
+        assert!(err.to_string().contains("Unsupported"));
+    }
+
+    #[test]
+    fn test_load_document() {
+        let backup = sample_backup();
+
+        let from_archive = load_document(&backup.to_bytes().unwrap()).unwrap();
+        assert_eq!(*from_archive.id(), backup.root_id);
+        let from_raw = load_document(&backup.document).unwrap();
+        assert_eq!(from_raw.link_count().unwrap(), 1);
+
+        assert!(load_document(b"garbage").is_err());
+    }
+
+    #[test]
+    fn test_restore() {
+        let temp_dir = TempDir::new().unwrap();
+        let target = Config {
+            data_dir: temp_dir.path().join("data"),
+            ..Config::default()
+        };
+        let config_path = temp_dir.path().join("config.toml");
+
+        // Existing data is replaced
+        let mut existing = RottDocument::new();
+        AutomergePersistence::new(target.clone())
+            .save(&mut existing)
+            .unwrap();
+
+        let backup = sample_backup();
+        backup.restore(&target, Some(&config_path)).unwrap();
+
+        let persistence = AutomergePersistence::new(target.clone());
+        let doc = persistence.load().unwrap().unwrap();
+        assert_eq!(*doc.id(), backup.root_id);
+        assert_eq!(
+            persistence.load_root_doc_id().unwrap(),
+            Some(backup.root_id)
+        );
+        assert!(!target.document_dir(existing.id()).exists());
+        let archive_id = *doc.get_archives().unwrap().get(&2020).unwrap();
+        let archive = persistence.load_document(&archive_id).unwrap().unwrap();
+        assert_eq!(archive.link_count().unwrap(), 1);
+        let blobs = AttachmentStore::from_config(&target);
+        assert_eq!(
+            blobs.get(&hash_content(b"attached")).unwrap().as_deref(),
+            Some(&b"attached"[..])
+        );
+
+        let config: Config =
+            toml::from_str(&std::fs::read_to_string(&config_path).unwrap()).unwrap();
+        assert_eq!(config.data_dir, target.data_dir);
+        assert_eq!(config.favorite_tags, ["starred"]);
+    }
+}
@@ -0,0 +1,376 @@
+//! Browser bookmark exports
+//!
+//! Chrome, Firefox, Safari and Edge all export bookmarks as "Netscape
+//! bookmark" HTML: folders are `<H3>` headings, each followed by a `<DL>`
+//! list holding its bookmarks (`<A HREF=...>`) and subfolders. The format
+//! is loose HTML (unclosed `<DT>` and `<p>` tags), so it's read with a
+//! small tag scanner rather than an HTML parser.
+//!
+//! [`parse_bookmarks`] reads the bookmarks with the folders they're in, and
+//! [`Bookmark::to_link`] turns one into a link tagged with its folders.
+
+use chrono::{DateTime, TimeZone, Utc};
+
+use crate::models::Link;
+use crate::validate::normalize_tag;
+
+/// Folders browsers put every bookmark under, which aren't worth a tag
+const ROOT_FOLDERS: &[&str] = &[
+    "Bookmarks",
+    "Bookmarks bar",
+    "Bookmarks Bar",
+    "Bookmarks Menu",
+    "Bookmarks Toolbar",
+    "Favorites",
+    "Favorites bar",
+    "Favourites bar",
+    "Mobile bookmarks",
+    "Mobile Bookmarks",
+    "Other bookmarks",
+    "Other Bookmarks",
+];
+
+/// A bookmark from a browser export
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bookmark {
+    pub url: String,
+    /// The bookmark's name (None if blank)
+    pub title: Option<String>,
+    /// Folders the bookmark is in, outermost first, without browser root
+    /// folders such as "Bookmarks bar"
+    pub folders: Vec<String>,
+    /// Tags Firefox exports in the `TAGS` attribute
+    pub tags: Vec<String>,
+    /// The description Firefox exports after the bookmark (`<DD>`)
+    pub description: Option<String>,
+    /// When the bookmark was added (`ADD_DATE`)
+    pub added_at: Option<DateTime<Utc>>,
+}
+
+impl Bookmark {
+    /// The bookmark as a new link
+    ///
+    /// Folders and tags become tags (normalized, so "Rust Lang" is
+    /// `rust-lang`), and the link counts as saved when it was bookmarked.
+    pub fn to_link(&self) -> Link {
+        let mut link = Link::new(&self.url);
+        if let Some(ref title) = self.title {
+            link.set_title(title.clone());
+        }
+        link.description = self.description.clone();
+        for tag in self.folders.iter().chain(&self.tags) {
+            if let Some(tag) = normalize_tag(tag) {
+                link.add_tag(tag);
+            }
+        }
+        if let Some(added_at) = self.added_at {
+            link.created_at = added_at;
+            link.updated_at = added_at;
+        }
+        link
+    }
+}
+
+/// Read the bookmarks in a Netscape bookmark HTML export, in file order
+///
+/// Anything that isn't a bookmark or folder (separators, the file's
+/// heading, feeds without a `HREF`) is ignored, so a file that isn't a
+/// bookmark export just has no bookmarks.
+pub fn parse_bookmarks(html: &str) -> Vec<Bookmark> {
+    let mut bookmarks: Vec<Bookmark> = Vec::new();
+    // One entry per open <DL>: the folder it lists, if it's worth a tag
+    let mut lists: Vec<Option<String>> = Vec::new();
+    // The last folder heading, waiting for its <DL>
+    let mut heading: Option<Option<String>> = None;
+
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = Tag::parse(&rest[start + 1..start + end]);
+        rest = &rest[start + end + 1..];
+
+        match tag.name.as_str() {
+            "h3" => {
+                let (text, after) = text_until(rest, "</h3");
+                rest = after;
+                let name = decode_entities(text.trim());
+                let is_root = tag.attribute("personal_toolbar_folder").is_some()
+                    || tag.attribute("unfiled_bookmarks_folder").is_some()
+                    || ROOT_FOLDERS.contains(&name.as_str());
+                heading = Some((!is_root && !name.is_empty()).then_some(name));
+            }
+            "dl" => lists.push(heading.take().flatten()),
+            "/dl" => {
+                lists.pop();
+            }
+            "dd" => {
+                let end = rest.find('<').unwrap_or(rest.len());
+                let description = decode_entities(rest[..end].trim());
+                rest = &rest[end..];
+                if let Some(bookmark) = bookmarks.last_mut() {
+                    if bookmark.description.is_none() && !description.is_empty() {
+                        bookmark.description = Some(description);
+                    }
+                }
+            }
+            "a" => {
+                let (text, after) = text_until(rest, "</a");
+                rest = after;
+                let Some(url) = tag.attribute("href").map(|h| decode_entities(h.trim())) else {
+                    continue;
+                };
+                if url.is_empty() {
+                    continue;
+                }
+                let title = decode_entities(text.trim());
+                bookmarks.push(Bookmark {
+                    url,
+                    title: (!title.is_empty()).then_some(title),
+                    folders: lists.iter().flatten().cloned().collect(),
+                    tags: tag
+                        .attribute("tags")
+                        .map(|tags| {
+                            decode_entities(tags)
+                                .split(',')
+                                .map(str::trim)
+                                .filter(|t| !t.is_empty())
+                                .map(String::from)
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                    description: None,
+                    added_at: tag
+                        .attribute("add_date")
+                        .and_then(|d| d.trim().parse::<i64>().ok())
+                        .and_then(timestamp),
+                });
+            }
+            _ => {}
+        }
+    }
+    bookmarks
+}
+
+/// An opening or closing tag, with its name and attribute names lowercased
+struct Tag<'a> {
+    name: String,
+    attributes: Vec<(String, &'a str)>,
+}
+
+impl<'a> Tag<'a> {
+    /// Parse the inside of `<...>`
+    fn parse(inner: &'a str) -> Self {
+        let inner = inner.trim().trim_end_matches('/');
+        let name_end = inner
+            .find(|c: char| c.is_whitespace())
+            .unwrap_or(inner.len());
+        let name = inner[..name_end].to_ascii_lowercase();
+
+        let mut attributes = Vec::new();
+        let mut rest = inner[name_end..].trim_start();
+        while !rest.is_empty() {
+            let key_end = rest
+                .find(|c: char| c == '=' || c.is_whitespace())
+                .unwrap_or(rest.len());
+            let key = rest[..key_end].to_ascii_lowercase();
+            rest = rest[key_end..].trim_start();
+            let value = if let Some(after) = rest.strip_prefix('=') {
+                let after = after.trim_start();
+                let (value, remaining) = match after.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let body = &after[1..];
+                        let close = body.find(quote).unwrap_or(body.len());
+                        (&body[..close], body.get(close + 1..).unwrap_or(""))
+                    }
+                    _ => {
+                        let close = after.find(char::is_whitespace).unwrap_or(after.len());
+                        (&after[..close], &after[close..])
+                    }
+                };
+                rest = remaining.trim_start();
+                value
+            } else {
+                ""
+            };
+            if !key.is_empty() {
+                attributes.push((key, value));
+            }
+        }
+        Self { name, attributes }
+    }
+
+    /// The value of an attribute (by lowercase name)
+    fn attribute(&self, name: &str) -> Option<&'a str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| *value)
+    }
+}
+
+/// The text before a closing tag (matched ignoring case), and what follows
+/// that tag
+fn text_until<'a>(html: &'a str, closing: &str) -> (&'a str, &'a str) {
+    let at = html
+        .match_indices('<')
+        .map(|(at, _)| at)
+        .find(|&at| {
+            html.get(at..at + closing.len())
+                .is_some_and(|tag| tag.eq_ignore_ascii_case(closing))
+        })
+        .unwrap_or(html.len());
+    let after = html[at..]
+        .find('>')
+        .map(|end| &html[at + end + 1..])
+        .unwrap_or("");
+    (&html[..at], after)
+}
+
+/// An `ADD_DATE` as a time: seconds since the epoch, though some exports
+/// use milliseconds or microseconds
+fn timestamp(value: i64) -> Option<DateTime<Utc>> {
+    let seconds = match value {
+        v if v <= 0 => return None,
+        v if v > 100_000_000_000_000 => v / 1_000_000,
+        v if v > 100_000_000_000 => v / 1_000,
+        v => v,
+    };
+    Utc.timestamp_opt(seconds, 0).single()
+}
+
+/// Replace the HTML entities exports use (`&amp;`, `&#39;`, ...)
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(at) = rest.find('&') {
+        out.push_str(&rest[..at]);
+        rest = &rest[at..];
+        let decoded = rest.find(';').and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|n| n.parse().ok()))
+                    .and_then(char::from_u32),
+            }?;
+            Some((c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=UTF-8">
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks</H1>
+<DL><p>
+    <DT><H3 ADD_DATE="1700000000" PERSONAL_TOOLBAR_FOLDER="true">Bookmarks bar</H3>
+    <DL><p>
+        <DT><A HREF="https://www.rust-lang.org/" ADD_DATE="1700000100">Rust</A>
+        <DT><H3 ADD_DATE="1700000000">Reading List</H3>
+        <DL><p>
+            <DT><H3>Databases &amp; Storage</H3>
+            <DL><p>
+                <DT><A HREF="https://example.com/crdt?a=1&amp;b=2" ADD_DATE="1700000200000" TAGS="crdt,Sync">CRDTs &#8211; a primer</A>
+            </DL><p>
+            <DD>How they merge
+            <DT><a href='https://example.org/' add_date=1700000300></a>
+        </DL><p>
+    </DL><p>
+    <HR>
+    <DT><A HREF="https://example.net/top">Top level</A>
+    <DT><A FEEDURL="https://example.net/feed">Feed without a link</A>
+</DL><p>
+"#;
+
+    #[test]
+    fn test_parse_bookmarks() {
+        let bookmarks = parse_bookmarks(EXPORT);
+        let urls: Vec<_> = bookmarks.iter().map(|b| b.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://www.rust-lang.org/",
+                "https://example.com/crdt?a=1&b=2",
+                "https://example.org/",
+                "https://example.net/top",
+            ]
+        );
+
+        assert!(bookmarks[0].folders.is_empty());
+        assert_eq!(
+            bookmarks[0].added_at.map(|t| t.timestamp()),
+            Some(1_700_000_100)
+        );
+
+        let crdt = &bookmarks[1];
+        assert_eq!(crdt.title.as_deref(), Some("CRDTs – a primer"));
+        assert_eq!(crdt.folders, ["Reading List", "Databases & Storage"]);
+        assert_eq!(crdt.tags, ["crdt", "Sync"]);
+        assert_eq!(crdt.description.as_deref(), Some("How they merge"));
+        // Milliseconds are read as such
+        assert_eq!(crdt.added_at.map(|t| t.timestamp()), Some(1_700_000_200));
+
+        assert_eq!(bookmarks[2].title, None);
+        assert_eq!(bookmarks[2].folders, ["Reading List"]);
+        assert!(bookmarks[3].folders.is_empty());
+    }
+
+    #[test]
+    fn test_bookmark_to_link() {
+        let bookmarks = parse_bookmarks(EXPORT);
+
+        let link = bookmarks[1].to_link();
+        assert_eq!(link.title, "CRDTs – a primer");
+        assert_eq!(
+            link.tags,
+            ["reading-list", "databases-storage", "crdt", "sync"]
+        );
+        assert_eq!(link.created_at.timestamp(), 1_700_000_200);
+        assert_eq!(link.description.as_deref(), Some("How they merge"));
+
+        // Untitled bookmarks are titled by their URL
+        let link = bookmarks[2].to_link();
+        assert_eq!(link.title, "https://example.org/");
+        assert_eq!(link.tags, ["reading-list"]);
+    }
+
+    #[test]
+    fn test_not_a_bookmark_export() {
+        assert!(parse_bookmarks("").is_empty());
+        assert!(parse_bookmarks("<html><body><p>Hi</p></body></html>").is_empty());
+        assert!(parse_bookmarks("<DT><A HREF=\"https://x.com\"").is_empty());
+    }
+
+    #[test]
+    fn test_decode_entities() {
+        assert_eq!(decode_entities("a &amp; b"), "a & b");
+        assert_eq!(decode_entities("&#39;&#x27;&quot;"), "''\"");
+        assert_eq!(decode_entities("AT&T &bogus; &"), "AT&T &bogus; &");
+    }
+}
@@ -0,0 +1,165 @@
+//! Normalized URLs, for finding duplicates
+//!
+//! Two URLs for the same page often differ in ways that don't matter:
+//! `HTTPS://Example.com:443/post/?utm_source=feed` and
+//! `https://example.com/post` are one page. [`normalize_url`] parses a URL
+//! and brings it to one form:
+//!
+//! - the scheme and host are lowercased, and default ports dropped
+//! - tracking parameters (`utm_*`, `fbclid`, `gclid`, ...) are removed from
+//!   the query, and an empty query with them
+//! - a trailing slash is removed from the path, unless the
+//!   `url_trailing_slash` policy is `keep` (some sites serve different
+//!   pages with and without one)
+//!
+//! The path keeps its case, and the fragment is kept. Text that doesn't
+//! parse as a URL is only trimmed.
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Query parameters that only track where a visit came from
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "dclid", "gbraid", "wbraid", "msclkid", "yclid", "twclid", "igshid",
+    "mc_cid", "mc_eid", "_hsenc", "_hsmi", "mkt_tok", "vero_id", "ref_src",
+];
+
+/// Prefixes of whole families of tracking parameters
+const TRACKING_PREFIXES: &[&str] = &["utm_"];
+
+/// Whether a trailing slash on the path counts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrailingSlash {
+    /// `/post/` and `/post` are the same page
+    #[default]
+    Strip,
+    /// `/post/` and `/post` are different pages
+    Keep,
+}
+
+impl TrailingSlash {
+    /// Parse a policy's name (as in the config file)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "strip" => Some(TrailingSlash::Strip),
+            "keep" => Some(TrailingSlash::Keep),
+            _ => None,
+        }
+    }
+
+    /// Name used in the config file
+    pub fn name(self) -> &'static str {
+        match self {
+            TrailingSlash::Strip => "strip",
+            TrailingSlash::Keep => "keep",
+        }
+    }
+}
+
+/// Whether a query parameter only tracks where a visit came from
+pub fn is_tracking_param(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    TRACKING_PARAMS.contains(&name.as_str())
+        || TRACKING_PREFIXES
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+}
+
+/// Normalize a URL for duplicate detection (see the module docs)
+pub fn normalize_url(url: &str, trailing_slash: TrailingSlash) -> String {
+    let trimmed = url.trim();
+    let Ok(mut parsed) = Url::parse(trimmed) else {
+        return trimmed.to_string();
+    };
+
+    if parsed.query().is_some() {
+        let kept: Vec<(String, String)> = parsed
+            .query_pairs()
+            .filter(|(name, _)| !is_tracking_param(name))
+            .map(|(name, value)| (name.into_owned(), value.into_owned()))
+            .collect();
+        let removed = parsed.query_pairs().count() != kept.len();
+        if kept.is_empty() {
+            parsed.set_query(None);
+        } else if removed {
+            parsed.query_pairs_mut().clear().extend_pairs(kept);
+        }
+    }
+
+    if trailing_slash == TrailingSlash::Strip && !parsed.cannot_be_a_base() {
+        let path = parsed.path();
+        if path.len() > 1 && path.ends_with('/') {
+            let stripped = path.trim_end_matches('/').to_string();
+            parsed.set_path(&stripped);
+        }
+    }
+
+    let mut normalized = parsed.to_string();
+    // A bare host gets a "/" path when parsed; leave it off, as it was typed
+    if trailing_slash == TrailingSlash::Strip
+        && parsed.path() == "/"
+        && parsed.query().is_none()
+        && parsed.fragment().is_none()
+        && normalized.ends_with('/')
+    {
+        normalized.pop();
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(url: &str) -> String {
+        normalize_url(url, TrailingSlash::Strip)
+    }
+
+    #[test]
+    fn test_normalize_url() {
+        assert_eq!(
+            normalize("HTTPS://Example.COM:443/Path/Case/"),
+            "https://example.com/Path/Case"
+        );
+        assert_eq!(normalize("http://example.com:80/a"), "http://example.com/a");
+        assert_eq!(
+            normalize("http://example.com:8080/a"),
+            "http://example.com:8080/a"
+        );
+        assert_eq!(normalize("https://example.com/"), "https://example.com");
+        assert_eq!(normalize(" https://example.com "), "https://example.com");
+        assert_eq!(normalize("not a url"), "not a url");
+    }
+
+    #[test]
+    fn test_tracking_params_removed() {
+        assert_eq!(
+            normalize("https://example.com/post?utm_source=feed&UTM_Medium=rss&fbclid=x"),
+            "https://example.com/post"
+        );
+        assert_eq!(
+            normalize("https://example.com/watch?v=abc&utm_campaign=y#t=10"),
+            "https://example.com/watch?v=abc#t=10"
+        );
+        // Untouched when there's nothing to remove
+        assert_eq!(
+            normalize("https://example.com/search?q=a+b&page=2"),
+            "https://example.com/search?q=a+b&page=2"
+        );
+    }
+
+    #[test]
+    fn test_trailing_slash_policy() {
+        assert_eq!(
+            normalize_url("https://example.com/docs/", TrailingSlash::Keep),
+            "https://example.com/docs/"
+        );
+        assert_eq!(
+            normalize_url("https://example.com", TrailingSlash::Keep),
+            "https://example.com/"
+        );
+        assert_eq!(TrailingSlash::parse("Keep"), Some(TrailingSlash::Keep));
+        assert_eq!(TrailingSlash::parse("sometimes"), None);
+    }
+}
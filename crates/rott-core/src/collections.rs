@@ -0,0 +1,175 @@
+//! Named collections
+//!
+//! A collection is a separate root document with its own document ID, sync
+//! state and archives, so (say) work and personal links stay apart. The
+//! default collection is the data directory itself, as it was before
+//! collections existed; named collections live under `collections/<name>/`
+//! in it, laid out the same way:
+//!
+//! ```text
+//! <data_dir>/
+//! ├── root_doc_id                  # The default collection
+//! ├── <id[..2]>/<id[2..]>/...
+//! └── collections/
+//!     └── work/
+//!         ├── root_doc_id
+//!         └── <id[..2]>/<id[2..]>/...
+//! ```
+//!
+//! The active collection is the `collection` config key (or
+//! `ROTT_COLLECTION`); see [`Config::with_collection`].
+
+use std::fs;
+
+use anyhow::{bail, Context, Result};
+
+use crate::config::Config;
+use crate::document_id::DocumentId;
+use crate::identity::{Identity, InitResult};
+
+/// Name of the collection kept directly in the data directory
+pub const DEFAULT_COLLECTION: &str = "default";
+
+/// A collection on this device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Collection {
+    /// Collection name
+    pub name: String,
+    /// Root document ID (None if the collection isn't set up yet)
+    pub root_id: Option<DocumentId>,
+    /// Joined, but not synced to this device yet
+    pub pending_sync: bool,
+    /// Whether it's the active collection
+    pub active: bool,
+}
+
+/// Check a collection name: letters, digits, `-` and `_`
+pub fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        bail!("Collection name can't be empty");
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!(
+            "Invalid collection name '{}': use letters, digits, '-' and '_'",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Whether a collection has been created (or joined) on this device
+pub fn exists(config: &Config, name: &str) -> bool {
+    Identity::with_config(config.with_collection(name)).is_initialized()
+}
+
+/// Every collection on this device: the default first, then by name
+pub fn list(config: &Config) -> Result<Vec<Collection>> {
+    let mut names = vec![DEFAULT_COLLECTION.to_string()];
+    let dir = config.collections_dir();
+    if dir.exists() {
+        let mut named = Vec::new();
+        for entry in fs::read_dir(&dir).with_context(|| format!("Failed to read {:?}", dir))? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if entry.file_type()?.is_dir() && validate_name(&name).is_ok() {
+                named.push(name);
+            }
+        }
+        named.sort();
+        names.extend(named);
+    }
+
+    names
+        .into_iter()
+        .map(|name| {
+            let identity = Identity::with_config(config.with_collection(&name));
+            Ok(Collection {
+                root_id: identity.root_id()?,
+                pending_sync: identity.is_pending_sync()?,
+                active: name == config.collection_name(),
+                name,
+            })
+        })
+        .collect()
+}
+
+/// Create a collection with a new, empty document, or join one from
+/// another device by its document ID (pulled on the next sync)
+pub fn create(config: &Config, name: &str, join: Option<DocumentId>) -> Result<InitResult> {
+    validate_name(name)?;
+    if exists(config, name) {
+        bail!("Collection '{}' already exists", name);
+    }
+    let identity = Identity::with_config(config.with_collection(name));
+    match join {
+        Some(id) => identity.initialize_join(id),
+        None => identity.initialize_new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::Store;
+    use crate::Link;
+    use tempfile::TempDir;
+
+    fn test_config(temp_dir: &TempDir) -> Config {
+        Config {
+            data_dir: temp_dir.path().to_path_buf(),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("work").is_ok());
+        assert!(validate_name("side-project_2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../work").is_err());
+        assert!(validate_name("my work").is_err());
+    }
+
+    #[test]
+    fn test_create_and_list() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = test_config(&temp_dir);
+        Identity::with_config(config.clone())
+            .initialize_new()
+            .unwrap();
+
+        let work = create(&config, "work", None).unwrap();
+        assert!(create(&config, "work", None).is_err());
+        let joined = DocumentId::new();
+        create(&config, "home", Some(joined)).unwrap();
+
+        let collections = list(&config).unwrap();
+        let names: Vec<_> = collections.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["default", "home", "work"]);
+        assert!(collections[0].active);
+        assert_eq!(collections[1].root_id, Some(joined));
+        assert!(collections[1].pending_sync);
+        assert_eq!(collections[2].root_id, Some(work.root_id));
+        assert!(!collections[2].pending_sync);
+    }
+
+    #[test]
+    fn test_collections_are_separate() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = test_config(&temp_dir);
+        create(&config, "work", None).unwrap();
+
+        let mut personal = Store::open_with_config(config.clone()).unwrap();
+        personal
+            .add_link(&Link::new("https://example.com"))
+            .unwrap();
+
+        let work = Store::open_with_config(config.with_collection("work")).unwrap();
+        assert_ne!(work.root_id(), personal.root_id());
+        assert_eq!(work.link_count().unwrap(), 0);
+        assert_eq!(personal.link_count().unwrap(), 1);
+    }
+}
@@ -7,14 +7,30 @@
 //!
 //! Environment variables take precedence over config file values.
 
-use anyhow::{Context, Result};
-use serde::{Deserialize, Serialize};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use tracing::debug;
 
+use crate::canonical::TrailingSlash;
+use crate::document_id::DocumentId;
+use crate::maintenance::Interval;
+use crate::models::{Link, LinkSort};
+use crate::search::SearchBackend;
+
 /// Environment variable prefix
 const ENV_PREFIX: &str = "ROTT";
 
+/// Current configuration bundle format version
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Settings that belong to one machine and are never exported
+const MACHINE_KEYS: &[&str] = &["data_dir", "collection", "device_name"];
+
+/// Secret settings, exported only when asked for
+const SECRET_KEYS: &[&str] = &["lock_hash", "hypothesis_token", "add_token", "api_token"];
+
 /// Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -22,6 +38,11 @@ pub struct Config {
     #[serde(default = "default_data_dir")]
     pub data_dir: PathBuf,
 
+    /// Active collection (see [`crate::collections`]); the default
+    /// collection if unset
+    #[serde(default)]
+    pub collection: Option<String>,
+
     /// Sync server URL (optional)
     #[serde(default)]
     pub sync_url: Option<String>,
@@ -30,23 +51,324 @@ pub struct Config {
     #[serde(default)]
     pub sync_enabled: bool,
 
-    /// Tag used for Favorites filter in TUI
+    /// Write a trace of every CLI sync session (see [`crate::sync::trace`])
     #[serde(default)]
-    pub favorite_tag: Option<String>,
+    pub trace_sync: bool,
+
+    /// Compress sync payloads when the server supports it (see
+    /// [`crate::sync`]); servers that don't get them uncompressed
+    #[serde(default = "default_sync_compression")]
+    pub sync_compression: bool,
+
+    /// Milliseconds the TUI waits for more changes before pushing, so a
+    /// burst of edits goes out as one sync exchange (0: push each at once)
+    #[serde(default = "default_sync_batch_ms")]
+    pub sync_batch_ms: u64,
+
+    /// Name other devices syncing the collection see this one by, in the
+    /// TUI's presence indicator (see [`crate::sync::presence`]); the host
+    /// name if unset
+    #[serde(default)]
+    pub device_name: Option<String>,
+
+    /// Tags that make a link a favorite (the Favorites filter)
+    ///
+    /// Also read from `favorite_tag`, a single tag, as older configs have it.
+    #[serde(default, alias = "favorite_tag", deserialize_with = "one_or_many")]
+    pub favorite_tags: Vec<String>,
+
+    /// Whether a favorite needs any or all of the favorite tags
+    #[serde(default)]
+    pub favorite_match: FavoriteMatch,
 
     /// Log file path (optional, for TUI logging)
     #[serde(default)]
     pub log_file: Option<PathBuf>,
+
+    /// TUI accessibility mode: text markers instead of color-only cues
+    #[serde(default)]
+    pub accessible_mode: bool,
+
+    /// File or FIFO the TUI writes plain-text announcements to (optional)
+    #[serde(default)]
+    pub announce_path: Option<PathBuf>,
+
+    /// Domains that are never saved (matches subdomains too)
+    #[serde(default)]
+    pub blocked_domains: Vec<String>,
+
+    /// Tags automatically added to links from a domain
+    #[serde(default)]
+    pub domain_tags: BTreeMap<String, Vec<String>>,
+
+    /// Commands to open links with, by link kind, domain, or `default`
+    /// (see [`crate::openers`]); links nothing matches open in the browser
+    #[serde(default)]
+    pub openers: BTreeMap<String, String>,
+
+    /// Argon2 hash of the privacy lock passphrase (lock disabled if unset)
+    #[serde(default)]
+    pub lock_hash: Option<String>,
+
+    /// Minutes of inactivity before the privacy lock re-engages
+    #[serde(default = "default_lock_timeout_minutes")]
+    pub lock_timeout_minutes: u64,
+
+    /// URL schemes allowed besides http, https, and ftp
+    #[serde(default)]
+    pub allowed_schemes: Vec<String>,
+
+    /// Whether `/post/` and `/post` count as the same page when looking for
+    /// duplicate links
+    #[serde(default)]
+    pub url_trailing_slash: TrailingSlash,
+
+    /// Maximum length (in characters) of titles and fetched authors
+    #[serde(default = "default_max_title_length")]
+    pub max_title_length: usize,
+
+    /// Maximum length (in characters) of fetched descriptions
+    #[serde(default = "default_max_description_length")]
+    pub max_description_length: usize,
+
+    /// Hours fetched page metadata is reused for (0 turns the cache off)
+    #[serde(default = "default_metadata_cache_hours")]
+    pub metadata_cache_hours: u64,
+
+    /// How often the scheduled backup runs (disabled if unset)
+    #[serde(default)]
+    pub backup_interval: Option<Interval>,
+
+    /// Directory for scheduled backups (defaults to `backups` in the data dir)
+    #[serde(default)]
+    pub backup_dir: Option<PathBuf>,
+
+    /// Number of scheduled backups to keep
+    #[serde(default = "default_backup_keep")]
+    pub backup_keep: usize,
+
+    /// How often saved links are checked for changed content (disabled if unset)
+    #[serde(default)]
+    pub link_check_interval: Option<Interval>,
+
+    /// How often expired links are moved to the archives (disabled if unset)
+    #[serde(default)]
+    pub archive_expired_interval: Option<Interval>,
+
+    /// How often a report of what changed is written (disabled if unset)
+    #[serde(default)]
+    pub report_interval: Option<Interval>,
+
+    /// Directory for scheduled reports (defaults to `reports` in the data dir)
+    #[serde(default)]
+    pub report_dir: Option<PathBuf>,
+
+    /// Record when links are opened (synced, so other devices see them as read)
+    #[serde(default = "default_record_opens")]
+    pub record_opens: bool,
+
+    /// Filter the TUI's list fuzzily as you type (off: plain substring matching)
+    #[serde(default = "default_fuzzy_filter")]
+    pub fuzzy_filter: bool,
+
+    /// Order links are listed in, in the TUI and `rott link list` (the TUI's
+    /// `o` changes it until it quits)
+    #[serde(default)]
+    pub default_sort: LinkSort,
+
+    /// How searches find links: "scan" or "sqlite" (see [`crate::search`])
+    #[serde(default)]
+    pub search_provider: SearchBackend,
+
+    /// Tags bound to the keys 1-9 in the TUI's tag triage (`:triage`); the
+    /// most-used tags if unset
+    #[serde(default)]
+    pub triage_tags: Vec<String>,
+
+    /// Language of CLI and TUI text, such as "de" (from the environment's
+    /// locale if unset)
+    #[serde(default)]
+    pub locale: Option<String>,
+
+    /// Hypothes.is API token (annotation sync disabled if unset)
+    #[serde(default)]
+    pub hypothesis_token: Option<String>,
+
+    /// Token phones send to add links through `rott serve viewer`'s `/add`
+    /// (quick add disabled if unset)
+    #[serde(default)]
+    pub add_token: Option<String>,
+
+    /// Token every request to `rott serve api` must send (the API won't
+    /// start if unset)
+    #[serde(default)]
+    pub api_token: Option<String>,
+
+    /// Template for the body of new notes (see [`crate::template`])
+    #[serde(default)]
+    pub note_template: Option<String>,
+
+    /// Notes longer than this many bytes are shown in collapsible sections
+    /// in the TUI (see [`crate::sections`]); 0 shows every note whole
+    #[serde(default = "default_note_section_threshold")]
+    pub note_section_threshold: usize,
+
+    /// Command that screenshots a page, with `{url}` and `{output}`
+    /// placeholders (screenshots disabled if unset)
+    #[serde(default)]
+    pub screenshot_command: Option<String>,
+
+    /// Screenshot every link when it's saved (needs `screenshot_command`)
+    #[serde(default)]
+    pub screenshot_on_save: bool,
+
+    /// Keep a copy of every link's page text when it's saved
+    #[serde(default)]
+    pub archive_content_on_save: bool,
+
+    /// Capture aliases, by name (`[captures.<name>]`)
+    #[serde(default)]
+    pub captures: BTreeMap<String, CaptureTemplate>,
+
+    /// Checks links must pass before they're saved, by name
+    /// (`[checks.<name>]`, see [`crate::validate`])
+    #[serde(default)]
+    pub checks: BTreeMap<String, LinkCheck>,
+}
+
+/// How favorite tags combine
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FavoriteMatch {
+    /// Links with any favorite tag
+    #[default]
+    Any,
+    /// Links with every favorite tag
+    All,
+}
+
+impl FavoriteMatch {
+    /// Parse "any" or "all"
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "any" => Some(FavoriteMatch::Any),
+            "all" => Some(FavoriteMatch::All),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for FavoriteMatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FavoriteMatch::Any => write!(f, "any"),
+            FavoriteMatch::All => write!(f, "all"),
+        }
+    }
+}
+
+/// A capture alias: how `rott capture <name> <url>` saves a link
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureTemplate {
+    /// Tags added to the link
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Template for a note added to the link (see [`crate::template`])
+    #[serde(default)]
+    pub note: Option<String>,
+
+    /// Default values for the note's placeholders
+    #[serde(default)]
+    pub vars: BTreeMap<String, String>,
+}
+
+/// A check a link must pass to be saved as it is
+///
+/// A link fails if it fails any of the conditions set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkCheck {
+    /// Regular expression the URL must match
+    #[serde(default)]
+    pub url_pattern: Option<String>,
+
+    /// Fewest tags the link may have
+    #[serde(default)]
+    pub min_tags: usize,
+
+    /// Whether the link needs a title of its own (not just its URL)
+    #[serde(default)]
+    pub require_title: bool,
+
+    /// What happens to a link that fails
+    #[serde(default)]
+    pub action: CheckAction,
+
+    /// Message shown when a link is blocked (instead of the reason)
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// What happens to a link that fails a [`LinkCheck`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckAction {
+    /// The link isn't saved
+    #[default]
+    Block,
+    /// The link is saved with the `needs-review` tag
+    Review,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             data_dir: default_data_dir(),
+            collection: None,
             sync_url: None,
             sync_enabled: false,
-            favorite_tag: None,
+            trace_sync: false,
+            sync_compression: default_sync_compression(),
+            sync_batch_ms: default_sync_batch_ms(),
+            device_name: None,
+            favorite_tags: Vec::new(),
+            favorite_match: FavoriteMatch::default(),
             log_file: None,
+            accessible_mode: false,
+            announce_path: None,
+            blocked_domains: Vec::new(),
+            domain_tags: BTreeMap::new(),
+            openers: BTreeMap::new(),
+            lock_hash: None,
+            lock_timeout_minutes: default_lock_timeout_minutes(),
+            allowed_schemes: Vec::new(),
+            url_trailing_slash: TrailingSlash::default(),
+            max_title_length: default_max_title_length(),
+            max_description_length: default_max_description_length(),
+            metadata_cache_hours: default_metadata_cache_hours(),
+            backup_interval: None,
+            backup_dir: None,
+            backup_keep: default_backup_keep(),
+            link_check_interval: None,
+            archive_expired_interval: None,
+            report_interval: None,
+            report_dir: None,
+            record_opens: default_record_opens(),
+            fuzzy_filter: default_fuzzy_filter(),
+            default_sort: LinkSort::default(),
+            search_provider: SearchBackend::default(),
+            triage_tags: Vec::new(),
+            locale: None,
+            hypothesis_token: None,
+            add_token: None,
+            api_token: None,
+            note_template: None,
+            note_section_threshold: default_note_section_threshold(),
+            screenshot_command: None,
+            screenshot_on_save: false,
+            archive_content_on_save: false,
+            captures: BTreeMap::new(),
+            checks: BTreeMap::new(),
         }
     }
 }
@@ -55,7 +377,8 @@ impl Config {
     /// Load configuration from default location and environment
     ///
     /// Order of precedence (highest to lowest):
-    /// 1. Environment variables (ROTT_DATA_DIR, ROTT_SYNC_URL, ROTT_SYNC_ENABLED)
+    /// 1. Environment variables (ROTT_DATA_DIR, ROTT_COLLECTION, ROTT_SYNC_URL,
+    ///    ROTT_SYNC_ENABLED, ROTT_HYPOTHESIS_TOKEN)
     /// 2. Config file (~/.config/rott/config.toml or ROTT_CONFIG)
     /// 3. Default values
     pub fn load() -> Result<Self> {
@@ -112,6 +435,11 @@ impl Config {
             self.data_dir = PathBuf::from(val);
         }
 
+        // ROTT_COLLECTION
+        if let Ok(val) = std::env::var(format!("{}_COLLECTION", ENV_PREFIX)) {
+            self.collection = if val.is_empty() { None } else { Some(val) };
+        }
+
         // ROTT_SYNC_URL
         if let Ok(val) = std::env::var(format!("{}_SYNC_URL", ENV_PREFIX)) {
             self.sync_url = if val.is_empty() { None } else { Some(val) };
@@ -121,6 +449,16 @@ impl Config {
         if let Ok(val) = std::env::var(format!("{}_SYNC_ENABLED", ENV_PREFIX)) {
             self.sync_enabled = val.eq_ignore_ascii_case("true") || val == "1";
         }
+
+        // ROTT_HYPOTHESIS_TOKEN
+        if let Ok(val) = std::env::var(format!("{}_HYPOTHESIS_TOKEN", ENV_PREFIX)) {
+            self.hypothesis_token = if val.is_empty() { None } else { Some(val) };
+        }
+
+        // ROTT_LOCALE
+        if let Ok(val) = std::env::var(format!("{}_LOCALE", ENV_PREFIX)) {
+            self.locale = if val.is_empty() { None } else { Some(val) };
+        }
     }
 
     /// Ensure data directory exists
@@ -150,6 +488,87 @@ impl Config {
         Ok(())
     }
 
+    /// Export the configuration as a bundle for another machine
+    ///
+    /// The bundle is TOML with a format `version` and the settings under
+    /// `[config]`. The data directory, active collection and device name are
+    /// left out (they're specific to this machine), as are the privacy lock
+    /// passphrase hash and the Hypothes.is token unless `include_secrets` is
+    /// set.
+    pub fn export_bundle(&self, include_secrets: bool) -> Result<String> {
+        let toml::Value::Table(mut config) =
+            toml::Value::try_from(self).context("Failed to serialize config")?
+        else {
+            bail!("Failed to serialize config");
+        };
+        for key in MACHINE_KEYS {
+            config.remove(*key);
+        }
+        if !include_secrets {
+            for key in SECRET_KEYS {
+                config.remove(*key);
+            }
+        }
+
+        let mut bundle = toml::Table::new();
+        bundle.insert("version".to_string(), i64::from(BUNDLE_VERSION).into());
+        bundle.insert("config".to_string(), config.into());
+
+        let body = toml::to_string_pretty(&bundle).context("Failed to serialize bundle")?;
+        Ok(format!("# ROTT configuration bundle\n{}", body))
+    }
+
+    /// Apply an exported bundle on top of this configuration
+    ///
+    /// Returns the new configuration: the bundle's settings, with this
+    /// machine's data directory, active collection and device name, and this
+    /// machine's lock passphrase and Hypothes.is token if the bundle doesn't
+    /// carry them.
+    pub fn import_bundle(&self, bundle: &str) -> Result<Config> {
+        let mut bundle: toml::Table =
+            toml::from_str(bundle).context("Not a ROTT configuration bundle")?;
+
+        let version = bundle
+            .get("version")
+            .and_then(|v| v.as_integer())
+            .context("Not a ROTT configuration bundle: missing version")?;
+        if version < 1 || version > i64::from(BUNDLE_VERSION) {
+            bail!(
+                "Unsupported configuration bundle version {} (this version of ROTT reads version {})",
+                version,
+                BUNDLE_VERSION
+            );
+        }
+
+        let Some(toml::Value::Table(mut settings)) = bundle.remove("config") else {
+            bail!("Not a ROTT configuration bundle: missing [config]");
+        };
+        for key in MACHINE_KEYS {
+            settings.remove(*key);
+        }
+
+        // Parsed directly: environment overrides belong to this machine
+        let mut config: Config = toml::Value::Table(settings)
+            .try_into()
+            .context("Invalid configuration in bundle")?;
+        config.data_dir = self.data_dir.clone();
+        config.collection = self.collection.clone();
+        config.device_name = self.device_name.clone();
+        if config.lock_hash.is_none() {
+            config.lock_hash = self.lock_hash.clone();
+        }
+        if config.hypothesis_token.is_none() {
+            config.hypothesis_token = self.hypothesis_token.clone();
+        }
+        if config.add_token.is_none() {
+            config.add_token = self.add_token.clone();
+        }
+        if config.api_token.is_none() {
+            config.api_token = self.api_token.clone();
+        }
+        Ok(config)
+    }
+
     /// Get the config file path
     ///
     /// Can be overridden with ROTT_CONFIG environment variable
@@ -164,15 +583,245 @@ impl Config {
             .join("config.toml")
     }
 
-    /// Get the path to the Automerge document file
+    /// Name of the active collection
+    pub fn collection_name(&self) -> &str {
+        self.collection
+            .as_deref()
+            .unwrap_or(crate::collections::DEFAULT_COLLECTION)
+    }
+
+    /// This configuration with another collection active
+    pub fn with_collection(&self, name: &str) -> Config {
+        let mut config = self.clone();
+        config.collection = if name == crate::collections::DEFAULT_COLLECTION {
+            None
+        } else {
+            Some(name.to_string())
+        };
+        config
+    }
+
+    /// Get the directory holding a collection's documents and sync state
+    ///
+    /// The default collection is the data directory itself; named ones are
+    /// under `collections/` in it.
+    pub fn collection_dir(&self, name: &str) -> PathBuf {
+        if name == crate::collections::DEFAULT_COLLECTION {
+            self.data_dir.clone()
+        } else {
+            self.collections_dir().join(name)
+        }
+    }
+
+    /// Get the directory named collections are kept in
+    pub fn collections_dir(&self) -> PathBuf {
+        self.data_dir.join("collections")
+    }
+
+    /// Get the active collection's directory
+    pub fn store_dir(&self) -> PathBuf {
+        self.collection_dir(self.collection_name())
+    }
+
+    /// Get the path to the legacy single-file Automerge document
+    ///
+    /// Documents are now stored in chunks under [`Config::document_dir`];
+    /// this file is only read to migrate older data directories.
     pub fn automerge_path(&self) -> PathBuf {
-        self.data_dir.join("document.automerge")
+        self.store_dir().join("document.automerge")
+    }
+
+    /// Get the directory holding a document's saved chunks
+    ///
+    /// Matches the automerge-repo filesystem storage layout: the document
+    /// ID split after its first two characters.
+    pub fn document_dir(&self, id: &DocumentId) -> PathBuf {
+        let id = id.to_bs58check();
+        let (prefix, rest) = id.split_at(2);
+        self.store_dir().join(prefix).join(rest)
     }
 
     /// Get the path to the root document ID file
     pub fn root_doc_id_path(&self) -> PathBuf {
-        self.data_dir.join("root_doc_id")
+        self.store_dir().join("root_doc_id")
     }
+
+    /// Get the path of the marker that makes this device read-only
+    pub fn read_only_marker_path(&self) -> PathBuf {
+        self.store_dir().join("read_only")
+    }
+
+    /// Get the directory of the active collection's attachment blobs
+    pub fn attachments_dir(&self) -> PathBuf {
+        self.store_dir().join("attachments")
+    }
+
+    /// Get the path to the active collection's undo history
+    pub fn edit_history_path(&self) -> PathBuf {
+        self.store_dir().join("history.json")
+    }
+
+    /// Get the path to the active collection's record of failed metadata fetches
+    pub fn fetch_failures_path(&self) -> PathBuf {
+        self.store_dir().join("fetch_failures.json")
+    }
+
+    /// Get the path to the active collection's search index (for
+    /// `search_provider = "sqlite"`)
+    pub fn search_index_path(&self) -> PathBuf {
+        self.store_dir().join("search.sqlite")
+    }
+
+    /// Get the path to the active collection's sync state
+    pub fn sync_state_path(&self) -> PathBuf {
+        self.store_dir().join("sync_state.json")
+    }
+
+    /// Get the path to the active collection's last successful sync
+    pub fn last_sync_path(&self) -> PathBuf {
+        self.store_dir().join("last_sync.json")
+    }
+
+    /// Get the path to the privacy lock session file
+    pub fn lock_session_path(&self) -> PathBuf {
+        self.data_dir.join("lock_session")
+    }
+
+    /// Get the path to the maintenance job log
+    pub fn maintenance_log_path(&self) -> PathBuf {
+        self.data_dir.join("maintenance.json")
+    }
+
+    /// Get the path to the reading session log
+    pub fn sessions_log_path(&self) -> PathBuf {
+        self.data_dir.join("sessions.json")
+    }
+
+    /// Get the path of the marker that starts the TUI tour on next launch
+    pub fn tour_marker_path(&self) -> PathBuf {
+        self.data_dir.join("tour-pending")
+    }
+
+    /// Get the directory of the fetched metadata cache
+    pub fn metadata_cache_dir(&self) -> PathBuf {
+        self.data_dir.join("cache").join("metadata")
+    }
+
+    /// Get the directory of the site favicon cache
+    pub fn favicon_cache_dir(&self) -> PathBuf {
+        self.data_dir.join("cache").join("favicons")
+    }
+
+    /// Get the directory scheduled backups are written to
+    pub fn backup_dir(&self) -> PathBuf {
+        self.backup_dir
+            .clone()
+            .unwrap_or_else(|| self.data_dir.join("backups"))
+    }
+
+    /// Get the directory scheduled reports are written to
+    pub fn report_dir(&self) -> PathBuf {
+        self.report_dir
+            .clone()
+            .unwrap_or_else(|| self.data_dir.join("reports"))
+    }
+
+    /// Name this device goes by: `device_name`, else the host name
+    ///
+    /// Empty if neither is known.
+    pub fn device_label(&self) -> String {
+        if let Some(ref name) = self.device_name {
+            return name.clone();
+        }
+        std::env::var("HOSTNAME")
+            .ok()
+            .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+            .map(|name| name.trim().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Whether the privacy lock is enabled
+    pub fn is_lock_enabled(&self) -> bool {
+        self.lock_hash.is_some()
+    }
+
+    /// Whether a link is a favorite: it has any (or all, per
+    /// `favorite_match`) of the favorite tags
+    ///
+    /// Nothing is a favorite until favorite tags are configured.
+    pub fn is_favorite(&self, link: &Link) -> bool {
+        if self.favorite_tags.is_empty() {
+            return false;
+        }
+        let mut tags = self.favorite_tags.iter();
+        match self.favorite_match {
+            FavoriteMatch::Any => tags.any(|tag| link.has_tag(tag)),
+            FavoriteMatch::All => tags.all(|tag| link.has_tag(tag)),
+        }
+    }
+}
+
+/// Read a list that may be written as a single string
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(one) if one.is_empty() => Vec::new(),
+        OneOrMany::One(one) => vec![one],
+        OneOrMany::Many(many) => many,
+    })
+}
+
+/// Default privacy lock idle timeout
+fn default_lock_timeout_minutes() -> u64 {
+    15
+}
+
+/// Default limit for fetched titles
+fn default_max_title_length() -> usize {
+    300
+}
+
+/// Default limit for fetched descriptions
+fn default_max_description_length() -> usize {
+    1000
+}
+
+/// Default lifetime of cached page metadata
+fn default_metadata_cache_hours() -> u64 {
+    24
+}
+
+/// Opens are recorded unless turned off
+fn default_record_opens() -> bool {
+    true
+}
+
+fn default_sync_compression() -> bool {
+    true
+}
+
+fn default_sync_batch_ms() -> u64 {
+    500
+}
+
+fn default_note_section_threshold() -> usize {
+    4000
+}
+
+/// The realtime filter is fuzzy unless turned off
+fn default_fuzzy_filter() -> bool {
+    true
+}
+
+/// Default number of scheduled backups kept
+fn default_backup_keep() -> usize {
+    8
 }
 
 /// Get the default data directory
@@ -223,7 +872,14 @@ mod tests {
         }
     }
 
-    const ENV_VARS: &[&str] = &["ROTT_DATA_DIR", "ROTT_SYNC_URL", "ROTT_SYNC_ENABLED"];
+    const ENV_VARS: &[&str] = &[
+        "ROTT_DATA_DIR",
+        "ROTT_COLLECTION",
+        "ROTT_SYNC_URL",
+        "ROTT_SYNC_ENABLED",
+        "ROTT_HYPOTHESIS_TOKEN",
+        "ROTT_LOCALE",
+    ];
 
     #[test]
     fn test_default_config() {
@@ -242,6 +898,33 @@ mod tests {
 
         let id_path = config.root_doc_id_path();
         assert!(id_path.ends_with("root_doc_id"));
+
+        let id = DocumentId::new();
+        let encoded = id.to_bs58check();
+        let doc_dir = config.document_dir(&id);
+        assert!(doc_dir.ends_with(PathBuf::from(&encoded[..2]).join(&encoded[2..])));
+    }
+
+    #[test]
+    fn test_collection_paths() {
+        let config = Config {
+            data_dir: PathBuf::from("/data/rott"),
+            ..Config::default()
+        };
+        assert_eq!(config.collection_name(), "default");
+        assert_eq!(config.store_dir(), PathBuf::from("/data/rott"));
+
+        let work = config.with_collection("work");
+        assert_eq!(work.collection.as_deref(), Some("work"));
+        assert_eq!(
+            work.store_dir(),
+            PathBuf::from("/data/rott/collections/work")
+        );
+        assert!(work.root_doc_id_path().starts_with(work.store_dir()));
+        assert!(work.sync_state_path().starts_with(work.store_dir()));
+        assert!(work.last_sync_path().starts_with(work.store_dir()));
+
+        assert_eq!(work.with_collection("default").collection, None);
     }
 
     #[test]
@@ -302,8 +985,7 @@ mod tests {
             data_dir: PathBuf::from("/data/rott"),
             sync_url: Some("ws://sync.example.com".to_string()),
             sync_enabled: true,
-            favorite_tag: None,
-            log_file: None,
+            ..Config::default()
         };
 
         let toml_str = toml::to_string_pretty(&config).unwrap();
@@ -317,6 +999,39 @@ mod tests {
         assert_eq!(parsed.sync_enabled, config.sync_enabled);
     }
 
+    #[test]
+    fn test_favorite_tags() {
+        let _guard = EnvGuard::new(ENV_VARS);
+
+        // A single favorite_tag from older configs still loads
+        let config = Config::load_from_str(r#"favorite_tag = "starred""#).unwrap();
+        assert_eq!(config.favorite_tags, ["starred"]);
+        assert_eq!(config.favorite_match, FavoriteMatch::Any);
+
+        let mut config = Config::load_from_str(
+            r#"
+favorite_tags = ["starred", "rust"]
+favorite_match = "all"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.favorite_tags, ["starred", "rust"]);
+        assert_eq!(config.favorite_match, FavoriteMatch::All);
+
+        let mut link = Link::new("https://example.com");
+        link.add_tag("Starred");
+        assert!(!config.is_favorite(&link));
+        link.add_tag("rust");
+        assert!(config.is_favorite(&link));
+
+        config.favorite_match = FavoriteMatch::Any;
+        link.tags.retain(|t| t == "rust");
+        assert!(config.is_favorite(&link));
+
+        config.favorite_tags.clear();
+        assert!(!config.is_favorite(&link));
+    }
+
     #[test]
     fn test_load_from_str() {
         let _guard = EnvGuard::new(ENV_VARS);
@@ -333,6 +1048,99 @@ mod tests {
         assert!(config.sync_enabled);
     }
 
+    #[test]
+    fn test_maintenance_intervals() {
+        let _guard = EnvGuard::new(ENV_VARS);
+
+        let config = Config::load_from_str(r#"backup_interval = "weekly""#).unwrap();
+        assert_eq!(config.backup_interval, Some(Interval::Weekly));
+        assert!(config.link_check_interval.is_none());
+        assert!(config.backup_dir().starts_with(&config.data_dir));
+
+        let toml_str = toml::to_string_pretty(&config).unwrap();
+        assert!(toml_str.contains(r#"backup_interval = "weekly""#));
+
+        assert!(Config::load_from_str(r#"backup_interval = "hourly""#).is_err());
+    }
+
+    #[test]
+    fn test_bundle_roundtrip() {
+        let _guard = EnvGuard::new(ENV_VARS);
+
+        let config = Config {
+            data_dir: PathBuf::from("/old/machine"),
+            favorite_tags: vec!["starred".to_string()],
+            blocked_domains: vec!["mybank.com".to_string()],
+            lock_hash: Some("secret-hash".to_string()),
+            hypothesis_token: Some("secret-token".to_string()),
+            add_token: Some("secret-add-token".to_string()),
+            api_token: Some("secret-api-token".to_string()),
+            device_name: Some("old-laptop".to_string()),
+            ..Config::default()
+        };
+
+        let bundle = config.export_bundle(false).unwrap();
+        assert!(!bundle.contains("/old/machine"));
+        assert!(!bundle.contains("old-laptop"));
+        assert!(!bundle.contains("secret-hash"));
+        assert!(!bundle.contains("secret-token"));
+        assert!(!bundle.contains("secret-add-token"));
+        assert!(!bundle.contains("secret-api-token"));
+
+        let here = Config {
+            data_dir: PathBuf::from("/new/machine"),
+            device_name: Some("desktop".to_string()),
+            ..Config::default()
+        };
+        let imported = here.import_bundle(&bundle).unwrap();
+        assert_eq!(imported.data_dir, here.data_dir);
+        assert_eq!(imported.device_name.as_deref(), Some("desktop"));
+        assert_eq!(imported.favorite_tags, ["starred"]);
+        assert_eq!(imported.blocked_domains, config.blocked_domains);
+        assert!(imported.lock_hash.is_none());
+
+        let with_secrets = config.export_bundle(true).unwrap();
+        let imported = here.import_bundle(&with_secrets).unwrap();
+        assert_eq!(imported.lock_hash.as_deref(), Some("secret-hash"));
+        assert_eq!(imported.hypothesis_token.as_deref(), Some("secret-token"));
+        assert_eq!(imported.add_token.as_deref(), Some("secret-add-token"));
+        assert_eq!(imported.data_dir, here.data_dir);
+    }
+
+    #[test]
+    fn test_captures_from_toml() {
+        let config = Config::load_from_str(
+            r#"
+note_template = "Read: {{title}}"
+
+[captures.hn]
+tags = ["hn", "to-read"]
+note = "Found on Hacker News via {{source|the front page}}"
+
+[captures.hn.vars]
+source = "a comment"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.note_template.as_deref(), Some("Read: {{title}}"));
+        let hn = &config.captures["hn"];
+        assert_eq!(hn.tags, vec!["hn", "to-read"]);
+        assert_eq!(hn.vars["source"], "a comment");
+
+        let toml_str = toml::to_string_pretty(&config).unwrap();
+        let reloaded = Config::load_from_str(&toml_str).unwrap();
+        assert_eq!(reloaded.captures, config.captures);
+    }
+
+    #[test]
+    fn test_import_bundle_rejects_other_files() {
+        let config = Config::default();
+        assert!(config.import_bundle("data_dir = \"/x\"").is_err());
+        assert!(config.import_bundle("version = 99\n[config]\n").is_err());
+        assert!(config.import_bundle("not toml [").is_err());
+    }
+
     #[test]
     fn test_load_from_path_missing_file() {
         let _guard = EnvGuard::new(ENV_VARS);
@@ -0,0 +1,217 @@
+//! Duplicate link and note detection
+//!
+//! Links are duplicates when their URLs normalize to the same thing (see
+//! [`crate::canonical`]), comparing canonical URLs too.
+//! [`find_duplicate_links`] groups them; merging a group keeps the oldest
+//! link and merges the others into it (see `Store::merge_links`).
+//!
+//! Imports (and pasting the same quote onto several links) leave identical
+//! notes spread across the collection. [`find_duplicate_notes`] groups notes
+//! with the same body; merging a group keeps the oldest note and replaces
+//! each copy on another link with a short reference to it (see
+//! `Store::merge_duplicate_notes`).
+//!
+//! Threaded notes (replies, and notes with replies) are left alone, so
+//! merging never breaks a thread.
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::canonical::{normalize_url, TrailingSlash};
+use crate::models::{Link, Note};
+
+/// Reference notes start with this, so they're never merged themselves
+pub const REFERENCE_PREFIX: &str = "Same note as on ";
+
+/// Links whose URLs normalize to the same thing
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DuplicateLinks {
+    /// The normalized URL they share
+    pub url: String,
+    /// The link that's kept (the oldest)
+    pub keep: Link,
+    /// Links to merge into `keep`
+    pub duplicates: Vec<Link>,
+}
+
+/// Find links whose URL or canonical URL normalize to the same thing
+///
+/// Groups are ordered by their kept link's creation time.
+pub fn find_duplicate_links(links: &[Link], trailing_slash: TrailingSlash) -> Vec<DuplicateLinks> {
+    let mut groups: Vec<(Vec<String>, Vec<Link>)> = Vec::new();
+    for link in links {
+        let urls: Vec<String> = std::iter::once(&link.url)
+            .chain(link.canonical_url.as_ref())
+            .map(|url| normalize_url(url, trailing_slash))
+            .collect();
+        match groups
+            .iter_mut()
+            .find(|(seen, _)| urls.iter().any(|url| seen.contains(url)))
+        {
+            Some((seen, members)) => {
+                for url in urls {
+                    if !seen.contains(&url) {
+                        seen.push(url);
+                    }
+                }
+                members.push(link.clone());
+            }
+            None => groups.push((urls, vec![link.clone()])),
+        }
+    }
+
+    let mut duplicates: Vec<DuplicateLinks> = groups
+        .into_iter()
+        .filter(|(_, members)| members.len() > 1)
+        .map(|(seen, mut members)| {
+            members.sort_by_key(|l| (l.created_at, l.id));
+            let keep = members.remove(0);
+            DuplicateLinks {
+                url: seen[0].clone(),
+                keep,
+                duplicates: members,
+            }
+        })
+        .collect();
+    duplicates.sort_by_key(|d| d.keep.created_at);
+    duplicates
+}
+
+/// A note and the link it's on
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NoteRef {
+    pub link_id: Uuid,
+    pub link_title: String,
+    pub note: Note,
+}
+
+/// Notes with identical bodies
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DuplicateNotes {
+    /// The note that's kept (the oldest)
+    pub keep: NoteRef,
+    /// Copies to replace with a reference to `keep` (or remove, if they're
+    /// on the same link)
+    pub duplicates: Vec<NoteRef>,
+}
+
+impl DuplicateNotes {
+    /// The body of the reference that replaces a copy on another link
+    pub fn reference_body(&self) -> String {
+        format!(
+            "{}\"{}\" ({})",
+            REFERENCE_PREFIX,
+            self.keep.link_title,
+            &self.keep.link_id.to_string()[..8]
+        )
+    }
+}
+
+/// Find notes whose bodies are identical (ignoring surrounding whitespace)
+///
+/// Groups are ordered by their kept note's creation time.
+pub fn find_duplicate_notes(links: &[Link]) -> Vec<DuplicateNotes> {
+    let mut groups: Vec<(String, Vec<NoteRef>)> = Vec::new();
+    for link in links {
+        for note in &link.notes {
+            let body = note.body.trim();
+            let has_replies = link.notes.iter().any(|n| n.parent_id == Some(note.id));
+            if body.is_empty()
+                || body.starts_with(REFERENCE_PREFIX)
+                || note.parent_id.is_some()
+                || has_replies
+            {
+                continue;
+            }
+            let note_ref = NoteRef {
+                link_id: link.id,
+                link_title: link.title.clone(),
+                note: note.clone(),
+            };
+            match groups.iter_mut().find(|(b, _)| b == body) {
+                Some((_, notes)) => notes.push(note_ref),
+                None => groups.push((body.to_string(), vec![note_ref])),
+            }
+        }
+    }
+
+    let mut duplicates: Vec<DuplicateNotes> = groups
+        .into_iter()
+        .filter(|(_, notes)| notes.len() > 1)
+        .map(|(_, mut notes)| {
+            notes.sort_by_key(|n| (n.note.created_at, n.note.id));
+            let keep = notes.remove(0);
+            DuplicateNotes {
+                keep,
+                duplicates: notes,
+            }
+        })
+        .collect();
+    duplicates.sort_by_key(|d| d.keep.note.created_at);
+    duplicates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn link_with_notes(url: &str, bodies: &[&str]) -> Link {
+        let mut link = Link::new(url);
+        for body in bodies {
+            link.add_note(Note::new(*body));
+        }
+        link
+    }
+
+    #[test]
+    fn test_finds_duplicate_links() {
+        let mut a = Link::new("https://Example.com/post/");
+        let b = Link::new("https://example.com/post?utm_source=feed");
+        let mut c = Link::new("https://mirror.example.org/post");
+        c.canonical_url = Some("https://example.com/post".to_string());
+        let other = Link::new("https://example.com/other");
+        a.created_at = Utc::now() - Duration::days(1);
+
+        let groups = find_duplicate_links(
+            &[b.clone(), a.clone(), c.clone(), other],
+            TrailingSlash::Strip,
+        );
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].keep.id, a.id);
+        assert_eq!(groups[0].url, "https://example.com/post");
+        let ids: Vec<Uuid> = groups[0].duplicates.iter().map(|l| l.id).collect();
+        assert_eq!(ids, [b.id, c.id]);
+
+        // With the slash kept, the first link is on its own
+        let groups = find_duplicate_links(&[a.clone(), b, c], TrailingSlash::Keep);
+        assert_eq!(groups.len(), 1);
+        assert!(groups[0].duplicates.iter().all(|l| l.id != a.id) && groups[0].keep.id != a.id);
+    }
+
+    #[test]
+    fn test_finds_identical_bodies_and_keeps_oldest() {
+        let mut a = link_with_notes("https://a.example.com", &["Great quote", "Only here"]);
+        let b = link_with_notes("https://b.example.com", &["  Great quote\n"]);
+        a.notes[0].created_at = Utc::now() - Duration::days(1);
+
+        let groups = find_duplicate_notes(&[b.clone(), a.clone()]);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].keep.note.id, a.notes[0].id);
+        assert_eq!(groups[0].duplicates.len(), 1);
+        assert_eq!(groups[0].duplicates[0].link_id, b.id);
+        assert!(groups[0].reference_body().starts_with(REFERENCE_PREFIX));
+    }
+
+    #[test]
+    fn test_skips_threads_and_references() {
+        let mut a = link_with_notes("https://a.example.com", &["Same", "Ref"]);
+        let mut b = link_with_notes("https://b.example.com", &["Same", "Ref"]);
+        // A reply, and references left by an earlier merge
+        b.notes[0].parent_id = Some(b.notes[1].id);
+        a.notes[1].body = format!("{}\"X\" (12345678)", REFERENCE_PREFIX);
+        b.notes[1].body = a.notes[1].body.clone();
+
+        assert!(find_duplicate_notes(&[a, b]).is_empty());
+    }
+}
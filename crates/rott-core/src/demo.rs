@@ -0,0 +1,141 @@
+//! Sample data for trying ROTT out
+//!
+//! `rott init --demo` seeds a new store with these links so the TUI has
+//! something to show on first run. Every demo link is tagged
+//! [`DEMO_TAG`], so they're easy to find and remove later.
+
+use chrono::{Duration, NaiveDate, Utc};
+
+use crate::models::{Link, Note};
+
+/// Tag added to every demo link
+pub const DEMO_TAG: &str = "demo";
+
+/// A handful of links with tags, authors, and notes
+pub fn demo_links() -> Vec<Link> {
+    let now = Utc::now();
+    let published = |year, month, day| {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|date| date.and_utc())
+    };
+
+    let mut rust_book = demo_link(
+        "https://doc.rust-lang.org/book/",
+        "The Rust Programming Language",
+        Some("The official book on the Rust programming language."),
+        &["Steve Klabnik", "Carol Nichols"],
+        &["rust", "books"],
+    );
+    let mut pinned = Note::with_title("Start here", "Chapters 4 (ownership) and 10 (traits).");
+    pinned.pinned = true;
+    rust_book.add_note(pinned);
+    rust_book.add_note(Note::new("Skipped the async chapter for now."));
+    rust_book.opened_at = Some(now - Duration::days(2));
+
+    let mut automerge = demo_link(
+        "https://automerge.org/",
+        "Automerge",
+        Some("A library of data structures for building collaborative applications."),
+        &[],
+        &["crdt", "local-first"],
+    );
+    automerge.add_note(Note::new(
+        "What ROTT syncs with: every device keeps a full copy of the document.",
+    ));
+
+    let mut local_first = demo_link(
+        "https://www.inkandswitch.com/local-first/",
+        "Local-first software: You own your data, in spite of the cloud",
+        None,
+        &[
+            "Martin Kleppmann",
+            "Adam Wiggins",
+            "Peter van Hardenberg",
+            "Mark McGranaghan",
+        ],
+        &["local-first", "essays"],
+    );
+    local_first.published_at = published(2019, 4, 1);
+    local_first.add_note(Note::with_title(
+        "Seven ideals",
+        "Fast, multi-device, offline, collaboration, longevity, privacy, user control.",
+    ));
+
+    let mut crdts = demo_link(
+        "https://crdt.tech/",
+        "Conflict-free Replicated Data Types",
+        Some("Resources and community around CRDT technology."),
+        &[],
+        &["crdt"],
+    );
+    crdts.published_at = published(2021, 6, 15);
+
+    let ratatui = demo_link(
+        "https://ratatui.rs/",
+        "Ratatui",
+        Some("A Rust library for cooking up terminal user interfaces."),
+        &[],
+        &["rust", "tui"],
+    );
+
+    let example = demo_link(
+        "https://example.com/",
+        "A link with only the demo tag (press t to add more)",
+        None,
+        &[],
+        &[],
+    );
+
+    vec![rust_book, automerge, local_first, crdts, ratatui, example]
+}
+
+fn demo_link(
+    url: &str,
+    title: &str,
+    description: Option<&str>,
+    authors: &[&str],
+    tags: &[&str],
+) -> Link {
+    let mut link = Link::new(url);
+    link.set_title(title);
+    link.set_description(description.map(String::from));
+    link.set_author(authors.iter().map(|a| a.to_string()).collect());
+    link.add_tag(DEMO_TAG);
+    for tag in tags {
+        link.add_tag(*tag);
+    }
+    link
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::models::LinkSort;
+    use crate::store::Store;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_demo_links_can_be_saved() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config {
+            data_dir: temp_dir.path().to_path_buf(),
+            ..Config::default()
+        };
+        let mut store = Store::open_with_config(config).unwrap();
+
+        let links = demo_links();
+        for link in &links {
+            store.add_link(link).unwrap();
+        }
+        assert_eq!(
+            store
+                .get_links_by_tag(DEMO_TAG, LinkSort::default())
+                .unwrap()
+                .len(),
+            links.len()
+        );
+        assert!(store.note_count().unwrap() > 0);
+    }
+}
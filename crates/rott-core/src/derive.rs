@@ -0,0 +1,179 @@
+//! Derived link fields
+//!
+//! Derivers compute values from a link's own data (its domain, what kind of
+//! content it points to, a normalized URL) whenever the link is written
+//! through the `Store`. Every writer — CLI, TUI, imports — gets the same
+//! enrichment without reimplementing it.
+//!
+//! Results are persisted in the link's `derived` map, keyed by
+//! [`Deriver::key`]. A deriver returning `None` removes its key.
+
+use std::sync::Arc;
+
+use crate::canonical::{normalize_url, TrailingSlash};
+use crate::domains::extract_domain;
+use crate::models::Link;
+
+/// Computes a derived field for a link
+pub trait Deriver: Send + Sync {
+    /// Key the derived value is stored under
+    fn key(&self) -> &'static str;
+
+    /// Compute the value for a link, or `None` if it doesn't apply
+    fn derive(&self, link: &Link) -> Option<String>;
+}
+
+/// Run derivers on a link, updating its `derived` map
+///
+/// Doesn't touch `updated_at`: derived values follow from the link's own
+/// data and aren't user edits.
+pub fn apply_derivers(derivers: &[Arc<dyn Deriver>], link: &mut Link) {
+    for deriver in derivers {
+        match deriver.derive(link) {
+            Some(value) => {
+                link.derived.insert(deriver.key().to_string(), value);
+            }
+            None => {
+                link.derived.remove(deriver.key());
+            }
+        }
+    }
+}
+
+/// The derivers registered on every store
+///
+/// `trailing_slash` is the store's `url_trailing_slash` policy, for the
+/// normalized URL.
+pub fn default_derivers(trailing_slash: TrailingSlash) -> Vec<Arc<dyn Deriver>> {
+    vec![
+        Arc::new(DomainDeriver),
+        Arc::new(KindDeriver),
+        Arc::new(NormalizedUrlDeriver { trailing_slash }),
+    ]
+}
+
+/// The link's host without a leading `www.`
+pub struct DomainDeriver;
+
+impl Deriver for DomainDeriver {
+    fn key(&self) -> &'static str {
+        "domain"
+    }
+
+    fn derive(&self, link: &Link) -> Option<String> {
+        site_domain(&link.url)
+    }
+}
+
+/// A URL's host without a leading `www.`, as the `domain` field has it
+pub fn site_domain(url: &str) -> Option<String> {
+    let domain = extract_domain(url)?;
+    Some(
+        domain
+            .strip_prefix("www.")
+            .map(|d| d.to_string())
+            .unwrap_or(domain),
+    )
+}
+
+/// A coarse content kind guessed from the URL
+///
+/// One of `video`, `audio`, `pdf`, `image`, `repository`, `discussion`,
+/// or `article` (the fallback).
+pub struct KindDeriver;
+
+impl Deriver for KindDeriver {
+    fn key(&self) -> &'static str {
+        "kind"
+    }
+
+    fn derive(&self, link: &Link) -> Option<String> {
+        let domain = extract_domain(&link.url)?;
+        let path = link
+            .url
+            .split(['?', '#'])
+            .next()
+            .unwrap_or("")
+            .to_lowercase();
+
+        let on = |site: &str| domain == site || domain.ends_with(&format!(".{}", site));
+        let ext = |exts: &[&str]| exts.iter().any(|e| path.ends_with(e));
+
+        let video_site = on("youtube.com") || on("youtu.be") || on("vimeo.com");
+
+        let kind = if video_site || ext(&[".mp4", ".webm", ".mov"]) {
+            "video"
+        } else if ext(&[".mp3", ".ogg", ".m4a", ".wav"]) {
+            "audio"
+        } else if ext(&[".pdf"]) {
+            "pdf"
+        } else if ext(&[".png", ".jpg", ".jpeg", ".gif", ".webp", ".svg"]) {
+            "image"
+        } else if on("github.com") || on("gitlab.com") || on("codeberg.org") {
+            "repository"
+        } else if on("news.ycombinator.com") || on("reddit.com") || on("lobste.rs") {
+            "discussion"
+        } else {
+            "article"
+        };
+        Some(kind.to_string())
+    }
+}
+
+/// The URL as used for duplicate detection (see [`crate::canonical`])
+pub struct NormalizedUrlDeriver {
+    pub trailing_slash: TrailingSlash,
+}
+
+impl Deriver for NormalizedUrlDeriver {
+    fn key(&self) -> &'static str {
+        "normalized_url"
+    }
+
+    fn derive(&self, link: &Link) -> Option<String> {
+        Some(normalize_url(&link.url, self.trailing_slash))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind(url: &str) -> String {
+        KindDeriver.derive(&Link::new(url)).unwrap()
+    }
+
+    #[test]
+    fn test_default_derivers() {
+        let mut link = Link::new("https://WWW.Example.com/docs/?utm_source=feed");
+        let updated_at = link.updated_at;
+        apply_derivers(&default_derivers(TrailingSlash::Strip), &mut link);
+
+        assert_eq!(link.derived["domain"], "example.com");
+        assert_eq!(link.derived["kind"], "article");
+        assert_eq!(
+            link.derived["normalized_url"],
+            "https://www.example.com/docs"
+        );
+        assert_eq!(link.updated_at, updated_at);
+    }
+
+    #[test]
+    fn test_kind_detection() {
+        assert_eq!(kind("https://www.youtube.com/watch?v=abc"), "video");
+        assert_eq!(kind("https://github.com/rust-lang/rust"), "repository");
+        assert_eq!(kind("https://example.com/paper.PDF?dl=1"), "pdf");
+        assert_eq!(kind("https://old.reddit.com/r/rust"), "discussion");
+        assert_eq!(kind("https://blog.example.com/post"), "article");
+    }
+
+    #[test]
+    fn test_none_removes_key() {
+        let mut link = Link::new("not a url");
+        link.derived
+            .insert("domain".to_string(), "stale".to_string());
+        let derivers: Vec<Arc<dyn Deriver>> = vec![Arc::new(DomainDeriver)];
+        apply_derivers(&derivers, &mut link);
+        assert!(!link.derived.contains_key("domain"));
+    }
+}
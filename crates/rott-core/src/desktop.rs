@@ -0,0 +1,64 @@
+//! Clipboard access and opening URLs
+//!
+//! Thin wrappers over the `arboard` and `open` crates, so the CLI and TUI
+//! don't shell out to platform tools (xclip, pbcopy, clip.exe, xdg-open).
+//! Wayland is supported through the data-control protocol.
+
+use anyhow::{Context, Result};
+
+/// The system clipboard
+///
+/// On X11 and Wayland the copied text is served by this process, so keep
+/// the clipboard alive for as long as the text should stay available (the
+/// TUI holds one for its whole session).
+pub struct SystemClipboard {
+    inner: arboard::Clipboard,
+}
+
+impl SystemClipboard {
+    /// Connect to the system clipboard
+    pub fn new() -> Result<Self> {
+        let inner = arboard::Clipboard::new().context("Clipboard not available")?;
+        Ok(Self { inner })
+    }
+
+    /// Replace the clipboard contents with text
+    pub fn copy(&mut self, text: &str) -> Result<()> {
+        self.inner
+            .set_text(text.to_string())
+            .context("Failed to copy to clipboard")
+    }
+
+    /// Read text from the clipboard
+    pub fn paste(&mut self) -> Result<String> {
+        self.inner.get_text().context("Failed to read clipboard")
+    }
+
+    /// Read the primary selection (the text last selected with the mouse)
+    ///
+    /// Only X11 and Wayland have one; elsewhere this reads the clipboard.
+    #[cfg(all(unix, not(target_os = "macos")))]
+    pub fn paste_selection(&mut self) -> Result<String> {
+        use arboard::{GetExtLinux, LinuxClipboardKind};
+        self.inner
+            .get()
+            .clipboard(LinuxClipboardKind::Primary)
+            .text()
+            .context("Failed to read the primary selection")
+    }
+
+    /// Read the primary selection (the text last selected with the mouse)
+    ///
+    /// Only X11 and Wayland have one; elsewhere this reads the clipboard.
+    #[cfg(not(all(unix, not(target_os = "macos"))))]
+    pub fn paste_selection(&mut self) -> Result<String> {
+        self.paste()
+    }
+}
+
+/// Open a URL in the default browser
+///
+/// The browser is started detached, so it doesn't interfere with the TUI.
+pub fn open_url(url: &str) -> Result<()> {
+    open::that_detached(url).with_context(|| format!("Failed to open {}", url))
+}
@@ -6,28 +6,58 @@
 //! Document structure:
 //! ```text
 //! {
-//!   schema_version: 2,
+//!   schema_version: 3,
 //!   root_doc_id: "...",
 //!   links: {
 //!     "<uuid>": {
 //!       id, title, url, description, author, tags, created_at, updated_at,
+//!       content_hash?, content_changed?, content_checked_at?, unreachable?,
+//!       opened_at?, open_count?, read_position?: { line, lines, at },
+//!       expires_at?, published_at?, canonical_url?, paywalled?, language?,
+//!       via?, source_context?,
+//!       derived: { "<key>": "<value>", ... },
+//!       attachments?: { "<name>": "<sha256>", ... },
+//!       merged_from?: ["<uuid>", ...],
 //!       notes: {
-//!         "<uuid>": { id, title, body, created_at },
+//!         "<uuid>": { id, title, body, created_at, pinned?, order?, parent_id? },
 //!         ...
 //!       }
 //!     },
 //!     ...
-//!   }
+//!   },
+//!   tag_descriptions?: { "<tag>": "<markdown>", ... },
+//!   tag_visibility?: { "<tag>": "private" | "public", ... },
+//!   archives?: { "<year>": "<document id>", ... }
 //! }
 //! ```
-
-use automerge::{transaction::Transactable, AutoCommit, ObjType, ReadDoc, ROOT};
+//!
+//! Links moved out of the way by `Store::archive_links` live in one side
+//! document per year, with the same structure; `archives` maps each year to
+//! its document.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use automerge::{
+    transaction::{CommitOptions, Transactable},
+    AutoCommit, Change, ChangeHash, ObjId, ObjType, Patch, PatchAction, Prop, ReadDoc, ScalarValue,
+    Value, ROOT,
+};
 use chrono::{DateTime, TimeZone, Utc};
+use serde::Serialize;
 use thiserror::Error;
 use uuid::Uuid;
 
+use crate::canonical::{normalize_url, TrailingSlash};
+use crate::derive::site_domain;
 use crate::document_id::DocumentId;
-use crate::models::{Link, Note};
+use crate::events::{EventKind, StoreEvent};
+use crate::models::{
+    sort_notes, thread_notes, Link, LinkSort, LinksPage, Note, NoteVersion, NotesPage, ReadPosition,
+};
+use crate::query::{SearchHit, SearchQuery};
+use crate::validate::{normalize_tag, normalize_tags};
+use crate::visibility::TagVisibility;
 
 /// Errors that can occur during document operations
 #[derive(Error, Debug)]
@@ -48,9 +78,53 @@ pub enum DocumentError {
     InvalidTimestamp(i64),
 }
 
+/// A record that couldn't be read in full
+///
+/// Malformed records (from a buggy client or a bad merge) are read as far as
+/// possible rather than failing the whole read: missing or wrongly typed
+/// fields fall back to defaults and records stored under keys that aren't
+/// UUIDs are skipped. Each problem is reported as a warning.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecordWarning {
+    /// The link the problem is in (None if the link's key isn't a UUID)
+    pub link_id: Option<Uuid>,
+    /// The note the problem is in, if it's in a note
+    pub note_id: Option<Uuid>,
+    /// What's wrong
+    pub problem: String,
+}
+
+/// Reads a record's fields, noting the ones that couldn't be read
+struct Lenient<'a> {
+    warnings: &'a mut Vec<RecordWarning>,
+    link_id: Option<Uuid>,
+    note_id: Option<Uuid>,
+}
+
+impl Lenient<'_> {
+    /// The field's value, or `fallback` (with a warning) if it couldn't be read
+    fn or<T>(&mut self, result: Result<T, DocumentError>, fallback: T) -> T {
+        result.unwrap_or_else(|e| {
+            self.warn(e.to_string());
+            fallback
+        })
+    }
+
+    fn warn(&mut self, problem: String) {
+        self.warnings.push(RecordWarning {
+            link_id: self.link_id,
+            note_id: self.note_id,
+            problem,
+        });
+    }
+}
+
 /// Keys used in the Automerge document structure
 mod keys {
     pub const LINKS: &str = "links";
+    pub const TAG_DESCRIPTIONS: &str = "tag_descriptions";
+    pub const TAG_VISIBILITY: &str = "tag_visibility";
+    pub const ARCHIVES: &str = "archives";
     pub const NOTES: &str = "notes";
     pub const SCHEMA_VERSION: &str = "schema_version";
     pub const ROOT_DOC_ID: &str = "root_doc_id";
@@ -65,10 +139,88 @@ mod keys {
     pub const TAGS: &str = "tags";
     pub const CREATED_AT: &str = "created_at";
     pub const UPDATED_AT: &str = "updated_at";
+    pub const CONTENT_HASH: &str = "content_hash";
+    pub const CONTENT_CHANGED: &str = "content_changed";
+    pub const CONTENT_CHECKED_AT: &str = "content_checked_at";
+    pub const UNREACHABLE: &str = "unreachable";
+    pub const OPENED_AT: &str = "opened_at";
+    pub const OPEN_COUNT: &str = "open_count";
+    pub const EXPIRES_AT: &str = "expires_at";
+    pub const PUBLISHED_AT: &str = "published_at";
+    pub const CANONICAL_URL: &str = "canonical_url";
+    pub const PAYWALLED: &str = "paywalled";
+    pub const LANGUAGE: &str = "language";
+    pub const VIA: &str = "via";
+    pub const SOURCE_CONTEXT: &str = "source_context";
+    pub const DERIVED: &str = "derived";
+    pub const ATTACHMENTS: &str = "attachments";
+    pub const MERGED_FROM: &str = "merged_from";
+    pub const READ_POSITION: &str = "read_position";
+
+    // Read position fields
+    pub const LINE: &str = "line";
+    pub const LINES: &str = "lines";
+    pub const AT: &str = "at";
+
+    // Note fields
+    pub const PINNED: &str = "pinned";
+    pub const ORDER: &str = "order";
+    pub const PARENT_ID: &str = "parent_id";
+}
+
+/// What [`RottDocument::get_links_page`] sorts links by
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum SortKey {
+    /// A time, latest first
+    Newest(Reverse<DateTime<Utc>>),
+    /// A time, earliest first
+    Oldest(DateTime<Utc>),
+    /// A lowercased title
+    Title(String),
+    /// Whether there's no domain, the domain, and a lowercased title
+    Domain(bool, Option<String>, String),
+}
+
+/// Statistics about a document's history and size
+#[derive(Debug, Clone, Serialize)]
+pub struct DocumentStats {
+    /// Number of changes in the history
+    pub change_count: usize,
+    /// Number of operations across all changes
+    pub op_count: usize,
+    /// Size of the saved document in bytes
+    pub byte_size: usize,
+    /// Per-actor (device) change counts, most active first
+    pub actors: Vec<ActorStats>,
+    /// Links with the most current data, largest first
+    pub largest_links: Vec<LinkSize>,
+}
+
+/// Changes made by one actor
+#[derive(Debug, Clone, Serialize)]
+pub struct ActorStats {
+    /// Actor ID (hex)
+    pub actor_id: String,
+    /// Number of changes made by this actor
+    pub change_count: usize,
+    /// Number of operations in those changes
+    pub op_count: usize,
+    /// Time of this actor's most recent change, if recorded
+    pub last_change_at: Option<DateTime<Utc>>,
+}
+
+/// Approximate size of a link's current data
+#[derive(Debug, Clone, Serialize)]
+pub struct LinkSize {
+    pub link_id: Uuid,
+    pub title: String,
+    /// Bytes of keys and values in the link, including notes
+    pub bytes: usize,
 }
 
-/// Current schema version (bumped for notes-as-children change)
-pub const CURRENT_SCHEMA_VERSION: u64 = 2;
+/// Current schema version (2: notes as children; 3: tags normalized, see
+/// [`RottDocument::normalize_tags`])
+pub const CURRENT_SCHEMA_VERSION: u64 = 3;
 
 /// A ROTT document backed by Automerge
 pub struct RottDocument {
@@ -113,8 +265,24 @@ impl RottDocument {
 
     /// Load a document from Automerge bytes
     pub fn load(bytes: &[u8]) -> Result<Self, DocumentError> {
-        let doc = AutoCommit::load(bytes)?;
+        Self::from_automerge(AutoCommit::load(bytes)?)
+    }
+
+    /// Load a document from saved chunks (snapshots and incremental saves)
+    ///
+    /// Chunks can be given in any order; Automerge applies each change once
+    /// its dependencies are present.
+    pub fn load_chunks<'a>(
+        chunks: impl IntoIterator<Item = &'a [u8]>,
+    ) -> Result<Self, DocumentError> {
+        let mut doc = AutoCommit::new();
+        for chunk in chunks {
+            doc.load_incremental(chunk)?;
+        }
+        Self::from_automerge(doc)
+    }
 
+    fn from_automerge(doc: AutoCommit) -> Result<Self, DocumentError> {
         // Extract the document ID from the document
         let id_str = match doc.get(ROOT, keys::ROOT_DOC_ID)? {
             Some((value, _)) => value
@@ -145,6 +313,32 @@ impl RottDocument {
         self.doc.save()
     }
 
+    /// Commit the pending changes, recording when they were made and by
+    /// which device (see [`crate::report`])
+    ///
+    /// Changes committed any other way (when the document is saved, merged
+    /// or synced) record neither.
+    pub fn commit_as(&mut self, device: &str) {
+        if self.doc.pending_ops() == 0 {
+            return;
+        }
+        let mut options = CommitOptions::default().with_time(Utc::now().timestamp());
+        if !device.is_empty() {
+            options = options.with_message(device.to_string());
+        }
+        self.doc.commit_with(options);
+    }
+
+    /// Save only the changes made since `heads`
+    pub fn save_after(&mut self, heads: &[ChangeHash]) -> Vec<u8> {
+        self.doc.save_after(heads)
+    }
+
+    /// Current heads of the document
+    pub fn heads(&mut self) -> Vec<ChangeHash> {
+        self.doc.get_heads()
+    }
+
     /// Fork the document (for creating a new branch)
     pub fn fork(&mut self) -> Self {
         Self {
@@ -153,6 +347,16 @@ impl RottDocument {
         }
     }
 
+    /// Fork the document as it was at `heads` (for reading it as it was then)
+    ///
+    /// Fails if a head isn't in the document's history.
+    pub fn fork_at(&mut self, heads: &[ChangeHash]) -> Result<Self, DocumentError> {
+        Ok(Self {
+            id: self.id,
+            doc: self.doc.fork_at(heads)?,
+        })
+    }
+
     /// Merge another document into this one
     pub fn merge(&mut self, other: &mut RottDocument) -> Result<(), DocumentError> {
         self.doc.merge(&mut other.doc)?;
@@ -183,11 +387,97 @@ impl RottDocument {
             .doc
             .put_object(&links_id, link.id.to_string(), ObjType::Map)?;
 
-        self.write_link_fields(&link_id, link)?;
+        self.write_link_fields(&link_id, link, None)?;
+        // Created up front, even at zero: if two devices each created it on
+        // a first open, one counter would replace the other when merged
+        self.doc.put(
+            &link_id,
+            keys::OPEN_COUNT,
+            ScalarValue::counter(link.open_count as i64),
+        )?;
+        if let Some(ref position) = link.read_position {
+            self.write_read_position(&link_id, position)?;
+        }
+        Ok(())
+    }
+
+    /// Record how far a link's archived text was read
+    ///
+    /// Like opens, `update_link` never writes the position, so editing a
+    /// link on one device doesn't undo reading done on another.
+    pub fn set_read_position(
+        &mut self,
+        id: Uuid,
+        position: &ReadPosition,
+    ) -> Result<(), DocumentError> {
+        let links_id = self
+            .doc
+            .get(ROOT, keys::LINKS)?
+            .ok_or_else(|| DocumentError::MissingField("links".to_string()))?
+            .1;
+
+        let link_id = self
+            .doc
+            .get(&links_id, id.to_string())?
+            .ok_or_else(|| DocumentError::MissingField(format!("link {}", id)))?
+            .1;
+
+        self.write_read_position(&link_id, position)
+    }
+
+    /// Write a read position as one map, so positions saved on two devices
+    /// at once don't mix
+    fn write_read_position(
+        &mut self,
+        link_id: &automerge::ObjId,
+        position: &ReadPosition,
+    ) -> Result<(), DocumentError> {
+        let position_id = self
+            .doc
+            .put_object(link_id, keys::READ_POSITION, ObjType::Map)?;
+        self.doc
+            .put(&position_id, keys::LINE, position.line as i64)?;
+        self.doc
+            .put(&position_id, keys::LINES, position.lines as i64)?;
+        self.doc
+            .put(&position_id, keys::AT, position.at.timestamp_millis())?;
+        Ok(())
+    }
+
+    /// Record that a link was opened: set `opened_at` and count the open
+    ///
+    /// The count is an Automerge counter, so opens recorded on different
+    /// devices add up when merged instead of one overwriting the other.
+    /// `update_link` never writes it.
+    pub fn record_open(&mut self, id: Uuid, at: DateTime<Utc>) -> Result<(), DocumentError> {
+        let links_id = self
+            .doc
+            .get(ROOT, keys::LINKS)?
+            .ok_or_else(|| DocumentError::MissingField("links".to_string()))?
+            .1;
+
+        let link_id = self
+            .doc
+            .get(&links_id, id.to_string())?
+            .ok_or_else(|| DocumentError::MissingField(format!("link {}", id)))?
+            .1;
+
+        self.doc
+            .put(&link_id, keys::OPENED_AT, at.timestamp_millis())?;
+        // Links added before counts existed have no counter yet
+        match self.doc.get(&link_id, keys::OPEN_COUNT)? {
+            Some(_) => self.doc.increment(&link_id, keys::OPEN_COUNT, 1)?,
+            None => self
+                .doc
+                .put(&link_id, keys::OPEN_COUNT, ScalarValue::counter(1))?,
+        }
         Ok(())
     }
 
     /// Update an existing link
+    ///
+    /// Only fields that changed are written, so fields that couldn't be read
+    /// keep their stored value.
     pub fn update_link(&mut self, link: &Link) -> Result<(), DocumentError> {
         let links_id = self
             .doc
@@ -201,7 +491,8 @@ impl RottDocument {
             .ok_or_else(|| DocumentError::MissingField(format!("link {}", link.id)))?
             .1;
 
-        self.write_link_fields(&link_id, link)?;
+        let stored = self.read_link(&link_id, link.id, &mut Vec::new());
+        self.write_link_fields(&link_id, link, Some(&stored))?;
         Ok(())
     }
 
@@ -226,13 +517,24 @@ impl RottDocument {
             .1;
 
         match self.doc.get(&links_id, id.to_string())? {
-            Some((_, link_id)) => Ok(Some(self.read_link(&link_id, id)?)),
+            Some((_, link_id)) => Ok(Some(self.read_link(&link_id, id, &mut Vec::new()))),
             None => Ok(None),
         }
     }
 
     /// Get all links
+    ///
+    /// Malformed records are read as far as possible; see
+    /// [`get_all_links_with_warnings`](Self::get_all_links_with_warnings) to
+    /// find out which.
     pub fn get_all_links(&self) -> Result<Vec<Link>, DocumentError> {
+        Ok(self.get_all_links_with_warnings()?.0)
+    }
+
+    /// Get all links, along with the problems found reading them
+    pub fn get_all_links_with_warnings(
+        &self,
+    ) -> Result<(Vec<Link>, Vec<RecordWarning>), DocumentError> {
         let links_id = self
             .doc
             .get(ROOT, keys::LINKS)?
@@ -240,53 +542,160 @@ impl RottDocument {
             .1;
 
         let mut links = Vec::new();
+        let mut warnings = Vec::new();
         for key in self.doc.keys(&links_id) {
-            let id = Uuid::parse_str(&key).map_err(|_| DocumentError::InvalidUuid(key.clone()))?;
-            if let Some((_, link_id)) = self.doc.get(&links_id, &key)? {
-                links.push(self.read_link(&link_id, id)?);
+            let Ok(id) = Uuid::parse_str(&key) else {
+                warnings.push(RecordWarning {
+                    link_id: None,
+                    note_id: None,
+                    problem: format!("Link stored under \"{}\", which isn't a UUID; skipped", key),
+                });
+                continue;
+            };
+            match self.doc.get(&links_id, &key)? {
+                Some((Value::Object(_), link_id)) => {
+                    links.push(self.read_link(&link_id, id, &mut warnings))
+                }
+                Some(_) => warnings.push(RecordWarning {
+                    link_id: Some(id),
+                    note_id: None,
+                    problem: "Link isn't a record; skipped".to_string(),
+                }),
+                None => {}
             }
         }
-        Ok(links)
+        Ok((links, warnings))
+    }
+
+    /// Get one page of the links in the given order
+    ///
+    /// Only the field sorted by is read for every link; the links on the
+    /// page are read in full, so a large collection can be shown a page at
+    /// a time. Links sorting the same are ordered by ID.
+    pub fn get_links_page(
+        &self,
+        offset: usize,
+        limit: usize,
+        sort: LinkSort,
+    ) -> Result<LinksPage, DocumentError> {
+        let links_id = self
+            .doc
+            .get(ROOT, keys::LINKS)?
+            .ok_or_else(|| DocumentError::MissingField("links".to_string()))?
+            .1;
+
+        // Problems are reported by `get_all_links_with_warnings`
+        let mut warnings = Vec::new();
+        let mut sorted = Vec::new();
+        for key in self.doc.keys(&links_id) {
+            let Ok(id) = Uuid::parse_str(&key) else {
+                continue;
+            };
+            let Some((Value::Object(_), link_obj_id)) = self.doc.get(&links_id, &key)? else {
+                continue;
+            };
+            let mut field = Lenient {
+                warnings: &mut warnings,
+                link_id: Some(id),
+                note_id: None,
+            };
+            let key = match sort {
+                LinkSort::Updated => SortKey::Newest(Reverse(field.or(
+                    self.get_timestamp(&link_obj_id, keys::UPDATED_AT),
+                    DateTime::<Utc>::default(),
+                ))),
+                LinkSort::Created => SortKey::Newest(Reverse(field.or(
+                    self.get_timestamp(&link_obj_id, keys::CREATED_AT),
+                    DateTime::<Utc>::default(),
+                ))),
+                LinkSort::Oldest => SortKey::Oldest(field.or(
+                    self.get_timestamp(&link_obj_id, keys::CREATED_AT),
+                    DateTime::<Utc>::default(),
+                )),
+                LinkSort::Title => SortKey::Title(
+                    field
+                        .or(self.get_string(&link_obj_id, keys::TITLE), String::new())
+                        .to_lowercase(),
+                ),
+                LinkSort::Domain => {
+                    let domain = site_domain(
+                        &field.or(self.get_string(&link_obj_id, keys::URL), String::new()),
+                    );
+                    let title = field
+                        .or(self.get_string(&link_obj_id, keys::TITLE), String::new())
+                        .to_lowercase();
+                    SortKey::Domain(domain.is_none(), domain, title)
+                }
+            };
+            sorted.push((key, id, link_obj_id));
+        }
+        sorted.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+
+        let links = sorted
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .map(|(_, id, link_obj_id)| self.read_link(link_obj_id, *id, &mut warnings))
+            .collect();
+        Ok(LinksPage {
+            offset,
+            links,
+            total: sorted.len(),
+        })
     }
 
-    /// Get links filtered by tag
+    /// Get links filtered by tag (ignoring case, so `Rust` finds `rust`)
     pub fn get_links_by_tag(&self, tag: &str) -> Result<Vec<Link>, DocumentError> {
         let all_links = self.get_all_links()?;
         Ok(all_links
             .into_iter()
-            .filter(|link| link.tags.iter().any(|t| t == tag))
+            .filter(|link| link.has_tag(tag))
             .collect())
     }
 
     /// Get a link by URL (for duplicate detection)
     ///
-    /// Performs a linear scan with basic URL normalization (trailing slash removal,
-    /// domain lowercasing). Returns the first match found.
-    pub fn get_link_by_url(&self, url: &str) -> Result<Option<Link>, DocumentError> {
-        let normalized = normalize_url(url);
+    /// Performs a linear scan comparing normalized URLs (see
+    /// [`crate::canonical`]), matching both saved and canonical URLs.
+    /// Returns the first match found.
+    pub fn get_link_by_url(
+        &self,
+        url: &str,
+        trailing_slash: TrailingSlash,
+    ) -> Result<Option<Link>, DocumentError> {
+        let normalized = normalize_url(url, trailing_slash);
         let all_links = self.get_all_links()?;
         Ok(all_links.into_iter().find(|link| {
-            let link_normalized = normalize_url(&link.url);
-            link_normalized == normalized || link.url == url
+            std::iter::once(&link.url)
+                .chain(link.canonical_url.as_ref())
+                .any(|saved| saved == url || normalize_url(saved, trailing_slash) == normalized)
         }))
     }
 
     /// Search links using case-insensitive substring matching
     ///
-    /// Searches across title, URL, and description fields.
+    /// Searches across title, URL, and description fields, and the titles
+    /// and bodies of notes.
+    ///
+    /// Operators (`author:name`, `has:paywall`, `lang:de`; see [`SearchQuery`]) narrow
+    /// the results; the rest of the query is matched against titles, URLs,
+    /// descriptions, and notes.
     pub fn search_links(&self, query: &str) -> Result<Vec<Link>, DocumentError> {
-        let query_lower = query.to_lowercase();
+        Ok(self
+            .search(query)?
+            .into_iter()
+            .map(|hit| hit.link)
+            .collect())
+    }
+
+    /// Search links like [`search_links`](Self::search_links), noting which
+    /// note matched
+    pub fn search(&self, query: &str) -> Result<Vec<SearchHit>, DocumentError> {
+        let query = SearchQuery::parse(query);
         let all_links = self.get_all_links()?;
         Ok(all_links
             .into_iter()
-            .filter(|link| {
-                link.title.to_lowercase().contains(&query_lower)
-                    || link.url.to_lowercase().contains(&query_lower)
-                    || link
-                        .description
-                        .as_ref()
-                        .is_some_and(|d| d.to_lowercase().contains(&query_lower))
-            })
+            .filter_map(|link| query.hit(link))
             .collect())
     }
 
@@ -294,7 +703,7 @@ impl RottDocument {
     pub fn get_tags_with_counts(&self) -> Result<Vec<(String, i64)>, DocumentError> {
         let mut counts: std::collections::HashMap<String, i64> = std::collections::HashMap::new();
         for link in self.get_all_links()? {
-            for tag in link.tags {
+            for tag in normalize_tags(&link.tags) {
                 *counts.entry(tag).or_insert(0) += 1;
             }
         }
@@ -303,6 +712,39 @@ impl RottDocument {
         Ok(result)
     }
 
+    /// Get links by an author (ignoring case)
+    pub fn get_links_by_author(&self, author: &str) -> Result<Vec<Link>, DocumentError> {
+        let author = author_key(author);
+        let all_links = self.get_all_links()?;
+        Ok(all_links
+            .into_iter()
+            .filter(|link| link.author.iter().any(|a| author_key(a) == author))
+            .collect())
+    }
+
+    /// Get authors with usage counts
+    ///
+    /// Names differing only in case are counted together, under the first
+    /// spelling seen. Sorted by count, then name.
+    pub fn get_authors_with_counts(&self) -> Result<Vec<(String, i64)>, DocumentError> {
+        let mut counts: BTreeMap<String, (String, i64)> = BTreeMap::new();
+        for link in self.get_all_links()? {
+            for author in &link.author {
+                let key = author_key(author);
+                if key.is_empty() {
+                    continue;
+                }
+                counts
+                    .entry(key)
+                    .or_insert_with(|| (author.trim().to_string(), 0))
+                    .1 += 1;
+            }
+        }
+        let mut result: Vec<_> = counts.into_values().collect();
+        result.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(result)
+    }
+
     /// Get count of all links
     pub fn link_count(&self) -> Result<usize, DocumentError> {
         Ok(self.get_all_links()?.len())
@@ -389,117 +831,930 @@ impl RottDocument {
         Ok(())
     }
 
-    // ==================== Tags ====================
+    /// Update an existing note's title and body (and when they were edited)
+    pub fn update_note(&mut self, link_id: Uuid, note: &Note) -> Result<(), DocumentError> {
+        let links_id = self
+            .doc
+            .get(ROOT, keys::LINKS)?
+            .ok_or_else(|| DocumentError::MissingField("links".to_string()))?
+            .1;
 
-    /// Get all unique tags from links
-    pub fn get_all_tags(&self) -> Result<Vec<String>, DocumentError> {
-        let mut tags = std::collections::HashSet::new();
+        let link_obj_id = self
+            .doc
+            .get(&links_id, link_id.to_string())?
+            .ok_or_else(|| DocumentError::MissingField(format!("link {}", link_id)))?
+            .1;
 
-        for link in self.get_all_links()? {
-            for tag in link.tags {
-                tags.insert(tag);
-            }
+        let notes_id = self
+            .doc
+            .get(&link_obj_id, keys::NOTES)?
+            .ok_or_else(|| DocumentError::MissingField(format!("notes for link {}", link_id)))?
+            .1;
+
+        let note_obj_id = self
+            .doc
+            .get(&notes_id, note.id.to_string())?
+            .ok_or_else(|| DocumentError::MissingField(format!("note {}", note.id)))?
+            .1;
+
+        if note.title.is_none() {
+            self.delete_if_present(&note_obj_id, keys::TITLE)?;
         }
+        self.write_note_fields(&note_obj_id, note)?;
 
-        let mut tags: Vec<_> = tags.into_iter().collect();
-        tags.sort();
-        Ok(tags)
-    }
+        // Update link's updated_at
+        self.doc.put(
+            &link_obj_id,
+            keys::UPDATED_AT,
+            Utc::now().timestamp_millis(),
+        )?;
 
-    // ==================== Private helpers ====================
+        Ok(())
+    }
 
-    fn write_link_fields(
-        &mut self,
-        obj_id: &automerge::ObjId,
-        link: &Link,
-    ) -> Result<(), DocumentError> {
-        self.doc.put(obj_id, keys::ID, link.id.to_string())?;
-        self.doc.put(obj_id, keys::TITLE, link.title.clone())?;
-        self.doc.put(obj_id, keys::URL, link.url.clone())?;
+    /// Update a note's pinned state and position
+    ///
+    /// Unlike [`RottDocument::update_note`], this doesn't count as editing
+    /// the link, so its `updated_at` is left alone.
+    pub fn set_note_position(&mut self, link_id: Uuid, note: &Note) -> Result<(), DocumentError> {
+        let links_id = self
+            .doc
+            .get(ROOT, keys::LINKS)?
+            .ok_or_else(|| DocumentError::MissingField("links".to_string()))?
+            .1;
 
-        if let Some(ref desc) = link.description {
-            self.doc.put(obj_id, keys::DESCRIPTION, desc.clone())?;
-        }
+        let link_obj_id = self
+            .doc
+            .get(&links_id, link_id.to_string())?
+            .ok_or_else(|| DocumentError::MissingField(format!("link {}", link_id)))?
+            .1;
 
-        // Write author array
-        let author_id = self.doc.put_object(obj_id, keys::AUTHOR, ObjType::List)?;
-        for (i, author) in link.author.iter().enumerate() {
-            self.doc.insert(&author_id, i, author.clone())?;
-        }
+        let notes_id = self
+            .doc
+            .get(&link_obj_id, keys::NOTES)?
+            .ok_or_else(|| DocumentError::MissingField(format!("notes for link {}", link_id)))?
+            .1;
 
-        // Write tags array
-        let tags_id = self.doc.put_object(obj_id, keys::TAGS, ObjType::List)?;
-        for (i, tag) in link.tags.iter().enumerate() {
-            self.doc.insert(&tags_id, i, tag.clone())?;
-        }
+        let note_obj_id = self
+            .doc
+            .get(&notes_id, note.id.to_string())?
+            .ok_or_else(|| DocumentError::MissingField(format!("note {}", note.id)))?
+            .1;
 
-        self.doc
-            .put(obj_id, keys::CREATED_AT, link.created_at.timestamp_millis())?;
-        self.doc
-            .put(obj_id, keys::UPDATED_AT, link.updated_at.timestamp_millis())?;
+        self.write_note_position(&note_obj_id, note)
+    }
 
-        // Write notes map
-        let notes_id = self.doc.put_object(obj_id, keys::NOTES, ObjType::Map)?;
-        for note in &link.notes {
-            let note_obj_id = self
-                .doc
-                .put_object(&notes_id, note.id.to_string(), ObjType::Map)?;
-            self.write_note_fields(&note_obj_id, note)?;
+    /// Get the version history of a note, oldest first
+    ///
+    /// Walks the document's change history and records each distinct
+    /// title/body the note has had. The note is located by ID at each point
+    /// in history, so versions survive the notes map being rewritten when
+    /// its link is updated.
+    pub fn note_history(
+        &mut self,
+        link_id: Uuid,
+        note_id: Uuid,
+    ) -> Result<Vec<NoteVersion>, DocumentError> {
+        let changes: Vec<(ChangeHash, i64)> = self
+            .doc
+            .get_changes(&[])
+            .into_iter()
+            .map(|c| (c.hash(), c.timestamp()))
+            .collect();
+
+        let mut versions: Vec<NoteVersion> = Vec::new();
+        for (hash, timestamp) in changes {
+            let Some((title, body)) = self.read_note_at(link_id, note_id, &[hash]) else {
+                continue;
+            };
+
+            let unchanged = versions
+                .last()
+                .is_some_and(|v| v.title == title && v.body == body);
+            if !unchanged {
+                versions.push(NoteVersion {
+                    change_hash: hash.to_string(),
+                    // Automerge change times are in seconds; 0 means unrecorded
+                    timestamp: (timestamp > 0)
+                        .then(|| Utc.timestamp_opt(timestamp, 0).single())
+                        .flatten(),
+                    title,
+                    body,
+                });
+            }
         }
 
-        Ok(())
+        Ok(versions)
     }
 
-    fn read_link(&self, obj_id: &automerge::ObjId, id: Uuid) -> Result<Link, DocumentError> {
-        let title = self.get_string(obj_id, keys::TITLE)?;
-        let url = self.get_string(obj_id, keys::URL)?;
-        let description = self.get_optional_string(obj_id, keys::DESCRIPTION)?;
-        let author = self.get_string_list(obj_id, keys::AUTHOR)?;
-        let tags = self.get_string_list(obj_id, keys::TAGS)?;
-        let created_at = self.get_timestamp(obj_id, keys::CREATED_AT)?;
-        let updated_at = self.get_timestamp(obj_id, keys::UPDATED_AT)?;
+    // ==================== Event Log ====================
 
-        // Read notes
-        let notes = self.read_notes_for_link(obj_id)?;
+    /// Replay the document's history as events (see [`crate::events`])
+    ///
+    /// Changes are applied one at a time, in causal order, to an empty
+    /// document. After each, the links it touched are read and compared
+    /// with how they were before, and `each` is called with what changed.
+    pub fn replay_events(&mut self, mut each: impl FnMut(StoreEvent)) -> Result<(), DocumentError> {
+        let changes: Vec<Change> = self.doc.get_changes(&[]).into_iter().cloned().collect();
+        let mut replay = RottDocument {
+            id: self.id.clone(),
+            doc: AutoCommit::new(),
+        };
+        let mut known: HashMap<Uuid, Link> = HashMap::new();
+
+        for change in changes {
+            let hash = change.hash().to_string();
+            let timestamp = change.timestamp();
+            // Automerge change times are in seconds; 0 means unrecorded
+            let at = (timestamp > 0)
+                .then(|| Utc.timestamp_opt(timestamp, 0).single())
+                .flatten();
+            let device = change
+                .message()
+                .map(|message| message.to_string())
+                .filter(|message| !message.is_empty());
+
+            let before = replay.doc.get_heads();
+            replay.doc.apply_changes([change])?;
+            let after = replay.doc.get_heads();
+            let Some((_, links_id)) = replay.doc.get(ROOT, keys::LINKS)? else {
+                continue;
+            };
+
+            let touched = touched_links(replay.doc.diff(&before, &after), &links_id);
+
+            let event = |kind| StoreEvent {
+                change: hash.clone(),
+                at,
+                device: device.clone(),
+                kind,
+            };
+            for key in touched {
+                let Ok(link_id) = Uuid::parse_str(&key) else {
+                    continue;
+                };
+                let Some(link) = replay.get_link(link_id)? else {
+                    if known.remove(&link_id).is_some() {
+                        each(event(EventKind::LinkDeleted { link_id }));
+                    }
+                    continue;
+                };
+                let previous = known.insert(link_id, link.clone());
+                if previous.as_ref() == Some(&link) {
+                    continue;
+                }
 
-        Ok(Link {
-            id,
-            title,
-            url,
-            description,
-            author,
-            tags,
-            created_at,
-            updated_at,
-            notes,
-        })
+                each(event(EventKind::LinkUpserted { link: link.clone() }));
+                let old_notes = previous.map(|p| p.notes).unwrap_or_default();
+                for note in &link.notes {
+                    if !old_notes.contains(note) {
+                        each(event(EventKind::NoteUpserted {
+                            link_id,
+                            note: note.clone(),
+                        }));
+                    }
+                }
+                for old in &old_notes {
+                    if !link.notes.iter().any(|note| note.id == old.id) {
+                        each(event(EventKind::NoteDeleted {
+                            link_id,
+                            note_id: old.id,
+                        }));
+                    }
+                }
+            }
+        }
+        Ok(())
     }
 
-    fn read_notes_for_link(
-        &self,
-        link_obj_id: &automerge::ObjId,
-    ) -> Result<Vec<Note>, DocumentError> {
-        let notes_id = match self.doc.get(link_obj_id, keys::NOTES)? {
-            Some((_, id)) => id,
-            None => return Ok(Vec::new()),
+    /// IDs of the links added, changed or deleted since `heads`
+    ///
+    /// None if that can't be worked out, because a head isn't in this
+    /// document's history or the links map itself was replaced; everything
+    /// has to be treated as changed then.
+    pub fn links_changed_since(
+        &mut self,
+        heads: &[ChangeHash],
+    ) -> Result<Option<HashSet<Uuid>>, DocumentError> {
+        if heads
+            .iter()
+            .any(|hash| self.doc.get_change_by_hash(hash).is_none())
+        {
+            return Ok(None);
+        }
+        let before = self.doc.get_at(ROOT, keys::LINKS, heads)?.map(|(_, id)| id);
+        let now = self.doc.get(ROOT, keys::LINKS)?.map(|(_, id)| id);
+        let links_id = match (before, now) {
+            (_, None) => return Ok(Some(HashSet::new())),
+            (Some(before), Some(now)) if before == now => now,
+            _ => return Ok(None),
         };
 
-        let mut notes = Vec::new();
-        for key in self.doc.keys(&notes_id) {
-            let id = Uuid::parse_str(&key).map_err(|_| DocumentError::InvalidUuid(key.clone()))?;
-            if let Some((_, note_obj_id)) = self.doc.get(&notes_id, &key)? {
-                notes.push(self.read_note(&note_obj_id, id)?);
+        let after = self.doc.get_heads();
+        let touched = touched_links(self.doc.diff(heads, &after), &links_id);
+        Ok(Some(
+            touched
+                .iter()
+                .filter_map(|key| Uuid::parse_str(key).ok())
+                .collect(),
+        ))
+    }
+
+    // ==================== Statistics ====================
+
+    /// Collect statistics about the document's history and size
+    ///
+    /// `top` limits how many of the largest links are reported.
+    pub fn stats(&mut self, top: usize) -> Result<DocumentStats, DocumentError> {
+        let mut change_count = 0;
+        let mut op_count = 0;
+        let mut actors: BTreeMap<String, ActorStats> = BTreeMap::new();
+
+        for change in self.doc.get_changes(&[]) {
+            change_count += 1;
+            op_count += change.len();
+
+            let actor_id = change.actor_id().to_hex_string();
+            let timestamp = change.timestamp();
+            let actor = actors.entry(actor_id.clone()).or_insert(ActorStats {
+                actor_id,
+                change_count: 0,
+                op_count: 0,
+                last_change_at: None,
+            });
+            actor.change_count += 1;
+            actor.op_count += change.len();
+            // Automerge change times are in seconds; 0 means unrecorded
+            if let Some(time) = (timestamp > 0)
+                .then(|| Utc.timestamp_opt(timestamp, 0).single())
+                .flatten()
+            {
+                actor.last_change_at = actor.last_change_at.max(Some(time));
             }
         }
 
-        // Sort by created_at
-        notes.sort_by(|a, b| a.created_at.cmp(&b.created_at));
-        Ok(notes)
+        let mut actors: Vec<ActorStats> = actors.into_values().collect();
+        actors.sort_by(|a, b| b.change_count.cmp(&a.change_count));
+
+        let mut largest_links = Vec::new();
+        if let Some((_, links_id)) = self.doc.get(ROOT, keys::LINKS)? {
+            for key in self.doc.keys(&links_id) {
+                let Ok(link_id) = Uuid::parse_str(&key) else {
+                    continue;
+                };
+                if let Some((_, link_obj_id)) = self.doc.get(&links_id, key.as_str())? {
+                    largest_links.push(LinkSize {
+                        link_id,
+                        title: self
+                            .get_string(&link_obj_id, keys::TITLE)
+                            .unwrap_or_default(),
+                        bytes: self.object_size(&link_obj_id),
+                    });
+                }
+            }
+        }
+        largest_links.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+        largest_links.truncate(top);
+
+        Ok(DocumentStats {
+            change_count,
+            op_count,
+            byte_size: self.doc.save().len(),
+            actors,
+            largest_links,
+        })
     }
 
-    fn write_note_fields(
-        &mut self,
-        obj_id: &automerge::ObjId,
+    // ==================== Tags ====================
+
+    /// Get all unique tags from links
+    ///
+    /// Tags are listed in normalized form, so case variants (`Rust` and
+    /// `rust`) appear once.
+    pub fn get_all_tags(&self) -> Result<Vec<String>, DocumentError> {
+        let mut tags = std::collections::HashSet::new();
+
+        for link in self.get_all_links()? {
+            for tag in normalize_tags(&link.tags) {
+                tags.insert(tag);
+            }
+        }
+
+        let mut tags: Vec<_> = tags.into_iter().collect();
+        tags.sort();
+        Ok(tags)
+    }
+
+    /// Get the description of a tag, if it has one
+    pub fn get_tag_description(&self, tag: &str) -> Result<Option<String>, DocumentError> {
+        let Some(tag) = normalize_tag(tag) else {
+            return Ok(None);
+        };
+        match self.doc.get(ROOT, keys::TAG_DESCRIPTIONS)? {
+            // A description that isn't text counts as missing
+            Some((_, descriptions_id)) => Ok(self
+                .get_optional_string(&descriptions_id, &tag)
+                .ok()
+                .flatten()),
+            None => Ok(None),
+        }
+    }
+
+    /// Get every tag description, by tag
+    pub fn get_tag_descriptions(&self) -> Result<BTreeMap<String, String>, DocumentError> {
+        let mut descriptions = BTreeMap::new();
+        if let Some((_, descriptions_id)) = self.doc.get(ROOT, keys::TAG_DESCRIPTIONS)? {
+            for tag in self.doc.keys(&descriptions_id) {
+                // Descriptions that aren't text are left out
+                if let Ok(Some(description)) = self.get_optional_string(&descriptions_id, &tag) {
+                    descriptions.insert(tag, description);
+                }
+            }
+        }
+        Ok(descriptions)
+    }
+
+    /// Set or clear (with None or blank text) the description of a tag
+    ///
+    /// Descriptions are kept under the normalized tag, and don't need any
+    /// link to have the tag.
+    pub fn set_tag_description(
+        &mut self,
+        tag: &str,
+        description: Option<&str>,
+    ) -> Result<(), DocumentError> {
+        let tag = normalize_tag(tag).ok_or_else(|| DocumentError::InvalidType(tag.to_string()))?;
+        let description = description.map(str::trim).filter(|d| !d.is_empty());
+        let descriptions_id = match self.doc.get(ROOT, keys::TAG_DESCRIPTIONS)? {
+            Some((_, id)) => id,
+            None if description.is_none() => return Ok(()),
+            None => self
+                .doc
+                .put_object(ROOT, keys::TAG_DESCRIPTIONS, ObjType::Map)?,
+        };
+        match description {
+            Some(description) => self.doc.put(&descriptions_id, tag, description)?,
+            None => self.delete_if_present(&descriptions_id, &tag)?,
+        }
+        Ok(())
+    }
+
+    /// Get every tag's visibility mark, by tag
+    ///
+    /// Marks that aren't `private` or `public` are left out.
+    pub fn get_tag_visibility(&self) -> Result<BTreeMap<String, TagVisibility>, DocumentError> {
+        let mut marks = BTreeMap::new();
+        if let Some((_, visibility_id)) = self.doc.get(ROOT, keys::TAG_VISIBILITY)? {
+            for tag in self.doc.keys(&visibility_id) {
+                let mark = self
+                    .get_optional_string(&visibility_id, &tag)
+                    .ok()
+                    .flatten()
+                    .and_then(|mark| mark.parse().ok());
+                if let Some(mark) = mark {
+                    marks.insert(tag, mark);
+                }
+            }
+        }
+        Ok(marks)
+    }
+
+    /// Mark a tag private or public, or clear its mark with None
+    ///
+    /// Marks are kept under the normalized tag.
+    pub fn set_tag_visibility(
+        &mut self,
+        tag: &str,
+        visibility: Option<TagVisibility>,
+    ) -> Result<(), DocumentError> {
+        let tag = normalize_tag(tag).ok_or_else(|| DocumentError::InvalidType(tag.to_string()))?;
+        let visibility_id = match self.doc.get(ROOT, keys::TAG_VISIBILITY)? {
+            Some((_, id)) => id,
+            None if visibility.is_none() => return Ok(()),
+            None => self
+                .doc
+                .put_object(ROOT, keys::TAG_VISIBILITY, ObjType::Map)?,
+        };
+        match visibility {
+            Some(visibility) => self.doc.put(&visibility_id, tag, visibility.as_str())?,
+            None => self.delete_if_present(&visibility_id, &tag)?,
+        }
+        Ok(())
+    }
+
+    /// The schema version the document was last migrated to (0 if unset)
+    pub fn schema_version(&self) -> Result<u64, DocumentError> {
+        self.get_count(&ROOT, keys::SCHEMA_VERSION)
+    }
+
+    /// Record that the document is at [`CURRENT_SCHEMA_VERSION`]
+    pub fn set_schema_version(&mut self) -> Result<(), DocumentError> {
+        self.doc
+            .put(ROOT, keys::SCHEMA_VERSION, CURRENT_SCHEMA_VERSION)?;
+        Ok(())
+    }
+
+    /// Rewrite tags that aren't in normalized form
+    ///
+    /// Merges case variants and other duplicates (`Rust`, `rust`) on each
+    /// link. Doesn't count as editing the links: `updated_at` is left
+    /// alone. Returns the number of links changed.
+    pub fn normalize_tags(&mut self) -> Result<usize, DocumentError> {
+        let Some((_, links_id)) = self.doc.get(ROOT, keys::LINKS)? else {
+            return Ok(0);
+        };
+
+        let mut changed = 0;
+        for link in self.get_all_links()? {
+            let tags = normalize_tags(&link.tags);
+            if tags == link.tags {
+                continue;
+            }
+            let Some((_, link_obj_id)) = self.doc.get(&links_id, link.id.to_string())? else {
+                continue;
+            };
+            let tags_id = self
+                .doc
+                .put_object(&link_obj_id, keys::TAGS, ObjType::List)?;
+            for (i, tag) in tags.into_iter().enumerate() {
+                self.doc.insert(&tags_id, i, tag)?;
+            }
+            changed += 1;
+        }
+        Ok(changed)
+    }
+
+    // ==================== Archives ====================
+
+    /// Get the archive documents, by year
+    ///
+    /// Entries that aren't a year and a document ID are left out.
+    pub fn get_archives(&self) -> Result<BTreeMap<i32, DocumentId>, DocumentError> {
+        let mut archives = BTreeMap::new();
+        if let Some((_, archives_id)) = self.doc.get(ROOT, keys::ARCHIVES)? {
+            for key in self.doc.keys(&archives_id) {
+                let Ok(year) = key.parse::<i32>() else {
+                    continue;
+                };
+                if let Ok(Some(id)) = self.get_optional_string(&archives_id, &key) {
+                    if let Ok(id) = DocumentId::from_bs58check(&id) {
+                        archives.insert(year, id);
+                    }
+                }
+            }
+        }
+        Ok(archives)
+    }
+
+    /// Record the archive document for a year
+    pub fn set_archive(&mut self, year: i32, id: &DocumentId) -> Result<(), DocumentError> {
+        let archives_id = match self.doc.get(ROOT, keys::ARCHIVES)? {
+            Some((_, id)) => id,
+            None => self.doc.put_object(ROOT, keys::ARCHIVES, ObjType::Map)?,
+        };
+        self.doc
+            .put(&archives_id, year.to_string(), id.to_bs58check())?;
+        Ok(())
+    }
+
+    // ==================== Private helpers ====================
+
+    /// Write a link's fields
+    ///
+    /// With `stored` (the link as read before the update), only fields that
+    /// differ from it are written. A field that couldn't be read comes back
+    /// as a default on both sides, so an update leaves the stored value
+    /// alone unless that field is actually changed.
+    fn write_link_fields(
+        &mut self,
+        obj_id: &automerge::ObjId,
+        link: &Link,
+        stored: Option<&Link>,
+    ) -> Result<(), DocumentError> {
+        fn changed<T: PartialEq>(stored: Option<&T>, new: &T) -> bool {
+            stored != Some(new)
+        }
+
+        if changed(stored.map(|s| &s.id), &link.id) {
+            self.doc.put(obj_id, keys::ID, link.id.to_string())?;
+        }
+        if changed(stored.map(|s| &s.title), &link.title) {
+            self.doc.put(obj_id, keys::TITLE, link.title.clone())?;
+        }
+        if changed(stored.map(|s| &s.url), &link.url) {
+            self.doc.put(obj_id, keys::URL, link.url.clone())?;
+        }
+
+        if changed(stored.map(|s| &s.description), &link.description) {
+            match link.description {
+                Some(ref desc) => self.doc.put(obj_id, keys::DESCRIPTION, desc.clone())?,
+                None => self.delete_if_present(obj_id, keys::DESCRIPTION)?,
+            }
+        }
+
+        // Write author array
+        if changed(stored.map(|s| &s.author), &link.author) {
+            let author_id = self.doc.put_object(obj_id, keys::AUTHOR, ObjType::List)?;
+            for (i, author) in link.author.iter().enumerate() {
+                self.doc.insert(&author_id, i, author.clone())?;
+            }
+        }
+
+        // Write tags array
+        if changed(stored.map(|s| &s.tags), &link.tags) {
+            let tags_id = self.doc.put_object(obj_id, keys::TAGS, ObjType::List)?;
+            for (i, tag) in link.tags.iter().enumerate() {
+                self.doc.insert(&tags_id, i, tag.clone())?;
+            }
+        }
+
+        if changed(stored.map(|s| &s.created_at), &link.created_at) {
+            self.doc
+                .put(obj_id, keys::CREATED_AT, link.created_at.timestamp_millis())?;
+        }
+        if changed(stored.map(|s| &s.updated_at), &link.updated_at) {
+            self.doc
+                .put(obj_id, keys::UPDATED_AT, link.updated_at.timestamp_millis())?;
+        }
+
+        // Content change tracking (absent for links saved before it existed)
+        if changed(stored.map(|s| &s.content_hash), &link.content_hash) {
+            match link.content_hash {
+                Some(ref hash) => self.doc.put(obj_id, keys::CONTENT_HASH, hash.clone())?,
+                None => self.delete_if_present(obj_id, keys::CONTENT_HASH)?,
+            }
+        }
+        if changed(stored.map(|s| &s.content_changed), &link.content_changed) {
+            self.doc
+                .put(obj_id, keys::CONTENT_CHANGED, link.content_changed)?;
+        }
+        if changed(
+            stored.map(|s| &s.content_checked_at),
+            &link.content_checked_at,
+        ) {
+            match link.content_checked_at {
+                Some(checked) => {
+                    self.doc
+                        .put(obj_id, keys::CONTENT_CHECKED_AT, checked.timestamp_millis())?
+                }
+                None => self.delete_if_present(obj_id, keys::CONTENT_CHECKED_AT)?,
+            }
+        }
+        if changed(stored.map(|s| &s.unreachable), &link.unreachable) {
+            if link.unreachable {
+                self.doc.put(obj_id, keys::UNREACHABLE, true)?;
+            } else {
+                self.delete_if_present(obj_id, keys::UNREACHABLE)?;
+            }
+        }
+        if changed(stored.map(|s| &s.opened_at), &link.opened_at) {
+            match link.opened_at {
+                Some(opened) => self
+                    .doc
+                    .put(obj_id, keys::OPENED_AT, opened.timestamp_millis())?,
+                None => self.delete_if_present(obj_id, keys::OPENED_AT)?,
+            }
+        }
+        if changed(stored.map(|s| &s.expires_at), &link.expires_at) {
+            match link.expires_at {
+                Some(expires) => {
+                    self.doc
+                        .put(obj_id, keys::EXPIRES_AT, expires.timestamp_millis())?
+                }
+                None => self.delete_if_present(obj_id, keys::EXPIRES_AT)?,
+            }
+        }
+        if changed(stored.map(|s| &s.published_at), &link.published_at) {
+            match link.published_at {
+                Some(published) => {
+                    self.doc
+                        .put(obj_id, keys::PUBLISHED_AT, published.timestamp_millis())?
+                }
+                None => self.delete_if_present(obj_id, keys::PUBLISHED_AT)?,
+            }
+        }
+        if changed(stored.map(|s| &s.paywalled), &link.paywalled) {
+            if link.paywalled {
+                self.doc.put(obj_id, keys::PAYWALLED, true)?;
+            } else {
+                self.delete_if_present(obj_id, keys::PAYWALLED)?;
+            }
+        }
+        if changed(stored.map(|s| &s.canonical_url), &link.canonical_url) {
+            match link.canonical_url {
+                Some(ref canonical) => {
+                    self.doc
+                        .put(obj_id, keys::CANONICAL_URL, canonical.clone())?
+                }
+                None => self.delete_if_present(obj_id, keys::CANONICAL_URL)?,
+            }
+        }
+        if changed(stored.map(|s| &s.language), &link.language) {
+            match link.language {
+                Some(ref language) => self.doc.put(obj_id, keys::LANGUAGE, language.clone())?,
+                None => self.delete_if_present(obj_id, keys::LANGUAGE)?,
+            }
+        }
+        if changed(stored.map(|s| &s.via), &link.via) {
+            match link.via {
+                Some(ref via) => self.doc.put(obj_id, keys::VIA, via.clone())?,
+                None => self.delete_if_present(obj_id, keys::VIA)?,
+            }
+        }
+        if changed(stored.map(|s| &s.source_context), &link.source_context) {
+            match link.source_context {
+                Some(ref context) => self
+                    .doc
+                    .put(obj_id, keys::SOURCE_CONTEXT, context.clone())?,
+                None => self.delete_if_present(obj_id, keys::SOURCE_CONTEXT)?,
+            }
+        }
+
+        // Write derived fields map
+        if changed(stored.map(|s| &s.derived), &link.derived) {
+            let derived_id = self.doc.put_object(obj_id, keys::DERIVED, ObjType::Map)?;
+            for (key, value) in &link.derived {
+                self.doc.put(&derived_id, key.as_str(), value.clone())?;
+            }
+        }
+
+        // Attachments (absent for links without any)
+        if changed(stored.map(|s| &s.attachments), &link.attachments) {
+            if link.attachments.is_empty() {
+                self.delete_if_present(obj_id, keys::ATTACHMENTS)?;
+            } else {
+                let attachments_id =
+                    self.doc
+                        .put_object(obj_id, keys::ATTACHMENTS, ObjType::Map)?;
+                for (name, hash) in &link.attachments {
+                    self.doc.put(&attachments_id, name.as_str(), hash.clone())?;
+                }
+            }
+        }
+
+        // Links merged into this one (absent for links without any)
+        if changed(stored.map(|s| &s.merged_from), &link.merged_from) {
+            if link.merged_from.is_empty() {
+                self.delete_if_present(obj_id, keys::MERGED_FROM)?;
+            } else {
+                let merged_id = self
+                    .doc
+                    .put_object(obj_id, keys::MERGED_FROM, ObjType::List)?;
+                for (i, id) in link.merged_from.iter().enumerate() {
+                    self.doc.insert(&merged_id, i, id.to_string())?;
+                }
+            }
+        }
+
+        // Write notes map
+        let Some(stored) = stored else {
+            let notes_id = self.doc.put_object(obj_id, keys::NOTES, ObjType::Map)?;
+            for note in &link.notes {
+                let note_obj_id =
+                    self.doc
+                        .put_object(&notes_id, note.id.to_string(), ObjType::Map)?;
+                self.write_note_fields(&note_obj_id, note)?;
+            }
+            return Ok(());
+        };
+        if stored.notes == link.notes {
+            return Ok(());
+        }
+        // Notes are updated one by one, so notes that couldn't be read (and
+        // so aren't in `stored`) are kept
+        let notes_id = match self.doc.get(obj_id, keys::NOTES)? {
+            Some((Value::Object(_), id)) => id,
+            _ => self.doc.put_object(obj_id, keys::NOTES, ObjType::Map)?,
+        };
+        for note in &link.notes {
+            if stored.notes.contains(note) {
+                continue;
+            }
+            let note_obj_id = self
+                .doc
+                .put_object(&notes_id, note.id.to_string(), ObjType::Map)?;
+            self.write_note_fields(&note_obj_id, note)?;
+        }
+        for note in &stored.notes {
+            if !link.notes.iter().any(|n| n.id == note.id) {
+                self.doc.delete(&notes_id, note.id.to_string())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read a link, falling back to defaults for fields that can't be read
+    fn read_link(
+        &self,
+        obj_id: &automerge::ObjId,
+        id: Uuid,
+        warnings: &mut Vec<RecordWarning>,
+    ) -> Link {
+        let mut field = Lenient {
+            warnings: &mut *warnings,
+            link_id: Some(id),
+            note_id: None,
+        };
+        let url = field.or(self.get_string(obj_id, keys::URL), String::new());
+        let title = field.or(self.get_string(obj_id, keys::TITLE), url.clone());
+        let description = field.or(self.get_optional_string(obj_id, keys::DESCRIPTION), None);
+        let author = field.or(self.get_string_list(obj_id, keys::AUTHOR), Vec::new());
+        let tags = field.or(self.get_string_list(obj_id, keys::TAGS), Vec::new());
+        let created_at = field.or(
+            self.get_timestamp(obj_id, keys::CREATED_AT),
+            DateTime::<Utc>::default(),
+        );
+        let updated_at = field.or(self.get_timestamp(obj_id, keys::UPDATED_AT), created_at);
+        let content_hash = field.or(self.get_optional_string(obj_id, keys::CONTENT_HASH), None);
+        let content_changed = field.or(self.get_bool(obj_id, keys::CONTENT_CHANGED), false);
+        let content_checked_at = field.or(
+            self.get_optional_timestamp(obj_id, keys::CONTENT_CHECKED_AT),
+            None,
+        );
+        let unreachable = field.or(self.get_bool(obj_id, keys::UNREACHABLE), false);
+        let opened_at = field.or(self.get_optional_timestamp(obj_id, keys::OPENED_AT), None);
+        let open_count = field.or(self.get_count(obj_id, keys::OPEN_COUNT), 0);
+        let read_position = field.or(self.get_read_position(obj_id), None);
+        let expires_at = field.or(self.get_optional_timestamp(obj_id, keys::EXPIRES_AT), None);
+        let published_at = field.or(
+            self.get_optional_timestamp(obj_id, keys::PUBLISHED_AT),
+            None,
+        );
+        let canonical_url = field.or(self.get_optional_string(obj_id, keys::CANONICAL_URL), None);
+        let paywalled = field.or(self.get_bool(obj_id, keys::PAYWALLED), false);
+        let language = field.or(self.get_optional_string(obj_id, keys::LANGUAGE), None);
+        let via = field.or(self.get_optional_string(obj_id, keys::VIA), None);
+        let source_context = field.or(self.get_optional_string(obj_id, keys::SOURCE_CONTEXT), None);
+
+        let derived = field.or(self.get_string_map(obj_id, keys::DERIVED), BTreeMap::new());
+        let attachments = field.or(
+            self.get_string_map(obj_id, keys::ATTACHMENTS),
+            BTreeMap::new(),
+        );
+        let merged_from = field
+            .or(self.get_string_list(obj_id, keys::MERGED_FROM), Vec::new())
+            .iter()
+            .filter_map(|id| Uuid::parse_str(id).ok())
+            .collect();
+
+        // Read notes
+        let notes = self.read_notes_for_link(obj_id, id, warnings);
+
+        Link {
+            id,
+            title,
+            url,
+            description,
+            author,
+            tags,
+            created_at,
+            updated_at,
+            notes,
+            content_hash,
+            content_changed,
+            content_checked_at,
+            unreachable,
+            opened_at,
+            open_count,
+            read_position,
+            expires_at,
+            published_at,
+            canonical_url,
+            paywalled,
+            language,
+            via,
+            source_context,
+            derived,
+            attachments,
+            merged_from,
+        }
+    }
+
+    /// Get one page of a link's notes, in the threaded order they're shown in
+    ///
+    /// Only the notes on the page are read in full, so links with hundreds
+    /// of notes can be shown a page at a time. Returns None if the link
+    /// doesn't exist.
+    pub fn get_notes_page(
+        &self,
+        link_id: Uuid,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Option<NotesPage>, DocumentError> {
+        let Some((_, links_id)) = self.doc.get(ROOT, keys::LINKS)? else {
+            return Ok(None);
+        };
+        let Some((_, link_obj_id)) = self.doc.get(&links_id, link_id.to_string())? else {
+            return Ok(None);
+        };
+        let notes_id = match self.doc.get(&link_obj_id, keys::NOTES)? {
+            Some((_, id)) => id,
+            None => {
+                return Ok(Some(NotesPage {
+                    link_id,
+                    offset,
+                    notes: Vec::new(),
+                    total: 0,
+                }))
+            }
+        };
+
+        // Problems are reported by `get_all_links_with_warnings`
+        let mut warnings = Vec::new();
+        let mut positions = Vec::new();
+        for key in self.doc.keys(&notes_id) {
+            let Ok(id) = Uuid::parse_str(&key) else {
+                continue;
+            };
+            if let Some((Value::Object(_), note_obj_id)) = self.doc.get(&notes_id, &key)? {
+                let mut field = Lenient {
+                    warnings: &mut warnings,
+                    link_id: Some(link_id),
+                    note_id: Some(id),
+                };
+                positions.push(self.read_note_position(&note_obj_id, id, &mut field));
+            }
+        }
+        sort_notes(&mut positions);
+
+        let threaded = thread_notes(&positions);
+        let mut notes = Vec::new();
+        for (position, depth) in threaded.iter().skip(offset).take(limit) {
+            if let Some((_, note_obj_id)) = self.doc.get(&notes_id, position.id.to_string())? {
+                let mut field = Lenient {
+                    warnings: &mut warnings,
+                    link_id: Some(link_id),
+                    note_id: Some(position.id),
+                };
+                notes.push((
+                    self.read_note(&note_obj_id, position.id, &mut field),
+                    *depth,
+                ));
+            }
+        }
+
+        Ok(Some(NotesPage {
+            link_id,
+            offset,
+            notes,
+            total: threaded.len(),
+        }))
+    }
+
+    /// Read a link's notes, skipping (with a warning) any that aren't records
+    fn read_notes_for_link(
+        &self,
+        link_obj_id: &automerge::ObjId,
+        link_id: Uuid,
+        warnings: &mut Vec<RecordWarning>,
+    ) -> Vec<Note> {
+        let mut field = Lenient {
+            warnings,
+            link_id: Some(link_id),
+            note_id: None,
+        };
+        let notes_id = match self.doc.get(link_obj_id, keys::NOTES) {
+            Ok(Some((Value::Object(_), id))) => id,
+            Ok(None) => return Vec::new(),
+            Ok(Some(_)) => {
+                field.warn("Notes aren't a record; skipped".to_string());
+                return Vec::new();
+            }
+            Err(e) => {
+                field.warn(e.to_string());
+                return Vec::new();
+            }
+        };
+
+        let mut notes = Vec::new();
+        for key in self.doc.keys(&notes_id) {
+            let Ok(id) = Uuid::parse_str(&key) else {
+                field.note_id = None;
+                field.warn(format!(
+                    "Note stored under \"{}\", which isn't a UUID; skipped",
+                    key
+                ));
+                continue;
+            };
+            field.note_id = Some(id);
+            match self.doc.get(&notes_id, &key) {
+                Ok(Some((Value::Object(_), note_obj_id))) => {
+                    notes.push(self.read_note(&note_obj_id, id, &mut field))
+                }
+                Ok(Some(_)) => field.warn("Note isn't a record; skipped".to_string()),
+                Ok(None) => {}
+                Err(e) => field.warn(e.to_string()),
+            }
+        }
+
+        sort_notes(&mut notes);
+        notes
+    }
+
+    fn write_note_fields(
+        &mut self,
+        obj_id: &automerge::ObjId,
         note: &Note,
     ) -> Result<(), DocumentError> {
         self.doc.put(obj_id, keys::ID, note.id.to_string())?;
@@ -511,21 +1766,95 @@ impl RottDocument {
         self.doc.put(obj_id, keys::BODY, note.body.clone())?;
         self.doc
             .put(obj_id, keys::CREATED_AT, note.created_at.timestamp_millis())?;
+        self.write_note_position(obj_id, note)?;
+        match note.parent_id {
+            Some(parent_id) => self
+                .doc
+                .put(obj_id, keys::PARENT_ID, parent_id.to_string())?,
+            None => self.delete_if_present(obj_id, keys::PARENT_ID)?,
+        }
+        match note.updated_at {
+            Some(updated_at) => {
+                self.doc
+                    .put(obj_id, keys::UPDATED_AT, updated_at.timestamp_millis())?
+            }
+            None => self.delete_if_present(obj_id, keys::UPDATED_AT)?,
+        }
 
         Ok(())
     }
 
-    fn read_note(&self, obj_id: &automerge::ObjId, id: Uuid) -> Result<Note, DocumentError> {
-        let title = self.get_optional_string(obj_id, keys::TITLE)?;
-        let body = self.get_string(obj_id, keys::BODY)?;
-        let created_at = self.get_timestamp(obj_id, keys::CREATED_AT)?;
+    fn write_note_position(
+        &mut self,
+        obj_id: &automerge::ObjId,
+        note: &Note,
+    ) -> Result<(), DocumentError> {
+        // Absent for notes that were never pinned or moved
+        if note.pinned {
+            self.doc.put(obj_id, keys::PINNED, true)?;
+        } else {
+            self.delete_if_present(obj_id, keys::PINNED)?;
+        }
+        match note.order {
+            Some(order) => self.doc.put(obj_id, keys::ORDER, order)?,
+            None => self.delete_if_present(obj_id, keys::ORDER)?,
+        }
+        Ok(())
+    }
+
+    fn read_note(&self, obj_id: &automerge::ObjId, id: Uuid, field: &mut Lenient<'_>) -> Note {
+        let mut note = self.read_note_position(obj_id, id, field);
+        note.title = field.or(self.get_optional_string(obj_id, keys::TITLE), None);
+        note.body = field.or(self.get_string(obj_id, keys::BODY), String::new());
+        note.updated_at = field.or(self.get_optional_timestamp(obj_id, keys::UPDATED_AT), None);
+        note
+    }
+
+    /// Read what's needed to sort and thread a note, leaving out its title
+    /// and body
+    fn read_note_position(
+        &self,
+        obj_id: &automerge::ObjId,
+        id: Uuid,
+        field: &mut Lenient<'_>,
+    ) -> Note {
+        let created_at = field.or(
+            self.get_timestamp(obj_id, keys::CREATED_AT),
+            DateTime::<Utc>::default(),
+        );
+        let pinned = field.or(self.get_bool(obj_id, keys::PINNED), false);
+        let order = field.or(self.get_order(obj_id), None);
+        // A reply whose parent can't be read is shown as a top-level note
+        let parent_id = field.or(
+            self.get_optional_string(obj_id, keys::PARENT_ID)
+                .and_then(|parent_id| {
+                    parent_id
+                        .map(|p| Uuid::parse_str(&p).map_err(|_| DocumentError::InvalidUuid(p)))
+                        .transpose()
+                }),
+            None,
+        );
 
-        Ok(Note {
+        Note {
             id,
-            title,
-            body,
+            title: None,
+            body: String::new(),
             created_at,
-        })
+            pinned,
+            order,
+            parent_id,
+            updated_at: None,
+        }
+    }
+
+    fn get_order(&self, obj_id: &automerge::ObjId) -> Result<Option<f64>, DocumentError> {
+        match self.doc.get(obj_id, keys::ORDER)? {
+            Some((value, _)) => value
+                .to_f64()
+                .map(Some)
+                .ok_or_else(|| DocumentError::InvalidType(keys::ORDER.to_string())),
+            None => Ok(None),
+        }
     }
 
     fn get_string(&self, obj_id: &automerge::ObjId, key: &str) -> Result<String, DocumentError> {
@@ -544,7 +1873,10 @@ impl RottDocument {
         key: &str,
     ) -> Result<Option<String>, DocumentError> {
         match self.doc.get(obj_id, key)? {
-            Some((value, _)) => Ok(value.to_str().map(|s| s.to_string())),
+            Some((value, _)) => value
+                .to_str()
+                .map(|s| Some(s.to_string()))
+                .ok_or_else(|| DocumentError::InvalidType(key.to_string())),
             None => Ok(None),
         }
     }
@@ -571,6 +1903,134 @@ impl RottDocument {
         }
     }
 
+    /// Read a note's title and body as of the given heads
+    fn read_note_at(
+        &self,
+        link_id: Uuid,
+        note_id: Uuid,
+        heads: &[ChangeHash],
+    ) -> Option<(Option<String>, String)> {
+        let links_id = self.doc.get_at(ROOT, keys::LINKS, heads).ok()??.1;
+        let link_obj_id = self
+            .doc
+            .get_at(&links_id, link_id.to_string(), heads)
+            .ok()??
+            .1;
+        let notes_id = self.doc.get_at(&link_obj_id, keys::NOTES, heads).ok()??.1;
+        let note_obj_id = self
+            .doc
+            .get_at(&notes_id, note_id.to_string(), heads)
+            .ok()??
+            .1;
+
+        let body = self
+            .doc
+            .get_at(&note_obj_id, keys::BODY, heads)
+            .ok()??
+            .0
+            .to_str()?
+            .to_string();
+        let title = self
+            .doc
+            .get_at(&note_obj_id, keys::TITLE, heads)
+            .ok()
+            .flatten()
+            .and_then(|(value, _)| value.to_str().map(|s| s.to_string()));
+
+        Some((title, body))
+    }
+
+    /// Approximate bytes of keys and values in an object, recursively
+    fn object_size(&self, obj_id: &automerge::ObjId) -> usize {
+        let entry_size = |entry: Option<(Value, automerge::ObjId)>| match entry {
+            Some((Value::Object(_), child_id)) => self.object_size(&child_id),
+            Some((Value::Scalar(scalar), _)) => match scalar.as_ref() {
+                ScalarValue::Str(s) => s.len(),
+                ScalarValue::Bytes(b) => b.len(),
+                _ => 8,
+            },
+            None => 0,
+        };
+
+        match self.doc.object_type(obj_id) {
+            Ok(ObjType::Map) | Ok(ObjType::Table) => self
+                .doc
+                .keys(obj_id)
+                .map(|key| {
+                    key.len() + entry_size(self.doc.get(obj_id, key.as_str()).ok().flatten())
+                })
+                .sum(),
+            Ok(ObjType::List) => (0..self.doc.length(obj_id))
+                .map(|i| entry_size(self.doc.get(obj_id, i).ok().flatten()))
+                .sum(),
+            Ok(ObjType::Text) => self.doc.length(obj_id),
+            Err(_) => 0,
+        }
+    }
+
+    fn get_string_map(
+        &self,
+        obj_id: &automerge::ObjId,
+        key: &str,
+    ) -> Result<BTreeMap<String, String>, DocumentError> {
+        let mut result = BTreeMap::new();
+        if let Some((_, map_id)) = self.doc.get(obj_id, key)? {
+            for entry_key in self.doc.keys(&map_id) {
+                // Values that aren't text are left out
+                if let Ok(Some(value)) = self.get_optional_string(&map_id, &entry_key) {
+                    result.insert(entry_key, value);
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    fn delete_if_present(
+        &mut self,
+        obj_id: &automerge::ObjId,
+        key: &str,
+    ) -> Result<(), DocumentError> {
+        if self.doc.get(obj_id, key)?.is_some() {
+            self.doc.delete(obj_id, key)?;
+        }
+        Ok(())
+    }
+
+    fn get_bool(&self, obj_id: &automerge::ObjId, key: &str) -> Result<bool, DocumentError> {
+        match self.doc.get(obj_id, key)? {
+            Some((value, _)) => value
+                .to_bool()
+                .ok_or_else(|| DocumentError::InvalidType(key.to_string())),
+            None => Ok(false),
+        }
+    }
+
+    /// A counter's value, or 0 if it isn't set
+    fn get_count(&self, obj_id: &automerge::ObjId, key: &str) -> Result<u64, DocumentError> {
+        match self.doc.get(obj_id, key)? {
+            Some((value, _)) => value
+                .to_i64()
+                .map(|count| count.max(0) as u64)
+                .ok_or_else(|| DocumentError::InvalidType(key.to_string())),
+            None => Ok(0),
+        }
+    }
+
+    /// A link's read position, or None if it hasn't been read
+    fn get_read_position(
+        &self,
+        obj_id: &automerge::ObjId,
+    ) -> Result<Option<ReadPosition>, DocumentError> {
+        let Some((_, position_id)) = self.doc.get(obj_id, keys::READ_POSITION)? else {
+            return Ok(None);
+        };
+        Ok(Some(ReadPosition {
+            line: self.get_count(&position_id, keys::LINE)? as usize,
+            lines: self.get_count(&position_id, keys::LINES)? as usize,
+            at: self.get_timestamp(&position_id, keys::AT)?,
+        }))
+    }
+
     fn get_timestamp(
         &self,
         obj_id: &automerge::ObjId,
@@ -588,6 +2048,17 @@ impl RottDocument {
             None => Err(DocumentError::MissingField(key.to_string())),
         }
     }
+
+    fn get_optional_timestamp(
+        &self,
+        obj_id: &automerge::ObjId,
+        key: &str,
+    ) -> Result<Option<DateTime<Utc>>, DocumentError> {
+        match self.doc.get(obj_id, key)? {
+            Some(_) => self.get_timestamp(obj_id, key).map(Some),
+            None => Ok(None),
+        }
+    }
 }
 
 impl Default for RottDocument {
@@ -596,72 +2067,209 @@ impl Default for RottDocument {
     }
 }
 
-/// Normalize a URL for duplicate detection
-///
-/// - Removes trailing slashes (except for root path)
-/// - Lowercases the domain portion
-fn normalize_url(url: &str) -> String {
-    let mut normalized = url.trim().to_string();
-
-    // Remove trailing slash (but not for root path)
-    if normalized.ends_with('/') && normalized.matches('/').count() > 3 {
-        normalized.pop();
-    }
-
-    // Try to lowercase just the domain part
-    if let Some(idx) = normalized.find("://") {
-        let (scheme, rest) = normalized.split_at(idx + 3);
-        if let Some(path_idx) = rest.find('/') {
-            let (domain, path) = rest.split_at(path_idx);
-            normalized = format!("{}{}{}", scheme, domain.to_lowercase(), path);
-        } else {
-            normalized = format!("{}{}", scheme, rest.to_lowercase());
+/// Key authors are compared by
+fn author_key(author: &str) -> String {
+    author.trim().to_lowercase()
+}
+
+/// Keys of the links a diff touches, directly or below them
+fn touched_links(patches: Vec<Patch>, links_id: &ObjId) -> BTreeSet<String> {
+    let mut touched = BTreeSet::new();
+    for patch in patches {
+        if patch.obj == *links_id {
+            if let PatchAction::PutMap { key, .. } | PatchAction::DeleteMap { key } = patch.action {
+                touched.insert(key);
+            }
+        } else if let Some((obj, Prop::Map(key))) = patch.path.get(1) {
+            if obj == links_id {
+                touched.insert(key.clone());
+            }
+        }
+    }
+    touched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_document() {
+        let doc = RottDocument::new();
+        assert!(doc.get_all_links().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_add_and_get_link() {
+        let mut doc = RottDocument::new();
+        let mut link = Link::new("https://example.com");
+        link.set_title("Example Site");
+        link.add_tag("test");
+
+        doc.add_link(&link).unwrap();
+
+        let retrieved = doc.get_link(link.id).unwrap().unwrap();
+        assert_eq!(retrieved.id, link.id);
+        assert_eq!(retrieved.title, "Example Site");
+        assert_eq!(retrieved.url, "https://example.com");
+        assert_eq!(retrieved.tags, vec!["test"]);
+        assert!(retrieved.notes.is_empty());
+    }
+
+    #[test]
+    fn test_links_changed_since() {
+        let mut doc = RottDocument::new();
+        let kept = Link::new("https://example.com/kept");
+        let mut edited = Link::new("https://example.com/edited");
+        let deleted = Link::new("https://example.com/deleted");
+        for link in [&kept, &edited, &deleted] {
+            doc.add_link(link).unwrap();
         }
+        let heads = doc.heads();
+        assert_eq!(
+            doc.links_changed_since(&heads).unwrap(),
+            Some(HashSet::new())
+        );
+
+        edited.set_title("Edited");
+        doc.update_link(&edited).unwrap();
+        doc.delete_link(deleted.id).unwrap();
+        let added = Link::new("https://example.com/added");
+        doc.add_link(&added).unwrap();
+
+        let changed = doc.links_changed_since(&heads).unwrap().unwrap();
+        assert_eq!(changed, HashSet::from([edited.id, deleted.id, added.id]));
+
+        // Heads from another document can't be diffed against
+        let mut other = RottDocument::new();
+        other.add_link(&kept).unwrap();
+        assert_eq!(doc.links_changed_since(&other.heads()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_update_link() {
+        let mut doc = RottDocument::new();
+        let mut link = Link::new("https://example.com");
+        doc.add_link(&link).unwrap();
+
+        link.set_title("Updated Title");
+        link.add_tag("updated");
+        doc.update_link(&link).unwrap();
+
+        let retrieved = doc.get_link(link.id).unwrap().unwrap();
+        assert_eq!(retrieved.title, "Updated Title");
+        assert!(retrieved.tags.contains(&"updated".to_string()));
+    }
+
+    #[test]
+    fn test_content_tracking_roundtrip() {
+        let mut doc = RottDocument::new();
+        let mut link = Link::new("https://example.com/pricing");
+        doc.add_link(&link).unwrap();
+
+        // Links without a fingerprint read back with no tracking data
+        let retrieved = doc.get_link(link.id).unwrap().unwrap();
+        assert!(retrieved.content_hash.is_none());
+        assert!(!retrieved.content_changed);
+        assert!(retrieved.content_checked_at.is_none());
+
+        link.set_content_hash(Some("0410d84600088803".to_string()));
+        link.content_changed = true;
+        doc.update_link(&link).unwrap();
+
+        let retrieved = doc.get_link(link.id).unwrap().unwrap();
+        assert_eq!(retrieved.content_hash.as_deref(), Some("0410d84600088803"));
+        assert!(retrieved.content_changed);
+        assert!(retrieved.content_checked_at.is_some());
+
+        link.set_content_hash(None);
+        doc.update_link(&link).unwrap();
+
+        let retrieved = doc.get_link(link.id).unwrap().unwrap();
+        assert!(retrieved.content_hash.is_none());
+        assert!(!retrieved.content_changed);
+        assert!(!retrieved.unreachable);
+        assert!(retrieved.opened_at.is_none());
+        assert!(retrieved.expires_at.is_none());
+        assert!(retrieved.published_at.is_none());
+        assert!(retrieved.language.is_none());
+        assert!(retrieved.via.is_none());
+
+        link.unreachable = true;
+        link.language = Some("de".to_string());
+        link.set_source(Some("Simon".to_string()), Some("HN thread".to_string()));
+        link.opened_at = Some(Utc::now());
+        link.set_expires_at(Some(Utc::now() + chrono::Duration::days(30)));
+        link.set_published_at(Some(Utc::now() - chrono::Duration::days(400)));
+        doc.update_link(&link).unwrap();
+
+        let retrieved = doc.get_link(link.id).unwrap().unwrap();
+        assert!(retrieved.unreachable);
+        assert_eq!(retrieved.language.as_deref(), Some("de"));
+        assert_eq!(retrieved.via.as_deref(), Some("Simon"));
+        assert_eq!(retrieved.source_context.as_deref(), Some("HN thread"));
+        assert_eq!(
+            retrieved.opened_at.map(|t| t.timestamp_millis()),
+            link.opened_at.map(|t| t.timestamp_millis())
+        );
+        assert_eq!(
+            retrieved.expires_at.map(|t| t.timestamp_millis()),
+            link.expires_at.map(|t| t.timestamp_millis())
+        );
+        assert_eq!(
+            retrieved.published_at.map(|t| t.timestamp_millis()),
+            link.published_at.map(|t| t.timestamp_millis())
+        );
     }
 
-    normalized
-}
+    #[test]
+    fn test_derived_fields_roundtrip() {
+        let mut doc = RottDocument::new();
+        let mut link = Link::new("https://example.com");
+        link.derived
+            .insert("domain".to_string(), "example.com".to_string());
+        doc.add_link(&link).unwrap();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let retrieved = doc.get_link(link.id).unwrap().unwrap();
+        assert_eq!(retrieved.derived, link.derived);
 
-    #[test]
-    fn test_new_document() {
-        let doc = RottDocument::new();
-        assert!(doc.get_all_links().unwrap().is_empty());
+        link.derived.clear();
+        doc.update_link(&link).unwrap();
+        let retrieved = doc.get_link(link.id).unwrap().unwrap();
+        assert!(retrieved.derived.is_empty());
     }
 
     #[test]
-    fn test_add_and_get_link() {
+    fn test_attachments_roundtrip() {
         let mut doc = RottDocument::new();
         let mut link = Link::new("https://example.com");
-        link.set_title("Example Site");
-        link.add_tag("test");
-
+        link.attachments
+            .insert("screenshot.png".to_string(), "ab12".to_string());
         doc.add_link(&link).unwrap();
 
         let retrieved = doc.get_link(link.id).unwrap().unwrap();
-        assert_eq!(retrieved.id, link.id);
-        assert_eq!(retrieved.title, "Example Site");
-        assert_eq!(retrieved.url, "https://example.com");
-        assert_eq!(retrieved.tags, vec!["test"]);
-        assert!(retrieved.notes.is_empty());
+        assert_eq!(retrieved.attachments, link.attachments);
+
+        link.attachments.clear();
+        doc.update_link(&link).unwrap();
+        let retrieved = doc.get_link(link.id).unwrap().unwrap();
+        assert!(retrieved.attachments.is_empty());
     }
 
     #[test]
-    fn test_update_link() {
+    fn test_merged_from_roundtrip() {
         let mut doc = RottDocument::new();
         let mut link = Link::new("https://example.com");
+        link.merged_from.push(Uuid::new_v4());
         doc.add_link(&link).unwrap();
 
-        link.set_title("Updated Title");
-        link.add_tag("updated");
-        doc.update_link(&link).unwrap();
+        let retrieved = doc.get_link(link.id).unwrap().unwrap();
+        assert_eq!(retrieved.merged_from, link.merged_from);
 
+        link.merged_from.clear();
+        doc.update_link(&link).unwrap();
         let retrieved = doc.get_link(link.id).unwrap().unwrap();
-        assert_eq!(retrieved.title, "Updated Title");
-        assert!(retrieved.tags.contains(&"updated".to_string()));
+        assert!(retrieved.merged_from.is_empty());
     }
 
     #[test]
@@ -733,6 +2341,35 @@ mod tests {
         assert_eq!(retrieved.notes[0].body, "This article covers...");
     }
 
+    #[test]
+    fn test_note_position() {
+        let mut doc = RottDocument::new();
+        let link = Link::new("https://example.com");
+        doc.add_link(&link).unwrap();
+
+        let first = Note::new("First");
+        let mut summary = Note::new("Summary");
+        summary.created_at = first.created_at + chrono::Duration::seconds(1);
+        doc.add_note_to_link(link.id, &first).unwrap();
+        doc.add_note_to_link(link.id, &summary).unwrap();
+        let updated_at = doc.get_link(link.id).unwrap().unwrap().updated_at;
+
+        summary.pinned = true;
+        doc.set_note_position(link.id, &summary).unwrap();
+        let retrieved = doc.get_link(link.id).unwrap().unwrap();
+        assert_eq!(retrieved.notes[0].id, summary.id);
+        assert!(retrieved.notes[0].pinned);
+        assert_eq!(retrieved.updated_at, updated_at);
+
+        summary.pinned = false;
+        summary.order = Some(first.created_at.timestamp_millis() as f64 - 1.0);
+        doc.set_note_position(link.id, &summary).unwrap();
+        let retrieved = doc.get_link(link.id).unwrap().unwrap();
+        assert_eq!(retrieved.notes[0].id, summary.id);
+        assert!(!retrieved.notes[0].pinned);
+        assert_eq!(retrieved.notes[0].order, summary.order);
+    }
+
     #[test]
     fn test_remove_note_from_link() {
         let mut doc = RottDocument::new();
@@ -749,6 +2386,192 @@ mod tests {
         assert!(retrieved.notes.is_empty());
     }
 
+    #[test]
+    fn test_get_links_page() {
+        let mut doc = RottDocument::new();
+        let start = Utc::now() - chrono::Duration::hours(1);
+        for (i, title) in ["Beta", "alpha", "Gamma"].iter().enumerate() {
+            let mut link = Link::new(format!("https://example.com/{}", i));
+            link.title = title.to_string();
+            link.created_at = start + chrono::Duration::minutes(i as i64);
+            link.updated_at = start - chrono::Duration::minutes(i as i64);
+            doc.add_link(&link).unwrap();
+        }
+        let titles = |page: &LinksPage| -> Vec<String> {
+            page.links.iter().map(|l| l.title.clone()).collect()
+        };
+
+        let page = doc.get_links_page(0, 2, LinkSort::Updated).unwrap();
+        assert_eq!(page.total, 3);
+        assert!(page.has_more());
+        assert_eq!(titles(&page), ["Beta", "alpha"]);
+
+        let mut all = page.clone();
+        all.extend(doc.get_links_page(2, 2, LinkSort::Updated).unwrap());
+        assert!(!all.has_more());
+        assert_eq!(titles(&all), ["Beta", "alpha", "Gamma"]);
+
+        let page = doc.get_links_page(0, 10, LinkSort::Created).unwrap();
+        assert_eq!(titles(&page), ["Gamma", "alpha", "Beta"]);
+        let page = doc.get_links_page(0, 10, LinkSort::Title).unwrap();
+        assert_eq!(titles(&page), ["alpha", "Beta", "Gamma"]);
+        let page = doc.get_links_page(0, 10, LinkSort::Oldest).unwrap();
+        assert_eq!(titles(&page), ["Beta", "alpha", "Gamma"]);
+
+        // Pages come in the same order as sorting all the links
+        for sort in LinkSort::ALL {
+            let mut links = doc.get_all_links().unwrap();
+            sort.sort(&mut links);
+            assert_eq!(doc.get_links_page(0, 10, sort).unwrap().links, links);
+        }
+        assert!(doc
+            .get_links_page(5, 10, LinkSort::Title)
+            .unwrap()
+            .links
+            .is_empty());
+    }
+
+    #[test]
+    fn test_get_notes_page() {
+        let mut doc = RottDocument::new();
+        let mut link = Link::new("https://example.com");
+        let start = Utc::now() - chrono::Duration::hours(1);
+        let mut first = Note::new("first");
+        first.created_at = start;
+        let mut reply = Note::new("reply");
+        reply.parent_id = Some(first.id);
+        reply.created_at = start + chrono::Duration::minutes(10);
+        link.add_note(first);
+        for i in 0..5 {
+            let mut note = Note::new(format!("note {}", i));
+            note.created_at = start + chrono::Duration::minutes(i + 1);
+            link.add_note(note);
+        }
+        link.add_note(reply);
+        doc.add_link(&link).unwrap();
+
+        let page = doc.get_notes_page(link.id, 0, 3).unwrap().unwrap();
+        assert_eq!(page.total, 7);
+        assert!(page.has_more());
+        let bodies: Vec<_> = page
+            .notes
+            .iter()
+            .map(|(n, d)| (n.body.as_str(), *d))
+            .collect();
+        assert_eq!(bodies, vec![("first", 0), ("reply", 1), ("note 0", 0)]);
+
+        let mut all = page.clone();
+        all.extend(doc.get_notes_page(link.id, 3, 10).unwrap().unwrap());
+        assert!(!all.has_more());
+        assert_eq!(all.notes.len(), 7);
+        assert_eq!(all.notes[6].0.body, "note 4");
+
+        assert!(doc.get_notes_page(Uuid::new_v4(), 0, 3).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_note_reply_round_trips() {
+        let mut doc = RottDocument::new();
+        let link = Link::new("https://example.com");
+        doc.add_link(&link).unwrap();
+
+        let note = Note::new("Observation");
+        let mut reply = Note::new("Follow-up");
+        reply.parent_id = Some(note.id);
+        doc.add_note_to_link(link.id, &note).unwrap();
+        doc.add_note_to_link(link.id, &reply).unwrap();
+
+        let retrieved = doc.get_link(link.id).unwrap().unwrap();
+        assert_eq!(
+            retrieved.get_note(reply.id).unwrap().parent_id,
+            Some(note.id)
+        );
+        assert_eq!(retrieved.get_note(note.id).unwrap().parent_id, None);
+    }
+
+    #[test]
+    fn test_update_note() {
+        let mut doc = RottDocument::new();
+        let link = Link::new("https://example.com");
+        doc.add_link(&link).unwrap();
+
+        let mut note = Note::with_title("Title", "Original");
+        doc.add_note_to_link(link.id, &note).unwrap();
+
+        note.set_title(None);
+        note.set_body("Edited");
+        doc.update_note(link.id, &note).unwrap();
+
+        let retrieved = doc.get_link(link.id).unwrap().unwrap();
+        let saved = retrieved.get_note(note.id).unwrap();
+        assert_eq!(saved.body, "Edited");
+        assert!(saved.title.is_none());
+        assert_eq!(
+            saved.updated_at.map(|t| t.timestamp_millis()),
+            note.updated_at.map(|t| t.timestamp_millis())
+        );
+        assert!(saved.is_edited());
+    }
+
+    #[test]
+    fn test_note_history() {
+        let mut doc = RottDocument::new();
+        let mut link = Link::new("https://example.com");
+        doc.add_link(&link).unwrap();
+
+        let mut note = Note::new("First draft");
+        doc.add_note_to_link(link.id, &note).unwrap();
+        doc.save();
+
+        note.set_body("Second draft");
+        doc.update_note(link.id, &note).unwrap();
+        doc.save();
+
+        // Updating the link rewrites the notes map but keeps the history
+        link.add_note(note.clone());
+        link.add_tag("research");
+        doc.update_link(&link).unwrap();
+        doc.save();
+
+        note.set_title(Some("Final".to_string()));
+        note.set_body("Third draft");
+        doc.update_note(link.id, &note).unwrap();
+        doc.save();
+
+        let history = doc.note_history(link.id, note.id).unwrap();
+        let bodies: Vec<_> = history.iter().map(|v| v.body.as_str()).collect();
+        assert_eq!(bodies, vec!["First draft", "Second draft", "Third draft"]);
+        assert_eq!(history[2].title.as_deref(), Some("Final"));
+
+        assert!(doc
+            .note_history(link.id, Uuid::new_v4())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut doc = RottDocument::new();
+        let small = Link::new("https://a.example");
+        let mut large = Link::new("https://b.example");
+        large.set_title("A link with a much longer title");
+        large.add_note(Note::new("Plenty of note text to make this link larger"));
+        doc.add_link(&small).unwrap();
+        doc.save();
+        doc.add_link(&large).unwrap();
+        doc.save();
+
+        let stats = doc.stats(1).unwrap();
+        // One change per save
+        assert_eq!(stats.change_count, 2);
+        assert!(stats.op_count > stats.change_count);
+        assert!(stats.byte_size > 0);
+        assert_eq!(stats.actors.len(), 1);
+        assert_eq!(stats.actors[0].change_count, 2);
+        assert_eq!(stats.largest_links.len(), 1);
+        assert_eq!(stats.largest_links[0].link_id, large.id);
+    }
+
     #[test]
     fn test_multiple_notes_sorted() {
         let mut doc = RottDocument::new();
@@ -780,6 +2603,61 @@ mod tests {
         assert_eq!(retrieved.notes[0].body, "Inline note");
     }
 
+    #[test]
+    fn test_tag_descriptions() {
+        let mut doc = RottDocument::new();
+        assert_eq!(doc.get_tag_description("rust").unwrap(), None);
+        // Clearing a tag that was never described is a no-op
+        doc.set_tag_description("rust", None).unwrap();
+
+        doc.set_tag_description("Rust Lang", Some("  The **Rust** language  "))
+            .unwrap();
+        assert_eq!(
+            doc.get_tag_description("rust-lang").unwrap().as_deref(),
+            Some("The **Rust** language")
+        );
+        assert_eq!(doc.get_tag_descriptions().unwrap().len(), 1);
+
+        doc.set_tag_description("rust-lang", Some("   ")).unwrap();
+        assert_eq!(doc.get_tag_description("rust-lang").unwrap(), None);
+        assert!(doc.get_tag_descriptions().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_tag_visibility() {
+        let mut doc = RottDocument::new();
+        doc.set_tag_visibility("work", None).unwrap();
+        assert!(doc.get_tag_visibility().unwrap().is_empty());
+
+        doc.set_tag_visibility("Work Stuff", Some(TagVisibility::Private))
+            .unwrap();
+        doc.set_tag_visibility("blog", Some(TagVisibility::Public))
+            .unwrap();
+        let marks = doc.get_tag_visibility().unwrap();
+        assert_eq!(marks.get("work-stuff"), Some(&TagVisibility::Private));
+        assert_eq!(marks.get("blog"), Some(&TagVisibility::Public));
+
+        doc.set_tag_visibility("work-stuff", None).unwrap();
+        assert_eq!(doc.get_tag_visibility().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_archives() {
+        let mut doc = RottDocument::new();
+        assert!(doc.get_archives().unwrap().is_empty());
+
+        let archive = DocumentId::new();
+        doc.set_archive(2019, &archive).unwrap();
+        let archives_id = doc.doc.get(ROOT, keys::ARCHIVES).unwrap().unwrap().1;
+        doc.doc.put(&archives_id, "someday", "not a year").unwrap();
+        doc.doc.put(&archives_id, "2020", "not an id").unwrap();
+
+        assert_eq!(
+            doc.get_archives().unwrap().into_iter().collect::<Vec<_>>(),
+            vec![(2019, archive)]
+        );
+    }
+
     #[test]
     fn test_get_all_tags() {
         let mut doc = RottDocument::new();
@@ -799,6 +2677,46 @@ mod tests {
         assert_eq!(tags, vec!["idea", "rust", "web"]);
     }
 
+    #[test]
+    fn test_schema_version() {
+        let mut doc = RottDocument::new();
+        assert_eq!(doc.schema_version().unwrap(), CURRENT_SCHEMA_VERSION);
+
+        doc.doc.put(ROOT, keys::SCHEMA_VERSION, 2u64).unwrap();
+        assert_eq!(doc.schema_version().unwrap(), 2);
+        doc.set_schema_version().unwrap();
+        assert_eq!(doc.schema_version().unwrap(), CURRENT_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_tags_ignore_case() {
+        let mut doc = RottDocument::new();
+
+        // Written before normalization
+        let mut link1 = Link::new("https://example.com");
+        link1.set_tags(vec!["Rust".into(), "rust".into(), "Web Dev".into()]);
+        let mut link2 = Link::new("https://example2.com");
+        link2.set_tags(vec!["rust".into()]);
+        doc.add_link(&link1).unwrap();
+        doc.add_link(&link2).unwrap();
+
+        assert_eq!(doc.get_all_tags().unwrap(), vec!["rust", "web-dev"]);
+        assert_eq!(doc.get_links_by_tag("RUST").unwrap().len(), 2);
+        assert_eq!(
+            doc.get_tags_with_counts().unwrap(),
+            vec![("rust".to_string(), 2), ("web-dev".to_string(), 1)]
+        );
+
+        assert_eq!(doc.normalize_tags().unwrap(), 1);
+        let migrated = doc.get_link(link1.id).unwrap().unwrap();
+        assert_eq!(migrated.tags, vec!["rust", "web-dev"]);
+        assert_eq!(
+            migrated.updated_at.timestamp_millis(),
+            link1.updated_at.timestamp_millis()
+        );
+        assert_eq!(doc.normalize_tags().unwrap(), 0);
+    }
+
     #[test]
     fn test_save_and_load() {
         let mut doc = RottDocument::new();
@@ -817,6 +2735,23 @@ mod tests {
         assert_eq!(links[0].notes.len(), 1);
     }
 
+    #[test]
+    fn test_load_chunks() {
+        let mut doc = RottDocument::new();
+        doc.add_link(&Link::new("https://example.com/one")).unwrap();
+        let snapshot = doc.save();
+        let heads = doc.heads();
+
+        doc.add_link(&Link::new("https://example.com/two")).unwrap();
+        let incremental = doc.save_after(&heads);
+
+        // Order doesn't matter
+        let loaded =
+            RottDocument::load_chunks([incremental.as_slice(), snapshot.as_slice()]).unwrap();
+        assert_eq!(*loaded.id(), *doc.id());
+        assert_eq!(loaded.link_count().unwrap(), 2);
+    }
+
     #[test]
     fn test_document_id_and_url() {
         let doc = RottDocument::new();
@@ -845,6 +2780,67 @@ mod tests {
         assert_eq!(links.len(), 2);
     }
 
+    #[test]
+    fn test_open_counts_add_up_across_devices() {
+        let mut doc1 = RottDocument::new();
+        let link = Link::new("https://example.com");
+        doc1.add_link(&link).unwrap();
+        doc1.record_open(link.id, Utc::now()).unwrap();
+        let mut doc2 = doc1.fork();
+
+        doc1.record_open(link.id, Utc::now()).unwrap();
+        doc2.record_open(link.id, Utc::now()).unwrap();
+        doc2.record_open(link.id, Utc::now()).unwrap();
+        doc1.merge(&mut doc2).unwrap();
+
+        let merged = doc1.get_link(link.id).unwrap().unwrap();
+        assert_eq!(merged.open_count, 4);
+        assert!(merged.opened_at.is_some());
+
+        // Editing the link leaves the count alone
+        doc1.update_link(&merged).unwrap();
+        assert_eq!(doc1.get_link(link.id).unwrap().unwrap().open_count, 4);
+    }
+
+    #[test]
+    fn test_first_opens_on_two_devices_add_up() {
+        let mut doc1 = RottDocument::new();
+        let link = Link::new("https://example.com");
+        doc1.add_link(&link).unwrap();
+        let mut doc2 = doc1.fork();
+
+        doc1.record_open(link.id, Utc::now()).unwrap();
+        doc2.record_open(link.id, Utc::now()).unwrap();
+        doc1.merge(&mut doc2).unwrap();
+
+        assert_eq!(doc1.get_link(link.id).unwrap().unwrap().open_count, 2);
+    }
+
+    #[test]
+    fn test_read_position_syncs_past_edits() {
+        let mut doc1 = RottDocument::new();
+        let mut link = Link::new("https://example.com");
+        doc1.add_link(&link).unwrap();
+        let mut doc2 = doc1.fork();
+
+        // Read on one device while the link is edited on the other
+        let position = ReadPosition {
+            line: 120,
+            lines: 300,
+            at: Utc::now(),
+        };
+        doc2.set_read_position(link.id, &position).unwrap();
+        link.set_title("Edited");
+        doc1.update_link(&link).unwrap();
+        doc1.merge(&mut doc2).unwrap();
+
+        let merged = doc1.get_link(link.id).unwrap().unwrap();
+        assert_eq!(merged.title, "Edited");
+        let read = merged.read_position.unwrap();
+        assert_eq!((read.line, read.lines), (120, 300));
+        assert_eq!(read.at.timestamp_millis(), position.at.timestamp_millis());
+    }
+
     #[test]
     fn test_get_link_by_url_found() {
         let mut doc = RottDocument::new();
@@ -852,7 +2848,9 @@ mod tests {
         link.set_title("Rust");
         doc.add_link(&link).unwrap();
 
-        let found = doc.get_link_by_url("https://rust-lang.org").unwrap();
+        let found = doc
+            .get_link_by_url("https://rust-lang.org", TrailingSlash::Strip)
+            .unwrap();
         assert!(found.is_some());
         assert_eq!(found.unwrap().title, "Rust");
     }
@@ -862,7 +2860,9 @@ mod tests {
         let mut doc = RottDocument::new();
         doc.add_link(&Link::new("https://rust-lang.org")).unwrap();
 
-        let found = doc.get_link_by_url("https://not-exists.com").unwrap();
+        let found = doc
+            .get_link_by_url("https://not-exists.com", TrailingSlash::Strip)
+            .unwrap();
         assert!(found.is_none());
     }
 
@@ -873,8 +2873,43 @@ mod tests {
             .unwrap();
 
         // Should match with different casing on domain
-        let found = doc.get_link_by_url("https://example.com/path/").unwrap();
+        let found = doc
+            .get_link_by_url("https://example.com/path/", TrailingSlash::Strip)
+            .unwrap();
+        assert!(found.is_some());
+        // And without the trailing slash or the tracking parameters
+        let found = doc
+            .get_link_by_url(
+                "https://example.com/path?utm_source=rss",
+                TrailingSlash::Strip,
+            )
+            .unwrap();
         assert!(found.is_some());
+        let found = doc
+            .get_link_by_url("https://example.com/path", TrailingSlash::Keep)
+            .unwrap();
+        assert!(found.is_none());
+    }
+
+    #[test]
+    fn test_get_link_by_canonical_url() {
+        let mut doc = RottDocument::new();
+        let mut link = Link::new("https://amp.example.com/story?utm_source=feed");
+        link.set_canonical_url(Some("https://example.com/story".to_string()));
+        doc.add_link(&link).unwrap();
+
+        let retrieved = doc.get_link(link.id).unwrap().unwrap();
+        assert_eq!(
+            retrieved.canonical_url.as_deref(),
+            Some("https://example.com/story")
+        );
+        assert_eq!(retrieved.display_url(), "https://example.com/story");
+        assert_eq!(retrieved.url, link.url);
+
+        let found = doc
+            .get_link_by_url("https://Example.com/story/", TrailingSlash::Strip)
+            .unwrap();
+        assert_eq!(found.map(|l| l.id), Some(link.id));
     }
 
     #[test]
@@ -911,6 +2946,43 @@ mod tests {
         assert_eq!(results.len(), 1);
     }
 
+    #[test]
+    fn test_search_links_by_author() {
+        let mut doc = RottDocument::new();
+        let mut link = Link::new("https://example.com/a");
+        link.set_title("Rust in production");
+        link.set_author(vec!["Jane Doe".to_string()]);
+        doc.add_link(&link).unwrap();
+        let mut other = Link::new("https://example.com/b");
+        other.set_title("Rust at scale");
+        other.set_author(vec!["John Smith".to_string()]);
+        doc.add_link(&other).unwrap();
+
+        let results = doc.search_links("author:jane rust").unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, link.id);
+        assert_eq!(doc.search_links("author:\"john smith\"").unwrap().len(), 1);
+        assert!(doc.search_links("author:jane scale").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_authors_with_counts() {
+        let mut doc = RottDocument::new();
+        let mut link1 = Link::new("https://example.com/a");
+        link1.set_author(vec!["Jane Doe".to_string(), "John Smith".to_string()]);
+        let mut link2 = Link::new("https://example.com/b");
+        link2.set_author(vec!["jane doe".to_string()]);
+        doc.add_link(&link1).unwrap();
+        doc.add_link(&link2).unwrap();
+
+        assert_eq!(
+            doc.get_authors_with_counts().unwrap(),
+            vec![("Jane Doe".to_string(), 2), ("John Smith".to_string(), 1)]
+        );
+        assert_eq!(doc.get_links_by_author("JANE DOE").unwrap().len(), 2);
+        assert!(doc.get_links_by_author("Jane").unwrap().is_empty());
+    }
+
     #[test]
     fn test_search_links_no_results() {
         let mut doc = RottDocument::new();
@@ -931,6 +3003,20 @@ mod tests {
         assert_eq!(results.len(), 1);
     }
 
+    #[test]
+    fn test_search_notes() {
+        let mut doc = RottDocument::new();
+        let link = Link::new("https://example.com");
+        doc.add_link(&link).unwrap();
+        let note = Note::new("Remember the lifetime elision rules");
+        doc.add_note_to_link(link.id, &note).unwrap();
+
+        let hits = doc.search("elision").unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].note_id, Some(note.id));
+        assert_eq!(doc.search("example").unwrap()[0].note_id, None);
+    }
+
     #[test]
     fn test_get_tags_with_counts() {
         let mut doc = RottDocument::new();
@@ -980,19 +3066,105 @@ mod tests {
     }
 
     #[test]
-    fn test_normalize_url() {
-        assert_eq!(
-            super::normalize_url("https://Example.COM/path"),
-            "https://example.com/path"
-        );
-        assert_eq!(
-            super::normalize_url("https://example.com/Path/Case"),
-            "https://example.com/Path/Case"
-        );
-        // Trailing slash removed when there's a path
+    fn test_malformed_records_read_leniently() {
+        let mut doc = RottDocument::new();
+        let mut link = Link::new("https://example.com");
+        link.set_title("Example");
+        let note = Note::new("A note");
+        link.add_note(note.clone());
+        let good = Link::new("https://good.example.com");
+        doc.add_link(&link).unwrap();
+        doc.add_link(&good).unwrap();
+
+        // A wrongly typed title, a missing timestamp, a note without a body,
+        // and a link under a key that isn't a UUID
+        let links_id = doc.doc.get(ROOT, keys::LINKS).unwrap().unwrap().1;
+        let link_obj = doc
+            .doc
+            .get(&links_id, link.id.to_string())
+            .unwrap()
+            .unwrap()
+            .1;
+        doc.doc.put(&link_obj, keys::TITLE, 42).unwrap();
+        doc.doc.delete(&link_obj, keys::CREATED_AT).unwrap();
+        let notes_id = doc.doc.get(&link_obj, keys::NOTES).unwrap().unwrap().1;
+        let note_obj = doc
+            .doc
+            .get(&notes_id, note.id.to_string())
+            .unwrap()
+            .unwrap()
+            .1;
+        doc.doc.delete(&note_obj, keys::BODY).unwrap();
+        doc.doc
+            .put_object(&links_id, "not-a-uuid", ObjType::Map)
+            .unwrap();
+
+        let (links, warnings) = doc.get_all_links_with_warnings().unwrap();
+        assert_eq!(links.len(), 2);
+        let read = links.iter().find(|l| l.id == link.id).unwrap();
+        assert_eq!(read.title, "https://example.com");
+        assert_eq!(read.created_at, DateTime::<Utc>::default());
+        assert_eq!(read.notes.len(), 1);
+        assert_eq!(read.notes[0].body, "");
+
+        assert_eq!(warnings.len(), 4);
+        assert!(warnings.iter().any(|w| w.link_id.is_none()));
+        assert!(warnings
+            .iter()
+            .any(|w| w.note_id == Some(note.id) && w.problem.contains(keys::BODY)));
+        assert!(warnings
+            .iter()
+            .all(|w| w.link_id.is_none() || w.link_id == Some(link.id)));
+
+        // Other reads still work
+        assert_eq!(doc.get_all_links().unwrap().len(), 2);
+        assert!(doc.get_link(link.id).unwrap().is_some());
         assert_eq!(
-            super::normalize_url("https://example.com/path/"),
-            "https://example.com/path"
+            doc.get_notes_page(link.id, 0, 10).unwrap().unwrap().total,
+            1
         );
     }
+
+    #[test]
+    fn test_update_keeps_unreadable_fields() {
+        let mut doc = RottDocument::new();
+        let mut link = Link::new("https://example.com");
+        link.add_tag("rust");
+        doc.add_link(&link).unwrap();
+
+        // A timestamp stored as text (as a newer client might) and a note
+        // that isn't a record
+        let links_id = doc.doc.get(ROOT, keys::LINKS).unwrap().unwrap().1;
+        let link_obj = doc
+            .doc
+            .get(&links_id, link.id.to_string())
+            .unwrap()
+            .unwrap()
+            .1;
+        doc.doc
+            .put(&link_obj, keys::CREATED_AT, "2024-01-01T00:00:00Z")
+            .unwrap();
+        let notes_id = doc.doc.get(&link_obj, keys::NOTES).unwrap().unwrap().1;
+        let damaged = Uuid::new_v4();
+        doc.doc.put(&notes_id, damaged.to_string(), 7).unwrap();
+
+        let mut read = doc.get_link(link.id).unwrap().unwrap();
+        assert_eq!(read.created_at, DateTime::<Utc>::default());
+        read.set_title("Edited");
+        read.add_note(Note::new("A new note"));
+        doc.update_link(&read).unwrap();
+
+        let created_at = doc.doc.get(&link_obj, keys::CREATED_AT).unwrap().unwrap().0;
+        assert_eq!(created_at.to_str(), Some("2024-01-01T00:00:00Z"));
+        assert!(doc
+            .doc
+            .get(&notes_id, damaged.to_string())
+            .unwrap()
+            .is_some());
+
+        let updated = doc.get_link(link.id).unwrap().unwrap();
+        assert_eq!(updated.title, "Edited");
+        assert_eq!(updated.tags, vec!["rust"]);
+        assert_eq!(updated.notes.len(), 1);
+    }
 }
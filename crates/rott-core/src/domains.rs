@@ -0,0 +1,163 @@
+//! Domain rules for link capture
+//!
+//! Lets users keep certain domains out of their collection entirely
+//! (internal tools, localhost, banking sites) and automatically tag
+//! links from others.
+//!
+//! A rule for `example.com` matches `example.com` and any subdomain
+//! such as `docs.example.com`.
+
+use std::collections::BTreeMap;
+
+use crate::config::Config;
+
+/// Blocklist and auto-tag rules applied when links are captured
+#[derive(Debug, Clone, Default)]
+pub struct DomainRules {
+    /// Domains that are never saved
+    blocked: Vec<String>,
+    /// Tags automatically added to links from a domain
+    tags: BTreeMap<String, Vec<String>>,
+}
+
+impl DomainRules {
+    /// Build rules from configuration
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            blocked: config
+                .blocked_domains
+                .iter()
+                .map(|d| d.trim().to_lowercase())
+                .filter(|d| !d.is_empty())
+                .collect(),
+            tags: config
+                .domain_tags
+                .iter()
+                .map(|(d, tags)| (d.trim().to_lowercase(), tags.clone()))
+                .collect(),
+        }
+    }
+
+    /// Get the blocklist entry matching a URL, if any
+    pub fn blocking_rule(&self, url: &str) -> Option<&str> {
+        let host = extract_domain(url)?;
+        self.blocked
+            .iter()
+            .find(|rule| domain_matches(&host, rule))
+            .map(|s| s.as_str())
+    }
+
+    /// Check whether a URL's domain is blocked
+    pub fn is_blocked(&self, url: &str) -> bool {
+        self.blocking_rule(url).is_some()
+    }
+
+    /// Get the tags to automatically add to a URL
+    pub fn tags_for(&self, url: &str) -> Vec<String> {
+        let Some(host) = extract_domain(url) else {
+            return Vec::new();
+        };
+
+        let mut result = Vec::new();
+        for (rule, tags) in &self.tags {
+            if domain_matches(&host, rule) {
+                for tag in tags {
+                    if !result.contains(tag) {
+                        result.push(tag.clone());
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Extract the lowercased host (without port or credentials) from a URL
+pub fn extract_domain(url: &str) -> Option<String> {
+    let rest = url.trim().split_once("://").map(|(_, r)| r)?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?;
+
+    // Strip port, taking care not to split IPv6 literals
+    let host = if let Some(stripped) = host.strip_prefix('[') {
+        stripped.split(']').next()?
+    } else {
+        host.split(':').next()?
+    };
+
+    if host.is_empty() {
+        None
+    } else {
+        Some(host.to_lowercase())
+    }
+}
+
+/// Check whether a host matches a domain rule (exact or subdomain)
+pub(crate) fn domain_matches(host: &str, rule: &str) -> bool {
+    host == rule || host.ends_with(&format!(".{}", rule))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(blocked: &[&str], tags: &[(&str, &str)]) -> DomainRules {
+        let config = Config {
+            blocked_domains: blocked.iter().map(|s| s.to_string()).collect(),
+            domain_tags: tags
+                .iter()
+                .map(|(d, t)| (d.to_string(), t.split(',').map(String::from).collect()))
+                .collect(),
+            ..Config::default()
+        };
+        DomainRules::from_config(&config)
+    }
+
+    #[test]
+    fn test_extract_domain() {
+        assert_eq!(
+            extract_domain("https://Example.com/path"),
+            Some("example.com".to_string())
+        );
+        assert_eq!(
+            extract_domain("http://localhost:8080/x"),
+            Some("localhost".to_string())
+        );
+        assert_eq!(
+            extract_domain("https://user:pw@host.io?q=1"),
+            Some("host.io".to_string())
+        );
+        assert_eq!(
+            extract_domain("http://[::1]:3000/"),
+            Some("::1".to_string())
+        );
+        assert_eq!(extract_domain("not a url"), None);
+    }
+
+    #[test]
+    fn test_blocked_matches_subdomains() {
+        let rules = rules(&["bank.com", "localhost"], &[]);
+        assert!(rules.is_blocked("https://bank.com/login"));
+        assert!(rules.is_blocked("https://online.bank.com/"));
+        assert!(rules.is_blocked("http://localhost:3000"));
+        assert!(!rules.is_blocked("https://notbank.com/"));
+        assert_eq!(
+            rules.blocking_rule("https://online.bank.com"),
+            Some("bank.com")
+        );
+    }
+
+    #[test]
+    fn test_tags_for() {
+        let rules = rules(
+            &[],
+            &[("github.com", "code"), ("gist.github.com", "code,snippet")],
+        );
+        assert_eq!(rules.tags_for("https://github.com/rust-lang"), vec!["code"]);
+        assert_eq!(
+            rules.tags_for("https://gist.github.com/abc"),
+            vec!["code", "snippet"]
+        );
+        assert!(rules.tags_for("https://example.com").is_empty());
+    }
+}
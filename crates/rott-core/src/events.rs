@@ -0,0 +1,425 @@
+//! Event log and projections
+//!
+//! The document only holds the current state of each link. Its history is
+//! kept as Automerge changes, and [`RottDocument::replay_events`] turns
+//! those into a stream of [`StoreEvent`]s: a link or note saved, or
+//! deleted, in the order the changes were made. A change that rewrites a
+//! link without altering it produces no event.
+//!
+//! A [`Projection`] is a view built from that stream, for data the current
+//! state can't answer quickly or at all (what was deleted and when, which
+//! notes mention which links). Each projection consumes the same events,
+//! so any of them can be rebuilt on its own (`rott maintenance reindex
+//! <projection>`); rebuilt projections are saved as JSON under
+//! `projections/` in the data directory, for other tools to read. Tools
+//! that build their own views (a search index, embeddings) can read the
+//! event stream itself with `rott maintenance events`.
+//!
+//! Projections are rebuilt on request, not kept up to date on every write.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::document::RottDocument;
+use crate::models::{Link, Note};
+use crate::urls::url_strs;
+
+/// Directory for rebuilt projections, in the data directory
+pub const PROJECTION_DIR: &str = "projections";
+
+/// One thing that happened to the collection
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StoreEvent {
+    /// Hash of the Automerge change it came from
+    pub change: String,
+    /// When the change was made (if the device recorded it)
+    pub at: Option<DateTime<Utc>>,
+    /// Name of the device that made the change (if it recorded one)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device: Option<String>,
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+/// What an event did
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum EventKind {
+    /// A link was saved, or changed (its notes included)
+    LinkUpserted { link: Link },
+    /// A link was deleted
+    LinkDeleted { link_id: Uuid },
+    /// A note was added or changed
+    NoteUpserted { link_id: Uuid, note: Note },
+    /// A note was removed from its link
+    NoteDeleted { link_id: Uuid, note_id: Uuid },
+}
+
+/// A view of the collection built from its events
+pub trait Projection {
+    /// Name used by `rott maintenance reindex` and for its file
+    fn name(&self) -> &'static str;
+
+    /// One-line description, for listing
+    fn description(&self) -> &'static str;
+
+    /// Update the view with the next event
+    fn apply(&mut self, event: &StoreEvent);
+
+    /// The view, as saved
+    fn to_json(&self) -> serde_json::Value;
+
+    /// One-line summary of the view, for output
+    fn summary(&self) -> String;
+}
+
+/// A fresh instance of every projection
+pub fn projections() -> Vec<Box<dyn Projection>> {
+    vec![Box::<Activity>::default(), Box::<Backlinks>::default()]
+}
+
+/// Fresh instances of the named projection, or of all of them for `all`
+pub fn projections_named(name: &str) -> Result<Vec<Box<dyn Projection>>> {
+    let all = projections();
+    if name == "all" {
+        return Ok(all);
+    }
+    let names: Vec<&str> = all.iter().map(|p| p.name()).collect();
+    match all.into_iter().find(|p| p.name() == name) {
+        Some(projection) => Ok(vec![projection]),
+        None => bail!(
+            "Unknown projection '{}' (use one of: {}, all)",
+            name,
+            names.join(", ")
+        ),
+    }
+}
+
+/// Where a projection is saved
+pub fn projection_path(data_dir: &Path, name: &str) -> PathBuf {
+    data_dir.join(PROJECTION_DIR).join(format!("{}.json", name))
+}
+
+/// Save a rebuilt projection
+///
+/// `heads` are the document heads it was built to, so a reader can tell
+/// whether it's behind the document.
+pub fn save_projection(
+    data_dir: &Path,
+    projection: &dyn Projection,
+    heads: &[String],
+    events: usize,
+) -> Result<PathBuf> {
+    let path = projection_path(data_dir, projection.name());
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let saved = serde_json::json!({
+        "projection": projection.name(),
+        "rebuilt_at": Utc::now(),
+        "heads": heads,
+        "events": events,
+        "data": projection.to_json(),
+    });
+    fs::write(&path, serde_json::to_vec_pretty(&saved)?)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Replay a document's events into projections
+///
+/// Returns the number of events replayed.
+pub fn rebuild(doc: &mut RottDocument, projections: &mut [Box<dyn Projection>]) -> Result<usize> {
+    let mut events = 0;
+    doc.replay_events(|event| {
+        events += 1;
+        for projection in projections.iter_mut() {
+            projection.apply(&event);
+        }
+    })
+    .context("Failed to replay document history")?;
+    Ok(events)
+}
+
+// ==================== Activity ====================
+
+/// Counts for one month
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MonthActivity {
+    pub links_added: usize,
+    pub links_changed: usize,
+    pub links_deleted: usize,
+    pub notes_added: usize,
+    pub notes_changed: usize,
+    pub notes_deleted: usize,
+}
+
+/// What was added, changed and deleted each month (by when the change was
+/// made), including links that no longer exist
+#[derive(Debug, Default)]
+pub struct Activity {
+    months: BTreeMap<String, MonthActivity>,
+    seen_links: BTreeSet<Uuid>,
+    seen_notes: BTreeSet<Uuid>,
+}
+
+impl Activity {
+    /// Counts by month (`YYYY-MM`; `unknown` for changes without a time)
+    pub fn months(&self) -> &BTreeMap<String, MonthActivity> {
+        &self.months
+    }
+}
+
+impl Projection for Activity {
+    fn name(&self) -> &'static str {
+        "activity"
+    }
+
+    fn description(&self) -> &'static str {
+        "Links and notes added, changed and deleted per month"
+    }
+
+    fn apply(&mut self, event: &StoreEvent) {
+        let month = event
+            .at
+            .map(|at| at.format("%Y-%m").to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        let counts = self.months.entry(month).or_default();
+        match &event.kind {
+            EventKind::LinkUpserted { link } => {
+                if self.seen_links.insert(link.id) {
+                    counts.links_added += 1;
+                } else {
+                    counts.links_changed += 1;
+                }
+            }
+            EventKind::LinkDeleted { .. } => counts.links_deleted += 1,
+            EventKind::NoteUpserted { note, .. } => {
+                if self.seen_notes.insert(note.id) {
+                    counts.notes_added += 1;
+                } else {
+                    counts.notes_changed += 1;
+                }
+            }
+            EventKind::NoteDeleted { .. } => counts.notes_deleted += 1,
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!(self.months)
+    }
+
+    fn summary(&self) -> String {
+        let deleted: usize = self.months.values().map(|m| m.links_deleted).sum();
+        format!(
+            "{} month(s), {} link(s) added, {} deleted",
+            self.months.len(),
+            self.seen_links.len(),
+            deleted
+        )
+    }
+}
+
+// ==================== Backlinks ====================
+
+/// A note that mentions a saved link
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Mention {
+    pub link_id: Uuid,
+    pub note_id: Uuid,
+}
+
+/// Which notes mention the URL of another saved link
+#[derive(Debug, Default)]
+pub struct Backlinks {
+    /// Link IDs by URL
+    urls: HashMap<String, Uuid>,
+    /// URLs of each link, to forget on delete
+    link_urls: HashMap<Uuid, String>,
+    /// URLs mentioned by each note
+    mentions: BTreeMap<Mention, Vec<String>>,
+}
+
+impl Backlinks {
+    /// Notes mentioning each link, by the mentioned link's ID
+    pub fn backlinks(&self) -> BTreeMap<Uuid, Vec<Mention>> {
+        let mut backlinks: BTreeMap<Uuid, Vec<Mention>> = BTreeMap::new();
+        for (mention, urls) in &self.mentions {
+            let targets: BTreeSet<Uuid> = urls
+                .iter()
+                .filter_map(|url| self.urls.get(url).copied())
+                .filter(|target| *target != mention.link_id)
+                .collect();
+            for target in targets {
+                backlinks.entry(target).or_default().push(mention.clone());
+            }
+        }
+        backlinks
+    }
+
+    fn note_mentions(&mut self, link_id: Uuid, note: &Note) {
+        let mention = Mention {
+            link_id,
+            note_id: note.id,
+        };
+        let urls: Vec<String> = url_strs(&note.body)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        if urls.is_empty() {
+            self.mentions.remove(&mention);
+        } else {
+            self.mentions.insert(mention, urls);
+        }
+    }
+}
+
+impl Projection for Backlinks {
+    fn name(&self) -> &'static str {
+        "backlinks"
+    }
+
+    fn description(&self) -> &'static str {
+        "Notes that mention other saved links"
+    }
+
+    fn apply(&mut self, event: &StoreEvent) {
+        match &event.kind {
+            EventKind::LinkUpserted { link } => {
+                if let Some(old) = self.link_urls.insert(link.id, link.url.clone()) {
+                    if old != link.url && self.urls.get(&old) == Some(&link.id) {
+                        self.urls.remove(&old);
+                    }
+                }
+                self.urls.insert(link.url.clone(), link.id);
+            }
+            EventKind::LinkDeleted { link_id } => {
+                if let Some(url) = self.link_urls.remove(link_id) {
+                    if self.urls.get(&url) == Some(link_id) {
+                        self.urls.remove(&url);
+                    }
+                }
+                self.mentions
+                    .retain(|mention, _| mention.link_id != *link_id);
+            }
+            EventKind::NoteUpserted { link_id, note } => self.note_mentions(*link_id, note),
+            EventKind::NoteDeleted { link_id, note_id } => {
+                self.mentions.remove(&Mention {
+                    link_id: *link_id,
+                    note_id: *note_id,
+                });
+            }
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!(self.backlinks())
+    }
+
+    fn summary(&self) -> String {
+        let backlinks = self.backlinks();
+        format!(
+            "{} link(s) mentioned by {} note(s)",
+            backlinks.len(),
+            backlinks
+                .values()
+                .flatten()
+                .map(|m| m.note_id)
+                .collect::<BTreeSet<_>>()
+                .len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(doc: &mut RottDocument) -> Vec<&'static str> {
+        let mut kinds = Vec::new();
+        doc.replay_events(|event| {
+            kinds.push(match event.kind {
+                EventKind::LinkUpserted { .. } => "link",
+                EventKind::LinkDeleted { .. } => "link deleted",
+                EventKind::NoteUpserted { .. } => "note",
+                EventKind::NoteDeleted { .. } => "note deleted",
+            })
+        })
+        .unwrap();
+        kinds
+    }
+
+    #[test]
+    fn test_replay_events() {
+        let mut doc = RottDocument::new();
+        let mut link = Link::new("https://example.com");
+        doc.add_link(&link).unwrap();
+        doc.inner_mut().commit();
+
+        let note = Note::new("First thoughts");
+        doc.add_note_to_link(link.id, &note).unwrap();
+        doc.inner_mut().commit();
+
+        link.set_title("Example");
+        link.notes.push(note.clone());
+        doc.update_link(&link).unwrap();
+        doc.inner_mut().commit();
+
+        doc.remove_note_from_link(link.id, note.id).unwrap();
+        doc.inner_mut().commit();
+        doc.delete_link(link.id).unwrap();
+        doc.inner_mut().commit();
+
+        assert_eq!(
+            kinds(&mut doc),
+            vec![
+                "link",
+                "link",
+                "note",
+                "link",
+                "link",
+                "note deleted",
+                "link deleted"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_projections() {
+        let mut doc = RottDocument::new();
+        let target = Link::new("https://example.com/paper");
+        let mut citing = Link::new("https://example.com/blog");
+        citing.add_note(Note::new("Builds on https://example.com/paper"));
+        let gone = Link::new("https://example.com/gone");
+        doc.add_link(&target).unwrap();
+        doc.add_link(&citing).unwrap();
+        doc.add_link(&gone).unwrap();
+        doc.inner_mut().commit();
+        doc.delete_link(gone.id).unwrap();
+        doc.inner_mut().commit();
+
+        let mut projections = projections();
+        let events = rebuild(&mut doc, &mut projections).unwrap();
+        assert!(events >= 4);
+
+        let backlinks = projections[1].to_json();
+        let mentions = backlinks[target.id.to_string()].as_array().unwrap();
+        assert_eq!(mentions.len(), 1);
+        assert_eq!(mentions[0]["link_id"], citing.id.to_string());
+
+        let months = projections[0].to_json();
+        let month = months.as_object().unwrap().values().next().unwrap();
+        assert_eq!(month["links_added"], 3);
+        assert_eq!(month["links_deleted"], 1);
+        assert_eq!(month["notes_added"], 1);
+
+        assert!(projections_named("all").unwrap().len() == 2);
+        assert!(projections_named("nope").is_err());
+    }
+}
@@ -0,0 +1,152 @@
+//! Site favicons
+//!
+//! A site's favicon is fetched when a link to it is saved and kept in
+//! `cache/favicons` in the data directory, one entry per domain (see
+//! [`FaviconCache`]). The web viewer shows the icons. Terminals mostly
+//! can't draw images, so the TUI shows a site's initials instead, in a
+//! color picked from its domain (see [`initials`] and [`domain_color`]).
+
+use anyhow::Result;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::metadata_cache::MetadataCache;
+
+/// Largest favicon kept, in bytes; bigger ones are skipped
+pub const MAX_FAVICON_BYTES: usize = 64 * 1024;
+
+/// How long a cached favicon is used before it's fetched again
+const FAVICON_TTL_DAYS: i64 = 30;
+
+/// Number of colors [`domain_color`] picks from
+pub const DOMAIN_COLORS: usize = 6;
+
+/// A fetched favicon
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Favicon {
+    /// Image type, such as `image/png`
+    pub content_type: String,
+    /// The image, base64-encoded
+    pub data: String,
+}
+
+impl Favicon {
+    /// A favicon from the fetched image
+    pub fn new(content_type: impl Into<String>, image: &[u8]) -> Self {
+        Self {
+            content_type: content_type.into(),
+            data: STANDARD.encode(image),
+        }
+    }
+
+    /// The image as a `data:` URI, for `<img src>`
+    pub fn data_uri(&self) -> String {
+        format!("data:{};base64,{}", self.content_type, self.data)
+    }
+}
+
+/// Cache of favicons, keyed by domain
+#[derive(Debug, Clone)]
+pub struct FaviconCache {
+    cache: MetadataCache,
+}
+
+impl FaviconCache {
+    /// The favicon cache in a configuration's data directory
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            cache: MetadataCache::new(config.favicon_cache_dir(), Duration::days(FAVICON_TTL_DAYS)),
+        }
+    }
+
+    /// The cached favicon of `domain`, unless it's missing or too old
+    pub fn get(&self, domain: &str, now: DateTime<Utc>) -> Option<Favicon> {
+        self.cache.get(domain, now)
+    }
+
+    /// Store the favicon fetched for `domain`
+    pub fn put(&self, domain: &str, favicon: &Favicon, now: DateTime<Utc>) -> Result<()> {
+        self.cache.put(domain, favicon, now)
+    }
+}
+
+/// Up to two letters standing in for a site's favicon
+///
+/// Taken from the domain's name without `www.` and the top-level domain:
+/// the first letters of its first two words if it has dashes
+/// (`rust-lang.org` is "RL"), otherwise its first two letters
+/// (`github.com` is "GI").
+pub fn initials(domain: &str) -> String {
+    let host = domain.strip_prefix("www.").unwrap_or(domain);
+    let name = match host.rsplit_once('.') {
+        Some((name, _)) => name.rsplit('.').next().unwrap_or(name),
+        None => host,
+    };
+    let words: Vec<&str> = name.split('-').filter(|w| !w.is_empty()).collect();
+    let letters: String = match words.as_slice() {
+        [first, second, ..] => first
+            .chars()
+            .take(1)
+            .chain(second.chars().take(1))
+            .collect(),
+        _ => name
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .take(2)
+            .collect(),
+    };
+    letters.to_uppercase()
+}
+
+/// A color for a domain's initials, as an index below [`DOMAIN_COLORS`]
+///
+/// The same domain always gets the same color.
+pub fn domain_color(domain: &str) -> usize {
+    // FNV-1a: stable across runs and platforms, unlike the std hasher
+    let hash = domain.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+    (hash % DOMAIN_COLORS as u64) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_initials() {
+        assert_eq!(initials("github.com"), "GI");
+        assert_eq!(initials("www.rust-lang.org"), "RL");
+        assert_eq!(initials("blog.example.co"), "EX");
+        assert_eq!(initials("localhost"), "LO");
+        assert_eq!(initials("x.com"), "X");
+    }
+
+    #[test]
+    fn test_domain_color_is_stable() {
+        assert_eq!(domain_color("github.com"), domain_color("github.com"));
+        assert!(domain_color("example.com") < DOMAIN_COLORS);
+    }
+
+    #[test]
+    fn test_cache_and_data_uri() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config {
+            data_dir: temp_dir.path().to_path_buf(),
+            ..Config::default()
+        };
+        let cache = FaviconCache::from_config(&config);
+        let now = Utc::now();
+
+        let favicon = Favicon::new("image/png", b"png");
+        assert_eq!(favicon.data_uri(), "data:image/png;base64,cG5n");
+        assert_eq!(cache.get("example.com", now), None);
+        cache.put("example.com", &favicon, now).unwrap();
+        assert_eq!(cache.get("example.com", now), Some(favicon));
+        assert_eq!(cache.get("example.com", now + Duration::days(31)), None);
+    }
+}
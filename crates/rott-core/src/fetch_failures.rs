@@ -0,0 +1,152 @@
+//! Metadata fetches that failed, in whole or in part
+//!
+//! Fetching a page's metadata can fail outright (the site was down) or in
+//! part (the page loaded, but had no description). Which parts failed for
+//! each link, and why, is kept in the collection's `fetch_failures.json`,
+//! so `rott link refresh --failed-only` can try just those links again
+//! later. A link's entry is dropped once a fetch gets everything.
+//!
+//! The record is local to the device, like the metadata cache: another
+//! device may well have fetched the page fine.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::Config;
+
+/// What failed when a link's metadata was last fetched
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FetchFailure {
+    /// The link's URL at the time
+    pub url: String,
+    /// The parts that failed (`page` when nothing could be fetched), each
+    /// with why
+    pub failed: BTreeMap<String, String>,
+    /// When the fetch was made
+    pub at: DateTime<Utc>,
+}
+
+/// The failed fetches of a collection's links
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FetchFailures {
+    links: BTreeMap<Uuid, FetchFailure>,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl FetchFailures {
+    /// Load the active collection's record (empty if there isn't one yet)
+    pub fn load(config: &Config) -> Result<Self> {
+        let path = config.fetch_failures_path();
+        let mut failures = if path.exists() {
+            let json = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            serde_json::from_str(&json)
+                .with_context(|| format!("Failed to parse {}", path.display()))?
+        } else {
+            Self::default()
+        };
+        failures.path = Some(path);
+        Ok(failures)
+    }
+
+    /// Save the record
+    pub fn save(&self) -> Result<()> {
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json).context("Failed to save fetch failures")?;
+        Ok(())
+    }
+
+    /// Record the outcome of fetching a link's metadata
+    ///
+    /// With nothing in `failed`, the link's entry is removed.
+    pub fn record(
+        &mut self,
+        link_id: Uuid,
+        url: &str,
+        failed: BTreeMap<String, String>,
+        at: DateTime<Utc>,
+    ) {
+        if failed.is_empty() {
+            self.links.remove(&link_id);
+        } else {
+            self.links.insert(
+                link_id,
+                FetchFailure {
+                    url: url.to_string(),
+                    failed,
+                    at,
+                },
+            );
+        }
+    }
+
+    /// Forget a link (e.g. one that's been deleted)
+    pub fn remove(&mut self, link_id: Uuid) {
+        self.links.remove(&link_id);
+    }
+
+    /// What failed for a link, if anything
+    pub fn get(&self, link_id: Uuid) -> Option<&FetchFailure> {
+        self.links.get(&link_id)
+    }
+
+    /// Every link with a failed fetch
+    pub fn iter(&self) -> impl Iterator<Item = (Uuid, &FetchFailure)> {
+        self.links.iter().map(|(id, failure)| (*id, failure))
+    }
+
+    /// Number of links with a failed fetch
+    pub fn len(&self) -> usize {
+        self.links.len()
+    }
+
+    /// Whether no fetch has failed
+    pub fn is_empty(&self) -> bool {
+        self.links.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_and_reload() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config {
+            data_dir: temp_dir.path().to_path_buf(),
+            ..Config::default()
+        };
+        let id = Uuid::new_v4();
+        let now = Utc::now();
+
+        let mut failures = FetchFailures::load(&config).unwrap();
+        assert!(failures.is_empty());
+        let failed = BTreeMap::from([("description".to_string(), "not found".to_string())]);
+        failures.record(id, "https://example.com", failed.clone(), now);
+        failures.save().unwrap();
+
+        let mut failures = FetchFailures::load(&config).unwrap();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures.get(id).unwrap().failed, failed);
+
+        // A complete fetch clears the entry
+        failures.record(id, "https://example.com", BTreeMap::new(), now);
+        assert!(failures.get(id).is_none());
+    }
+}
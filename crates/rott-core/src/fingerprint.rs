@@ -0,0 +1,163 @@
+//! Content fingerprints for change detection
+//!
+//! A fingerprint is a 64-bit SimHash of a page's visible text. Unlike a
+//! cryptographic hash, similar texts produce fingerprints that differ in
+//! only a few bits, so small edits (a timestamp, a typo fix) can be told
+//! apart from substantial rewrites by counting differing bits.
+//!
+//! Fingerprints are stored on links as 16-character lowercase hex strings.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Number of consecutive words hashed together
+const SHINGLE_SIZE: usize = 3;
+
+/// Fingerprints differing in more than this many bits are a significant change
+pub const SIGNIFICANT_CHANGE_BITS: u32 = 8;
+
+/// A SimHash fingerprint of page content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Fingerprint(u64);
+
+impl Fingerprint {
+    /// Compute the fingerprint of some text
+    ///
+    /// Text is compared by its lowercased words, so whitespace, punctuation
+    /// and case differences don't affect the result. Returns `None` if the
+    /// text contains no words.
+    pub fn of_text(text: &str) -> Option<Self> {
+        let words: Vec<String> = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(|w| w.to_lowercase())
+            .collect();
+
+        if words.is_empty() {
+            return None;
+        }
+
+        let shingles: Vec<String> = if words.len() < SHINGLE_SIZE {
+            vec![words.join(" ")]
+        } else {
+            words.windows(SHINGLE_SIZE).map(|w| w.join(" ")).collect()
+        };
+
+        let mut weights = [0i64; 64];
+        for shingle in &shingles {
+            let hash = fnv1a(shingle.as_bytes());
+            for (bit, weight) in weights.iter_mut().enumerate() {
+                if hash & (1 << bit) != 0 {
+                    *weight += 1;
+                } else {
+                    *weight -= 1;
+                }
+            }
+        }
+
+        let mut value = 0u64;
+        for (bit, weight) in weights.iter().enumerate() {
+            if *weight > 0 {
+                value |= 1 << bit;
+            }
+        }
+        Some(Self(value))
+    }
+
+    /// Number of bits that differ between two fingerprints
+    pub fn distance(&self, other: &Fingerprint) -> u32 {
+        (self.0 ^ other.0).count_ones()
+    }
+
+    /// Check whether another fingerprint represents a significant change
+    pub fn differs_significantly(&self, other: &Fingerprint) -> bool {
+        self.distance(other) > SIGNIFICANT_CHANGE_BITS
+    }
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl FromStr for Fingerprint {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        u64::from_str_radix(s, 16).map(Self)
+    }
+}
+
+impl TryFrom<String> for Fingerprint {
+    type Error = std::num::ParseIntError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Fingerprint> for String {
+    fn from(fingerprint: Fingerprint) -> Self {
+        fingerprint.to_string()
+    }
+}
+
+/// 64-bit FNV-1a hash (stable across platforms and releases)
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn long_text(edit: impl Fn(usize, String) -> String) -> String {
+        (0..600)
+            .map(|i| edit(i, format!("w{}", (i * 7919) % 1000)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn test_fingerprint_is_stable() {
+        let fp = Fingerprint::of_text("hello world").unwrap();
+        assert_eq!(fp.to_string(), "0410d84600088803");
+        assert_eq!("0410d84600088803".parse::<Fingerprint>().unwrap(), fp);
+        assert_eq!(Fingerprint::of_text("Hello, WORLD!"), Some(fp));
+        assert_eq!(Fingerprint::of_text("  ...  "), None);
+    }
+
+    #[test]
+    fn test_small_edit_is_not_significant() {
+        let original = Fingerprint::of_text(&long_text(|_, w| w)).unwrap();
+        let edited = long_text(|i, w| if i == 300 { "changed".into() } else { w });
+        let edited = Fingerprint::of_text(&edited).unwrap();
+
+        assert!(original.distance(&edited) > 0);
+        assert!(!original.differs_significantly(&edited));
+    }
+
+    #[test]
+    fn test_rewrite_is_significant() {
+        let original = Fingerprint::of_text(&long_text(|_, w| w)).unwrap();
+        let rewritten = Fingerprint::of_text(&long_text(|i, w| {
+            if i % 2 == 0 {
+                format!("changed{}", i)
+            } else {
+                w
+            }
+        }))
+        .unwrap();
+
+        assert!(original.differs_significantly(&rewritten));
+    }
+}
@@ -0,0 +1,232 @@
+//! Fuzzy matching for the realtime filter
+//!
+//! A pattern matches text when its characters appear in the text in order,
+//! ignoring case, so `rstasync` matches "Rust async book". Matches are
+//! scored like fzf's: each matched character scores, characters at the
+//! start of a word and runs of consecutive characters score extra, and
+//! gaps between matched characters cost a little. The best-scoring
+//! alignment is found, along with the positions it matched, for
+//! highlighting.
+
+use crate::models::Link;
+
+const SCORE_MATCH: i32 = 16;
+/// Matching the first character of a word
+const BONUS_BOUNDARY: i32 = 8;
+/// Matching right after the previous matched character
+const BONUS_CONSECUTIVE: i32 = 6;
+/// Starting a gap between matched characters
+const PENALTY_GAP_START: i32 = 3;
+/// Each further character in a gap
+const PENALTY_GAP_EXTENSION: i32 = 1;
+
+/// Tags and URLs match a little worse than the same match in a title
+const PENALTY_OTHER_FIELD: i32 = 4;
+
+const IMPOSSIBLE: i32 = i32::MIN / 2;
+
+/// How well a pattern matched some text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub score: i32,
+    /// Character indices of the matched characters in the text
+    pub positions: Vec<usize>,
+}
+
+/// How well a filter matched a link
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkMatch {
+    /// Sum of each term's best match
+    pub score: i32,
+    /// Character indices of title characters matched (for highlighting)
+    pub title_positions: Vec<usize>,
+}
+
+/// Fuzzy-match `pattern` against `text`
+///
+/// Whitespace in the pattern is ignored. Returns `None` if the pattern's
+/// characters don't all appear in the text, in order.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(fold_case)
+        .collect();
+    let original: Vec<char> = text.chars().collect();
+    let text: Vec<char> = original.iter().copied().map(fold_case).collect();
+
+    if pattern.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+    if !is_subsequence(&pattern, &text) {
+        return None;
+    }
+
+    let bonus: Vec<i32> = (0..original.len())
+        .map(|j| boundary_bonus(&original, j))
+        .collect();
+
+    // scores[i][j]: best score for pattern[..=i] with pattern[i] at text[j]
+    let n = text.len();
+    let mut scores = vec![vec![IMPOSSIBLE; n]; pattern.len()];
+    for (j, &c) in text.iter().enumerate() {
+        if c == pattern[0] {
+            scores[0][j] = SCORE_MATCH + bonus[j];
+        }
+    }
+    for i in 1..pattern.len() {
+        // Best score[i - 1][k] for k <= j - 2, less the gap up to j
+        let mut gapped = IMPOSSIBLE;
+        for j in 1..n {
+            if j >= 2 {
+                gapped =
+                    (gapped - PENALTY_GAP_EXTENSION).max(scores[i - 1][j - 2] - PENALTY_GAP_START);
+            }
+            if text[j] != pattern[i] {
+                continue;
+            }
+            let best = (scores[i - 1][j - 1] + BONUS_CONSECUTIVE).max(gapped);
+            if best > IMPOSSIBLE / 2 {
+                scores[i][j] = best + SCORE_MATCH + bonus[j];
+            }
+        }
+    }
+
+    let last = pattern.len() - 1;
+    let (mut j, &score) = scores[last]
+        .iter()
+        .enumerate()
+        .max_by_key(|&(j, &score)| (score, std::cmp::Reverse(j)))?;
+    if score <= IMPOSSIBLE / 2 {
+        return None;
+    }
+
+    // Walk back through the alignment that gave the best score
+    let mut positions = vec![j];
+    for i in (1..=last).rev() {
+        let before = scores[i][j] - SCORE_MATCH - bonus[j];
+        j = if j >= 1 && scores[i - 1][j - 1] + BONUS_CONSECUTIVE == before {
+            j - 1
+        } else {
+            (0..j.saturating_sub(1))
+                .rev()
+                .find(|&k| scores[i - 1][k] - gap_penalty(j - k - 1) == before)?
+        };
+        positions.push(j);
+    }
+    positions.reverse();
+
+    Some(FuzzyMatch { score, positions })
+}
+
+/// Fuzzy-match filter terms against a link's title, URL and tags
+///
+/// Every term must match one of them; each term scores its best match.
+pub fn fuzzy_match_link(terms: &[&str], link: &Link) -> Option<LinkMatch> {
+    let mut score = 0;
+    let mut title_positions = Vec::new();
+
+    for term in terms {
+        let other = std::iter::once(link.url.as_str())
+            .chain(link.tags.iter().map(String::as_str))
+            .filter_map(|field| fuzzy_match(term, field))
+            .map(|m| m.score - PENALTY_OTHER_FIELD)
+            .max();
+        let title = fuzzy_match(term, &link.title)
+            .filter(|title| other.is_none_or(|other| title.score >= other));
+
+        match (title, other) {
+            (Some(title), _) => {
+                score += title.score;
+                title_positions.extend(title.positions);
+            }
+            (None, Some(other)) => score += other,
+            (None, None) => return None,
+        }
+    }
+
+    title_positions.sort_unstable();
+    title_positions.dedup();
+    Some(LinkMatch {
+        score,
+        title_positions,
+    })
+}
+
+fn fold_case(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+fn is_subsequence(pattern: &[char], text: &[char]) -> bool {
+    let mut text = text.iter();
+    pattern.iter().all(|p| text.any(|t| t == p))
+}
+
+/// Bonus for matching the character at `j`: the start of a word, or an
+/// uppercase letter in camelCase
+fn boundary_bonus(text: &[char], j: usize) -> i32 {
+    let Some(&prev) = j.checked_sub(1).and_then(|p| text.get(p)) else {
+        return BONUS_BOUNDARY;
+    };
+    let c = text[j];
+    if !prev.is_alphanumeric() && c.is_alphanumeric()
+        || prev.is_lowercase() && c.is_uppercase()
+        || !prev.is_numeric() && c.is_numeric()
+    {
+        BONUS_BOUNDARY
+    } else {
+        0
+    }
+}
+
+/// Cost of skipping `len` characters between matches
+fn gap_penalty(len: usize) -> i32 {
+    PENALTY_GAP_START + PENALTY_GAP_EXTENSION * (len as i32 - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_in_order_ignoring_case() {
+        let m = fuzzy_match("rstasync", "Rust async book").unwrap();
+        assert_eq!(m.positions, vec![0, 2, 3, 5, 6, 7, 8, 9]);
+        assert!(fuzzy_match("asyncrust", "Rust async book").is_none());
+        assert!(fuzzy_match("xyz", "Rust async book").is_none());
+        assert_eq!(fuzzy_match("", "anything").unwrap().score, 0);
+        assert!(fuzzy_match("rust async", "Rust async book").is_some());
+    }
+
+    #[test]
+    fn test_prefers_word_starts_and_runs() {
+        // "ab" at the start of words beats letters buried in a word
+        let m = fuzzy_match("ab", "grab a book").unwrap();
+        assert_eq!(m.positions, vec![5, 7]);
+
+        let tight = fuzzy_match("async", "async book").unwrap();
+        let loose = fuzzy_match("async", "a sync book").unwrap();
+        let scattered = fuzzy_match("async", "all systems nominal, yes? no, clearly").unwrap();
+        assert!(tight.score > loose.score);
+        assert!(loose.score > scattered.score);
+    }
+
+    #[test]
+    fn test_link_match_uses_best_field() {
+        let mut link = Link::new("https://doc.rust-lang.org/async-book");
+        link.set_title("The Async Book");
+        link.add_tag("programming");
+
+        let m = fuzzy_match_link(&["asyncbk"], &link).unwrap();
+        assert_eq!(m.title_positions, vec![4, 5, 6, 7, 8, 10, 13]);
+
+        // A term matching only the tag still counts, without highlighting
+        let m = fuzzy_match_link(&["prgm", "book"], &link).unwrap();
+        assert_eq!(m.title_positions, vec![10, 11, 12, 13]);
+
+        assert!(fuzzy_match_link(&["book", "zzz"], &link).is_none());
+    }
+}
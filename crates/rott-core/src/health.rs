@@ -0,0 +1,203 @@
+//! Link health scores
+//!
+//! Combines what's known about a link (whether the page could be fetched
+//! or has changed, how long since it was checked, edited, or opened) into
+//! a score from 0 to 100. Links scoring below [`NEEDS_ATTENTION_BELOW`] are
+//! surfaced by the "Needs Attention" filter and `rott link list --stale`,
+//! worst first.
+//!
+//! Scores depend on the current time, so they're computed when links are
+//! read rather than stored in the document.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::models::Link;
+
+/// Links scoring below this need attention
+pub const NEEDS_ATTENTION_BELOW: u8 = 75;
+
+/// Links untouched for this many days are stale
+const UNTOUCHED_DAYS: i64 = 180;
+
+/// Links should be checked for changes at least this often
+const CHECK_OVERDUE_DAYS: i64 = 90;
+
+/// Grace period before a new link counts as never opened or checked
+const GRACE_DAYS: i64 = 30;
+
+/// Something wrong with a link
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Issue {
+    /// The page couldn't be fetched at the last check
+    Unreachable,
+    /// The page changed significantly since it was saved
+    ContentChanged,
+    /// Not edited in a long time
+    Untouched,
+    /// Never opened from ROTT
+    NeverOpened,
+    /// Not checked for changes recently (or ever)
+    CheckOverdue,
+}
+
+impl Issue {
+    /// Points taken off the score
+    fn weight(self) -> u8 {
+        match self {
+            Issue::Unreachable => 50,
+            Issue::ContentChanged => 30,
+            Issue::Untouched => 20,
+            Issue::NeverOpened => 15,
+            Issue::CheckOverdue => 10,
+        }
+    }
+
+    /// Short description for listings
+    pub fn label(self) -> &'static str {
+        match self {
+            Issue::Unreachable => "unreachable",
+            Issue::ContentChanged => "content changed",
+            Issue::Untouched => "untouched",
+            Issue::NeverOpened => "never opened",
+            Issue::CheckOverdue => "check overdue",
+        }
+    }
+}
+
+/// A link's health
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Health {
+    /// 100 is healthy; lower is worse
+    pub score: u8,
+    /// What lowered the score, worst first
+    pub issues: Vec<Issue>,
+}
+
+impl Health {
+    /// Assess a link as of `now`
+    ///
+    /// Without `opens_tracked` (opens aren't being recorded), never having
+    /// been opened doesn't count against a link.
+    pub fn of(link: &Link, now: DateTime<Utc>, opens_tracked: bool) -> Self {
+        let older_than = |time: DateTime<Utc>, days: i64| now - time > Duration::days(days);
+        let settled = older_than(link.created_at, GRACE_DAYS);
+
+        let mut issues = Vec::new();
+        if link.unreachable {
+            issues.push(Issue::Unreachable);
+        }
+        if link.content_changed {
+            issues.push(Issue::ContentChanged);
+        }
+        if older_than(link.updated_at, UNTOUCHED_DAYS) {
+            issues.push(Issue::Untouched);
+        }
+        if opens_tracked && link.opened_at.is_none() && settled {
+            issues.push(Issue::NeverOpened);
+        }
+        let check_overdue = match link.content_checked_at {
+            Some(checked) => older_than(checked, CHECK_OVERDUE_DAYS),
+            None => settled,
+        };
+        if check_overdue {
+            issues.push(Issue::CheckOverdue);
+        }
+
+        let penalty: u8 = issues.iter().map(|i| i.weight()).sum::<u8>().min(100);
+        Self {
+            score: 100 - penalty,
+            issues,
+        }
+    }
+
+    /// Whether the link should be looked at
+    pub fn needs_attention(&self) -> bool {
+        self.score < NEEDS_ATTENTION_BELOW
+    }
+}
+
+/// Links that need attention, worst first
+pub fn needs_attention(
+    links: Vec<Link>,
+    now: DateTime<Utc>,
+    opens_tracked: bool,
+) -> Vec<(Link, Health)> {
+    let mut stale: Vec<(Link, Health)> = links
+        .into_iter()
+        .map(|link| {
+            let health = Health::of(&link, now, opens_tracked);
+            (link, health)
+        })
+        .filter(|(_, health)| health.needs_attention())
+        .collect();
+    stale.sort_by_key(|(link, health)| (health.score, link.updated_at));
+    stale
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link_aged(days: i64) -> Link {
+        let mut link = Link::new("https://example.com");
+        link.created_at = Utc::now() - Duration::days(days);
+        link.updated_at = link.created_at;
+        link
+    }
+
+    #[test]
+    fn test_new_link_is_healthy() {
+        let health = Health::of(&link_aged(0), Utc::now(), true);
+        assert_eq!(health.score, 100);
+        assert!(health.issues.is_empty());
+        assert!(!health.needs_attention());
+    }
+
+    #[test]
+    fn test_signals_lower_the_score() {
+        let now = Utc::now();
+
+        let mut changed = link_aged(1);
+        changed.content_changed = true;
+        let health = Health::of(&changed, now, true);
+        assert_eq!(health.issues, vec![Issue::ContentChanged]);
+        assert!(health.needs_attention());
+
+        // Old, never opened, never checked
+        let forgotten = link_aged(365);
+        let health = Health::of(&forgotten, now, true);
+        assert_eq!(
+            health.issues,
+            vec![Issue::Untouched, Issue::NeverOpened, Issue::CheckOverdue]
+        );
+        assert_eq!(health.score, 55);
+        let untracked = Health::of(&forgotten, now, false);
+        assert!(!untracked.issues.contains(&Issue::NeverOpened));
+
+        // Opened and checked recently, only untouched
+        let mut kept_up = link_aged(365);
+        kept_up.opened_at = Some(now);
+        kept_up.content_checked_at = Some(now);
+        assert!(!Health::of(&kept_up, now, true).needs_attention());
+
+        let mut dead = link_aged(365);
+        dead.unreachable = true;
+        dead.content_changed = true;
+        assert_eq!(Health::of(&dead, now, true).score, 0);
+    }
+
+    #[test]
+    fn test_needs_attention_worst_first() {
+        let now = Utc::now();
+        let mut dead = link_aged(2);
+        dead.unreachable = true;
+        let forgotten = link_aged(365);
+        let fresh = link_aged(0);
+
+        let stale = needs_attention(vec![fresh, forgotten.clone(), dead.clone()], now, true);
+        let ids: Vec<_> = stale.iter().map(|(link, _)| link.id).collect();
+        assert_eq!(ids, vec![dead.id, forgotten.id]);
+    }
+}
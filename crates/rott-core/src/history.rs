@@ -0,0 +1,296 @@
+//! Undo and redo of edits
+//!
+//! Each edit made through the [`Store`](crate::Store) (adding, editing,
+//! merging or deleting a link, changing its tags, adding, editing,
+//! removing, pinning, moving or splitting a note, importing links,
+//! archiving or unarchiving links, describing, marking or pruning tags)
+//! is recorded as the affected links and tag settings before and after
+//! it. Commands that change many links at once record a single edit.
+//! Undoing an edit writes the "before" copies back as new changes; redoing
+//! it writes the "after" copies. Undoing an archiving moves the links back
+//! out of their archives, and redoing it moves them in again. Nothing is
+//! rolled back in the Automerge history, so an undo syncs like any other
+//! edit.
+//!
+//! The history is kept in the collection's `history.json`, local to the
+//! device, so `rott undo` can undo an edit made in the TUI and the other
+//! way around. It keeps the last [`HISTORY_LIMIT`] edits, and fewer if
+//! they'd take more than [`HISTORY_MAX_BYTES`] (a bulk edit copies every
+//! link it touches). Opens and changes synced from other devices aren't
+//! recorded, nor is an edit that leaves everything as it was. An edit
+//! can't be undone (or redone) once a link or tag it touched has been
+//! changed by something else, since that change would be lost.
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::models::Link;
+use crate::visibility::TagVisibility;
+
+/// How many edits are kept for undo
+pub const HISTORY_LIMIT: usize = 50;
+
+/// Most the saved history may take, in bytes; the oldest edits are
+/// forgotten to keep under it (the latest is always kept)
+pub const HISTORY_MAX_BYTES: usize = 4 * 1024 * 1024;
+
+/// One link before and after an edit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinkChange {
+    /// The link before the edit (None if the edit added it)
+    pub before: Option<Link>,
+    /// The link after the edit (None if the edit deleted it)
+    pub after: Option<Link>,
+}
+
+impl LinkChange {
+    /// The changed link's ID
+    pub fn link_id(&self) -> Uuid {
+        self.before
+            .as_ref()
+            .or(self.after.as_ref())
+            .map(|link| link.id)
+            .expect("a change has a link on at least one side")
+    }
+}
+
+/// A tag's description and visibility mark
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagSettings {
+    pub description: Option<String>,
+    pub visibility: Option<TagVisibility>,
+}
+
+/// One tag's settings before and after an edit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagChange {
+    pub tag: String,
+    pub before: TagSettings,
+    pub after: TagSettings,
+}
+
+/// Which way an edit moved its links between the collection and its
+/// archives
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArchiveMove {
+    /// Into the archives
+    Archived,
+    /// Back out of them
+    Unarchived,
+}
+
+impl ArchiveMove {
+    /// The move that undoes this one
+    pub fn reversed(self) -> Self {
+        match self {
+            ArchiveMove::Archived => ArchiveMove::Unarchived,
+            ArchiveMove::Unarchived => ArchiveMove::Archived,
+        }
+    }
+}
+
+/// An edit that can be undone
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Edit {
+    /// What the edit did, e.g. `delete 'Example'`
+    pub label: String,
+    /// When the edit was made
+    pub at: DateTime<Utc>,
+    /// The links it changed (in the root document, for archive moves)
+    pub changes: Vec<LinkChange>,
+    /// The tag settings it changed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<TagChange>,
+    /// Whether it moved its links into or out of the archives
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive: Option<ArchiveMove>,
+}
+
+impl Edit {
+    /// Whether this is a merge of two links (`merge 'A' into 'B'`)
+    pub fn is_merge(&self) -> bool {
+        self.label.starts_with("merge '")
+    }
+}
+
+/// The edits of a collection that can be undone and redone
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EditHistory {
+    #[serde(default)]
+    undo: Vec<Edit>,
+    #[serde(default)]
+    redo: Vec<Edit>,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl EditHistory {
+    /// Load the active collection's history (empty if there isn't one yet)
+    pub fn load(config: &Config) -> Result<Self> {
+        let path = config.edit_history_path();
+        let mut history = if path.exists() {
+            let json = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            serde_json::from_str(&json)
+                .with_context(|| format!("Failed to parse {}", path.display()))?
+        } else {
+            Self::default()
+        };
+        history.path = Some(path);
+        Ok(history)
+    }
+
+    /// Save the history, first forgetting the oldest edits if it would
+    /// take more than [`HISTORY_MAX_BYTES`]
+    pub fn save(&mut self) -> Result<()> {
+        let Some(path) = self.path.clone() else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let json = self.to_json_within(HISTORY_MAX_BYTES)?;
+        fs::write(&path, json).context("Failed to save edit history")?;
+        Ok(())
+    }
+
+    /// The history as JSON, forgetting the oldest edits (undone ones
+    /// last) until it takes at most `max_bytes` or one edit is left
+    fn to_json_within(&mut self, max_bytes: usize) -> Result<Vec<u8>> {
+        let json = serde_json::to_vec(self)?;
+        let mut excess = json.len().saturating_sub(max_bytes);
+        if excess == 0 {
+            return Ok(json);
+        }
+        while excess > 0 && self.undo.len() + self.redo.len() > 1 {
+            let edit = if self.undo.is_empty() {
+                self.redo.remove(0)
+            } else {
+                self.undo.remove(0)
+            };
+            excess = excess.saturating_sub(serde_json::to_vec(&edit)?.len());
+        }
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    /// Record a new edit
+    ///
+    /// Edits that were undone can't be redone after this. The oldest edit
+    /// is forgotten once there are more than [`HISTORY_LIMIT`].
+    pub fn record(&mut self, edit: Edit) {
+        self.redo.clear();
+        self.undo.push(edit);
+        if self.undo.len() > HISTORY_LIMIT {
+            let excess = self.undo.len() - HISTORY_LIMIT;
+            self.undo.drain(..excess);
+        }
+    }
+
+    /// The edit an undo would undo
+    pub fn next_undo(&self) -> Option<&Edit> {
+        self.undo.last()
+    }
+
+    /// The edit a redo would redo
+    pub fn next_redo(&self) -> Option<&Edit> {
+        self.redo.last()
+    }
+
+    /// Move the last edit from the undo stack to the redo stack
+    pub fn undone(&mut self) -> Option<Edit> {
+        let edit = self.undo.pop()?;
+        self.redo.push(edit.clone());
+        Some(edit)
+    }
+
+    /// Move the last undone edit back to the undo stack
+    pub fn redone(&mut self) -> Option<Edit> {
+        let edit = self.redo.pop()?;
+        self.undo.push(edit.clone());
+        Some(edit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(label: &str) -> Edit {
+        Edit {
+            label: label.to_string(),
+            at: Utc::now(),
+            changes: vec![LinkChange {
+                before: None,
+                after: Some(Link::new("https://example.com")),
+            }],
+            tags: Vec::new(),
+            archive: None,
+        }
+    }
+
+    #[test]
+    fn test_undo_redo_stacks() {
+        let mut history = EditHistory::default();
+        history.record(edit("one"));
+        history.record(edit("two"));
+
+        assert_eq!(history.undone().unwrap().label, "two");
+        assert_eq!(history.next_undo().unwrap().label, "one");
+        assert_eq!(history.next_redo().unwrap().label, "two");
+        assert_eq!(history.redone().unwrap().label, "two");
+        assert!(history.next_redo().is_none());
+
+        // A new edit drops what was undone
+        history.undone();
+        history.record(edit("three"));
+        assert!(history.next_redo().is_none());
+        assert_eq!(history.next_undo().unwrap().label, "three");
+    }
+
+    #[test]
+    fn test_history_limit() {
+        let mut history = EditHistory::default();
+        for i in 0..HISTORY_LIMIT + 5 {
+            history.record(edit(&i.to_string()));
+        }
+        assert_eq!(history.undo.len(), HISTORY_LIMIT);
+        assert_eq!(history.undo[0].label, "5");
+    }
+
+    #[test]
+    fn test_history_size_limit() {
+        let mut history = EditHistory::default();
+        for i in 0..10 {
+            history.record(edit(&i.to_string()));
+        }
+        let latest: usize = history.undo[7..]
+            .iter()
+            .map(|edit| serde_json::to_vec(edit).unwrap().len())
+            .sum();
+
+        // Room for the latest three edits: the older ones are forgotten
+        let json = history.to_json_within(latest + 40).unwrap();
+        assert!(json.len() <= latest + 40);
+        assert_eq!(history.undo.len(), 3);
+        assert_eq!(history.next_undo().unwrap().label, "9");
+
+        // The latest edit is kept however big it is
+        history.to_json_within(1).unwrap();
+        assert_eq!(history.undo.len(), 1);
+        assert_eq!(history.next_undo().unwrap().label, "9");
+
+        // Edits saved before tags and archive moves were recorded still load
+        let old = r#"{"undo": [{"label": "add 'A'", "at": "2024-01-01T00:00:00Z",
+            "changes": []}]}"#;
+        let history: EditHistory = serde_json::from_str(old).unwrap();
+        assert!(history.next_undo().unwrap().tags.is_empty());
+    }
+}
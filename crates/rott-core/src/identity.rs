@@ -72,6 +72,20 @@ impl Identity {
         self.persistence.load_root_doc_id()
     }
 
+    /// Whether this device only reads the document (see [`Self::set_read_only`])
+    pub fn is_read_only(&self) -> bool {
+        self.persistence.is_read_only()
+    }
+
+    /// Make this device read-only, or writable again
+    ///
+    /// A read-only device pulls the shared document but never pushes
+    /// changes: the store refuses to change it and sync only receives.
+    /// It's kept per device and per collection, and isn't synced.
+    pub fn set_read_only(&self, read_only: bool) -> Result<()> {
+        self.persistence.set_read_only(read_only)
+    }
+
     /// Get the config file path (for display purposes)
     pub fn config_path(&self) -> PathBuf {
         Config::config_file_path()
@@ -158,8 +172,7 @@ mod tests {
             data_dir: temp_dir.path().to_path_buf(),
             sync_url: None,
             sync_enabled: false,
-            favorite_tag: None,
-            log_file: None,
+            ..Config::default()
         }
     }
 
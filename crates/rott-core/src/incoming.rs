@@ -0,0 +1,137 @@
+//! Links coming in from someone else's collection
+//!
+//! When links are merged from another person's document or backup,
+//! [`incoming_changes`] works out what would change here, one item per
+//! link, so each can be accepted or rejected before anything is saved (see
+//! `Store::accept_incoming`).
+//!
+//! Merging only ever adds: links that aren't here yet, and tags and notes
+//! missing from links that are. Incoming links are matched to local ones by
+//! ID, then by URL. Nothing local is changed or removed. Added links are
+//! checked like links saved here, since the other collection may allow
+//! what this one doesn't.
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::models::{Link, Note};
+
+/// One incoming item to accept or reject
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IncomingChange {
+    /// A link that isn't in the collection
+    Added { link: Link },
+    /// Tags and notes missing from a link that is
+    Changed {
+        link_id: Uuid,
+        title: String,
+        tags: Vec<String>,
+        notes: Vec<Note>,
+    },
+}
+
+/// What merging `incoming` into `local` would change, in incoming order
+pub fn incoming_changes(local: &[Link], incoming: &[Link]) -> Vec<IncomingChange> {
+    let mut changes = Vec::new();
+    for link in incoming {
+        let existing = local
+            .iter()
+            .find(|l| l.id == link.id)
+            .or_else(|| local.iter().find(|l| l.url == link.url));
+        let Some(existing) = existing else {
+            changes.push(IncomingChange::Added { link: link.clone() });
+            continue;
+        };
+
+        let tags: Vec<String> = link
+            .tags
+            .iter()
+            .filter(|t| !existing.has_tag(t))
+            .cloned()
+            .collect();
+        let mut notes: Vec<Note> = link
+            .notes
+            .iter()
+            .filter(|note| {
+                existing.get_note(note.id).is_none()
+                    && !existing
+                        .notes
+                        .iter()
+                        .any(|n| n.body.trim() == note.body.trim())
+            })
+            .cloned()
+            .collect();
+        // Replies to notes that won't be here become top-level notes
+        let ids: Vec<Uuid> = existing.notes.iter().chain(&notes).map(|n| n.id).collect();
+        for note in &mut notes {
+            if note.parent_id.is_some_and(|parent| !ids.contains(&parent)) {
+                note.parent_id = None;
+            }
+        }
+
+        if !tags.is_empty() || !notes.is_empty() {
+            changes.push(IncomingChange::Changed {
+                link_id: existing.id,
+                title: existing.title.clone(),
+                tags,
+                notes,
+            });
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_added_and_changed() {
+        let mut mine = Link::new("https://example.com/shared");
+        mine.add_tag("rust");
+        mine.add_note(Note::new("My note"));
+
+        // Their copy of the same page, saved separately (a different ID)
+        let mut theirs = Link::new("https://example.com/shared");
+        theirs.add_tag("Rust");
+        theirs.add_tag("async");
+        theirs.add_note(Note::new("My note"));
+        theirs.add_note(Note::new("Their note"));
+        let new = Link::new("https://example.com/new");
+
+        let changes = incoming_changes(&[mine.clone()], &[theirs, new.clone()]);
+        assert_eq!(changes.len(), 2);
+        let IncomingChange::Changed {
+            link_id,
+            tags,
+            notes,
+            ..
+        } = &changes[0]
+        else {
+            panic!("expected a change to an existing link");
+        };
+        assert_eq!(*link_id, mine.id);
+        assert_eq!(tags, &vec!["async".to_string()]);
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].body, "Their note");
+        assert_eq!(changes[1], IncomingChange::Added { link: new });
+    }
+
+    #[test]
+    fn test_nothing_new_and_orphaned_replies() {
+        let mut mine = Link::new("https://example.com");
+        mine.add_note(Note::new("Same"));
+        let mut theirs = mine.clone();
+        assert!(incoming_changes(&[mine.clone()], &[theirs.clone()]).is_empty());
+
+        let mut reply = Note::new("A reply");
+        reply.parent_id = Some(Uuid::new_v4());
+        theirs.add_note(reply);
+        let changes = incoming_changes(&[mine], &[theirs]);
+        let IncomingChange::Changed { notes, .. } = &changes[0] else {
+            panic!("expected a change");
+        };
+        assert_eq!(notes[0].parent_id, None);
+    }
+}
@@ -0,0 +1,119 @@
+//! Content languages
+//!
+//! A link's language is detected when its page is fetched, from what the
+//! page declares (`<html lang>`, `Content-Language`, `og:locale`), and
+//! stored as a lowercase ISO 639 code (`en`, `de`, `pt`). Regional variants
+//! are folded into their language, so `en-GB` and `en_US` are both `en`.
+
+use std::collections::BTreeMap;
+
+use crate::models::Link;
+
+/// Common languages by code and English name
+const NAMES: &[(&str, &str)] = &[
+    ("ar", "Arabic"),
+    ("cs", "Czech"),
+    ("da", "Danish"),
+    ("de", "German"),
+    ("el", "Greek"),
+    ("en", "English"),
+    ("es", "Spanish"),
+    ("fi", "Finnish"),
+    ("fr", "French"),
+    ("he", "Hebrew"),
+    ("hi", "Hindi"),
+    ("hu", "Hungarian"),
+    ("id", "Indonesian"),
+    ("it", "Italian"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+    ("nl", "Dutch"),
+    ("no", "Norwegian"),
+    ("pl", "Polish"),
+    ("pt", "Portuguese"),
+    ("ro", "Romanian"),
+    ("ru", "Russian"),
+    ("sv", "Swedish"),
+    ("th", "Thai"),
+    ("tr", "Turkish"),
+    ("uk", "Ukrainian"),
+    ("vi", "Vietnamese"),
+    ("zh", "Chinese"),
+];
+
+/// Normalize a language tag or name to its language code
+///
+/// Accepts tags like `en`, `en-US`, or `pt_BR`, and English names of the
+/// common languages (`german`). Returns `None` for anything else.
+pub fn normalize_language(value: &str) -> Option<String> {
+    let value = value.trim().to_lowercase();
+    if let Some((code, _)) = NAMES
+        .iter()
+        .find(|(_, name)| name.eq_ignore_ascii_case(&value))
+    {
+        return Some(code.to_string());
+    }
+    let primary = value.split(['-', '_']).next().unwrap_or_default();
+    if (2..=3).contains(&primary.len()) && primary.chars().all(|c| c.is_ascii_lowercase()) {
+        Some(primary.to_string())
+    } else {
+        None
+    }
+}
+
+/// The English name of a language, or its code if it isn't a common one
+pub fn language_name(code: &str) -> &str {
+    NAMES
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, name)| *name)
+        .unwrap_or(code)
+}
+
+/// Number of links in each detected language, most common first
+pub fn language_counts(links: &[Link]) -> Vec<(String, usize)> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for language in links.iter().filter_map(|l| l.language.as_deref()) {
+        *counts.entry(language).or_default() += 1;
+    }
+    let mut counts: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|(language, count)| (language.to_string(), count))
+        .collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_language() {
+        assert_eq!(normalize_language("en-GB").as_deref(), Some("en"));
+        assert_eq!(normalize_language("pt_BR").as_deref(), Some("pt"));
+        assert_eq!(normalize_language(" DE ").as_deref(), Some("de"));
+        assert_eq!(normalize_language("German").as_deref(), Some("de"));
+        assert_eq!(normalize_language("fil").as_deref(), Some("fil"));
+        assert_eq!(normalize_language(""), None);
+        assert_eq!(normalize_language("x1"), None);
+        assert_eq!(normalize_language("klingon"), None);
+    }
+
+    #[test]
+    fn test_language_counts() {
+        let mut links: Vec<Link> = (0..4)
+            .map(|i| Link::new(format!("https://example.com/{}", i)))
+            .collect();
+        links[0].language = Some("de".to_string());
+        links[1].language = Some("en".to_string());
+        links[2].language = Some("en".to_string());
+
+        assert_eq!(
+            language_counts(&links),
+            vec![("en".to_string(), 2), ("de".to_string(), 1)]
+        );
+        assert_eq!(language_name("de"), "German");
+        assert_eq!(language_name("fil"), "fil");
+    }
+}
@@ -20,31 +20,107 @@
 //! store.add_link(&link)?;
 //!
 //! // Query links
-//! let links = store.get_all_links()?;
+//! let links = store.get_all_links(LinkSort::Updated)?;
 //! ```
 //!
 //! # Modules
 //!
 //! - `store`: Unified storage interface (main entry point)
 //! - `models`: Data structures for links, notes, and tags
+//! - `query`: Search query operators (`author:`, `has:`)
 //! - `document`: Automerge document handling
 //! - `document_id`: Document ID compatible with automerge-repo
+//! - `anonymize`: Anonymized copies of a document for bug reports
+//! - `attachments`: Files attached to links, stored outside the document
+//! - `bookmarks`: Reading browser bookmark exports
+//! - `canonical`: Normalized URLs, for finding duplicate links
+//! - `collections`: Named collections, each its own document
+//! - `dedupe`: Finding duplicate links, and identical notes across links
+//! - `demo`: Sample data for `rott init --demo`
+//! - `derive`: Derived link fields computed on write
+//! - `domains`: Domain blocklist and auto-tag rules for capture
+//! - `events`: Event log replayed from document history, and projections built from it
+//! - `favicons`: Cached site favicons, and initials to show in their place
+//! - `fetch_failures`: Which parts of links' metadata fetches failed, for refreshing later
+//! - `fingerprint`: Content fingerprints for detecting changed pages
+//! - `fuzzy`: Fuzzy matching for the TUI's realtime filter
+//! - `health`: Link health scores for surfacing stale links
+//! - `history`: Undo and redo of edits made through the store
+//! - `incoming`: Reviewing links merged from someone else's collection
+//! - `language`: Content language codes and names
+//! - `lock`: Privacy lock for shared machines
+//! - `maintenance`: Periodic maintenance jobs (scheduled backups, link checks)
+//! - `metadata_cache`: On-disk cache of fetched page metadata, shared between processes
+//! - `openers`: Commands to open links with, by kind or domain
+//! - `reading`: Reading sessions and statistics
+//! - `report`: Reports of what changed in the collection over a period
+//! - `search`: Search providers (document scan, SQLite FTS)
+//! - `sections`: Sections of long Markdown notes, split at their headings
+//! - `tag_tree`: The tag hierarchy, from tags' `-`-separated segments
+//! - `template`: `{{placeholder}}` templates for captures and notes
+//! - `urls`: Finding URLs in note text
+//! - `validate`: Validation of links before they're written
+//! - `visibility`: Private and public tags, and which links may be exported
 //! - `storage`: Automerge persistence
 //! - `config`: Application configuration
 
+pub mod anonymize;
+pub mod attachments;
+pub mod backup;
+pub mod bookmarks;
+pub mod canonical;
+pub mod collections;
 pub mod config;
+pub mod dedupe;
+pub mod demo;
+pub mod derive;
+pub mod desktop;
 pub mod document;
 pub mod document_id;
+pub mod domains;
+pub mod events;
+pub mod favicons;
+pub mod fetch_failures;
+pub mod fingerprint;
+pub mod fuzzy;
+pub mod health;
+pub mod history;
 pub mod identity;
+pub mod incoming;
+pub mod language;
+pub mod lock;
+pub mod maintenance;
+pub mod metadata_cache;
 pub mod models;
+pub mod openers;
+pub mod query;
+pub mod reading;
+pub mod report;
+pub mod search;
+pub mod sections;
 pub mod storage;
 pub mod store;
 pub mod sync;
+pub mod tag_tree;
+pub mod template;
+pub mod urls;
+pub mod validate;
+pub mod visibility;
 
+pub use backup::Backup;
 pub use config::Config;
-pub use document::{DocumentError, RottDocument};
+pub use derive::Deriver;
+pub use document::{DocumentError, DocumentStats, RecordWarning, RottDocument};
 pub use document_id::{DocumentId, DocumentIdError};
+pub use domains::DomainRules;
+pub use fingerprint::Fingerprint;
+pub use health::Health;
 pub use identity::{Identity, InitResult};
-pub use models::{Link, Note, Tag};
+pub use lock::LockSession;
+pub use models::{Link, LinkSort, LinksPage, Note, NoteVersion, NotesPage, ReadPosition, Tag};
+pub use query::SearchHit;
+pub use report::ChangeReport;
+pub use search::SearchProvider;
 pub use storage::{AutomergePersistence, StorageError, StorageStats};
-pub use store::Store;
+pub use store::{ImportSummary, IncomingSummary, LinkMerge, ReadOnlyError, RestoreSummary, Store};
+pub use validate::{ValidationError, Validator};
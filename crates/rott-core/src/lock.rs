@@ -0,0 +1,142 @@
+//! Privacy lock for shared machines
+//!
+//! When a passphrase is configured (`lock_hash` in the config), the CLI and
+//! TUI refuse to show data until it is entered. Entering it starts an unlock
+//! session that expires after `lock_timeout_minutes` without activity.
+//!
+//! This is a screen lock, not encryption: the data files stay readable by
+//! anyone with access to the user account.
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context, Result};
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use chrono::{Duration, Utc};
+
+use crate::config::Config;
+
+/// Hash a passphrase for storage in the config (argon2id PHC string)
+pub fn hash_passphrase(passphrase: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(passphrase.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| anyhow!("Failed to hash passphrase: {}", e))
+}
+
+/// Check a passphrase against a stored hash
+///
+/// Returns false for malformed hashes.
+pub fn verify_passphrase(passphrase: &str, hash: &str) -> bool {
+    let Ok(parsed) = PasswordHash::new(hash) else {
+        return false;
+    };
+    Argon2::default()
+        .verify_password(passphrase.as_bytes(), &parsed)
+        .is_ok()
+}
+
+/// An unlock session, shared by the CLI and TUI
+///
+/// Stored as the timestamp of the last activity in the data directory.
+pub struct LockSession {
+    path: PathBuf,
+    timeout: Duration,
+}
+
+impl LockSession {
+    /// Get the lock session for a configuration
+    pub fn new(config: &Config) -> Self {
+        Self {
+            path: config.lock_session_path(),
+            timeout: Duration::minutes(config.lock_timeout_minutes as i64),
+        }
+    }
+
+    /// Check whether the session is unlocked and hasn't timed out
+    pub fn is_unlocked(&self) -> bool {
+        let Ok(content) = fs::read_to_string(&self.path) else {
+            return false;
+        };
+        let Ok(last_activity) = content.trim().parse::<i64>() else {
+            return false;
+        };
+        let idle = Utc::now().timestamp() - last_activity;
+        (0..self.timeout.num_seconds()).contains(&idle)
+    }
+
+    /// Start (or extend) an unlocked session
+    pub fn unlock(&self) -> Result<()> {
+        fs::write(&self.path, Utc::now().timestamp().to_string())
+            .with_context(|| format!("Failed to write lock session: {:?}", self.path))
+    }
+
+    /// Record activity, extending the session if it is unlocked
+    pub fn touch(&self) -> Result<()> {
+        if self.is_unlocked() {
+            self.unlock()?;
+        }
+        Ok(())
+    }
+
+    /// End the session immediately
+    pub fn lock(&self) -> Result<()> {
+        if self.path.exists() {
+            fs::remove_file(&self.path)
+                .with_context(|| format!("Failed to remove lock session: {:?}", self.path))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn test_config(temp_dir: &TempDir) -> Config {
+        Config {
+            data_dir: temp_dir.path().to_path_buf(),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_hash_and_verify() {
+        let hash = hash_passphrase("correct horse").unwrap();
+        assert!(hash.starts_with("$argon2"));
+        assert!(verify_passphrase("correct horse", &hash));
+        assert!(!verify_passphrase("wrong horse", &hash));
+        assert!(!verify_passphrase("correct horse", "not a hash"));
+    }
+
+    #[test]
+    fn test_session_unlock_and_lock() {
+        let temp_dir = TempDir::new().unwrap();
+        let session = LockSession::new(&test_config(&temp_dir));
+
+        assert!(!session.is_unlocked());
+        session.unlock().unwrap();
+        assert!(session.is_unlocked());
+        session.lock().unwrap();
+        assert!(!session.is_unlocked());
+    }
+
+    #[test]
+    fn test_session_expires() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = test_config(&temp_dir);
+        let session = LockSession::new(&config);
+
+        let stale = Utc::now() - Duration::minutes(config.lock_timeout_minutes as i64 + 1);
+        fs::write(config.lock_session_path(), stale.timestamp().to_string()).unwrap();
+        assert!(!session.is_unlocked());
+
+        // Touching an expired session doesn't revive it
+        session.touch().unwrap();
+        assert!(!session.is_unlocked());
+    }
+}
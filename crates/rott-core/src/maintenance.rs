@@ -0,0 +1,346 @@
+//! Periodic maintenance jobs
+//!
+//! Jobs (scheduled backups, link checking, archiving expired links, change
+//! reports) are enabled by giving them an interval in the configuration.
+//! `rott maintenance run-due` runs whichever are due, so a daily cron entry
+//! or systemd timer is enough to keep them going. When each job last ran is
+//! kept in `maintenance.json` in the data directory.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::backup::BACKUP_EXTENSION;
+use crate::config::Config;
+
+/// Prefix of backup files written by the scheduled backup job
+const SCHEDULED_BACKUP_PREFIX: &str = "rott-";
+
+/// How often a job runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Interval {
+    Daily,
+    Weekly,
+    Monthly,
+    /// Every N days
+    Days(u32),
+}
+
+impl Interval {
+    /// Parse "daily", "weekly", "monthly", or a number of days ("10d")
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "daily" => Some(Interval::Daily),
+            "weekly" => Some(Interval::Weekly),
+            "monthly" => Some(Interval::Monthly),
+            other => {
+                let days = other.strip_suffix('d').unwrap_or(other);
+                match days.parse() {
+                    Ok(0) | Err(_) => None,
+                    Ok(days) => Some(Interval::Days(days)),
+                }
+            }
+        }
+    }
+
+    /// Time between runs (a month counts as 30 days)
+    pub fn duration(self) -> Duration {
+        match self {
+            Interval::Daily => Duration::days(1),
+            Interval::Weekly => Duration::days(7),
+            Interval::Monthly => Duration::days(30),
+            Interval::Days(days) => Duration::days(days.into()),
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Interval::Daily => write!(f, "daily"),
+            Interval::Weekly => write!(f, "weekly"),
+            Interval::Monthly => write!(f, "monthly"),
+            Interval::Days(days) => write!(f, "{}d", days),
+        }
+    }
+}
+
+impl TryFrom<String> for Interval {
+    type Error = String;
+
+    fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
+        Interval::parse(&value).ok_or_else(|| {
+            format!(
+                "invalid interval '{}' (use daily, weekly, monthly, or a number of days like 10d)",
+                value
+            )
+        })
+    }
+}
+
+impl From<Interval> for String {
+    fn from(interval: Interval) -> Self {
+        interval.to_string()
+    }
+}
+
+/// A periodic maintenance job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Job {
+    /// Write a backup archive to the backup directory
+    Backup,
+    /// Check saved links for changed content
+    LinkCheck,
+    /// Move expired links to the archives
+    ArchiveExpired,
+    /// Write a report of what changed since the last one (see [`crate::report`])
+    Report,
+}
+
+impl Job {
+    /// All jobs, in the order they run
+    pub const ALL: [Job; 4] = [
+        Job::Backup,
+        Job::LinkCheck,
+        Job::ArchiveExpired,
+        Job::Report,
+    ];
+
+    /// Name used in output and the log file
+    pub fn name(self) -> &'static str {
+        match self {
+            Job::Backup => "backup",
+            Job::LinkCheck => "link_check",
+            Job::ArchiveExpired => "archive_expired",
+            Job::Report => "report",
+        }
+    }
+
+    /// Configured interval (None if the job is disabled)
+    pub fn interval(self, config: &Config) -> Option<Interval> {
+        match self {
+            Job::Backup => config.backup_interval,
+            Job::LinkCheck => config.link_check_interval,
+            Job::ArchiveExpired => config.archive_expired_interval,
+            Job::Report => config.report_interval,
+        }
+    }
+}
+
+/// The outcome of a job's last run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobRecord {
+    pub last_run: DateTime<Utc>,
+    pub succeeded: bool,
+    /// Summary of what the job did, or the error
+    pub message: String,
+}
+
+/// When each job last ran
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct MaintenanceLog {
+    jobs: BTreeMap<Job, JobRecord>,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl MaintenanceLog {
+    /// Load the log from the data directory (empty if there isn't one yet)
+    pub fn load(config: &Config) -> Result<Self> {
+        let path = config.maintenance_log_path();
+        let mut log = if path.exists() {
+            let json = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            serde_json::from_str(&json)
+                .with_context(|| format!("Failed to parse {}", path.display()))?
+        } else {
+            Self::default()
+        };
+        log.path = Some(path);
+        Ok(log)
+    }
+
+    /// Save the log
+    pub fn save(&self) -> Result<()> {
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json).context("Failed to save maintenance log")?;
+        Ok(())
+    }
+
+    /// Last run of a job
+    pub fn last_run(&self, job: Job) -> Option<&JobRecord> {
+        self.jobs.get(&job)
+    }
+
+    /// Record a run of a job
+    pub fn record(&mut self, job: Job, at: DateTime<Utc>, result: &Result<String>) {
+        let (succeeded, message) = match result {
+            Ok(summary) => (true, summary.clone()),
+            Err(e) => (false, format!("{:#}", e)),
+        };
+        self.jobs.insert(
+            job,
+            JobRecord {
+                last_run: at,
+                succeeded,
+                message,
+            },
+        );
+    }
+
+    /// Status of every enabled job
+    ///
+    /// A job that has never run, or whose last run failed, is due now.
+    pub fn status(&self, config: &Config, now: DateTime<Utc>) -> Vec<JobStatus> {
+        Job::ALL
+            .into_iter()
+            .filter_map(|job| {
+                let interval = job.interval(config)?;
+                let last = self.last_run(job).cloned();
+                let next_due = match &last {
+                    Some(record) if record.succeeded => record.last_run + interval.duration(),
+                    _ => now,
+                };
+                Some(JobStatus {
+                    job,
+                    interval,
+                    last,
+                    next_due,
+                    due: next_due <= now,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Schedule and last run of an enabled job
+#[derive(Debug, Clone)]
+pub struct JobStatus {
+    pub job: Job,
+    pub interval: Interval,
+    pub last: Option<JobRecord>,
+    pub next_due: DateTime<Utc>,
+    pub due: bool,
+}
+
+/// File name for a scheduled backup taken at `at`
+pub fn scheduled_backup_name(at: DateTime<Utc>) -> String {
+    format!(
+        "{}{}.{}",
+        SCHEDULED_BACKUP_PREFIX,
+        at.format("%Y%m%d-%H%M%S"),
+        BACKUP_EXTENSION
+    )
+}
+
+/// File name for a change report written at `at`
+pub fn report_name(at: DateTime<Utc>) -> String {
+    format!("rott-report-{}.md", at.format("%Y-%m-%d"))
+}
+
+/// Delete all but the newest `keep` scheduled backups in `dir`
+///
+/// Only files named by [`scheduled_backup_name`] are considered, so
+/// backups made by hand are never removed. Returns the deleted paths.
+pub fn prune_backups(dir: &Path, keep: usize) -> Result<Vec<PathBuf>> {
+    let mut backups: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            name.starts_with(SCHEDULED_BACKUP_PREFIX)
+                && path.extension().and_then(|e| e.to_str()) == Some(BACKUP_EXTENSION)
+        })
+        .collect();
+
+    // Timestamped names sort oldest first
+    backups.sort();
+    let excess = backups.len().saturating_sub(keep);
+    let removed: Vec<PathBuf> = backups.into_iter().take(excess).collect();
+    for path in &removed {
+        fs::remove_file(path).with_context(|| format!("Failed to remove {}", path.display()))?;
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn test_config(temp_dir: &TempDir) -> Config {
+        Config {
+            data_dir: temp_dir.path().to_path_buf(),
+            backup_interval: Some(Interval::Weekly),
+            link_check_interval: Some(Interval::Days(3)),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(Interval::parse("weekly"), Some(Interval::Weekly));
+        assert_eq!(Interval::parse("Monthly"), Some(Interval::Monthly));
+        assert_eq!(Interval::parse("10d"), Some(Interval::Days(10)));
+        assert_eq!(Interval::parse("10"), Some(Interval::Days(10)));
+        assert_eq!(Interval::parse("0d"), None);
+        assert_eq!(Interval::parse("hourly"), None);
+        assert_eq!(Interval::Days(10).to_string(), "10d");
+    }
+
+    #[test]
+    fn test_due_jobs() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = test_config(&temp_dir);
+        let now = Utc::now();
+
+        let mut log = MaintenanceLog::load(&config).unwrap();
+        assert!(log.status(&config, now).iter().all(|s| s.due));
+
+        log.record(Job::Backup, now, &Ok("done".to_string()));
+        log.record(Job::LinkCheck, now, &Err(anyhow::anyhow!("offline")));
+        log.save().unwrap();
+
+        let log = MaintenanceLog::load(&config).unwrap();
+        let status = log.status(&config, now + Duration::days(1));
+        assert!(!status[0].due);
+        assert_eq!(status[0].next_due, now + Duration::days(7));
+        // Failed runs are retried
+        assert!(status[1].due);
+        assert_eq!(status[1].last.as_ref().unwrap().message, "offline");
+
+        let disabled = Config {
+            link_check_interval: None,
+            ..config
+        };
+        assert_eq!(log.status(&disabled, now).len(), 1);
+    }
+
+    #[test]
+    fn test_prune_backups() {
+        let temp_dir = TempDir::new().unwrap();
+        let start = Utc::now();
+        for day in 0..4 {
+            let name = scheduled_backup_name(start + Duration::days(day));
+            fs::write(temp_dir.path().join(name), b"").unwrap();
+        }
+        let manual = temp_dir.path().join("before-upgrade.rottpack");
+        fs::write(&manual, b"").unwrap();
+
+        let removed = prune_backups(temp_dir.path(), 2).unwrap();
+        assert_eq!(removed.len(), 2);
+        assert!(removed[0].ends_with(scheduled_backup_name(start)));
+        assert!(manual.exists());
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 3);
+    }
+}
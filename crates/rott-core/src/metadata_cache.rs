@@ -0,0 +1,179 @@
+//! On-disk cache of fetched page metadata
+//!
+//! Fetching a page is the slow part of saving a link. The cache keeps what
+//! was fetched for each URL for `metadata_cache_hours`, so adding the same
+//! URL again (after a failed add, from another terminal, or from the TUI
+//! while the CLI is running) reuses it instead of downloading the page.
+//!
+//! Each URL is one JSON file under `cache/metadata` in the data directory,
+//! named by the SHA-256 of the URL. Files are written to a temporary name
+//! and renamed into place, so processes sharing the cache never read a
+//! partly written entry; if two processes fetch the same URL, the last
+//! write wins.
+//!
+//! The cache stores whatever the caller gives it, so it doesn't depend on
+//! how metadata is represented.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config::Config;
+
+/// A cached value and when it was fetched
+#[derive(Serialize, Deserialize)]
+struct Entry<T> {
+    url: String,
+    fetched_at: DateTime<Utc>,
+    value: T,
+}
+
+/// Cache of fetched metadata, keyed by URL
+#[derive(Debug, Clone)]
+pub struct MetadataCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl MetadataCache {
+    /// Create a cache in `dir` whose entries last `ttl`
+    pub fn new(dir: impl Into<PathBuf>, ttl: Duration) -> Self {
+        Self {
+            dir: dir.into(),
+            ttl,
+        }
+    }
+
+    /// The cache for a configuration, or `None` if caching is turned off
+    /// (`metadata_cache_hours = 0`)
+    pub fn from_config(config: &Config) -> Option<Self> {
+        if config.metadata_cache_hours == 0 {
+            return None;
+        }
+        let hours = i64::try_from(config.metadata_cache_hours).unwrap_or(i64::MAX / 3600);
+        Some(Self::new(
+            config.metadata_cache_dir(),
+            Duration::hours(hours),
+        ))
+    }
+
+    /// The cached value for `url`, if there's one younger than the TTL
+    ///
+    /// Unreadable entries (including ones written by an older version in a
+    /// different shape) count as missing.
+    pub fn get<T: DeserializeOwned>(&self, url: &str, now: DateTime<Utc>) -> Option<T> {
+        let data = fs::read(self.entry_path(url)).ok()?;
+        let entry: Entry<T> = serde_json::from_slice(&data).ok()?;
+        (entry.url == url && now - entry.fetched_at < self.ttl).then_some(entry.value)
+    }
+
+    /// Store the value fetched for `url`
+    pub fn put<T: Serialize>(&self, url: &str, value: &T, now: DateTime<Utc>) -> Result<()> {
+        let entry = Entry {
+            url: url.to_string(),
+            fetched_at: now,
+            value,
+        };
+        let data = serde_json::to_vec(&entry).context("Failed to serialize cache entry")?;
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+
+        // A temporary name unique to this process, renamed into place
+        let path = self.entry_path(url);
+        let temp_path = path.with_extension(format!("{}.tmp", std::process::id()));
+        let mut file = fs::File::create(&temp_path)
+            .with_context(|| format!("Failed to create {}", temp_path.display()))?;
+        file.write_all(&data)
+            .with_context(|| format!("Failed to write {}", temp_path.display()))?;
+        fs::rename(&temp_path, &path)
+            .with_context(|| format!("Failed to rename {}", temp_path.display()))?;
+        Ok(())
+    }
+
+    /// Remove every entry, returning how many there were
+    pub fn clear(&self) -> Result<usize> {
+        self.remove_where(|_| true)
+    }
+
+    /// Remove entries older than the TTL, returning how many were removed
+    pub fn prune(&self, now: DateTime<Utc>) -> Result<usize> {
+        self.remove_where(|path| {
+            let fetched_at = fs::read(path)
+                .ok()
+                .and_then(|data| serde_json::from_slice::<Entry<serde_json::Value>>(&data).ok())
+                .map(|entry| entry.fetched_at);
+            fetched_at.is_none_or(|fetched_at| now - fetched_at >= self.ttl)
+        })
+    }
+
+    fn remove_where(&self, remove: impl Fn(&Path) -> bool) -> Result<usize> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read {}", self.dir.display()))
+            }
+        };
+        let mut removed = 0;
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") && remove(&path) {
+                fs::remove_file(&path)
+                    .with_context(|| format!("Failed to remove {}", path.display()))?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    fn entry_path(&self, url: &str) -> PathBuf {
+        let hash: String = Sha256::digest(url.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        self.dir.join(format!("{}.json", hash))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_get_put_and_expiry() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = MetadataCache::new(temp_dir.path(), Duration::hours(1));
+        let now = Utc::now();
+        let url = "https://example.com/a";
+
+        assert_eq!(cache.get::<String>(url, now), None);
+        cache.put(url, &"Example".to_string(), now).unwrap();
+        assert_eq!(cache.get::<String>(url, now).as_deref(), Some("Example"));
+        assert_eq!(cache.get::<String>("https://example.com/b", now), None);
+        // Expired entries aren't returned, and are pruned
+        let later = now + Duration::hours(2);
+        assert_eq!(cache.get::<String>(url, later), None);
+        assert_eq!(cache.prune(now).unwrap(), 0);
+        assert_eq!(cache.prune(later).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_clear_and_wrong_shape() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = MetadataCache::new(temp_dir.path().join("cache"), Duration::hours(1));
+        let now = Utc::now();
+
+        assert_eq!(cache.clear().unwrap(), 0);
+        cache.put("https://example.com", &42u32, now).unwrap();
+        assert_eq!(cache.get::<String>("https://example.com", now), None);
+        assert_eq!(cache.clear().unwrap(), 1);
+        assert_eq!(cache.get::<u32>("https://example.com", now), None);
+    }
+}
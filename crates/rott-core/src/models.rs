@@ -4,10 +4,16 @@
 //! Notes are children of Links, serving as annotations or comments.
 //! These models are designed to work with Automerge for CRDT-based sync.
 
+use std::collections::{BTreeMap, HashMap, HashSet};
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::derive::site_domain;
+use crate::urls::title_from_url;
+use crate::validate::tags_match;
+
 /// A note attached to a link
 ///
 /// Notes serve as annotations, comments, or supplementary information
@@ -22,6 +28,18 @@ pub struct Note {
     pub body: String,
     /// When this note was created
     pub created_at: DateTime<Utc>,
+    /// Pinned notes are listed before all others
+    #[serde(default)]
+    pub pinned: bool,
+    /// Manual position among the link's notes (creation order if unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<f64>,
+    /// The note this one replies to (see [`thread_notes`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<Uuid>,
+    /// When the title or body was last edited (unset if never)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 impl Note {
@@ -32,6 +50,10 @@ impl Note {
             title: None,
             body: body.into(),
             created_at: Utc::now(),
+            pinned: false,
+            order: None,
+            parent_id: None,
+            updated_at: None,
         }
     }
 
@@ -42,6 +64,10 @@ impl Note {
             title: Some(title.into()),
             body: body.into(),
             created_at: Utc::now(),
+            pinned: false,
+            order: None,
+            parent_id: None,
+            updated_at: None,
         }
     }
 
@@ -52,18 +78,233 @@ impl Note {
             title: None,
             body: body.into(),
             created_at: Utc::now(),
+            pinned: false,
+            order: None,
+            parent_id: None,
+            updated_at: None,
         }
     }
 
     /// Set the title
     pub fn set_title(&mut self, title: Option<String>) {
         self.title = title;
+        self.updated_at = Some(Utc::now());
     }
 
     /// Set the body
     pub fn set_body(&mut self, body: impl Into<String>) {
         self.body = body.into();
+        self.updated_at = Some(Utc::now());
+    }
+
+    /// Whether the note was edited after it was created
+    pub fn is_edited(&self) -> bool {
+        self.updated_at.is_some()
+    }
+
+    /// Number of words in the title and body
+    pub fn word_count(&self) -> usize {
+        self.title
+            .iter()
+            .chain([&self.body])
+            .map(|text| text.split_whitespace().count())
+            .sum()
+    }
+
+    /// Position key: the manual order, or the creation time
+    pub fn sort_key(&self) -> f64 {
+        self.order
+            .unwrap_or(self.created_at.timestamp_millis() as f64)
+    }
+}
+
+/// Arrange sorted notes into threads, with each note's depth
+///
+/// Threads are one level deep: every top-level note is followed by its
+/// replies, in order. A reply whose parent has been deleted (or is itself a
+/// reply) is shown as a top-level note.
+pub fn thread_notes(notes: &[Note]) -> Vec<(&Note, usize)> {
+    let ids: HashSet<Uuid> = notes.iter().map(|n| n.id).collect();
+    let is_top_level = |note: &Note| note.parent_id.is_none_or(|parent| !ids.contains(&parent));
+    let top_level: HashSet<Uuid> = notes
+        .iter()
+        .filter(|n| is_top_level(n))
+        .map(|n| n.id)
+        .collect();
+
+    let mut replies: HashMap<Uuid, Vec<&Note>> = HashMap::new();
+    for note in notes {
+        if let Some(parent) = note.parent_id.filter(|p| top_level.contains(p)) {
+            replies.entry(parent).or_default().push(note);
+        }
+    }
+
+    let mut threaded = Vec::with_capacity(notes.len());
+    for note in notes {
+        if note.parent_id.is_some_and(|p| top_level.contains(&p)) {
+            continue;
+        }
+        threaded.push((note, 0));
+        for reply in replies.get(&note.id).into_iter().flatten() {
+            threaded.push((*reply, 1));
+        }
     }
+    threaded
+}
+
+/// One page of a link's notes, in threaded order (see [`thread_notes`])
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotesPage {
+    /// The link the notes belong to
+    pub link_id: Uuid,
+    /// Position of the first note on this page among all the link's notes
+    pub offset: usize,
+    /// Notes on this page, each with its thread depth
+    pub notes: Vec<(Note, usize)>,
+    /// Number of notes on the link
+    pub total: usize,
+}
+
+impl NotesPage {
+    /// Whether there are notes after this page
+    pub fn has_more(&self) -> bool {
+        self.offset + self.notes.len() < self.total
+    }
+
+    /// Add the next page's notes to this one
+    pub fn extend(&mut self, next: NotesPage) {
+        self.notes.extend(next.notes);
+        self.total = next.total;
+    }
+}
+
+/// Order of a list of links
+///
+/// The default for the TUI and `rott link list` is the `default_sort`
+/// config key. Links sorting the same are ordered by ID, so the order is
+/// the same on every device.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkSort {
+    /// Most recently updated first
+    #[default]
+    Updated,
+    /// Most recently saved first
+    Created,
+    /// Saved longest ago first
+    Oldest,
+    /// By title, ignoring case
+    Title,
+    /// By domain (without `www.`), then title; links without one last
+    Domain,
+}
+
+impl LinkSort {
+    /// All orders, in the order the TUI cycles through them
+    pub const ALL: [LinkSort; 5] = [
+        LinkSort::Updated,
+        LinkSort::Created,
+        LinkSort::Oldest,
+        LinkSort::Title,
+        LinkSort::Domain,
+    ];
+
+    /// Parse an order's name (as in the config file)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "updated" | "recent" => Some(LinkSort::Updated),
+            "created" | "newest" => Some(LinkSort::Created),
+            "oldest" => Some(LinkSort::Oldest),
+            "title" => Some(LinkSort::Title),
+            "domain" => Some(LinkSort::Domain),
+            _ => None,
+        }
+    }
+
+    /// Name used in the config file
+    pub fn name(self) -> &'static str {
+        match self {
+            LinkSort::Updated => "updated",
+            LinkSort::Created => "created",
+            LinkSort::Oldest => "oldest",
+            LinkSort::Title => "title",
+            LinkSort::Domain => "domain",
+        }
+    }
+
+    /// The order after this one, wrapping around
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|sort| *sort == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Sort links in this order
+    pub fn sort(self, links: &mut [Link]) {
+        match self {
+            LinkSort::Updated => {
+                links.sort_by(|a, b| b.updated_at.cmp(&a.updated_at).then(a.id.cmp(&b.id)))
+            }
+            LinkSort::Created => {
+                links.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(a.id.cmp(&b.id)))
+            }
+            LinkSort::Oldest => {
+                links.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)))
+            }
+            LinkSort::Title => {
+                links.sort_by_cached_key(|link| (link.title.to_lowercase(), link.id))
+            }
+            LinkSort::Domain => links.sort_by_cached_key(|link| {
+                let domain = site_domain(&link.url);
+                (domain.is_none(), domain, link.title.to_lowercase(), link.id)
+            }),
+        }
+    }
+}
+
+/// One page of the links, in a [`LinkSort`] order
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinksPage {
+    /// Position of the first link on this page among all the links
+    pub offset: usize,
+    /// Links on this page
+    pub links: Vec<Link>,
+    /// Number of links in the store
+    pub total: usize,
+}
+
+impl LinksPage {
+    /// Whether there are links after this page
+    pub fn has_more(&self) -> bool {
+        self.offset + self.links.len() < self.total
+    }
+
+    /// Add the next page's links to this one
+    pub fn extend(&mut self, next: LinksPage) {
+        self.links.extend(next.links);
+        self.total = next.total;
+    }
+}
+
+/// Sort notes for display: pinned first, then by position
+pub fn sort_notes(notes: &mut [Note]) {
+    notes.sort_by(|a, b| {
+        b.pinned
+            .cmp(&a.pinned)
+            .then(a.sort_key().total_cmp(&b.sort_key()))
+    });
+}
+
+/// A past version of a note, reconstructed from document history
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NoteVersion {
+    /// Hash of the change that produced this version
+    pub change_hash: String,
+    /// When the change was made (if the writer recorded a time)
+    pub timestamp: Option<DateTime<Utc>>,
+    /// Note title at this version
+    pub title: Option<String>,
+    /// Note body at this version
+    pub body: String,
 }
 
 /// A saved link with metadata
@@ -87,6 +328,100 @@ pub struct Link {
     pub updated_at: DateTime<Utc>,
     /// Notes/annotations attached to this link
     pub notes: Vec<Note>,
+    /// Fingerprint of the page content when it was saved (hex SimHash)
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    /// Whether the page content has changed significantly since it was saved
+    #[serde(default)]
+    pub content_changed: bool,
+    /// When the page content was last checked for changes
+    #[serde(default)]
+    pub content_checked_at: Option<DateTime<Utc>>,
+    /// Whether the page couldn't be fetched at the last check
+    #[serde(default)]
+    pub unreachable: bool,
+    /// When the link was last opened in a browser from ROTT
+    #[serde(default)]
+    pub opened_at: Option<DateTime<Utc>>,
+    /// How many times the link has been opened, across all devices
+    #[serde(default)]
+    pub open_count: u64,
+    /// How far its archived page text was read in the TUI's reader, on
+    /// whichever device read it last
+    #[serde(default)]
+    pub read_position: Option<ReadPosition>,
+    /// When the link stops being useful (an event, a sale, a deadline)
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// When the linked content was published (from the page's metadata)
+    #[serde(default)]
+    pub published_at: Option<DateTime<Utc>>,
+    /// The page's canonical URL (`<link rel="canonical">`), if it differs
+    /// from the saved URL
+    #[serde(default)]
+    pub canonical_url: Option<String>,
+    /// Whether the page is behind a paywall or login (detected when fetched)
+    #[serde(default)]
+    pub paywalled: bool,
+    /// Language of the page (ISO 639 code, detected when fetched; see
+    /// [`crate::language`])
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Who recommended the link (e.g. "Simon")
+    #[serde(default)]
+    pub via: Option<String>,
+    /// Where the link was found (e.g. "HN thread", optionally with its URL)
+    #[serde(default)]
+    pub source_context: Option<String>,
+    /// Fields computed from the link's data by the store's derivers
+    #[serde(default)]
+    pub derived: BTreeMap<String, String>,
+    /// Files attached to the link, by name, as the SHA-256 of their content
+    /// (see [`crate::attachments`])
+    #[serde(default)]
+    pub attachments: BTreeMap<String, String>,
+    /// IDs of duplicate links merged into this one (see
+    /// [`Store::merge_links`](crate::Store::merge_links))
+    #[serde(default)]
+    pub merged_from: Vec<Uuid>,
+}
+
+/// How far a link's archived page text has been read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadPosition {
+    /// First line shown when reading stopped (`lines` once it reached the end)
+    pub line: usize,
+    /// Lines in the text when it was read
+    pub lines: usize,
+    /// When reading stopped
+    pub at: DateTime<Utc>,
+}
+
+impl ReadPosition {
+    /// The part of the text read, from 0.0 to 1.0
+    pub fn progress(&self) -> f64 {
+        if self.lines == 0 {
+            return 1.0;
+        }
+        (self.line as f64 / self.lines as f64).min(1.0)
+    }
+
+    /// Whether reading reached the end
+    pub fn is_finished(&self) -> bool {
+        self.line >= self.lines
+    }
+
+    /// The line to resume at in a text of `lines` lines
+    ///
+    /// If the text has changed length since (it was archived again), the
+    /// position is scaled to the same part of the new text.
+    pub fn line_in(&self, lines: usize) -> usize {
+        if self.lines == lines {
+            self.line
+        } else {
+            (self.progress() * lines as f64).round() as usize
+        }
+    }
 }
 
 impl Link {
@@ -104,6 +439,23 @@ impl Link {
             created_at: now,
             updated_at: now,
             notes: Vec::new(),
+            content_hash: None,
+            content_changed: false,
+            content_checked_at: None,
+            unreachable: false,
+            opened_at: None,
+            open_count: 0,
+            read_position: None,
+            expires_at: None,
+            published_at: None,
+            canonical_url: None,
+            paywalled: false,
+            language: None,
+            via: None,
+            source_context: None,
+            derived: BTreeMap::new(),
+            attachments: BTreeMap::new(),
+            merged_from: Vec::new(),
         }
     }
 
@@ -121,6 +473,23 @@ impl Link {
             created_at: now,
             updated_at: now,
             notes: Vec::new(),
+            content_hash: None,
+            content_changed: false,
+            content_checked_at: None,
+            unreachable: false,
+            opened_at: None,
+            open_count: 0,
+            read_position: None,
+            expires_at: None,
+            published_at: None,
+            canonical_url: None,
+            paywalled: false,
+            language: None,
+            via: None,
+            source_context: None,
+            derived: BTreeMap::new(),
+            attachments: BTreeMap::new(),
+            merged_from: Vec::new(),
         }
     }
 
@@ -136,24 +505,91 @@ impl Link {
         self.updated_at = Utc::now();
     }
 
+    /// Set who recommended the link and where it was found
+    ///
+    /// Blank values are cleared.
+    pub fn set_source(&mut self, via: Option<String>, source_context: Option<String>) {
+        let present = |value: Option<String>| {
+            value
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        self.via = present(via);
+        self.source_context = present(source_context);
+        self.updated_at = Utc::now();
+    }
+
+    /// Set when the linked content was published
+    pub fn set_published_at(&mut self, published_at: Option<DateTime<Utc>>) {
+        self.published_at = published_at;
+        self.updated_at = Utc::now();
+    }
+
+    /// Set when the link expires
+    pub fn set_expires_at(&mut self, expires_at: Option<DateTime<Utc>>) {
+        self.expires_at = expires_at;
+        self.updated_at = Utc::now();
+    }
+
+    /// Whether the link's expiry time has passed
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires| expires <= now)
+    }
+
+    /// Set the canonical URL (ignored if it's the saved URL)
+    pub fn set_canonical_url(&mut self, canonical_url: Option<String>) {
+        self.canonical_url = canonical_url.filter(|c| *c != self.url);
+        self.updated_at = Utc::now();
+    }
+
+    /// URL to show: the canonical one if known, otherwise the saved one
+    pub fn display_url(&self) -> &str {
+        self.canonical_url.as_deref().unwrap_or(&self.url)
+    }
+
+    /// Whether the link has no real title: it's blank, the URL, or the
+    /// fallback made from the URL (see [`title_from_url`])
+    pub fn has_placeholder_title(&self) -> bool {
+        let title = self.title.trim();
+        title.is_empty()
+            || title == self.url
+            || self.canonical_url.as_deref() == Some(title)
+            || title_from_url(&self.url).as_deref() == Some(title)
+    }
+
     /// Set the authors
     pub fn set_author(&mut self, author: Vec<String>) {
         self.author = author;
         self.updated_at = Utc::now();
     }
 
-    /// Add a tag
+    /// Record the fingerprint of the page content as the new baseline
+    ///
+    /// Clears any pending change indicator.
+    pub fn set_content_hash(&mut self, hash: Option<String>) {
+        self.content_hash = hash;
+        self.content_changed = false;
+        self.content_checked_at = Some(Utc::now());
+        self.updated_at = Utc::now();
+    }
+
+    /// Whether the link has a tag (ignoring case)
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| tags_match(t, tag))
+    }
+
+    /// Add a tag (unless it's already there, ignoring case)
     pub fn add_tag(&mut self, tag: impl Into<String>) {
         let tag = tag.into();
-        if !self.tags.contains(&tag) {
+        if !self.has_tag(&tag) {
             self.tags.push(tag);
             self.updated_at = Utc::now();
         }
     }
 
-    /// Remove a tag
+    /// Remove a tag (ignoring case)
     pub fn remove_tag(&mut self, tag: &str) {
-        if let Some(pos) = self.tags.iter().position(|t| t == tag) {
+        if let Some(pos) = self.tags.iter().position(|t| tags_match(t, tag)) {
             self.tags.remove(pos);
             self.updated_at = Utc::now();
         }
@@ -191,10 +627,94 @@ impl Link {
         }
     }
 
+    /// The note a reply to `id` should be attached to
+    ///
+    /// Threads stay one level deep, so replying to a reply attaches to the
+    /// note it replied to. Returns None if there's no note `id`.
+    pub fn reply_parent(&self, id: Uuid) -> Option<Uuid> {
+        let note = self.get_note(id)?;
+        match note.parent_id {
+            Some(parent) if self.get_note(parent).is_some() => Some(parent),
+            _ => Some(note.id),
+        }
+    }
+
     /// Get all notes
     pub fn notes(&self) -> &[Note] {
         &self.notes
     }
+
+    /// Number of words across all notes
+    pub fn note_word_count(&self) -> usize {
+        self.notes.iter().map(Note::word_count).sum()
+    }
+
+    /// Bytes of text written for the link: its description and notes
+    ///
+    /// Pages aren't stored, so this measures what was saved about the page
+    /// rather than the page itself.
+    pub fn content_size(&self) -> usize {
+        let notes: usize = self
+            .notes
+            .iter()
+            .map(|n| n.title.as_ref().map_or(0, String::len) + n.body.len())
+            .sum();
+        self.description.as_ref().map_or(0, String::len) + notes
+    }
+
+    /// Move a note one place up or down
+    ///
+    /// Notes only move among notes with the same pinned state. Moving gives
+    /// the note an order key between its new neighbours; when there's no
+    /// room left between them, the whole group is renumbered. Returns the
+    /// notes whose order changed (empty if the note can't move that way).
+    pub fn move_note(&mut self, id: Uuid, up: bool) -> Vec<Note> {
+        sort_notes(&mut self.notes);
+        let Some(index) = self.notes.iter().position(|n| n.id == id) else {
+            return Vec::new();
+        };
+        let pinned = self.notes[index].pinned;
+        let in_group = |i: Option<usize>| {
+            i.and_then(|i| self.notes.get(i))
+                .filter(|n| n.pinned == pinned)
+                .map(Note::sort_key)
+        };
+
+        // The neighbour to pass, and the one beyond it
+        let (past, beyond) = if up {
+            (index.checked_sub(1), index.checked_sub(2))
+        } else {
+            (Some(index + 1), Some(index + 2))
+        };
+        let Some(past_key) = in_group(past) else {
+            return Vec::new();
+        };
+        let step = if up { -1.0 } else { 1.0 };
+        let beyond_key = in_group(beyond).unwrap_or(past_key + step * 2.0);
+        let key = (past_key + beyond_key) / 2.0;
+
+        if key != past_key && key != beyond_key {
+            self.notes[index].order = Some(key);
+            let moved = self.notes[index].clone();
+            sort_notes(&mut self.notes);
+            return vec![moved];
+        }
+
+        // Out of room: renumber the group with the note already moved
+        let past = past.unwrap_or(index);
+        self.notes.swap(index, past);
+        let mut changed = Vec::new();
+        for (position, note) in self
+            .notes
+            .iter_mut()
+            .filter(|n| n.pinned == pinned)
+            .enumerate()
+        {
+            note.order = Some(position as f64);
+            changed.push(note.clone());
+        }
+        changed
+    }
 }
 
 /// A tag for organizing links
@@ -244,6 +764,22 @@ mod tests {
         assert!(link.author.is_empty());
         assert!(link.description.is_none());
         assert!(link.notes.is_empty());
+        assert!(link.content_hash.is_none());
+        assert!(!link.content_changed);
+    }
+
+    #[test]
+    fn test_note_word_count_and_content_size() {
+        let mut link = Link::new("https://example.com");
+        assert_eq!(link.note_word_count(), 0);
+        assert_eq!(link.content_size(), 0);
+
+        link.set_description(Some("An example".to_string()));
+        link.add_note(Note::new("Three words here"));
+        link.add_note(Note::with_title("Summary", "  two\nwords "));
+        assert_eq!(link.notes[1].word_count(), 3);
+        assert_eq!(link.note_word_count(), 6);
+        assert_eq!(link.content_size(), 10 + 16 + 7 + 12);
     }
 
     #[test]
@@ -264,6 +800,18 @@ mod tests {
         assert!(link.updated_at > original_updated);
     }
 
+    #[test]
+    fn test_placeholder_titles() {
+        let mut link = Link::new("https://example.com/rust-lifetimes");
+        assert!(link.has_placeholder_title());
+        link.set_title("Rust lifetimes (example.com)");
+        assert!(link.has_placeholder_title());
+        link.set_title("  ");
+        assert!(link.has_placeholder_title());
+        link.set_title("Understanding Lifetimes");
+        assert!(!link.has_placeholder_title());
+    }
+
     #[test]
     fn test_link_tags() {
         let mut link = Link::new("https://example.com");
@@ -332,6 +880,7 @@ mod tests {
         let mut link = Link::new("https://example.com");
         let note = Note::new("Original body");
         let note_id = note.id;
+        assert!(!note.is_edited());
 
         link.add_note(note);
 
@@ -340,6 +889,90 @@ mod tests {
         }
 
         assert_eq!(link.get_note(note_id).unwrap().body, "Updated body");
+        assert!(link.get_note(note_id).unwrap().is_edited());
+    }
+
+    #[test]
+    fn test_move_and_pin_notes() {
+        let mut link = Link::new("https://example.com");
+        for body in ["first", "second", "summary"] {
+            let mut note = Note::new(body);
+            note.created_at = link.created_at + chrono::Duration::seconds(link.notes.len() as i64);
+            link.add_note(note);
+        }
+        let bodies =
+            |link: &Link| -> Vec<String> { link.notes.iter().map(|n| n.body.clone()).collect() };
+
+        let summary = link.notes[2].id;
+        assert_eq!(link.move_note(summary, true).len(), 1);
+        assert_eq!(bodies(&link), vec!["first", "summary", "second"]);
+        link.move_note(summary, true);
+        assert_eq!(bodies(&link), vec!["summary", "first", "second"]);
+        // Already at the top
+        assert!(link.move_note(summary, true).is_empty());
+
+        // A pinned note goes first and other notes can't move past it
+        let second = link.notes[2].id;
+        link.get_note_mut(second).unwrap().pinned = true;
+        sort_notes(&mut link.notes);
+        assert_eq!(bodies(&link), vec!["second", "summary", "first"]);
+        assert!(link.move_note(link.notes[1].id, true).is_empty());
+    }
+
+    #[test]
+    fn test_move_note_renumbers_when_out_of_room() {
+        let mut link = Link::new("https://example.com");
+        let mut a = Note::new("a");
+        a.order = Some(1.0);
+        let mut b = Note::new("b");
+        b.order = Some(1.0 + f64::EPSILON);
+        let mut c = Note::new("c");
+        c.order = Some(1.0 + 2.0 * f64::EPSILON);
+        link.notes = vec![a, b, c];
+
+        let c_id = link.notes[2].id;
+        let changed = link.move_note(c_id, true);
+        assert_eq!(changed.len(), 3);
+        let bodies: Vec<_> = link.notes.iter().map(|n| n.body.as_str()).collect();
+        assert_eq!(bodies, vec!["a", "c", "b"]);
+    }
+
+    #[test]
+    fn test_notes_are_threaded_one_level_deep() {
+        let mut link = Link::new("https://example.com");
+        let first = Note::new("first");
+        let second = Note::new("second");
+        link.add_note(first.clone());
+        link.add_note(second.clone());
+
+        let mut reply = Note::new("reply");
+        reply.parent_id = link.reply_parent(first.id);
+        link.add_note(reply.clone());
+        // Replying to a reply attaches to the original note
+        assert_eq!(link.reply_parent(reply.id), Some(first.id));
+        assert_eq!(link.reply_parent(Uuid::new_v4()), None);
+
+        let mut orphan = Note::new("orphan");
+        orphan.parent_id = Some(Uuid::new_v4());
+        link.add_note(orphan);
+
+        let threaded: Vec<_> = thread_notes(&link.notes)
+            .into_iter()
+            .map(|(note, depth)| (note.body.as_str(), depth))
+            .collect();
+        assert_eq!(
+            threaded,
+            vec![("first", 0), ("reply", 1), ("second", 0), ("orphan", 0)]
+        );
+
+        // A chain of replies to replies shows each note once
+        let mut nested = Note::new("nested");
+        nested.parent_id = Some(reply.id);
+        let mut deeper = Note::new("deeper");
+        deeper.parent_id = Some(nested.id);
+        link.add_note(nested);
+        link.add_note(deeper);
+        assert_eq!(thread_notes(&link.notes).len(), link.notes.len());
     }
 
     #[test]
@@ -349,6 +982,55 @@ mod tests {
         assert_eq!(tag.name(), "rust");
     }
 
+    #[test]
+    fn test_read_position() {
+        let position = ReadPosition {
+            line: 50,
+            lines: 200,
+            at: Utc::now(),
+        };
+        assert_eq!(position.progress(), 0.25);
+        assert!(!position.is_finished());
+        assert_eq!(position.line_in(200), 50);
+        // Archived again, longer: the same part of the text
+        assert_eq!(position.line_in(400), 100);
+
+        let finished = ReadPosition {
+            line: 200,
+            ..position
+        };
+        assert!(finished.is_finished());
+        assert_eq!(finished.progress(), 1.0);
+    }
+
+    #[test]
+    fn test_link_sort() {
+        let mut zebra = Link::new("https://www.zebra.org/a");
+        zebra.set_title("Zebra");
+        let mut apple = Link::new("https://apple.com/b");
+        apple.set_title("apple");
+        let mut local = Link::new("file-without-host");
+        local.set_title("Local");
+        local.created_at = apple.created_at - chrono::Duration::days(1);
+        let titles = |sort: LinkSort| {
+            let mut links = vec![zebra.clone(), apple.clone(), local.clone()];
+            sort.sort(&mut links);
+            links.into_iter().map(|l| l.title).collect::<Vec<_>>()
+        };
+
+        assert_eq!(titles(LinkSort::Title), ["apple", "Local", "Zebra"]);
+        assert_eq!(titles(LinkSort::Domain), ["apple", "Zebra", "Local"]);
+        assert_eq!(titles(LinkSort::Oldest)[0], "Local");
+        assert_eq!(titles(LinkSort::Created)[2], "Local");
+
+        assert_eq!(LinkSort::parse("Oldest"), Some(LinkSort::Oldest));
+        assert_eq!(LinkSort::parse("random"), None);
+        assert_eq!(LinkSort::Domain.next(), LinkSort::Updated);
+        assert!(LinkSort::ALL
+            .iter()
+            .all(|s| LinkSort::parse(s.name()) == Some(*s)));
+    }
+
     #[test]
     fn test_tag_from() {
         let tag1: Tag = "rust".into();
@@ -0,0 +1,202 @@
+//! Opening links with the right program
+//!
+//! The browser is the wrong tool for videos, PDFs and the like. The
+//! `openers` config key maps a link's kind (the derived `kind`: `video`,
+//! `pdf`, `audio`, ...), a domain, or `default` to a command:
+//!
+//! ```toml
+//! [openers]
+//! video = "mpv {url}"
+//! pdf = "zathura {url}"
+//! "arxiv.org" = "zathura {url}"
+//! ```
+//!
+//! A domain rule (any key with a `.`) matches subdomains too and wins over
+//! the kind; the longest matching domain wins over shorter ones. Links
+//! nothing matches open with `default`, or in the browser if there's no
+//! `default`. The command's arguments are split on whitespace, and `{url}`
+//! in them is replaced with the URL (which is added as the last argument if
+//! there's no `{url}`). Commands are run directly, not through a shell.
+
+use std::collections::BTreeMap;
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+
+use crate::config::Config;
+use crate::derive::{Deriver, KindDeriver};
+use crate::desktop::open_url;
+use crate::domains::{domain_matches, extract_domain};
+use crate::models::Link;
+
+/// The key of the command for links nothing else matches
+pub const DEFAULT_OPENER: &str = "default";
+
+/// Commands links are opened with, from configuration
+#[derive(Debug, Clone, Default)]
+pub struct Openers {
+    /// Commands by domain, longest domain first
+    domains: Vec<(String, String)>,
+    /// Commands by link kind
+    kinds: BTreeMap<String, String>,
+    /// Command for everything else (the browser if unset)
+    default: Option<String>,
+}
+
+impl Openers {
+    /// Build the openers from configuration
+    pub fn from_config(config: &Config) -> Self {
+        let mut openers = Self::default();
+        for (key, command) in &config.openers {
+            let key = key.trim().to_lowercase();
+            let command = command.trim().to_string();
+            if command.is_empty() {
+                continue;
+            }
+            if key == DEFAULT_OPENER {
+                openers.default = Some(command);
+            } else if key.contains('.') {
+                openers.domains.push((key, command));
+            } else {
+                openers.kinds.insert(key, command);
+            }
+        }
+        openers
+            .domains
+            .sort_by_key(|(domain, _)| std::cmp::Reverse(domain.len()));
+        openers
+    }
+
+    /// The command a link is opened with (None for the browser)
+    pub fn command_for(&self, link: &Link) -> Option<&str> {
+        if let Some(host) = extract_domain(&link.url) {
+            if let Some((_, command)) = self
+                .domains
+                .iter()
+                .find(|(domain, _)| domain_matches(&host, domain))
+            {
+                return Some(command);
+            }
+        }
+        let kind = link
+            .derived
+            .get("kind")
+            .cloned()
+            .or_else(|| KindDeriver.derive(link));
+        kind.and_then(|kind| self.kinds.get(&kind))
+            .or(self.default.as_ref())
+            .map(String::as_str)
+    }
+
+    /// Open a link with its command, or in the browser
+    ///
+    /// The command is started detached, with no terminal input or output,
+    /// so it doesn't interfere with the TUI.
+    pub fn open(&self, link: &Link) -> Result<()> {
+        match self.command_for(link) {
+            Some(command) => run_detached(&command_line(command, &link.url)),
+            None => open_url(&link.url),
+        }
+    }
+
+    /// Open a URL that isn't a saved link (one found in a note)
+    pub fn open_url(&self, url: &str) -> Result<()> {
+        self.open(&Link::new(url))
+    }
+}
+
+/// Split a command into program and arguments, with the URL filled in
+pub fn command_line(command: &str, url: &str) -> Vec<String> {
+    let mut args: Vec<String> = command
+        .split_whitespace()
+        .map(|arg| arg.replace("{url}", url))
+        .collect();
+    if !command.contains("{url}") {
+        args.push(url.to_string());
+    }
+    args
+}
+
+fn run_detached(args: &[String]) -> Result<()> {
+    let Some((program, args)) = args.split_first() else {
+        anyhow::bail!("Empty opener command");
+    };
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run '{}'", program))?;
+    // Reap the child when it exits, without waiting for it here
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn openers(rules: &[(&str, &str)]) -> Openers {
+        let config = Config {
+            openers: rules
+                .iter()
+                .map(|(key, command)| (key.to_string(), command.to_string()))
+                .collect(),
+            ..Config::default()
+        };
+        Openers::from_config(&config)
+    }
+
+    #[test]
+    fn test_command_for() {
+        let openers = openers(&[
+            ("video", "mpv {url}"),
+            ("PDF", "zathura"),
+            ("arxiv.org", "zathura {url}"),
+            ("export.arxiv.org", "curl -O {url}"),
+        ]);
+        let link = |url: &str| Link::new(url);
+
+        assert_eq!(
+            openers.command_for(&link("https://www.youtube.com/watch?v=abc")),
+            Some("mpv {url}")
+        );
+        assert_eq!(
+            openers.command_for(&link("https://example.com/paper.pdf")),
+            Some("zathura")
+        );
+        assert_eq!(
+            openers.command_for(&link("https://arxiv.org/abs/1234")),
+            Some("zathura {url}")
+        );
+        assert_eq!(
+            openers.command_for(&link("https://export.arxiv.org/abs/1234")),
+            Some("curl -O {url}")
+        );
+        // Nothing matches and there's no default: the browser
+        assert_eq!(openers.command_for(&link("https://example.com")), None);
+
+        let with_default = self::openers(&[("default", "firefox --new-tab")]);
+        assert_eq!(
+            with_default.command_for(&link("https://example.com")),
+            Some("firefox --new-tab")
+        );
+    }
+
+    #[test]
+    fn test_command_line() {
+        assert_eq!(
+            command_line("mpv --fs {url}", "https://example.com/v"),
+            ["mpv", "--fs", "https://example.com/v"]
+        );
+        assert_eq!(
+            command_line("zathura", "https://example.com/a.pdf"),
+            ["zathura", "https://example.com/a.pdf"]
+        );
+        assert_eq!(
+            command_line("open-in --url={url}", "https://example.com"),
+            ["open-in", "--url=https://example.com"]
+        );
+    }
+}
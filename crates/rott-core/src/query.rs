@@ -0,0 +1,323 @@
+//! Search query operators
+//!
+//! Besides plain text, a search can contain:
+//!
+//! - `author:name` (or `author:"Full Name"`): links with an author
+//!   containing the name
+//! - `has:paywall` / `-has:paywall`: links that are (or aren't) behind a
+//!   paywall or login
+//! - `lang:de` / `-lang:de`: links that are (or aren't) in a language, by
+//!   code or English name (`lang:german`)
+//! - `via:name` (or `via:"HN thread"`): links recommended by someone, or
+//!   found somewhere, containing the name
+//!
+//! Operators are combined with AND; the remaining words are matched as one
+//! piece of text by the caller.
+
+use uuid::Uuid;
+
+use crate::language::normalize_language;
+use crate::models::{Link, Note};
+
+/// A link found by a search, with the note that matched (if any)
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub link: Link,
+    /// The first note whose title or body contains the text
+    pub note_id: Option<Uuid>,
+}
+
+/// A link property `has:` can test
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HasFlag {
+    /// Behind a paywall or login
+    Paywall,
+}
+
+impl HasFlag {
+    /// Parse a `has:` value
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "paywall" | "paywalled" => Some(HasFlag::Paywall),
+            _ => None,
+        }
+    }
+
+    /// Whether a link has this property
+    pub fn matches(self, link: &Link) -> bool {
+        match self {
+            HasFlag::Paywall => link.paywalled,
+        }
+    }
+}
+
+/// A search split into operators and text
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    /// Lowercased author names (`author:`)
+    pub authors: Vec<String>,
+    /// Properties links must have (`has:`), or must not have (`-has:`)
+    pub flags: Vec<(HasFlag, bool)>,
+    /// Language codes links must be in (`lang:`), or must not be in (`-lang:`)
+    pub languages: Vec<(String, bool)>,
+    /// Lowercased sources (`via:`), matched against who recommended a link
+    /// and where it was found
+    pub via: Vec<String>,
+    /// Everything else, as typed
+    pub text: String,
+}
+
+impl SearchQuery {
+    /// Split a query into operators and text
+    ///
+    /// `has:` and `lang:` values that aren't known are left in the text.
+    pub fn parse(query: &str) -> Self {
+        let mut parsed = Self::default();
+        let mut text = Vec::new();
+        let mut rest = query.trim();
+        while !rest.is_empty() {
+            if let Some(value) = rest.strip_prefix("author:") {
+                let (name, remaining) = quoted_value(value);
+                if !name.trim().is_empty() {
+                    parsed.authors.push(name.trim().to_lowercase());
+                }
+                rest = remaining.trim_start();
+                continue;
+            }
+            if let Some(value) = rest.strip_prefix("via:") {
+                let (name, remaining) = quoted_value(value);
+                if !name.trim().is_empty() {
+                    parsed.via.push(name.trim().to_lowercase());
+                }
+                rest = remaining.trim_start();
+                continue;
+            }
+
+            let (word, remaining) = rest.split_once(' ').unwrap_or((rest, ""));
+            let (negated, operator) = match word.strip_prefix('-') {
+                Some(operator) => (true, operator),
+                None => (false, word),
+            };
+            if let Some(flag) = operator.strip_prefix("has:").and_then(HasFlag::parse) {
+                parsed.flags.push((flag, !negated));
+            } else if let Some(language) =
+                operator.strip_prefix("lang:").and_then(normalize_language)
+            {
+                parsed.languages.push((language, !negated));
+            } else {
+                text.push(word);
+            }
+            rest = remaining.trim_start();
+        }
+        parsed.text = text.join(" ");
+        parsed
+    }
+
+    /// Whether a link satisfies every operator (the text isn't checked)
+    pub fn matches_operators(&self, link: &Link) -> bool {
+        self.authors.iter().all(|author| {
+            link.author
+                .iter()
+                .any(|a| a.to_lowercase().contains(author))
+        }) && self
+            .flags
+            .iter()
+            .all(|(flag, wanted)| flag.matches(link) == *wanted)
+            && self.languages.iter().all(|(language, wanted)| {
+                (link.language.as_deref() == Some(language.as_str())) == *wanted
+            })
+            && self.via.iter().all(|via| {
+                [&link.via, &link.source_context]
+                    .into_iter()
+                    .flatten()
+                    .any(|source| source.to_lowercase().contains(via))
+            })
+    }
+
+    /// Whether a link satisfies every operator and contains the text in its
+    /// title, URL, description, or one of its notes (case-insensitively)
+    pub fn matches(&self, link: &Link) -> bool {
+        let text = self.text.to_lowercase();
+        self.matches_operators(link)
+            && (link.title.to_lowercase().contains(&text)
+                || link.url.to_lowercase().contains(&text)
+                || link
+                    .description
+                    .as_ref()
+                    .is_some_and(|d| d.to_lowercase().contains(&text))
+                || self.matching_note(link).is_some())
+    }
+
+    /// The first of a link's notes whose title or body contains the text
+    /// (case-insensitively); None without text, since every note would match
+    pub fn matching_note<'a>(&self, link: &'a Link) -> Option<&'a Note> {
+        let text = self.text.trim().to_lowercase();
+        if text.is_empty() {
+            return None;
+        }
+        link.notes.iter().find(|note| {
+            note.body.to_lowercase().contains(&text)
+                || note
+                    .title
+                    .as_ref()
+                    .is_some_and(|t| t.to_lowercase().contains(&text))
+        })
+    }
+
+    /// The link as a hit, if it matches (see [`matches`](Self::matches))
+    pub fn hit(&self, link: Link) -> Option<SearchHit> {
+        if !self.matches(&link) {
+            return None;
+        }
+        let note_id = self.matching_note(&link).map(|note| note.id);
+        Some(SearchHit { link, note_id })
+    }
+}
+
+/// A combination of tags links must have, for scripting
+/// (`rott tags links`)
+///
+/// Tags match ignoring case, as elsewhere. An empty list doesn't restrict
+/// anything, so an empty filter matches every link.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagFilter {
+    /// Tags a link must have every one of
+    pub all_of: Vec<String>,
+    /// Tags a link must have at least one of
+    pub any_of: Vec<String>,
+    /// Tags a link must have none of
+    pub none_of: Vec<String>,
+}
+
+impl TagFilter {
+    /// Whether the filter has no tags at all
+    pub fn is_empty(&self) -> bool {
+        self.all_of.is_empty() && self.any_of.is_empty() && self.none_of.is_empty()
+    }
+
+    /// Whether a link's tags satisfy the filter
+    pub fn matches(&self, link: &Link) -> bool {
+        self.all_of.iter().all(|tag| link.has_tag(tag))
+            && (self.any_of.is_empty() || self.any_of.iter().any(|tag| link.has_tag(tag)))
+            && !self.none_of.iter().any(|tag| link.has_tag(tag))
+    }
+}
+
+/// An operator's value (a word, or a quoted phrase) and the rest of the query
+fn quoted_value(value: &str) -> (&str, &str) {
+    match value.strip_prefix('"') {
+        Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+        None => value.split_once(' ').unwrap_or((value, "")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_filter() {
+        let tagged = |tags: &[&str]| {
+            let mut link = Link::new("https://example.com");
+            link.set_tags(tags.iter().map(|t| t.to_string()).collect());
+            link
+        };
+        let tags = |list: &[&str]| list.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        let filter = TagFilter {
+            all_of: tags(&["rust", "async"]),
+            any_of: Vec::new(),
+            none_of: tags(&["video"]),
+        };
+        assert!(filter.matches(&tagged(&["Rust", "async", "tokio"])));
+        assert!(!filter.matches(&tagged(&["rust"])));
+        assert!(!filter.matches(&tagged(&["rust", "async", "video"])));
+
+        let filter = TagFilter {
+            any_of: tags(&["go", "zig"]),
+            ..TagFilter::default()
+        };
+        assert!(filter.matches(&tagged(&["zig"])));
+        assert!(!filter.matches(&tagged(&["rust"])));
+        assert!(TagFilter::default().matches(&tagged(&[])));
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let query = SearchQuery::parse("a author:x -has:paywall b has:nothing");
+        assert_eq!(query.authors, vec!["x"]);
+        assert_eq!(query.flags, vec![(HasFlag::Paywall, false)]);
+        assert_eq!(query.text, "a b has:nothing");
+
+        let query = SearchQuery::parse("author:\"Jane Doe\" has:paywall");
+        assert_eq!(query.authors, vec!["jane doe"]);
+        assert_eq!(query.flags, vec![(HasFlag::Paywall, true)]);
+        assert!(query.text.is_empty());
+    }
+
+    #[test]
+    fn test_matches_operators() {
+        let mut link = Link::new("https://news.example.com/story");
+        link.set_author(vec!["Jane Doe".to_string()]);
+        assert!(SearchQuery::parse("author:jane -has:paywall").matches_operators(&link));
+        assert!(!SearchQuery::parse("has:paywall").matches_operators(&link));
+
+        link.paywalled = true;
+        assert!(SearchQuery::parse("has:paywall").matches_operators(&link));
+        assert!(!SearchQuery::parse("author:john has:paywall").matches_operators(&link));
+    }
+
+    #[test]
+    fn test_lang_operator() {
+        let query = SearchQuery::parse("lang:en-US -lang:German lang:?? rust");
+        assert_eq!(
+            query.languages,
+            vec![("en".to_string(), true), ("de".to_string(), false)]
+        );
+        assert_eq!(query.text, "lang:?? rust");
+
+        let mut link = Link::new("https://example.com");
+        assert!(!SearchQuery::parse("lang:en").matches_operators(&link));
+        assert!(SearchQuery::parse("-lang:en").matches_operators(&link));
+        link.language = Some("en".to_string());
+        assert!(SearchQuery::parse("lang:english -lang:de").matches_operators(&link));
+        assert!(!SearchQuery::parse("lang:de").matches_operators(&link));
+    }
+
+    #[test]
+    fn test_via_operator() {
+        let query = SearchQuery::parse("via:\"HN thread\" rust via:simon");
+        assert_eq!(query.via, vec!["hn thread", "simon"]);
+        assert_eq!(query.text, "rust");
+
+        let mut link = Link::new("https://example.com");
+        assert!(!SearchQuery::parse("via:simon").matches_operators(&link));
+        link.set_source(
+            Some("Simon".to_string()),
+            Some("HN thread https://news.ycombinator.com/item?id=1".to_string()),
+        );
+        assert!(SearchQuery::parse("via:simon via:hn").matches_operators(&link));
+        assert!(!SearchQuery::parse("via:lobsters").matches_operators(&link));
+    }
+
+    #[test]
+    fn test_matches_notes() {
+        let mut link = Link::new("https://example.com/post");
+        let mut first = Note::new("Nothing to see");
+        first.title = Some("Borrow checker".to_string());
+        let second = Note::new("Compare with the Borrow Checker in chapter 4");
+        link.notes = vec![first.clone(), second];
+
+        let query = SearchQuery::parse("borrow checker");
+        assert!(query.matches(&link));
+        let hit = query.hit(link.clone()).unwrap();
+        assert_eq!(hit.note_id, Some(first.id));
+
+        assert!(SearchQuery::parse("chapter 4").matches(&link));
+        assert!(!SearchQuery::parse("chapter 5").matches(&link));
+
+        // The link's own fields still match, with no note to point at
+        let hit = SearchQuery::parse("example").hit(link.clone()).unwrap();
+        assert_eq!(hit.note_id, None);
+        assert_eq!(SearchQuery::parse("").matching_note(&link), None);
+    }
+}
@@ -0,0 +1,324 @@
+//! Reading sessions
+//!
+//! A reading session (`:read` in the TUI) works through the unread links,
+//! recording each link opened or cleared (deleted) along the way. Time spent
+//! on a link is estimated as the time until the next thing happened in the
+//! session, capped at [`MAX_READ_MINUTES`] so a session left open overnight
+//! doesn't count as reading.
+//!
+//! Sessions are kept on this machine only, in `sessions.json` in the data
+//! directory, and summarized by `rott stats` (and `:stats`).
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::Config;
+
+/// Longest time counted for one link, in minutes
+pub const MAX_READ_MINUTES: i64 = 60;
+
+/// Sessions older than this are dropped from the log, in days
+const KEEP_DAYS: i64 = 365;
+
+/// What happened to a link during a session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadAction {
+    /// Opened in the browser
+    Opened,
+    /// Deleted from the collection
+    Cleared,
+}
+
+/// A link opened or cleared during a session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadEvent {
+    pub link_id: Uuid,
+    pub action: ReadAction,
+    pub at: DateTime<Utc>,
+}
+
+/// One sitting of working through the unread links
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadingSession {
+    pub started_at: DateTime<Utc>,
+    #[serde(default)]
+    pub ended_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub events: Vec<ReadEvent>,
+}
+
+impl ReadingSession {
+    /// Start a session
+    pub fn start(now: DateTime<Utc>) -> Self {
+        Self {
+            started_at: now,
+            ended_at: None,
+            events: Vec::new(),
+        }
+    }
+
+    /// Record a link being opened or cleared
+    pub fn record(&mut self, link_id: Uuid, action: ReadAction, at: DateTime<Utc>) {
+        self.events.push(ReadEvent {
+            link_id,
+            action,
+            at,
+        });
+    }
+
+    /// End the session
+    pub fn end(&mut self, now: DateTime<Utc>) {
+        self.ended_at = Some(now);
+    }
+
+    /// Distinct links opened
+    pub fn links_read(&self) -> Vec<Uuid> {
+        let mut read: Vec<Uuid> = Vec::new();
+        for event in &self.events {
+            if event.action == ReadAction::Opened && !read.contains(&event.link_id) {
+                read.push(event.link_id);
+            }
+        }
+        read
+    }
+
+    /// Number of links cleared
+    pub fn links_cleared(&self) -> usize {
+        self.events
+            .iter()
+            .filter(|e| e.action == ReadAction::Cleared)
+            .count()
+    }
+
+    /// Estimated time spent on each link read, in order
+    ///
+    /// A link opened more than once gets the time of every opening.
+    pub fn reading_times(&self, now: DateTime<Utc>) -> Vec<(Uuid, Duration)> {
+        let end = self.ended_at.unwrap_or(now);
+        let mut times: Vec<(Uuid, Duration)> = Vec::new();
+        for (i, event) in self.events.iter().enumerate() {
+            if event.action != ReadAction::Opened {
+                continue;
+            }
+            let until = self.events.get(i + 1).map_or(end, |next| next.at);
+            let spent = (until - event.at)
+                .max(Duration::zero())
+                .min(Duration::minutes(MAX_READ_MINUTES));
+            match times.iter_mut().find(|(id, _)| *id == event.link_id) {
+                Some((_, total)) => *total += spent,
+                None => times.push((event.link_id, spent)),
+            }
+        }
+        times
+    }
+
+    /// Total estimated reading time
+    pub fn reading_time(&self, now: DateTime<Utc>) -> Duration {
+        self.reading_times(now)
+            .into_iter()
+            .fold(Duration::zero(), |total, (_, spent)| total + spent)
+    }
+}
+
+/// Reading over a period
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReadingStats {
+    /// Start of the period
+    pub since: DateTime<Utc>,
+    /// Sessions started in the period
+    pub sessions: usize,
+    /// Links read (each counted once per session)
+    pub articles: usize,
+    /// Links cleared
+    pub cleared: usize,
+    /// Median minutes per link read (None if nothing was read)
+    pub median_minutes: Option<i64>,
+    /// Total minutes read
+    pub total_minutes: i64,
+}
+
+impl ReadingStats {
+    /// One-line summary, e.g. "Read 12 articles this week, median 6 min"
+    pub fn summary(&self, period: &str) -> String {
+        let mut summary = match self.median_minutes {
+            Some(median) if self.articles > 0 => format!(
+                "Read {} {} {}, median {} min",
+                self.articles,
+                if self.articles == 1 {
+                    "article"
+                } else {
+                    "articles"
+                },
+                period,
+                median
+            ),
+            _ => format!("Nothing read {}", period),
+        };
+        if self.cleared > 0 {
+            summary.push_str(&format!(", {} cleared", self.cleared));
+        }
+        summary
+    }
+}
+
+/// Past reading sessions
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SessionLog {
+    sessions: Vec<ReadingSession>,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl SessionLog {
+    /// Load the log from the data directory (empty if there isn't one yet)
+    pub fn load(config: &Config) -> Result<Self> {
+        let path = config.sessions_log_path();
+        let mut log = if path.exists() {
+            let json = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            serde_json::from_str(&json)
+                .with_context(|| format!("Failed to parse {}", path.display()))?
+        } else {
+            Self::default()
+        };
+        log.path = Some(path);
+        Ok(log)
+    }
+
+    /// Save the log
+    pub fn save(&self) -> Result<()> {
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json).context("Failed to save reading sessions")?;
+        Ok(())
+    }
+
+    /// All sessions, oldest first
+    pub fn sessions(&self) -> &[ReadingSession] {
+        &self.sessions
+    }
+
+    /// Add a finished session, dropping ones older than a year
+    ///
+    /// Sessions where nothing happened aren't kept.
+    pub fn push(&mut self, session: ReadingSession, now: DateTime<Utc>) {
+        self.sessions
+            .retain(|s| s.started_at >= now - Duration::days(KEEP_DAYS));
+        if !session.events.is_empty() {
+            self.sessions.push(session);
+        }
+    }
+
+    /// Summarize the sessions started since `since`
+    pub fn stats(&self, since: DateTime<Utc>, now: DateTime<Utc>) -> ReadingStats {
+        let sessions: Vec<&ReadingSession> = self
+            .sessions
+            .iter()
+            .filter(|s| s.started_at >= since)
+            .collect();
+
+        let mut minutes: Vec<i64> = sessions
+            .iter()
+            .flat_map(|s| s.reading_times(now))
+            .map(|(_, spent)| spent.num_minutes())
+            .collect();
+        minutes.sort_unstable();
+
+        ReadingStats {
+            since,
+            sessions: sessions.len(),
+            articles: sessions.iter().map(|s| s.links_read().len()).sum(),
+            cleared: sessions.iter().map(|s| s.links_cleared()).sum(),
+            median_minutes: median(&minutes),
+            total_minutes: minutes.iter().sum(),
+        }
+    }
+}
+
+/// Median of sorted values (the lower middle for an even count)
+fn median(sorted: &[i64]) -> Option<i64> {
+    if sorted.is_empty() {
+        None
+    } else {
+        Some(sorted[(sorted.len() - 1) / 2])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reading_times_are_capped() {
+        let start = Utc::now();
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut session = ReadingSession::start(start);
+        session.record(a, ReadAction::Opened, start);
+        session.record(b, ReadAction::Opened, start + Duration::minutes(4));
+        session.record(c, ReadAction::Cleared, start + Duration::minutes(100));
+        session.record(a, ReadAction::Opened, start + Duration::minutes(101));
+        session.end(start + Duration::minutes(103));
+
+        assert_eq!(session.links_read(), vec![a, b]);
+        assert_eq!(session.links_cleared(), 1);
+        assert_eq!(
+            session.reading_times(start),
+            vec![
+                (a, Duration::minutes(6)),
+                (b, Duration::minutes(MAX_READ_MINUTES))
+            ]
+        );
+        assert_eq!(session.reading_time(start), Duration::minutes(66));
+    }
+
+    #[test]
+    fn test_stats_for_period() {
+        let now = Utc::now();
+        let mut log = SessionLog::default();
+
+        let mut old = ReadingSession::start(now - Duration::days(10));
+        old.record(Uuid::new_v4(), ReadAction::Opened, old.started_at);
+        old.end(old.started_at + Duration::minutes(30));
+        log.push(old, now);
+
+        let mut recent = ReadingSession::start(now - Duration::hours(1));
+        let start = recent.started_at;
+        for minutes in [0, 2, 8] {
+            recent.record(
+                Uuid::new_v4(),
+                ReadAction::Opened,
+                start + Duration::minutes(minutes),
+            );
+        }
+        recent.end(start + Duration::minutes(18));
+        log.push(recent, now);
+
+        // Nothing happened; not kept
+        log.push(ReadingSession::start(now), now);
+        assert_eq!(log.sessions().len(), 2);
+
+        let stats = log.stats(now - Duration::days(7), now);
+        assert_eq!(stats.sessions, 1);
+        assert_eq!(stats.articles, 3);
+        assert_eq!(stats.median_minutes, Some(6));
+        assert_eq!(stats.total_minutes, 18);
+
+        assert_eq!(
+            stats.summary("this week"),
+            "Read 3 articles this week, median 6 min"
+        );
+
+        let empty = SessionLog::default().stats(now - Duration::days(7), now);
+        assert_eq!(empty.articles, 0);
+        assert_eq!(empty.median_minutes, None);
+        assert_eq!(empty.summary("this week"), "Nothing read this week");
+    }
+}
@@ -0,0 +1,359 @@
+//! Reports of what changed in the collection
+//!
+//! A report covers a period (a week, for the scheduled `report` job; see
+//! [`crate::maintenance`]) and is built from the event log (see
+//! [`crate::events`]): the links added, archived and deleted, tags used for
+//! the first time, notes added and removed, and how many changes each
+//! device made.
+//!
+//! Devices are known by the name saved with each change (`device_name`, or
+//! the host name; see [`RottDocument::commit_as`](crate::RottDocument::commit_as)).
+//! Changes made before names were recorded, or synced from other Automerge
+//! clients, have neither a device nor a time: they count as history before
+//! the period.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::events::{EventKind, StoreEvent};
+
+/// Device name for changes that didn't record one
+pub const UNKNOWN_DEVICE: &str = "unknown device";
+
+/// A link mentioned in a report
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReportLink {
+    pub id: Uuid,
+    pub title: String,
+    pub url: String,
+}
+
+/// Changes one device made during the period
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DeviceActivity {
+    /// Changes to links and notes
+    pub changes: usize,
+    /// When the last of them was made
+    pub last_change_at: Option<DateTime<Utc>>,
+}
+
+/// What changed in the collection during a period
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChangeReport {
+    /// Start of the period
+    pub since: DateTime<Utc>,
+    /// End of the period
+    pub until: DateTime<Utc>,
+    /// Links saved for the first time
+    pub added: Vec<ReportLink>,
+    /// Links that existed before and were changed
+    pub changed: usize,
+    /// Links moved to the archives
+    pub archived: Vec<ReportLink>,
+    /// Links deleted (and not archived)
+    pub deleted: Vec<ReportLink>,
+    /// Tags used for the first time
+    pub new_tags: Vec<String>,
+    pub notes_added: usize,
+    pub notes_changed: usize,
+    pub notes_removed: usize,
+    /// Changes by device name
+    pub devices: BTreeMap<String, DeviceActivity>,
+}
+
+impl ChangeReport {
+    /// Whether nothing changed during the period
+    pub fn is_empty(&self) -> bool {
+        self.devices.is_empty()
+    }
+
+    /// The report as Markdown
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "# Collection report, {} to {}\n",
+            self.since.format("%Y-%m-%d"),
+            self.until.format("%Y-%m-%d")
+        );
+        if self.is_empty() {
+            out.push_str("Nothing changed.\n");
+            return out;
+        }
+
+        let _ = writeln!(
+            out,
+            "- {} link(s) added, {} changed, {} archived, {} deleted",
+            self.added.len(),
+            self.changed,
+            self.archived.len(),
+            self.deleted.len()
+        );
+        let _ = writeln!(
+            out,
+            "- {} note(s) added, {} changed, {} removed",
+            self.notes_added, self.notes_changed, self.notes_removed
+        );
+        if !self.new_tags.is_empty() {
+            let _ = writeln!(out, "- New tags: {}", self.new_tags.join(", "));
+        }
+
+        for (heading, links) in [
+            ("Added", &self.added),
+            ("Archived", &self.archived),
+            ("Deleted", &self.deleted),
+        ] {
+            if links.is_empty() {
+                continue;
+            }
+            let _ = writeln!(out, "\n## {}\n", heading);
+            for link in links {
+                let _ = writeln!(out, "- [{}]({})", escape_title(&link.title), link.url);
+            }
+        }
+
+        out.push_str("\n## Devices\n\n| Device | Changes | Last change |\n|---|---|---|\n");
+        for (device, activity) in &self.devices {
+            let last = activity
+                .last_change_at
+                .map(|at| at.format("%Y-%m-%d %H:%M UTC").to_string())
+                .unwrap_or_default();
+            let _ = writeln!(out, "| {} | {} | {} |", device, activity.changes, last);
+        }
+        out
+    }
+}
+
+/// Square brackets in a title would end the Markdown link text early
+fn escape_title(title: &str) -> String {
+    title.replace('[', "\\[").replace(']', "\\]")
+}
+
+/// Builds a [`ChangeReport`] from the event log
+///
+/// Every event is applied, from the start of the history: earlier ones
+/// tell which links and tags are new, and the titles of deleted links.
+#[derive(Debug)]
+pub struct ReportBuilder {
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    /// Every link seen, with its latest title and URL
+    links: HashMap<Uuid, ReportLink>,
+    /// Links that exist now
+    present: HashSet<Uuid>,
+    seen_tags: BTreeSet<String>,
+    seen_notes: HashSet<Uuid>,
+    added: Vec<Uuid>,
+    changed: BTreeSet<Uuid>,
+    deleted: Vec<Uuid>,
+    new_tags: Vec<String>,
+    notes_added: usize,
+    notes_changed: usize,
+    notes_removed: usize,
+    devices: BTreeMap<String, DeviceActivity>,
+    /// The change the last event came from (a change can make several)
+    last_change: Option<String>,
+}
+
+impl ReportBuilder {
+    /// Start a report of the changes made from `since` up to `until`
+    pub fn new(since: DateTime<Utc>, until: DateTime<Utc>) -> Self {
+        Self {
+            since,
+            until,
+            links: HashMap::new(),
+            present: HashSet::new(),
+            seen_tags: BTreeSet::new(),
+            seen_notes: HashSet::new(),
+            added: Vec::new(),
+            changed: BTreeSet::new(),
+            deleted: Vec::new(),
+            new_tags: Vec::new(),
+            notes_added: 0,
+            notes_changed: 0,
+            notes_removed: 0,
+            devices: BTreeMap::new(),
+            last_change: None,
+        }
+    }
+
+    /// Apply the next event
+    pub fn apply(&mut self, event: &StoreEvent) {
+        let in_period = event
+            .at
+            .is_some_and(|at| at >= self.since && at < self.until);
+
+        if in_period && self.last_change.as_ref() != Some(&event.change) {
+            let device = event.device.as_deref().unwrap_or(UNKNOWN_DEVICE);
+            let activity = self.devices.entry(device.to_string()).or_default();
+            activity.changes += 1;
+            activity.last_change_at = activity.last_change_at.max(event.at);
+        }
+        self.last_change = Some(event.change.clone());
+
+        match &event.kind {
+            EventKind::LinkUpserted { link } => {
+                let entry = ReportLink {
+                    id: link.id,
+                    title: link.title.clone(),
+                    url: link.url.clone(),
+                };
+                let first_seen = self.links.insert(link.id, entry).is_none();
+                self.present.insert(link.id);
+                for tag in &link.tags {
+                    if self.seen_tags.insert(tag.clone()) && in_period {
+                        self.new_tags.push(tag.clone());
+                    }
+                }
+                if in_period {
+                    if first_seen {
+                        self.added.push(link.id);
+                    } else if !self.added.contains(&link.id) {
+                        self.changed.insert(link.id);
+                    }
+                }
+            }
+            EventKind::LinkDeleted { link_id } => {
+                self.present.remove(link_id);
+                if in_period {
+                    self.deleted.push(*link_id);
+                }
+            }
+            EventKind::NoteUpserted { note, .. } => {
+                let first_seen = self.seen_notes.insert(note.id);
+                if in_period {
+                    if first_seen {
+                        self.notes_added += 1;
+                    } else {
+                        self.notes_changed += 1;
+                    }
+                }
+            }
+            EventKind::NoteDeleted { .. } => {
+                if in_period {
+                    self.notes_removed += 1;
+                }
+            }
+        }
+    }
+
+    /// Finish the report
+    ///
+    /// `archived` are the IDs of links in the archives: a deleted link
+    /// that's among them was archived. Links deleted and saved again
+    /// during the period aren't listed as deleted.
+    pub fn finish(self, archived: &HashSet<Uuid>) -> ChangeReport {
+        let link = |id: &Uuid| {
+            self.links.get(id).cloned().unwrap_or(ReportLink {
+                id: *id,
+                title: String::new(),
+                url: String::new(),
+            })
+        };
+        let mut gone: Vec<Uuid> = self
+            .deleted
+            .iter()
+            .filter(|id| !self.present.contains(id))
+            .copied()
+            .collect();
+        gone.dedup();
+        let (archived_links, deleted): (Vec<Uuid>, Vec<Uuid>) =
+            gone.into_iter().partition(|id| archived.contains(id));
+
+        ChangeReport {
+            since: self.since,
+            until: self.until,
+            added: self.added.iter().map(link).collect(),
+            changed: self
+                .changed
+                .iter()
+                .filter(|id| self.present.contains(id))
+                .count(),
+            archived: archived_links.iter().map(link).collect(),
+            deleted: deleted.iter().map(link).collect(),
+            new_tags: self.new_tags,
+            notes_added: self.notes_added,
+            notes_changed: self.notes_changed,
+            notes_removed: self.notes_removed,
+            devices: self.devices,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Link;
+    use chrono::Duration;
+
+    fn event(change: &str, at: DateTime<Utc>, device: Option<&str>, kind: EventKind) -> StoreEvent {
+        StoreEvent {
+            change: change.to_string(),
+            at: Some(at),
+            device: device.map(str::to_string),
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let until = Utc::now();
+        let since = until - Duration::days(7);
+        let before = since - Duration::days(1);
+        let during = since + Duration::days(1);
+
+        let mut old = Link::new("https://example.com/old");
+        old.set_tags(vec!["rust".to_string()]);
+        let archived = Link::new("https://example.com/archived");
+        let mut new = Link::new("https://example.com/new");
+        new.set_title("New [draft]");
+        new.set_tags(vec!["rust".to_string(), "databases".to_string()]);
+        let upsert = |link: &Link| EventKind::LinkUpserted { link: link.clone() };
+
+        let mut builder = ReportBuilder::new(since, until);
+        for event in [
+            event("a", before, None, upsert(&old)),
+            event("a", before, None, upsert(&archived)),
+            event("b", during, Some("laptop"), upsert(&new)),
+            event("c", during, Some("laptop"), upsert(&old)),
+            event(
+                "d",
+                during,
+                Some("phone"),
+                EventKind::LinkDeleted {
+                    link_id: archived.id,
+                },
+            ),
+        ] {
+            builder.apply(&event);
+        }
+        let report = builder.finish(&HashSet::from([archived.id]));
+
+        assert_eq!(report.added.len(), 1);
+        assert_eq!(report.added[0].id, new.id);
+        assert_eq!(report.changed, 1);
+        assert_eq!(report.archived[0].url, archived.url);
+        assert!(report.deleted.is_empty());
+        assert_eq!(report.new_tags, ["databases"]);
+        assert_eq!(report.devices["laptop"].changes, 2);
+        assert_eq!(report.devices["phone"].changes, 1);
+        assert!(!report.devices.contains_key(UNKNOWN_DEVICE));
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("- 1 link(s) added, 1 changed, 1 archived, 0 deleted"));
+        assert!(markdown.contains("- [New \\[draft\\]](https://example.com/new)"));
+        assert!(markdown.contains("| laptop | 2 |"));
+    }
+
+    #[test]
+    fn test_empty_report() {
+        let until = Utc::now();
+        let report = ReportBuilder::new(until - Duration::days(7), until).finish(&HashSet::new());
+        assert!(report.is_empty());
+        assert!(report.to_markdown().contains("Nothing changed."));
+    }
+}
@@ -0,0 +1,420 @@
+//! Search providers
+//!
+//! How the store finds links matching a search is chosen with the
+//! `search_provider` setting:
+//!
+//! - `scan` (default): checks every link in the document, matching the text
+//!   anywhere in titles, URLs, descriptions, and notes. Nothing to build or
+//!   keep up to date, which is fast enough for most collections.
+//! - `sqlite`: keeps an SQLite FTS5 index in the collection's directory
+//!   (`search.sqlite`). Text matches from the start of words (`concur`
+//!   finds "concurrency", `currency` doesn't), and the best matches come
+//!   first. Only in builds with the `sqlite-search` feature; other builds
+//!   fall back to `scan`.
+//!
+//! A provider other than `scan` is a [`SearchProvider`]: an index that
+//! answers a query with the IDs of the links it matches. The index records
+//! the document heads it's up to date with; before each search, the store
+//! sends it only the links changed since those heads (see
+//! [`RottDocument::links_changed_since`]), or every link if they can't be
+//! diffed against, such as for a new index.
+//!
+//! Every provider applies the query's operators (see [`crate::query`]) the
+//! same way. Other providers (such as semantic search) can be set on a
+//! store with [`Store::set_search_provider`](crate::Store::set_search_provider).
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use automerge::ChangeHash;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::document::RottDocument;
+use crate::models::Link;
+use crate::query::SearchQuery;
+
+/// A search index, kept up to date with the document by the store
+pub trait SearchProvider: Send + Sync {
+    /// Name used in the config file
+    fn name(&self) -> &'static str;
+
+    /// Document heads the index was last brought up to (None if it hasn't
+    /// been built)
+    fn indexed_heads(&self) -> Result<Option<Vec<ChangeHash>>>;
+
+    /// Bring the index up to the document at `heads`
+    fn update(&self, heads: &[ChangeHash], update: IndexUpdate) -> Result<()>;
+
+    /// IDs of the links whose text matches the query, best first
+    ///
+    /// None if the query has nothing for the index to go on (only
+    /// operators), in which case every link is checked instead.
+    fn search(&self, query: &SearchQuery) -> Result<Option<Vec<Uuid>>>;
+}
+
+/// What to bring an index up to date with
+#[derive(Debug, Clone)]
+pub enum IndexUpdate {
+    /// Every link in the document: replace the whole index
+    Rebuild(Vec<Link>),
+    /// Links changed since the indexed heads (None: deleted)
+    Changes(Vec<(Uuid, Option<Link>)>),
+}
+
+/// Bring an index up to date with the document, sending it only the links
+/// changed since its heads
+pub(crate) fn update_index(index: &dyn SearchProvider, doc: &mut RottDocument) -> Result<()> {
+    let mut heads = doc.heads();
+    heads.sort();
+    let changed = match index.indexed_heads()? {
+        Some(mut indexed) => {
+            indexed.sort();
+            if indexed == heads {
+                return Ok(());
+            }
+            doc.links_changed_since(&indexed)?
+        }
+        None => None,
+    };
+    let update = match changed {
+        Some(ids) => IndexUpdate::Changes(
+            ids.into_iter()
+                .map(|id| Ok((id, doc.get_link(id)?)))
+                .collect::<Result<_>>()?,
+        ),
+        None => IndexUpdate::Rebuild(doc.get_all_links()?),
+    };
+    index.update(&heads, update)
+}
+
+/// The search providers `search_provider` can choose
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchBackend {
+    /// Check every link (no provider)
+    #[default]
+    Scan,
+    /// An SQLite FTS5 index (`SqliteSearch`, with the `sqlite-search` feature)
+    Sqlite,
+}
+
+impl SearchBackend {
+    /// Parse a provider's name (as in the config file)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "scan" => Some(SearchBackend::Scan),
+            "sqlite" => Some(SearchBackend::Sqlite),
+            _ => None,
+        }
+    }
+
+    /// Name used in the config file
+    pub fn name(self) -> &'static str {
+        match self {
+            SearchBackend::Scan => "scan",
+            SearchBackend::Sqlite => "sqlite",
+        }
+    }
+
+    /// Whether this build includes the provider
+    pub fn is_available(self) -> bool {
+        match self {
+            SearchBackend::Scan => true,
+            SearchBackend::Sqlite => cfg!(feature = "sqlite-search"),
+        }
+    }
+}
+
+/// The provider configured with `search_provider` (None for `scan`)
+///
+/// Falls back to scanning (with a warning) if the configured provider
+/// isn't in this build or its index can't be opened.
+pub fn provider_from_config(config: &Config) -> Option<Arc<dyn SearchProvider>> {
+    match config.search_provider {
+        SearchBackend::Scan => None,
+        SearchBackend::Sqlite => sqlite_provider(config),
+    }
+}
+
+#[cfg(feature = "sqlite-search")]
+fn sqlite_provider(config: &Config) -> Option<Arc<dyn SearchProvider>> {
+    let path = config.search_index_path();
+    match SqliteSearch::open(&path) {
+        Ok(provider) => Some(Arc::new(provider)),
+        Err(e) => {
+            warn!(
+                "Failed to open search index {:?}, searching without it: {:#}",
+                path, e
+            );
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "sqlite-search"))]
+fn sqlite_provider(_config: &Config) -> Option<Arc<dyn SearchProvider>> {
+    warn!("search_provider = \"sqlite\" needs a build with the sqlite-search feature; using scan");
+    None
+}
+
+#[cfg(feature = "sqlite-search")]
+pub use sqlite::SqliteSearch;
+
+#[cfg(feature = "sqlite-search")]
+mod sqlite {
+    use std::path::Path;
+    use std::sync::{Mutex, MutexGuard};
+
+    use anyhow::{Context, Result};
+    use automerge::ChangeHash;
+    use rusqlite::{params, Connection, OptionalExtension, Transaction};
+    use uuid::Uuid;
+
+    use super::{IndexUpdate, SearchBackend, SearchProvider};
+    use crate::models::Link;
+    use crate::query::SearchQuery;
+
+    const SCHEMA: &str = "CREATE VIRTUAL TABLE IF NOT EXISTS links_fts USING fts5(
+        id UNINDEXED, title, url, description, notes,
+        tokenize = 'unicode61 remove_diacritics 2'
+    );
+    CREATE TABLE IF NOT EXISTS index_state (key TEXT PRIMARY KEY, value TEXT NOT NULL);";
+
+    /// An SQLite FTS5 index of links' text
+    ///
+    /// The index is only a cache: it's brought up to date with the
+    /// document's changes before each search, and can be deleted at any
+    /// time (it's rebuilt on the next search).
+    pub struct SqliteSearch {
+        conn: Mutex<Connection>,
+    }
+
+    impl SqliteSearch {
+        /// Open (or create) an index file
+        pub fn open(path: &Path) -> Result<Self> {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create {:?}", parent))?;
+            }
+            let conn = Connection::open(path)
+                .with_context(|| format!("Failed to open search index {:?}", path))?;
+            Self::with_connection(conn)
+        }
+
+        /// An index kept in memory, for tests and short-lived stores
+        pub fn in_memory() -> Result<Self> {
+            Self::with_connection(Connection::open_in_memory()?)
+        }
+
+        fn with_connection(conn: Connection) -> Result<Self> {
+            conn.execute_batch(SCHEMA)
+                .context("Failed to create search index")?;
+            Ok(Self {
+                conn: Mutex::new(conn),
+            })
+        }
+
+        fn conn(&self) -> Result<MutexGuard<'_, Connection>> {
+            self.conn
+                .lock()
+                .map_err(|_| anyhow::anyhow!("Search index lock poisoned"))
+        }
+
+        /// Index a link, replacing what was indexed for it
+        fn insert(tx: &Transaction, link: &Link) -> Result<()> {
+            let id = link.id.to_string();
+            let fields = indexed_fields(link);
+            tx.execute("DELETE FROM links_fts WHERE id = ?1", params![id])?;
+            tx.execute(
+                "INSERT INTO links_fts (id, title, url, description, notes)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![id, fields[0], fields[1], fields[2], fields[3]],
+            )?;
+            Ok(())
+        }
+    }
+
+    impl SearchProvider for SqliteSearch {
+        fn name(&self) -> &'static str {
+            SearchBackend::Sqlite.name()
+        }
+
+        fn indexed_heads(&self) -> Result<Option<Vec<ChangeHash>>> {
+            let heads: Option<String> = self
+                .conn()?
+                .query_row(
+                    "SELECT value FROM index_state WHERE key = 'heads'",
+                    [],
+                    |row| row.get(0),
+                )
+                .optional()
+                .context("Failed to read search index")?;
+            // Unreadable heads just mean rebuilding the index
+            Ok(heads.and_then(|heads| {
+                heads
+                    .split_whitespace()
+                    .map(|head| head.parse().ok())
+                    .collect()
+            }))
+        }
+
+        fn update(&self, heads: &[ChangeHash], update: IndexUpdate) -> Result<()> {
+            let mut conn = self.conn()?;
+            let tx = conn.transaction()?;
+            match update {
+                IndexUpdate::Rebuild(links) => {
+                    tx.execute("DELETE FROM links_fts", [])?;
+                    for link in &links {
+                        Self::insert(&tx, link)?;
+                    }
+                }
+                IndexUpdate::Changes(changes) => {
+                    for (id, link) in &changes {
+                        match link {
+                            Some(link) => Self::insert(&tx, link)?,
+                            None => {
+                                tx.execute(
+                                    "DELETE FROM links_fts WHERE id = ?1",
+                                    params![id.to_string()],
+                                )?;
+                            }
+                        }
+                    }
+                }
+            }
+            let heads: Vec<String> = heads.iter().map(ToString::to_string).collect();
+            tx.execute(
+                "INSERT OR REPLACE INTO index_state (key, value) VALUES ('heads', ?1)",
+                params![heads.join(" ")],
+            )?;
+            tx.commit().context("Failed to update search index")?;
+            Ok(())
+        }
+
+        fn search(&self, query: &SearchQuery) -> Result<Option<Vec<Uuid>>> {
+            // Without words there's nothing for the index to narrow down
+            let Some(expression) = match_expression(&query.text) else {
+                return Ok(None);
+            };
+
+            let conn = self.conn()?;
+            let mut stmt =
+                conn.prepare("SELECT id FROM links_fts WHERE links_fts MATCH ?1 ORDER BY rank")?;
+            let ids = stmt
+                .query_map(params![expression], |row| row.get::<_, String>(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()
+                .context("Failed to search index")?;
+            Ok(Some(
+                ids.iter()
+                    .filter_map(|id| Uuid::parse_str(id).ok())
+                    .collect(),
+            ))
+        }
+    }
+
+    /// A link's indexed columns: title, URL, description, and notes
+    fn indexed_fields(link: &Link) -> [String; 4] {
+        let notes = link
+            .notes
+            .iter()
+            .flat_map(|note| note.title.iter().chain(std::iter::once(&note.body)))
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join("\n");
+        [
+            link.title.clone(),
+            link.url.clone(),
+            link.description.clone().unwrap_or_default(),
+            notes,
+        ]
+    }
+
+    /// The text as an FTS5 phrase whose last word matches as a prefix, or
+    /// None if it has no words
+    fn match_expression(text: &str) -> Option<String> {
+        if !text.chars().any(char::is_alphanumeric) {
+            return None;
+        }
+        Some(format!("\"{}\"*", text.trim().replace('"', "\"\"")))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::document::RottDocument;
+        use crate::models::Note;
+        use crate::search::update_index;
+
+        #[test]
+        fn test_sqlite_search_follows_changes() {
+            let index = SqliteSearch::in_memory().unwrap();
+            let mut doc = RottDocument::new();
+            let mut rust = Link::new("https://example.com/rust");
+            rust.set_title("Structured concurrency in Rust");
+            let mut go = Link::new("https://example.com/go");
+            go.set_title("Go channels");
+            go.notes
+                .push(Note::new("Compare with structured concurrency"));
+            doc.add_link(&rust).unwrap();
+            doc.add_link(&go).unwrap();
+
+            let search = |doc: &mut RottDocument, text: &str| {
+                update_index(&index, doc).unwrap();
+                index.search(&SearchQuery::parse(text)).unwrap().unwrap()
+            };
+            let hits = search(&mut doc, "structured concur");
+            assert_eq!(hits.len(), 2);
+            assert!(hits.contains(&go.id));
+            assert!(search(&mut doc, "currency").is_empty());
+
+            // Edited and deleted links are reindexed
+            go.set_title("Go and Rust channels");
+            doc.update_link(&go).unwrap();
+            doc.delete_link(rust.id).unwrap();
+            assert_eq!(search(&mut doc, "rust"), vec![go.id]);
+            assert!(search(&mut doc, "structured concurrency in").is_empty());
+
+            // Only operators: nothing for the index to go on
+            assert!(index
+                .search(&SearchQuery::parse("tag:rust"))
+                .unwrap()
+                .is_none());
+        }
+
+        #[test]
+        fn test_sqlite_search_sends_only_changes() {
+            let index = SqliteSearch::in_memory().unwrap();
+            let mut doc = RottDocument::new();
+            let first = Link::new("https://example.com/first");
+            doc.add_link(&first).unwrap();
+            update_index(&index, &mut doc).unwrap();
+
+            let mut second = Link::new("https://example.com/second");
+            second.set_title("Second");
+            doc.add_link(&second).unwrap();
+            let indexed = index.indexed_heads().unwrap().unwrap();
+            let changed = doc.links_changed_since(&indexed).unwrap().unwrap();
+            assert_eq!(changed.into_iter().collect::<Vec<_>>(), vec![second.id]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_backend_names() {
+        for backend in [SearchBackend::Scan, SearchBackend::Sqlite] {
+            assert_eq!(SearchBackend::parse(backend.name()), Some(backend));
+        }
+        assert_eq!(
+            SearchBackend::parse(" SQLite "),
+            Some(SearchBackend::Sqlite)
+        );
+        assert_eq!(SearchBackend::parse("tantivy"), None);
+    }
+}
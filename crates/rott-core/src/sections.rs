@@ -0,0 +1,164 @@
+//! Sections of long Markdown notes
+//!
+//! Research dumps pasted into a note can run to pages. Past a size (the
+//! `note_section_threshold` config key), the TUI shows such a note as
+//! collapsible sections, and `rott link note split` turns each section into
+//! a note of its own.
+//!
+//! A note's sections start at its top-level headings: those of the lowest
+//! level it uses, so a note whose biggest headings are `##` is split at
+//! each `##`. Text before the first of them is the preamble. Lines inside
+//! fenced code blocks are never headings.
+
+/// A note body split at its top-level headings
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NoteSections {
+    /// Text before the first top-level heading
+    pub preamble: String,
+    /// The sections, in order
+    pub sections: Vec<NoteSection>,
+}
+
+/// A top-level heading and the text under it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteSection {
+    /// The heading's text, without the `#`s
+    pub heading: String,
+    /// The heading's level (1 for `#`)
+    pub level: usize,
+    /// The text under the heading, up to the next top-level heading
+    pub body: String,
+}
+
+impl NoteSections {
+    /// Split a note body at its top-level headings
+    pub fn parse(body: &str) -> Self {
+        let headings = heading_lines(body);
+        let Some(top) = headings.iter().map(|(_, level, _)| *level).min() else {
+            return Self {
+                preamble: body.to_string(),
+                sections: Vec::new(),
+            };
+        };
+
+        let lines: Vec<&str> = body.lines().collect();
+        let starts: Vec<(usize, String)> = headings
+            .into_iter()
+            .filter(|(_, level, _)| *level == top)
+            .map(|(index, _, heading)| (index, heading))
+            .collect();
+
+        let preamble = lines[..starts[0].0].join("\n");
+        let sections = starts
+            .iter()
+            .enumerate()
+            .map(|(i, (start, heading))| {
+                let end = starts.get(i + 1).map_or(lines.len(), |(next, _)| *next);
+                NoteSection {
+                    heading: heading.clone(),
+                    level: top,
+                    body: lines[start + 1..end].join("\n"),
+                }
+            })
+            .collect();
+        Self {
+            preamble: preamble.trim_end().to_string(),
+            sections,
+        }
+    }
+}
+
+/// Whether a note body is long enough to show in sections
+///
+/// A threshold of 0 turns sections off.
+pub fn is_long(body: &str, threshold: usize) -> bool {
+    threshold > 0 && body.len() > threshold
+}
+
+/// The ATX headings in a body: line index, level, and text
+fn heading_lines(body: &str) -> Vec<(usize, usize, String)> {
+    let mut headings = Vec::new();
+    let mut fence: Option<&str> = None;
+    for (index, line) in body.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if let Some(open) = fence {
+            if trimmed.starts_with(open) {
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") {
+            fence = Some("```");
+            continue;
+        }
+        if trimmed.starts_with("~~~") {
+            fence = Some("~~~");
+            continue;
+        }
+        if let Some((level, text)) = parse_heading(line) {
+            headings.push((index, level, text));
+        }
+    }
+    headings
+}
+
+/// Parse an ATX heading (`## Text`), returning its level and text
+fn parse_heading(line: &str) -> Option<(usize, String)> {
+    // Up to three spaces of indentation; four makes it code
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+    let line = &line[indent..];
+    let level = line.len() - line.trim_start_matches('#').len();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    // A closing run of #s isn't part of the text
+    let text = rest.trim().trim_end_matches('#').trim_end();
+    Some((level, text.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sections() {
+        let body = "Intro\n\n## One\nfirst\n### Detail\nmore\n## Two ##\nsecond\n";
+        let parsed = NoteSections::parse(body);
+        assert_eq!(parsed.preamble, "Intro");
+        let headings: Vec<_> = parsed.sections.iter().map(|s| s.heading.as_str()).collect();
+        assert_eq!(headings, ["One", "Two"]);
+        assert_eq!(parsed.sections[0].level, 2);
+        assert_eq!(parsed.sections[0].body, "first\n### Detail\nmore");
+        assert_eq!(parsed.sections[1].body, "second");
+    }
+
+    #[test]
+    fn test_headings_in_code_and_lookalikes() {
+        let body = "# Real\n```sh\n# not a heading\n```\n#hashtag\n    # code\n# Next";
+        let parsed = NoteSections::parse(body);
+        assert_eq!(parsed.preamble, "");
+        assert_eq!(parsed.sections.len(), 2);
+        assert_eq!(
+            parsed.sections[0].body,
+            "```sh\n# not a heading\n```\n#hashtag\n    # code"
+        );
+
+        let plain = NoteSections::parse("no headings\nat all");
+        assert_eq!(plain.preamble, "no headings\nat all");
+        assert!(plain.sections.is_empty());
+    }
+
+    #[test]
+    fn test_is_long() {
+        assert!(is_long(&"x".repeat(11), 10));
+        assert!(!is_long(&"x".repeat(10), 10));
+        assert!(!is_long(&"x".repeat(1000), 0));
+    }
+}
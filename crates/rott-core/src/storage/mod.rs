@@ -4,7 +4,8 @@
 //!
 //! ## Architecture
 //!
-//! - **Automerge**: Source of truth, stored as binary file
+//! - **Automerge**: Source of truth, stored as chunks in the automerge-repo
+//!   filesystem layout
 //!
 //! All queries are served directly from the in-memory Automerge document.
 //!
@@ -20,4 +21,4 @@ pub mod error;
 pub mod persistence;
 
 pub use error::{StorageError, StorageResult};
-pub use persistence::{AutomergePersistence, StorageStats};
+pub use persistence::{AutomergePersistence, SetAside, StorageStats};
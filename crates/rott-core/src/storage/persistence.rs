@@ -5,9 +5,25 @@
 //!
 //! Storage location: `~/.local/share/rott/` (configurable via `Config`)
 //!
-//! Files:
-//! - `document.automerge` - The Automerge binary document
-//! - `root_doc_id` - The document ID (bs58check encoded)
+//! Documents use the automerge-repo filesystem storage layout, so the data
+//! directory can be served directly by automerge-repo tooling:
+//!
+//! ```text
+//! <data_dir>/
+//! ├── root_doc_id                  # The document ID (bs58check encoded)
+//! └── <id[..2]>/<id[2..]>/
+//!     ├── snapshot/<heads hash>    # Full saves
+//!     └── incremental/<hash>       # Changes saved since a snapshot
+//! ```
+//!
+//! Each save only appends the changes made since the last load or save. Once
+//! the incremental chunks outgrow the snapshots (or too many pile up) they're
+//! compacted into a new snapshot. Only chunks this process has loaded or
+//! written are removed, so chunks written concurrently by another process
+//! are never lost.
+//!
+//! Older data directories with a single `document.automerge` file are
+//! migrated on first load.
 //!
 //! ## Error Handling
 //!
@@ -17,28 +33,121 @@
 //! - Missing directories: Created automatically
 
 use std::fs::{self, File};
-use std::io::Write;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
 
 use anyhow::{Context, Result};
+use automerge::ChangeHash;
 use chrono::Utc;
+use sha2::{Digest, Sha256};
+use tracing::{debug, warn};
 
 use super::error::{StorageError, StorageResult};
 use crate::config::Config;
-use crate::document::RottDocument;
+use crate::document::{DocumentError, RottDocument};
 use crate::document_id::DocumentId;
 
+/// Delta files allowed before compacting, however small they are
+const MAX_INCREMENTAL_CHUNKS: usize = 64;
+
+/// Kind of saved chunk, named after its automerge-repo storage key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChunkKind {
+    Snapshot,
+    Incremental,
+}
+
+impl ChunkKind {
+    fn dir_name(self) -> &'static str {
+        match self {
+            ChunkKind::Snapshot => "snapshot",
+            ChunkKind::Incremental => "incremental",
+        }
+    }
+}
+
+/// A chunk file on disk
+#[derive(Debug, Clone)]
+struct Chunk {
+    path: PathBuf,
+    kind: ChunkKind,
+    size: u64,
+}
+
+/// What this process knows is already on disk
+#[derive(Debug, Default)]
+struct SavedState {
+    doc_id: Option<DocumentId>,
+    /// Heads as of the last load or save
+    heads: Vec<ChangeHash>,
+    /// Chunks holding the changes up to `heads`
+    chunks: Vec<Chunk>,
+}
+
+/// Where a document is stored
+enum Stored {
+    Chunks { dir: PathBuf, chunks: Vec<Chunk> },
+    Legacy(PathBuf),
+    Missing,
+}
+
+/// Stored data moved out of the way by
+/// [`AutomergePersistence::set_aside`]
+#[must_use = "set-aside data is kept until it's discarded or put back"]
+#[derive(Debug)]
+pub struct SetAside {
+    /// Original path and where it was moved to
+    moved: Vec<(PathBuf, PathBuf)>,
+}
+
+impl SetAside {
+    /// Delete the set-aside data
+    pub fn discard(self) -> Result<()> {
+        for (_, aside) in &self.moved {
+            remove_path(aside)?;
+        }
+        Ok(())
+    }
+
+    /// Move the data back, replacing anything written in its place
+    pub fn put_back(self) -> Result<()> {
+        for (path, aside) in self.moved.iter().rev() {
+            if path.exists() {
+                remove_path(path)?;
+            }
+            fs::rename(aside, path)
+                .with_context(|| format!("Failed to move {:?} back to {:?}", aside, path))?;
+        }
+        Ok(())
+    }
+}
+
+/// Remove a file or a directory with its contents
+fn remove_path(path: &Path) -> Result<()> {
+    let removed = if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+    removed.with_context(|| format!("Failed to delete {:?}", path))
+}
+
 /// Persistence layer for Automerge documents
 ///
 /// Provides atomic file operations for saving/loading documents.
 pub struct AutomergePersistence {
     config: Config,
+    saved: Mutex<SavedState>,
 }
 
 impl AutomergePersistence {
     /// Create a new persistence handler with the given configuration
     pub fn new(config: Config) -> Self {
-        Self { config }
+        Self {
+            config,
+            saved: Mutex::new(SavedState::default()),
+        }
     }
 
     /// Load configuration from default location and create persistence handler
@@ -54,44 +163,111 @@ impl AutomergePersistence {
 
     /// Check if a document exists on disk
     pub fn exists(&self) -> bool {
-        self.config.automerge_path().exists()
+        !matches!(self.locate(), Ok(Stored::Missing))
     }
 
-    /// Save a document to disk using atomic write
+    /// Save a document to disk
     ///
-    /// This writes to a temporary file first, then renames it to the target path.
-    /// This ensures the file is never left in a partially-written state.
+    /// Appends the new changes (see [`append_changes`](Self::append_changes))
+    /// and compacts once the delta files outgrow the snapshots.
     pub fn save(&self, doc: &mut RottDocument) -> Result<()> {
-        let bytes = doc.save();
-        let target_path = self.config.automerge_path();
+        if self.append_changes(doc)? && self.needs_compaction() {
+            self.compact(doc)?;
+        }
+        Ok(())
+    }
 
-        atomic_write(&target_path, &bytes)
-            .with_context(|| format!("Failed to save document to {:?}", target_path))?;
+    /// Append the changes made since the last load or save as a delta file
+    ///
+    /// Only the new changes are written, so saving after a small edit costs
+    /// the size of the edit rather than the whole document. A document this
+    /// handler hasn't loaded or saved before is written in full as a
+    /// snapshot. Returns whether anything was written.
+    ///
+    /// The delta is taken from the heads this handler last saw rather than
+    /// `AutoCommit::save_incremental`'s own marker: changes merged in from
+    /// disk are already stored and mustn't be written again.
+    pub fn append_changes(&self, doc: &mut RottDocument) -> Result<bool> {
+        let dir = self.config.document_dir(doc.id());
+        let heads = doc.heads();
+
+        let written = {
+            let mut saved = self.saved.lock().unwrap_or_else(PoisonError::into_inner);
+
+            let written = if saved.doc_id != Some(*doc.id()) {
+                *saved = SavedState {
+                    doc_id: Some(*doc.id()),
+                    ..SavedState::default()
+                };
+                write_snapshot(doc, &dir, &heads, &mut saved)?;
+                true
+            } else if saved.heads != heads {
+                let bytes = doc.save_after(&saved.heads);
+                let name = format!("{:x}", Sha256::digest(&bytes));
+                let chunk = write_chunk(&dir, ChunkKind::Incremental, &name, &bytes)?;
+                saved.chunks.push(chunk);
+                true
+            } else {
+                false
+            };
+
+            saved.heads = heads;
+            written
+        };
 
         // Also save the document ID for reference
         self.save_root_doc_id(doc.id())?;
 
-        Ok(())
+        Ok(written)
+    }
+
+    /// Rewrite the document as a single snapshot
+    ///
+    /// Removes the snapshot and delta files it replaces. Files written by
+    /// other processes since the last load are left alone.
+    pub fn compact(&self, doc: &mut RottDocument) -> Result<()> {
+        let dir = self.config.document_dir(doc.id());
+        let heads = doc.heads();
+
+        let mut saved = self.saved.lock().unwrap_or_else(PoisonError::into_inner);
+        if saved.doc_id != Some(*doc.id()) {
+            *saved = SavedState {
+                doc_id: Some(*doc.id()),
+                ..SavedState::default()
+            };
+        }
+        write_snapshot(doc, &dir, &heads, &mut saved)?;
+        saved.heads = heads;
+        drop(saved);
+
+        self.save_root_doc_id(doc.id())
+    }
+
+    /// Whether the delta files have grown enough to be worth compacting
+    pub fn needs_compaction(&self) -> bool {
+        should_compact(
+            &self
+                .saved
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .chunks,
+        )
     }
 
     /// Load a document from disk
     ///
-    /// Returns `None` if the document file doesn't exist.
-    /// Returns an error if the file exists but can't be read or parsed.
+    /// Returns `None` if no document has been saved.
+    /// Returns an error if the document exists but can't be read or parsed.
     pub fn load(&self) -> Result<Option<RottDocument>> {
-        let path = self.config.automerge_path();
-
-        if !path.exists() {
+        let stored = self.locate()?;
+        let Some(chunks) = read_stored(&stored)? else {
             return Ok(None);
-        }
-
-        let bytes =
-            fs::read(&path).with_context(|| format!("Failed to read document from {:?}", path))?;
+        };
 
-        let doc = RottDocument::load(&bytes)
-            .with_context(|| format!("Failed to parse document from {:?}", path))?;
+        let doc = parse_stored(&stored, &chunks)
+            .with_context(|| format!("Failed to parse document from {:?}", stored.path()))?;
 
-        Ok(Some(doc))
+        self.loaded(doc, stored).map(Some)
     }
 
     /// Load an existing document or create a new one
@@ -108,6 +284,51 @@ impl AutomergePersistence {
         Ok(doc)
     }
 
+    /// Load a document other than the root, such as a year's archive
+    ///
+    /// Returns `None` if the document hasn't been saved on this device.
+    pub fn load_document(&self, id: &DocumentId) -> Result<Option<RottDocument>> {
+        let dir = self.config.document_dir(id);
+        let chunks = list_chunks(&dir).map_err(|e| StorageError::from_io(e, dir.clone()))?;
+        if chunks.is_empty() {
+            return Ok(None);
+        }
+
+        let stored = Stored::Chunks { dir, chunks };
+        let bytes = read_stored(&stored)?.unwrap_or_default();
+        let doc = parse_stored(&stored, &bytes)
+            .with_context(|| format!("Failed to parse document from {:?}", stored.path()))?;
+        Ok(Some(doc))
+    }
+
+    /// Save a document other than the root as a single snapshot
+    ///
+    /// Changes already on disk (from another process, or a sync) are merged
+    /// in first, so the snapshot replaces every chunk it was built from.
+    /// The root document ID file is left alone.
+    pub fn save_document(&self, doc: &mut RottDocument) -> Result<()> {
+        let dir = self.config.document_dir(doc.id());
+        let chunks = list_chunks(&dir).map_err(|e| StorageError::from_io(e, dir.clone()))?;
+        if !chunks.is_empty() {
+            let stored = Stored::Chunks {
+                dir: dir.clone(),
+                chunks: chunks.clone(),
+            };
+            let bytes = read_stored(&stored)?.unwrap_or_default();
+            let mut on_disk = parse_stored(&stored, &bytes)
+                .with_context(|| format!("Failed to parse document from {:?}", dir))?;
+            doc.merge(&mut on_disk)?;
+        }
+
+        let heads = doc.heads();
+        let mut saved = SavedState {
+            doc_id: Some(*doc.id()),
+            heads: Vec::new(),
+            chunks,
+        };
+        write_snapshot(doc, &dir, &heads, &mut saved)
+    }
+
     /// Save the root document ID to a separate file
     ///
     /// This provides a quick way to get the document ID without loading
@@ -138,6 +359,24 @@ impl AutomergePersistence {
         Ok(self.load_root_doc_id()?.is_some())
     }
 
+    /// Whether this device was joined read-only
+    ///
+    /// See [`Store::is_read_only`](crate::Store::is_read_only).
+    pub fn is_read_only(&self) -> bool {
+        self.config.read_only_marker_path().exists()
+    }
+
+    /// Mark this device read-only, or writable again
+    pub fn set_read_only(&self, read_only: bool) -> Result<()> {
+        let path = self.config.read_only_marker_path();
+        if read_only {
+            atomic_write(&path, b"").with_context(|| format!("Failed to write {:?}", path))?;
+        } else if path.exists() {
+            fs::remove_file(&path).with_context(|| format!("Failed to delete {:?}", path))?;
+        }
+        Ok(())
+    }
+
     /// Load the root document ID from disk
     ///
     /// Returns `None` if the ID file doesn't exist.
@@ -170,7 +409,18 @@ impl AutomergePersistence {
     /// Removes the Automerge document and root doc ID.
     /// Use with caution!
     pub fn delete_all(&self) -> Result<()> {
-        let paths = [self.config.automerge_path(), self.config.root_doc_id_path()];
+        if let Some(id) = self.load_root_doc_id()? {
+            let dir = self.config.document_dir(&id);
+            if dir.exists() {
+                fs::remove_dir_all(&dir).with_context(|| format!("Failed to delete {:?}", dir))?;
+            }
+        }
+
+        let paths = [
+            self.config.automerge_path(),
+            self.config.root_doc_id_path(),
+            self.config.read_only_marker_path(),
+        ];
 
         for path in paths {
             if path.exists() {
@@ -178,39 +428,71 @@ impl AutomergePersistence {
             }
         }
 
+        *self.saved.lock().unwrap_or_else(PoisonError::into_inner) = SavedState::default();
+
         Ok(())
     }
 
+    /// Move all stored data out of the way, to be discarded or put back
+    ///
+    /// Moves what [`delete_all`](Self::delete_all) would delete, so data can
+    /// be replaced without a window where a failed write leaves nothing.
+    pub fn set_aside(&self) -> Result<SetAside> {
+        let mut paths = Vec::new();
+        if let Some(id) = self.load_root_doc_id()? {
+            paths.push(self.config.document_dir(&id));
+        }
+        paths.extend([
+            self.config.automerge_path(),
+            self.config.root_doc_id_path(),
+            self.config.read_only_marker_path(),
+        ]);
+
+        let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
+        let mut set_aside = SetAside { moved: Vec::new() };
+        for path in paths {
+            if !path.exists() {
+                continue;
+            }
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("document");
+            let aside = path.with_file_name(format!("{}.replaced.{}", name, timestamp));
+            if let Err(e) = fs::rename(&path, &aside) {
+                // Don't leave the data half moved
+                let _ = set_aside.put_back();
+                return Err(e).with_context(|| format!("Failed to move {:?} aside", path));
+            }
+            set_aside.moved.push((path, aside));
+        }
+
+        *self.saved.lock().unwrap_or_else(PoisonError::into_inner) = SavedState::default();
+
+        Ok(set_aside)
+    }
+
     /// Load document with automatic recovery from corruption
     ///
     /// If the document is corrupted:
-    /// 1. Creates a backup of the corrupted file
+    /// 1. Moves the corrupted data aside as a backup
     /// 2. Creates a fresh document
     /// 3. Returns the fresh document with a warning
     ///
     /// Returns `(document, was_recovered)` where `was_recovered` is true
     /// if the document was corrupted and a fresh one was created.
     pub fn load_with_recovery(&self) -> Result<(RottDocument, bool)> {
-        let path = self.config.automerge_path();
-
-        if !path.exists() {
+        let stored = self.locate()?;
+        let Some(chunks) = read_stored(&stored)? else {
             return Ok((RottDocument::new(), false));
-        }
-
-        // Try to read the file
-        let bytes = match fs::read(&path) {
-            Ok(b) => b,
-            Err(e) => {
-                return Err(StorageError::from_io(e, path.clone()).into());
-            }
         };
 
         // Try to parse the document
-        match RottDocument::load(&bytes) {
-            Ok(doc) => Ok((doc, false)),
+        match parse_stored(&stored, &chunks) {
+            Ok(doc) => Ok((self.loaded(doc, stored)?, false)),
             Err(_) => {
                 // Document is corrupted - attempt recovery
-                let backup_path = self.backup_corrupt_file(&path)?;
+                let backup_path = self.backup_corrupt(stored.path())?;
 
                 // Log the corruption (in production, this would go to a proper logger)
                 eprintln!(
@@ -242,8 +524,49 @@ impl AutomergePersistence {
         Ok((doc, false))
     }
 
-    /// Create a backup of a corrupted file
-    fn backup_corrupt_file(&self, path: &Path) -> Result<PathBuf> {
+    /// Find the stored document: chunks for the root document ID, or the
+    /// legacy single file
+    fn locate(&self) -> Result<Stored> {
+        if let Some(id) = self.load_root_doc_id()? {
+            let dir = self.config.document_dir(&id);
+            let chunks = list_chunks(&dir).map_err(|e| StorageError::from_io(e, dir.clone()))?;
+            if !chunks.is_empty() {
+                return Ok(Stored::Chunks { dir, chunks });
+            }
+        }
+
+        let legacy = self.config.automerge_path();
+        if legacy.exists() {
+            return Ok(Stored::Legacy(legacy));
+        }
+
+        Ok(Stored::Missing)
+    }
+
+    /// Record a freshly loaded document, migrating legacy storage
+    fn loaded(&self, mut doc: RottDocument, stored: Stored) -> Result<RottDocument> {
+        match stored {
+            Stored::Chunks { chunks, .. } => {
+                *self.saved.lock().unwrap_or_else(PoisonError::into_inner) = SavedState {
+                    doc_id: Some(*doc.id()),
+                    heads: doc.heads(),
+                    chunks,
+                };
+            }
+            Stored::Legacy(path) => {
+                debug!("Migrating {:?} to chunked storage", path);
+                self.save(&mut doc)
+                    .context("Failed to migrate document to chunked storage")?;
+                fs::remove_file(&path)
+                    .with_context(|| format!("Failed to remove migrated {:?}", path))?;
+            }
+            Stored::Missing => {}
+        }
+        Ok(doc)
+    }
+
+    /// Move corrupted data aside so a fresh document can be created
+    fn backup_corrupt(&self, path: &Path) -> Result<PathBuf> {
         let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
         let backup_name = format!(
             "{}.corrupt.{}.backup",
@@ -254,21 +577,21 @@ impl AutomergePersistence {
         );
         let backup_path = path.with_file_name(backup_name);
 
-        fs::copy(path, &backup_path)
+        fs::rename(path, &backup_path)
             .with_context(|| format!("Failed to create backup at {:?}", backup_path))?;
 
         Ok(backup_path)
     }
 
-    /// Validate that the data directory is writable
+    /// Validate that the active collection's directory is writable
     ///
     /// Creates the directory if it doesn't exist and tests write access.
     pub fn validate_storage(&self) -> StorageResult<()> {
-        let data_dir = &self.config.data_dir;
+        let data_dir = self.config.store_dir();
 
         // Create directory if needed
         if !data_dir.exists() {
-            fs::create_dir_all(data_dir).map_err(|e| StorageError::CreateDirectory {
+            fs::create_dir_all(&data_dir).map_err(|e| StorageError::CreateDirectory {
                 path: data_dir.clone(),
                 source: e,
             })?;
@@ -282,21 +605,179 @@ impl AutomergePersistence {
                 let _ = fs::remove_file(&test_path);
                 Ok(())
             }
-            Err(e) => Err(StorageError::from_io(e, data_dir.clone())),
+            Err(e) => Err(StorageError::from_io(e, data_dir)),
+        }
+    }
+
+    /// A marker of the stored document that changes whenever it's saved
+    ///
+    /// Made from the chunk files' names and sizes, so another process's
+    /// saves can be noticed by polling without reading the document.
+    /// Returns `None` if nothing has been saved.
+    pub fn stored_version(&self) -> Option<String> {
+        let Ok(Stored::Chunks { chunks, .. }) = self.locate() else {
+            return None;
+        };
+        let mut hasher = Sha256::new();
+        for chunk in &chunks {
+            hasher.update(chunk.path.to_string_lossy().as_bytes());
+            hasher.update(chunk.size.to_le_bytes());
         }
+        Some(format!("{:x}", hasher.finalize()))
     }
 
     /// Get storage statistics
     pub fn storage_stats(&self) -> StorageStats {
-        let doc_path = self.config.automerge_path();
+        let document_size = match self.locate() {
+            Ok(Stored::Chunks { chunks, .. }) => Some(chunks.iter().map(|c| c.size).sum()),
+            Ok(Stored::Legacy(path)) => fs::metadata(path).map(|m| m.len()).ok(),
+            _ => None,
+        };
 
         StorageStats {
-            document_size: fs::metadata(&doc_path).map(|m| m.len()).ok(),
-            document_exists: doc_path.exists(),
+            document_exists: document_size.is_some(),
+            document_size,
+        }
+    }
+}
+
+impl Stored {
+    fn path(&self) -> &Path {
+        match self {
+            Stored::Chunks { dir, .. } => dir,
+            Stored::Legacy(path) => path,
+            Stored::Missing => Path::new(""),
         }
     }
 }
 
+/// Read the bytes of a stored document, one entry per chunk
+fn read_stored(stored: &Stored) -> Result<Option<Vec<Vec<u8>>>> {
+    let paths: Vec<&Path> = match stored {
+        Stored::Chunks { chunks, .. } => chunks.iter().map(|c| c.path.as_path()).collect(),
+        Stored::Legacy(path) => vec![path],
+        Stored::Missing => return Ok(None),
+    };
+
+    let mut bytes = Vec::with_capacity(paths.len());
+    for path in paths {
+        bytes.push(fs::read(path).map_err(|e| StorageError::from_io(e, path.to_path_buf()))?);
+    }
+    Ok(Some(bytes))
+}
+
+fn parse_stored(stored: &Stored, chunks: &[Vec<u8>]) -> Result<RottDocument, DocumentError> {
+    match stored {
+        Stored::Legacy(_) => RottDocument::load(&chunks[0]),
+        _ => RottDocument::load_chunks(chunks.iter().map(Vec::as_slice)),
+    }
+}
+
+/// List the chunk files in a document directory
+fn list_chunks(dir: &Path) -> io::Result<Vec<Chunk>> {
+    let mut chunks = Vec::new();
+
+    for kind in [ChunkKind::Snapshot, ChunkKind::Incremental] {
+        let entries = match fs::read_dir(dir.join(kind.dir_name())) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+
+        let mut found = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            let path = entry.path();
+            // Skip leftover temp files from interrupted writes
+            if path.extension().is_some() {
+                continue;
+            }
+            let metadata = entry.metadata()?;
+            if metadata.is_file() {
+                found.push(Chunk {
+                    path,
+                    kind,
+                    size: metadata.len(),
+                });
+            }
+        }
+        found.sort_by(|a, b| a.path.cmp(&b.path));
+        chunks.extend(found);
+    }
+
+    Ok(chunks)
+}
+
+fn write_chunk(dir: &Path, kind: ChunkKind, name: &str, bytes: &[u8]) -> Result<Chunk> {
+    let path = dir.join(kind.dir_name()).join(name);
+
+    atomic_write(&path, bytes)
+        .with_context(|| format!("Failed to save document chunk to {:?}", path))?;
+
+    Ok(Chunk {
+        path,
+        kind,
+        size: bytes.len() as u64,
+    })
+}
+
+/// Write the whole document as a snapshot, replacing the known chunks
+fn write_snapshot(
+    doc: &mut RottDocument,
+    dir: &Path,
+    heads: &[ChangeHash],
+    saved: &mut SavedState,
+) -> Result<()> {
+    let bytes = doc.save();
+    let snapshot = write_chunk(dir, ChunkKind::Snapshot, &heads_hash(heads), &bytes)?;
+
+    for chunk in saved.chunks.drain(..) {
+        if chunk.path == snapshot.path {
+            continue;
+        }
+        // Another process may have compacted it away already
+        if let Err(e) = fs::remove_file(&chunk.path) {
+            if e.kind() != io::ErrorKind::NotFound {
+                warn!("Failed to remove compacted chunk {:?}: {}", chunk.path, e);
+            }
+        }
+    }
+
+    saved.chunks.push(snapshot);
+    Ok(())
+}
+
+/// Compact once the incremental chunks outgrow the snapshots, or there are
+/// too many of them
+fn should_compact(chunks: &[Chunk]) -> bool {
+    let size_of = |kind| {
+        chunks
+            .iter()
+            .filter(|c| c.kind == kind)
+            .map(|c| c.size)
+            .sum::<u64>()
+    };
+    let incremental_count = chunks
+        .iter()
+        .filter(|c| c.kind == ChunkKind::Incremental)
+        .count();
+
+    incremental_count > MAX_INCREMENTAL_CHUNKS
+        || size_of(ChunkKind::Incremental) > size_of(ChunkKind::Snapshot)
+}
+
+/// Storage key for a snapshot: a hash of the document heads
+fn heads_hash(heads: &[ChangeHash]) -> String {
+    let mut heads = heads.to_vec();
+    heads.sort();
+
+    let mut hasher = Sha256::new();
+    for head in &heads {
+        hasher.update(head.0);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
 /// Storage statistics
 #[derive(Debug, Clone)]
 pub struct StorageStats {
@@ -371,8 +852,7 @@ mod tests {
             data_dir: temp_dir.path().to_path_buf(),
             sync_url: None,
             sync_enabled: false,
-            favorite_tag: None,
-            log_file: None,
+            ..Config::default()
         }
     }
 
@@ -434,6 +914,30 @@ mod tests {
         assert_eq!(loaded.get_all_links().unwrap().len(), 1);
     }
 
+    #[test]
+    fn test_side_documents_leave_root_alone() {
+        let temp_dir = TempDir::new().unwrap();
+        let persistence = AutomergePersistence::new(test_config(&temp_dir));
+        let root = persistence.load_or_create().unwrap();
+
+        let mut side = RottDocument::new();
+        assert!(persistence.load_document(side.id()).unwrap().is_none());
+        side.add_link(&Link::new("https://example.com")).unwrap();
+        persistence.save_document(&mut side).unwrap();
+
+        // A second copy's changes are merged, not lost
+        let mut other = persistence.load_document(side.id()).unwrap().unwrap();
+        other.add_link(&Link::new("https://example.org")).unwrap();
+        persistence.save_document(&mut other).unwrap();
+        side.add_link(&Link::new("https://example.net")).unwrap();
+        persistence.save_document(&mut side).unwrap();
+
+        let loaded = persistence.load_document(side.id()).unwrap().unwrap();
+        assert_eq!(loaded.get_all_links().unwrap().len(), 3);
+        assert_eq!(persistence.load_root_doc_id().unwrap(), Some(*root.id()));
+        assert_eq!(persistence.load().unwrap().unwrap().id(), root.id());
+    }
+
     #[test]
     fn test_root_doc_id_persistence() {
         let temp_dir = TempDir::new().unwrap();
@@ -486,6 +990,29 @@ mod tests {
         assert!(persistence.load_root_doc_id().unwrap().is_none());
     }
 
+    #[test]
+    fn test_set_aside_put_back_and_discard() {
+        let temp_dir = TempDir::new().unwrap();
+        let persistence = AutomergePersistence::new(test_config(&temp_dir));
+        let mut doc = RottDocument::new();
+        persistence.save(&mut doc).unwrap();
+
+        // Put back over a document written in its place
+        let aside = persistence.set_aside().unwrap();
+        assert!(!persistence.exists());
+        let mut other = RottDocument::new();
+        AutomergePersistence::new(test_config(&temp_dir))
+            .save(&mut other)
+            .unwrap();
+        aside.put_back().unwrap();
+        assert_eq!(persistence.load_root_doc_id().unwrap(), Some(*doc.id()));
+        assert_eq!(persistence.load().unwrap().unwrap().id(), doc.id());
+
+        persistence.set_aside().unwrap().discard().unwrap();
+        assert!(!persistence.exists());
+        assert!(persistence.load_root_doc_id().unwrap().is_none());
+    }
+
     #[test]
     fn test_atomic_write_creates_parent_dirs() {
         let temp_dir = TempDir::new().unwrap();
@@ -553,6 +1080,126 @@ mod tests {
         assert_eq!(final_doc.get_all_links().unwrap().len(), 9);
     }
 
+    #[test]
+    fn test_saves_are_incremental() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = test_config(&temp_dir);
+        let persistence = AutomergePersistence::new(config.clone());
+
+        let mut doc = RottDocument::new();
+        for i in 0..10 {
+            doc.add_link(&Link::new(format!("https://example{}.com", i)))
+                .unwrap();
+        }
+        persistence.save(&mut doc).unwrap();
+
+        doc.add_link(&Link::new("https://example.com/new")).unwrap();
+        persistence.save(&mut doc).unwrap();
+
+        // Saving again with no changes writes nothing
+        persistence.save(&mut doc).unwrap();
+
+        let dir = config.document_dir(doc.id());
+        let count = |kind: &str| fs::read_dir(dir.join(kind)).unwrap().count();
+        assert_eq!(count("snapshot"), 1);
+        assert_eq!(count("incremental"), 1);
+
+        // A fresh handler reads all the chunks back
+        let loaded = AutomergePersistence::new(config).load().unwrap().unwrap();
+        assert_eq!(loaded.get_all_links().unwrap().len(), 11);
+    }
+
+    #[test]
+    fn test_append_changes_writes_only_the_delta() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = test_config(&temp_dir);
+        let persistence = AutomergePersistence::new(config.clone());
+
+        let mut doc = RottDocument::new();
+        for i in 0..50 {
+            doc.add_link(&Link::new(format!("https://example{}.com", i)))
+                .unwrap();
+        }
+        assert!(persistence.append_changes(&mut doc).unwrap());
+        let full_size = persistence.storage_stats().total_size();
+
+        doc.add_link(&Link::new("https://example.com/new")).unwrap();
+        assert!(persistence.append_changes(&mut doc).unwrap());
+        assert!(!persistence.append_changes(&mut doc).unwrap());
+
+        let delta_size = persistence.storage_stats().total_size() - full_size;
+        assert!(delta_size < full_size / 10);
+
+        // Compacting folds the delta back into one snapshot
+        persistence.compact(&mut doc).unwrap();
+        let chunks = list_chunks(&config.document_dir(doc.id())).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].kind, ChunkKind::Snapshot);
+
+        let loaded = AutomergePersistence::new(config).load().unwrap().unwrap();
+        assert_eq!(loaded.get_all_links().unwrap().len(), 51);
+    }
+
+    #[test]
+    fn test_many_small_deltas_are_compacted() {
+        let chunk = |kind, size| Chunk {
+            path: PathBuf::new(),
+            kind,
+            size,
+        };
+        let mut chunks = vec![chunk(ChunkKind::Snapshot, 1_000_000)];
+        chunks.extend((0..MAX_INCREMENTAL_CHUNKS).map(|_| chunk(ChunkKind::Incremental, 100)));
+        assert!(!should_compact(&chunks));
+
+        chunks.push(chunk(ChunkKind::Incremental, 100));
+        assert!(should_compact(&chunks));
+    }
+
+    #[test]
+    fn test_incremental_chunks_are_compacted() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = test_config(&temp_dir);
+        let persistence = AutomergePersistence::new(config.clone());
+
+        let mut doc = persistence.load_or_create().unwrap();
+        for i in 0..20 {
+            doc.add_link(&Link::new(format!("https://example{}.com", i)))
+                .unwrap();
+            persistence.save(&mut doc).unwrap();
+        }
+
+        let chunks = list_chunks(&config.document_dir(doc.id())).unwrap();
+        let snapshots = chunks
+            .iter()
+            .filter(|c| c.kind == ChunkKind::Snapshot)
+            .count();
+        assert_eq!(snapshots, 1);
+        assert!(!should_compact(&chunks));
+
+        let loaded = AutomergePersistence::new(config).load().unwrap().unwrap();
+        assert_eq!(loaded.get_all_links().unwrap().len(), 20);
+    }
+
+    #[test]
+    fn test_migrates_legacy_document() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = test_config(&temp_dir);
+        let persistence = AutomergePersistence::new(config.clone());
+
+        let mut doc = RottDocument::new();
+        doc.add_link(&Link::new("https://example.com")).unwrap();
+        fs::write(config.automerge_path(), doc.save()).unwrap();
+        assert!(persistence.exists());
+
+        let loaded = persistence.load().unwrap().unwrap();
+        assert_eq!(*loaded.id(), *doc.id());
+        assert_eq!(loaded.get_all_links().unwrap().len(), 1);
+
+        assert!(!config.automerge_path().exists());
+        assert!(config.document_dir(doc.id()).join("snapshot").exists());
+        assert_eq!(persistence.load_root_doc_id().unwrap(), Some(*doc.id()));
+    }
+
     #[test]
     fn test_load_with_recovery_valid_document() {
         let temp_dir = TempDir::new().unwrap();
@@ -644,8 +1291,7 @@ mod tests {
             data_dir: nested_dir.clone(),
             sync_url: None,
             sync_enabled: false,
-            favorite_tag: None,
-            log_file: None,
+            ..Config::default()
         };
         let persistence = AutomergePersistence::new(config);
 
@@ -654,6 +1300,28 @@ mod tests {
         assert!(nested_dir.exists());
     }
 
+    #[test]
+    fn test_stored_version_changes_on_save() {
+        let temp_dir = TempDir::new().unwrap();
+        let persistence = AutomergePersistence::new(test_config(&temp_dir));
+        assert_eq!(persistence.stored_version(), None);
+
+        let mut doc = persistence.load_or_create().unwrap();
+        let version = persistence.stored_version();
+        assert!(version.is_some());
+        persistence.save(&mut doc).unwrap();
+        assert_eq!(persistence.stored_version(), version);
+
+        // A save from another process shows up
+        let other = AutomergePersistence::new(test_config(&temp_dir));
+        let mut other_doc = other.load().unwrap().unwrap();
+        other_doc
+            .add_link(&Link::new("https://example.com"))
+            .unwrap();
+        other.save(&mut other_doc).unwrap();
+        assert_ne!(persistence.stored_version(), version);
+    }
+
     #[test]
     fn test_storage_stats() {
         let temp_dir = TempDir::new().unwrap();
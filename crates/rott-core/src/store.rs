@@ -22,22 +22,133 @@
 //! store.add_link(&link)?;
 //!
 //! // Query data
-//! let links = store.get_all_links()?;
+//! let links = store.get_all_links(LinkSort::Updated)?;
 //! ```
 
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use anyhow::{Context, Result};
+use automerge::ChangeHash;
+use chrono::{DateTime, Datelike, Duration, Utc};
+use thiserror::Error;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+use crate::anonymize::anonymize_document;
+use crate::attachments::AttachmentStore;
+use crate::backup::Backup;
+use crate::canonical::normalize_url;
 use crate::config::Config;
-use crate::document::RottDocument;
+use crate::dedupe::{find_duplicate_links, DuplicateLinks, DuplicateNotes};
+use crate::derive::{apply_derivers, default_derivers, Deriver};
+use crate::document::{
+    DocumentError, DocumentStats, RecordWarning, RottDocument, CURRENT_SCHEMA_VERSION,
+};
 use crate::document_id::DocumentId;
-use crate::models::{Link, Note};
+use crate::domains::DomainRules;
+use crate::events::{rebuild, save_projection, Projection, StoreEvent};
+use crate::history::{ArchiveMove, Edit, EditHistory, LinkChange, TagChange, TagSettings};
+use crate::incoming::IncomingChange;
+use crate::models::{Link, LinkSort, LinksPage, Note, NoteVersion, NotesPage, ReadPosition};
+use crate::query::{SearchHit, SearchQuery};
+use crate::report::{ChangeReport, ReportBuilder};
+use crate::search::{provider_from_config, update_index, SearchBackend, SearchProvider};
+use crate::sections::NoteSections;
 use crate::storage::{AutomergePersistence, StorageStats};
 use crate::sync::{SyncClient, SyncState};
+use crate::tag_tree::{tag_tree, TagNode};
+use crate::validate::{normalize_tag, normalize_tags, ValidationError, Validator};
+use crate::visibility::{TagVisibility, Visibility};
+
+/// Opening a link again within this many minutes isn't recorded
+pub const OPEN_DEBOUNCE_MINUTES: i64 = 10;
+
+/// What [`Store::restore_links`] brought back
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RestoreSummary {
+    /// Deleted links that were re-added
+    pub links_restored: usize,
+    /// Deleted notes re-added to existing links
+    pub notes_restored: usize,
+    /// Links that were already complete
+    pub unchanged: usize,
+    /// Deleted links not restored because their URL was saved again since
+    pub skipped_duplicates: usize,
+    /// Deleted links not restored because they're blocked or fail
+    /// validation now, with the reason
+    pub rejected: Vec<(Link, String)>,
+}
+
+/// What [`Store::import_links`] saves and skips
+#[derive(Debug, Clone, Default)]
+pub struct ImportSummary {
+    /// Links saved, as they were written (tagged and validated)
+    pub added: Vec<Link>,
+    /// Links whose URL was already saved, with the ID of the link that has
+    /// it (which may be one added earlier in the same import)
+    pub duplicates: Vec<(Link, Uuid)>,
+    /// Links that were blocked or failed validation, with the reason
+    pub rejected: Vec<(Link, String)>,
+}
+
+/// What [`Store::accept_incoming`] saved and skipped
+#[derive(Debug, Clone, Default)]
+pub struct IncomingSummary {
+    /// Changes applied
+    pub applied: usize,
+    /// Incoming links that were blocked or failed validation, with the
+    /// reason
+    pub rejected: Vec<(Link, String)>,
+}
+
+/// A merge done by [`Store::merge_links`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkMerge {
+    /// The kept link as it was before the merge
+    pub kept: Link,
+    /// The duplicate, as it was when it was deleted
+    pub duplicate: Link,
+    /// The kept link as the merge left it
+    pub merged: Link,
+}
+
+/// A change was attempted on a read-only device
+///
+/// Devices joined with `rott init --join <id> --read-only` pull the shared
+/// document but never change it (see [`Store::is_read_only`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("This device is read-only: it can read and sync the document but not change it")]
+pub struct ReadOnlyError;
+
+/// Check a link from elsewhere (an import, a backup) before it's added
+///
+/// The same checks as [`Store::add_link`]: blocked domains, domain tags,
+/// and validation. Returns why the link can't be added.
+fn admit(rules: &DomainRules, validator: &Validator, link: &mut Link) -> Result<(), String> {
+    if let Some(rule) = rules.blocking_rule(&link.url) {
+        return Err(format!("links from '{}' are blocked", rule));
+    }
+    for tag in rules.tags_for(&link.url) {
+        link.add_tag(tag);
+    }
+    validator.validate(link, None).map_err(|e| e.to_string())
+}
+
+/// A link without its opens and read position
+///
+/// Opens and read positions are recorded without changing anything else,
+/// so they don't count when checking whether a link was changed.
+fn unopened(link: &Link) -> Link {
+    Link {
+        opened_at: None,
+        open_count: 0,
+        read_position: None,
+        ..link.clone()
+    }
+}
 
 /// Unified storage interface for ROTT
 ///
@@ -49,6 +160,13 @@ pub struct Store {
     persistence: AutomergePersistence,
     /// Configuration
     config: Config,
+    /// Derived field computations run on every link write
+    derivers: Vec<Arc<dyn Deriver>>,
+    /// The index searches go through (`search_provider`; None to check
+    /// every link)
+    search: Option<Arc<dyn SearchProvider>>,
+    /// Whether this device only reads the document
+    read_only: bool,
 }
 
 impl Store {
@@ -107,11 +225,46 @@ impl Store {
 
         debug!("Store opened successfully, root_id={}", doc.id());
 
-        Ok(Self {
+        let read_only = persistence.is_read_only();
+        let derivers = default_derivers(config.url_trailing_slash);
+        let search = provider_from_config(&config);
+        let mut store = Self {
             doc: Arc::new(Mutex::new(doc)),
             persistence,
             config,
+            derivers,
+            search,
+            read_only,
+        };
+        if !read_only {
+            store.migrate()?;
+        }
+        Ok(store)
+    }
+
+    /// Bring a document written by an older version up to
+    /// [`CURRENT_SCHEMA_VERSION`]
+    ///
+    /// The version is kept in the document, so this runs once per
+    /// collection and costs nothing on later opens.
+    fn migrate(&mut self) -> Result<()> {
+        let version = tokio::task::block_in_place(|| self.doc.blocking_lock().schema_version())
+            .context("Failed to read schema version")?;
+        if version >= CURRENT_SCHEMA_VERSION {
+            return Ok(());
+        }
+        let changed = tokio::task::block_in_place(|| {
+            let mut doc = self.doc.blocking_lock();
+            let changed = doc.normalize_tags()?;
+            doc.set_schema_version()?;
+            Ok::<_, DocumentError>(changed)
         })
+        .context("Failed to migrate document")?;
+        info!(
+            "Migrated document from schema version {} (normalized tags on {} link(s))",
+            version, changed
+        );
+        self.save()
     }
 
     /// Check if in pending sync state (joined but not yet synced)
@@ -164,9 +317,13 @@ impl Store {
         let mut doc = RottDocument::empty_for_sync(root_id);
 
         // Create sync client and sync state
-        let sync_state_path = config.data_dir.join("sync_state.json");
+        let sync_state_path = config.sync_state_path();
         let sync_state = SyncState::with_path(sync_state_path).unwrap_or_else(|_| SyncState::new());
-        let client = SyncClient::new(sync_url, root_id).with_sync_state(sync_state);
+        let client = SyncClient::new(sync_url, root_id)
+            .with_sync_state(sync_state)
+            .with_read_only(persistence.is_read_only())
+            .with_compression(config.sync_compression)
+            .with_last_sync_path(config.last_sync_path());
 
         // Perform sync
         let updated = client.sync_once(&mut doc).await?;
@@ -221,6 +378,62 @@ impl Store {
         &self.config
     }
 
+    /// Whether this device only reads the document
+    ///
+    /// Every change on a read-only device fails with [`ReadOnlyError`], and
+    /// sync only pulls.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// The document's current heads, as hex change hashes
+    ///
+    /// Heads identify the document's exact state: [`at_heads`](Self::at_heads)
+    /// reads it as it was then, however it has changed since.
+    pub fn heads(&self) -> Vec<String> {
+        tokio::task::block_in_place(|| {
+            let mut doc = self.doc.blocking_lock();
+            doc.heads().iter().map(|h| h.to_string()).collect()
+        })
+    }
+
+    /// A read-only copy of the store as it was at `heads` (from
+    /// [`heads`](Self::heads))
+    ///
+    /// Only kept in memory: every change to it fails with [`ReadOnlyError`].
+    pub fn at_heads(&self, heads: &[String]) -> Result<Store> {
+        let hashes = heads
+            .iter()
+            .map(|head| {
+                head.trim()
+                    .parse::<ChangeHash>()
+                    .map_err(|_| anyhow::anyhow!("'{}' is not a change hash", head))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let doc = tokio::task::block_in_place(|| {
+            self.doc
+                .blocking_lock()
+                .fork_at(&hashes)
+                .context("Heads aren't in this document's history")
+        })?;
+        Ok(Self {
+            doc: Arc::new(Mutex::new(doc)),
+            persistence: AutomergePersistence::new(self.config.clone()),
+            config: self.config.clone(),
+            derivers: self.derivers.clone(),
+            // The index follows the current document, not this past one
+            search: None,
+            read_only: true,
+        })
+    }
+
+    fn ensure_writable(&self) -> Result<()> {
+        if self.read_only {
+            return Err(ReadOnlyError.into());
+        }
+        Ok(())
+    }
+
     /// Check if this is a new store (just created)
     pub fn is_new(&self) -> bool {
         tokio::task::block_in_place(|| self.doc.blocking_lock().link_count().unwrap_or(0) == 0)
@@ -230,46 +443,540 @@ impl Store {
 
     /// Add a new link
     ///
-    /// Returns an error if a link with the same URL already exists.
+    /// Domain rules from the configuration are enforced: links from blocked
+    /// domains are rejected, and auto-tags are added before saving. The link
+    /// is then validated (see [`crate::validate`]); a rejected link is
+    /// reported as a [`ValidationError`](crate::ValidationError).
+    /// Registered derivers then compute the link's derived fields.
+    ///
+    /// Returns an error if a link with the same URL (saved or canonical)
+    /// already exists.
     pub fn add_link(&mut self, link: &Link) -> Result<()> {
+        self.ensure_writable()?;
+        let mut link = link.clone();
+        self.apply_domain_rules(&mut link)?;
+        self.validator().validate(&mut link, None)?;
+        apply_derivers(&self.derivers, &mut link);
+
         // Check for duplicate URL
-        if let Some(existing) = self.get_link_by_url(&link.url)? {
-            anyhow::bail!(
-                "A link with this URL already exists: '{}' (ID: {})",
-                existing.title,
-                existing.id
-            );
+        for url in std::iter::once(&link.url).chain(link.canonical_url.as_ref()) {
+            if let Some(existing) = self.get_link_by_url(url)? {
+                anyhow::bail!(
+                    "A link with this URL already exists: '{}' (ID: {})",
+                    existing.title,
+                    existing.id
+                );
+            }
         }
 
         tokio::task::block_in_place(|| {
             self.doc
                 .blocking_lock()
-                .add_link(link)
+                .add_link(&link)
                 .context("Failed to add link to document")
         })?;
-        self.save()
+        self.save()?;
+        self.record_edit(format!("add '{}'", link.title), vec![(link.id, None)]);
+        Ok(())
+    }
+
+    /// Register a deriver to run on every link write
+    ///
+    /// Replaces any registered deriver with the same key.
+    pub fn register_deriver(&mut self, deriver: impl Deriver + 'static) {
+        self.derivers.retain(|d| d.key() != deriver.key());
+        self.derivers.push(Arc::new(deriver));
+    }
+
+    /// Search with a different provider than `search_provider` chose
+    pub fn set_search_provider(&mut self, provider: impl SearchProvider + 'static) {
+        self.search = Some(Arc::new(provider));
+    }
+
+    /// Name of the provider searches go through
+    pub fn search_provider_name(&self) -> &'static str {
+        self.search
+            .as_ref()
+            .map_or(SearchBackend::Scan.name(), |search| search.name())
+    }
+
+    /// Get the validation policy from the configuration
+    pub fn validator(&self) -> Validator {
+        Validator::from_config(&self.config)
+    }
+
+    /// Get the domain rules from the configuration
+    pub fn domain_rules(&self) -> DomainRules {
+        DomainRules::from_config(&self.config)
+    }
+
+    /// Apply configured domain rules to a link before it is saved
+    ///
+    /// Returns an error if the link's domain is blocked. Otherwise adds any
+    /// auto-tags configured for the domain.
+    pub fn apply_domain_rules(&self, link: &mut Link) -> Result<()> {
+        let rules = self.domain_rules();
+
+        if let Some(rule) = rules.blocking_rule(&link.url) {
+            anyhow::bail!(
+                "Links from '{}' are blocked by configuration (blocked_domains)",
+                rule
+            );
+        }
+
+        for tag in rules.tags_for(&link.url) {
+            link.add_tag(tag);
+        }
+
+        Ok(())
     }
 
     /// Update an existing link
+    ///
+    /// The link is validated like in [`Store::add_link`], except that a URL
+    /// or title unchanged from the stored link isn't rejected. Derived
+    /// fields are recomputed before saving. A link that ends up as it's
+    /// stored isn't written, or recorded for undo.
     pub fn update_link(&mut self, link: &Link) -> Result<()> {
+        self.ensure_writable()?;
+        let mut link = link.clone();
+        let previous = self.get_link(link.id)?;
+        self.validator().validate(&mut link, previous.as_ref())?;
+        apply_derivers(&self.derivers, &mut link);
+        if previous.as_ref() == Some(&link) {
+            return Ok(());
+        }
+
         tokio::task::block_in_place(|| {
             self.doc
                 .blocking_lock()
-                .update_link(link)
+                .update_link(&link)
                 .context("Failed to update link in document")
         })?;
-        self.save()
+        self.save()?;
+        self.record_edit(format!("edit '{}'", link.title), vec![(link.id, previous)]);
+        Ok(())
+    }
+
+    /// Update and delete several links at once
+    ///
+    /// Every update is validated (as in [`Store::update_link`]) before
+    /// anything is written, so either all of the changes are saved, in a
+    /// single save, or none are.
+    pub fn update_links(&mut self, links: &[Link], deletes: &[Uuid]) -> Result<()> {
+        let label = match (links, deletes) {
+            ([link], []) => format!("edit '{}'", link.title),
+            _ => format!("edit {} links", links.len() + deletes.len()),
+        };
+        self.write_links(links, deletes, label)
+    }
+
+    /// [`Store::update_links`], recorded for undo as one edit under `label`
+    ///
+    /// For commands that change many links at once (a content check, a
+    /// refresh), so that they undo together rather than pushing the user's
+    /// own edits out of the history. Links that end up as they're stored
+    /// aren't written.
+    pub fn write_links(
+        &mut self,
+        links: &[Link],
+        deletes: &[Uuid],
+        label: impl Into<String>,
+    ) -> Result<()> {
+        self.ensure_writable()?;
+        let validator = self.validator();
+        let mut updated = Vec::with_capacity(links.len());
+        let mut before = Vec::with_capacity(links.len() + deletes.len());
+        for link in links {
+            let mut link = link.clone();
+            let previous = self.get_link(link.id)?;
+            validator
+                .validate(&mut link, previous.as_ref())
+                .with_context(|| format!("Link {}", link.id))?;
+            apply_derivers(&self.derivers, &mut link);
+            if previous.as_ref() == Some(&link) {
+                continue;
+            }
+            before.push((link.id, previous));
+            updated.push(link);
+        }
+        for id in deletes {
+            before.push((*id, self.get_link(*id)?));
+        }
+        if before.is_empty() {
+            return Ok(());
+        }
+
+        tokio::task::block_in_place(|| {
+            let mut doc = self.doc.blocking_lock();
+            for link in &updated {
+                doc.update_link(link)
+                    .context("Failed to update link in document")?;
+            }
+            for id in deletes {
+                doc.delete_link(*id)
+                    .context("Failed to delete link from document")?;
+            }
+            Ok::<_, anyhow::Error>(())
+        })?;
+        self.save()?;
+        self.record_edit(label.into(), before);
+        Ok(())
+    }
+
+    /// Save the accepted changes from another collection, in one save
+    ///
+    /// Added links keep their IDs, timestamps, and notes, but go through
+    /// the same checks as [`Store::add_link`] first; those that fail are
+    /// skipped and listed in the summary. Changed links get the incoming
+    /// tags and notes added. Links whose URL was saved here since the
+    /// changes were worked out are skipped. The accepted changes undo as
+    /// one edit.
+    pub fn accept_incoming(&mut self, changes: &[IncomingChange]) -> Result<IncomingSummary> {
+        self.ensure_writable()?;
+        let rules = self.domain_rules();
+        let validator = self.validator();
+        let mut summary = IncomingSummary::default();
+        let mut before = Vec::new();
+        tokio::task::block_in_place(|| {
+            let mut doc = self.doc.blocking_lock();
+            for change in changes {
+                match change {
+                    IncomingChange::Added { link } => {
+                        if doc.get_link(link.id)?.is_some()
+                            || doc
+                                .get_link_by_url(&link.url, self.config.url_trailing_slash)?
+                                .is_some()
+                        {
+                            continue;
+                        }
+                        let mut link = link.clone();
+                        if let Err(reason) = admit(&rules, &validator, &mut link) {
+                            summary.rejected.push((link, reason));
+                            continue;
+                        }
+                        apply_derivers(&self.derivers, &mut link);
+                        doc.add_link(&link).context("Failed to add link")?;
+                        before.push((link.id, None));
+                    }
+                    IncomingChange::Changed {
+                        link_id,
+                        tags,
+                        notes,
+                        ..
+                    } => {
+                        let Some(mut link) = doc.get_link(*link_id)? else {
+                            continue;
+                        };
+                        before.push((link.id, Some(link.clone())));
+                        if !tags.is_empty() {
+                            for tag in tags.iter().filter_map(|t| normalize_tag(t)) {
+                                link.add_tag(tag);
+                            }
+                            apply_derivers(&self.derivers, &mut link);
+                            doc.update_link(&link).context("Failed to update link")?;
+                        }
+                        for note in notes {
+                            doc.add_note_to_link(*link_id, note)
+                                .context("Failed to add note")?;
+                        }
+                    }
+                }
+                summary.applied += 1;
+            }
+            Ok::<_, anyhow::Error>(())
+        })?;
+        if summary.applied > 0 {
+            self.save()?;
+            self.record_edit(
+                format!("accept {} incoming changes", summary.applied),
+                before,
+            );
+        }
+        Ok(summary)
+    }
+
+    /// Record that a link was opened in the browser
+    ///
+    /// The time is synced, so the link shows as read on every device, and
+    /// the open is counted in the link's `open_count`, which adds up opens
+    /// from every device. Doesn't count as an edit: `updated_at` is left
+    /// alone. Nothing is written if `record_opens` is off, on a read-only
+    /// device, or if the link was already opened in the last
+    /// [`OPEN_DEBOUNCE_MINUTES`] (so reopening a link, or a script opening
+    /// links in a loop, doesn't flood the document and sync with changes).
+    ///
+    /// Returns whether the open was recorded.
+    pub fn record_open(&mut self, id: Uuid) -> Result<bool> {
+        if !self.config.record_opens || self.read_only {
+            return Ok(false);
+        }
+        let link = self
+            .get_link(id)?
+            .with_context(|| format!("Link not found: {}", id))?;
+        let now = Utc::now();
+        if link
+            .opened_at
+            .is_some_and(|opened| now - opened < Duration::minutes(OPEN_DEBOUNCE_MINUTES))
+        {
+            return Ok(false);
+        }
+        tokio::task::block_in_place(|| {
+            self.doc
+                .blocking_lock()
+                .record_open(id, now)
+                .context("Failed to record open")
+        })?;
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Record how far a link's archived text was read
+    ///
+    /// The position is synced, so reading can resume on another device.
+    /// Like an open, it isn't an edit: `updated_at` is left alone and it
+    /// can't be undone. Nothing is written on a read-only device.
+    ///
+    /// Returns whether the position was recorded.
+    pub fn set_read_position(&mut self, id: Uuid, position: ReadPosition) -> Result<bool> {
+        if self.read_only {
+            return Ok(false);
+        }
+        tokio::task::block_in_place(|| {
+            self.doc
+                .blocking_lock()
+                .set_read_position(id, &position)
+                .context("Failed to record read position")
+        })?;
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Work out what [`Store::import_links`] would do, without saving
+    ///
+    /// Each link goes through the same checks as [`Store::add_link`]:
+    /// blocked domains, validation, domain tags, and duplicate URLs, both
+    /// against saved links and earlier links in `links`.
+    pub fn plan_import(&self, links: Vec<Link>) -> Result<ImportSummary> {
+        let rules = self.domain_rules();
+        let validator = self.validator();
+        let mut saved: HashMap<String, Uuid> = HashMap::new();
+        for link in self.get_all_links(LinkSort::default())? {
+            for url in std::iter::once(&link.url).chain(link.canonical_url.as_ref()) {
+                saved.insert(normalize_url(url, self.config.url_trailing_slash), link.id);
+            }
+        }
+
+        let mut summary = ImportSummary::default();
+        for mut link in links {
+            if let Err(reason) = admit(&rules, &validator, &mut link) {
+                summary.rejected.push((link, reason));
+                continue;
+            }
+            if let Some(&existing) =
+                saved.get(&normalize_url(&link.url, self.config.url_trailing_slash))
+            {
+                summary.duplicates.push((link, existing));
+                continue;
+            }
+            apply_derivers(&self.derivers, &mut link);
+            saved.insert(
+                normalize_url(&link.url, self.config.url_trailing_slash),
+                link.id,
+            );
+            summary.added.push(link);
+        }
+        Ok(summary)
+    }
+
+    /// Add many links at once, skipping duplicates and invalid links
+    ///
+    /// See [`Store::plan_import`] for what's skipped. Unlike calling
+    /// [`Store::add_link`] for each link, the document is saved once.
+    pub fn import_links(&mut self, links: Vec<Link>) -> Result<ImportSummary> {
+        self.ensure_writable()?;
+        let summary = self.plan_import(links)?;
+        if summary.added.is_empty() {
+            return Ok(summary);
+        }
+        tokio::task::block_in_place(|| {
+            let mut doc = self.doc.blocking_lock();
+            for link in &summary.added {
+                doc.add_link(link)
+                    .with_context(|| format!("Failed to add {} to document", link.url))?;
+            }
+            anyhow::Ok(())
+        })?;
+        self.save()?;
+        self.record_edit(
+            format!("import {} links", summary.added.len()),
+            summary.added.iter().map(|link| (link.id, None)).collect(),
+        );
+        info!("Imported {} link(s)", summary.added.len());
+        Ok(summary)
+    }
+
+    /// Bring links back from an older copy of the document
+    ///
+    /// Links missing from the store are re-added as they were (same IDs,
+    /// timestamps, and notes); links that still exist get back any notes
+    /// they've lost. Everything is written as new changes, so nothing else
+    /// is rolled back. Links whose URL has since been saved again under a
+    /// new ID are skipped, and re-added links go through the same checks as
+    /// [`Store::add_link`], since the rules may have changed since the
+    /// copy was made. The restore undoes as one edit.
+    pub fn restore_links(&mut self, links: &[Link]) -> Result<RestoreSummary> {
+        self.ensure_writable()?;
+        let rules = self.domain_rules();
+        let validator = self.validator();
+        let mut summary = RestoreSummary::default();
+        let mut before = Vec::new();
+
+        for link in links {
+            match self.get_link(link.id)? {
+                None => {
+                    if self.get_link_by_url(&link.url)?.is_some() {
+                        summary.skipped_duplicates += 1;
+                        continue;
+                    }
+                    let mut link = link.clone();
+                    if let Err(reason) = admit(&rules, &validator, &mut link) {
+                        summary.rejected.push((link, reason));
+                        continue;
+                    }
+                    apply_derivers(&self.derivers, &mut link);
+                    tokio::task::block_in_place(|| {
+                        self.doc
+                            .blocking_lock()
+                            .add_link(&link)
+                            .context("Failed to restore link")
+                    })?;
+                    summary.links_restored += 1;
+                    before.push((link.id, None));
+                }
+                Some(live) => {
+                    let missing: Vec<&Note> = link
+                        .notes
+                        .iter()
+                        .filter(|note| live.get_note(note.id).is_none())
+                        .collect();
+                    if missing.is_empty() {
+                        summary.unchanged += 1;
+                    } else {
+                        before.push((link.id, Some(live.clone())));
+                    }
+                    for note in missing {
+                        tokio::task::block_in_place(|| {
+                            self.doc
+                                .blocking_lock()
+                                .add_note_to_link(link.id, note)
+                                .context("Failed to restore note")
+                        })?;
+                        summary.notes_restored += 1;
+                    }
+                }
+            }
+        }
+
+        if !before.is_empty() {
+            self.save()?;
+            self.record_edit(format!("restore {} links", before.len()), before);
+        }
+        Ok(summary)
+    }
+
+    /// Merge a duplicate link into another and delete it
+    ///
+    /// The kept link gets the duplicate's tags, authors, notes (highlights
+    /// included) and attachments, any fields it's missing, the earlier of
+    /// the two creation times, and the duplicate's ID in
+    /// [`Link::merged_from`]. The update and the delete are saved together,
+    /// and recorded as one edit, so [`Store::undo`] (or
+    /// [`Store::undo_merge`]) reverses both.
+    pub fn merge_links(&mut self, keep: Uuid, duplicate: Uuid) -> Result<LinkMerge> {
+        self.ensure_writable()?;
+        if keep == duplicate {
+            anyhow::bail!("Can't merge a link into itself");
+        }
+        let kept = self
+            .get_link(keep)?
+            .with_context(|| format!("Link not found: {}", keep))?;
+        let dup = self
+            .get_link(duplicate)?
+            .with_context(|| format!("Link not found: {}", duplicate))?;
+
+        let mut link = kept.clone();
+        for tag in &dup.tags {
+            if !link.tags.contains(tag) {
+                link.tags.push(tag.clone());
+            }
+        }
+        for author in &dup.author {
+            if !link.author.contains(author) {
+                link.author.push(author.clone());
+            }
+        }
+        link.notes.extend(dup.notes.iter().cloned());
+        for (name, hash) in &dup.attachments {
+            link.attachments
+                .entry(name.clone())
+                .or_insert_with(|| hash.clone());
+        }
+        link.description = link.description.or_else(|| dup.description.clone());
+        link.published_at = link.published_at.or(dup.published_at);
+        link.canonical_url = link.canonical_url.or_else(|| dup.canonical_url.clone());
+        link.language = link.language.or_else(|| dup.language.clone());
+        link.via = link.via.or_else(|| dup.via.clone());
+        link.source_context = link.source_context.or_else(|| dup.source_context.clone());
+        link.opened_at = link.opened_at.max(dup.opened_at);
+        link.created_at = link.created_at.min(dup.created_at);
+        link.merged_from.extend(dup.merged_from.iter().copied());
+        link.merged_from.push(dup.id);
+        link.updated_at = Utc::now();
+
+        let label = format!("merge '{}' into '{}'", dup.title, kept.title);
+        self.write_links(&[link], &[duplicate], label)?;
+        let merged = self
+            .get_link(keep)?
+            .with_context(|| format!("Link not found: {}", keep))?;
+        info!("Merged link {} into {}", duplicate, keep);
+        Ok(LinkMerge {
+            kept,
+            duplicate: dup,
+            merged,
+        })
+    }
+
+    /// Undo the last [`Store::merge_links`]
+    ///
+    /// The same as [`Store::undo`], but only if the last edit is a merge:
+    /// the kept link goes back to how it was and the duplicate is re-added
+    /// with its ID, in one save. Refused if either has been changed since.
+    /// Returns the undone merge, or None if the last edit isn't one.
+    pub fn undo_merge(&mut self) -> Result<Option<Edit>> {
+        self.ensure_writable()?;
+        let history = EditHistory::load(&self.config)?;
+        if !history.next_undo().is_some_and(Edit::is_merge) {
+            return Ok(None);
+        }
+        self.undo()
     }
 
     /// Delete a link
     pub fn delete_link(&mut self, id: Uuid) -> Result<()> {
+        self.ensure_writable()?;
+        let previous = self.get_link(id)?;
         tokio::task::block_in_place(|| {
             self.doc
                 .blocking_lock()
                 .delete_link(id)
                 .context("Failed to delete link from document")
         })?;
-        self.save()
+        self.save()?;
+        if let Some(link) = previous {
+            self.record_edit(format!("delete '{}'", link.title), vec![(id, Some(link))]);
+        }
+        Ok(())
     }
 
     /// Get a link by ID (includes notes)
@@ -287,121 +994,1224 @@ impl Store {
         tokio::task::block_in_place(|| {
             self.doc
                 .blocking_lock()
-                .get_link_by_url(url)
+                .get_link_by_url(url, self.config.url_trailing_slash)
                 .context("Failed to get link by URL")
         })
     }
 
-    /// Get all links
-    pub fn get_all_links(&self) -> Result<Vec<Link>> {
-        tokio::task::block_in_place(|| {
+    /// Find links whose URLs normalize to the same thing
+    ///
+    /// Links saved before URLs were normalized this way, with another
+    /// `url_trailing_slash` policy, or on two devices before they synced can
+    /// share a page. Merge each group with [`Store::merge_links`].
+    pub fn find_duplicates(&self) -> Result<Vec<DuplicateLinks>> {
+        let links = self.get_all_links(LinkSort::default())?;
+        Ok(find_duplicate_links(&links, self.config.url_trailing_slash))
+    }
+
+    /// Get all links, in the given order
+    pub fn get_all_links(&self, sort: LinkSort) -> Result<Vec<Link>> {
+        let mut links = tokio::task::block_in_place(|| {
             self.doc
                 .blocking_lock()
                 .get_all_links()
                 .context("Failed to get links")
-        })
+        })?;
+        sort.sort(&mut links);
+        Ok(links)
     }
 
-    /// Get links by tag
-    pub fn get_links_by_tag(&self, tag: &str) -> Result<Vec<Link>> {
+    /// Get one page of all links, in the given order
+    ///
+    /// For lists too long to load at once; see
+    /// [`RottDocument::get_links_page`].
+    pub fn get_links_page(&self, offset: usize, limit: usize, sort: LinkSort) -> Result<LinksPage> {
         tokio::task::block_in_place(|| {
             self.doc
                 .blocking_lock()
-                .get_links_by_tag(tag)
-                .context("Failed to get links by tag")
+                .get_links_page(offset, limit, sort)
+                .context("Failed to get links")
         })
     }
 
-    /// Search links using substring matching
-    pub fn search_links(&self, query: &str) -> Result<Vec<Link>> {
+    /// Find records that can only be read in part
+    ///
+    /// Reads carry on past malformed records; this lists what was wrong
+    /// with them.
+    pub fn check_records(&self) -> Result<Vec<RecordWarning>> {
         tokio::task::block_in_place(|| {
             self.doc
                 .blocking_lock()
-                .search_links(query)
-                .context("Failed to search links")
+                .get_all_links_with_warnings()
+                .map(|(_, warnings)| warnings)
+                .context("Failed to read links")
         })
     }
 
-    // ==================== Note Operations (via Link) ====================
-
-    /// Add a note to a link
-    pub fn add_note_to_link(&mut self, link_id: Uuid, note: &Note) -> Result<()> {
+    /// Collect statistics about the document's history and size
+    pub fn document_stats(&self, top: usize) -> Result<DocumentStats> {
         tokio::task::block_in_place(|| {
             self.doc
                 .blocking_lock()
-                .add_note_to_link(link_id, note)
-                .context("Failed to add note to link")
-        })?;
-        self.save()
+                .stats(top)
+                .context("Failed to collect document statistics")
+        })
     }
 
-    /// Remove a note from a link
-    pub fn remove_note_from_link(&mut self, link_id: Uuid, note_id: Uuid) -> Result<()> {
-        tokio::task::block_in_place(|| {
-            self.doc
-                .blocking_lock()
-                .remove_note_from_link(link_id, note_id)
-                .context("Failed to remove note from link")
-        })?;
-        self.save()
+    /// Create a full backup of the document, its archives and attachments,
+    /// and the configuration
+    ///
+    /// Only archives and attachments on this device are included; see
+    /// [`missing_archives`](Self::missing_archives).
+    pub fn backup(&self) -> Result<Backup> {
+        let document = tokio::task::block_in_place(|| self.doc.blocking_lock().save());
+        let mut archives = Vec::new();
+        for (_, id) in self.archives()? {
+            if let Some(mut archive) = self.load_archive(&id)? {
+                archives.push(archive.save());
+            }
+        }
+        let blobs = AttachmentStore::from_config(&self.config);
+        let mut attachments = BTreeMap::new();
+        for (hash, _) in blobs.blobs()? {
+            if let Some(content) = blobs.get(&hash)? {
+                attachments.insert(hash, content);
+            }
+        }
+        Ok(Backup::new(self.root_id(), document, &self.config)
+            .with_archives(archives)
+            .with_attachments(attachments))
     }
 
-    // ==================== Tag Operations ====================
-
-    /// Get all unique tags
-    pub fn get_all_tags(&self) -> Result<Vec<String>> {
+    /// Create an anonymized copy of the document for bug reports (see
+    /// [`crate::anonymize`])
+    pub fn anonymized(&self) -> Result<RottDocument> {
         tokio::task::block_in_place(|| {
-            self.doc
-                .blocking_lock()
-                .get_all_tags()
-                .context("Failed to get tags")
+            anonymize_document(&self.doc.blocking_lock()).context("Failed to anonymize document")
         })
     }
 
-    /// Get tags with usage counts
-    pub fn get_tags_with_counts(&self) -> Result<Vec<(String, i64)>> {
+    /// Replay the document's history as events (see [`crate::events`])
+    pub fn replay_events(&self, each: impl FnMut(StoreEvent)) -> Result<()> {
         tokio::task::block_in_place(|| {
             self.doc
                 .blocking_lock()
-                .get_tags_with_counts()
-                .context("Failed to get tag counts")
+                .replay_events(each)
+                .context("Failed to replay document history")
         })
     }
 
-    // ==================== Stats ====================
+    /// Report what changed between `since` and `until` (see [`crate::report`])
+    pub fn change_report(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<ChangeReport> {
+        let mut builder = ReportBuilder::new(since, until);
+        self.replay_events(|event| builder.apply(&event))?;
+        let archived: HashSet<Uuid> = self.archived_links()?.iter().map(|link| link.id).collect();
+        Ok(builder.finish(&archived))
+    }
 
-    /// Get count of links
-    pub fn link_count(&self) -> Result<i64> {
-        tokio::task::block_in_place(|| {
+    /// Rebuild projections from the event log and save them under
+    /// `projections/` in the collection's directory
+    ///
+    /// Returns the number of events replayed and where each projection was
+    /// saved.
+    pub fn reindex(
+        &self,
+        projections: &mut [Box<dyn Projection>],
+    ) -> Result<(usize, Vec<PathBuf>)> {
+        let (events, heads) = tokio::task::block_in_place(|| {
+            let mut doc = self.doc.blocking_lock();
+            let events = rebuild(&mut doc, projections)?;
+            let heads: Vec<String> = doc.heads().iter().map(|h| h.to_string()).collect();
+            anyhow::Ok((events, heads))
+        })?;
+        let paths = projections
+            .iter()
+            .map(|p| save_projection(&self.config.store_dir(), p.as_ref(), &heads, events))
+            .collect::<Result<Vec<_>>>()?;
+        Ok((events, paths))
+    }
+
+    /// Get links by tag, in the given order
+    pub fn get_links_by_tag(&self, tag: &str, sort: LinkSort) -> Result<Vec<Link>> {
+        let mut links = tokio::task::block_in_place(|| {
             self.doc
                 .blocking_lock()
-                .link_count()
-                .map(|c| c as i64)
-                .context("Failed to count links")
-        })
+                .get_links_by_tag(tag)
+                .context("Failed to get links by tag")
+        })?;
+        sort.sort(&mut links);
+        Ok(links)
     }
 
-    /// Get count of notes (across all links)
-    pub fn note_count(&self) -> Result<i64> {
+    /// Get the favorite links (see [`Config::is_favorite`])
+    pub fn favorite_links(&self) -> Result<Vec<Link>> {
+        let mut links = self.get_all_links(LinkSort::default())?;
+        links.retain(|link| self.config.is_favorite(link));
+        Ok(links)
+    }
+
+    /// Get links whose expiry time has passed, soonest expired first
+    pub fn expired_links(&self, now: DateTime<Utc>) -> Result<Vec<Link>> {
+        let mut links = self.get_all_links(LinkSort::default())?;
+        links.retain(|link| link.is_expired(now));
+        links.sort_by_key(|link| link.expires_at);
+        Ok(links)
+    }
+
+    /// Get the most opened links, most opened first
+    ///
+    /// Links that have never been opened are left out. Ties go to the link
+    /// opened most recently.
+    pub fn most_opened_links(&self, limit: usize) -> Result<Vec<Link>> {
+        let mut links = self.get_all_links(LinkSort::default())?;
+        links.retain(|link| link.open_count > 0);
+        links.sort_by(|a, b| {
+            b.open_count
+                .cmp(&a.open_count)
+                .then(b.opened_at.cmp(&a.opened_at))
+        });
+        links.truncate(limit);
+        Ok(links)
+    }
+
+    /// Get links by an author (ignoring case)
+    pub fn get_links_by_author(&self, author: &str) -> Result<Vec<Link>> {
         tokio::task::block_in_place(|| {
             self.doc
                 .blocking_lock()
-                .note_count()
-                .map(|c| c as i64)
-                .context("Failed to count notes")
+                .get_links_by_author(author)
+                .context("Failed to get links by author")
         })
     }
 
-    // ==================== Advanced ====================
+    /// Search links and their notes with the configured provider (supports
+    /// `author:`; see [`crate::search`])
+    pub fn search_links(&self, query: &str) -> Result<Vec<Link>> {
+        Ok(self
+            .search(query)?
+            .into_iter()
+            .map(|hit| hit.link)
+            .collect())
+    }
 
-    /// Save the document to disk
+    /// Search like [`search_links`](Self::search_links), with the note each
+    /// link matched by (if any)
     ///
-    /// This first merges any external changes from disk (e.g., from CLI
-    /// while TUI is running), then saves the merged document.
-    pub fn save(&mut self) -> Result<()> {
-        tokio::task::block_in_place(|| {
+    /// An index is first sent the links changed since it was last brought
+    /// up to date; only the links it matches are read from the document.
+    pub fn search(&self, query: &str) -> Result<Vec<SearchHit>> {
+        let query = SearchQuery::parse(query);
+        tokio::task::block_in_place(|| -> Result<Vec<SearchHit>> {
             let mut doc = self.doc.blocking_lock();
-
+            let ids = match self.search {
+                Some(ref index) => {
+                    update_index(index.as_ref(), &mut doc)
+                        .context("Failed to update search index")?;
+                    index.search(&query)?
+                }
+                None => None,
+            };
+            let Some(ids) = ids else {
+                return Ok(doc
+                    .get_all_links()?
+                    .into_iter()
+                    .filter_map(|link| query.hit(link))
+                    .collect());
+            };
+            let mut hits = Vec::new();
+            for id in ids {
+                let Some(link) = doc.get_link(id)? else {
+                    continue;
+                };
+                if query.matches_operators(&link) {
+                    // The note the text is in, if the index found it in one
+                    let note_id = query.matching_note(&link).map(|note| note.id);
+                    hits.push(SearchHit { link, note_id });
+                }
+            }
+            Ok(hits)
+        })
+        .context("Failed to search links")
+    }
+
+    // ==================== Note Operations (via Link) ====================
+
+    /// Add a note to a link
+    pub fn add_note_to_link(&mut self, link_id: Uuid, note: &Note) -> Result<()> {
+        self.ensure_writable()?;
+        let previous = self.get_link(link_id)?;
+        tokio::task::block_in_place(|| {
+            self.doc
+                .blocking_lock()
+                .add_note_to_link(link_id, note)
+                .context("Failed to add note to link")
+        })?;
+        self.save()?;
+        self.record_note_edit("add a note to", link_id, previous);
+        Ok(())
+    }
+
+    /// Get one page of a link's notes, in threaded order, reading only the
+    /// notes on the page (None if the link doesn't exist)
+    pub fn get_notes_page(
+        &self,
+        link_id: Uuid,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Option<NotesPage>> {
+        tokio::task::block_in_place(|| {
+            self.doc
+                .blocking_lock()
+                .get_notes_page(link_id, offset, limit)
+                .context("Failed to get notes")
+        })
+    }
+
+    /// Remove a note from a link
+    pub fn remove_note_from_link(&mut self, link_id: Uuid, note_id: Uuid) -> Result<()> {
+        self.ensure_writable()?;
+        let previous = self.get_link(link_id)?;
+        tokio::task::block_in_place(|| {
+            self.doc
+                .blocking_lock()
+                .remove_note_from_link(link_id, note_id)
+                .context("Failed to remove note from link")
+        })?;
+        self.save()?;
+        self.record_note_edit("remove a note from", link_id, previous);
+        Ok(())
+    }
+
+    /// Merge a group of identical notes, keeping its oldest note
+    ///
+    /// Copies on other links are replaced by a note referring to the kept
+    /// one; copies on the kept note's own link are removed. Saved once.
+    pub fn merge_duplicate_notes(&mut self, group: &DuplicateNotes) -> Result<()> {
+        self.ensure_writable()?;
+        let mut link_ids = vec![group.keep.link_id];
+        for duplicate in &group.duplicates {
+            if !link_ids.contains(&duplicate.link_id) {
+                link_ids.push(duplicate.link_id);
+            }
+        }
+        let before = self.links_before(&link_ids)?;
+        let reference = group.reference_body();
+        tokio::task::block_in_place(|| {
+            let mut doc = self.doc.blocking_lock();
+            let mut referenced: Vec<Uuid> = Vec::new();
+            for duplicate in &group.duplicates {
+                doc.remove_note_from_link(duplicate.link_id, duplicate.note.id)
+                    .context("Failed to remove duplicate note")?;
+                if duplicate.link_id == group.keep.link_id
+                    || referenced.contains(&duplicate.link_id)
+                {
+                    continue;
+                }
+                let mut note = Note::new(reference.clone());
+                note.created_at = duplicate.note.created_at;
+                note.pinned = duplicate.note.pinned;
+                note.order = duplicate.note.order;
+                doc.add_note_to_link(duplicate.link_id, &note)
+                    .context("Failed to add reference note")?;
+                referenced.push(duplicate.link_id);
+            }
+            Ok::<_, anyhow::Error>(())
+        })?;
+        self.save()?;
+        self.record_edit(
+            format!("dedupe {} copies of a note", group.duplicates.len()),
+            before,
+        );
+        Ok(())
+    }
+
+    /// Update an existing note's title and body
+    pub fn update_note(&mut self, link_id: Uuid, note: &Note) -> Result<()> {
+        self.ensure_writable()?;
+        let previous = self.get_link(link_id)?;
+        tokio::task::block_in_place(|| {
+            self.doc
+                .blocking_lock()
+                .update_note(link_id, note)
+                .context("Failed to update note")
+        })?;
+        self.save()?;
+        self.record_note_edit("edit a note on", link_id, previous);
+        Ok(())
+    }
+
+    /// Update many notes in one save
+    ///
+    /// Every note is checked before anything is written, so either all of
+    /// the edits land or none do.
+    pub fn update_notes(&mut self, notes: &[(Uuid, Note)]) -> Result<()> {
+        self.ensure_writable()?;
+        let mut link_ids: Vec<Uuid> = Vec::new();
+        for (link_id, _) in notes {
+            if !link_ids.contains(link_id) {
+                link_ids.push(*link_id);
+            }
+        }
+        let before = self.links_before(&link_ids)?;
+        tokio::task::block_in_place(|| {
+            let mut doc = self.doc.blocking_lock();
+            for (link_id, note) in notes {
+                let link = doc
+                    .get_link(*link_id)?
+                    .with_context(|| format!("Link not found: {}", link_id))?;
+                if link.get_note(note.id).is_none() {
+                    anyhow::bail!("Note not found: {}", note.id);
+                }
+            }
+            for (link_id, note) in notes {
+                doc.update_note(*link_id, note)
+                    .context("Failed to update note")?;
+            }
+            Ok::<_, anyhow::Error>(())
+        })?;
+        self.save()?;
+        self.record_edit(format!("edit {} notes", notes.len()), before);
+        Ok(())
+    }
+
+    /// Pin or unpin a note (pinned notes are listed first)
+    pub fn pin_note(&mut self, link_id: Uuid, note_id: Uuid, pinned: bool) -> Result<()> {
+        self.ensure_writable()?;
+        let previous = self.get_link(link_id)?;
+        tokio::task::block_in_place(|| {
+            let mut doc = self.doc.blocking_lock();
+            let link = doc
+                .get_link(link_id)?
+                .with_context(|| format!("Link not found: {}", link_id))?;
+            let mut note = link
+                .get_note(note_id)
+                .cloned()
+                .with_context(|| format!("Note not found: {}", note_id))?;
+            note.pinned = pinned;
+            doc.set_note_position(link_id, &note)
+                .context("Failed to pin note")
+        })?;
+        self.save()?;
+        let action = if pinned {
+            "pin a note on"
+        } else {
+            "unpin a note on"
+        };
+        self.record_note_edit(action, link_id, previous);
+        Ok(())
+    }
+
+    /// Move a note one place up or down among the link's notes
+    ///
+    /// Returns false (and changes nothing) if the note is already first or
+    /// last among notes with the same pinned state.
+    pub fn move_note(&mut self, link_id: Uuid, note_id: Uuid, up: bool) -> Result<bool> {
+        self.ensure_writable()?;
+        let previous = self.get_link(link_id)?;
+        let moved = tokio::task::block_in_place(|| -> Result<bool> {
+            let mut doc = self.doc.blocking_lock();
+            let mut link = doc
+                .get_link(link_id)?
+                .with_context(|| format!("Link not found: {}", link_id))?;
+            if link.get_note(note_id).is_none() {
+                anyhow::bail!("Note not found: {}", note_id);
+            }
+            let changed = link.move_note(note_id, up);
+            for note in &changed {
+                doc.set_note_position(link_id, note)
+                    .context("Failed to move note")?;
+            }
+            Ok(!changed.is_empty())
+        })?;
+        if moved {
+            self.save()?;
+            self.record_note_edit("move a note on", link_id, previous);
+        }
+        Ok(moved)
+    }
+
+    /// Split a note into one note per top-level section (see
+    /// [`crate::sections`])
+    ///
+    /// The note keeps its title and the text before its first section; if
+    /// it has neither, it becomes the first section instead. Each other
+    /// section becomes a new note, titled by its heading and placed right
+    /// after the note. Saved once. Returns the new notes (empty, with
+    /// nothing changed, if there's nothing to split).
+    pub fn split_note(&mut self, link_id: Uuid, note_id: Uuid) -> Result<Vec<Note>> {
+        self.ensure_writable()?;
+        let previous = self.get_link(link_id)?;
+        let added = tokio::task::block_in_place(|| -> Result<Vec<Note>> {
+            let mut doc = self.doc.blocking_lock();
+            let link = doc
+                .get_link(link_id)?
+                .with_context(|| format!("Link not found: {}", link_id))?;
+            let mut kept = link
+                .get_note(note_id)
+                .cloned()
+                .with_context(|| format!("Note not found: {}", note_id))?;
+
+            let parsed = NoteSections::parse(&kept.body);
+            let mut sections = parsed.sections.into_iter();
+            if parsed.preamble.is_empty() && kept.title.is_none() {
+                let Some(first) = sections.next() else {
+                    return Ok(Vec::new());
+                };
+                kept.title = Some(first.heading);
+                kept.body = first.body.trim_matches('\n').to_string();
+            } else {
+                kept.body = parsed.preamble;
+            }
+            let sections: Vec<_> = sections.collect();
+            if sections.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            // Order keys between the note and the one after it
+            let start = kept.sort_key();
+            let next = link
+                .notes()
+                .iter()
+                .filter(|n| n.pinned == kept.pinned && n.sort_key() > start)
+                .map(Note::sort_key)
+                .reduce(f64::min);
+            let step = next.map_or(1.0, |next| (next - start) / (sections.len() + 1) as f64);
+            kept.order = Some(start);
+            kept.updated_at = Some(Utc::now());
+
+            let added: Vec<Note> = sections
+                .into_iter()
+                .enumerate()
+                .map(|(i, section)| {
+                    let mut note =
+                        Note::with_title(section.heading, section.body.trim_matches('\n'));
+                    note.created_at = kept.created_at;
+                    note.pinned = kept.pinned;
+                    note.parent_id = kept.parent_id;
+                    note.order = Some(start + step * (i + 1) as f64);
+                    note
+                })
+                .collect();
+            doc.update_note(link_id, &kept)
+                .context("Failed to update note")?;
+            for note in &added {
+                doc.add_note_to_link(link_id, note)
+                    .context("Failed to add note")?;
+            }
+            Ok(added)
+        })?;
+        if !added.is_empty() {
+            self.save()?;
+            self.record_note_edit("split a note on", link_id, previous);
+        }
+        Ok(added)
+    }
+
+    /// Get the version history of a note, oldest first
+    pub fn note_history(&self, link_id: Uuid, note_id: Uuid) -> Result<Vec<NoteVersion>> {
+        tokio::task::block_in_place(|| {
+            self.doc
+                .blocking_lock()
+                .note_history(link_id, note_id)
+                .context("Failed to read note history")
+        })
+    }
+
+    // ==================== Tag Operations ====================
+
+    /// Get all unique tags
+    pub fn get_all_tags(&self) -> Result<Vec<String>> {
+        tokio::task::block_in_place(|| {
+            self.doc
+                .blocking_lock()
+                .get_all_tags()
+                .context("Failed to get tags")
+        })
+    }
+
+    /// Get the description of a tag, if it has one
+    pub fn tag_description(&self, tag: &str) -> Result<Option<String>> {
+        tokio::task::block_in_place(|| {
+            self.doc
+                .blocking_lock()
+                .get_tag_description(tag)
+                .context("Failed to get tag description")
+        })
+    }
+
+    /// Get every tag description, by tag
+    pub fn tag_descriptions(&self) -> Result<BTreeMap<String, String>> {
+        tokio::task::block_in_place(|| {
+            self.doc
+                .blocking_lock()
+                .get_tag_descriptions()
+                .context("Failed to get tag descriptions")
+        })
+    }
+
+    /// Set a tag's description (Markdown), or clear it with None
+    ///
+    /// The description is synced like links, so every device shows it.
+    pub fn set_tag_description(&mut self, tag: &str, description: Option<&str>) -> Result<()> {
+        self.ensure_writable()?;
+        let Some(key) = normalize_tag(tag) else {
+            return Err(ValidationError::InvalidTag(tag.to_string()).into());
+        };
+        let before = self.tag_settings(&key)?;
+        tokio::task::block_in_place(|| {
+            self.doc
+                .blocking_lock()
+                .set_tag_description(tag, description)
+                .context("Failed to set tag description")
+        })?;
+        self.save()?;
+        self.record(
+            format!("describe tag '{}'", key),
+            Vec::new(),
+            vec![(key, before)],
+            None,
+        );
+        Ok(())
+    }
+
+    /// Get the visibility marks on tags
+    pub fn tag_visibility(&self) -> Result<Visibility> {
+        tokio::task::block_in_place(|| {
+            self.doc
+                .blocking_lock()
+                .get_tag_visibility()
+                .map(Visibility::new)
+                .context("Failed to get tag visibility")
+        })
+    }
+
+    /// Mark a tag private or public, or clear its mark with None
+    pub fn set_tag_visibility(
+        &mut self,
+        tag: &str,
+        visibility: Option<TagVisibility>,
+    ) -> Result<()> {
+        self.ensure_writable()?;
+        let Some(key) = normalize_tag(tag) else {
+            return Err(ValidationError::InvalidTag(tag.to_string()).into());
+        };
+        let before = self.tag_settings(&key)?;
+        tokio::task::block_in_place(|| {
+            self.doc
+                .blocking_lock()
+                .set_tag_visibility(tag, visibility)
+                .context("Failed to set tag visibility")
+        })?;
+        self.save()?;
+        let label = match visibility {
+            Some(visibility) => format!("mark tag '{}' {}", key, visibility.as_str()),
+            None => format!("unmark tag '{}'", key),
+        };
+        self.record(label, Vec::new(), vec![(key, before)], None);
+        Ok(())
+    }
+
+    /// A tag's description and visibility mark, for recording an edit
+    fn tag_settings(&self, tag: &str) -> Result<TagSettings> {
+        Ok(TagSettings {
+            description: self.tag_descriptions()?.remove(tag),
+            visibility: self.tag_visibility()?.tags().get(tag).copied(),
+        })
+    }
+
+    /// Get the links that may be exported: all but those with a private tag
+    ///
+    /// Everything that copies links out of the collection goes through
+    /// this (see [`crate::visibility`]).
+    pub fn shareable_links(&self) -> Result<Vec<Link>> {
+        let visibility = self.tag_visibility()?;
+        let mut links = self.get_all_links(LinkSort::default())?;
+        links.retain(|link| !visibility.is_private(link));
+        Ok(links)
+    }
+
+    /// Merge tags that differ only in case or punctuation
+    ///
+    /// Run once when a document from before normalization is opened (see
+    /// [`CURRENT_SCHEMA_VERSION`]), and by `rott maintenance normalize-tags`
+    /// for tags synced from older versions since. Saves only if a link
+    /// changed; returns the number of links changed.
+    pub fn normalize_tags(&mut self) -> Result<usize> {
+        self.ensure_writable()?;
+        let changed = tokio::task::block_in_place(|| {
+            self.doc
+                .blocking_lock()
+                .normalize_tags()
+                .context("Failed to normalize tags")
+        })?;
+        if changed > 0 {
+            info!("Normalized tags on {} link(s)", changed);
+            self.save()?;
+        }
+        Ok(changed)
+    }
+
+    /// Get tags with usage counts
+    pub fn get_tags_with_counts(&self) -> Result<Vec<(String, i64)>> {
+        tokio::task::block_in_place(|| {
+            self.doc
+                .blocking_lock()
+                .get_tags_with_counts()
+                .context("Failed to get tag counts")
+        })
+    }
+
+    /// Get the tag hierarchy (see [`crate::tag_tree`])
+    pub fn tag_tree(&self) -> Result<Vec<TagNode>> {
+        Ok(tag_tree(&self.get_all_links(LinkSort::default())?))
+    }
+
+    /// Remove the descriptions and visibility marks of tags no link uses
+    ///
+    /// Tags on archived links count as used. A dry run reports what would
+    /// be removed without changing anything. Returns the tags removed.
+    pub fn prune_tags(&mut self, dry_run: bool) -> Result<Vec<String>> {
+        let mut used = HashSet::new();
+        for link in self
+            .get_all_links(LinkSort::default())?
+            .iter()
+            .chain(&self.archived_links()?)
+        {
+            used.extend(normalize_tags(&link.tags));
+        }
+        let mut unused: Vec<String> = self
+            .tag_descriptions()?
+            .into_keys()
+            .chain(self.tag_visibility()?.tags().keys().cloned())
+            .filter(|tag| !used.contains(tag))
+            .collect();
+        unused.sort();
+        unused.dedup();
+        if dry_run || unused.is_empty() {
+            return Ok(unused);
+        }
+
+        self.ensure_writable()?;
+        let before = unused
+            .iter()
+            .map(|tag| Ok((tag.clone(), self.tag_settings(tag)?)))
+            .collect::<Result<Vec<_>>>()?;
+        tokio::task::block_in_place(|| {
+            let mut doc = self.doc.blocking_lock();
+            for tag in &unused {
+                doc.set_tag_description(tag, None)?;
+                doc.set_tag_visibility(tag, None)?;
+            }
+            Ok::<_, DocumentError>(())
+        })
+        .context("Failed to prune tags")?;
+        info!("Pruned {} unused tag(s)", unused.len());
+        self.save()?;
+        self.record(
+            format!("prune {} tags", unused.len()),
+            Vec::new(),
+            before,
+            None,
+        );
+        Ok(unused)
+    }
+
+    /// Get authors with usage counts
+    pub fn get_authors_with_counts(&self) -> Result<Vec<(String, i64)>> {
+        tokio::task::block_in_place(|| {
+            self.doc
+                .blocking_lock()
+                .get_authors_with_counts()
+                .context("Failed to get author counts")
+        })
+    }
+
+    // ==================== Archives ====================
+
+    /// Archive documents, by year
+    pub fn archives(&self) -> Result<BTreeMap<i32, DocumentId>> {
+        tokio::task::block_in_place(|| {
+            self.doc
+                .blocking_lock()
+                .get_archives()
+                .context("Failed to read archives")
+        })
+    }
+
+    /// Load an archive document, if it's on this device
+    pub fn load_archive(&self, id: &DocumentId) -> Result<Option<RottDocument>> {
+        self.persistence
+            .load_document(id)
+            .context("Failed to load archive")
+    }
+
+    /// Save an archive document
+    pub fn save_archive(&self, archive: &mut RottDocument) -> Result<()> {
+        self.persistence
+            .save_document(archive)
+            .context("Failed to save archive")
+    }
+
+    /// Move links into per-year archive documents
+    ///
+    /// Each link (with its notes) goes to the archive for the year it was
+    /// saved in, created and recorded in the root document if needed.
+    /// Archived links are left out of the root document, so listing,
+    /// searching and syncing it stay fast; they're only read when asked for
+    /// with [`archived_links`](Self::archived_links) or
+    /// [`search_archived`](Self::search_archived).
+    ///
+    /// Archive from one device at a time: two devices creating the same
+    /// year's archive before syncing would each record their own.
+    ///
+    /// Returns the number of links moved.
+    pub fn archive_links(&mut self, ids: &[Uuid]) -> Result<usize> {
+        self.ensure_writable()?;
+        let before = self.links_before(ids)?;
+        let moved = self.move_to_archives(ids)?;
+        if moved > 0 {
+            self.record(
+                format!("archive {} links", moved),
+                before,
+                Vec::new(),
+                Some(ArchiveMove::Archived),
+            );
+        }
+        Ok(moved)
+    }
+
+    /// Move links into their archives, without recording it for undo
+    fn move_to_archives(&mut self, ids: &[Uuid]) -> Result<usize> {
+        let archives = self.archives()?;
+        let mut by_year: BTreeMap<i32, Vec<Link>> = BTreeMap::new();
+        for &id in ids {
+            if let Some(link) = self.get_link(id)? {
+                by_year
+                    .entry(link.created_at.year())
+                    .or_default()
+                    .push(link);
+            }
+        }
+
+        let mut created = Vec::new();
+        let mut moved = Vec::new();
+        for (year, links) in by_year {
+            let mut archive = match archives.get(&year) {
+                Some(id) => self.load_archive(id)?.with_context(|| {
+                    format!(
+                        "The {} archive isn't on this device yet; run `rott sync` first",
+                        year
+                    )
+                })?,
+                None => {
+                    let archive = RottDocument::new();
+                    created.push((year, *archive.id()));
+                    archive
+                }
+            };
+            for link in &links {
+                if archive.get_link(link.id)?.is_some() {
+                    archive.update_link(link)
+                } else {
+                    archive.add_link(link)
+                }
+                .context("Failed to archive link")?;
+            }
+            self.save_archive(&mut archive)?;
+            moved.extend(links.iter().map(|link| link.id));
+        }
+
+        if moved.is_empty() {
+            return Ok(0);
+        }
+        tokio::task::block_in_place(|| {
+            let mut doc = self.doc.blocking_lock();
+            for (year, id) in &created {
+                doc.set_archive(*year, id)
+                    .context("Failed to record archive")?;
+            }
+            for &id in &moved {
+                doc.delete_link(id)
+                    .context("Failed to remove archived link")?;
+            }
+            Ok::<_, anyhow::Error>(())
+        })?;
+        self.save()?;
+        Ok(moved.len())
+    }
+
+    /// Years whose archive is recorded but not yet synced to this device
+    pub fn missing_archives(&self) -> Result<Vec<i32>> {
+        let mut missing = Vec::new();
+        for (year, id) in self.archives()? {
+            if self.load_archive(&id)?.is_none() {
+                missing.push(year);
+            }
+        }
+        Ok(missing)
+    }
+
+    /// Get every archived link on this device
+    pub fn archived_links(&self) -> Result<Vec<Link>> {
+        self.read_archives(|archive| archive.get_all_links())
+    }
+
+    /// Search archived links (same syntax as [`search_links`](Self::search_links))
+    pub fn search_archived(&self, query: &str) -> Result<Vec<Link>> {
+        self.read_archives(|archive| archive.search_links(query))
+    }
+
+    /// Move an archived link back into the root document
+    pub fn unarchive_link(&mut self, id: Uuid) -> Result<Link> {
+        self.ensure_writable()?;
+        let before = self.links_before(&[id])?;
+        let link = self.move_from_archives(id)?;
+        self.record(
+            format!("unarchive '{}'", link.title),
+            before,
+            Vec::new(),
+            Some(ArchiveMove::Unarchived),
+        );
+        Ok(link)
+    }
+
+    /// Move a link out of its archive, without recording it for undo
+    fn move_from_archives(&mut self, id: Uuid) -> Result<Link> {
+        for (_, archive_id) in self.archives()? {
+            let Some(mut archive) = self.load_archive(&archive_id)? else {
+                continue;
+            };
+            let Some(link) = archive.get_link(id)? else {
+                continue;
+            };
+            if self.get_link(id)?.is_none() {
+                tokio::task::block_in_place(|| {
+                    self.doc
+                        .blocking_lock()
+                        .add_link(&link)
+                        .context("Failed to restore archived link")
+                })?;
+                self.save()?;
+            }
+            archive
+                .delete_link(id)
+                .context("Failed to remove link from archive")?;
+            self.save_archive(&mut archive)?;
+            return Ok(link);
+        }
+        anyhow::bail!("No archived link {}", id)
+    }
+
+    /// Hashes of every attachment a link refers to, archived links included
+    ///
+    /// Fails while an archive hasn't synced to this device yet: the
+    /// attachments its links refer to would look unreferenced.
+    pub fn referenced_attachments(&self) -> Result<HashSet<String>> {
+        let missing = self.missing_archives()?;
+        if !missing.is_empty() {
+            let years: Vec<String> = missing.iter().map(i32::to_string).collect();
+            anyhow::bail!(
+                "The {} archive(s) aren't on this device yet, so attachments they refer to \
+                 can't be told from unused ones; run `rott sync` first",
+                years.join(", ")
+            );
+        }
+        let mut links = self.get_all_links(LinkSort::default())?;
+        links.extend(self.archived_links()?);
+        Ok(links
+            .into_iter()
+            .flat_map(|link| link.attachments.into_values())
+            .collect())
+    }
+
+    /// Collect links from each archive on this device
+    ///
+    /// Links that are also in the root document (restored elsewhere before
+    /// the archive synced) are left out.
+    fn read_archives(
+        &self,
+        read: impl Fn(&RottDocument) -> Result<Vec<Link>, DocumentError>,
+    ) -> Result<Vec<Link>> {
+        let mut links = Vec::new();
+        for (_, id) in self.archives()? {
+            if let Some(archive) = self.load_archive(&id)? {
+                links.extend(read(&archive).context("Failed to read archive")?);
+            }
+        }
+        let live: HashSet<Uuid> = self
+            .get_all_links(LinkSort::default())?
+            .iter()
+            .map(|link| link.id)
+            .collect();
+        links.retain(|link| !live.contains(&link.id));
+        Ok(links)
+    }
+
+    // ==================== Stats ====================
+
+    /// Get count of links
+    pub fn link_count(&self) -> Result<i64> {
+        tokio::task::block_in_place(|| {
+            self.doc
+                .blocking_lock()
+                .link_count()
+                .map(|c| c as i64)
+                .context("Failed to count links")
+        })
+    }
+
+    /// Get count of notes (across all links)
+    pub fn note_count(&self) -> Result<i64> {
+        tokio::task::block_in_place(|| {
+            self.doc
+                .blocking_lock()
+                .note_count()
+                .map(|c| c as i64)
+                .context("Failed to count notes")
+        })
+    }
+
+    // ==================== Undo ====================
+
+    /// Undo the last edit (see [`crate::history`])
+    ///
+    /// The links and tag settings it changed are written back as they
+    /// were, in one save; archived links are moved back out of their
+    /// archives. Refused if any of them has been changed since. Returns
+    /// the undone edit, or None if there's nothing to undo.
+    pub fn undo(&mut self) -> Result<Option<Edit>> {
+        self.ensure_writable()?;
+        let mut history = EditHistory::load(&self.config)?;
+        let Some(edit) = history.next_undo().cloned() else {
+            return Ok(None);
+        };
+        self.apply_edit(&edit, true)
+            .with_context(|| format!("Can't undo {}", edit.label))?;
+        history.undone();
+        history.save()?;
+        info!("Undid {}", edit.label);
+        Ok(Some(edit))
+    }
+
+    /// Redo the last undone edit
+    ///
+    /// Refused, like [`Store::undo`], if a link it changed has been changed
+    /// since the undo. Returns the redone edit, or None if there's nothing
+    /// to redo.
+    pub fn redo(&mut self) -> Result<Option<Edit>> {
+        self.ensure_writable()?;
+        let mut history = EditHistory::load(&self.config)?;
+        let Some(edit) = history.next_redo().cloned() else {
+            return Ok(None);
+        };
+        self.apply_edit(&edit, false)
+            .with_context(|| format!("Can't redo {}", edit.label))?;
+        history.redone();
+        history.save()?;
+        info!("Redid {}", edit.label);
+        Ok(Some(edit))
+    }
+
+    /// Put an edit's links and tags back as they were before it (`undo`)
+    /// or after it
+    fn apply_edit(&mut self, edit: &Edit, undo: bool) -> Result<()> {
+        let links: Vec<_> = edit
+            .changes
+            .iter()
+            .map(|change| match undo {
+                true => (change.after.as_ref(), change.before.as_ref()),
+                false => (change.before.as_ref(), change.after.as_ref()),
+            })
+            .collect();
+        let tags: Vec<_> = edit
+            .tags
+            .iter()
+            .map(|change| match undo {
+                true => (change.tag.as_str(), &change.after, &change.before),
+                false => (change.tag.as_str(), &change.before, &change.after),
+            })
+            .collect();
+        let archive = edit
+            .archive
+            .map(|archive| if undo { archive.reversed() } else { archive });
+
+        match archive {
+            None => self.revert(&links, &tags),
+            Some(ArchiveMove::Archived) => {
+                self.check_links(&links)?;
+                let ids: Vec<Uuid> = links
+                    .iter()
+                    .filter_map(|(current, _)| current.map(|link| link.id))
+                    .collect();
+                self.move_to_archives(&ids)?;
+                Ok(())
+            }
+            Some(ArchiveMove::Unarchived) => {
+                self.check_links(&links)?;
+                for link in links.iter().filter_map(|(_, target)| *target) {
+                    self.move_from_archives(link.id)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Write links and tag settings from one state to another, in one save
+    ///
+    /// Each link pair is a link as it should be now and as it's to be
+    /// written (None for absent); each tag likewise. Opens since don't
+    /// count as a change, and are kept.
+    fn revert(
+        &mut self,
+        links: &[(Option<&Link>, Option<&Link>)],
+        tags: &[(&str, &TagSettings, &TagSettings)],
+    ) -> Result<()> {
+        let writes = self.check_links(links)?;
+        for &(tag, expected, _) in tags {
+            if self.tag_settings(tag)? != *expected {
+                anyhow::bail!("tag '{}' has changed since", tag);
+            }
+        }
+
+        tokio::task::block_in_place(|| {
+            let mut doc = self.doc.blocking_lock();
+            for (id, current, target) in writes {
+                match (current, target) {
+                    (Some(current), Some(target)) => doc
+                        .update_link(&Link {
+                            opened_at: current.opened_at,
+                            ..target.clone()
+                        })
+                        .context("Failed to update link in document")?,
+                    (None, Some(target)) => {
+                        doc.add_link(target).context("Failed to restore link")?
+                    }
+                    (Some(_), None) => doc
+                        .delete_link(id)
+                        .context("Failed to delete link from document")?,
+                    (None, None) => {}
+                }
+            }
+            for &(tag, _, target) in tags {
+                doc.set_tag_description(tag, target.description.as_deref())
+                    .context("Failed to set tag description")?;
+                doc.set_tag_visibility(tag, target.visibility)
+                    .context("Failed to set tag visibility")?;
+            }
+            Ok::<_, anyhow::Error>(())
+        })?;
+        self.save()
+    }
+
+    /// Check each link is as expected (see [`Store::revert`]), returning
+    /// the writes that put them in their target state
+    fn check_links<'a>(
+        &self,
+        changes: &[(Option<&'a Link>, Option<&'a Link>)],
+    ) -> Result<Vec<(Uuid, Option<Link>, Option<&'a Link>)>> {
+        let mut writes = Vec::with_capacity(changes.len());
+        for &(expected, target) in changes {
+            let id = expected.or(target).map(|link| link.id).unwrap_or_default();
+            let current = self.get_link(id)?;
+            if current.as_ref().map(unopened) != expected.map(unopened) {
+                match current {
+                    Some(_) => anyhow::bail!("link {} has changed since", id),
+                    None => anyhow::bail!("link {} has been deleted since", id),
+                }
+            }
+            if let (None, Some(target)) = (&current, target) {
+                if let Some(existing) = self.get_link_by_url(&target.url)? {
+                    anyhow::bail!(
+                        "a link with this URL already exists: '{}' (ID: {})",
+                        existing.title,
+                        existing.id
+                    );
+                }
+            }
+            writes.push((id, current, target));
+        }
+        Ok(writes)
+    }
+
+    /// Record an edit for [`Store::undo`], given each link it changed as
+    /// it was before
+    ///
+    /// The edit is saved already, so failing to record it is only logged.
+    fn record_edit(&self, label: String, before: Vec<(Uuid, Option<Link>)>) {
+        self.record(label, before, Vec::new(), None);
+    }
+
+    /// Record an edit that may also change tag settings (given each tag's
+    /// as it was before) or move links between the collection and its
+    /// archives
+    fn record(
+        &self,
+        label: String,
+        before: Vec<(Uuid, Option<Link>)>,
+        tags_before: Vec<(String, TagSettings)>,
+        archive: Option<ArchiveMove>,
+    ) {
+        let record = || -> Result<()> {
+            let mut changes = Vec::with_capacity(before.len());
+            for (id, before) in before {
+                let after = self.get_link(id)?;
+                if before != after {
+                    changes.push(LinkChange { before, after });
+                }
+            }
+            let mut tags = Vec::with_capacity(tags_before.len());
+            for (tag, before) in tags_before {
+                let after = self.tag_settings(&tag)?;
+                if before != after {
+                    tags.push(TagChange { tag, before, after });
+                }
+            }
+            if changes.is_empty() && tags.is_empty() {
+                return Ok(());
+            }
+            let mut history = EditHistory::load(&self.config)?;
+            history.record(Edit {
+                label,
+                at: Utc::now(),
+                changes,
+                tags,
+                archive,
+            });
+            history.save()
+        };
+        if let Err(e) = record() {
+            warn!("Failed to record edit for undo: {:#}", e);
+        }
+    }
+
+    /// Links as they are before an edit, for [`Store::record_edit`]
+    fn links_before(&self, ids: &[Uuid]) -> Result<Vec<(Uuid, Option<Link>)>> {
+        ids.iter().map(|&id| Ok((id, self.get_link(id)?))).collect()
+    }
+
+    /// Record an edit of a link's notes, labelled with the link's title
+    fn record_note_edit(&self, action: &str, link_id: Uuid, before: Option<Link>) {
+        let title = before
+            .as_ref()
+            .map_or(String::new(), |link| link.title.clone());
+        self.record_edit(format!("{} '{}'", action, title), vec![(link_id, before)]);
+    }
+
+    // ==================== Advanced ====================
+
+    /// Save the document to disk
+    ///
+    /// This first merges any external changes from disk (e.g., from CLI
+    /// while TUI is running), then saves the merged document.
+    pub fn save(&mut self) -> Result<()> {
+        tokio::task::block_in_place(|| {
+            let mut doc = self.doc.blocking_lock();
+            doc.commit_as(&self.config.device_label());
+
             // First, merge any external changes from disk
             if let Some(mut disk_doc) = self
                 .persistence
@@ -439,6 +2249,12 @@ impl Store {
         })
     }
 
+    /// A marker of the saved document that changes whenever any process
+    /// saves it (see [`AutomergePersistence::stored_version`])
+    pub fn stored_version(&self) -> Option<String> {
+        self.persistence.stored_version()
+    }
+
     /// Get storage statistics
     pub fn storage_stats(&self) -> StorageStats {
         self.persistence.storage_stats()
@@ -455,6 +2271,10 @@ impl Store {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::canonical::TrailingSlash;
+    use crate::dedupe::{find_duplicate_notes, REFERENCE_PREFIX};
+    use crate::incoming::incoming_changes;
+    use crate::validate::ValidationError;
     use tempfile::TempDir;
 
     fn test_config(temp_dir: &TempDir) -> Config {
@@ -462,8 +2282,7 @@ mod tests {
             data_dir: temp_dir.path().to_path_buf(),
             sync_url: None,
             sync_enabled: false,
-            favorite_tag: None,
-            log_file: None,
+            ..Config::default()
         }
     }
 
@@ -479,7 +2298,7 @@ mod tests {
         assert!(store.root_url().starts_with("automerge:"));
 
         // Files should exist
-        assert!(config.automerge_path().exists());
+        assert!(config.document_dir(&store.root_id()).exists());
         assert!(config.root_doc_id_path().exists());
     }
 
@@ -551,6 +2370,141 @@ mod tests {
         assert!(retrieved.tags.contains(&"updated".to_string()));
     }
 
+    #[test]
+    fn test_update_links_is_all_or_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+
+        let mut a = Link::new("https://a.example.com");
+        let mut b = Link::new("https://b.example.com");
+        let c = Link::new("https://c.example.com");
+        for link in [&a, &b, &c] {
+            store.add_link(link).unwrap();
+        }
+
+        a.set_title("Renamed");
+        b.url = "not a url".to_string();
+        assert!(store.update_links(&[a.clone(), b], &[c.id]).is_err());
+        assert_eq!(store.get_link(a.id).unwrap().unwrap().title, a.url);
+        assert_eq!(store.link_count().unwrap(), 3);
+
+        store.update_links(&[a.clone()], &[c.id]).unwrap();
+        assert_eq!(store.get_link(a.id).unwrap().unwrap().title, "Renamed");
+        assert!(store.get_link(c.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_merge_duplicate_notes() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+
+        let mut a = Link::new("https://a.example.com");
+        a.add_note(Note::new("Shared quote"));
+        let mut b = Link::new("https://b.example.com");
+        b.add_note(Note::new("Shared quote"));
+        b.add_note(Note::new("Shared quote"));
+        store.add_link(&a).unwrap();
+        store.add_link(&b).unwrap();
+
+        let groups = find_duplicate_notes(&store.get_all_links(LinkSort::default()).unwrap());
+        assert_eq!(groups.len(), 1);
+        store.merge_duplicate_notes(&groups[0]).unwrap();
+
+        let kept_on = groups[0].keep.link_id;
+        let other = if kept_on == a.id { b.id } else { a.id };
+        let kept = store.get_link(kept_on).unwrap().unwrap();
+        assert_eq!(kept.notes.len(), 1);
+        assert_eq!(kept.notes[0].body, "Shared quote");
+        let other = store.get_link(other).unwrap().unwrap();
+        assert_eq!(other.notes.len(), 1);
+        assert!(other.notes[0].body.starts_with(REFERENCE_PREFIX));
+        assert!(
+            find_duplicate_notes(&store.get_all_links(LinkSort::default()).unwrap()).is_empty()
+        );
+    }
+
+    #[test]
+    fn test_update_notes() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+
+        let mut link = Link::new("https://example.com");
+        link.add_note(Note::new("Old name here"));
+        link.add_note(Note::new("Old name there"));
+        store.add_link(&link).unwrap();
+
+        let mut edits: Vec<(Uuid, Note)> = link
+            .notes
+            .iter()
+            .map(|n| {
+                let mut n = n.clone();
+                n.set_body(n.body.replace("Old", "New"));
+                (link.id, n)
+            })
+            .collect();
+        store.update_notes(&edits).unwrap();
+        let saved = store.get_link(link.id).unwrap().unwrap();
+        assert!(saved.notes.iter().all(|n| n.body.starts_with("New name")));
+
+        // A missing note fails the whole batch
+        edits[0].1.set_body("Changed");
+        edits.push((link.id, Note::new("Not on the link")));
+        assert!(store.update_notes(&edits).is_err());
+        let saved = store.get_link(link.id).unwrap().unwrap();
+        assert!(saved.notes.iter().all(|n| n.body != "Changed"));
+    }
+
+    #[test]
+    fn test_accept_incoming() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+
+        let mine = Link::new("https://example.com/mine");
+        store.add_link(&mine).unwrap();
+
+        let mut theirs = Link::new("https://example.com/mine");
+        theirs.add_tag("Shared Reading");
+        theirs.add_note(Note::new("From a friend"));
+        let new = Link::new("https://example.com/new");
+        let rejected = Link::new("https://example.com/rejected");
+
+        let changes = incoming_changes(
+            &store.get_all_links(LinkSort::default()).unwrap(),
+            &[theirs, new.clone(), rejected.clone()],
+        );
+        assert_eq!(changes.len(), 3);
+        assert_eq!(store.accept_incoming(&changes[..2]).unwrap().applied, 2);
+
+        let merged = store.get_link(mine.id).unwrap().unwrap();
+        assert_eq!(merged.tags, vec!["shared-reading"]);
+        assert_eq!(merged.notes[0].body, "From a friend");
+        assert!(store.get_link(new.id).unwrap().is_some());
+        assert!(store.get_link(rejected.id).unwrap().is_none());
+        // Accepting again changes nothing
+        assert_eq!(store.accept_incoming(&changes[1..2]).unwrap().applied, 0);
+
+        // The accepted changes undo together
+        let undone = store.undo().unwrap().unwrap();
+        assert_eq!(undone.label, "accept 2 incoming changes");
+        assert!(store.get_link(new.id).unwrap().is_none());
+        assert!(store.get_link(mine.id).unwrap().unwrap().notes.is_empty());
+
+        // Links that fail validation are skipped, not added
+        let mut script = Link::new("https://example.com/script");
+        script.url = "javascript:alert(document.cookie)".to_string();
+        let changes = vec![
+            IncomingChange::Added {
+                link: script.clone(),
+            },
+            IncomingChange::Added { link: new.clone() },
+        ];
+        let summary = store.accept_incoming(&changes).unwrap();
+        assert_eq!(summary.applied, 1);
+        assert_eq!(summary.rejected.len(), 1);
+        assert!(store.get_link(script.id).unwrap().is_none());
+        assert!(store.get_link(new.id).unwrap().is_some());
+    }
+
     #[test]
     fn test_delete_link() {
         let temp_dir = TempDir::new().unwrap();
@@ -574,7 +2528,7 @@ mod tests {
         store.add_link(&Link::new("https://two.com")).unwrap();
         store.add_link(&Link::new("https://three.com")).unwrap();
 
-        let links = store.get_all_links().unwrap();
+        let links = store.get_all_links(LinkSort::default()).unwrap();
         assert_eq!(links.len(), 3);
     }
 
@@ -591,7 +2545,7 @@ mod tests {
         link2.add_tag("python");
         store.add_link(&link2).unwrap();
 
-        let rust_links = store.get_links_by_tag("rust").unwrap();
+        let rust_links = store.get_links_by_tag("rust", LinkSort::default()).unwrap();
         assert_eq!(rust_links.len(), 1);
         assert_eq!(rust_links[0].url, "https://rust-lang.org");
     }
@@ -609,6 +2563,26 @@ mod tests {
         assert_eq!(results.len(), 1);
     }
 
+    #[test]
+    fn test_search_finds_notes() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+
+        let link = Link::new("https://example.com/talk");
+        store.add_link(&link).unwrap();
+        let mut note = Note::new("The part about structured concurrency");
+        note.title = Some("Takeaways".to_string());
+        store.add_note_to_link(link.id, &note).unwrap();
+
+        let hits = store.search("STRUCTURED concurrency").unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].link.id, link.id);
+        assert_eq!(hits[0].note_id, Some(note.id));
+        assert_eq!(store.search("takeaways").unwrap()[0].note_id, Some(note.id));
+        assert_eq!(store.search_links("concurrency").unwrap().len(), 1);
+        assert!(store.search("structured parallelism").unwrap().is_empty());
+    }
+
     #[test]
     fn test_add_note_to_link() {
         let temp_dir = TempDir::new().unwrap();
@@ -635,15 +2609,153 @@ mod tests {
         let link_id = link.id;
         store.add_link(&link).unwrap();
 
-        let note = Note::with_title("Summary", "This is a summary of the article");
-        store.add_note_to_link(link_id, &note).unwrap();
+        let note = Note::with_title("Summary", "This is a summary of the article");
+        store.add_note_to_link(link_id, &note).unwrap();
+
+        let retrieved = store.get_link(link_id).unwrap().unwrap();
+        assert_eq!(retrieved.notes[0].title, Some("Summary".to_string()));
+    }
+
+    #[test]
+    fn test_remove_note_from_link() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+
+        let link = Link::new("https://example.com");
+        let link_id = link.id;
+        store.add_link(&link).unwrap();
+
+        let note = Note::new("To be removed");
+        let note_id = note.id;
+        store.add_note_to_link(link_id, &note).unwrap();
+
+        assert_eq!(store.note_count().unwrap(), 1);
+
+        store.remove_note_from_link(link_id, note_id).unwrap();
+
+        assert_eq!(store.note_count().unwrap(), 0);
+        let retrieved = store.get_link(link_id).unwrap().unwrap();
+        assert!(retrieved.notes.is_empty());
+    }
+
+    #[test]
+    fn test_link_with_multiple_notes() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+
+        let link = Link::new("https://example.com");
+        let link_id = link.id;
+        store.add_link(&link).unwrap();
+
+        store
+            .add_note_to_link(link_id, &Note::new("First note"))
+            .unwrap();
+        store
+            .add_note_to_link(link_id, &Note::new("Second note"))
+            .unwrap();
+        store
+            .add_note_to_link(link_id, &Note::with_title("Third", "With title"))
+            .unwrap();
+
+        let retrieved = store.get_link(link_id).unwrap().unwrap();
+        assert_eq!(retrieved.notes.len(), 3);
+        assert_eq!(store.note_count().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_record_open() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+
+        let link = Link::new("https://example.com");
+        store.add_link(&link).unwrap();
+
+        assert!(store.record_open(link.id).unwrap());
+        let opened = store.get_link(link.id).unwrap().unwrap();
+        assert!(opened.opened_at.is_some());
+        assert_eq!(opened.open_count, 1);
+        assert_eq!(
+            opened.updated_at.timestamp_millis(),
+            link.updated_at.timestamp_millis()
+        );
+
+        // Reopening right away isn't recorded again
+        assert!(!store.record_open(link.id).unwrap());
+        assert_eq!(store.get_link(link.id).unwrap().unwrap().open_count, 1);
+        assert_eq!(store.most_opened_links(10).unwrap().len(), 1);
+        drop(store);
+
+        let config = Config {
+            record_opens: false,
+            ..test_config(&temp_dir)
+        };
+        let mut store = Store::open_with_config(config).unwrap();
+        let other = Link::new("https://example.org");
+        store.add_link(&other).unwrap();
+        assert!(!store.record_open(other.id).unwrap());
+        assert!(store
+            .get_link(other.id)
+            .unwrap()
+            .unwrap()
+            .opened_at
+            .is_none());
+    }
+
+    #[test]
+    fn test_import_links() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+        let saved = Link::new("https://example.com/saved");
+        store.add_link(&saved).unwrap();
+
+        let mut tagged = Link::new("https://example.com/new");
+        tagged.add_tag("Reading List");
+        let links = vec![
+            tagged,
+            Link::new("https://example.com/saved/"),
+            Link::new("https://example.com/new"),
+            Link::new("not a url"),
+        ];
+
+        let plan = store.plan_import(links.clone()).unwrap();
+        assert_eq!(plan.added.len(), 1);
+        assert_eq!(store.get_all_links(LinkSort::default()).unwrap().len(), 1);
+
+        let summary = store.import_links(links).unwrap();
+        assert_eq!(summary.added.len(), 1);
+        assert_eq!(summary.added[0].tags, ["reading-list"]);
+        assert_eq!(summary.duplicates[0].1, saved.id);
+        // Repeated within the import
+        assert_eq!(summary.duplicates[1].1, summary.added[0].id);
+        assert_eq!(summary.rejected.len(), 1);
+        assert_eq!(store.get_all_links(LinkSort::default()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_expired_links() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+        let now = Utc::now();
+
+        let mut cfp = Link::new("https://example.com/cfp");
+        cfp.set_expires_at(Some(now - Duration::days(1)));
+        let mut sale = Link::new("https://example.com/sale");
+        sale.set_expires_at(Some(now + Duration::days(1)));
+        store.add_link(&cfp).unwrap();
+        store.add_link(&sale).unwrap();
+        store.add_link(&Link::new("https://example.com")).unwrap();
 
-        let retrieved = store.get_link(link_id).unwrap().unwrap();
-        assert_eq!(retrieved.notes[0].title, Some("Summary".to_string()));
+        let expired = store.expired_links(now).unwrap();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].id, cfp.id);
+        assert_eq!(
+            store.expired_links(now + Duration::days(2)).unwrap().len(),
+            2
+        );
     }
 
     #[test]
-    fn test_remove_note_from_link() {
+    fn test_pin_and_move_notes() {
         let temp_dir = TempDir::new().unwrap();
         let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
 
@@ -651,41 +2763,66 @@ mod tests {
         let link_id = link.id;
         store.add_link(&link).unwrap();
 
-        let note = Note::new("To be removed");
-        let note_id = note.id;
-        store.add_note_to_link(link_id, &note).unwrap();
-
-        assert_eq!(store.note_count().unwrap(), 1);
-
-        store.remove_note_from_link(link_id, note_id).unwrap();
+        let first = Note::new("First");
+        let mut second = Note::new("Second");
+        second.created_at = first.created_at + chrono::Duration::seconds(1);
+        let mut summary = Note::new("Summary");
+        summary.created_at = first.created_at + chrono::Duration::seconds(2);
+        for note in [&first, &second, &summary] {
+            store.add_note_to_link(link_id, note).unwrap();
+        }
 
-        assert_eq!(store.note_count().unwrap(), 0);
-        let retrieved = store.get_link(link_id).unwrap().unwrap();
-        assert!(retrieved.notes.is_empty());
+        assert!(store.move_note(link_id, summary.id, true).unwrap());
+        let bodies = |store: &Store| -> Vec<String> {
+            let link = store.get_link(link_id).unwrap().unwrap();
+            link.notes.into_iter().map(|n| n.body).collect()
+        };
+        assert_eq!(bodies(&store), vec!["First", "Summary", "Second"]);
+
+        store.pin_note(link_id, second.id, true).unwrap();
+        assert_eq!(bodies(&store), vec!["Second", "First", "Summary"]);
+        assert!(!store.move_note(link_id, first.id, true).unwrap());
+
+        // Order survives a reload
+        drop(store);
+        let store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+        assert_eq!(bodies(&store), vec!["Second", "First", "Summary"]);
     }
 
     #[test]
-    fn test_link_with_multiple_notes() {
+    fn test_split_note() {
         let temp_dir = TempDir::new().unwrap();
         let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
 
         let link = Link::new("https://example.com");
         let link_id = link.id;
         store.add_link(&link).unwrap();
-
-        store
-            .add_note_to_link(link_id, &Note::new("First note"))
-            .unwrap();
-        store
-            .add_note_to_link(link_id, &Note::new("Second note"))
-            .unwrap();
-        store
-            .add_note_to_link(link_id, &Note::with_title("Third", "With title"))
-            .unwrap();
-
-        let retrieved = store.get_link(link_id).unwrap().unwrap();
-        assert_eq!(retrieved.notes.len(), 3);
-        assert_eq!(store.note_count().unwrap(), 3);
+        let dump = Note::with_title("Research", "Sources below\n\n# One\nfirst\n\n# Two\nsecond");
+        let mut after = Note::new("After");
+        after.created_at = dump.created_at + chrono::Duration::seconds(1);
+        store.add_note_to_link(link_id, &dump).unwrap();
+        store.add_note_to_link(link_id, &after).unwrap();
+
+        let added = store.split_note(link_id, dump.id).unwrap();
+        assert_eq!(added.len(), 2);
+        let link = store.get_link(link_id).unwrap().unwrap();
+        let notes: Vec<_> = link
+            .notes
+            .iter()
+            .map(|n| (n.title.as_deref(), n.body.as_str()))
+            .collect();
+        assert_eq!(
+            notes,
+            [
+                (Some("Research"), "Sources below"),
+                (Some("One"), "first"),
+                (Some("Two"), "second"),
+                (None, "After"),
+            ]
+        );
+
+        // Nothing left to split
+        assert!(store.split_note(link_id, added[0].id).unwrap().is_empty());
     }
 
     #[test]
@@ -728,6 +2865,31 @@ mod tests {
         assert_eq!(shared.1, 2);
     }
 
+    #[test]
+    fn test_prune_tags() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+
+        let mut link = Link::new("https://example.com");
+        link.add_tag("rust");
+        store.add_link(&link).unwrap();
+        store.set_tag_description("rust", Some("Rust")).unwrap();
+        store.set_tag_description("golang", Some("Go")).unwrap();
+        store
+            .set_tag_visibility("work", Some(TagVisibility::Private))
+            .unwrap();
+
+        // A dry run changes nothing
+        assert_eq!(store.prune_tags(true).unwrap(), ["golang", "work"]);
+        assert_eq!(store.tag_descriptions().unwrap().len(), 2);
+
+        assert_eq!(store.prune_tags(false).unwrap(), ["golang", "work"]);
+        let descriptions = store.tag_descriptions().unwrap();
+        assert_eq!(descriptions.keys().collect::<Vec<_>>(), ["rust"]);
+        assert!(store.tag_visibility().unwrap().tags().is_empty());
+        assert!(store.prune_tags(false).unwrap().is_empty());
+    }
+
     #[test]
     fn test_is_new() {
         let temp_dir = TempDir::new().unwrap();
@@ -766,7 +2928,7 @@ mod tests {
             assert_eq!(store.link_count().unwrap(), 1);
             assert_eq!(store.note_count().unwrap(), 1);
 
-            let links = store.get_all_links().unwrap();
+            let links = store.get_all_links(LinkSort::default()).unwrap();
             assert_eq!(links[0].title, "Persistent Link");
             assert_eq!(links[0].notes.len(), 1);
             assert_eq!(links[0].notes[0].body, "Persistent note");
@@ -789,6 +2951,306 @@ mod tests {
 
         // Count should still be 1
         assert_eq!(store.link_count().unwrap(), 1);
+
+        // A variant URL of the same article, found by its canonical URL
+        let mut amp = Link::new("https://example.com/amp/?utm_source=feed");
+        amp.set_canonical_url(Some("https://example.com".to_string()));
+        let result = store.add_link(&amp);
+        assert!(result.unwrap_err().to_string().contains("already exists"));
+        assert_eq!(store.link_count().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_blocked_domain_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = test_config(&temp_dir);
+        config.blocked_domains = vec!["bank.com".to_string()];
+        let mut store = Store::open_with_config(config).unwrap();
+
+        let result = store.add_link(&Link::new("https://online.bank.com/login"));
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("blocked"));
+        assert_eq!(store.link_count().unwrap(), 0);
+
+        store.add_link(&Link::new("https://example.com")).unwrap();
+        assert_eq!(store.link_count().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_links_validated_on_write() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+
+        let err = store
+            .add_link(&Link::new("javascript:alert(1)"))
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ValidationError>(),
+            Some(ValidationError::SchemeNotAllowed { .. })
+        ));
+        assert_eq!(store.link_count().unwrap(), 0);
+
+        let mut link = Link::new("https://example.com");
+        link.add_tag("Rust Lang");
+        store.add_link(&link).unwrap();
+        let mut saved = store.get_link(link.id).unwrap().unwrap();
+        assert_eq!(saved.tags, vec!["rust-lang"]);
+
+        saved.set_title("x".repeat(store.config().max_title_length + 1));
+        assert!(store.update_link(&saved).is_err());
+        saved.url = "not a url".to_string();
+        assert!(store.update_link(&saved).is_err());
+    }
+
+    #[test]
+    fn test_domain_auto_tags() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = test_config(&temp_dir);
+        config
+            .domain_tags
+            .insert("github.com".to_string(), vec!["code".to_string()]);
+        let mut store = Store::open_with_config(config).unwrap();
+
+        let mut link = Link::new("https://github.com/rust-lang/rust");
+        link.add_tag("rust");
+        store.add_link(&link).unwrap();
+
+        let retrieved = store.get_link(link.id).unwrap().unwrap();
+        assert_eq!(retrieved.tags, vec!["rust", "code"]);
+    }
+
+    #[test]
+    fn test_derived_fields_computed_on_write() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+
+        let link = Link::new("https://www.youtube.com/watch?v=abc");
+        store.add_link(&link).unwrap();
+
+        let mut saved = store.get_link(link.id).unwrap().unwrap();
+        assert_eq!(saved.derived["domain"], "youtube.com");
+        assert_eq!(saved.derived["kind"], "video");
+
+        // Derived fields follow URL changes on update
+        saved.url = "https://github.com/rust-lang/rust".to_string();
+        store.update_link(&saved).unwrap();
+
+        let saved = store.get_link(link.id).unwrap().unwrap();
+        assert_eq!(saved.derived["domain"], "github.com");
+        assert_eq!(saved.derived["kind"], "repository");
+    }
+
+    #[test]
+    fn test_shareable_links_leave_out_private_tags() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+
+        let mut work = Link::new("https://example.com/work");
+        work.add_tag("work");
+        work.add_tag("blog");
+        let mut post = Link::new("https://example.com/post");
+        post.add_tag("blog");
+        store.add_link(&work).unwrap();
+        store.add_link(&post).unwrap();
+        assert_eq!(store.shareable_links().unwrap().len(), 2);
+
+        store
+            .set_tag_visibility("Work", Some(TagVisibility::Private))
+            .unwrap();
+        store
+            .set_tag_visibility("blog", Some(TagVisibility::Public))
+            .unwrap();
+        let shareable = store.shareable_links().unwrap();
+        assert_eq!(shareable.len(), 1);
+        assert_eq!(shareable[0].id, post.id);
+
+        store.set_tag_visibility("work", None).unwrap();
+        assert_eq!(store.shareable_links().unwrap().len(), 2);
+        assert!(store.set_tag_visibility("!!", None).is_err());
+    }
+
+    #[test]
+    fn test_archive_links() {
+        use chrono::TimeZone;
+
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+
+        let mut old = Link::new("https://example.com/old");
+        old.created_at = Utc.with_ymd_and_hms(2019, 5, 1, 0, 0, 0).unwrap();
+        old.add_note(Note::new("archived with the link"));
+        let mut older = Link::new("https://example.com/older");
+        older.created_at = Utc.with_ymd_and_hms(2018, 1, 1, 0, 0, 0).unwrap();
+        let recent = Link::new("https://example.com/recent");
+        for link in [&old, &older, &recent] {
+            store.add_link(link).unwrap();
+        }
+
+        assert_eq!(store.archive_links(&[old.id, older.id]).unwrap(), 2);
+        assert_eq!(
+            store
+                .archives()
+                .unwrap()
+                .keys()
+                .copied()
+                .collect::<Vec<_>>(),
+            vec![2018, 2019]
+        );
+        assert_eq!(store.get_all_links(LinkSort::default()).unwrap().len(), 1);
+        assert!(store.missing_archives().unwrap().is_empty());
+        assert_eq!(store.archived_links().unwrap().len(), 2);
+
+        let found = store.search_archived("older").unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, older.id);
+
+        let restored = store.unarchive_link(old.id).unwrap();
+        assert_eq!(restored.notes.len(), 1);
+        assert!(store.get_link(old.id).unwrap().is_some());
+        assert_eq!(store.archived_links().unwrap().len(), 1);
+        assert!(store.unarchive_link(old.id).is_err());
+
+        // Archiving into a year that already has an archive reuses it
+        assert_eq!(store.archive_links(&[old.id]).unwrap(), 1);
+        assert_eq!(store.archives().unwrap().len(), 2);
+        assert_eq!(store.archived_links().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_backup_includes_archives() {
+        use chrono::TimeZone;
+
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+        let mut old = Link::new("https://example.com/old");
+        old.created_at = Utc.with_ymd_and_hms(2019, 5, 1, 0, 0, 0).unwrap();
+        store.add_link(&old).unwrap();
+        store
+            .add_link(&Link::new("https://example.com/recent"))
+            .unwrap();
+        store.archive_links(&[old.id]).unwrap();
+
+        let backup = store.backup().unwrap();
+        assert_eq!(backup.link_count().unwrap(), 1);
+        assert_eq!(backup.archived_link_count().unwrap(), 1);
+
+        let other_dir = TempDir::new().unwrap();
+        backup.restore(&test_config(&other_dir), None).unwrap();
+        let restored = Store::open_with_config(test_config(&other_dir)).unwrap();
+        assert!(restored.missing_archives().unwrap().is_empty());
+        assert_eq!(restored.archived_links().unwrap()[0].id, old.id);
+    }
+
+    #[test]
+    fn test_referenced_attachments_needs_every_archive() {
+        use chrono::TimeZone;
+
+        let temp_dir = TempDir::new().unwrap();
+        let config = test_config(&temp_dir);
+        let mut store = Store::open_with_config(config.clone()).unwrap();
+        let mut old = Link::new("https://example.com/old");
+        old.created_at = Utc.with_ymd_and_hms(2019, 5, 1, 0, 0, 0).unwrap();
+        old.attachments
+            .insert("page.html".to_string(), "a".repeat(64));
+        store.add_link(&old).unwrap();
+        store.archive_links(&[old.id]).unwrap();
+        assert!(store
+            .referenced_attachments()
+            .unwrap()
+            .contains(&"a".repeat(64)));
+
+        // As if the archive hadn't synced to this device
+        let archive_id = store.archives().unwrap()[&2019];
+        std::fs::remove_dir_all(config.document_dir(&archive_id)).unwrap();
+        assert!(store.referenced_attachments().is_err());
+    }
+
+    #[test]
+    fn test_restore_links() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+
+        let mut deleted = Link::new("https://example.com/deleted");
+        deleted.add_note(Note::new("kept with the link"));
+        let mut edited = Link::new("https://example.com/edited");
+        let lost_note = Note::new("deleted note");
+        edited.add_note(lost_note.clone());
+        let readded = Link::new("https://example.com/readded");
+        for link in [&deleted, &edited, &readded] {
+            store.add_link(link).unwrap();
+        }
+
+        let snapshot = store.get_all_links(LinkSort::default()).unwrap();
+
+        store.delete_link(deleted.id).unwrap();
+        store
+            .remove_note_from_link(edited.id, lost_note.id)
+            .unwrap();
+        store.delete_link(readded.id).unwrap();
+        store
+            .add_link(&Link::new("https://example.com/readded"))
+            .unwrap();
+
+        let summary = store.restore_links(&snapshot).unwrap();
+        assert_eq!(
+            summary,
+            RestoreSummary {
+                links_restored: 1,
+                notes_restored: 1,
+                unchanged: 0,
+                skipped_duplicates: 1,
+                rejected: Vec::new(),
+            }
+        );
+
+        let original = snapshot.iter().find(|l| l.id == deleted.id).unwrap();
+        let restored = store.get_link(deleted.id).unwrap().unwrap();
+        assert_eq!(restored.created_at, original.created_at);
+        assert_eq!(restored.notes.len(), 1);
+        let edited = store.get_link(edited.id).unwrap().unwrap();
+        assert!(edited.get_note(lost_note.id).is_some());
+
+        // Restoring again is a no-op
+        let summary = store.restore_links(&snapshot).unwrap();
+        assert_eq!(summary.links_restored + summary.notes_restored, 0);
+
+        // The restore undoes as one edit
+        assert_eq!(store.undo().unwrap().unwrap().label, "restore 2 links");
+        assert!(store.get_link(deleted.id).unwrap().is_none());
+
+        // Links the current rules reject aren't brought back
+        let mut unsafe_link = Link::new("https://example.com/script");
+        unsafe_link.url = "javascript:alert(1)".to_string();
+        let summary = store.restore_links(&[unsafe_link.clone()]).unwrap();
+        assert_eq!(summary.links_restored, 0);
+        assert_eq!(summary.rejected.len(), 1);
+        assert!(store.get_link(unsafe_link.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_register_deriver() {
+        struct TitleLength;
+
+        impl Deriver for TitleLength {
+            fn key(&self) -> &'static str {
+                "title_length"
+            }
+
+            fn derive(&self, link: &Link) -> Option<String> {
+                Some(link.title.chars().count().to_string())
+            }
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+        store.register_deriver(TitleLength);
+
+        let mut link = Link::new("https://example.com");
+        link.set_title("Example");
+        store.add_link(&link).unwrap();
+
+        let saved = store.get_link(link.id).unwrap().unwrap();
+        assert_eq!(saved.derived["title_length"], "7");
     }
 
     #[test]
@@ -809,4 +3271,302 @@ mod tests {
         let not_found = store.get_link_by_url("https://not-exists.com").unwrap();
         assert!(not_found.is_none());
     }
+
+    #[test]
+    fn test_find_duplicates() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config {
+            url_trailing_slash: TrailingSlash::Keep,
+            ..test_config(&temp_dir)
+        };
+        let mut store = Store::open_with_config(config.clone()).unwrap();
+        store
+            .add_link(&Link::new("https://example.com/post/"))
+            .unwrap();
+        store
+            .add_link(&Link::new("https://example.com/post"))
+            .unwrap();
+        assert!(store.find_duplicates().unwrap().is_empty());
+        drop(store);
+
+        let config = Config {
+            url_trailing_slash: TrailingSlash::Strip,
+            ..config
+        };
+        let mut store = Store::open_with_config(config).unwrap();
+        let groups = store.find_duplicates().unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].duplicates.len(), 1);
+
+        store
+            .merge_links(groups[0].keep.id, groups[0].duplicates[0].id)
+            .unwrap();
+        assert!(store.find_duplicates().unwrap().is_empty());
+        assert_eq!(store.get_all_links(LinkSort::default()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_merge_links_and_undo() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+
+        let mut keep = Link::new("https://example.com/post");
+        keep.tags = vec!["rust".to_string()];
+        keep.author = vec!["Alice".to_string()];
+        keep.add_note(Note::new("Kept note"));
+        let mut dup = Link::new("https://mirror.example.org/post");
+        dup.tags = vec!["rust".to_string(), "async".to_string()];
+        dup.author = vec!["Bob".to_string()];
+        dup.description = Some("A post".to_string());
+        dup.created_at = keep.created_at - Duration::days(3);
+        dup.add_note(Note::new("Highlight"));
+        store.add_link(&keep).unwrap();
+        store.add_link(&dup).unwrap();
+
+        assert!(store.merge_links(keep.id, keep.id).is_err());
+        let merge = store.merge_links(keep.id, dup.id).unwrap();
+        let merged = store.get_link(keep.id).unwrap().unwrap();
+        assert_eq!(merged, merge.merged);
+        assert_eq!(merged.tags, ["rust", "async"]);
+        assert_eq!(merged.author, ["Alice", "Bob"]);
+        assert_eq!(merged.notes.len(), 2);
+        assert_eq!(merged.description.as_deref(), Some("A post"));
+        assert_eq!(merged.created_at, merge.duplicate.created_at);
+        assert_eq!(merged.merged_from, [dup.id]);
+        assert!(store.get_link(dup.id).unwrap().is_none());
+
+        let undone = store.undo_merge().unwrap().unwrap();
+        assert!(undone.is_merge());
+        assert_eq!(store.get_link(keep.id).unwrap().unwrap(), merge.kept);
+        assert_eq!(store.get_link(dup.id).unwrap().unwrap(), merge.duplicate);
+        assert!(store.undo_merge().unwrap().is_none());
+
+        // Only a merge is undone, and not once the kept link has changed
+        store.merge_links(keep.id, dup.id).unwrap();
+        let mut link = store.get_link(keep.id).unwrap().unwrap();
+        link.set_title("Edited");
+        store.update_link(&link).unwrap();
+        assert!(store.undo_merge().unwrap().is_none());
+        store.undo().unwrap();
+        link.set_title("Edited elsewhere");
+        tokio::task::block_in_place(|| {
+            store.doc.blocking_lock().update_link(&link).unwrap();
+        });
+        assert!(store.undo_merge().is_err());
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+        assert!(store.undo().unwrap().is_none());
+
+        let mut link = Link::new("https://example.com");
+        link.set_title("Example");
+        store.add_link(&link).unwrap();
+        let mut tagged = store.get_link(link.id).unwrap().unwrap();
+        tagged.tags = vec!["rust".to_string()];
+        store.update_link(&tagged).unwrap();
+        store
+            .add_note_to_link(link.id, &Note::new("A note"))
+            .unwrap();
+        store.delete_link(link.id).unwrap();
+
+        let undone = store.undo().unwrap().unwrap();
+        assert_eq!(undone.label, "delete 'Example'");
+        let restored = store.get_link(link.id).unwrap().unwrap();
+        assert_eq!(restored.notes.len(), 1);
+
+        store.undo().unwrap();
+        assert!(store.get_link(link.id).unwrap().unwrap().notes.is_empty());
+        store.undo().unwrap();
+        assert!(store.get_link(link.id).unwrap().unwrap().tags.is_empty());
+
+        // Redo puts the edits back, in order; opens since don't get in the way
+        store.record_open(link.id).unwrap();
+        assert_eq!(store.redo().unwrap().unwrap().label, "edit 'Example'");
+        assert_eq!(store.get_link(link.id).unwrap().unwrap().tags, ["rust"]);
+
+        // Nothing is undone over a change made since
+        let mut edited = store.get_link(link.id).unwrap().unwrap();
+        edited.set_title("Edited");
+        tokio::task::block_in_place(|| {
+            store.doc.blocking_lock().update_link(&edited).unwrap();
+        });
+        assert!(store.undo().is_err());
+
+        // A new edit drops what was undone
+        edited.set_title("Edited again");
+        store.update_link(&edited).unwrap();
+        assert!(store.redo().unwrap().is_none());
+        store.undo().unwrap();
+        assert_eq!(store.get_link(link.id).unwrap().unwrap().title, "Edited");
+    }
+
+    #[test]
+    fn test_bulk_edits_undo_together() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+        let summary = store
+            .import_links(vec![
+                Link::new("https://example.com/a"),
+                Link::new("https://example.com/b"),
+            ])
+            .unwrap();
+        let mut links = summary.added;
+
+        // Unchanged links aren't written or recorded
+        store.update_link(&links[0]).unwrap();
+        store.write_links(&links, &[], "check 2 links").unwrap();
+        assert_eq!(store.undo().unwrap().unwrap().label, "import 2 links");
+        store.redo().unwrap();
+
+        for link in &mut links {
+            link.unreachable = true;
+        }
+        store.write_links(&links, &[], "check 2 links").unwrap();
+        let undone = store.undo().unwrap().unwrap();
+        assert_eq!(undone.label, "check 2 links");
+        assert_eq!(undone.changes.len(), 2);
+        assert!(!store.get_link(links[1].id).unwrap().unwrap().unreachable);
+    }
+
+    #[test]
+    fn test_tags_normalized_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = test_config(&temp_dir);
+        let mut link = Link::new("https://example.com");
+        link.set_tags(vec!["Rust".into(), "rust".into()]);
+        {
+            let mut store = Store::open_with_config(config.clone()).unwrap();
+            // As synced from a version that didn't normalize tags
+            tokio::task::block_in_place(|| {
+                store.doc.blocking_lock().add_link(&link).unwrap();
+            });
+            store.save().unwrap();
+        }
+
+        // The migration already ran, so opening doesn't rewrite them
+        let mut store = Store::open_with_config(config).unwrap();
+        assert_eq!(store.get_link(link.id).unwrap().unwrap().tags.len(), 2);
+        assert_eq!(store.normalize_tags().unwrap(), 1);
+        assert_eq!(store.get_link(link.id).unwrap().unwrap().tags, ["rust"]);
+    }
+
+    #[test]
+    fn test_undo_tag_settings() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+        store.set_tag_description("Rust", Some("Systems")).unwrap();
+        store
+            .set_tag_visibility("rust", Some(TagVisibility::Private))
+            .unwrap();
+        store.set_tag_description("rust", Some("Safe")).unwrap();
+
+        let undone = store.undo().unwrap().unwrap();
+        assert_eq!(undone.label, "describe tag 'rust'");
+        assert_eq!(store.tag_descriptions().unwrap()["rust"], "Systems");
+        assert_eq!(
+            store.undo().unwrap().unwrap().label,
+            "mark tag 'rust' private"
+        );
+        assert!(store.tag_visibility().unwrap().tags().is_empty());
+
+        // Pruning puts back every setting of every tag it removed
+        store.redo().unwrap();
+        assert_eq!(store.prune_tags(false).unwrap(), ["rust"]);
+        assert_eq!(store.undo().unwrap().unwrap().label, "prune 1 tags");
+        assert_eq!(store.tag_descriptions().unwrap()["rust"], "Systems");
+        assert_eq!(store.tag_visibility().unwrap().tags().len(), 1);
+
+        // Nothing is undone over a change made since
+        store.redo().unwrap();
+        tokio::task::block_in_place(|| {
+            store
+                .doc
+                .blocking_lock()
+                .set_tag_description("rust", Some("Elsewhere"))
+                .unwrap();
+        });
+        assert!(store.undo().is_err());
+    }
+
+    #[test]
+    fn test_undo_archiving() {
+        use chrono::TimeZone;
+
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+        let mut old = Link::new("https://example.com/old");
+        old.set_title("Old");
+        old.created_at = Utc.with_ymd_and_hms(2019, 5, 1, 0, 0, 0).unwrap();
+        store.add_link(&old).unwrap();
+
+        store.archive_links(&[old.id]).unwrap();
+        let undone = store.undo().unwrap().unwrap();
+        assert_eq!(undone.label, "archive 1 links");
+        assert!(store.get_link(old.id).unwrap().is_some());
+        assert!(store.archived_links().unwrap().is_empty());
+
+        store.redo().unwrap();
+        assert!(store.get_link(old.id).unwrap().is_none());
+        assert_eq!(store.archived_links().unwrap().len(), 1);
+
+        store.unarchive_link(old.id).unwrap();
+        assert_eq!(store.undo().unwrap().unwrap().label, "unarchive 'Old'");
+        assert!(store.get_link(old.id).unwrap().is_none());
+        assert_eq!(store.archived_links().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_at_heads_reads_earlier_state() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = Store::open_with_config(test_config(&temp_dir)).unwrap();
+        let mut link = Link::new("https://example.com");
+        link.set_title("Before");
+        store.add_link(&link).unwrap();
+        let heads = store.heads();
+
+        link.set_title("After");
+        store.update_link(&link).unwrap();
+        store.add_link(&Link::new("https://rust-lang.org")).unwrap();
+
+        let then = store.at_heads(&heads).unwrap();
+        assert_eq!(then.link_count().unwrap(), 1);
+        assert_eq!(then.get_link(link.id).unwrap().unwrap().title, "Before");
+        assert_eq!(then.heads(), heads);
+        assert!(then.is_read_only());
+        assert_eq!(store.link_count().unwrap(), 2);
+
+        assert!(store.at_heads(&["not-a-hash".to_string()]).is_err());
+        assert!(store.at_heads(&["ab".repeat(32)]).is_err());
+    }
+
+    #[test]
+    fn test_read_only_refuses_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = test_config(&temp_dir);
+        let link = Link::new("https://example.com");
+        {
+            let mut store = Store::open_with_config(config.clone()).unwrap();
+            store.add_link(&link).unwrap();
+        }
+        AutomergePersistence::new(config.clone())
+            .set_read_only(true)
+            .unwrap();
+
+        let mut store = Store::open_with_config(config).unwrap();
+        assert!(store.is_read_only());
+        assert_eq!(store.link_count().unwrap(), 1);
+
+        let err = store
+            .add_link(&Link::new("https://rust-lang.org"))
+            .unwrap_err();
+        assert!(err.downcast_ref::<ReadOnlyError>().is_some());
+        assert!(store.delete_link(link.id).is_err());
+        assert!(store.set_tag_description("rust", Some("Rust")).is_err());
+        // Opening is still allowed, just not recorded
+        assert!(!store.record_open(link.id).unwrap());
+        assert_eq!(store.link_count().unwrap(), 1);
+    }
 }
@@ -2,20 +2,26 @@
 //!
 //! WebSocket-based client for syncing with automerge-repo-sync-server.
 
+use std::path::PathBuf;
+use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::{Context, Result};
 use automerge::sync::{Message as SyncMessage, SyncDoc};
-use futures_util::{SinkExt, StreamExt};
+use chrono::Utc;
+use futures_util::{FutureExt, SinkExt, StreamExt};
 use tokio::net::TcpStream;
 use tokio::sync::{mpsc, watch, Mutex};
 use tokio_tungstenite::tungstenite::Message;
 use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
 use tracing::{debug, info, warn};
 
-use super::message::{ClientMessage, PeerId, ServerMessage};
+use super::message::{payload, ClientMessage, PeerId, ServerMessage};
+use super::progress::{LastSync, SyncProgress};
 use super::state::SyncState;
+use super::trace::{Direction, SyncTrace, TraceEntry};
+use super::{next_message, receive_message};
 use crate::document::RottDocument;
 use crate::document_id::DocumentId;
 
@@ -45,6 +51,10 @@ pub enum SyncEvent {
     Error(String),
     /// Peer connected
     PeerConnected(String),
+    /// More was exchanged with the server (totals for this sync)
+    Progress(SyncProgress),
+    /// The sync finished
+    Synced(LastSync),
 }
 
 /// Sync client for automerge-repo-sync-server
@@ -65,6 +75,16 @@ pub struct SyncClient {
     event_rx: Option<mpsc::UnboundedReceiver<SyncEvent>>,
     /// Sync state
     sync_state: Arc<Mutex<SyncState>>,
+    /// Where the session is traced, if it is
+    trace: Option<std::sync::Mutex<SyncTrace>>,
+    /// Only pull: never send the server changes
+    read_only: bool,
+    /// Offer to compress sync payloads (see [`super::message`])
+    compression: bool,
+    /// What the current (or last) sync has exchanged
+    progress: std::sync::Mutex<SyncProgress>,
+    /// Where the last finished sync is saved, if anywhere
+    last_sync_path: Option<PathBuf>,
 }
 
 impl SyncClient {
@@ -85,6 +105,11 @@ impl SyncClient {
             event_tx,
             event_rx: Some(event_rx),
             sync_state: Arc::new(Mutex::new(SyncState::new())),
+            trace: None,
+            read_only: false,
+            compression: false,
+            progress: std::sync::Mutex::new(SyncProgress::default()),
+            last_sync_path: None,
         }
     }
 
@@ -94,6 +119,52 @@ impl SyncClient {
         self
     }
 
+    /// Trace the session's messages (see [`super::trace`])
+    pub fn with_trace(mut self, trace: SyncTrace) -> Self {
+        self.trace = Some(std::sync::Mutex::new(trace));
+        self
+    }
+
+    /// Only pull changes, never push any
+    ///
+    /// Sending a message that carries changes fails with a
+    /// [`ReadOnlyError`](crate::ReadOnlyError) instead.
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Offer the server compressed sync payloads
+    ///
+    /// Payloads are only compressed if the server agrees when the
+    /// connection is made; otherwise they go as they are.
+    pub fn with_compression(mut self, compression: bool) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Save when each sync finishes (and what it exchanged) to `path`
+    pub fn with_last_sync_path(mut self, path: PathBuf) -> Self {
+        self.last_sync_path = Some(path);
+        self
+    }
+
+    /// What the current (or last) sync has exchanged
+    pub fn progress(&self) -> SyncProgress {
+        *self.progress.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Where the session is traced, if it is
+    pub fn trace_path(&self) -> Option<PathBuf> {
+        self.trace.as_ref().map(|trace| {
+            trace
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .path()
+                .to_path_buf()
+        })
+    }
+
     /// Get the current status
     pub fn status(&self) -> SyncStatus {
         *self.status_rx.borrow()
@@ -119,13 +190,20 @@ impl SyncClient {
     /// This is a one-shot sync - connects, syncs, then disconnects.
     pub async fn sync_once(&self, doc: &mut RottDocument) -> Result<bool> {
         info!("Starting sync to {}", self.url);
+        *self.progress.lock().unwrap_or_else(|e| e.into_inner()) = SyncProgress::default();
         self.set_status(SyncStatus::Connecting);
+        self.trace_event(
+            doc,
+            "connect",
+            Some(format!("{} document {}", self.url, self.doc_id)),
+        );
 
         // Connect
         let ws_stream = match self.connect().await {
             Ok(s) => s,
             Err(e) => {
                 warn!("Sync connection failed: {}", e);
+                self.trace_event(doc, "error", Some(format!("{:#}", e)));
                 self.set_status(SyncStatus::Error);
                 self.emit(SyncEvent::Error(e.to_string()));
                 return Err(e);
@@ -140,8 +218,15 @@ impl SyncClient {
 
         self.set_status(SyncStatus::Disconnected);
         match &result {
-            Ok(updated) => info!("Sync complete, document_updated={}", updated),
-            Err(e) => warn!("Sync failed: {}", e),
+            Ok(updated) => {
+                info!("Sync complete, document_updated={}", updated);
+                self.trace_event(doc, "done", None);
+                self.finished();
+            }
+            Err(e) => {
+                warn!("Sync failed: {}", e);
+                self.trace_event(doc, "error", Some(format!("{:#}", e)));
+            }
         }
 
         result
@@ -163,16 +248,20 @@ impl SyncClient {
         ws_stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
         doc: &mut RottDocument,
     ) -> Result<bool> {
-        let (mut write, mut read) = ws_stream.split();
+        let (mut write, read) = ws_stream.split();
+        let mut read = read.peekable();
 
         self.set_status(SyncStatus::Syncing);
 
         // Send join message
-        let join_msg = ClientMessage::join(&self.peer_id);
-        write.send(Message::Binary(join_msg.encode())).await?;
+        let join_msg = ClientMessage::join(&self.peer_id, self.compression);
+        write
+            .send(Message::Binary(self.encode_traced(doc, &join_msg)))
+            .await?;
 
         // Wait for peer response and server peer ID
         let server_peer_id: String;
+        let compress: bool;
         let timeout = Duration::from_secs(10);
         let deadline = tokio::time::Instant::now() + timeout;
 
@@ -189,9 +278,12 @@ impl SyncClient {
                 msg = read.next() => {
                     match msg {
                         Some(Ok(Message::Binary(data))) => {
-                            match ServerMessage::decode(&data) {
-                                Ok(ServerMessage::Peer { sender_id, .. }) => {
+                            let decoded = ServerMessage::decode(&data);
+                            self.trace_received(doc, &decoded, data.len());
+                            match decoded {
+                                Ok(ServerMessage::Peer { sender_id, peer_metadata, .. }) => {
                                     server_peer_id = sender_id.clone();
+                                    compress = self.compression && peer_metadata.accepts_deflate();
                                     self.emit(SyncEvent::PeerConnected(sender_id));
                                     break;
                                 }
@@ -234,18 +326,17 @@ impl SyncClient {
         let mut sync_state = self.sync_state.lock().await;
         let peer_sync_state = sync_state.get_or_create(&server_peer_id);
 
-        if let Some(sync_msg) = doc
-            .inner_mut()
-            .sync()
-            .generate_sync_message(peer_sync_state)
-        {
+        if let Some((sync_msg, changes)) = next_message(doc, peer_sync_state, self.read_only)? {
             let request_msg = ClientMessage::request(
                 &self.peer_id,
                 &server_peer_id,
                 &self.doc_id,
                 sync_msg.encode(),
-            );
-            write.send(Message::Binary(request_msg.encode())).await?;
+            )
+            .compressed(compress);
+            let bytes = self.encode_traced(doc, &request_msg);
+            self.record_progress(|progress| progress.record_sent(bytes.len(), changes));
+            write.send(Message::Binary(bytes)).await?;
         }
 
         drop(sync_state);
@@ -265,10 +356,26 @@ impl SyncClient {
                 msg = read.next() => {
                     match msg {
                         Some(Ok(Message::Binary(data))) => {
-                            match ServerMessage::decode(&data) {
-                                Ok(ServerMessage::Sync { sender_id, data, .. }) => {
+                            let size = data.len();
+                            let decoded = ServerMessage::decode(&data);
+                            // Sync messages are traced once applied
+                            if !matches!(decoded, Ok(ServerMessage::Sync { .. })) {
+                                self.trace_received(doc, &decoded, size);
+                            }
+                            match decoded {
+                                Ok(ServerMessage::Sync { sender_id, data, compression, .. }) => {
+                                    let mut batch = vec![(payload(data, compression.as_deref())?, size)];
+                                    // Apply the sync messages that have already arrived before
+                                    // replying, rather than a round trip for each
+                                    while let Some(Some(Ok(Message::Binary(next)))) =
+                                        Pin::new(&mut read).next_if(is_sync_message).now_or_never()
+                                    {
+                                        if let Ok(ServerMessage::Sync { data, compression, .. }) = ServerMessage::decode(&next) {
+                                            batch.push((payload(data, compression.as_deref())?, next.len()));
+                                        }
+                                    }
                                     let (should_continue, was_updated) = self
-                                        .handle_sync_message(&sender_id, data, doc, &mut write)
+                                        .handle_sync_messages(&sender_id, batch, compress, doc, &mut write)
                                         .await?;
                                     if was_updated {
                                         updated = true;
@@ -277,19 +384,26 @@ impl SyncClient {
                                         break;
                                     }
                                 }
+                                Ok(ServerMessage::DocUnavailable { .. }) if self.read_only => {
+                                    // Nothing to pull, and a read-only device doesn't upload
+                                    break;
+                                }
                                 Ok(ServerMessage::DocUnavailable { .. }) => {
                                     // Document doesn't exist on server yet, upload it
                                     let mut sync_state = self.sync_state.lock().await;
                                     let peer_sync_state = sync_state.get_or_create(&server_peer_id);
 
-                                    if let Some(sync_msg) = doc.inner_mut().sync().generate_sync_message(peer_sync_state) {
+                                    if let Some((sync_msg, changes)) = next_message(doc, peer_sync_state, false)? {
                                         let msg = ClientMessage::sync(
                                             &self.peer_id,
                                             &server_peer_id,
                                             &self.doc_id,
                                             sync_msg.encode(),
-                                        );
-                                        write.send(Message::Binary(msg.encode())).await?;
+                                        )
+                                        .compressed(compress);
+                                        let bytes = self.encode_traced(doc, &msg);
+                                        self.record_progress(|progress| progress.record_sent(bytes.len(), changes));
+                                        write.send(Message::Binary(bytes)).await?;
                                     }
                                 }
                                 Ok(ServerMessage::Error { message, .. }) => {
@@ -330,11 +444,14 @@ impl SyncClient {
         Ok(updated)
     }
 
-    /// Handle a sync message from the server
-    async fn handle_sync_message<S>(
+    /// Handle a batch of sync messages from the server
+    ///
+    /// Each is applied in turn, then one response covers them all.
+    async fn handle_sync_messages<S>(
         &self,
         sender_id: &str,
-        data: Vec<u8>,
+        batch: Vec<(Vec<u8>, usize)>,
+        compress: bool,
         doc: &mut RottDocument,
         write: &mut futures_util::stream::SplitSink<S, Message>,
     ) -> Result<(bool, bool)>
@@ -342,24 +459,46 @@ impl SyncClient {
         S: futures_util::Sink<Message> + Unpin,
         <S as futures_util::Sink<Message>>::Error: std::error::Error + Send + Sync + 'static,
     {
-        // Decode the sync message
-        let Ok(sync_msg) = SyncMessage::decode(&data) else {
-            return Ok((true, false));
-        };
-
-        // Apply to our document
         let mut sync_state = self.sync_state.lock().await;
         let peer_state = sync_state.get_or_create(sender_id);
 
-        doc.inner_mut()
-            .sync()
-            .receive_sync_message(peer_state, sync_msg)?;
+        let mut applied = false;
+        for (data, size) in batch {
+            // Decode the sync message
+            let sync_msg = match SyncMessage::decode(&data) {
+                Ok(sync_msg) => sync_msg,
+                Err(e) => {
+                    self.trace(
+                        doc,
+                        Direction::Received,
+                        "sync",
+                        size,
+                        None,
+                        Some(format!("undecodable Automerge sync message: {}", e)),
+                    );
+                    continue;
+                }
+            };
+
+            // Apply to our document
+            let heads_before = self.heads(doc);
+            let changes = receive_message(doc, peer_state, sync_msg)?;
+            self.trace(doc, Direction::Received, "sync", size, heads_before, None);
+            self.record_progress(|progress| progress.record_received(size, changes));
+            applied = true;
+        }
+        if !applied {
+            return Ok((true, false));
+        }
 
         // Generate response
-        if let Some(response) = doc.inner_mut().sync().generate_sync_message(peer_state) {
+        if let Some((response, changes)) = next_message(doc, peer_state, self.read_only)? {
             let client_msg =
-                ClientMessage::sync(&self.peer_id, sender_id, &self.doc_id, response.encode());
-            write.send(Message::Binary(client_msg.encode())).await?;
+                ClientMessage::sync(&self.peer_id, sender_id, &self.doc_id, response.encode())
+                    .compressed(compress);
+            let bytes = self.encode_traced(doc, &client_msg);
+            self.record_progress(|progress| progress.record_sent(bytes.len(), changes));
+            write.send(Message::Binary(bytes)).await?;
             Ok((true, true))
         } else {
             // No more messages to send, sync complete
@@ -367,6 +506,113 @@ impl SyncClient {
         }
     }
 
+    /// Encode a message to send, tracing it
+    fn encode_traced(&self, doc: &mut RottDocument, msg: &ClientMessage) -> Vec<u8> {
+        let bytes = msg.encode();
+        self.trace(doc, Direction::Sent, msg.kind(), bytes.len(), None, None);
+        bytes
+    }
+
+    /// Trace a received message that doesn't change the document
+    fn trace_received<E: std::fmt::Display>(
+        &self,
+        doc: &mut RottDocument,
+        decoded: &std::result::Result<ServerMessage, E>,
+        size: usize,
+    ) {
+        match decoded {
+            Ok(ServerMessage::Error { message, .. }) => self.trace(
+                doc,
+                Direction::Received,
+                "error",
+                size,
+                None,
+                Some(message.clone()),
+            ),
+            Ok(msg) => self.trace(doc, Direction::Received, msg.kind(), size, None, None),
+            Err(e) => self.trace(
+                doc,
+                Direction::Received,
+                "undecodable",
+                size,
+                None,
+                Some(e.to_string()),
+            ),
+        }
+    }
+
+    fn trace_event(&self, doc: &mut RottDocument, event: &str, detail: Option<String>) {
+        self.trace(doc, Direction::Event, event, 0, None, detail);
+    }
+
+    /// Record a trace entry, if tracing
+    ///
+    /// `heads_before` defaults to the current heads (for messages that
+    /// don't change the document).
+    fn trace(
+        &self,
+        doc: &mut RottDocument,
+        direction: Direction,
+        message_type: &str,
+        bytes: usize,
+        heads_before: Option<Vec<String>>,
+        detail: Option<String>,
+    ) {
+        let Some(ref trace) = self.trace else {
+            return;
+        };
+        let heads_after = self.heads(doc).unwrap_or_default();
+        let entry = TraceEntry {
+            at: Utc::now(),
+            direction,
+            message_type: message_type.to_string(),
+            bytes,
+            heads_before: heads_before.unwrap_or_else(|| heads_after.clone()),
+            heads_after,
+            detail,
+        };
+        trace
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .record(&entry);
+    }
+
+    /// The document's heads, if tracing (they're only needed for traces)
+    fn heads(&self, doc: &mut RottDocument) -> Option<Vec<String>> {
+        self.trace.as_ref()?;
+        Some(
+            doc.inner_mut()
+                .get_heads()
+                .iter()
+                .map(|hash| hash.to_string())
+                .collect(),
+        )
+    }
+
+    /// Update the sync's progress, and report it
+    fn record_progress(&self, update: impl FnOnce(&mut SyncProgress)) {
+        let progress = {
+            let mut progress = self.progress.lock().unwrap_or_else(|e| e.into_inner());
+            update(&mut progress);
+            *progress
+        };
+        self.emit(SyncEvent::Progress(progress));
+    }
+
+    /// Record that the sync finished, saving it if there's a path for it
+    ///
+    /// A last sync that can't be saved is logged rather than failing the
+    /// sync.
+    fn finished(&self) {
+        let last = LastSync::now(self.progress());
+        if let Some(ref path) = self.last_sync_path {
+            if let Err(e) = last.save(path) {
+                warn!("Couldn't save the last sync time: {:#}", e);
+            }
+        }
+        self.emit(SyncEvent::Synced(last));
+    }
+
     fn set_status(&self, status: SyncStatus) {
         let _ = self.status.send(status);
         self.emit(SyncEvent::StatusChanged(status));
@@ -377,6 +623,12 @@ impl SyncClient {
     }
 }
 
+/// Whether a received frame is a sync message (which can be batched)
+fn is_sync_message(msg: &Result<Message, tokio_tungstenite::tungstenite::Error>) -> bool {
+    matches!(msg, Ok(Message::Binary(data))
+        if matches!(ServerMessage::decode(data), Ok(ServerMessage::Sync { .. })))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -390,6 +642,12 @@ mod tests {
         assert!(client.peer_id().starts_with("rott-"));
     }
 
+    #[test]
+    fn test_progress_starts_empty() {
+        let client = SyncClient::new("ws://localhost:3030", DocumentId::new());
+        assert_eq!(client.progress(), SyncProgress::default());
+    }
+
     #[test]
     fn test_sync_status() {
         let doc_id = DocumentId::new();
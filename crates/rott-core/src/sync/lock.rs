@@ -0,0 +1,221 @@
+//! Lock that keeps syncs from overlapping
+//!
+//! Every CLI command auto-syncs, so a script adding many links starts many
+//! syncs at once. Their WebSocket sessions, and their writes to the sync
+//! state, would interleave; instead only the process holding `sync.lock` in
+//! the data directory syncs. The file is created exclusively, holds the
+//! holder's PID, and is removed when the sync ends. While held, its
+//! modification time is refreshed every [`SyncLock::REFRESH_EVERY`], so a
+//! long sync keeps it. A lock left behind by a process that crashed stops
+//! being refreshed and stops counting once it's older than
+//! [`SyncLock::STALE_AFTER`].
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+
+/// Lock file name, in the data directory
+const LOCK_FILE: &str = "sync.lock";
+
+/// How often a waiting process checks the lock
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// The right to sync, held until dropped
+#[derive(Debug)]
+pub struct SyncLock {
+    path: PathBuf,
+    /// Stops the refresh thread when dropped
+    stop: Option<Sender<()>>,
+    refresh: Option<JoinHandle<()>>,
+}
+
+impl SyncLock {
+    /// Age after which a lock is assumed abandoned
+    pub const STALE_AFTER: Duration = Duration::from_secs(120);
+
+    /// How often the holder refreshes the lock's modification time
+    pub const REFRESH_EVERY: Duration = Duration::from_secs(30);
+
+    /// Take the lock, or return `None` if another process is syncing
+    pub fn try_acquire(data_dir: &Path) -> io::Result<Option<Self>> {
+        fs::create_dir_all(data_dir)?;
+        let path = data_dir.join(LOCK_FILE);
+
+        // Two attempts: the second after clearing a stale lock
+        for _ in 0..2 {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    writeln!(file, "{}", std::process::id())?;
+                    return Ok(Some(Self::hold(path)));
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    if !is_stale(&path) {
+                        return Ok(None);
+                    }
+                    tracing::warn!("Removing stale sync lock {}", path.display());
+                    match fs::remove_file(&path) {
+                        Ok(()) => {}
+                        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                        Err(e) => return Err(e),
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Keep the lock at `path` fresh until dropped
+    fn hold(path: PathBuf) -> Self {
+        let (stop, stopped) = mpsc::channel::<()>();
+        let refreshed = path.clone();
+        let refresh = std::thread::spawn(move || {
+            // Runs until the sender is dropped
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(Self::REFRESH_EVERY) {
+                if !is_ours(&refreshed) {
+                    break;
+                }
+                if let Err(e) = touch(&refreshed) {
+                    tracing::warn!("Couldn't refresh sync lock {}: {}", refreshed.display(), e);
+                }
+            }
+        });
+        Self {
+            path,
+            stop: Some(stop),
+            refresh: Some(refresh),
+        }
+    }
+
+    /// Wait for any sync in progress to finish, then take the lock
+    ///
+    /// Gives up and returns `None` after `timeout`.
+    pub async fn acquire(data_dir: &Path, timeout: Duration) -> io::Result<Option<Self>> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            if let Some(lock) = Self::try_acquire(data_dir)? {
+                return Ok(Some(lock));
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Ok(None);
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+impl Drop for SyncLock {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(refresh) = self.refresh.take() {
+            let _ = refresh.join();
+        }
+        // If the lock was taken over (we were stalled past STALE_AFTER), the
+        // file is someone else's now
+        if is_ours(&self.path) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Whether the lock at `path` holds this process's PID
+fn is_ours(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|pid| pid.trim() == std::process::id().to_string())
+}
+
+/// Set the lock's modification time to now
+fn touch(path: &Path) -> io::Result<()> {
+    fs::File::options()
+        .write(true)
+        .open(path)?
+        .set_modified(SystemTime::now())
+}
+
+/// Whether the lock at `path` was last refreshed longer ago than
+/// [`SyncLock::STALE_AFTER`]
+fn is_stale(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age > SyncLock::STALE_AFTER)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_only_one_holder() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let lock = SyncLock::try_acquire(temp_dir.path()).unwrap();
+        assert!(lock.is_some());
+        assert!(SyncLock::try_acquire(temp_dir.path()).unwrap().is_none());
+
+        // Released on drop
+        drop(lock);
+        assert!(!temp_dir.path().join(LOCK_FILE).exists());
+        assert!(SyncLock::try_acquire(temp_dir.path()).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_stale_lock_is_taken_over() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(LOCK_FILE);
+        fs::write(&path, "12345\n").unwrap();
+        let old = SystemTime::now() - SyncLock::STALE_AFTER - Duration::from_secs(1);
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+
+        let lock = SyncLock::try_acquire(temp_dir.path()).unwrap();
+        assert!(lock.is_some());
+        assert_eq!(
+            fs::read_to_string(&path).unwrap().trim(),
+            std::process::id().to_string()
+        );
+    }
+
+    #[test]
+    fn test_drop_leaves_a_lock_taken_over() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(LOCK_FILE);
+        let lock = SyncLock::try_acquire(temp_dir.path()).unwrap().unwrap();
+
+        // Another process decided our lock was stale and took it
+        fs::write(&path, "12345\n").unwrap();
+        drop(lock);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "12345\n");
+    }
+
+    #[tokio::test]
+    async fn test_acquire_waits_for_release() {
+        let temp_dir = TempDir::new().unwrap();
+        let lock = SyncLock::try_acquire(temp_dir.path()).unwrap().unwrap();
+
+        // Times out while held
+        let waited = SyncLock::acquire(temp_dir.path(), Duration::from_millis(300))
+            .await
+            .unwrap();
+        assert!(waited.is_none());
+
+        let dir = temp_dir.path().to_path_buf();
+        let waiter = tokio::spawn(async move {
+            SyncLock::acquire(&dir, Duration::from_secs(5))
+                .await
+                .unwrap()
+                .is_some()
+        });
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        drop(lock);
+        assert!(waiter.await.unwrap());
+    }
+}
@@ -1,7 +1,30 @@
 //! Sync protocol message types
 //!
 //! Messages exchanged with automerge-repo-sync-server using CBOR encoding.
+//!
+//! ## Compression
+//!
+//! Sync payloads can be deflate-compressed, which shrinks the first sync of
+//! a large document several times over. It's negotiated: the client lists
+//! the encodings it accepts in its join message's peer metadata
+//! (`rottCompression`), and only compresses if the server's peer message
+//! lists one back. Servers that don't know about it ignore the field and
+//! never list it, so with them payloads go uncompressed. A compressed
+//! payload is marked with its encoding (`compression`), in either
+//! direction; small payloads aren't worth compressing and go without.
+//!
+//! ## Ephemeral messages
+//!
+//! Ephemeral messages carry data about a document that isn't part of it
+//! (see [`super::presence`]). The server relays them to the other peers
+//! syncing the same document and doesn't store them. Each sender numbers
+//! its messages (`count`) within a session (`sessionId`).
+
+use std::io::{Read, Write};
 
+use anyhow::{Context, Result};
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
 use serde::{Deserialize, Serialize};
 
 use crate::document_id::DocumentId;
@@ -12,6 +35,15 @@ pub type PeerId = String;
 /// Protocol version
 pub const PROTOCOL_V1: &str = "1";
 
+/// Deflate payload encoding
+pub const COMPRESSION_DEFLATE: &str = "deflate";
+
+/// Payloads smaller than this are sent uncompressed
+pub const COMPRESS_MIN_BYTES: usize = 1024;
+
+/// Largest payload accepted after decompression
+const MAX_PAYLOAD_BYTES: u64 = 256 * 1024 * 1024;
+
 /// Peer metadata
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -20,6 +52,20 @@ pub struct PeerMetadata {
     pub storage_id: Option<String>,
     #[serde(default)]
     pub is_ephemeral: bool,
+    /// Payload encodings the peer accepts, besides none
+    #[serde(
+        default,
+        rename = "rottCompression",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub compression: Vec<String>,
+}
+
+impl PeerMetadata {
+    /// Whether the peer accepts deflate-compressed payloads
+    pub fn accepts_deflate(&self) -> bool {
+        self.compression.iter().any(|c| c == COMPRESSION_DEFLATE)
+    }
 }
 
 /// Messages sent to the sync server
@@ -49,6 +95,9 @@ pub enum ClientMessage {
         /// Automerge sync message bytes
         #[serde(with = "serde_bytes")]
         data: Vec<u8>,
+        /// How `data` is compressed, if it is
+        #[serde(skip_serializing_if = "Option::is_none")]
+        compression: Option<String>,
     },
 
     /// Request a document
@@ -63,6 +112,27 @@ pub enum ClientMessage {
         /// Automerge sync message bytes
         #[serde(with = "serde_bytes")]
         data: Vec<u8>,
+        /// How `data` is compressed, if it is
+        #[serde(skip_serializing_if = "Option::is_none")]
+        compression: Option<String>,
+    },
+
+    /// Data for the other peers syncing the document, not stored
+    #[serde(rename = "ephemeral")]
+    Ephemeral {
+        #[serde(rename = "senderId")]
+        sender_id: PeerId,
+        #[serde(rename = "targetId")]
+        target_id: PeerId,
+        /// Sequence number within the session
+        count: u64,
+        #[serde(rename = "sessionId")]
+        session_id: String,
+        #[serde(rename = "documentId")]
+        document_id: String,
+        /// CBOR-encoded payload
+        #[serde(with = "serde_bytes")]
+        data: Vec<u8>,
     },
 }
 
@@ -95,6 +165,9 @@ pub enum ServerMessage {
         /// Automerge sync message bytes
         #[serde(with = "serde_bytes")]
         data: Vec<u8>,
+        /// How `data` is compressed, if it is
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        compression: Option<String>,
     },
 
     /// Error from server
@@ -117,14 +190,37 @@ pub enum ServerMessage {
         #[serde(rename = "documentId")]
         document_id: String,
     },
+
+    /// Data relayed from another peer syncing the document
+    #[serde(rename = "ephemeral")]
+    Ephemeral {
+        #[serde(rename = "senderId")]
+        sender_id: PeerId,
+        #[serde(rename = "targetId")]
+        target_id: PeerId,
+        /// Sequence number within the sender's session
+        #[serde(default)]
+        count: u64,
+        #[serde(rename = "sessionId", default)]
+        session_id: String,
+        #[serde(rename = "documentId")]
+        document_id: String,
+        /// CBOR-encoded payload
+        #[serde(with = "serde_bytes")]
+        data: Vec<u8>,
+    },
 }
 
 impl ClientMessage {
-    /// Create a join message
-    pub fn join(sender_id: &str) -> Self {
+    /// Create a join message, offering compressed payloads if `compression`
+    pub fn join(sender_id: &str, compression: bool) -> Self {
+        let mut peer_metadata = PeerMetadata::default();
+        if compression {
+            peer_metadata.compression = vec![COMPRESSION_DEFLATE.to_string()];
+        }
         ClientMessage::Join {
             sender_id: sender_id.to_string(),
-            peer_metadata: PeerMetadata::default(),
+            peer_metadata,
             supported_protocol_versions: vec![PROTOCOL_V1.to_string()],
         }
     }
@@ -136,6 +232,7 @@ impl ClientMessage {
             target_id: target_id.to_string(),
             document_id: doc_id.to_bs58check(),
             data,
+            compression: None,
         }
     }
 
@@ -146,6 +243,54 @@ impl ClientMessage {
             target_id: target_id.to_string(),
             document_id: doc_id.to_bs58check(),
             data,
+            compression: None,
+        }
+    }
+
+    /// Create an ephemeral message
+    pub fn ephemeral(
+        sender_id: &str,
+        target_id: &str,
+        doc_id: &DocumentId,
+        session_id: &str,
+        count: u64,
+        data: Vec<u8>,
+    ) -> Self {
+        ClientMessage::Ephemeral {
+            sender_id: sender_id.to_string(),
+            target_id: target_id.to_string(),
+            count,
+            session_id: session_id.to_string(),
+            document_id: doc_id.to_bs58check(),
+            data,
+        }
+    }
+
+    /// Deflate the payload, if `enabled` (negotiated with the server) and
+    /// it's big enough to be worth it
+    pub fn compressed(mut self, enabled: bool) -> Self {
+        if let ClientMessage::Sync {
+            data, compression, ..
+        }
+        | ClientMessage::Request {
+            data, compression, ..
+        } = &mut self
+        {
+            if enabled && compression.is_none() && data.len() >= COMPRESS_MIN_BYTES {
+                *data = deflate(data);
+                *compression = Some(COMPRESSION_DEFLATE.to_string());
+            }
+        }
+        self
+    }
+
+    /// The message's `type` on the wire
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ClientMessage::Join { .. } => "join",
+            ClientMessage::Sync { .. } => "sync",
+            ClientMessage::Request { .. } => "request",
+            ClientMessage::Ephemeral { .. } => "ephemeral",
         }
     }
 
@@ -158,19 +303,54 @@ impl ClientMessage {
 }
 
 impl ServerMessage {
+    /// The message's `type` on the wire
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ServerMessage::Peer { .. } => "peer",
+            ServerMessage::Sync { .. } => "sync",
+            ServerMessage::Error { .. } => "error",
+            ServerMessage::DocUnavailable { .. } => "doc-unavailable",
+            ServerMessage::Ephemeral { .. } => "ephemeral",
+        }
+    }
+
     /// Decode message from CBOR bytes
     pub fn decode(bytes: &[u8]) -> Result<Self, ciborium::de::Error<std::io::Error>> {
         ciborium::from_reader(bytes)
     }
 }
 
+/// A received payload, decompressed if it was compressed
+pub fn payload(data: Vec<u8>, compression: Option<&str>) -> Result<Vec<u8>> {
+    match compression {
+        None => Ok(data),
+        Some(COMPRESSION_DEFLATE) => {
+            let mut inflated = Vec::new();
+            DeflateDecoder::new(data.as_slice())
+                .take(MAX_PAYLOAD_BYTES)
+                .read_to_end(&mut inflated)
+                .context("Failed to decompress sync payload")?;
+            Ok(inflated)
+        }
+        Some(other) => anyhow::bail!("Unsupported sync payload compression '{}'", other),
+    }
+}
+
+fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder
+        .write_all(data)
+        .expect("Compressing to memory failed");
+    encoder.finish().expect("Compressing to memory failed")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_join_message_encoding() {
-        let msg = ClientMessage::join("peer-123");
+        let msg = ClientMessage::join("peer-123", false);
         let bytes = msg.encode();
 
         // Should be non-empty CBOR
@@ -208,4 +388,90 @@ mod tests {
             _ => panic!("Expected Peer message"),
         }
     }
+
+    #[test]
+    fn test_ephemeral_message_relayed() {
+        let doc_id = DocumentId::new();
+        let sent = ClientMessage::ephemeral("peer-1", "server", &doc_id, "session", 3, vec![7]);
+        assert_eq!(sent.kind(), "ephemeral");
+
+        // The server relays it unchanged but for the target
+        match ServerMessage::decode(&sent.encode()).unwrap() {
+            ServerMessage::Ephemeral {
+                sender_id,
+                count,
+                data,
+                ..
+            } => {
+                assert_eq!(sender_id, "peer-1");
+                assert_eq!(count, 3);
+                assert_eq!(data, [7]);
+            }
+            other => panic!("Expected Ephemeral message, got {}", other.kind()),
+        }
+    }
+
+    #[test]
+    fn test_compression_negotiation() {
+        let ClientMessage::Join { peer_metadata, .. } = ClientMessage::join("peer-1", true) else {
+            panic!("Expected Join message");
+        };
+        assert!(peer_metadata.accepts_deflate());
+
+        // A server that doesn't know about compression leaves the field out
+        let msg = ServerMessage::Peer {
+            sender_id: "server".to_string(),
+            target_id: "peer-1".to_string(),
+            peer_metadata: PeerMetadata::default(),
+            selected_protocol_version: "1".to_string(),
+        };
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&msg, &mut bytes).unwrap();
+        match ServerMessage::decode(&bytes).unwrap() {
+            ServerMessage::Peer { peer_metadata, .. } => {
+                assert!(!peer_metadata.accepts_deflate())
+            }
+            _ => panic!("Expected Peer message"),
+        }
+    }
+
+    #[test]
+    fn test_compressed_payload_roundtrip() {
+        let doc_id = DocumentId::new();
+        let data = b"automerge change ".repeat(200);
+
+        // Small payloads, and servers that didn't agree, go uncompressed
+        let small =
+            ClientMessage::sync("peer-1", "server", &doc_id, vec![1, 2, 3]).compressed(true);
+        assert!(matches!(
+            small,
+            ClientMessage::Sync {
+                compression: None,
+                ..
+            }
+        ));
+        let off = ClientMessage::sync("peer-1", "server", &doc_id, data.clone()).compressed(false);
+        assert!(matches!(
+            off,
+            ClientMessage::Sync {
+                compression: None,
+                ..
+            }
+        ));
+
+        let msg = ClientMessage::sync("peer-1", "server", &doc_id, data.clone()).compressed(true);
+        let ClientMessage::Sync {
+            data: sent,
+            compression,
+            ..
+        } = msg
+        else {
+            panic!("Expected Sync message");
+        };
+        assert_eq!(compression.as_deref(), Some(COMPRESSION_DEFLATE));
+        assert!(sent.len() < data.len() / 4);
+        assert_eq!(payload(sent, compression.as_deref()).unwrap(), data);
+
+        assert!(payload(vec![1], Some("brotli")).is_err());
+    }
 }
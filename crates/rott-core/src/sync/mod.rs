@@ -30,19 +30,82 @@
 //!     match event {
 //!         SyncTaskEvent::DocumentUpdated => refresh_ui(),
 //!         SyncTaskEvent::StatusChanged(status) => update_indicator(status),
+//!         SyncTaskEvent::PeersChanged(activity) => show_peers(activity),
+//!         SyncTaskEvent::Synced(last) => show_last_sync(last),
 //!         _ => {}
 //!     }
 //! }
 //! ```
+//!
+//! ## Progress
+//!
+//! Both clients count the changes, bytes, and round trips each sync
+//! exchanges ([`SyncProgress`]), and save when the last one finished
+//! ([`LastSync`]) if given a path for it.
+//!
+//! ## Read-only devices
+//!
+//! A device joined with `rott init --join <id> --read-only` only pulls.
+//! Both clients are told so ([`SyncClient::with_read_only`],
+//! [`PersistentSyncConfig::read_only`]) and stop with a
+//! [`ReadOnlyError`] rather than send a message carrying changes; the store
+//! refuses to make any in the first place.
 
 mod client;
+mod lock;
 mod message;
 mod persistent;
+pub mod presence;
+mod progress;
 mod state;
+pub mod trace;
 
 pub use client::{SyncClient, SyncEvent, SyncStatus};
+pub use lock::SyncLock;
 pub use persistent::{
     spawn_sync_task, ConnectionStatus, PersistentSyncConfig, PersistentSyncHandle, SyncCommand,
     SyncTaskEvent,
 };
+pub use presence::PeerActivity;
+pub use progress::{LastSync, SyncProgress};
 pub use state::SyncState;
+pub use trace::SyncTrace;
+
+use anyhow::Result;
+use automerge::sync::{Message as SyncMessage, State as PeerState, SyncDoc};
+
+use crate::document::RottDocument;
+use crate::store::ReadOnlyError;
+
+/// Generate the next sync message for a peer, if there's one to send,
+/// with the number of changes it carries
+///
+/// When `read_only`, a message that would send the peer changes is an
+/// error instead.
+fn next_message(
+    doc: &mut RottDocument,
+    peer_state: &mut PeerState,
+    read_only: bool,
+) -> Result<Option<(SyncMessage, usize)>> {
+    let sent_before = peer_state.sent_hashes.len();
+    let message = doc.inner_mut().sync().generate_sync_message(peer_state);
+    let changes = peer_state.sent_hashes.len().saturating_sub(sent_before);
+    if read_only && changes > 0 {
+        return Err(ReadOnlyError.into());
+    }
+    Ok(message.map(|message| (message, changes)))
+}
+
+/// Apply a sync message from a peer, returning the number of changes it
+/// brought
+fn receive_message(
+    doc: &mut RottDocument,
+    peer_state: &mut PeerState,
+    message: SyncMessage,
+) -> Result<usize> {
+    let heads_before = doc.heads();
+    doc.inner_mut()
+        .sync()
+        .receive_sync_message(peer_state, message)?;
+    Ok(doc.inner_mut().get_changes(&heads_before).len())
+}
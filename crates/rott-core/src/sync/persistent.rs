@@ -2,20 +2,33 @@
 //!
 //! Maintains a long-lived WebSocket connection for real-time sync.
 //! Handles reconnection automatically with exponential backoff.
+//! While connected, announces this device to the other peers and reports
+//! theirs (see [`super::presence`]).
+//!
+//! With a [`SyncTrace`] in the config, every message on the connection is
+//! traced the way [`SyncClient`](super::SyncClient) traces a one-shot sync,
+//! in one trace that spans reconnections (see [`super::trace`]).
 
+use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use automerge::sync::{Message as SyncMessage, SyncDoc};
+use chrono::Utc;
 use futures_util::{SinkExt, StreamExt};
 use tokio::net::TcpStream;
 use tokio::sync::{mpsc, watch, Mutex};
 use tokio_tungstenite::tungstenite::Message;
 use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+use tracing::warn;
 
-use super::message::{ClientMessage, PeerId, ServerMessage};
+use super::message::{payload, ClientMessage, PeerId, PeerMetadata, ServerMessage};
+use super::presence::{PeerActivity, PeerPresence, Presence, PRESENCE_INTERVAL};
+use super::progress::{LastSync, SyncProgress};
 use super::state::SyncState;
+use super::trace::{Direction, SyncTrace, TraceEntry};
+use super::{next_message, receive_message};
 use crate::document::RottDocument;
 use crate::document_id::DocumentId;
 
@@ -35,6 +48,12 @@ pub enum SyncTaskEvent {
     StatusChanged(ConnectionStatus),
     /// Document was updated from remote changes
     DocumentUpdated,
+    /// Other devices came online, went away, or announced a change
+    PeersChanged(PeerActivity),
+    /// More was exchanged with the server (totals for the current exchange)
+    Progress(SyncProgress),
+    /// An exchange with the server finished
+    Synced(LastSync),
     /// Error occurred
     Error(String),
 }
@@ -73,6 +92,19 @@ pub struct PersistentSyncConfig {
     pub initial_reconnect_delay: Duration,
     /// Maximum reconnect delay
     pub max_reconnect_delay: Duration,
+    /// Only pull: never send the server changes
+    pub read_only: bool,
+    /// Offer to compress sync payloads (used if the server agrees)
+    pub compression: bool,
+    /// How long to wait for more changes before pushing, so a burst of
+    /// edits goes out as one exchange
+    pub batch_delay: Duration,
+    /// Name other devices see this one by (the peer ID if empty)
+    pub device_name: String,
+    /// Where the last finished exchange is saved, if anywhere
+    pub last_sync_path: Option<PathBuf>,
+    /// Where the connection is traced, if it is
+    pub trace: Option<Arc<std::sync::Mutex<SyncTrace>>>,
 }
 
 impl Default for PersistentSyncConfig {
@@ -82,6 +114,12 @@ impl Default for PersistentSyncConfig {
             doc_id: DocumentId::new(),
             initial_reconnect_delay: Duration::from_secs(1),
             max_reconnect_delay: Duration::from_secs(30),
+            read_only: false,
+            compression: false,
+            batch_delay: Duration::ZERO,
+            device_name: String::new(),
+            last_sync_path: None,
+            trace: None,
         }
     }
 }
@@ -121,6 +159,15 @@ async fn sync_task_loop(
 ) {
     let peer_id: PeerId = format!("rott-{}", &uuid::Uuid::new_v4().to_string()[..8]);
     let mut reconnect_delay = config.initial_reconnect_delay;
+    // Kept across reconnections, so the last change is still announced
+    let mut presence = Presence {
+        device: if config.device_name.is_empty() {
+            peer_id.clone()
+        } else {
+            config.device_name.clone()
+        },
+        last_change: None,
+    };
 
     loop {
         // Try to connect
@@ -128,10 +175,18 @@ async fn sync_task_loop(
         let _ = event_tx
             .send(SyncTaskEvent::StatusChanged(ConnectionStatus::Connecting))
             .await;
+        trace_event(
+            &config,
+            &doc,
+            "connect",
+            Some(format!("{} document {}", config.url, config.doc_id)),
+        )
+        .await;
 
         match connect_and_sync(
             &config,
             &peer_id,
+            &mut presence,
             &doc,
             &sync_state,
             &mut command_rx,
@@ -142,6 +197,7 @@ async fn sync_task_loop(
         {
             Ok(should_shutdown) => {
                 if should_shutdown {
+                    trace_event(&config, &doc, "done", None).await;
                     let _ = status_tx.send(ConnectionStatus::Disconnected);
                     let _ = event_tx
                         .send(SyncTaskEvent::StatusChanged(ConnectionStatus::Disconnected))
@@ -149,9 +205,11 @@ async fn sync_task_loop(
                     break;
                 }
                 // Connection closed normally, reset backoff
+                trace_event(&config, &doc, "disconnected", None).await;
                 reconnect_delay = config.initial_reconnect_delay;
             }
             Err(e) => {
+                trace_event(&config, &doc, "error", Some(format!("{:#}", e))).await;
                 let _ = event_tx
                     .send(SyncTaskEvent::Error(format!("Connection error: {}", e)))
                     .await;
@@ -183,9 +241,11 @@ async fn sync_task_loop(
 }
 
 /// Connect and run sync loop until disconnection or shutdown
+#[allow(clippy::too_many_arguments)]
 async fn connect_and_sync(
     config: &PersistentSyncConfig,
     peer_id: &str,
+    presence: &mut Presence,
     doc: &Arc<Mutex<RottDocument>>,
     sync_state: &Arc<Mutex<SyncState>>,
     command_rx: &mut mpsc::Receiver<SyncCommand>,
@@ -197,11 +257,19 @@ async fn connect_and_sync(
     let (mut write, mut read) = ws_stream.split();
 
     // Send join message
-    let join_msg = ClientMessage::join(peer_id);
-    write.send(Message::Binary(join_msg.encode())).await?;
+    let join_msg = ClientMessage::join(peer_id, config.compression);
+    write
+        .send(Message::Binary(encode_traced(config, doc, &join_msg).await))
+        .await?;
 
     // Wait for peer response
-    let server_peer_id = wait_for_peer(&mut read).await?;
+    let (server_peer_id, server_metadata) = wait_for_peer(config, doc, &mut read).await?;
+
+    // Compress for this connection only if the server agreed to
+    let config = &PersistentSyncConfig {
+        compression: config.compression && server_metadata.accepts_deflate(),
+        ..config.clone()
+    };
 
     // Connected successfully
     let _ = status_tx.send(ConnectionStatus::Connected);
@@ -218,7 +286,7 @@ async fn connect_and_sync(
     do_sync(
         peer_id,
         &server_peer_id,
-        &config.doc_id,
+        config,
         doc,
         sync_state,
         &mut write,
@@ -232,20 +300,39 @@ async fn connect_and_sync(
         .send(SyncTaskEvent::StatusChanged(ConnectionStatus::Connected))
         .await;
 
+    // The first tick is immediate: announce this device on connecting
+    let mut announcer = Announcer::new();
+    let mut heartbeat = tokio::time::interval(PRESENCE_INTERVAL);
+    heartbeat.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut peers = PeerPresence::default();
+
     // Main loop: wait for commands or incoming messages
     loop {
         tokio::select! {
+            // Announce this device, and forget peers that stopped announcing
+            _ = heartbeat.tick() => {
+                let msg = announcer.next(peer_id, &server_peer_id, &config.doc_id, presence);
+                write.send(Message::Binary(encode_traced(config, doc, &msg).await)).await?;
+                if peers.expire(Instant::now()) {
+                    let _ = event_tx.send(SyncTaskEvent::PeersChanged(peers.activity())).await;
+                }
+            }
+
             // Check for commands
             cmd = command_rx.recv() => {
                 match cmd {
                     Some(SyncCommand::PushChanges) => {
+                        if !batch_pushes(command_rx, config.batch_delay).await {
+                            write.close().await.ok();
+                            return Ok(true); // Shut down while waiting
+                        }
                         let _ = status_tx.send(ConnectionStatus::Syncing);
                         let _ = event_tx.send(SyncTaskEvent::StatusChanged(ConnectionStatus::Syncing)).await;
 
                         do_sync(
                             peer_id,
                             &server_peer_id,
-                            &config.doc_id,
+                            config,
                             doc,
                             sync_state,
                             &mut write,
@@ -255,6 +342,13 @@ async fn connect_and_sync(
 
                         let _ = status_tx.send(ConnectionStatus::Connected);
                         let _ = event_tx.send(SyncTaskEvent::StatusChanged(ConnectionStatus::Connected)).await;
+
+                        // Let the other devices know about the change now
+                        if !config.read_only {
+                            presence.last_change = Some(Utc::now());
+                            let msg = announcer.next(peer_id, &server_peer_id, &config.doc_id, presence);
+                            write.send(Message::Binary(encode_traced(config, doc, &msg).await)).await?;
+                        }
                     }
                     Some(SyncCommand::Shutdown) => {
                         write.close().await.ok();
@@ -272,24 +366,41 @@ async fn connect_and_sync(
             msg = read.next() => {
                 match msg {
                     Some(Ok(Message::Binary(data))) => {
-                        if let Ok(ServerMessage::Sync { data, .. }) = ServerMessage::decode(&data) {
-                            // Incoming sync from server
-                            let _ = status_tx.send(ConnectionStatus::Syncing);
-                            let _ = event_tx.send(SyncTaskEvent::StatusChanged(ConnectionStatus::Syncing)).await;
-
-                            handle_incoming_sync(
-                                peer_id,
-                                &server_peer_id,
-                                &config.doc_id,
-                                &data,
-                                doc,
-                                sync_state,
-                                &mut write,
-                                event_tx,
-                            ).await?;
-
-                            let _ = status_tx.send(ConnectionStatus::Connected);
-                            let _ = event_tx.send(SyncTaskEvent::StatusChanged(ConnectionStatus::Connected)).await;
+                        let size = data.len();
+                        let decoded = ServerMessage::decode(&data);
+                        // Sync messages are traced once applied
+                        if !matches!(decoded, Ok(ServerMessage::Sync { .. })) {
+                            trace_received(config, doc, &decoded, size).await;
+                        }
+                        match decoded {
+                            Ok(ServerMessage::Sync { data, compression, .. }) => {
+                                let data = payload(data, compression.as_deref())?;
+                                // Incoming sync from server
+                                let _ = status_tx.send(ConnectionStatus::Syncing);
+                                let _ = event_tx.send(SyncTaskEvent::StatusChanged(ConnectionStatus::Syncing)).await;
+
+                                handle_incoming_sync(
+                                    peer_id,
+                                    &server_peer_id,
+                                    config,
+                                    &data,
+                                    size,
+                                    doc,
+                                    sync_state,
+                                    &mut write,
+                                    event_tx,
+                                ).await?;
+
+                                let _ = status_tx.send(ConnectionStatus::Connected);
+                                let _ = event_tx.send(SyncTaskEvent::StatusChanged(ConnectionStatus::Connected)).await;
+                            }
+                            Ok(ServerMessage::Ephemeral { sender_id, data, .. }) if sender_id != peer_id => {
+                                if let Some(announced) = Presence::decode(&data) {
+                                    peers.update(&sender_id, announced, Instant::now());
+                                    let _ = event_tx.send(SyncTaskEvent::PeersChanged(peers.activity())).await;
+                                }
+                            }
+                            _ => {}
                         }
                     }
                     Some(Ok(Message::Close(_))) | None => {
@@ -306,10 +417,63 @@ async fn connect_and_sync(
     }
 }
 
-/// Wait for peer handshake response
+/// Numbers this device's presence announcements on one connection
+struct Announcer {
+    session_id: String,
+    count: u64,
+}
+
+impl Announcer {
+    fn new() -> Self {
+        Self {
+            session_id: uuid::Uuid::new_v4().to_string(),
+            count: 0,
+        }
+    }
+
+    /// The next announcement of `presence`
+    fn next(
+        &mut self,
+        peer_id: &str,
+        server_peer_id: &str,
+        doc_id: &DocumentId,
+        presence: &Presence,
+    ) -> ClientMessage {
+        self.count += 1;
+        ClientMessage::ephemeral(
+            peer_id,
+            server_peer_id,
+            doc_id,
+            &self.session_id,
+            self.count,
+            presence.encode(),
+        )
+    }
+}
+
+/// Wait out the batch delay, taking in further push requests
+///
+/// Returns false if the task was told to shut down meanwhile.
+async fn batch_pushes(command_rx: &mut mpsc::Receiver<SyncCommand>, delay: Duration) -> bool {
+    let deadline = tokio::time::Instant::now() + delay;
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep_until(deadline) => return true,
+            cmd = command_rx.recv() => match cmd {
+                Some(SyncCommand::PushChanges) => {}
+                Some(SyncCommand::Shutdown) | None => return false,
+            },
+        }
+    }
+}
+
+/// Wait for peer handshake response, returning the server's peer ID and
+/// metadata
 async fn wait_for_peer(
+    config: &PersistentSyncConfig,
+    doc: &Arc<Mutex<RottDocument>>,
     read: &mut futures_util::stream::SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
-) -> Result<String> {
+) -> Result<(String, PeerMetadata)> {
     let timeout = Duration::from_secs(10);
     let deadline = tokio::time::Instant::now() + timeout;
 
@@ -323,8 +487,10 @@ async fn wait_for_peer(
             msg = read.next() => {
                 match msg {
                     Some(Ok(Message::Binary(data))) => {
-                        if let Ok(ServerMessage::Peer { sender_id, .. }) = ServerMessage::decode(&data) {
-                            return Ok(sender_id);
+                        let decoded = ServerMessage::decode(&data);
+                        trace_received(config, doc, &decoded, data.len()).await;
+                        if let Ok(ServerMessage::Peer { sender_id, peer_metadata, .. }) = decoded {
+                            return Ok((sender_id, peer_metadata));
                         }
                     }
                     Some(Ok(Message::Close(_))) | None => {
@@ -348,7 +514,7 @@ async fn wait_for_peer(
 async fn do_sync<S>(
     peer_id: &str,
     server_peer_id: &str,
-    doc_id: &DocumentId,
+    config: &PersistentSyncConfig,
     doc: &Arc<Mutex<RottDocument>>,
     sync_state: &Arc<Mutex<SyncState>>,
     write: &mut futures_util::stream::SplitSink<S, Message>,
@@ -360,22 +526,23 @@ where
     <S as futures_util::Sink<Message>>::Error: std::error::Error + Send + Sync + 'static,
     <S as futures_util::Stream>::Item: Into<Result<Message, tokio_tungstenite::tungstenite::Error>>,
 {
+    let mut progress = SyncProgress::default();
+    let mut failed = false;
+
     // Generate and send initial sync message
     let initial_msg = {
         let mut doc_guard = doc.lock().await;
         let mut state_guard = sync_state.lock().await;
         let peer_state = state_guard.get_or_create(server_peer_id);
-        let result = doc_guard
-            .inner_mut()
-            .sync()
-            .generate_sync_message(peer_state)
-            .map(|m| m.encode());
-        result
+        next_message(&mut doc_guard, peer_state, config.read_only)?.map(|(m, n)| (m.encode(), n))
     };
 
-    if let Some(msg_bytes) = initial_msg {
-        let request = ClientMessage::request(peer_id, server_peer_id, doc_id, msg_bytes);
-        write.send(Message::Binary(request.encode())).await?;
+    if let Some((msg_bytes, changes)) = initial_msg {
+        let request = ClientMessage::request(peer_id, server_peer_id, &config.doc_id, msg_bytes)
+            .compressed(config.compression);
+        let request = encode_traced(config, doc, &request).await;
+        progress.record_sent(request.len(), changes);
+        write.send(Message::Binary(request)).await?;
     }
 
     // Process responses
@@ -393,44 +560,56 @@ where
                 let msg: Option<Result<Message, _>> = msg.map(|m| m.into());
                 match msg {
                     Some(Ok(Message::Binary(data))) => {
-                        match ServerMessage::decode(&data) {
-                            Ok(ServerMessage::Sync { data, .. }) => {
+                        let size = data.len();
+                        let decoded = ServerMessage::decode(&data);
+                        // Sync messages are traced once applied
+                        if !matches!(decoded, Ok(ServerMessage::Sync { .. })) {
+                            trace_received(config, doc, &decoded, size).await;
+                        }
+                        match decoded {
+                            Ok(ServerMessage::Sync { data, compression, .. }) => {
+                                let data = payload(data, compression.as_deref())?;
                                 let should_continue = process_sync_message(
                                     peer_id,
                                     server_peer_id,
-                                    doc_id,
-                                    &data,
+                                    config,
+                                    (&data, size),
                                     doc,
                                     sync_state,
                                     write,
                                     event_tx,
+                                    &mut progress,
                                 ).await?;
 
                                 if !should_continue {
                                     break;
                                 }
                             }
+                            Ok(ServerMessage::DocUnavailable { .. }) if config.read_only => {
+                                // Nothing to pull, and a read-only device doesn't upload
+                                break;
+                            }
                             Ok(ServerMessage::DocUnavailable { .. }) => {
                                 // Document doesn't exist on server, push ours
                                 let msg_bytes = {
                                     let mut doc_guard = doc.lock().await;
                                     let mut state_guard = sync_state.lock().await;
                                     let peer_state = state_guard.get_or_create(server_peer_id);
-                                    let result = doc_guard
-                                        .inner_mut()
-                                        .sync()
-                                        .generate_sync_message(peer_state)
-                                        .map(|m| m.encode());
-                                    result
+                                    next_message(&mut doc_guard, peer_state, false)?
+                                        .map(|(m, n)| (m.encode(), n))
                                 };
 
-                                if let Some(bytes) = msg_bytes {
-                                    let msg = ClientMessage::sync(peer_id, server_peer_id, doc_id, bytes);
-                                    write.send(Message::Binary(msg.encode())).await?;
+                                if let Some((bytes, changes)) = msg_bytes {
+                                    let msg = ClientMessage::sync(peer_id, server_peer_id, &config.doc_id, bytes)
+                                        .compressed(config.compression);
+                                    let msg = encode_traced(config, doc, &msg).await;
+                                    progress.record_sent(msg.len(), changes);
+                                    write.send(Message::Binary(msg)).await?;
                                 }
                             }
                             Ok(ServerMessage::Error { message, .. }) => {
                                 let _ = event_tx.send(SyncTaskEvent::Error(message)).await;
+                                failed = true;
                                 break;
                             }
                             _ => {}
@@ -452,28 +631,67 @@ where
     // Save sync state
     let state_guard = sync_state.lock().await;
     state_guard.save().ok();
+    drop(state_guard);
 
+    if !failed {
+        finish_sync(config, doc, progress, event_tx).await;
+    }
     Ok(())
 }
 
-/// Process an incoming sync message
+/// Report a finished exchange, saving it if there's a path for it
+///
+/// A last sync that can't be saved is logged rather than failing the sync.
+async fn finish_sync(
+    config: &PersistentSyncConfig,
+    doc: &Arc<Mutex<RottDocument>>,
+    progress: SyncProgress,
+    event_tx: &mpsc::Sender<SyncTaskEvent>,
+) {
+    trace_event(config, doc, "synced", None).await;
+    let last = LastSync::now(progress);
+    if let Some(ref path) = config.last_sync_path {
+        if let Err(e) = last.save(path) {
+            warn!("Couldn't save the last sync time: {:#}", e);
+        }
+    }
+    let _ = event_tx.send(SyncTaskEvent::Synced(last)).await;
+}
+
+/// Process an incoming sync message (its payload, and the size of the
+/// frame it came in), counting it in `progress`
 #[allow(clippy::too_many_arguments)]
 async fn process_sync_message<S>(
     peer_id: &str,
     server_peer_id: &str,
-    doc_id: &DocumentId,
-    data: &[u8],
+    config: &PersistentSyncConfig,
+    (data, size): (&[u8], usize),
     doc: &Arc<Mutex<RottDocument>>,
     sync_state: &Arc<Mutex<SyncState>>,
     write: &mut futures_util::stream::SplitSink<S, Message>,
     event_tx: &mpsc::Sender<SyncTaskEvent>,
+    progress: &mut SyncProgress,
 ) -> Result<bool>
 where
     S: futures_util::Sink<Message> + Unpin,
     <S as futures_util::Sink<Message>>::Error: std::error::Error + Send + Sync + 'static,
 {
-    let Ok(sync_msg) = SyncMessage::decode(data) else {
-        return Ok(true);
+    let sync_msg = match SyncMessage::decode(data) {
+        Ok(sync_msg) => sync_msg,
+        Err(e) => {
+            let detail = format!("undecodable Automerge sync message: {}", e);
+            let mut doc_guard = doc.lock().await;
+            trace(
+                config,
+                &mut doc_guard,
+                Direction::Received,
+                "sync",
+                size,
+                None,
+                Some(detail),
+            );
+            return Ok(true);
+        }
     };
 
     // Process message and generate response in one block
@@ -482,41 +700,52 @@ where
         let mut state_guard = sync_state.lock().await;
         let peer_state = state_guard.get_or_create(server_peer_id);
 
-        doc_guard
-            .inner_mut()
-            .sync()
-            .receive_sync_message(peer_state, sync_msg)?;
+        let heads_before = heads(config, &mut doc_guard);
+        let changes = receive_message(&mut doc_guard, peer_state, sync_msg)?;
+        trace(
+            config,
+            &mut doc_guard,
+            Direction::Received,
+            "sync",
+            size,
+            heads_before,
+            None,
+        );
+        progress.record_received(size, changes);
 
         // Generate response if needed
-        let result = doc_guard
-            .inner_mut()
-            .sync()
-            .generate_sync_message(peer_state)
-            .map(|m| m.encode());
-        result
+        next_message(&mut doc_guard, peer_state, config.read_only)?.map(|(m, n)| (m.encode(), n))
     };
 
     // Notify that document was updated
     let _ = event_tx.send(SyncTaskEvent::DocumentUpdated).await;
 
     // Send response if we have one
-    if let Some(bytes) = response_bytes {
-        let msg = ClientMessage::sync(peer_id, server_peer_id, doc_id, bytes);
-        write.send(Message::Binary(msg.encode())).await?;
-        Ok(true)
+    let more = if let Some((bytes, changes)) = response_bytes {
+        let msg = ClientMessage::sync(peer_id, server_peer_id, &config.doc_id, bytes)
+            .compressed(config.compression);
+        let msg = encode_traced(config, doc, &msg).await;
+        progress.record_sent(msg.len(), changes);
+        write.send(Message::Binary(msg)).await?;
+        true
     } else {
         // No more messages, sync complete
-        Ok(false)
-    }
+        false
+    };
+    let _ = event_tx.send(SyncTaskEvent::Progress(*progress)).await;
+    Ok(more)
 }
 
 /// Handle incoming sync message (server-initiated)
+///
+/// Each one is an exchange of its own, reported as finished once answered.
 #[allow(clippy::too_many_arguments)]
 async fn handle_incoming_sync<S>(
     peer_id: &str,
     server_peer_id: &str,
-    doc_id: &DocumentId,
+    config: &PersistentSyncConfig,
     data: &[u8],
+    size: usize,
     doc: &Arc<Mutex<RottDocument>>,
     sync_state: &Arc<Mutex<SyncState>>,
     write: &mut futures_util::stream::SplitSink<S, Message>,
@@ -526,20 +755,137 @@ where
     S: futures_util::Sink<Message> + Unpin,
     <S as futures_util::Sink<Message>>::Error: std::error::Error + Send + Sync + 'static,
 {
+    let mut progress = SyncProgress::default();
     process_sync_message(
         peer_id,
         server_peer_id,
-        doc_id,
-        data,
+        config,
+        (data, size),
         doc,
         sync_state,
         write,
         event_tx,
+        &mut progress,
     )
     .await?;
+    finish_sync(config, doc, progress, event_tx).await;
     Ok(())
 }
 
+/// Encode a message to send, tracing it
+async fn encode_traced(
+    config: &PersistentSyncConfig,
+    doc: &Arc<Mutex<RottDocument>>,
+    msg: &ClientMessage,
+) -> Vec<u8> {
+    let bytes = msg.encode();
+    if config.trace.is_some() {
+        let mut doc_guard = doc.lock().await;
+        trace(
+            config,
+            &mut doc_guard,
+            Direction::Sent,
+            msg.kind(),
+            bytes.len(),
+            None,
+            None,
+        );
+    }
+    bytes
+}
+
+/// Trace a received message that doesn't change the document
+async fn trace_received<E: std::fmt::Display>(
+    config: &PersistentSyncConfig,
+    doc: &Arc<Mutex<RottDocument>>,
+    decoded: &std::result::Result<ServerMessage, E>,
+    size: usize,
+) {
+    if config.trace.is_none() {
+        return;
+    }
+    let (message_type, detail) = match decoded {
+        Ok(ServerMessage::Error { message, .. }) => ("error", Some(message.clone())),
+        Ok(msg) => (msg.kind(), None),
+        Err(e) => ("undecodable", Some(e.to_string())),
+    };
+    let mut doc_guard = doc.lock().await;
+    trace(
+        config,
+        &mut doc_guard,
+        Direction::Received,
+        message_type,
+        size,
+        None,
+        detail,
+    );
+}
+
+async fn trace_event(
+    config: &PersistentSyncConfig,
+    doc: &Arc<Mutex<RottDocument>>,
+    event: &str,
+    detail: Option<String>,
+) {
+    if config.trace.is_none() {
+        return;
+    }
+    let mut doc_guard = doc.lock().await;
+    trace(
+        config,
+        &mut doc_guard,
+        Direction::Event,
+        event,
+        0,
+        None,
+        detail,
+    );
+}
+
+/// Record a trace entry, if tracing
+///
+/// `heads_before` defaults to the current heads (for messages that
+/// don't change the document).
+fn trace(
+    config: &PersistentSyncConfig,
+    doc: &mut RottDocument,
+    direction: Direction,
+    message_type: &str,
+    bytes: usize,
+    heads_before: Option<Vec<String>>,
+    detail: Option<String>,
+) {
+    let Some(ref trace) = config.trace else {
+        return;
+    };
+    let heads_after = heads(config, doc).unwrap_or_default();
+    let entry = TraceEntry {
+        at: Utc::now(),
+        direction,
+        message_type: message_type.to_string(),
+        bytes,
+        heads_before: heads_before.unwrap_or_else(|| heads_after.clone()),
+        heads_after,
+        detail,
+    };
+    trace
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .record(&entry);
+}
+
+/// The document's heads, if tracing (they're only needed for traces)
+fn heads(config: &PersistentSyncConfig, doc: &mut RottDocument) -> Option<Vec<String>> {
+    config.trace.as_ref()?;
+    Some(
+        doc.inner_mut()
+            .get_heads()
+            .iter()
+            .map(|hash| hash.to_string())
+            .collect(),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -567,5 +913,41 @@ mod tests {
         let config = PersistentSyncConfig::default();
         assert_eq!(config.initial_reconnect_delay, Duration::from_secs(1));
         assert_eq!(config.max_reconnect_delay, Duration::from_secs(30));
+        assert!(config.trace.is_none());
+    }
+
+    #[test]
+    fn test_trace_records_heads() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let trace = SyncTrace::create(temp_dir.path(), &DocumentId::new()).unwrap();
+        let path = trace.path().to_path_buf();
+        let config = PersistentSyncConfig {
+            trace: Some(Arc::new(std::sync::Mutex::new(trace))),
+            ..Default::default()
+        };
+        let mut doc = RottDocument::new();
+
+        let heads_before = heads(&config, &mut doc);
+        doc.add_link(&crate::Link::new("https://example.com"))
+            .unwrap();
+        trace(
+            &config,
+            &mut doc,
+            Direction::Received,
+            "sync",
+            100,
+            heads_before,
+            None,
+        );
+        drop(config);
+
+        let entries = crate::sync::trace::read_trace(&path).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].message_type, "sync");
+        assert_ne!(entries[0].heads_before, entries[0].heads_after);
+
+        // Without a trace, nothing is recorded or even looked up
+        let config = PersistentSyncConfig::default();
+        assert!(heads(&config, &mut doc).is_none());
     }
 }
@@ -0,0 +1,149 @@
+//! Presence of other devices syncing the same document
+//!
+//! While the TUI's persistent connection is up, it announces itself every
+//! [`PRESENCE_INTERVAL`] in an ephemeral message (see [`super::message`]):
+//! the device's name and when it last pushed a change of its own. The
+//! server relays these to the other peers syncing the document, so each
+//! device can show how many others are online and which one changed the
+//! collection last. Nothing about presence is stored.
+//!
+//! A peer that hasn't announced itself for [`PRESENCE_TTL`] counts as gone;
+//! peers don't say goodbye. Ephemeral messages that aren't presence (from
+//! other automerge-repo clients) are ignored.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::message::PeerId;
+
+/// How often a device announces itself
+pub const PRESENCE_INTERVAL: Duration = Duration::from_secs(20);
+
+/// How long a peer counts as online after it last announced itself
+pub const PRESENCE_TTL: Duration = Duration::from_secs(60);
+
+/// What a device announces about itself
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Presence {
+    /// Name of the device (`device_name`, or the host name)
+    #[serde(rename = "rottDevice")]
+    pub device: String,
+    /// When the device last pushed a change of its own
+    #[serde(rename = "rottLastChange", default)]
+    pub last_change: Option<DateTime<Utc>>,
+}
+
+impl Presence {
+    /// Encode as an ephemeral message's payload
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(self, &mut bytes).expect("CBOR encoding failed");
+        bytes
+    }
+
+    /// Decode an ephemeral message's payload, if it's presence
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        ciborium::from_reader(bytes).ok()
+    }
+}
+
+/// Activity of the other devices, as the TUI shows it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PeerActivity {
+    /// Other devices online now
+    pub online: usize,
+    /// The most recent change announced by another device: its name and
+    /// when
+    pub last_change: Option<(String, DateTime<Utc>)>,
+}
+
+/// The presence announced by each peer, and when it was heard
+#[derive(Debug, Default)]
+pub struct PeerPresence {
+    peers: HashMap<PeerId, (Presence, Instant)>,
+}
+
+impl PeerPresence {
+    /// Record a peer's announcement
+    pub fn update(&mut self, peer: &str, presence: Presence, now: Instant) {
+        self.peers.insert(peer.to_string(), (presence, now));
+    }
+
+    /// Forget peers that haven't announced themselves for the TTL
+    ///
+    /// Returns whether any were forgotten.
+    pub fn expire(&mut self, now: Instant) -> bool {
+        let before = self.peers.len();
+        self.peers
+            .retain(|_, (_, heard)| now.duration_since(*heard) < PRESENCE_TTL);
+        self.peers.len() != before
+    }
+
+    /// Forget every peer (the connection was lost)
+    pub fn clear(&mut self) {
+        self.peers.clear();
+    }
+
+    /// Activity of the peers online
+    pub fn activity(&self) -> PeerActivity {
+        let last_change = self
+            .peers
+            .values()
+            .filter_map(|(presence, _)| Some((presence.device.clone(), presence.last_change?)))
+            .max_by_key(|(_, at)| *at);
+        PeerActivity {
+            online: self.peers.len(),
+            last_change,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn presence(device: &str, last_change: Option<DateTime<Utc>>) -> Presence {
+        Presence {
+            device: device.to_string(),
+            last_change,
+        }
+    }
+
+    #[test]
+    fn test_presence_roundtrip() {
+        let sent = presence("laptop", Some(Utc::now()));
+        assert_eq!(Presence::decode(&sent.encode()), Some(sent));
+
+        // Another client's ephemeral payload isn't presence
+        let mut other = Vec::new();
+        ciborium::into_writer(&serde_json::json!({"cursor": 3}), &mut other).unwrap();
+        assert_eq!(Presence::decode(&other), None);
+    }
+
+    #[test]
+    fn test_peer_activity() {
+        let now = Instant::now();
+        let earlier = Utc::now() - chrono::Duration::minutes(5);
+        let later = Utc::now();
+        let mut peers = PeerPresence::default();
+        peers.update("a", presence("laptop", Some(earlier)), now);
+        peers.update("b", presence("phone", Some(later)), now);
+        peers.update("c", presence("desktop", None), now);
+
+        let activity = peers.activity();
+        assert_eq!(activity.online, 3);
+        assert_eq!(activity.last_change, Some(("phone".to_string(), later)));
+
+        // A peer that keeps announcing itself stays; the others expire
+        let much_later = now + PRESENCE_TTL;
+        peers.update("a", presence("laptop", Some(earlier)), much_later);
+        assert!(peers.expire(much_later));
+        assert!(!peers.expire(much_later));
+        let activity = peers.activity();
+        assert_eq!(activity.online, 1);
+        assert_eq!(activity.last_change, Some(("laptop".to_string(), earlier)));
+    }
+}
@@ -0,0 +1,123 @@
+//! Sync progress and the last successful sync
+//!
+//! Both clients count what each sync exchanges with the server
+//! ([`SyncProgress`]). When a sync finishes, the time and its counts are
+//! saved in the collection's directory ([`LastSync`]), so `rott status` and
+//! the TUI can say when this device last synced, even after a restart.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// What a sync has exchanged with the server so far
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncProgress {
+    /// Changes sent to the server
+    pub changes_sent: usize,
+    /// Changes received from the server
+    pub changes_received: usize,
+    /// Bytes of sync messages sent (compressed, if they were)
+    pub bytes_sent: usize,
+    /// Bytes of sync messages received (compressed, if they were)
+    pub bytes_received: usize,
+    /// Sync messages sent, each answered by the server
+    pub round_trips: usize,
+}
+
+impl SyncProgress {
+    /// Count a sync message sent, carrying `changes` changes
+    pub fn record_sent(&mut self, bytes: usize, changes: usize) {
+        self.bytes_sent += bytes;
+        self.changes_sent += changes;
+        self.round_trips += 1;
+    }
+
+    /// Count a sync message received, which brought `changes` changes
+    pub fn record_received(&mut self, bytes: usize, changes: usize) {
+        self.bytes_received += bytes;
+        self.changes_received += changes;
+    }
+
+    /// Whether any changes went either way
+    pub fn exchanged_changes(&self) -> bool {
+        self.changes_sent > 0 || self.changes_received > 0
+    }
+}
+
+/// When this device last finished a sync, and what it exchanged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LastSync {
+    /// When the sync finished
+    pub at: DateTime<Utc>,
+    /// What it exchanged
+    #[serde(flatten)]
+    pub progress: SyncProgress,
+}
+
+impl LastSync {
+    /// A sync that just finished
+    pub fn now(progress: SyncProgress) -> Self {
+        Self {
+            at: Utc::now(),
+            progress,
+        }
+    }
+
+    /// Load the last sync saved at `path` (None if there's been none)
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let last = serde_json::from_str(&json)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some(last))
+    }
+
+    /// Save to `path`
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_progress_counts() {
+        let mut progress = SyncProgress::default();
+        assert!(!progress.exchanged_changes());
+        progress.record_sent(120, 2);
+        progress.record_sent(40, 0);
+        progress.record_received(300, 5);
+        assert_eq!(progress.changes_sent, 2);
+        assert_eq!(progress.changes_received, 5);
+        assert_eq!(progress.bytes_sent, 160);
+        assert_eq!(progress.bytes_received, 300);
+        assert_eq!(progress.round_trips, 2);
+        assert!(progress.exchanged_changes());
+    }
+
+    #[test]
+    fn test_last_sync_persistence() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("last_sync.json");
+        assert_eq!(LastSync::load(&path).unwrap(), None);
+
+        let mut progress = SyncProgress::default();
+        progress.record_received(300, 5);
+        let last = LastSync::now(progress);
+        last.save(&path).unwrap();
+        assert_eq!(LastSync::load(&path).unwrap(), Some(last));
+    }
+}
@@ -0,0 +1,291 @@
+//! Sync protocol traces
+//!
+//! With `--trace-sync` (or `trace_sync = true` in the config), each sync
+//! session writes a JSONL file under `sync-traces/` in the data directory,
+//! one line per protocol message: when, which way, its type and size, and
+//! the document's heads before and after it. Session events (connecting,
+//! errors, undecodable messages) are recorded too. A trace is what to
+//! attach when reporting a sync problem against a server; `rott sync debug
+//! last` summarizes the latest one.
+//!
+//! The persistent connection the TUI keeps open writes one trace for as
+//! long as it runs, reconnections included. Only the [`TRACES_KEPT`] most
+//! recent traces are kept.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::document_id::DocumentId;
+
+/// Directory for traces, in the data directory
+pub const TRACE_DIR: &str = "sync-traces";
+
+/// Number of traces kept; older ones are removed when a new one starts
+pub const TRACES_KEPT: usize = 20;
+
+/// Which way a message went
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Sent,
+    Received,
+    /// Not a message: something that happened in the session
+    Event,
+}
+
+/// One line of a trace
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceEntry {
+    pub at: DateTime<Utc>,
+    pub direction: Direction,
+    /// Message type on the wire (`join`, `sync`, `peer`, ...) or event name
+    #[serde(rename = "type")]
+    pub message_type: String,
+    /// Encoded size of the message in bytes (0 for events)
+    pub bytes: usize,
+    /// Document heads (change hashes) before the message was handled
+    pub heads_before: Vec<String>,
+    /// Document heads after the message was handled
+    pub heads_after: Vec<String>,
+    /// Server error text, URLs, decode failures
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// A trace file being written
+#[derive(Debug)]
+pub struct SyncTrace {
+    path: PathBuf,
+    file: File,
+}
+
+impl SyncTrace {
+    /// Start a trace for a session syncing `doc_id`
+    pub fn create(data_dir: &Path, doc_id: &DocumentId) -> Result<Self> {
+        let dir = data_dir.join(TRACE_DIR);
+        fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        prune_traces(&dir, TRACES_KEPT.saturating_sub(1));
+
+        // Names sort by start time
+        let doc_id = doc_id.to_bs58check();
+        let name = format!(
+            "{}-{}.jsonl",
+            Utc::now().format("%Y%m%dT%H%M%S%.3fZ"),
+            &doc_id[..doc_id.len().min(8)]
+        );
+        let path = dir.join(name);
+        let file =
+            File::create(&path).with_context(|| format!("Failed to create {}", path.display()))?;
+        Ok(Self { path, file })
+    }
+
+    /// Where the trace is written
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append an entry
+    ///
+    /// Failing to write a trace never fails the sync; it's only logged.
+    pub fn record(&mut self, entry: &TraceEntry) {
+        let written = serde_json::to_string(entry)
+            .map_err(std::io::Error::from)
+            .and_then(|line| writeln!(self.file, "{}", line));
+        if let Err(e) = written {
+            warn!("Failed to write sync trace {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// The most recent trace in the data directory, if any
+pub fn latest_trace(data_dir: &Path) -> Result<Option<PathBuf>> {
+    Ok(trace_files(&data_dir.join(TRACE_DIR))?.pop())
+}
+
+/// Read a trace's entries
+///
+/// Lines that can't be parsed (e.g. cut off by a crash) are skipped.
+pub fn read_trace(path: &Path) -> Result<Vec<TraceEntry>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.with_context(|| format!("Failed to read {}", path.display()))?;
+        if let Ok(entry) = serde_json::from_str(&line) {
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+/// What happened in a traced session
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TraceSummary {
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub sent: usize,
+    pub received: usize,
+    pub bytes_sent: usize,
+    pub bytes_received: usize,
+    /// Number of messages of each type, sent and received together
+    pub message_types: BTreeMap<String, usize>,
+    /// Document heads when the session started and ended
+    pub heads_start: Vec<String>,
+    pub heads_end: Vec<String>,
+    /// Errors and other event details, in order
+    pub events: Vec<String>,
+}
+
+impl TraceSummary {
+    /// Summarize a trace's entries (None if there are none)
+    pub fn of(entries: &[TraceEntry]) -> Option<Self> {
+        let first = entries.first()?;
+        let last = entries.last()?;
+        let mut summary = Self {
+            started_at: first.at,
+            ended_at: last.at,
+            sent: 0,
+            received: 0,
+            bytes_sent: 0,
+            bytes_received: 0,
+            message_types: BTreeMap::new(),
+            heads_start: first.heads_before.clone(),
+            heads_end: last.heads_after.clone(),
+            events: Vec::new(),
+        };
+        for entry in entries {
+            match entry.direction {
+                Direction::Sent => {
+                    summary.sent += 1;
+                    summary.bytes_sent += entry.bytes;
+                }
+                Direction::Received => {
+                    summary.received += 1;
+                    summary.bytes_received += entry.bytes;
+                }
+                Direction::Event => {}
+            }
+            if entry.direction != Direction::Event {
+                *summary
+                    .message_types
+                    .entry(entry.message_type.clone())
+                    .or_default() += 1;
+            }
+            if entry.direction == Direction::Event || entry.detail.is_some() {
+                summary.events.push(match entry.detail {
+                    Some(ref detail) => format!("{}: {}", entry.message_type, detail),
+                    None => entry.message_type.clone(),
+                });
+            }
+        }
+        Some(summary)
+    }
+
+    /// Whether the document changed during the session
+    pub fn document_changed(&self) -> bool {
+        self.heads_start != self.heads_end
+    }
+}
+
+/// Trace files in `dir`, oldest first
+fn trace_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "jsonl"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Remove the oldest traces in `dir`, leaving `keep`
+fn prune_traces(dir: &Path, keep: usize) {
+    let Ok(files) = trace_files(dir) else {
+        return;
+    };
+    let excess = files.len().saturating_sub(keep);
+    for path in &files[..excess] {
+        if let Err(e) = fs::remove_file(path) {
+            warn!("Failed to remove old sync trace {}: {}", path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(
+        direction: Direction,
+        message_type: &str,
+        bytes: usize,
+        heads: [&str; 2],
+    ) -> TraceEntry {
+        TraceEntry {
+            at: Utc::now(),
+            direction,
+            message_type: message_type.to_string(),
+            bytes,
+            heads_before: vec![heads[0].to_string()],
+            heads_after: vec![heads[1].to_string()],
+            detail: None,
+        }
+    }
+
+    #[test]
+    fn test_trace_round_trip_and_summary() {
+        let temp_dir = TempDir::new().unwrap();
+        let doc_id = DocumentId::new();
+
+        let mut trace = SyncTrace::create(temp_dir.path(), &doc_id).unwrap();
+        trace.record(&entry(Direction::Sent, "join", 40, ["a", "a"]));
+        trace.record(&entry(Direction::Received, "peer", 60, ["a", "a"]));
+        trace.record(&entry(Direction::Sent, "request", 100, ["a", "a"]));
+        trace.record(&entry(Direction::Received, "sync", 500, ["a", "b"]));
+        let mut error = entry(Direction::Event, "error", 0, ["b", "b"]);
+        error.detail = Some("timed out".to_string());
+        trace.record(&error);
+        let path = trace.path().to_path_buf();
+        drop(trace);
+
+        assert_eq!(latest_trace(temp_dir.path()).unwrap(), Some(path.clone()));
+        let entries = read_trace(&path).unwrap();
+        assert_eq!(entries.len(), 5);
+        assert_eq!(entries[0].direction, Direction::Sent);
+
+        let summary = TraceSummary::of(&entries).unwrap();
+        assert_eq!((summary.sent, summary.received), (2, 2));
+        assert_eq!((summary.bytes_sent, summary.bytes_received), (140, 560));
+        assert_eq!(summary.message_types["sync"], 1);
+        assert!(summary.document_changed());
+        assert_eq!(summary.events, vec!["error: timed out"]);
+
+        assert!(TraceSummary::of(&[]).is_none());
+    }
+
+    #[test]
+    fn test_old_traces_are_pruned() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join(TRACE_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        for i in 0..TRACES_KEPT + 5 {
+            fs::write(dir.join(format!("20200101T0000{:02}.000Z-x.jsonl", i)), "").unwrap();
+        }
+
+        let trace = SyncTrace::create(temp_dir.path(), &DocumentId::new()).unwrap();
+        let files = trace_files(&dir).unwrap();
+        assert_eq!(files.len(), TRACES_KEPT);
+        assert_eq!(files.last().unwrap(), trace.path());
+        assert!(!dir.join("20200101T000000.000Z-x.jsonl").exists());
+    }
+}
@@ -0,0 +1,154 @@
+//! The tag hierarchy
+//!
+//! Tags are lowercase-kebab (see [`normalize_tag`](crate::validate::normalize_tag)),
+//! so there's no separator reserved for nesting; the hierarchy follows the
+//! `-`-separated segments instead. `lang-rust` and `lang-go` sit under
+//! `lang`, whether or not any link is tagged `lang` itself:
+//!
+//! ```text
+//! lang (5)
+//! ├── go (2)
+//! └── rust (3)
+//! to-read (4)
+//! ```
+//!
+//! A segment that isn't a tag and has only one tag below it isn't a level
+//! of its own: `to-read` is one node, not `read` under `to`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::models::Link;
+use crate::validate::normalize_tags;
+
+/// A node of the tag hierarchy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TagNode {
+    /// The node's segments, relative to its parent (`rust` under `lang`)
+    pub name: String,
+    /// The full tag (`lang-rust`)
+    pub tag: String,
+    /// Links tagged with exactly this tag
+    pub count: usize,
+    /// Links tagged with this tag or any below it, each counted once
+    pub total: usize,
+    /// Tags below this one, by name
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TagNode>,
+}
+
+/// A node while the tree is built, keyed by segment
+#[derive(Default)]
+struct Branch {
+    count: usize,
+    total: usize,
+    children: BTreeMap<String, Branch>,
+}
+
+/// Build the tag hierarchy of some links
+///
+/// Top-level nodes are sorted by name, as are the children of each node.
+pub fn tag_tree(links: &[Link]) -> Vec<TagNode> {
+    let mut root = Branch::default();
+    for link in links {
+        let tags = normalize_tags(&link.tags);
+        // Every node a link is under, so totals count it once
+        let mut prefixes = BTreeSet::new();
+        for tag in &tags {
+            let segments: Vec<&str> = tag.split('-').collect();
+            for end in 1..=segments.len() {
+                prefixes.insert(segments[..end].to_vec());
+            }
+            branch_mut(&mut root, &segments).count += 1;
+        }
+        for prefix in prefixes {
+            branch_mut(&mut root, &prefix).total += 1;
+        }
+    }
+    into_nodes(root.children, "")
+}
+
+fn branch_mut<'a>(root: &'a mut Branch, segments: &[&str]) -> &'a mut Branch {
+    segments.iter().fold(root, |branch, segment| {
+        branch.children.entry(segment.to_string()).or_default()
+    })
+}
+
+/// Turn branches into nodes, merging a segment that isn't a tag into its
+/// only child
+fn into_nodes(branches: BTreeMap<String, Branch>, parent: &str) -> Vec<TagNode> {
+    branches
+        .into_iter()
+        .map(|(segment, mut branch)| {
+            let mut name = segment;
+            while branch.count == 0 && branch.children.len() == 1 {
+                let (child_segment, child) = branch.children.into_iter().next().unwrap();
+                name = format!("{}-{}", name, child_segment);
+                branch = child;
+            }
+            let tag = if parent.is_empty() {
+                name.clone()
+            } else {
+                format!("{}-{}", parent, name)
+            };
+            TagNode {
+                children: into_nodes(branch.children, &tag),
+                name,
+                tag,
+                count: branch.count,
+                total: branch.total,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tagged(tags: &[&str]) -> Link {
+        let mut link = Link::new("https://example.com");
+        link.tags = tags.iter().map(|t| t.to_string()).collect();
+        link
+    }
+
+    #[test]
+    fn test_tag_tree() {
+        let links = vec![
+            tagged(&["lang-rust", "lang-go", "to-read"]),
+            tagged(&["lang-rust"]),
+            tagged(&["Lang"]),
+            tagged(&["to-read"]),
+        ];
+        let tree = tag_tree(&links);
+
+        assert_eq!(tree.len(), 2);
+        let lang = &tree[0];
+        assert_eq!((lang.name.as_str(), lang.count, lang.total), ("lang", 1, 3));
+        let children: Vec<_> = lang
+            .children
+            .iter()
+            .map(|node| (node.name.as_str(), node.tag.as_str(), node.total))
+            .collect();
+        assert_eq!(children, [("go", "lang-go", 1), ("rust", "lang-rust", 2)]);
+
+        // A segment on its own isn't a level
+        let to_read = &tree[1];
+        assert_eq!(
+            (to_read.name.as_str(), to_read.tag.as_str()),
+            ("to-read", "to-read")
+        );
+        assert_eq!((to_read.count, to_read.total), (2, 2));
+        assert!(to_read.children.is_empty());
+    }
+
+    #[test]
+    fn test_merged_segments_keep_full_tags() {
+        let tree = tag_tree(&[tagged(&["a-b-c", "a-b-d"])]);
+        assert_eq!(tree.len(), 1);
+        assert_eq!((tree[0].name.as_str(), tree[0].count), ("a-b", 0));
+        let tags: Vec<_> = tree[0].children.iter().map(|n| n.tag.as_str()).collect();
+        assert_eq!(tags, ["a-b-c", "a-b-d"]);
+    }
+}
@@ -0,0 +1,165 @@
+//! Text templates with `{{placeholders}}`
+//!
+//! Used for capture aliases (`[captures.<name>]` in the config) and the
+//! note template (`note_template`). A template is plain text with
+//! placeholders:
+//!
+//! - `{{name}}`: the value of `name`; an error if it has none
+//! - `{{name|fallback}}`: the value of `name`, or `fallback` if it's unset
+//!   or empty
+//!
+//! [`link_vars`] gives the values every template can use: `url`, `domain`,
+//! `title`, `date`, and `time`.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Local};
+use thiserror::Error;
+
+use crate::domains::extract_domain;
+
+/// Why a template couldn't be rendered
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TemplateError {
+    #[error("Unclosed '{{{{' in template")]
+    Unclosed,
+
+    #[error("Empty placeholder '{{{{}}}}' in template")]
+    EmptyPlaceholder,
+
+    #[error("No value for '{{{{{0}}}}}' (pass it as {0}=<value>)")]
+    MissingValue(String),
+}
+
+/// One piece of a parsed template
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part<'a> {
+    Text(&'a str),
+    Placeholder {
+        name: &'a str,
+        fallback: Option<&'a str>,
+    },
+}
+
+fn parse(template: &str) -> Result<Vec<Part<'_>>, TemplateError> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            parts.push(Part::Text(&rest[..start]));
+        }
+        let after = &rest[start + 2..];
+        let end = after.find("}}").ok_or(TemplateError::Unclosed)?;
+        let inner = &after[..end];
+        let (name, fallback) = match inner.split_once('|') {
+            Some((name, fallback)) => (name.trim(), Some(fallback.trim())),
+            None => (inner.trim(), None),
+        };
+        if name.is_empty() {
+            return Err(TemplateError::EmptyPlaceholder);
+        }
+        parts.push(Part::Placeholder { name, fallback });
+        rest = &after[end + 2..];
+    }
+    if !rest.is_empty() {
+        parts.push(Part::Text(rest));
+    }
+    Ok(parts)
+}
+
+/// Names used in a template without a fallback, in order of first use
+pub fn required_names(template: &str) -> Result<Vec<String>, TemplateError> {
+    let mut names: Vec<String> = Vec::new();
+    for part in parse(template)? {
+        if let Part::Placeholder {
+            name,
+            fallback: None,
+        } = part
+        {
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    Ok(names)
+}
+
+/// Render a template with the given values
+pub fn render(template: &str, vars: &BTreeMap<String, String>) -> Result<String, TemplateError> {
+    let mut rendered = String::with_capacity(template.len());
+    for part in parse(template)? {
+        match part {
+            Part::Text(text) => rendered.push_str(text),
+            Part::Placeholder { name, fallback } => {
+                match (vars.get(name).filter(|v| !v.is_empty()), fallback) {
+                    (Some(value), _) => rendered.push_str(value),
+                    (None, Some(fallback)) => rendered.push_str(fallback),
+                    (None, None) => return Err(TemplateError::MissingValue(name.to_string())),
+                }
+            }
+        }
+    }
+    Ok(rendered)
+}
+
+/// The values every template can use, for a link being saved
+pub fn link_vars(url: &str, title: &str, now: DateTime<Local>) -> BTreeMap<String, String> {
+    let mut vars = BTreeMap::new();
+    vars.insert("url".to_string(), url.to_string());
+    vars.insert("title".to_string(), title.to_string());
+    if let Some(domain) = extract_domain(url) {
+        vars.insert("domain".to_string(), domain);
+    }
+    vars.insert("date".to_string(), now.format("%Y-%m-%d").to_string());
+    vars.insert("time".to_string(), now.format("%H:%M").to_string());
+    vars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_render_placeholders() {
+        let values = vars(&[("url", "https://example.com"), ("source", ""), ("n", "3")]);
+        assert_eq!(
+            render("{{url}} via {{ source | a friend }} ({{n}})", &values).unwrap(),
+            "https://example.com via a friend (3)"
+        );
+        assert_eq!(
+            render("no placeholders", &values).unwrap(),
+            "no placeholders"
+        );
+        assert_eq!(
+            render("{{missing}}", &values),
+            Err(TemplateError::MissingValue("missing".to_string()))
+        );
+        assert_eq!(render("{{url", &values), Err(TemplateError::Unclosed));
+        assert_eq!(
+            render("{{ }}", &values),
+            Err(TemplateError::EmptyPlaceholder)
+        );
+    }
+
+    #[test]
+    fn test_required_names() {
+        assert_eq!(
+            required_names("{{url}} {{source}} {{url}} {{who|me}}").unwrap(),
+            vec!["url", "source"]
+        );
+    }
+
+    #[test]
+    fn test_link_vars() {
+        let values = link_vars("https://www.example.com/a", "A", Local::now());
+        assert_eq!(values["domain"], "www.example.com");
+        assert_eq!(values["date"].len(), 10);
+    }
+}
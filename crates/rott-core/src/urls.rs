@@ -0,0 +1,234 @@
+//! URLs in free text
+//!
+//! Note bodies often mention other pages. [`find_urls`] picks out the
+//! `http://` and `https://` URLs in a piece of text so they can be opened or
+//! saved as links of their own. Punctuation that usually ends a sentence
+//! rather than the URL (a trailing `.` or `,`, or a `)` closing a
+//! parenthetical) is left out.
+//!
+//! [`title_from_url`] makes a readable title out of a URL, for links whose
+//! page gave no title.
+
+use std::ops::Range;
+
+use crate::domains::extract_domain;
+
+const SCHEMES: &[&str] = &["https://", "http://"];
+
+/// Path segments that say nothing about the page
+const EMPTY_SEGMENTS: &[&str] = &["index", "default", "home", "amp"];
+
+/// File extensions dropped from a path segment used as a title
+const PAGE_EXTENSIONS: &[&str] = &["html", "htm", "php", "asp", "aspx", "jsp", "shtml"];
+
+/// Byte ranges of the URLs in `text`, in order
+pub fn find_urls(text: &str) -> Vec<Range<usize>> {
+    let mut urls = Vec::new();
+    let mut pos = 0;
+    while pos < text.len() {
+        let Some((start, scheme)) = SCHEMES
+            .iter()
+            .filter_map(|scheme| text[pos..].find(scheme).map(|i| (pos + i, *scheme)))
+            .min_by_key(|(start, _)| *start)
+        else {
+            break;
+        };
+
+        // Part of a longer word (e.g. "xhttps://") isn't a URL
+        let inside_word = text[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric());
+        let rest = &text[start..];
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '`'))
+            .unwrap_or(rest.len());
+        let end = start + trim_trailing(&rest[..len]);
+
+        if !inside_word && end > start + scheme.len() {
+            urls.push(start..end);
+        }
+        pos = (start + len).max(start + scheme.len());
+    }
+    urls
+}
+
+/// The URLs in `text`, as strings
+pub fn url_strs(text: &str) -> Vec<&str> {
+    find_urls(text).into_iter().map(|r| &text[r]).collect()
+}
+
+/// A title made from a URL: the last meaningful path segment in words,
+/// followed by the site
+///
+/// `https://www.example.com/blog/understanding-rust-lifetimes.html` becomes
+/// "Understanding rust lifetimes (example.com)". Segments that are only
+/// numbers or IDs are skipped, and a URL with no useful path is titled by
+/// its site alone. Returns None if the URL has no host.
+pub fn title_from_url(url: &str) -> Option<String> {
+    let domain = extract_domain(url)?;
+    let site = domain.strip_prefix("www.").unwrap_or(&domain).to_string();
+
+    let rest = url.trim().split_once("://").map_or("", |(_, r)| r);
+    let path = rest
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default()
+        .split_once('/')
+        .map_or("", |(_, p)| p);
+    let words = path
+        .split('/')
+        .rev()
+        .map(segment_words)
+        .find(|words| !words.is_empty());
+
+    Some(match words {
+        Some(words) => {
+            let mut title = words.join(" ");
+            if let Some(first) = title.chars().next() {
+                title.replace_range(..first.len_utf8(), &first.to_uppercase().to_string());
+            }
+            format!("{} ({})", title, site)
+        }
+        None => site,
+    })
+}
+
+/// The words in a path segment, or none if it doesn't describe the page
+fn segment_words(segment: &str) -> Vec<String> {
+    let segment = percent_decode(segment);
+    let segment = match segment.rsplit_once('.') {
+        Some((stem, ext)) if PAGE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()) => {
+            stem.to_string()
+        }
+        _ => segment,
+    };
+    if EMPTY_SEGMENTS.contains(&segment.to_ascii_lowercase().as_str()) {
+        return Vec::new();
+    }
+
+    let mut words: Vec<String> = segment
+        .split(|c: char| matches!(c, '-' | '_' | '+' | '~') || c.is_whitespace())
+        .filter(|w| !w.is_empty())
+        .map(String::from)
+        .collect();
+    // Slugs often end in an ID ("my-post-3f2a1b9c8d7e")
+    if words.len() > 1 && words.last().is_some_and(|w| is_id(w)) {
+        words.pop();
+    }
+    if !words.iter().any(|w| w.chars().any(char::is_alphabetic)) || is_id(&segment) {
+        return Vec::new();
+    }
+    words
+}
+
+/// Whether a word looks like an ID rather than a word: all digits, or a
+/// long run of hex digits with at least one digit in it
+fn is_id(word: &str) -> bool {
+    word.chars().all(|c| c.is_ascii_digit())
+        || (word.len() >= 8
+            && word.chars().all(|c| c.is_ascii_hexdigit())
+            && word.chars().any(|c| c.is_ascii_digit()))
+}
+
+/// Decode `%XX` escapes (invalid escapes are kept as they are)
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = if bytes[i] == b'%' {
+            text.get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        } else {
+            None
+        };
+        match escaped {
+            Some(byte) => {
+                out.push(byte);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Length of `url` without trailing punctuation that belongs to the sentence
+fn trim_trailing(url: &str) -> usize {
+    let mut end = url.len();
+    while let Some(c) = url[..end].chars().next_back() {
+        let unbalanced = |open: char, close: char| {
+            c == close && url[..end].matches(close).count() > url[..end].matches(open).count()
+        };
+        if matches!(c, '.' | ',' | ';' | ':' | '!' | '?' | '\'' | '*')
+            || unbalanced('(', ')')
+            || unbalanced('[', ']')
+        {
+            end -= c.len_utf8();
+        } else {
+            break;
+        }
+    }
+    end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_urls() {
+        let text = "See https://example.com/a, and (http://b.example.com/x_(y)) too.\n\
+                    Also <https://c.example.com>. Not xhttps://d.example.com or https://";
+        assert_eq!(
+            url_strs(text),
+            vec![
+                "https://example.com/a",
+                "http://b.example.com/x_(y)",
+                "https://c.example.com",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_urls_ranges() {
+        let text = "ünïcode https://example.com/ü.";
+        let ranges = find_urls(text);
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(&text[ranges[0].clone()], "https://example.com/ü");
+        assert!(find_urls("no links here").is_empty());
+    }
+
+    #[test]
+    fn test_title_from_url() {
+        let title = |url| title_from_url(url).unwrap();
+        assert_eq!(
+            title("https://www.example.com/blog/understanding-rust-lifetimes.html"),
+            "Understanding rust lifetimes (example.com)"
+        );
+        assert_eq!(
+            title("https://medium.com/@jane/why-crdts-matter-3f2a1b9c8d7e?source=rss"),
+            "Why crdts matter (medium.com)"
+        );
+        // IDs and index pages fall back to an earlier segment
+        assert_eq!(
+            title("https://news.example.org/local_news/2024/05/12345/"),
+            "Local news (news.example.org)"
+        );
+        assert_eq!(
+            title("https://example.com/docs/index.php#intro"),
+            "Docs (example.com)"
+        );
+        assert_eq!(
+            title("https://example.com/caf%C3%A9%20menus"),
+            "Café menus (example.com)"
+        );
+        assert_eq!(title("https://www.example.com/"), "example.com");
+        assert_eq!(title("https://example.com/123/456"), "example.com");
+        assert_eq!(title_from_url("not a url"), None);
+    }
+}
@@ -0,0 +1,453 @@
+//! Validation of links before they're written
+//!
+//! Anything written to the document stays in its history (and on every
+//! synced device) for good, so the store checks links on the way in:
+//!
+//! - the URL must parse, and its scheme must be `http`, `https`, `ftp`, or
+//!   one listed in `allowed_schemes`
+//! - titles can't be longer than `max_title_length` characters
+//! - tags are normalized to lowercase-kebab (`Rust Lang` → `rust-lang`)
+//! - the checks configured under `[checks.<name>]` pass: the URL matches
+//!   `url_pattern`, the link has at least `min_tags` tags, and it has a
+//!   title if `require_title` is set
+//!
+//! A link that fails a check with `action = "block"` isn't saved; one that
+//! fails a check with `action = "review"` is saved with the
+//! [`REVIEW_TAG`], to be looked at later. This applies however the link
+//! arrives (CLI, TUI, captures, imports). An edit is only held to checks
+//! the link passed before it, so links saved before a check was added can
+//! still be edited, and removing the review tag from a link sticks.
+
+use regex::Regex;
+use thiserror::Error;
+
+use crate::config::{CheckAction, Config, LinkCheck};
+use crate::domains::extract_domain;
+use crate::models::Link;
+
+/// URL schemes allowed without configuration
+pub const DEFAULT_SCHEMES: [&str; 3] = ["http", "https", "ftp"];
+
+/// Tag added to links that fail a check with `action = "review"`
+pub const REVIEW_TAG: &str = "needs-review";
+
+/// Why a link was rejected
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ValidationError {
+    #[error("URL is empty")]
+    EmptyUrl,
+
+    #[error("'{0}' is not a valid URL")]
+    InvalidUrl(String),
+
+    #[error(
+        "URL scheme '{scheme}' is not allowed (allowed: {allowed}). \
+         Add it to allowed_schemes to save these links."
+    )]
+    SchemeNotAllowed { scheme: String, allowed: String },
+
+    #[error("Title is {length} characters long; the limit is {max} (max_title_length)")]
+    TitleTooLong { length: usize, max: usize },
+
+    #[error("Tag '{0}' has no letters or digits")]
+    InvalidTag(String),
+
+    #[error("Check '{check}' failed: {reason}")]
+    CheckFailed { check: String, reason: String },
+}
+
+/// Checks links against the configured policy
+#[derive(Debug, Clone)]
+pub struct Validator {
+    /// Allowed URL schemes (lowercase)
+    schemes: Vec<String>,
+    /// Longest title allowed, in characters
+    max_title_length: usize,
+    /// Configured checks, by name
+    checks: Vec<(String, Check)>,
+}
+
+/// A configured check, with its pattern compiled
+#[derive(Debug, Clone)]
+struct Check {
+    /// The URL pattern, or why it doesn't compile
+    url_pattern: Option<Result<Regex, String>>,
+    min_tags: usize,
+    require_title: bool,
+    action: CheckAction,
+    message: Option<String>,
+}
+
+impl Check {
+    fn new(check: &LinkCheck) -> Self {
+        Self {
+            url_pattern: check
+                .url_pattern
+                .as_deref()
+                .map(|pattern| Regex::new(pattern).map_err(|e| e.to_string())),
+            min_tags: check.min_tags,
+            require_title: check.require_title,
+            action: check.action,
+            message: check.message.clone(),
+        }
+    }
+
+    /// Why a link fails the check, if it does
+    fn failure(&self, link: &Link) -> Option<String> {
+        match &self.url_pattern {
+            Some(Ok(pattern)) if !pattern.is_match(&link.url) => {
+                return Some(format!("URL doesn't match '{}'", pattern.as_str()));
+            }
+            Some(Err(e)) => return Some(format!("url_pattern isn't a valid pattern: {}", e)),
+            _ => {}
+        }
+        let tags = link.tags.iter().filter(|tag| *tag != REVIEW_TAG).count();
+        if tags < self.min_tags {
+            return Some(format!(
+                "the link has {} tag(s); at least {} are needed",
+                tags, self.min_tags
+            ));
+        }
+        if self.require_title && (link.title.trim().is_empty() || link.title == link.url) {
+            return Some("the link has no title".to_string());
+        }
+        None
+    }
+}
+
+impl Validator {
+    /// Build the policy from configuration
+    pub fn from_config(config: &Config) -> Self {
+        let mut schemes: Vec<String> = DEFAULT_SCHEMES.iter().map(|s| s.to_string()).collect();
+        for scheme in &config.allowed_schemes {
+            let scheme = scheme.trim().trim_end_matches(':').to_lowercase();
+            if !scheme.is_empty() && !schemes.contains(&scheme) {
+                schemes.push(scheme);
+            }
+        }
+        Self {
+            schemes,
+            max_title_length: config.max_title_length,
+            checks: config
+                .checks
+                .iter()
+                .map(|(name, check)| (name.clone(), Check::new(check)))
+                .collect(),
+        }
+    }
+
+    /// Check that a URL parses and its scheme is allowed
+    pub fn check_url(&self, url: &str) -> Result<(), ValidationError> {
+        let url = url.trim();
+        if url.is_empty() {
+            return Err(ValidationError::EmptyUrl);
+        }
+        let invalid = || ValidationError::InvalidUrl(url.to_string());
+
+        if url.chars().any(char::is_whitespace) {
+            return Err(invalid());
+        }
+        let (scheme, rest) = url.split_once(':').ok_or_else(invalid)?;
+        if !is_scheme(scheme) || rest.is_empty() {
+            return Err(invalid());
+        }
+        // Hierarchical URLs (scheme://host/...) need a host
+        if rest.starts_with("//") && extract_domain(url).is_none() {
+            return Err(invalid());
+        }
+
+        let scheme = scheme.to_lowercase();
+        if DEFAULT_SCHEMES.contains(&scheme.as_str()) && !rest.starts_with("//") {
+            return Err(invalid());
+        }
+        if !self.schemes.contains(&scheme) {
+            return Err(ValidationError::SchemeNotAllowed {
+                scheme,
+                allowed: self.schemes.join(", "),
+            });
+        }
+        Ok(())
+    }
+
+    /// Check that a title fits within the limit
+    pub fn check_title(&self, title: &str) -> Result<(), ValidationError> {
+        let length = title.chars().count();
+        if length > self.max_title_length {
+            return Err(ValidationError::TitleTooLong {
+                length,
+                max: self.max_title_length,
+            });
+        }
+        Ok(())
+    }
+
+    /// Validate a link, normalizing it in place
+    ///
+    /// The URL is trimmed and tags are normalized (duplicates after
+    /// normalization are dropped). `previous` is the stored version of the
+    /// link when updating: a URL or title that hasn't changed isn't checked
+    /// again, so links saved before a policy change can still be edited.
+    pub fn validate(
+        &self,
+        link: &mut Link,
+        previous: Option<&Link>,
+    ) -> Result<(), ValidationError> {
+        let url = link.url.trim();
+        if url != link.url {
+            if link.title == link.url {
+                link.title = url.to_string();
+            }
+            link.url = url.to_string();
+        }
+
+        if !previous.is_some_and(|p| p.url == link.url) {
+            self.check_url(&link.url)?;
+        }
+        // A title defaulted from the URL may be long; that's fine
+        if link.title != link.url && !previous.is_some_and(|p| p.title == link.title) {
+            self.check_title(&link.title)?;
+        }
+
+        let mut tags: Vec<String> = Vec::with_capacity(link.tags.len());
+        for tag in &link.tags {
+            let normalized =
+                normalize_tag(tag).ok_or_else(|| ValidationError::InvalidTag(tag.clone()))?;
+            if !tags.contains(&normalized) {
+                tags.push(normalized);
+            }
+        }
+        link.tags = tags;
+
+        self.run_checks(link, previous)
+    }
+
+    /// Run the configured checks on a validated link
+    ///
+    /// Fails on the first blocking check the link fails; review checks tag
+    /// it instead.
+    fn run_checks(&self, link: &mut Link, previous: Option<&Link>) -> Result<(), ValidationError> {
+        for (name, check) in &self.checks {
+            let Some(reason) = check.failure(link) else {
+                continue;
+            };
+            if previous.is_some_and(|p| check.failure(p).is_some()) {
+                continue;
+            }
+            match check.action {
+                CheckAction::Block => {
+                    return Err(ValidationError::CheckFailed {
+                        check: name.clone(),
+                        reason: check.message.clone().unwrap_or(reason),
+                    })
+                }
+                CheckAction::Review => {
+                    if !link.tags.iter().any(|tag| tag == REVIEW_TAG) {
+                        link.tags.push(REVIEW_TAG.to_string());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Normalize a tag to lowercase-kebab
+///
+/// Letters and digits are kept (lowercased); every other run of characters
+/// becomes a single `-`. Returns None if nothing is left.
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let mut normalized = String::with_capacity(tag.len());
+    let mut pending_dash = false;
+    for c in tag.chars() {
+        if c.is_alphanumeric() {
+            if pending_dash && !normalized.is_empty() {
+                normalized.push('-');
+            }
+            pending_dash = false;
+            normalized.extend(c.to_lowercase());
+        } else {
+            pending_dash = true;
+        }
+    }
+    if normalized.is_empty() {
+        None
+    } else {
+        Some(normalized)
+    }
+}
+
+/// Whether two tags are the same once normalized (`Rust` and `rust`)
+pub fn tags_match(a: &str, b: &str) -> bool {
+    tag_key(a) == tag_key(b)
+}
+
+/// Normalize a list of tags, dropping empty ones and duplicates
+pub fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags.iter().filter_map(|t| normalize_tag(t)) {
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+/// Key tags are compared by
+fn tag_key(tag: &str) -> String {
+    normalize_tag(tag).unwrap_or_else(|| tag.trim().to_lowercase())
+}
+
+/// Whether `s` is a syntactically valid URL scheme (RFC 3986)
+fn is_scheme(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validator(extra: &[&str]) -> Validator {
+        Validator::from_config(&Config {
+            allowed_schemes: extra.iter().map(|s| s.to_string()).collect(),
+            max_title_length: 20,
+            ..Config::default()
+        })
+    }
+
+    #[test]
+    fn test_check_url() {
+        let v = validator(&[]);
+        assert!(v.check_url("https://example.com/a?b#c").is_ok());
+        assert!(v.check_url("FTP://files.example.com").is_ok());
+        assert_eq!(v.check_url("  "), Err(ValidationError::EmptyUrl));
+        for bad in [
+            "example.com",
+            "https://",
+            "https:example.com",
+            "http://a b.com",
+        ] {
+            assert!(
+                matches!(v.check_url(bad), Err(ValidationError::InvalidUrl(_))),
+                "{}",
+                bad
+            );
+        }
+        assert!(matches!(
+            v.check_url("javascript:alert(1)"),
+            Err(ValidationError::SchemeNotAllowed { .. })
+        ));
+
+        let v = validator(&["gemini", "mailto:"]);
+        assert!(v.check_url("gemini://example.org/").is_ok());
+        assert!(v.check_url("mailto:someone@example.com").is_ok());
+    }
+
+    #[test]
+    fn test_normalize_tag() {
+        assert_eq!(normalize_tag("Rust Lang"), Some("rust-lang".to_string()));
+        assert_eq!(normalize_tag("  web__dev! "), Some("web-dev".to_string()));
+        assert_eq!(normalize_tag("C++"), Some("c".to_string()));
+        assert_eq!(normalize_tag("Café"), Some("café".to_string()));
+        assert_eq!(normalize_tag("--"), None);
+
+        assert!(tags_match("Rust", "rust"));
+        assert!(tags_match("Web Dev", "web-dev"));
+        assert!(!tags_match("rust", "rusty"));
+        assert_eq!(
+            normalize_tags(&["Rust".into(), "?".into(), "rust".into(), "Go".into()]),
+            vec!["rust", "go"]
+        );
+    }
+
+    #[test]
+    fn test_validate_link() {
+        let v = validator(&[]);
+        let mut link = Link::new(" https://example.com/a-very-long-path-indeed ");
+        link.set_tags(vec!["Rust".into(), "rust".into(), "Web Dev".into()]);
+        v.validate(&mut link, None).unwrap();
+        assert_eq!(link.url, "https://example.com/a-very-long-path-indeed");
+        assert_eq!(link.title, link.url);
+        assert_eq!(link.tags, vec!["rust", "web-dev"]);
+
+        link.set_title("A title that is far too long");
+        assert!(matches!(
+            v.validate(&mut link, None),
+            Err(ValidationError::TitleTooLong { max: 20, .. })
+        ));
+        // Unchanged since it was stored
+        let stored = link.clone();
+        assert!(v.validate(&mut link, Some(&stored)).is_ok());
+
+        link.set_tags(vec!["!!".into()]);
+        assert_eq!(
+            v.validate(&mut link, None),
+            Err(ValidationError::InvalidTag("!!".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_checks() {
+        let mut config = Config::default();
+        config.checks.insert(
+            "https-only".to_string(),
+            LinkCheck {
+                url_pattern: Some("^https://".to_string()),
+                message: Some("Save the https version".to_string()),
+                ..LinkCheck::default()
+            },
+        );
+        config.checks.insert(
+            "tagged".to_string(),
+            LinkCheck {
+                min_tags: 1,
+                require_title: true,
+                action: CheckAction::Review,
+                ..LinkCheck::default()
+            },
+        );
+        let v = Validator::from_config(&config);
+
+        let mut link = Link::new("http://example.com");
+        assert_eq!(
+            v.validate(&mut link, None),
+            Err(ValidationError::CheckFailed {
+                check: "https-only".to_string(),
+                reason: "Save the https version".to_string(),
+            })
+        );
+
+        // Failing a review check tags the link instead
+        let mut link = Link::new("https://example.com");
+        v.validate(&mut link, None).unwrap();
+        assert_eq!(link.tags, [REVIEW_TAG]);
+
+        // An edit isn't held to a check the link already failed
+        let stored = link.clone();
+        link.tags.clear();
+        v.validate(&mut link, Some(&stored)).unwrap();
+        assert!(link.tags.is_empty());
+
+        let mut link = Link::new("https://example.com");
+        link.set_title("Example");
+        link.tags = vec!["rust".to_string()];
+        v.validate(&mut link, None).unwrap();
+        assert_eq!(link.tags, ["rust"]);
+
+        // A pattern that doesn't compile fails every link, saying why
+        config.checks.clear();
+        config.checks.insert(
+            "broken".to_string(),
+            LinkCheck {
+                url_pattern: Some("(".to_string()),
+                ..LinkCheck::default()
+            },
+        );
+        let v = Validator::from_config(&config);
+        assert!(matches!(
+            v.validate(&mut Link::new("https://example.com"), None),
+            Err(ValidationError::CheckFailed { .. })
+        ));
+    }
+}
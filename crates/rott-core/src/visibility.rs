@@ -0,0 +1,136 @@
+//! Tag visibility: which links may leave the collection
+//!
+//! A tag can be marked `private` or `public`. The marks are kept in the
+//! document, so every device agrees on them. Links with a private tag are
+//! left out wherever links are copied out of the collection for another
+//! person or tool (the text and SQLite exports), unless they're asked for.
+//! Public marks links that are meant to be published. A link with both a
+//! private and a public tag is private.
+//!
+//! Backups are full copies and keep private links. Sync can't leave them
+//! out either: every device holds the whole document.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::Link;
+use crate::validate::normalize_tag;
+
+/// How a tag's links are treated outside the collection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagVisibility {
+    /// Left out of exports
+    Private,
+    /// Meant to be published
+    Public,
+}
+
+impl TagVisibility {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TagVisibility::Private => "private",
+            TagVisibility::Public => "public",
+        }
+    }
+}
+
+impl fmt::Display for TagVisibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TagVisibility {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "private" => Ok(TagVisibility::Private),
+            "public" => Ok(TagVisibility::Public),
+            other => Err(format!(
+                "Unknown visibility '{}' (use private or public)",
+                other
+            )),
+        }
+    }
+}
+
+/// The visibility of every marked tag
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Visibility {
+    /// Marks by normalized tag
+    tags: BTreeMap<String, TagVisibility>,
+}
+
+impl Visibility {
+    pub fn new(tags: BTreeMap<String, TagVisibility>) -> Self {
+        Self { tags }
+    }
+
+    /// Marked tags, by normalized tag
+    pub fn tags(&self) -> &BTreeMap<String, TagVisibility> {
+        &self.tags
+    }
+
+    /// A tag's mark, if it has one
+    pub fn of_tag(&self, tag: &str) -> Option<TagVisibility> {
+        self.tags.get(&normalize_tag(tag)?).copied()
+    }
+
+    /// A link's visibility from its tags (private wins over public)
+    pub fn of_link(&self, link: &Link) -> Option<TagVisibility> {
+        let marks: Vec<TagVisibility> = link.tags.iter().filter_map(|t| self.of_tag(t)).collect();
+        if marks.contains(&TagVisibility::Private) {
+            Some(TagVisibility::Private)
+        } else if marks.contains(&TagVisibility::Public) {
+            Some(TagVisibility::Public)
+        } else {
+            None
+        }
+    }
+
+    /// Whether a link has a private tag
+    pub fn is_private(&self, link: &Link) -> bool {
+        self.of_link(link) == Some(TagVisibility::Private)
+    }
+
+    /// Whether a link has a public tag and no private one
+    pub fn is_public(&self, link: &Link) -> bool {
+        self.of_link(link) == Some(TagVisibility::Public)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("Private".parse(), Ok(TagVisibility::Private));
+        assert_eq!(" public ".parse(), Ok(TagVisibility::Public));
+        assert!("secret".parse::<TagVisibility>().is_err());
+    }
+
+    #[test]
+    fn test_private_wins() {
+        let visibility = Visibility::new(BTreeMap::from([
+            ("work".to_string(), TagVisibility::Private),
+            ("blog".to_string(), TagVisibility::Public),
+        ]));
+
+        let mut link = Link::new("https://example.com");
+        assert_eq!(visibility.of_link(&link), None);
+
+        link.add_tag("Blog");
+        assert!(visibility.is_public(&link));
+        assert!(!visibility.is_private(&link));
+
+        link.add_tag("work");
+        assert!(visibility.is_private(&link));
+        assert!(!visibility.is_public(&link));
+    }
+}
@@ -0,0 +1,89 @@
+//! Store behaviour over seeded collections
+
+use rott_core::config::FavoriteMatch;
+use rott_core::events::EventKind;
+use rott_core::LinkSort;
+use rott_testing::{Fixtures, StoreBuilder};
+
+#[test]
+fn test_private_tags_hide_links_from_export_only() {
+    let mut fixtures = Fixtures::default();
+    let store = StoreBuilder::new()
+        .link(fixtures.link("https://example.com/review", "Review", &["work"]))
+        .link(fixtures.link("https://example.com/essay", "Essay", &["essays"]))
+        .private_tag("work")
+        .build()
+        .unwrap();
+
+    assert_eq!(store.get_all_links(LinkSort::default()).unwrap().len(), 2);
+    assert_eq!(
+        store
+            .get_links_by_tag("work", LinkSort::default())
+            .unwrap()
+            .len(),
+        1
+    );
+
+    let shareable = store.shareable_links().unwrap();
+    assert_eq!(shareable.len(), 1);
+    assert_eq!(shareable[0].title, "Essay");
+}
+
+#[test]
+fn test_replay_sees_every_seeded_link() {
+    let store = StoreBuilder::new().generated(12).build().unwrap();
+
+    let mut upserted = 0;
+    store
+        .replay_events(|event| {
+            if matches!(event.kind, EventKind::LinkUpserted { .. }) {
+                upserted += 1;
+            }
+        })
+        .unwrap();
+    assert_eq!(upserted, 12);
+}
+
+#[test]
+fn test_favorite_links_follow_favorite_match() {
+    let mut fixtures = Fixtures::default();
+    let both = fixtures.link("https://example.com/both", "Both", &["starred", "rust"]);
+    let one = fixtures.link("https://example.com/one", "One", &["starred"]);
+    let neither = fixtures.link("https://example.com/neither", "Neither", &["later"]);
+    let store = StoreBuilder::new()
+        .fixtures(fixtures)
+        .links([both, one, neither])
+        .config(|config| {
+            config.favorite_tags = vec!["starred".to_string(), "rust".to_string()];
+            config.favorite_match = FavoriteMatch::All;
+        })
+        .build()
+        .unwrap();
+
+    let favorites = store.favorite_links().unwrap();
+    assert_eq!(favorites.len(), 1);
+    assert_eq!(favorites[0].title, "Both");
+}
+
+#[test]
+fn test_most_opened_links_keep_counts_across_reopen() {
+    let mut fixtures = Fixtures::default();
+    let mut often = fixtures.link("https://example.com/often", "Often", &[]);
+    often.open_count = 5;
+    let mut once = fixtures.link("https://example.com/once", "Once", &[]);
+    once.open_count = 1;
+    let never = fixtures.link("https://example.com/never", "Never", &[]);
+    let store = StoreBuilder::new()
+        .fixtures(fixtures)
+        .links([once, never, often])
+        .build()
+        .unwrap()
+        .reopen()
+        .unwrap();
+
+    let popular = store.most_opened_links(10).unwrap();
+    let titles: Vec<_> = popular.iter().map(|l| l.title.as_str()).collect();
+    assert_eq!(titles, ["Often", "Once"]);
+    assert_eq!(popular[0].open_count, 5);
+    assert_eq!(store.most_opened_links(1).unwrap().len(), 1);
+}
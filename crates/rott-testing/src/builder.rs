@@ -0,0 +1,213 @@
+//! Seeded stores in temporary directories
+
+use std::ops::{Deref, DerefMut};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use rott_core::visibility::TagVisibility;
+use rott_core::{Config, Link, Store};
+use tempfile::TempDir;
+
+use crate::clock::FakeClock;
+use crate::fixtures::Fixtures;
+
+/// Builder for a [`TestStore`]
+///
+/// Links are added through [`Store::add_link`], so they're validated and
+/// derived like links a user saves.
+#[derive(Default)]
+pub struct StoreBuilder {
+    fixtures: Fixtures,
+    configure: Vec<Box<dyn FnOnce(&mut Config)>>,
+    links: Vec<Link>,
+    tag_descriptions: Vec<(String, String)>,
+    tag_visibility: Vec<(String, TagVisibility)>,
+}
+
+impl StoreBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stamp generated links and notes by `clock`
+    pub fn clock(mut self, clock: FakeClock) -> Self {
+        self.fixtures = Fixtures::with_clock(clock);
+        self
+    }
+
+    /// Generate links with `fixtures`, carrying on from the IDs and time
+    /// it's already handed out
+    ///
+    /// Pass the [`Fixtures`] that made links given to [`link`](Self::link),
+    /// or generated links will reuse their IDs.
+    pub fn fixtures(mut self, fixtures: Fixtures) -> Self {
+        self.fixtures = fixtures;
+        self
+    }
+
+    /// Change the config before the store is opened (the data directory
+    /// is set afterwards, and sync is off unless turned on here)
+    pub fn config(mut self, configure: impl FnOnce(&mut Config) + 'static) -> Self {
+        self.configure.push(Box::new(configure));
+        self
+    }
+
+    /// Add a link
+    pub fn link(mut self, link: Link) -> Self {
+        self.links.push(link);
+        self
+    }
+
+    /// Add links
+    pub fn links(mut self, links: impl IntoIterator<Item = Link>) -> Self {
+        self.links.extend(links);
+        self
+    }
+
+    /// Add `count` generated links (see [`Fixtures::links`])
+    pub fn generated(mut self, count: usize) -> Self {
+        let links = self.fixtures.links(count);
+        self.links.extend(links);
+        self
+    }
+
+    /// Describe a tag
+    pub fn tag_description(mut self, tag: &str, description: &str) -> Self {
+        self.tag_descriptions
+            .push((tag.to_string(), description.to_string()));
+        self
+    }
+
+    /// Mark a tag private
+    pub fn private_tag(self, tag: &str) -> Self {
+        self.tag_visibility(tag, TagVisibility::Private)
+    }
+
+    /// Mark a tag private or public
+    pub fn tag_visibility(mut self, tag: &str, visibility: TagVisibility) -> Self {
+        self.tag_visibility.push((tag.to_string(), visibility));
+        self
+    }
+
+    /// Open the store in a new temporary directory and seed it
+    pub fn build(self) -> Result<TestStore> {
+        let dir = TempDir::new().context("Failed to create temporary data directory")?;
+        let mut config = Config {
+            sync_url: None,
+            sync_enabled: false,
+            ..Config::default()
+        };
+        for configure in self.configure {
+            configure(&mut config);
+        }
+        config.data_dir = dir.path().to_path_buf();
+
+        let mut store = Store::open_with_config(config).context("Failed to open test store")?;
+        for link in &self.links {
+            store
+                .add_link(link)
+                .with_context(|| format!("Failed to seed link {}", link.url))?;
+        }
+        for (tag, description) in &self.tag_descriptions {
+            store.set_tag_description(tag, Some(description))?;
+        }
+        for (tag, visibility) in &self.tag_visibility {
+            store.set_tag_visibility(tag, Some(*visibility))?;
+        }
+
+        Ok(TestStore { store, dir })
+    }
+}
+
+/// A store in a temporary data directory, removed when dropped
+///
+/// Derefs to the [`Store`].
+pub struct TestStore {
+    store: Store,
+    dir: TempDir,
+}
+
+impl TestStore {
+    /// The data directory
+    pub fn data_dir(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Close the store and open it again from disk, as the next command
+    /// would
+    pub fn reopen(self) -> Result<Self> {
+        let config = self.store.config().clone();
+        drop(self.store);
+        let store = Store::open_with_config(config).context("Failed to reopen test store")?;
+        Ok(Self {
+            store,
+            dir: self.dir,
+        })
+    }
+}
+
+impl Deref for TestStore {
+    type Target = Store;
+
+    fn deref(&self) -> &Store {
+        &self.store
+    }
+}
+
+impl DerefMut for TestStore {
+    fn deref_mut(&mut self) -> &mut Store {
+        &mut self.store
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rott_core::LinkSort;
+
+    #[test]
+    fn test_build_seeds_the_store() {
+        let mut fixtures = Fixtures::default();
+        let work = fixtures.link("https://example.com/work", "Work thing", &["work"]);
+        let store = StoreBuilder::new()
+            .fixtures(fixtures)
+            .link(work)
+            .generated(5)
+            .tag_description("rust", "The language")
+            .private_tag("work")
+            .config(|config| config.favorite_tags = vec!["starred".to_string()])
+            .build()
+            .unwrap();
+
+        assert_eq!(store.get_all_links(LinkSort::default()).unwrap().len(), 6);
+        assert_eq!(store.config().favorite_tags, ["starred"]);
+        assert_eq!(store.config().data_dir, store.data_dir());
+        assert_eq!(
+            store
+                .tag_descriptions()
+                .unwrap()
+                .get("rust")
+                .map(String::as_str),
+            Some("The language")
+        );
+        assert_eq!(store.shareable_links().unwrap().len(), 5);
+
+        let store = store.reopen().unwrap();
+        assert_eq!(store.get_all_links(LinkSort::default()).unwrap().len(), 6);
+    }
+
+    #[test]
+    fn test_builder_clock_stamps_generated_links() {
+        let clock = FakeClock::default();
+        let start = clock.now();
+        let store = StoreBuilder::new()
+            .clock(clock)
+            .generated(3)
+            .build()
+            .unwrap();
+
+        let mut links = store.get_all_links(LinkSort::default()).unwrap();
+        links.sort_by_key(|l| l.created_at);
+        assert_eq!(links[0].created_at, start);
+    }
+}
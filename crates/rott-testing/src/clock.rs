@@ -0,0 +1,69 @@
+//! Deterministic timestamps
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+/// A clock that only moves when told to
+///
+/// Starts at 2024-01-01 00:00 UTC unless given a start, and moves forward
+/// by a fixed step on each [`tick`](Self::tick).
+#[derive(Debug, Clone)]
+pub struct FakeClock {
+    now: DateTime<Utc>,
+    step: Duration,
+}
+
+impl Default for FakeClock {
+    fn default() -> Self {
+        Self::new(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap())
+    }
+}
+
+impl FakeClock {
+    /// A clock at `start`, ticking a minute at a time
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            now: start,
+            step: Duration::minutes(1),
+        }
+    }
+
+    /// Tick by `step` instead of a minute
+    pub fn with_step(mut self, step: Duration) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// The current time, without moving
+    pub fn now(&self) -> DateTime<Utc> {
+        self.now
+    }
+
+    /// Move forward by `by`
+    pub fn advance(&mut self, by: Duration) {
+        self.now += by;
+    }
+
+    /// The current time, then move forward one step
+    pub fn tick(&mut self) -> DateTime<Utc> {
+        let now = self.now;
+        self.now += self.step;
+        now
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick_and_advance() {
+        let mut clock = FakeClock::default().with_step(Duration::hours(1));
+        let start = clock.now();
+
+        assert_eq!(clock.tick(), start);
+        assert_eq!(clock.now(), start + Duration::hours(1));
+
+        clock.advance(Duration::days(1));
+        assert_eq!(clock.tick(), start + Duration::hours(25));
+    }
+}
@@ -0,0 +1,142 @@
+//! Links and notes for tests
+//!
+//! Everything made by one [`Fixtures`] has IDs counted up from 1 and
+//! timestamps from its [`FakeClock`], so two runs make the same data.
+
+use rott_core::{Link, Note};
+use uuid::Uuid;
+
+use crate::clock::FakeClock;
+
+/// Tags spread over generated links
+const TAGS: &[&str] = &["rust", "databases", "design", "essays", "later", "crdt"];
+
+/// Words generated titles and notes are made of
+const WORDS: &[&str] = &[
+    "async", "storage", "notes", "sync", "merge", "index", "query", "local", "first", "document",
+    "review", "design", "tags", "history", "reading", "list",
+];
+
+/// Maker of deterministic links and notes
+#[derive(Debug, Clone, Default)]
+pub struct Fixtures {
+    clock: FakeClock,
+    next_id: u128,
+}
+
+impl Fixtures {
+    /// Fixtures stamped by `clock`
+    pub fn with_clock(clock: FakeClock) -> Self {
+        Self { clock, next_id: 0 }
+    }
+
+    /// The clock fixtures are stamped by
+    pub fn clock(&mut self) -> &mut FakeClock {
+        &mut self.clock
+    }
+
+    fn id(&mut self) -> Uuid {
+        self.next_id += 1;
+        Uuid::from_u128(self.next_id)
+    }
+
+    /// A link with a title and tags, created at the next tick
+    pub fn link(&mut self, url: &str, title: &str, tags: &[&str]) -> Link {
+        let mut link = Link::new(url);
+        link.id = self.id();
+        link.set_title(title);
+        for tag in tags {
+            link.add_tag(*tag);
+        }
+        let at = self.clock.tick();
+        link.created_at = at;
+        link.updated_at = at;
+        link
+    }
+
+    /// A note, created at the next tick
+    pub fn note(&mut self, body: &str) -> Note {
+        let mut note = Note::new(body);
+        note.id = self.id();
+        note.created_at = self.clock.tick();
+        note
+    }
+
+    /// A note with a title, created at the next tick
+    pub fn titled_note(&mut self, title: &str, body: &str) -> Note {
+        let mut note = self.note(body);
+        note.title = Some(title.to_string());
+        note
+    }
+
+    /// Add a note to a link, keeping the link's timestamps on the clock
+    pub fn add_note(&mut self, link: &mut Link, body: &str) {
+        let note = self.note(body);
+        let at = note.created_at;
+        link.add_note(note);
+        link.updated_at = at;
+    }
+
+    /// `count` varied links: titles, one to three tags, some with notes,
+    /// each on its own URL
+    pub fn links(&mut self, count: usize) -> Vec<Link> {
+        (0..count)
+            .map(|i| {
+                let title = words(i, 3 + i % 4);
+                let tags: Vec<&str> = (0..1 + i % 3)
+                    .map(|t| TAGS[(i + t * 2) % TAGS.len()])
+                    .collect();
+                let mut link = self.link(
+                    &format!("https://example.com/{}/{}", TAGS[i % TAGS.len()], i),
+                    &title,
+                    &tags,
+                );
+                for n in 0..i % 3 {
+                    self.add_note(&mut link, &words(i * 7 + n, 8 + n * 4));
+                }
+                link
+            })
+            .collect()
+    }
+}
+
+/// `count` words, picked from `seed`
+fn words(seed: usize, count: usize) -> String {
+    (0..count)
+        .map(|i| WORDS[(seed * 31 + i * 17) % WORDS.len()])
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixtures_are_deterministic() {
+        let first = Fixtures::default().links(10);
+        let second = Fixtures::default().links(10);
+        assert_eq!(first, second);
+
+        assert_eq!(first[0].id, Uuid::from_u128(1));
+        assert!(first.windows(2).all(|w| w[0].created_at < w[1].created_at));
+        assert!(first
+            .iter()
+            .all(|l| !l.tags.is_empty() && !l.title.is_empty()));
+        assert!(first.iter().any(|l| l.notes.len() == 2));
+
+        let urls: std::collections::HashSet<_> = first.iter().map(|l| &l.url).collect();
+        assert_eq!(urls.len(), 10);
+    }
+
+    #[test]
+    fn test_notes_follow_the_clock() {
+        let mut fixtures = Fixtures::default();
+        let mut link = fixtures.link("https://example.com", "Example", &["rust"]);
+        fixtures.add_note(&mut link, "First");
+
+        assert_eq!(link.notes[0].created_at, link.updated_at);
+        assert!(link.updated_at > link.created_at);
+        assert_eq!(link.notes[0].id, Uuid::from_u128(2));
+    }
+}
@@ -0,0 +1,38 @@
+//! Shared test fixtures for ROTT crates
+//!
+//! Tests that need a [`Store`](rott_core::Store) with data in it shouldn't
+//! each set up a temporary directory, a config, and a handful of links by
+//! hand. This crate does it once:
+//!
+//! ```text
+//! let mut fixtures = Fixtures::default();
+//! let example = fixtures.link("https://example.com", "Example", &["rust"]);
+//! let store = StoreBuilder::new()
+//!     .fixtures(fixtures)
+//!     .link(example)
+//!     .generated(50)
+//!     .private_tag("work")
+//!     .build()?;
+//!
+//! assert_eq!(store.get_all_links(LinkSort::default())?.len(), 51);
+//! ```
+//!
+//! - [`StoreBuilder`]: a store in a temporary data directory, seeded with
+//!   links, notes, tag descriptions and tag visibility
+//! - [`Fixtures`]: links and notes with deterministic IDs and timestamps
+//! - [`FakeClock`]: the timestamps fixtures are stamped with
+//!
+//! Only seeded data follows the fake clock; the store itself stamps changes
+//! with the real time.
+//!
+//! Use it as a dev-dependency. In rott-core, only integration tests
+//! (`tests/`) can use it: unit tests are compiled into a different copy of
+//! rott-core than the one this crate links against.
+
+mod builder;
+mod clock;
+mod fixtures;
+
+pub use builder::{StoreBuilder, TestStore};
+pub use clock::FakeClock;
+pub use fixtures::Fixtures;